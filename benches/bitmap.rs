@@ -0,0 +1,60 @@
+//! Benchmarks of bitmap operations
+
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion};
+use hwlocality::bitmap::Bitmap;
+
+/// Compare batch unions and intersections with hand-written in-place folds
+fn union_intersect_all(c: &mut Criterion) {
+    for num_bitmaps in [4, 64, 1024] {
+        let bitmaps = (0..num_bitmaps)
+            .map(|idx| Bitmap::from_range(idx..idx + 256))
+            .collect::<Vec<_>>();
+
+        let mut group = c.benchmark_group("union");
+        group.bench_with_input(
+            BenchmarkId::new("fold_in_place", num_bitmaps),
+            &bitmaps,
+            |b, bitmaps| {
+                b.iter(|| {
+                    black_box(bitmaps)
+                        .iter()
+                        .fold(Bitmap::new(), |mut acc, bitmap| {
+                            acc |= bitmap;
+                            acc
+                        })
+                });
+            },
+        );
+        group.bench_with_input(
+            BenchmarkId::new("union_all", num_bitmaps),
+            &bitmaps,
+            |b, bitmaps| b.iter(|| Bitmap::union_all(black_box(bitmaps))),
+        );
+        group.finish();
+
+        let mut group = c.benchmark_group("intersection");
+        group.bench_with_input(
+            BenchmarkId::new("fold_in_place", num_bitmaps),
+            &bitmaps,
+            |b, bitmaps| {
+                b.iter(|| {
+                    black_box(bitmaps)
+                        .iter()
+                        .fold(Bitmap::full(), |mut acc, bitmap| {
+                            acc &= bitmap;
+                            acc
+                        })
+                });
+            },
+        );
+        group.bench_with_input(
+            BenchmarkId::new("intersect_all", num_bitmaps),
+            &bitmaps,
+            |b, bitmaps| b.iter(|| Bitmap::intersect_all(black_box(bitmaps))),
+        );
+        group.finish();
+    }
+}
+
+criterion_group!(benches, union_intersect_all);
+criterion_main!(benches);
@@ -14,8 +14,9 @@ mod vendored_deps {
 use vendored_deps::*;
 
 fn main() {
-    // We don't need hwloc on docs.rs since it only builds the docs
-    if std::env::var("DOCS_RS").is_err() {
+    // We don't need hwloc on docs.rs since it only builds the docs, nor when
+    // the crate is used without calling into hwloc (no-link feature)
+    if std::env::var("DOCS_RS").is_err() && !cfg!(feature = "no-link") {
         setup_hwloc();
     }
 }
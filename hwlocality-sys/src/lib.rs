@@ -199,6 +199,9 @@
     rust_2018_idioms,
     unused
 )]
+// The no-link feature disables a default behavior, which cannot be expressed
+// with a positive feature name without breaking `default-features = false`
+#![allow(clippy::negative_feature_names)]
 #![doc = include_str!("../README.md")]
 
 #[cfg(target_os = "linux")]
@@ -2341,12 +2344,210 @@ mod memory_attributes {
 #[cfg(feature = "hwloc-2_3_0")]
 pub use memory_attributes::*;
 
+// === Topology differences: https://hwloc.readthedocs.io/en/v2.9/group__hwlocality__diff.html
+
+/// Type of one object attribute difference
+///
+/// C enums can't be modeled as Rust enums because new variants would be UB
+#[doc(alias = "hwloc_topology_diff_obj_attr_type_t")]
+pub type hwloc_topology_diff_obj_attr_type_e = c_int;
+
+/// The object local memory is modified
+///
+/// The union is a [`hwloc_topology_diff_obj_attr_uint64_s`] (and the index
+/// field is ignored).
+pub const HWLOC_TOPOLOGY_DIFF_OBJ_ATTR_SIZE: hwloc_topology_diff_obj_attr_type_e = 0;
+
+/// The object name is modified
+///
+/// The union is a [`hwloc_topology_diff_obj_attr_string_s`] (and the name
+/// field is ignored).
+pub const HWLOC_TOPOLOGY_DIFF_OBJ_ATTR_NAME: hwloc_topology_diff_obj_attr_type_e = 1;
+
+/// The value of an info attribute is modified
+///
+/// The union is a [`hwloc_topology_diff_obj_attr_string_s`].
+pub const HWLOC_TOPOLOGY_DIFF_OBJ_ATTR_INFO: hwloc_topology_diff_obj_attr_type_e = 2;
+
+/// One object attribute difference
+#[derive(Copy, Clone)]
+#[repr(C)]
+pub union hwloc_topology_diff_obj_attr_u {
+    /// Common prefix of all object attribute differences
+    pub generic: hwloc_topology_diff_obj_attr_generic_s,
+
+    /// Integer attribute modification with an optional index
+    pub uint64: hwloc_topology_diff_obj_attr_uint64_s,
+
+    /// String attribute modification with an optional name
+    pub string: hwloc_topology_diff_obj_attr_string_s,
+}
+//
+impl Debug for hwloc_topology_diff_obj_attr_u {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("hwloc_topology_diff_obj_attr_u")
+            .finish_non_exhaustive()
+    }
+}
+
+/// Common prefix of all object attribute differences
+#[derive(Copy, Clone, Debug, Default, Eq, Hash, PartialEq)]
+#[doc(alias = "hwloc_topology_diff_obj_attr_u::hwloc_topology_diff_obj_attr_generic_s")]
+#[repr(C)]
+pub struct hwloc_topology_diff_obj_attr_generic_s {
+    /// Type of object attribute difference
+    #[doc(alias = "hwloc_topology_diff_obj_attr_generic_s::type")]
+    pub ty: hwloc_topology_diff_obj_attr_type_e,
+}
+
+/// Integer attribute modification with an optional index
+#[derive(Copy, Clone, Debug, Default, Eq, Hash, PartialEq)]
+#[doc(alias = "hwloc_topology_diff_obj_attr_u::hwloc_topology_diff_obj_attr_uint64_s")]
+#[repr(C)]
+pub struct hwloc_topology_diff_obj_attr_uint64_s {
+    /// Type of object attribute difference, must be
+    /// [`HWLOC_TOPOLOGY_DIFF_OBJ_ATTR_SIZE`]
+    #[doc(alias = "hwloc_topology_diff_obj_attr_uint64_s::type")]
+    pub ty: hwloc_topology_diff_obj_attr_type_e,
+
+    /// Index of the modified attribute (unused for now)
+    pub index: u64,
+
+    /// Attribute value before modification
+    pub oldvalue: u64,
+
+    /// Attribute value after modification
+    pub newvalue: u64,
+}
+
+/// String attribute modification with an optional name
+#[derive(Copy, Clone, Debug)]
+#[doc(alias = "hwloc_topology_diff_obj_attr_u::hwloc_topology_diff_obj_attr_string_s")]
+#[repr(C)]
+pub struct hwloc_topology_diff_obj_attr_string_s {
+    /// Type of object attribute difference, must be
+    /// [`HWLOC_TOPOLOGY_DIFF_OBJ_ATTR_NAME`] or
+    /// [`HWLOC_TOPOLOGY_DIFF_OBJ_ATTR_INFO`]
+    #[doc(alias = "hwloc_topology_diff_obj_attr_string_s::type")]
+    pub ty: hwloc_topology_diff_obj_attr_type_e,
+
+    /// Name of the modified info attribute (unused for name modifications)
+    pub name: *mut c_char,
+
+    /// Attribute value before modification, may be null
+    pub oldvalue: *mut c_char,
+
+    /// Attribute value after modification, may be null
+    pub newvalue: *mut c_char,
+}
+
+/// Type of one element of a difference list
+///
+/// C enums can't be modeled as Rust enums because new variants would be UB
+#[doc(alias = "hwloc_topology_diff_type_t")]
+pub type hwloc_topology_diff_type_e = c_int;
+
+/// An object attribute was changed
+///
+/// The union is a [`hwloc_topology_diff_obj_attr_s`].
+pub const HWLOC_TOPOLOGY_DIFF_OBJ_ATTR: hwloc_topology_diff_type_e = 0;
+
+/// The difference is too complex, it cannot be represented
+///
+/// The difference below this object has not been checked. The union is a
+/// [`hwloc_topology_diff_too_complex_s`].
+pub const HWLOC_TOPOLOGY_DIFF_TOO_COMPLEX: hwloc_topology_diff_type_e = 1;
+
+/// One element of a difference list between two topologies
+#[derive(Copy, Clone)]
+#[repr(C)]
+pub union hwloc_topology_diff_u {
+    /// Common prefix of all difference list elements
+    pub generic: hwloc_topology_diff_generic_s,
+
+    /// An object attribute was changed
+    pub obj_attr: hwloc_topology_diff_obj_attr_s,
+
+    /// The difference is too complex, it cannot be represented
+    pub too_complex: hwloc_topology_diff_too_complex_s,
+}
+//
+impl Debug for hwloc_topology_diff_u {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("hwloc_topology_diff_u")
+            .finish_non_exhaustive()
+    }
+}
+
+/// A difference list between two topologies, or null if they are identical
+pub type hwloc_topology_diff_t = *mut hwloc_topology_diff_u;
+
+/// Common prefix of all difference list elements
+#[derive(Copy, Clone, Debug)]
+#[doc(alias = "hwloc_topology_diff_u::hwloc_topology_diff_generic_s")]
+#[repr(C)]
+pub struct hwloc_topology_diff_generic_s {
+    /// Type of difference
+    #[doc(alias = "hwloc_topology_diff_generic_s::type")]
+    pub ty: hwloc_topology_diff_type_e,
+
+    /// Next element of the list, or null if this is the last element
+    pub next: hwloc_topology_diff_t,
+}
+
+/// An object attribute was changed
+#[derive(Copy, Clone, Debug)]
+#[doc(alias = "hwloc_topology_diff_u::hwloc_topology_diff_obj_attr_s")]
+#[repr(C)]
+pub struct hwloc_topology_diff_obj_attr_s {
+    /// Type of difference, must be [`HWLOC_TOPOLOGY_DIFF_OBJ_ATTR`]
+    #[doc(alias = "hwloc_topology_diff_obj_attr_s::type")]
+    pub ty: hwloc_topology_diff_type_e,
+
+    /// Next element of the list, or null if this is the last element
+    pub next: hwloc_topology_diff_t,
+
+    /// Depth of the modified object
+    pub obj_depth: c_int,
+
+    /// Logical index of the modified object
+    pub obj_index: c_uint,
+
+    /// Description of the attribute modification
+    pub diff: hwloc_topology_diff_obj_attr_u,
+}
+
+/// The difference is too complex, it cannot be represented
+#[derive(Copy, Clone, Debug)]
+#[doc(alias = "hwloc_topology_diff_u::hwloc_topology_diff_too_complex_s")]
+#[repr(C)]
+pub struct hwloc_topology_diff_too_complex_s {
+    /// Type of difference, must be [`HWLOC_TOPOLOGY_DIFF_TOO_COMPLEX`]
+    #[doc(alias = "hwloc_topology_diff_too_complex_s::type")]
+    pub ty: hwloc_topology_diff_type_e,
+
+    /// Next element of the list, or null if this is the last element
+    pub next: hwloc_topology_diff_t,
+
+    /// Depth of the object below which differences were not checked
+    pub obj_depth: c_int,
+
+    /// Logical index of the object below which differences were not checked
+    pub obj_index: c_uint,
+}
+
+/// Flags to be given to [`hwloc_topology_diff_apply()`]
+pub type hwloc_topology_diff_apply_flags_e = c_ulong;
+
+/// Apply topology diff in reverse direction
+pub const HWLOC_TOPOLOGY_DIFF_APPLY_REVERSE: hwloc_topology_diff_apply_flags_e = 1 << 0;
+
 // === Entry points
 
 /// Implement all the entry points with the right link name
 macro_rules! extern_c_block {
     ($link_name:literal) => {
-        #[link(name = $link_name)]
+        #[cfg_attr(not(feature = "no-link"), link(name = $link_name))]
         extern "C" {
             // === API versioning: https://hwloc.readthedocs.io/en/v2.9/group__hwlocality__api__version.html
 
@@ -2552,7 +2753,22 @@ macro_rules! extern_c_block {
                 separator: *const c_char,
                 verbose: c_int,
             ) -> c_int;
-            // NOTE: Not exposing type printf/scanf for now
+            #[must_use]
+            pub fn hwloc_obj_type_string(object_type: hwloc_obj_type_t) -> *const c_char;
+            #[must_use]
+            pub fn hwloc_type_sscanf(
+                string: *const c_char,
+                typep: *mut hwloc_obj_type_t,
+                attrp: *mut hwloc_obj_attr_u,
+                attrsize: usize,
+            ) -> c_int;
+            #[must_use]
+            pub fn hwloc_type_sscanf_as_depth(
+                string: *const c_char,
+                typep: *mut hwloc_obj_type_t,
+                topology: hwloc_topology_t,
+                depthp: *mut hwloc_get_type_depth_e,
+            ) -> c_int;
 
             // === Consulting and adding Key-Value info attributes: https://hwloc.readthedocs.io/en/v2.9/group__hwlocality__info__attr.html
 
@@ -2771,9 +2987,9 @@ macro_rules! extern_c_block {
                 topology: hwloc_topology_t,
                 filter: hwloc_type_filter_e,
             ) -> c_int;
-            // NOTE: set_userdata and get_userdata are NOT exposed because they
-            //       are hard to make work with copying, persistence and thread
-            //       safety and are not so useful as to justify the effort.
+            pub fn hwloc_topology_set_userdata(topology: hwloc_topology_t, userdata: *const c_void);
+            #[must_use]
+            pub fn hwloc_topology_get_userdata(topology: hwloc_topology_t) -> *mut c_void;
 
             // === Modifying a loaded Topology: https://hwloc.readthedocs.io/en/v2.9/group__hwlocality__tinker.html
 
@@ -2901,7 +3117,21 @@ macro_rules! extern_c_block {
                 len: usize,
                 bitmap: hwloc_const_bitmap_t,
             ) -> c_int;
-            // NOTE: Not exposing other printfs and scanfs for now
+            #[must_use]
+            pub fn hwloc_bitmap_list_sscanf(bitmap: hwloc_bitmap_t, string: *const c_char)
+                -> c_int;
+            #[must_use]
+            pub fn hwloc_bitmap_taskset_snprintf(
+                buf: *mut c_char,
+                len: usize,
+                bitmap: hwloc_const_bitmap_t,
+            ) -> c_int;
+            #[must_use]
+            pub fn hwloc_bitmap_taskset_sscanf(
+                bitmap: hwloc_bitmap_t,
+                string: *const c_char,
+            ) -> c_int;
+            // NOTE: Not exposing the default printf/scanf format and asprintfs for now
 
             pub fn hwloc_bitmap_zero(bitmap: hwloc_bitmap_t);
             pub fn hwloc_bitmap_fill(bitmap: hwloc_bitmap_t);
@@ -2909,8 +3139,14 @@ macro_rules! extern_c_block {
             pub fn hwloc_bitmap_only(bitmap: hwloc_bitmap_t, id: c_uint) -> c_int;
             #[must_use]
             pub fn hwloc_bitmap_allbut(bitmap: hwloc_bitmap_t, id: c_uint) -> c_int;
-            // NOTE: Not exposing ulong-based APIs for now, so no from_ulong, from_ith_ulong, from_ulongs
-            //       If I decide to add them, gate from_ulongs with #[cfg(feature = "hwloc-2_1_0")]
+            // NOTE: Not exposing single-ulong APIs for now, so no from_ulong, from_ith_ulong
+            #[cfg(feature = "hwloc-2_1_0")]
+            #[must_use]
+            pub fn hwloc_bitmap_from_ulongs(
+                bitmap: hwloc_bitmap_t,
+                nr: c_uint,
+                masks: *const c_ulong,
+            ) -> c_int;
             #[must_use]
             pub fn hwloc_bitmap_set(bitmap: hwloc_bitmap_t, id: c_uint) -> c_int;
             #[must_use]
@@ -2919,7 +3155,7 @@ macro_rules! extern_c_block {
                 begin: c_uint,
                 end: c_int,
             ) -> c_int;
-            // NOTE: Not exposing ulong-based APIs for now, so no set_ith_ulong
+            // NOTE: Not exposing single-ulong APIs for now, so no set_ith_ulong
             #[must_use]
             pub fn hwloc_bitmap_clr(bitmap: hwloc_bitmap_t, id: c_uint) -> c_int;
             #[must_use]
@@ -2929,8 +3165,19 @@ macro_rules! extern_c_block {
                 end: c_int,
             ) -> c_int;
             pub fn hwloc_bitmap_singlify(bitmap: hwloc_bitmap_t) -> c_int;
-            // NOTE: Not exposing ulong-based APIs for now, so no to_ulong, to_ith_ulong, to_ulongs and nr_ulongs
-            //       If I decide to add them, gate nr_ulongs and to_ulongs with #[cfg(feature = "hwloc-2_1_0")]
+            // NOTE: Not exposing single-ulong APIs for now, so no to_ulong
+            #[must_use]
+            pub fn hwloc_bitmap_to_ith_ulong(bitmap: hwloc_const_bitmap_t, i: c_uint) -> c_ulong;
+            #[cfg(feature = "hwloc-2_1_0")]
+            #[must_use]
+            pub fn hwloc_bitmap_nr_ulongs(bitmap: hwloc_const_bitmap_t) -> c_int;
+            #[cfg(feature = "hwloc-2_1_0")]
+            #[must_use]
+            pub fn hwloc_bitmap_to_ulongs(
+                bitmap: hwloc_const_bitmap_t,
+                nr: c_uint,
+                masks: *mut c_ulong,
+            ) -> c_int;
 
             #[must_use]
             pub fn hwloc_bitmap_isset(bitmap: hwloc_const_bitmap_t, id: c_uint) -> c_int;
@@ -3333,7 +3580,51 @@ macro_rules! extern_c_block {
             //       crate a `cpu_set_t`, but functions that manipulate them
             //       expect `&mut cpu_set_t`...
 
-            // TODO: Cover more later: interop, differences, sharing, etc...
+            // === Topology differences: https://hwloc.readthedocs.io/en/v2.9/group__hwlocality__diff.html
+
+            #[must_use]
+            pub fn hwloc_topology_diff_build(
+                topology: hwloc_topology_t,
+                newtopology: hwloc_topology_t,
+                flags: c_ulong,
+                diff: *mut hwloc_topology_diff_t,
+            ) -> c_int;
+            #[must_use]
+            pub fn hwloc_topology_diff_apply(
+                topology: hwloc_topology_t,
+                diff: hwloc_topology_diff_t,
+                flags: hwloc_topology_diff_apply_flags_e,
+            ) -> c_int;
+            #[must_use]
+            pub fn hwloc_topology_diff_destroy(diff: hwloc_topology_diff_t) -> c_int;
+            #[must_use]
+            pub fn hwloc_topology_diff_load_xml(
+                xmlpath: *const c_char,
+                diff: *mut hwloc_topology_diff_t,
+                refname: *mut *mut c_char,
+            ) -> c_int;
+            #[must_use]
+            pub fn hwloc_topology_diff_export_xml(
+                diff: hwloc_topology_diff_t,
+                refname: *const c_char,
+                xmlpath: *const c_char,
+            ) -> c_int;
+            #[must_use]
+            pub fn hwloc_topology_diff_load_xmlbuffer(
+                xmlbuffer: *const c_char,
+                buflen: c_int,
+                diff: *mut hwloc_topology_diff_t,
+                refname: *mut *mut c_char,
+            ) -> c_int;
+            #[must_use]
+            pub fn hwloc_topology_diff_export_xmlbuffer(
+                diff: hwloc_topology_diff_t,
+                refname: *const c_char,
+                xmlbuffer: *mut *mut c_char,
+                buflen: *mut c_int,
+            ) -> c_int;
+
+            // TODO: Cover more later: interop, sharing, etc...
             //       Beware that primitives that modify the topology should be
             //       exposed in the TopologyEditor, not Topology, because per
             //       hwloc documentation hwloc_topology_refresh() must be called
@@ -3422,6 +3713,46 @@ mod tests {
         Octal, PartialEq, Pointer, Read, Send, UpperExp, UpperHex, fmt::Write,
         io::Write
     );
+    assert_impl_all!(hwloc_topology_diff_generic_s:
+        Copy, Debug, Sized, Unpin, UnwindSafe
+    );
+    assert_not_impl_any!(hwloc_topology_diff_generic_s:
+        Binary, Default, Deref, Display, Drop, IntoIterator, LowerExp, LowerHex,
+        Octal, PartialEq, Pointer, Read, Send, UpperExp, UpperHex, fmt::Write,
+        io::Write
+    );
+    assert_impl_all!(hwloc_topology_diff_obj_attr_s:
+        Copy, Debug, Sized, Unpin, UnwindSafe
+    );
+    assert_not_impl_any!(hwloc_topology_diff_obj_attr_s:
+        Binary, Default, Deref, Display, Drop, IntoIterator, LowerExp, LowerHex,
+        Octal, PartialEq, Pointer, Read, Send, UpperExp, UpperHex, fmt::Write,
+        io::Write
+    );
+    assert_impl_all!(hwloc_topology_diff_obj_attr_string_s:
+        Copy, Debug, Sized, Unpin, UnwindSafe
+    );
+    assert_not_impl_any!(hwloc_topology_diff_obj_attr_string_s:
+        Binary, Default, Deref, Display, Drop, IntoIterator, LowerExp, LowerHex,
+        Octal, PartialEq, Pointer, Read, Send, UpperExp, UpperHex, fmt::Write,
+        io::Write
+    );
+    assert_impl_all!(hwloc_topology_diff_too_complex_s:
+        Copy, Debug, Sized, Unpin, UnwindSafe
+    );
+    assert_not_impl_any!(hwloc_topology_diff_too_complex_s:
+        Binary, Default, Deref, Display, Drop, IntoIterator, LowerExp, LowerHex,
+        Octal, PartialEq, Pointer, Read, Send, UpperExp, UpperHex, fmt::Write,
+        io::Write
+    );
+    assert_impl_all!(hwloc_topology_diff_u:
+        Copy, Debug, Sized, Unpin, UnwindSafe
+    );
+    assert_not_impl_any!(hwloc_topology_diff_u:
+        Binary, Default, Deref, Display, Drop, IntoIterator, LowerExp, LowerHex,
+        Octal, PartialEq, Pointer, Read, Send, UpperExp, UpperHex, fmt::Write,
+        io::Write
+    );
 
     // Types with pointers that can reasonably be null are like types with
     // non-nullable pointers, but also implement Default
@@ -3462,6 +3793,14 @@ mod tests {
         Octal, PartialEq, Pointer, Read, Send, UpperExp, UpperHex, fmt::Write,
         io::Write
     );
+    assert_impl_all!(hwloc_topology_diff_obj_attr_u:
+        Copy, Debug, Sized, Unpin, UnwindSafe
+    );
+    assert_not_impl_any!(hwloc_topology_diff_obj_attr_u:
+        Binary, Default, Deref, Display, Drop, IntoIterator, LowerExp, LowerHex,
+        Octal, PartialEq, Pointer, Read, Send, UpperExp, UpperHex, fmt::Write,
+        io::Write
+    );
 
     // Unions that contain only Sync types can additionally impl Sync, and this
     // also applies to types that contain such unions
@@ -3503,6 +3842,20 @@ mod tests {
         Binary, Deref, Display, Drop, IntoIterator, LowerExp, LowerHex, Octal,
         PartialOrd, Pointer, Read, UpperExp, UpperHex, fmt::Write, io::Write
     );
+    assert_impl_all!(hwloc_topology_diff_obj_attr_generic_s:
+        Copy, Debug, Default, Hash, Sized, Sync, Unpin, UnwindSafe
+    );
+    assert_not_impl_any!(hwloc_topology_diff_obj_attr_generic_s:
+        Binary, Deref, Display, Drop, IntoIterator, LowerExp, LowerHex, Octal,
+        PartialOrd, Pointer, Read, UpperExp, UpperHex, fmt::Write, io::Write
+    );
+    assert_impl_all!(hwloc_topology_diff_obj_attr_uint64_s:
+        Copy, Debug, Default, Hash, Sized, Sync, Unpin, UnwindSafe
+    );
+    assert_not_impl_any!(hwloc_topology_diff_obj_attr_uint64_s:
+        Binary, Deref, Display, Drop, IntoIterator, LowerExp, LowerHex, Octal,
+        PartialOrd, Pointer, Read, UpperExp, UpperHex, fmt::Write, io::Write
+    );
     assert_impl_all!(hwloc_group_attr_s:
         Copy, Debug, Default, Hash, Sized, Sync, Unpin, UnwindSafe
     );
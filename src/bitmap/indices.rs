@@ -0,0 +1,29 @@
+//! Bitmap indices
+//!
+//! This module contains the part of the bitmap API that is about indices
+//! rather than bitmaps, namely the [`BitmapIndex`] type and its arithmetic.
+//! None of it calls into hwloc or allocates memory, it only relies on `core`,
+//! and most of it is usable in `const` contexts.
+//!
+//! This makes it suitable for libraries that do not use hwloc themselves, but
+//! exchange CPU or NUMA node indices with processes that do, e.g. as part of a
+//! cross-process protocol. Such libraries can enable the `no-link` cargo
+//! feature in order to use this module without having hwloc installed at
+//! build time. They must then refrain from using other parts of hwlocality,
+//! which would result in link errors.
+
+#[cfg(doc)]
+use std::ffi::c_int;
+
+/// Valid bitmap index ranging from `0` to [`c_int::MAX`]
+///
+/// # Examples
+///
+/// ```
+/// use hwlocality::bitmap::indices::BitmapIndex;
+///
+/// const LAST_PU: Option<BitmapIndex> = BitmapIndex::try_from_usize(255);
+/// let next = LAST_PU.and_then(|idx| idx.checked_add(BitmapIndex::ONE));
+/// assert_eq!(next.map(BitmapIndex::to_usize), Some(256));
+/// ```
+pub type BitmapIndex = crate::ffi::PositiveInt;
@@ -49,6 +49,7 @@
 //
 // Main docs: https://hwloc.readthedocs.io/en/v2.9/group__hwlocality__bitmap.html
 
+pub mod indices;
 mod newtypes;
 mod reference;
 
@@ -57,12 +58,14 @@ use crate::{
     cpu::cpuset::CpuSet, memory::nodeset::NodeSet, object::TopologyObject, topology::Topology,
 };
 use crate::{
-    errors,
-    ffi::{self, PositiveInt},
+    errors::{self, ParameterError},
+    ffi::{self, string::LibcString},
 };
 use hwlocality_sys::hwloc_bitmap_s;
 #[cfg(any(test, feature = "proptest"))]
 use proptest::prelude::*;
+#[cfg(feature = "serde")]
+use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
 #[allow(unused)]
 #[cfg(test)]
 use similar_asserts::assert_eq;
@@ -71,22 +74,22 @@ use std::collections::HashSet;
 use std::{
     borrow::Borrow,
     cmp::Ordering,
-    ffi::{c_int, c_uint},
+    ffi::{c_char, c_int, c_uint, c_ulong, CStr},
     fmt::{self, Debug, Display, Formatter, Pointer},
     hash::{self, Hash},
     iter::FusedIterator,
+    mem::ManuallyDrop,
     ops::{
         BitAnd, BitAndAssign, BitOr, BitOrAssign, BitXor, BitXorAssign, Bound, Deref, Not,
-        RangeBounds, Sub, SubAssign,
+        RangeBounds, RangeInclusive, Sub, SubAssign,
     },
     ptr::NonNull,
 };
 
-/// Valid bitmap index ranging from `0` to [`c_int::MAX`]
-pub type BitmapIndex = PositiveInt;
-
-// Re-export BitmapRef so users don't need to know about the reference submodule
+// Re-export BitmapIndex and BitmapRef so users don't need to know about the
+// indices and reference submodules
 pub use self::{
+    indices::BitmapIndex,
     newtypes::{BitmapKind, OwnedBitmap, OwnedSpecializedBitmap, SpecializedBitmap},
     reference::BitmapRef,
 };
@@ -170,6 +173,44 @@ pub struct Bitmap(NonNull<hwloc_bitmap_s>);
 impl Bitmap {
     // === FFI interoperability ===
 
+    /// Take ownership of a bitmap that was allocated by another C library
+    ///
+    /// This is meant for interoperability with other C libraries which produce
+    /// hwloc bitmaps, such as hwloc-aware allocators or MPI implementations,
+    /// and lets you use these bitmaps without copying them. The resulting
+    /// `Bitmap` will free the underlying hwloc bitmap when it is dropped.
+    ///
+    /// # Safety
+    ///
+    /// - `bitmap` must point to a valid hwloc bitmap, which was allocated by
+    ///   the same hwloc library that hwlocality is linked to (e.g. using
+    ///   `hwloc_bitmap_alloc()` or `hwloc_bitmap_dup()`).
+    /// - Ownership of this bitmap is transferred to the returned `Bitmap`, so
+    ///   it must not be used, modified or freed by anyone else afterwards. In
+    ///   particular, bitmaps that belong to a topology must not be adopted
+    ///   this way, use [`Bitmap::borrow_from_raw()`] for these.
+    pub unsafe fn from_raw(bitmap: NonNull<hwloc_bitmap_s>) -> Self {
+        // SAFETY: Per function input precondition
+        unsafe { Self::from_owned_nonnull(bitmap) }
+    }
+
+    /// Hand over ownership of this bitmap to another C library
+    ///
+    /// The returned bitmap must eventually be freed using `hwloc_bitmap_free()`
+    /// or handed back to [`Bitmap::from_raw()`].
+    pub fn into_raw(self) -> NonNull<hwloc_bitmap_s> {
+        ManuallyDrop::new(self).0
+    }
+
+    /// Raw pointer to the underlying hwloc bitmap
+    ///
+    /// This is meant for interoperability with other C libraries which consume
+    /// hwloc bitmaps. The pointer is only valid as long as this `Bitmap` is not
+    /// modified or dropped, and the bitmap must not be modified through it.
+    pub fn as_raw(&self) -> *const hwloc_bitmap_s {
+        self.as_ptr()
+    }
+
     /// Wraps an owned nullable hwloc bitmap
     ///
     /// # Safety
@@ -196,12 +237,16 @@ impl Bitmap {
 
     /// Wraps a borrowed nullable hwloc bitmap
     ///
+    /// This can be used to access bitmaps that belong to hwloc topologies or
+    /// other C libraries without copying them. Unlike with
+    /// [`Bitmap::from_raw()`], the bitmap will not be automatically freed on
+    /// `Drop`. A null pointer results in a `None` output.
+    ///
     /// # Safety
     ///
-    /// If non-null, the pointer must target a bitmap that is valid for
-    /// `'target`. Unlike with [`Bitmap::from_owned_raw_mut()`], it will not be
-    /// automatically freed on `Drop`.
-    pub(crate) unsafe fn borrow_from_raw<'target>(
+    /// If non-null, the pointer must target a valid hwloc bitmap, which must
+    /// not be modified or freed for the entire `'target` lifetime.
+    pub unsafe fn borrow_from_raw<'target>(
         bitmap: *const hwloc_bitmap_s,
     ) -> Option<BitmapRef<'target, Self>> {
         // SAFETY: Per function input precondition
@@ -327,6 +372,69 @@ impl Bitmap {
         bitmap
     }
 
+    /// Creates a new `Bitmap` where only `idx` is set
+    ///
+    /// Accepts both [`BitmapIndex`] and [`usize`] operands. Use the former for
+    /// type-safety (it is guaranteed to be in range as a type invariant) or the
+    /// latter for convenience (it is more tightly integrated with Rust's
+    /// built-in integer support, for example it supports integer literals).
+    ///
+    /// This is the inverse of [`Bitmap::only_bit()`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use hwlocality::bitmap::Bitmap;
+    ///
+    /// let bitmap = Bitmap::singleton(42);
+    /// assert_eq!(format!("{bitmap}"), "42");
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// If `idx` is above the implementation-defined maximum index (at least
+    /// 2^15-1, usually 2^31-1).
+    pub fn singleton<Idx>(idx: Idx) -> Self
+    where
+        Idx: TryInto<BitmapIndex>,
+        <Idx as TryInto<BitmapIndex>>::Error: Debug,
+    {
+        let mut bitmap = Self::new();
+        bitmap.set_only(idx);
+        bitmap
+    }
+
+    /// Creates a new `Bitmap` with the given ranges of indices set
+    ///
+    /// This is the inverse of [`Bitmap::ranges()`], and accepts the same range
+    /// types as [`Bitmap::from_range()`]. Ranges may overlap.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use hwlocality::bitmap::Bitmap;
+    ///
+    /// let bitmap = Bitmap::from_ranges([0..=3, 8..=11, 10..=15]);
+    /// assert_eq!(format!("{bitmap}"), "0-3,8-15");
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// If any range goes beyond the implementation-defined maximum index (at
+    /// least 2^15-1, usually 2^31-1).
+    pub fn from_ranges<Idx, Range>(ranges: impl IntoIterator<Item = Range>) -> Self
+    where
+        Idx: Copy + TryInto<BitmapIndex>,
+        <Idx as TryInto<BitmapIndex>>::Error: Debug,
+        Range: RangeBounds<Idx>,
+    {
+        let mut bitmap = Self::new();
+        for range in ranges {
+            bitmap.set_range(range);
+        }
+        bitmap
+    }
+
     // === Getters and setters ===
 
     /// Turn this `Bitmap` into a copy of another `Bitmap`
@@ -815,6 +923,10 @@ impl Bitmap {
 
     /// Iterate over set indices
     ///
+    /// Indices are yielded in increasing order, but the iterator can also be
+    /// reversed to go through them in decreasing order. If the bitmap is
+    /// infinitely set, reverse iteration starts at [`BitmapIndex::MAX`].
+    ///
     /// # Examples
     ///
     /// ```
@@ -823,12 +935,46 @@ impl Bitmap {
     /// let bitmap = Bitmap::from_range(12..=21);
     /// let indices = bitmap.iter_set().map(usize::from).collect::<Vec<_>>();
     /// assert_eq!(indices, &[12, 13, 14, 15, 16, 17, 18, 19, 20, 21]);
+    ///
+    /// let highest = bitmap.iter_set().rev().take(3).map(usize::from).collect::<Vec<_>>();
+    /// assert_eq!(highest, &[21, 20, 19]);
     /// ```
     #[doc(alias = "hwloc_bitmap_foreach_begin")]
     #[doc(alias = "hwloc_bitmap_foreach_end")]
     #[doc(alias = "hwloc_bitmap_next")]
     pub fn iter_set(&self) -> Iter<&Self> {
-        Iter::new(self, Self::next_set)
+        Iter::new(self, true)
+    }
+
+    /// Iterate over ranges of consecutive set indices
+    ///
+    /// Ranges are yielded in increasing order, and are neither empty nor
+    /// adjacent to each other. This is much faster than
+    /// [`Bitmap::iter_set()`] when many consecutive indices are set, since it
+    /// only needs to query the bounds of each range.
+    ///
+    /// If the bitmap is infinitely set, the last range ends at
+    /// [`BitmapIndex::MAX`]. Beware that passing such a range to
+    /// [`Bitmap::from_ranges()`] builds a large finite bitmap, use
+    /// [`Bitmap::set_range()`] with a `start..` range instead.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use hwlocality::bitmap::{Bitmap, BitmapIndex};
+    ///
+    /// let bitmap = Bitmap::from_ranges([0..=3, 8..=11]);
+    /// let ranges = bitmap
+    ///     .ranges()
+    ///     .map(|range| usize::from(*range.start())..=usize::from(*range.end()))
+    ///     .collect::<Vec<_>>();
+    /// assert_eq!(ranges, [0..=3, 8..=11]);
+    ///
+    /// let last = Bitmap::from_range(12..).ranges().last().unwrap();
+    /// assert_eq!(*last.end(), BitmapIndex::MAX);
+    /// ```
+    pub fn ranges(&self) -> Ranges<&Self> {
+        Ranges::new(self)
     }
 
     /// Check the last set index, if any
@@ -853,6 +999,95 @@ impl Bitmap {
         )
     }
 
+    /// Check the first set index after `idx`, if any
+    ///
+    /// `idx` itself does not need to be set.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use hwlocality::bitmap::Bitmap;
+    ///
+    /// let bitmap = Bitmap::from_ranges([2..=3, 8..=9]);
+    /// let next_usize = |idx| bitmap.next_set_after(idx).map(usize::from);
+    /// assert_eq!(next_usize(2), Some(3));
+    /// assert_eq!(next_usize(5), Some(8));
+    /// assert_eq!(next_usize(9), None);
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// If `idx` is above the implementation-defined maximum index (at least
+    /// 2^15-1, usually 2^31-1).
+    #[doc(alias = "hwloc_bitmap_next")]
+    pub fn next_set_after<Idx>(&self, idx: Idx) -> Option<BitmapIndex>
+    where
+        Idx: TryInto<BitmapIndex>,
+        <Idx as TryInto<BitmapIndex>>::Error: Debug,
+    {
+        /// Polymorphized version of this function (avoids generics code bloat)
+        fn polymorphized(self_: &Bitmap, idx: Option<BitmapIndex>) -> Option<BitmapIndex> {
+            self_.next_set(Some(idx.expect(BAD_INDEX)))
+        }
+        polymorphized(self, idx.try_into().ok())
+    }
+
+    /// Check the last set index before `idx`, if any
+    ///
+    /// `idx` itself does not need to be set. hwloc has no native support for
+    /// this query, so it is implemented on top of the bitmap's word
+    /// representation.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use hwlocality::bitmap::Bitmap;
+    ///
+    /// let bitmap = Bitmap::from_ranges([2..=3, 80..=81]);
+    /// let prev_usize = |idx| bitmap.prev_set_before(idx).map(usize::from);
+    /// assert_eq!(prev_usize(81), Some(80));
+    /// assert_eq!(prev_usize(42), Some(3));
+    /// assert_eq!(prev_usize(2), None);
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// If `idx` is above the implementation-defined maximum index (at least
+    /// 2^15-1, usually 2^31-1).
+    pub fn prev_set_before<Idx>(&self, idx: Idx) -> Option<BitmapIndex>
+    where
+        Idx: TryInto<BitmapIndex>,
+        <Idx as TryInto<BitmapIndex>>::Error: Debug,
+    {
+        /// Polymorphized version of this function (avoids generics code bloat)
+        fn polymorphized(self_: &Bitmap, idx: Option<BitmapIndex>) -> Option<BitmapIndex> {
+            self_.prev_set(Some(idx.expect(BAD_INDEX)))
+        }
+        polymorphized(self, idx.try_into().ok())
+    }
+
+    /// The index that is set in the bitmap, if it is the only one
+    ///
+    /// Returns `None` if the bitmap is empty, or if it has more than one set
+    /// index. This is the inverse of [`Bitmap::singleton()`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use hwlocality::bitmap::Bitmap;
+    ///
+    /// let only_bit_usize = |b: Bitmap| b.only_bit().map(usize::from);
+    /// assert_eq!(only_bit_usize(Bitmap::singleton(42)), Some(42));
+    /// assert_eq!(Bitmap::new().only_bit(), None);
+    /// assert_eq!(Bitmap::from_range(12..=13).only_bit(), None);
+    /// assert_eq!(Bitmap::full().only_bit(), None);
+    /// ```
+    #[doc(alias = "try_only_set")]
+    pub fn only_bit(&self) -> Option<BitmapIndex> {
+        let first = self.first_set()?;
+        (self.last_set() == Some(first)).then_some(first)
+    }
+
     /// The number of indices that are set in the bitmap
     ///
     /// None means that an infinite number of indices are set.
@@ -916,7 +1151,7 @@ impl Bitmap {
     /// ```
     #[doc(alias = "hwloc_bitmap_next_unset")]
     pub fn iter_unset(&self) -> Iter<&Self> {
-        Iter::new(self, Self::next_unset)
+        Iter::new(self, false)
     }
 
     /// Check the last unset index, if any
@@ -1023,6 +1258,302 @@ impl Bitmap {
         polymorphized(self, &inner)
     }
 
+    /// Compute the union of many bitmaps
+    ///
+    /// This is a convenience shorthand for merging all bitmaps into a single
+    /// accumulator with the `|=` operator. It makes one hwloc call per input
+    /// bitmap, like such a hand-written in-place fold would, but avoids the
+    /// temporary bitmaps that a fold based on the `|` operator allocates.
+    ///
+    /// The union of zero bitmaps is the empty bitmap.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use hwlocality::bitmap::Bitmap;
+    ///
+    /// let bitmaps = [Bitmap::from_range(0..=3), Bitmap::from_range(8..=11)];
+    /// assert_eq!(Bitmap::union_all(&bitmaps).to_string(), "0-3,8-11");
+    /// assert_eq!(Bitmap::union_all(Vec::<Bitmap>::new()), Bitmap::new());
+    /// ```
+    #[doc(alias = "hwloc_bitmap_or")]
+    pub fn union_all<B: Borrow<Self>>(bitmaps: impl IntoIterator<Item = B>) -> Self {
+        let mut result = Self::new();
+        for bitmap in bitmaps {
+            result |= bitmap;
+        }
+        result
+    }
+
+    /// Compute the intersection of many bitmaps
+    ///
+    /// This is a convenience shorthand for merging all bitmaps into a single
+    /// accumulator with the `&=` operator. It makes one hwloc call per input
+    /// bitmap, like such a hand-written in-place fold would, but avoids the
+    /// temporary bitmaps that a fold based on the `&` operator allocates.
+    ///
+    /// The intersection of zero bitmaps is the full bitmap.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use hwlocality::bitmap::Bitmap;
+    ///
+    /// let bitmaps = [Bitmap::from_range(0..=7), Bitmap::from_range(4..=11)];
+    /// assert_eq!(Bitmap::intersect_all(&bitmaps).to_string(), "4-7");
+    /// assert_eq!(Bitmap::intersect_all(Vec::<Bitmap>::new()), Bitmap::full());
+    /// ```
+    #[doc(alias = "hwloc_bitmap_and")]
+    pub fn intersect_all<B: Borrow<Self>>(bitmaps: impl IntoIterator<Item = B>) -> Self {
+        let mut bitmaps = bitmaps.into_iter();
+        let Some(first) = bitmaps.next() else {
+            return Self::full();
+        };
+        let mut result = first.borrow().clone();
+        for bitmap in bitmaps {
+            result &= bitmap;
+        }
+        result
+    }
+
+    /// Format this bitmap in the list format of the Linux kernel
+    ///
+    /// This format is a comma-separated list of indices and inclusive index
+    /// ranges, like `0-3,8-11`. It is used by the `taskset --cpu-list` command,
+    /// the `cpuset.cpus` file of Linux control groups and many job schedulers.
+    /// Infinitely set bitmaps end with an open range, like `8-`.
+    ///
+    /// This is also the format used by the [`Display`] implementation of
+    /// bitmaps.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use hwlocality::bitmap::Bitmap;
+    ///
+    /// let mut bitmap = Bitmap::from_range(0..=3);
+    /// bitmap.set_range(8..=11);
+    /// assert_eq!(bitmap.to_list_string(), "0-3,8-11");
+    /// ```
+    #[doc(alias = "hwloc_bitmap_list_snprintf")]
+    pub fn to_list_string(&self) -> String {
+        self.to_string()
+    }
+
+    /// Parse a bitmap from the list format of the Linux kernel
+    ///
+    /// See [`to_list_string()`](Self::to_list_string) for a description of
+    /// this format. An empty string is parsed as an empty bitmap.
+    ///
+    /// Parsing is strict: each comma-separated item must be either an index
+    /// like `"5"` or a range like `"8-11"` whose start is not greater than
+    /// its end, and only the last item may be an infinite range like `"12-"`.
+    /// Whitespace, signs and empty items are rejected.
+    ///
+    /// # Errors
+    ///
+    /// - [`ParameterError`] if `list` is not a valid list string
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use hwlocality::bitmap::Bitmap;
+    ///
+    /// let bitmap = Bitmap::from_list_string("0-3,8-11")?;
+    /// assert_eq!(bitmap.weight(), Some(8));
+    /// assert!(bitmap.is_set(9));
+    /// # Ok::<(), eyre::Report>(())
+    /// ```
+    #[doc(alias = "hwloc_bitmap_list_sscanf")]
+    pub fn from_list_string(list: &str) -> Result<Self, ParameterError<String>> {
+        // hwloc's parser silently accepts some malformed lists, like "3-1"
+        if !is_valid_list_string(list) {
+            return Err(ParameterError(list.to_owned()));
+        }
+        // SAFETY: hwloc_bitmap_list_sscanf is an sscanf-like API
+        unsafe {
+            Self::sscanf(list, "hwloc_bitmap_list_sscanf", |bitmap, string| {
+                hwlocality_sys::hwloc_bitmap_list_sscanf(bitmap, string)
+            })
+        }
+    }
+
+    /// Format this bitmap as a hexadecimal mask, as used by `taskset`
+    ///
+    /// This format is a single hexadecimal number prefixed with `0x`, whose
+    /// bits are set according to the bitmap's indices, like `0xf0f` for the
+    /// bitmap that contains indices 0 to 3 and 8 to 11. This is the format
+    /// that the `taskset` command uses when `--cpu-list` is not specified.
+    /// Infinitely set bitmaps are prefixed with `0xf...f`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use hwlocality::bitmap::Bitmap;
+    ///
+    /// let mut bitmap = Bitmap::from_range(0..=3);
+    /// bitmap.set_range(8..=11);
+    /// assert_eq!(bitmap.to_taskset_string(), "0xf0f");
+    /// ```
+    #[doc(alias = "hwloc_bitmap_taskset_snprintf")]
+    pub fn to_taskset_string(&self) -> String {
+        // SAFETY: - Bitmaps are trusted to contain a valid ptr (type invariant)
+        //         - hwloc ops are trusted not to modify *const parameters
+        //         - hwloc_bitmap_taskset_snprintf is snprintf-like
+        let buf = unsafe {
+            ffi::call_snprintf(|buf, len| {
+                hwlocality_sys::hwloc_bitmap_taskset_snprintf(buf, len, self.as_ptr())
+            })
+        };
+        // SAFETY: call_snprintf() returns a NUL-terminated buffer
+        unsafe { CStr::from_ptr(buf.as_ptr()) }
+            .to_string_lossy()
+            .into_owned()
+    }
+
+    /// Parse a bitmap from the hexadecimal mask format of `taskset`
+    ///
+    /// See [`to_taskset_string()`](Self::to_taskset_string) for a description
+    /// of this format.
+    ///
+    /// # Errors
+    ///
+    /// - [`ParameterError`] if `mask` is not a valid taskset mask
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use hwlocality::bitmap::Bitmap;
+    ///
+    /// let bitmap = Bitmap::from_taskset_string("0xf0f")?;
+    /// assert_eq!(bitmap.to_list_string(), "0-3,8-11");
+    /// # Ok::<(), eyre::Report>(())
+    /// ```
+    #[doc(alias = "hwloc_bitmap_taskset_sscanf")]
+    pub fn from_taskset_string(mask: &str) -> Result<Self, ParameterError<String>> {
+        // SAFETY: hwloc_bitmap_taskset_sscanf is an sscanf-like API
+        unsafe {
+            Self::sscanf(mask, "hwloc_bitmap_taskset_sscanf", |bitmap, string| {
+                hwlocality_sys::hwloc_bitmap_taskset_sscanf(bitmap, string)
+            })
+        }
+    }
+
+    /// Convert this bitmap into an array of 64-bit words
+    ///
+    /// Bit `j` of word `i` is set if and only if index `64 * i + j` is set
+    /// in the bitmap, which matches the layout of the CPU masks used by
+    /// `sched_setaffinity()` and libnuma on 64-bit platforms. The returned
+    /// array ends with the word containing the last set index, so it is
+    /// empty for empty bitmaps.
+    ///
+    /// Infinite bitmaps cannot be represented in this format, so `None` is
+    /// returned for them.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use hwlocality::bitmap::Bitmap;
+    ///
+    /// let mut bitmap = Bitmap::from_range(0..=3);
+    /// bitmap.set(64);
+    /// assert_eq!(bitmap.to_words(), Some(vec![0xf, 0x1]));
+    /// assert_eq!(Bitmap::new().to_words(), Some(Vec::new()));
+    /// assert_eq!(Bitmap::full().to_words(), None);
+    /// ```
+    #[cfg(feature = "hwloc-2_1_0")]
+    #[doc(alias = "hwloc_bitmap_nr_ulongs")]
+    #[doc(alias = "hwloc_bitmap_to_ulongs")]
+    pub fn to_words(&self) -> Option<Vec<u64>> {
+        let nr_ulongs = errors::call_hwloc_int_raw(
+            "hwloc_bitmap_nr_ulongs",
+            // SAFETY: - Bitmaps are trusted to contain a valid ptr (type invariant)
+            //         - hwloc ops are trusted not to modify *const parameters
+            || unsafe { hwlocality_sys::hwloc_bitmap_nr_ulongs(self.as_ptr()) },
+            -1,
+        )
+        .expect(SHOULD_NOT_FAIL);
+        let nr_ulongs = c_uint::try_from(nr_ulongs).ok()?;
+        let mut ulongs = vec![0; usize::try_from(nr_ulongs).expect(SHOULD_NOT_FAIL)];
+        // SAFETY: - Bitmaps are trusted to contain a valid ptr (type invariant)
+        //         - hwloc ops are trusted not to modify *const parameters
+        //         - ulongs is a valid buffer of nr_ulongs elements
+        errors::call_hwloc_int_normal("hwloc_bitmap_to_ulongs", || unsafe {
+            hwlocality_sys::hwloc_bitmap_to_ulongs(self.as_ptr(), nr_ulongs, ulongs.as_mut_ptr())
+        })
+        .expect(SHOULD_NOT_FAIL);
+        Some(
+            ulongs
+                .chunks(ULONGS_PER_WORD)
+                .map(|chunk| {
+                    chunk.iter().enumerate().fold(0, |word, (idx, &ulong)| {
+                        // Lossless: c_ulong is at most 64 bits wide
+                        #[allow(
+                            clippy::cast_lossless,
+                            clippy::unnecessary_cast,
+                            trivial_numeric_casts
+                        )]
+                        let ulong = ulong as u64;
+                        word | (ulong << (idx * ULONG_BITS))
+                    })
+                })
+                .collect(),
+        )
+    }
+
+    /// Build a bitmap from an array of 64-bit words
+    ///
+    /// This is the inverse of [`to_words()`](Self::to_words): index
+    /// `64 * i + j` is set in the resulting bitmap if and only if bit `j` of
+    /// `words[i]` is set.
+    ///
+    /// # Panics
+    ///
+    /// If `words` is so large that it cannot be passed down to hwloc. This
+    /// would require a bitmap with more than 2^32 bits, so it should not
+    /// happen in practice.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use hwlocality::bitmap::Bitmap;
+    ///
+    /// let bitmap = Bitmap::from_words(&[0xf, 0x1]);
+    /// assert_eq!(bitmap.to_list_string(), "0-3,64");
+    /// assert_eq!(Bitmap::from_words(&[]), Bitmap::new());
+    /// ```
+    #[cfg(feature = "hwloc-2_1_0")]
+    #[doc(alias = "hwloc_bitmap_from_ulongs")]
+    pub fn from_words(words: &[u64]) -> Self {
+        // Truncation is intended: each ulong receives one slice of the word
+        #[allow(
+            clippy::cast_possible_truncation,
+            clippy::unnecessary_cast,
+            trivial_numeric_casts
+        )]
+        let ulongs = words
+            .iter()
+            .flat_map(|&word| {
+                (0..ULONGS_PER_WORD).map(move |idx| (word >> (idx * ULONG_BITS)) as c_ulong)
+            })
+            .collect::<Vec<_>>();
+        let nr_ulongs = c_uint::try_from(ulongs.len()).expect("too many words for hwloc");
+        let mut result = Self::new();
+        // SAFETY: - Bitmaps are trusted to contain a valid ptr (type invariant)
+        //         - hwloc ops are trusted to keep *mut parameters in a
+        //           valid state unless stated otherwise
+        //         - ulongs is a valid buffer of nr_ulongs elements
+        errors::call_hwloc_int_normal("hwloc_bitmap_from_ulongs", || unsafe {
+            hwlocality_sys::hwloc_bitmap_from_ulongs(
+                result.as_mut_ptr(),
+                nr_ulongs,
+                ulongs.as_ptr(),
+            )
+        })
+        .expect(MALLOC_FAIL_ONLY);
+        result
+    }
+
     // NOTE: When adding new methods, remember to add them to impl_newtype_ops too
 
     // === Implementation details ===
@@ -1160,6 +1691,125 @@ impl Bitmap {
             )
         }
     }
+
+    /// Reverse iterator building block
+    ///
+    /// Find the highest index below `index` that is set (if `set` is true) or
+    /// unset (if `set` is false). If `index` is `None`, the whole bitmap is
+    /// searched, and an infinite sequence of matching indices is considered
+    /// to end at [`BitmapIndex::MAX`].
+    fn prev(&self, index: Option<BitmapIndex>, set: bool) -> Option<BitmapIndex> {
+        let (first, last) = if set {
+            (self.first_set(), self.last_set())
+        } else {
+            (self.first_unset(), self.last_unset())
+        };
+        let first = first?;
+        let Some(index) = index else {
+            return Some(last.unwrap_or(BitmapIndex::MAX));
+        };
+
+        // Only scan the words between the first and last matching index, so
+        // that searching from a large index does not go through every word
+        let mut end = usize::from(index);
+        if let Some(last) = last {
+            end = end.min(usize::from(last) + 1);
+        }
+        let first = usize::from(first);
+        while end > first {
+            let ulong_idx = (end - 1) / ULONG_BITS;
+            let ulong_start = ulong_idx * ULONG_BITS;
+            // SAFETY: - Bitmaps are trusted to contain a valid ptr (type invariant)
+            //         - hwloc ops are trusted not to modify *const parameters
+            let ulong = unsafe {
+                hwlocality_sys::hwloc_bitmap_to_ith_ulong(
+                    self.as_ptr(),
+                    c_uint::try_from(ulong_idx).expect("should fit since indices do"),
+                )
+            };
+            let ulong = if set { ulong } else { !ulong };
+            let num_bits = end - ulong_start;
+            let mask = if num_bits == ULONG_BITS {
+                c_ulong::MAX
+            } else {
+                (1 << num_bits) - 1
+            };
+            let candidates = ulong & mask;
+            if candidates != 0 {
+                let offset = ULONG_BITS - 1 - candidates.leading_zeros() as usize;
+                return Some(
+                    BitmapIndex::try_from(ulong_start + offset)
+                        .expect("should be below the input index, which is a valid bitmap index"),
+                );
+            }
+            end = ulong_start;
+        }
+        None
+    }
+
+    /// Reverse set index iterator building block
+    fn prev_set(&self, index: Option<BitmapIndex>) -> Option<BitmapIndex> {
+        self.prev(index, true)
+    }
+
+    /// Reverse unset index iterator building block
+    fn prev_unset(&self, index: Option<BitmapIndex>) -> Option<BitmapIndex> {
+        self.prev(index, false)
+    }
+
+    /// Parse a bitmap using an sscanf-like hwloc API
+    ///
+    /// # Safety
+    ///
+    /// `sscanf` must be an hwloc API that takes an output bitmap and a C
+    /// string, reads the C string, and either overwrites the bitmap with its
+    /// parsed contents and returns 0 or returns -1 on parsing failure.
+    unsafe fn sscanf(
+        input: &str,
+        api: &'static str,
+        sscanf: impl FnOnce(*mut hwloc_bitmap_s, *const c_char) -> c_int,
+    ) -> Result<Self, ParameterError<String>> {
+        let make_error = || ParameterError(input.to_owned());
+        let string = LibcString::new(input).map_err(|_| make_error())?;
+        let mut result = Self::new();
+        // Per function precondition, sscanf can be called on a valid bitmap
+        // pointer and C string, which we provide here as type invariants
+        errors::call_hwloc_int_normal(api, || sscanf(result.as_mut_ptr(), string.borrow()))
+            .map_err(|_| make_error())?;
+        Ok(result)
+    }
+}
+
+/// Truth that `list` is a well-formed list string
+///
+/// See [`Bitmap::from_list_string()`] for the accepted syntax.
+fn is_valid_list_string(list: &str) -> bool {
+    /// Parse a bitmap index made only of decimal digits
+    fn parse_index(s: &str) -> Option<BitmapIndex> {
+        if s.is_empty() || !s.bytes().all(|b| b.is_ascii_digit()) {
+            return None;
+        }
+        s.parse().ok()
+    }
+
+    if list.is_empty() {
+        return true;
+    }
+    let mut items = list.split(',').peekable();
+    while let Some(item) = items.next() {
+        let valid = match item.split_once('-') {
+            None => parse_index(item).is_some(),
+            Some((start, "")) => parse_index(start).is_some() && items.peek().is_none(),
+            Some((start, end)) => match (parse_index(start), parse_index(end)) {
+                (Some(start), Some(end)) => start <= end,
+                _ => false,
+            },
+        };
+        if !valid {
+            return false;
+        }
+    }
+    true
 }
 
 /// Generic error message for `usize -> BitmapIndex` conversion errors
@@ -1173,6 +1823,13 @@ const MALLOC_FAIL_ONLY: &str =
 /// Common error message for operations that shouldn't fail
 const SHOULD_NOT_FAIL: &str = "This operation has no known failure mode";
 
+/// Number of bits in a C `unsigned long`, used by hwloc's word-based APIs
+const ULONG_BITS: usize = std::mem::size_of::<c_ulong>() * 8;
+
+/// Number of C `unsigned long`s in a 64-bit word
+#[cfg(feature = "hwloc-2_1_0")]
+const ULONGS_PER_WORD: usize = 64 / ULONG_BITS;
+
 #[cfg(any(test, feature = "proptest"))]
 impl Arbitrary for Bitmap {
     type Parameters = ();
@@ -1384,6 +2041,17 @@ impl Default for Bitmap {
     }
 }
 
+/// Bitmaps are deserialized from the list string format emitted by their
+/// [`Display`] implementation and [`Serialize`] implementation.
+#[cfg(feature = "serde")]
+impl<'de> Deserialize<'de> for Bitmap {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let s = String::deserialize(deserializer)?;
+        Self::from_list_string(&s)
+            .map_err(|_| de::Error::custom(format!("invalid bitmap list string {s:?}")))
+    }
+}
+
 impl Display for Bitmap {
     #[doc(alias = "hwloc_bitmap_list_snprintf")]
     fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
@@ -1459,25 +2127,61 @@ impl Hash for Bitmap {
 }
 
 /// Iterator over set or unset [`Bitmap`] indices
+///
+/// Indices are yielded in increasing order by default, but this iterator is
+/// double-ended, so [`Iterator::rev()`] can be used to go through them in
+/// decreasing order.
 #[derive(Copy, Clone, Debug, Eq, Hash, PartialEq)]
 pub struct Iter<B> {
     /// Bitmap over which we're iterating
     bitmap: B,
 
-    /// Last explored index
-    prev: Option<BitmapIndex>,
+    /// Last index yielded by forward iteration
+    front: Option<BitmapIndex>,
+
+    /// Last index yielded by reverse iteration
+    back: Option<BitmapIndex>,
 
-    /// Mapping from last index to next index
-    next: fn(&Bitmap, Option<BitmapIndex>) -> Option<BitmapIndex>,
+    /// Truth that all indices have been yielded
+    done: bool,
+
+    /// Truth that we're iterating over set indices, as opposed to unset ones
+    set: bool,
 }
 //
 impl<B> Iter<B> {
-    /// Set up a bitmap iterator
-    fn new(bitmap: B, next: fn(&Bitmap, Option<BitmapIndex>) -> Option<BitmapIndex>) -> Self {
+    /// Set up a bitmap iterator over set or unset indices
+    fn new(bitmap: B, set: bool) -> Self {
         Self {
             bitmap,
-            prev: None,
-            next,
+            front: None,
+            back: None,
+            done: false,
+            set,
+        }
+    }
+}
+//
+impl<B: Borrow<Bitmap>> DoubleEndedIterator for Iter<B> {
+    fn next_back(&mut self) -> Option<BitmapIndex> {
+        if self.done {
+            return None;
+        }
+        let bitmap = self.bitmap.borrow();
+        let prev = if self.set {
+            bitmap.prev_set(self.back)
+        } else {
+            bitmap.prev_unset(self.back)
+        };
+        match prev {
+            Some(prev) if self.front.map_or(true, |front| prev > front) => {
+                self.back = Some(prev);
+                Some(prev)
+            }
+            _ => {
+                self.done = true;
+                None
+            }
         }
     }
 }
@@ -1486,19 +2190,89 @@ impl<B: Borrow<Bitmap>> Iterator for Iter<B> {
     type Item = BitmapIndex;
 
     fn next(&mut self) -> Option<BitmapIndex> {
-        self.prev = (self.next)(self.bitmap.borrow(), self.prev);
-        self.prev
+        if self.done {
+            return None;
+        }
+        let bitmap = self.bitmap.borrow();
+        let next = if self.set {
+            bitmap.next_set(self.front)
+        } else {
+            bitmap.next_unset(self.front)
+        };
+        match next {
+            Some(next) if self.back.map_or(true, |back| next < back) => {
+                self.front = Some(next);
+                Some(next)
+            }
+            _ => {
+                self.done = true;
+                None
+            }
+        }
     }
 }
 //
 impl<B: Borrow<Bitmap>> FusedIterator for Iter<B> {}
 //
+/// Iterator over ranges of consecutive set [`Bitmap`] indices
+///
+/// Obtained via [`Bitmap::ranges()`].
+#[derive(Copy, Clone, Debug, Eq, Hash, PartialEq)]
+pub struct Ranges<B> {
+    /// Bitmap over which we're iterating
+    bitmap: B,
+
+    /// End of the last range that was yielded, if any
+    prev_end: Option<BitmapIndex>,
+
+    /// Truth that all ranges have been yielded
+    done: bool,
+}
+//
+impl<B> Ranges<B> {
+    /// Set up a range iterator
+    fn new(bitmap: B) -> Self {
+        Self {
+            bitmap,
+            prev_end: None,
+            done: false,
+        }
+    }
+}
+//
+impl<B: Borrow<Bitmap>> Iterator for Ranges<B> {
+    type Item = RangeInclusive<BitmapIndex>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+        let bitmap = self.bitmap.borrow();
+        let Some(start) = bitmap.next_set(self.prev_end) else {
+            self.done = true;
+            return None;
+        };
+        let end = bitmap
+            .next_unset(Some(start))
+            .map_or(BitmapIndex::MAX, |unset| {
+                unset
+                    .checked_add_signed(-1)
+                    .expect("next unset index should be above a set index")
+            });
+        self.done = end == BitmapIndex::MAX;
+        self.prev_end = Some(end);
+        Some(start..=end)
+    }
+}
+//
+impl<B: Borrow<Bitmap>> FusedIterator for Ranges<B> {}
+
 impl<'bitmap> IntoIterator for &'bitmap Bitmap {
     type Item = BitmapIndex;
     type IntoIter = Iter<Self>;
 
     fn into_iter(self) -> Self::IntoIter {
-        Iter::new(self, Bitmap::next_set)
+        Iter::new(self, true)
     }
 }
 //
@@ -1507,7 +2281,7 @@ impl IntoIterator for Bitmap {
     type IntoIter = Iter<Self>;
 
     fn into_iter(self) -> Self::IntoIter {
-        Iter::new(self, Self::next_set)
+        Iter::new(self, true)
     }
 }
 
@@ -1584,6 +2358,16 @@ impl Pointer for Bitmap {
 // SAFETY: Safe because Bitmap exposes no internal mutability
 unsafe impl Send for Bitmap {}
 
+/// Bitmaps are serialized in the list string format of their [`Display`]
+/// implementation, e.g. `"0-3,8,12-"` for a bitmap where indices 0 to 3, 8,
+/// and everything from 12 onwards is set.
+#[cfg(feature = "serde")]
+impl Serialize for Bitmap {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
 impl<B: Borrow<Bitmap>> Sub<B> for &Bitmap {
     type Output = Bitmap;
 
@@ -1703,7 +2487,7 @@ pub(crate) mod tests {
         Not<Output=Bitmap>,
         Sub<Bitmap>, Sub<&'static Bitmap>,
     );
-    assert_type_eq_all!(BitmapIndex, PositiveInt);
+    assert_type_eq_all!(BitmapIndex, crate::ffi::PositiveInt);
     assert_impl_all!(Iter<Bitmap>:
         Clone, Debug, FusedIterator<Item=BitmapIndex>, Hash, Sized, Sync, Unpin,
         UnwindSafe
@@ -1720,6 +2504,14 @@ pub(crate) mod tests {
         Binary, Default, Deref, Display, LowerExp, LowerHex, Octal, Pointer,
         Read, UpperExp, UpperHex, fmt::Write, io::Write
     );
+    assert_impl_all!(Ranges<&Bitmap>:
+        Copy, Debug, FusedIterator<Item=RangeInclusive<BitmapIndex>>, Hash,
+        Sized, Sync, Unpin, UnwindSafe
+    );
+    assert_not_impl_any!(Ranges<&Bitmap>:
+        Binary, Default, Deref, Display, LowerExp, LowerHex, Octal, Pointer,
+        Read, UpperExp, UpperHex, fmt::Write, io::Write
+    );
 
     // We can't fully check the value of infinite iterators because that would
     // literally take forever, so we only check a small subrange of the final
@@ -1897,6 +2689,18 @@ pub(crate) mod tests {
             prop_assert_eq!(borrow.0, inner);
             test_bitmap_ref_unary(&bitmap, borrow)?;
         }
+        {
+            // Public interop round trip, on a copy of the original
+            prop_assert_eq!(bitmap.as_raw(), inner.as_ptr().cast_const());
+            let copy = bitmap.clone();
+            let copy_ptr = copy.as_raw();
+            let raw = copy.into_raw();
+            prop_assert_eq!(raw.as_ptr().cast_const(), copy_ptr);
+            // SAFETY: raw comes from into_raw and isn't used after this
+            let copy = unsafe { Bitmap::from_raw(raw) };
+            prop_assert_eq!(copy.as_raw(), copy_ptr);
+            prop_assert_eq!(&copy, &bitmap);
+        }
         Ok(())
     }
 
@@ -2789,4 +3593,221 @@ pub(crate) mod tests {
             test_bitmap_ref_binops(&bitmap, &other)?;
         }
     }
+
+    /// Check reverse searches from a large index
+    #[test]
+    fn prev_from_large_index() {
+        let sparse = Bitmap::from_ranges([0..=0, 5..=5]);
+        assert_eq!(
+            sparse.prev_set_before(BitmapIndex::MAX),
+            Some(5.try_into().unwrap())
+        );
+        assert_eq!(Bitmap::new().prev_set_before(BitmapIndex::MAX), None);
+        let infinite = Bitmap::from_range(10..);
+        assert_eq!(
+            infinite
+                .iter_unset()
+                .rev()
+                .map(usize::from)
+                .collect::<Vec<_>>(),
+            (0..10).rev().collect::<Vec<_>>()
+        );
+        assert_eq!(Bitmap::full().iter_unset().next_back(), None);
+    }
+
+    proptest! {
+        #[test]
+        fn singleton_only_bit(bitmap: Bitmap, index in bitmap_index()) {
+            let singleton = Bitmap::singleton(index);
+            prop_assert_eq!(&singleton, &Bitmap::from(index));
+            prop_assert_eq!(singleton.only_bit(), Some(index));
+            let expected = (bitmap.weight() == Some(1)).then(|| bitmap.first_set().unwrap());
+            prop_assert_eq!(bitmap.only_bit(), expected);
+        }
+
+        #[test]
+        fn next_prev_set(bitmap: Bitmap, index in bitmap_index()) {
+            let ranges = bitmap.ranges().collect::<Vec<_>>();
+            let expected_next = index.checked_add_signed(1).and_then(|after| {
+                ranges
+                    .iter()
+                    .find(|range| *range.end() >= after)
+                    .map(|range| (*range.start()).max(after))
+            });
+            prop_assert_eq!(bitmap.next_set_after(index), expected_next);
+            let expected_prev = index.checked_add_signed(-1).and_then(|before| {
+                ranges
+                    .iter()
+                    .rev()
+                    .find(|range| *range.start() <= before)
+                    .map(|range| (*range.end()).min(before))
+            });
+            prop_assert_eq!(bitmap.prev_set_before(index), expected_prev);
+        }
+
+        #[test]
+        fn prev_set_before_max(bitmap: Bitmap) {
+            let expected = if bitmap.weight().is_some() {
+                bitmap.iter_set().filter(|&idx| idx < BitmapIndex::MAX).max()
+            } else {
+                BitmapIndex::MAX.checked_add_signed(-1)
+            };
+            prop_assert_eq!(bitmap.prev_set_before(BitmapIndex::MAX), expected);
+        }
+
+        #[test]
+        fn reverse_iteration(bitmap: Bitmap) {
+            if bitmap.weight().is_some() {
+                let forward = bitmap.iter_set().collect::<Vec<_>>();
+                let mut reverse = bitmap.iter_set().rev().collect::<Vec<_>>();
+                reverse.reverse();
+                prop_assert_eq!(&reverse, &forward);
+
+                let mut iter = bitmap.iter_set();
+                prop_assert_eq!(iter.next(), forward.first().copied());
+                prop_assert_eq!(iter.next_back(), forward.get(1..).and_then(<[_]>::last).copied());
+                let middle = forward.get(1..forward.len().saturating_sub(1)).unwrap_or(&[]);
+                prop_assert_eq!(iter.collect::<Vec<_>>(), middle);
+
+                prop_assert_eq!(bitmap.iter_unset().next_back(), Some(BitmapIndex::MAX));
+            } else {
+                prop_assert_eq!(bitmap.iter_set().next_back(), Some(BitmapIndex::MAX));
+
+                let forward = bitmap.iter_unset().collect::<Vec<_>>();
+                let mut reverse = bitmap.iter_unset().rev().collect::<Vec<_>>();
+                reverse.reverse();
+                prop_assert_eq!(reverse, forward);
+            }
+        }
+
+        #[test]
+        fn union_intersect_all(bitmaps in prop::collection::vec(any::<Bitmap>(), 0..5)) {
+            let union = bitmaps.iter().fold(Bitmap::new(), |acc, bitmap| acc | bitmap);
+            prop_assert_eq!(Bitmap::union_all(&bitmaps), union);
+            let intersection = bitmaps.iter().fold(Bitmap::full(), |acc, bitmap| acc & bitmap);
+            prop_assert_eq!(Bitmap::intersect_all(&bitmaps), intersection);
+        }
+
+        #[test]
+        fn list_string_roundtrip(bitmap: Bitmap) {
+            let list = bitmap.to_list_string();
+            prop_assert_eq!(&list, &bitmap.to_string());
+            prop_assert_eq!(Bitmap::from_list_string(&list).unwrap(), &bitmap);
+        }
+
+        #[test]
+        fn ranges_roundtrip(bitmap: Bitmap) {
+            let mut ranges = bitmap.ranges().collect::<Vec<_>>();
+            for (idx, range) in ranges.iter().enumerate() {
+                prop_assert!(range.start() <= range.end());
+                prop_assert!(bitmap.is_set(*range.start()));
+                prop_assert!(bitmap.is_set(*range.end()));
+                if let Some(next) = ranges.get(idx + 1) {
+                    prop_assert!(usize::from(*range.end()) + 1 < usize::from(*next.start()));
+                }
+            }
+            let (finite, infinite) = split_infinite_bitmap(bitmap.clone());
+            if let Some(infinite) = infinite {
+                // Don't feed the infinite range back to from_ranges(), that
+                // would allocate a 2^31-bit bitmap
+                let last = ranges.pop().unwrap();
+                prop_assert_eq!(*last.end(), BitmapIndex::MAX);
+                prop_assert!(*last.start() <= infinite.start);
+                let mut expected = Bitmap::from_ranges(ranges);
+                expected.set_range(*last.start()..infinite.start);
+                prop_assert_eq!(expected, finite);
+            } else {
+                prop_assert_eq!(Bitmap::from_ranges(ranges), &bitmap);
+            }
+        }
+
+        #[test]
+        fn taskset_string_roundtrip(bitmap: Bitmap) {
+            let mask = bitmap.to_taskset_string();
+            prop_assert!(mask.starts_with("0x"));
+            prop_assert_eq!(Bitmap::from_taskset_string(&mask).unwrap(), &bitmap);
+        }
+
+        #[cfg(feature = "hwloc-2_1_0")]
+        #[test]
+        fn words_roundtrip(bitmap: Bitmap) {
+            let Some(words) = bitmap.to_words() else {
+                prop_assert_eq!(bitmap.weight(), None);
+                return Ok(());
+            };
+            prop_assert_ne!(words.last(), Some(&0));
+            for (word_idx, word) in words.iter().enumerate() {
+                for bit in 0..64 {
+                    prop_assert_eq!(
+                        word & (1 << bit) != 0,
+                        bitmap.is_set(word_idx * 64 + bit)
+                    );
+                }
+            }
+            prop_assert_eq!(Bitmap::from_words(&words), bitmap);
+        }
+    }
+
+    #[test]
+    fn string_formats() {
+        let mut bitmap = Bitmap::from_range(0..=3);
+        bitmap.set_range(8..=11);
+        assert_eq!(bitmap.to_list_string(), "0-3,8-11");
+        assert_eq!(bitmap.to_taskset_string(), "0xf0f");
+        assert_eq!(Bitmap::from_list_string("0-3,8-11").unwrap(), &bitmap);
+        assert_eq!(Bitmap::from_taskset_string("0xf0f").unwrap(), &bitmap);
+        assert_eq!(Bitmap::from_list_string("").unwrap(), Bitmap::new());
+        assert_eq!(Bitmap::from_list_string("0-").unwrap(), Bitmap::full());
+        for invalid in [
+            "a", "0-a", "1\0", "3-1", "1,,2", ",", "-1", "1,", " 1", "+1", "0-,4", "1-2-3",
+        ] {
+            assert_eq!(
+                Bitmap::from_list_string(invalid).unwrap_err(),
+                ParameterError(invalid.to_owned())
+            );
+        }
+        assert_eq!(
+            Bitmap::from_taskset_string("zz").unwrap_err(),
+            ParameterError("zz".to_owned())
+        );
+    }
+
+    #[cfg(feature = "serde")]
+    proptest! {
+        #[test]
+        fn serde_roundtrip(bitmap: Bitmap) {
+            let json = serde_json::to_string(&bitmap).unwrap();
+            prop_assert_eq!(&json, &format!("\"{bitmap}\""));
+            prop_assert_eq!(serde_json::from_str::<Bitmap>(&json).unwrap(), bitmap);
+        }
+
+        #[test]
+        fn serde_any_string(s in crate::strategies::any_string()) {
+            let json = serde_json::to_string(&s).unwrap();
+            if let Ok(bitmap) = serde_json::from_str::<Bitmap>(&json) {
+                prop_assert_eq!(Bitmap::from_list_string(&bitmap.to_string()), Ok(bitmap));
+            }
+        }
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn serde_invalid() {
+        for s in ["a", "-", "0-a", "1,a", "3-1", "1,,2", ",", "-1"] {
+            let json = serde_json::to_string(s).unwrap();
+            assert!(serde_json::from_str::<Bitmap>(&json).is_err(), "{s:?}");
+        }
+        for (s, expected) in [
+            ("", Bitmap::new()),
+            ("0-", Bitmap::full()),
+            ("1,3-4", {
+                let mut bitmap = Bitmap::from_range(3..=4);
+                bitmap.set(1);
+                bitmap
+            }),
+        ] {
+            let json = serde_json::to_string(s).unwrap();
+            assert_eq!(serde_json::from_str::<Bitmap>(&json).unwrap(), expected);
+        }
+    }
 }
@@ -123,7 +123,7 @@ macro_rules! impl_bitmap_newtype {
             use $crate::{
                 bitmap::{
                     Bitmap, BitmapIndex, BitmapKind, BitmapRef, OwnedBitmap,
-                    Iter, SpecializedBitmap
+                    Iter, Ranges, SpecializedBitmap
                 },
             };
             use derive_more::{AsMut, AsRef, From, Into, IntoIterator, Not};
@@ -131,6 +131,8 @@ macro_rules! impl_bitmap_newtype {
             #[allow(unused)]
             #[cfg(any(test, feature = "proptest"))]
             use proptest::prelude::*;
+            #[cfg(feature = "serde")]
+            use serde::{Deserialize, Deserializer, Serialize, Serializer};
             #[cfg(test)]
             use similar_asserts::assert_eq;
             use std::{
@@ -176,6 +178,34 @@ macro_rules! impl_bitmap_newtype {
             /// Only documentation headers are repeated here, you will find most of
             /// the documentation attached to identically named `Bitmap` methods.
             impl $newtype {
+                /// Take ownership of a bitmap that was allocated by another C library
+                ///
+                /// See [`Bitmap::from_raw`](crate::bitmap::Bitmap::from_raw).
+                ///
+                /// # Safety
+                ///
+                /// Safety contract is identical to that of `Bitmap::from_raw`.
+                pub unsafe fn from_raw(bitmap: NonNull<hwloc_bitmap_s>) -> Self {
+                    // SAFETY: Safety contract inherited from identical Bitmap method
+                    unsafe {
+                        Self::from(Bitmap::from_raw(bitmap))
+                    }
+                }
+
+                /// Hand over ownership of this bitmap to another C library
+                ///
+                /// See [`Bitmap::into_raw`](crate::bitmap::Bitmap::into_raw).
+                pub fn into_raw(self) -> NonNull<hwloc_bitmap_s> {
+                    self.0.into_raw()
+                }
+
+                /// Raw pointer to the underlying hwloc bitmap
+                ///
+                /// See [`Bitmap::as_raw`](crate::bitmap::Bitmap::as_raw).
+                pub fn as_raw(&self) -> *const hwloc_bitmap_s {
+                    self.0.as_raw()
+                }
+
                 /// Wraps an owned nullable hwloc bitmap
                 ///
                 /// See [`Bitmap::from_owned_raw_mut`](crate::bitmap::Bitmap::from_owned_raw_mut).
@@ -201,8 +231,11 @@ macro_rules! impl_bitmap_newtype {
                 /// Wraps a borrowed nullable hwloc bitmap
                 ///
                 /// See [`Bitmap::borrow_from_raw`](crate::bitmap::Bitmap::borrow_from_raw).
-                #[allow(unused)]
-                pub(crate) unsafe fn borrow_from_raw<'target>(
+                ///
+                /// # Safety
+                ///
+                /// Safety contract is identical to that of `Bitmap::borrow_from_raw`.
+                pub unsafe fn borrow_from_raw<'target>(
                     bitmap: *const hwloc_bitmap_s
                 ) -> Option<BitmapRef<'target, Self>> {
                     // SAFETY: Safety contract inherited from identical Bitmap method
@@ -278,6 +311,29 @@ macro_rules! impl_bitmap_newtype {
                     Self::from(Bitmap::from_range(range))
                 }
 
+                /// Creates a new bitmap where only `idx` is set
+                ///
+                /// See [`Bitmap::singleton`](crate::bitmap::Bitmap::singleton).
+                pub fn singleton<Idx>(idx: Idx) -> Self
+                where
+                    Idx: TryInto<BitmapIndex>,
+                    <Idx as TryInto<BitmapIndex>>::Error: Debug,
+                {
+                    Self::from(Bitmap::singleton(idx))
+                }
+
+                /// Creates a new bitmap with the given ranges of indices set
+                ///
+                /// See [`Bitmap::from_ranges`](crate::bitmap::Bitmap::from_ranges).
+                pub fn from_ranges<Idx, Range>(ranges: impl IntoIterator<Item = Range>) -> Self
+                where
+                    Idx: Copy + TryInto<BitmapIndex>,
+                    <Idx as TryInto<BitmapIndex>>::Error: Debug,
+                    Range: RangeBounds<Idx>,
+                {
+                    Self::from(Bitmap::from_ranges(ranges))
+                }
+
                 /// Turn this bitmap into a copy of another bitmap
                 ///
                 /// See [`Bitmap::copy_from`](crate::bitmap::Bitmap::copy_from).
@@ -411,6 +467,13 @@ macro_rules! impl_bitmap_newtype {
                     self.0.iter_set()
                 }
 
+                /// Iterate over ranges of consecutive set indices
+                ///
+                /// See [`Bitmap::ranges`](crate::bitmap::Bitmap::ranges).
+                pub fn ranges(&self) -> Ranges<&Bitmap> {
+                    self.0.ranges()
+                }
+
                 /// Check the last set index, if any
                 ///
                 /// See [`Bitmap::last_set`](crate::bitmap::Bitmap::last_set).
@@ -418,6 +481,36 @@ macro_rules! impl_bitmap_newtype {
                     self.0.last_set()
                 }
 
+                /// Check the first set index after `idx`, if any
+                ///
+                /// See [`Bitmap::next_set_after`](crate::bitmap::Bitmap::next_set_after).
+                pub fn next_set_after<Idx>(&self, idx: Idx) -> Option<BitmapIndex>
+                where
+                    Idx: TryInto<BitmapIndex>,
+                    <Idx as TryInto<BitmapIndex>>::Error: Debug,
+                {
+                    self.0.next_set_after(idx)
+                }
+
+                /// Check the last set index before `idx`, if any
+                ///
+                /// See [`Bitmap::prev_set_before`](crate::bitmap::Bitmap::prev_set_before).
+                pub fn prev_set_before<Idx>(&self, idx: Idx) -> Option<BitmapIndex>
+                where
+                    Idx: TryInto<BitmapIndex>,
+                    <Idx as TryInto<BitmapIndex>>::Error: Debug,
+                {
+                    self.0.prev_set_before(idx)
+                }
+
+                /// The index that is set in the bitmap, if it is the only one
+                ///
+                /// See [`Bitmap::only_bit`](crate::bitmap::Bitmap::only_bit).
+                #[doc(alias = "try_only_set")]
+                pub fn only_bit(&self) -> Option<BitmapIndex> {
+                    self.0.only_bit()
+                }
+
                 /// The number of indices that are set in the bitmap
                 ///
                 /// See [`Bitmap::weight`](crate::bitmap::Bitmap::weight).
@@ -467,6 +560,90 @@ macro_rules! impl_bitmap_newtype {
                 pub fn includes(&self, inner: impl Deref<Target = Self>) -> bool {
                     self.0.includes(&inner.0)
                 }
+
+                /// Compute the union of many bitmaps
+                ///
+                /// See [`Bitmap::union_all`](crate::bitmap::Bitmap::union_all).
+                pub fn union_all<B: Borrow<Self>>(sets: impl IntoIterator<Item = B>) -> Self {
+                    let mut result = Self::new();
+                    for set in sets {
+                        result.0 |= &set.borrow().0;
+                    }
+                    result
+                }
+
+                /// Compute the intersection of many bitmaps
+                ///
+                /// See [`Bitmap::intersect_all`](crate::bitmap::Bitmap::intersect_all).
+                pub fn intersect_all<B: Borrow<Self>>(sets: impl IntoIterator<Item = B>) -> Self {
+                    let mut sets = sets.into_iter();
+                    let Some(first) = sets.next() else {
+                        return Self::full();
+                    };
+                    let mut result = first.borrow().clone();
+                    for set in sets {
+                        result.0 &= &set.borrow().0;
+                    }
+                    result
+                }
+
+                /// Format this bitmap in the list format of the Linux kernel
+                ///
+                /// See [`Bitmap::to_list_string`](crate::bitmap::Bitmap::to_list_string).
+                pub fn to_list_string(&self) -> String {
+                    self.0.to_list_string()
+                }
+
+                /// Parse a bitmap from the list format of the Linux kernel
+                ///
+                /// See [`Bitmap::from_list_string`](crate::bitmap::Bitmap::from_list_string).
+                ///
+                /// # Errors
+                ///
+                /// - [`ParameterError`](crate::errors::ParameterError) if
+                ///   `list` is not a valid list string
+                pub fn from_list_string(
+                    list: &str
+                ) -> Result<Self, $crate::errors::ParameterError<String>> {
+                    Bitmap::from_list_string(list).map(Self)
+                }
+
+                /// Format this bitmap as a hexadecimal mask, as used by `taskset`
+                ///
+                /// See [`Bitmap::to_taskset_string`](crate::bitmap::Bitmap::to_taskset_string).
+                pub fn to_taskset_string(&self) -> String {
+                    self.0.to_taskset_string()
+                }
+
+                /// Parse a bitmap from the hexadecimal mask format of `taskset`
+                ///
+                /// See [`Bitmap::from_taskset_string`](crate::bitmap::Bitmap::from_taskset_string).
+                ///
+                /// # Errors
+                ///
+                /// - [`ParameterError`](crate::errors::ParameterError) if
+                ///   `mask` is not a valid taskset mask
+                pub fn from_taskset_string(
+                    mask: &str
+                ) -> Result<Self, $crate::errors::ParameterError<String>> {
+                    Bitmap::from_taskset_string(mask).map(Self)
+                }
+
+                /// Convert this bitmap into an array of 64-bit words
+                ///
+                /// See [`Bitmap::to_words`](crate::bitmap::Bitmap::to_words).
+                #[cfg(feature = "hwloc-2_1_0")]
+                pub fn to_words(&self) -> Option<Vec<u64>> {
+                    self.0.to_words()
+                }
+
+                /// Build a bitmap from an array of 64-bit words
+                ///
+                /// See [`Bitmap::from_words`](crate::bitmap::Bitmap::from_words).
+                #[cfg(feature = "hwloc-2_1_0")]
+                pub fn from_words(words: &[u64]) -> Self {
+                    Self(Bitmap::from_words(words))
+                }
             }
 
             #[cfg(any(test, feature = "proptest"))]
@@ -567,6 +744,13 @@ macro_rules! impl_bitmap_newtype {
                 }
             }
 
+            #[cfg(feature = "serde")]
+            impl<'de> Deserialize<'de> for $newtype {
+                fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+                    Bitmap::deserialize(deserializer).map(Self)
+                }
+            }
+
             impl Display for $newtype {
                 fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
                     let text = format!("{}({})", stringify!($newtype), &self.0);
@@ -644,6 +828,13 @@ macro_rules! impl_bitmap_newtype {
 
             impl $crate::Sealed for $newtype {}
 
+            #[cfg(feature = "serde")]
+            impl Serialize for $newtype {
+                fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+                    self.0.serialize(serializer)
+                }
+            }
+
             impl<B: Borrow<$newtype>> Sub<B> for &$newtype {
                 type Output = $newtype;
 
@@ -763,6 +954,7 @@ macro_rules! impl_bitmap_newtype {
                     #[test]
                     fn from_idx(idx in bitmap_index()) {
                         prop_assert_eq!($newtype::from(idx), $newtype(Bitmap::from(idx)));
+                        prop_assert_eq!($newtype::singleton(idx), $newtype(Bitmap::singleton(idx)));
                     }
 
                     #[test]
@@ -773,6 +965,27 @@ macro_rules! impl_bitmap_newtype {
                         );
                     }
 
+                    #[test]
+                    fn from_ranges(ranges in prop::collection::vec(index_range(), 0..4)) {
+                        prop_assert_eq!(
+                            $newtype::from_ranges(ranges.clone()),
+                            $newtype(Bitmap::from_ranges(ranges))
+                        );
+                    }
+
+                    #[test]
+                    fn union_intersect_all(sets in prop::collection::vec(any::<$newtype>(), 0..4)) {
+                        let bitmaps = sets.iter().map(|set| set.0.clone()).collect::<Vec<_>>();
+                        prop_assert_eq!(
+                            $newtype::union_all(&sets),
+                            $newtype(Bitmap::union_all(&bitmaps))
+                        );
+                        prop_assert_eq!(
+                            $newtype::intersect_all(&sets),
+                            $newtype(Bitmap::intersect_all(&bitmaps))
+                        );
+                    }
+
                     #[test]
                     fn from_iterator(v in index_vec()) {
                         let new = v.iter().copied().collect::<$newtype>();
@@ -788,6 +1001,16 @@ macro_rules! impl_bitmap_newtype {
                     }
                 }
 
+                #[cfg(feature = "serde")]
+                proptest! {
+                    #[test]
+                    fn serde(new: $newtype) {
+                        let json = serde_json::to_string(&new).unwrap();
+                        prop_assert_eq!(&json, &serde_json::to_string(&new.0).unwrap());
+                        prop_assert_eq!(serde_json::from_str::<$newtype>(&json).unwrap(), new);
+                    }
+                }
+
                 proptest! {
                     #[test]
                     fn unary(new: $newtype) {
@@ -795,6 +1018,7 @@ macro_rules! impl_bitmap_newtype {
                         prop_assert_eq!(new.first_set(), new.0.first_set());
                         prop_assert_eq!(new.last_set(), new.0.last_set());
                         prop_assert_eq!(new.weight(), new.0.weight());
+                        prop_assert_eq!(new.only_bit(), new.0.only_bit());
                         prop_assert_eq!(new.first_unset(), new.0.first_unset());
                         prop_assert_eq!(new.last_unset(), new.0.last_unset());
                         prop_assert_eq!(
@@ -824,6 +1048,7 @@ macro_rules! impl_bitmap_newtype {
                             .into_iter()
                             .take(INFINITE_EXPLORE_ITERS)
                             .eq(new.0.iter_set().take(INFINITE_EXPLORE_ITERS)));
+                        prop_assert!(new.ranges().eq(new.0.ranges()));
                         prop_assert!(new
                             .iter_unset()
                             .take(INFINITE_EXPLORE_ITERS)
@@ -875,6 +1100,14 @@ macro_rules! impl_bitmap_newtype {
 
                         // Test low-level functions and BitmapRef<$newtype>
                         test_newtype_ref_unary(&&new, BitmapRef::from(&new))?;
+                        {
+                            let copy = new.clone();
+                            let raw = copy.into_raw();
+                            // SAFETY: raw comes from into_raw and isn't used after this
+                            let copy = unsafe { $newtype::from_raw(raw) };
+                            prop_assert_eq!(copy.as_raw(), raw.as_ptr().cast_const());
+                            prop_assert_eq!(&copy, &new);
+                        }
                         let new = ManuallyDrop::new(new);
                         let new_const = new.as_ptr();
                         let new_mut = new_const.cast_mut();
@@ -924,6 +1157,9 @@ macro_rules! impl_bitmap_newtype {
                         new: $newtype,
                         idx in bitmap_index()
                     ) {
+                        prop_assert_eq!(new.next_set_after(idx), new.0.next_set_after(idx));
+                        prop_assert_eq!(new.prev_set_before(idx), new.0.prev_set_before(idx));
+
                         let mut buf = new.clone();
                         buf.set_only(idx);
                         prop_assert_eq!(&buf, &$newtype::from(idx));
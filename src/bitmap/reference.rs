@@ -246,7 +246,7 @@ where
     type IntoIter = Iter<&'self_ Target>;
 
     fn into_iter(self) -> Self::IntoIter {
-        Iter::new(self.as_ref(), Bitmap::next_set)
+        Iter::new(self.as_ref(), true)
     }
 }
 
@@ -255,7 +255,7 @@ impl<'target, Target: OwnedBitmap> IntoIterator for BitmapRef<'target, Target> {
     type IntoIter = Iter<BitmapRef<'target, Bitmap>>;
 
     fn into_iter(self) -> Self::IntoIter {
-        Iter::new(self.cast(), Bitmap::next_set)
+        Iter::new(self.cast(), true)
     }
 }
 
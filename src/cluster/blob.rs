@@ -0,0 +1,557 @@
+//! Wire format for sending topologies to a collector
+//!
+//! When a [`Cluster`] is assembled from topologies gathered over the network,
+//! each machine must send its topology along with enough metadata for the
+//! collector to tell where it comes from and whether it arrived intact. The
+//! [`TopologyBlob`] type defined in this module provides a standard format for
+//! this, so that you don't need to invent your own.
+//!
+//! A blob is a short text header followed by the XML export of the topology:
+//!
+//! ```text
+//! hwlocality-topology-blob 1
+//! hostname node042
+//! hwloc-api-version 133632
+//! timestamp 1700000000
+//! fingerprint 6c62272e07bb0142
+//!
+//! <?xml version="1.0" encoding="UTF-8"?>
+//! ...
+//! ```
+//!
+//! The fingerprint is a 64-bit FNV-1a hash of the XML data, which lets the
+//! collector detect truncated or corrupted transfers. It is not a
+//! cryptographic hash and does not protect against malicious tampering.
+
+use super::{Cluster, LoadError};
+use crate::{
+    errors::RawHwlocError,
+    topology::{export::xml::XMLExportFlags, Topology},
+};
+#[allow(unused)]
+#[cfg(test)]
+use similar_asserts::assert_eq;
+use std::{
+    collections::btree_map::Entry,
+    time::{Duration, SystemTime},
+};
+use thiserror::Error;
+
+/// First line of every topology blob, followed by the format version
+const MAGIC: &str = "hwlocality-topology-blob";
+
+/// Version of the blob format emitted by this version of hwlocality
+const FORMAT_VERSION: &str = "1";
+
+/// Topology of a machine, with provenance metadata, ready to be sent to a
+/// collector
+///
+/// On each machine, use [`TopologyBlob::capture()`] to record the local
+/// topology, then send the output of [`TopologyBlob::to_bytes()`] to the
+/// collector by any means. On the collector, use [`Cluster::import_blob()`] to
+/// validate the blob and add the topology to the cluster, or
+/// [`TopologyBlob::parse()`] if you need to handle the data yourself.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct TopologyBlob {
+    /// Hostname of the machine that the topology was captured on
+    hostname: String,
+
+    /// hwloc API version of the machine that the topology was captured on
+    hwloc_api_version: usize,
+
+    /// Time at which the topology was captured, with one-second precision
+    timestamp: SystemTime,
+
+    /// Fingerprint of the XML data
+    fingerprint: u64,
+
+    /// XML export of the topology
+    xml: String,
+}
+//
+impl TopologyBlob {
+    /// Capture a topology, keyed by the hostname that hwloc recorded
+    ///
+    /// The hostname is read from the `HostName` info attribute of the
+    /// topology's root object, which hwloc sets when probing the local system
+    /// on most operating systems. Use [`TopologyBlob::capture_as()`] if you
+    /// want to use another name.
+    ///
+    /// # Errors
+    ///
+    /// - [`NoHostname`] if hwloc did not record the hostname.
+    /// - [`BadHostname`] if the recorded hostname is not suitable for
+    ///   inclusion in a blob.
+    /// - [`Export`] if the topology could not be exported to XML.
+    ///
+    /// [`BadHostname`]: CaptureError::BadHostname
+    /// [`Export`]: CaptureError::Export
+    /// [`NoHostname`]: CaptureError::NoHostname
+    pub fn capture(topology: &Topology) -> Result<Self, CaptureError> {
+        let hostname = topology
+            .root_object()
+            .info("HostName")
+            .ok_or(CaptureError::NoHostname)?;
+        let Ok(hostname) = hostname.to_str() else {
+            return Err(CaptureError::BadHostname(
+                hostname.to_string_lossy().into_owned(),
+            ));
+        };
+        Self::capture_as(topology, hostname)
+    }
+
+    /// Capture a topology, keyed by a user-specified hostname
+    ///
+    /// # Errors
+    ///
+    /// - [`BadHostname`] if `hostname` is empty or contains control
+    ///   characters such as line feeds.
+    /// - [`Export`] if the topology could not be exported to XML.
+    ///
+    /// [`BadHostname`]: CaptureError::BadHostname
+    /// [`Export`]: CaptureError::Export
+    pub fn capture_as(topology: &Topology, hostname: &str) -> Result<Self, CaptureError> {
+        if !is_valid_hostname(hostname) {
+            return Err(CaptureError::BadHostname(hostname.to_owned()));
+        }
+        let xml = topology.export_xml(XMLExportFlags::empty())?.to_string();
+        let since_epoch = SystemTime::now()
+            .duration_since(SystemTime::UNIX_EPOCH)
+            .unwrap_or_default();
+        Ok(Self {
+            hostname: hostname.to_owned(),
+            hwloc_api_version: crate::hwloc_api_version(),
+            timestamp: SystemTime::UNIX_EPOCH + Duration::from_secs(since_epoch.as_secs()),
+            fingerprint: fingerprint(&xml),
+            xml,
+        })
+    }
+
+    /// Parse and validate a blob that was emitted by
+    /// [`TopologyBlob::to_bytes()`]
+    ///
+    /// This checks that the blob is well-formed and that the XML data matches
+    /// the fingerprint, but it does not try to load the topology. Use
+    /// [`TopologyBlob::load()`] for that.
+    ///
+    /// # Errors
+    ///
+    /// - [`NotUtf8`] if `bytes` is not valid UTF-8.
+    /// - [`NotABlob`] if `bytes` does not start like a topology blob.
+    /// - [`UnsupportedVersion`] if the blob uses a format version that this
+    ///   version of hwlocality does not know about.
+    /// - [`MissingField`] if a mandatory header field is missing.
+    /// - [`InvalidField`] if a header field has an invalid value.
+    /// - [`FingerprintMismatch`] if the XML data does not match the
+    ///   fingerprint, which suggests that it was corrupted in transit.
+    ///
+    /// [`FingerprintMismatch`]: ParseBlobError::FingerprintMismatch
+    /// [`InvalidField`]: ParseBlobError::InvalidField
+    /// [`MissingField`]: ParseBlobError::MissingField
+    /// [`NotABlob`]: ParseBlobError::NotABlob
+    /// [`NotUtf8`]: ParseBlobError::NotUtf8
+    /// [`UnsupportedVersion`]: ParseBlobError::UnsupportedVersion
+    pub fn parse(bytes: &[u8]) -> Result<Self, ParseBlobError> {
+        let text = std::str::from_utf8(bytes).map_err(|_| ParseBlobError::NotUtf8)?;
+
+        // Check the magic line and format version
+        let (magic_line, rest) = text.split_once('\n').ok_or(ParseBlobError::NotABlob)?;
+        let (magic, version) = magic_line.split_once(' ').ok_or(ParseBlobError::NotABlob)?;
+        if magic != MAGIC {
+            return Err(ParseBlobError::NotABlob);
+        }
+        if version != FORMAT_VERSION {
+            return Err(ParseBlobError::UnsupportedVersion(version.to_owned()));
+        }
+
+        // Collect header fields, ignoring unknown ones for forward
+        // compatibility with future minor format extensions
+        let (header, xml) = rest
+            .split_once("\n\n")
+            .ok_or(ParseBlobError::MissingField("XML data"))?;
+        let (mut hostname, mut hwloc_api_version, mut timestamp, mut expected_fingerprint) =
+            (None, None, None, None);
+        for line in header.lines() {
+            let (key, value) = line.split_once(' ').unwrap_or((line, ""));
+            match key {
+                "hostname" => {
+                    if !is_valid_hostname(value) {
+                        return Err(ParseBlobError::InvalidField("hostname"));
+                    }
+                    hostname = Some(value.to_owned());
+                }
+                "hwloc-api-version" => {
+                    let version = value
+                        .parse::<usize>()
+                        .map_err(|_| ParseBlobError::InvalidField("hwloc-api-version"))?;
+                    hwloc_api_version = Some(version);
+                }
+                "timestamp" => {
+                    let secs = value
+                        .parse::<u64>()
+                        .map_err(|_| ParseBlobError::InvalidField("timestamp"))?;
+                    timestamp = Some(
+                        SystemTime::UNIX_EPOCH
+                            .checked_add(Duration::from_secs(secs))
+                            .ok_or(ParseBlobError::InvalidField("timestamp"))?,
+                    );
+                }
+                "fingerprint" => {
+                    let fingerprint = u64::from_str_radix(value, 16)
+                        .map_err(|_| ParseBlobError::InvalidField("fingerprint"))?;
+                    expected_fingerprint = Some(fingerprint);
+                }
+                _ => {}
+            }
+        }
+        let hostname = hostname.ok_or(ParseBlobError::MissingField("hostname"))?;
+        let hwloc_api_version =
+            hwloc_api_version.ok_or(ParseBlobError::MissingField("hwloc-api-version"))?;
+        let timestamp = timestamp.ok_or(ParseBlobError::MissingField("timestamp"))?;
+        let expected = expected_fingerprint.ok_or(ParseBlobError::MissingField("fingerprint"))?;
+
+        // Check data integrity
+        let actual = fingerprint(xml);
+        if actual != expected {
+            return Err(ParseBlobError::FingerprintMismatch { expected, actual });
+        }
+        Ok(Self {
+            hostname,
+            hwloc_api_version,
+            timestamp,
+            fingerprint: actual,
+            xml: xml.to_owned(),
+        })
+    }
+
+    /// Serialize this blob for transmission to the collector
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let since_epoch = self
+            .timestamp
+            .duration_since(SystemTime::UNIX_EPOCH)
+            .unwrap_or_default();
+        format!(
+            "{MAGIC} {FORMAT_VERSION}\n\
+            hostname {}\n\
+            hwloc-api-version {}\n\
+            timestamp {}\n\
+            fingerprint {:016x}\n\
+            \n\
+            {}",
+            self.hostname,
+            self.hwloc_api_version,
+            since_epoch.as_secs(),
+            self.fingerprint,
+            self.xml
+        )
+        .into_bytes()
+    }
+
+    /// Hostname of the machine that the topology was captured on
+    pub fn hostname(&self) -> &str {
+        &self.hostname
+    }
+
+    /// hwloc API version of the machine that the topology was captured on
+    ///
+    /// See [`hwloc_api_version()`](crate::hwloc_api_version()) for the
+    /// encoding of this number.
+    pub fn hwloc_api_version(&self) -> usize {
+        self.hwloc_api_version
+    }
+
+    /// Time at which the topology was captured, with one-second precision
+    pub fn timestamp(&self) -> SystemTime {
+        self.timestamp
+    }
+
+    /// Fingerprint of the XML data
+    pub fn fingerprint(&self) -> u64 {
+        self.fingerprint
+    }
+
+    /// XML export of the topology
+    pub fn xml(&self) -> &str {
+        &self.xml
+    }
+
+    /// Load the topology contained in this blob
+    ///
+    /// # Errors
+    ///
+    /// - [`LoadError`] if hwloc failed to load the XML data.
+    pub fn load(&self) -> Result<Topology, LoadError> {
+        Topology::builder()
+            .from_xml(&self.xml)?
+            .build()
+            .map_err(|_| LoadError::Build)
+    }
+}
+
+/// Error while capturing a [`TopologyBlob`]
+#[derive(Clone, Debug, Error, Eq, Hash, PartialEq)]
+pub enum CaptureError {
+    /// hwloc did not record the hostname of the topology
+    #[error("hwloc did not record the topology's hostname")]
+    NoHostname,
+
+    /// The hostname is not suitable for inclusion in a blob
+    #[error("hostname {0:?} is empty or contains control characters")]
+    BadHostname(String),
+
+    /// The topology could not be exported to XML
+    #[error(transparent)]
+    Export(#[from] RawHwlocError),
+}
+
+/// Error while parsing a [`TopologyBlob`]
+#[derive(Clone, Debug, Error, Eq, Hash, PartialEq)]
+pub enum ParseBlobError {
+    /// The blob is not valid UTF-8
+    #[error("topology blob is not valid UTF-8")]
+    NotUtf8,
+
+    /// The data does not look like a topology blob
+    #[error("data is not a hwlocality topology blob")]
+    NotABlob,
+
+    /// The blob uses an unknown format version
+    #[error("unsupported topology blob format version {0:?}")]
+    UnsupportedVersion(String),
+
+    /// A mandatory part of the blob is missing
+    #[error("topology blob lacks its {0}")]
+    MissingField(&'static str),
+
+    /// A header field has an invalid value
+    #[error("topology blob has an invalid {0} field")]
+    InvalidField(&'static str),
+
+    /// The XML data does not match the fingerprint
+    #[error("topology blob data has fingerprint {actual:016x}, expected {expected:016x}")]
+    FingerprintMismatch {
+        /// Fingerprint from the blob header
+        expected: u64,
+
+        /// Fingerprint of the XML data that was received
+        actual: u64,
+    },
+}
+
+/// # Importing topology blobs
+impl Cluster {
+    /// Validate a [`TopologyBlob`] emitted by [`TopologyBlob::to_bytes()`],
+    /// then add the topology that it contains to the cluster
+    ///
+    /// The machine is keyed by the blob's hostname, which is returned on
+    /// success.
+    ///
+    /// # Errors
+    ///
+    /// - [`Parse`] if the blob is malformed or corrupted.
+    /// - [`Load`] if hwloc failed to load the topology.
+    /// - [`DuplicateHostname`] if a machine with the same hostname is already
+    ///   present in the cluster. The cluster is left unchanged in this case.
+    ///
+    /// [`DuplicateHostname`]: ImportBlobError::DuplicateHostname
+    /// [`Load`]: ImportBlobError::Load
+    /// [`Parse`]: ImportBlobError::Parse
+    pub fn import_blob(&mut self, bytes: &[u8]) -> Result<String, ImportBlobError> {
+        let blob = TopologyBlob::parse(bytes)?;
+        let topology = blob
+            .load()
+            .map_err(|e| ImportBlobError::Load(blob.hostname.clone(), e))?;
+        match self.machines.entry(blob.hostname) {
+            Entry::Occupied(occupied) => {
+                Err(ImportBlobError::DuplicateHostname(occupied.key().clone()))
+            }
+            Entry::Vacant(vacant) => {
+                let hostname = vacant.key().clone();
+                vacant.insert(topology);
+                Ok(hostname)
+            }
+        }
+    }
+}
+
+/// Error while importing a [`TopologyBlob`] into a [`Cluster`]
+#[derive(Clone, Debug, Error, Eq, Hash, PartialEq)]
+pub enum ImportBlobError {
+    /// The blob is malformed or corrupted
+    #[error(transparent)]
+    Parse(#[from] ParseBlobError),
+
+    /// hwloc failed to load the topology of the specified machine
+    #[error("failed to load topology of machine {0:?}")]
+    Load(String, #[source] LoadError),
+
+    /// A machine with the same hostname is already present in the cluster
+    #[error("a machine with hostname {0:?} is already present in the cluster")]
+    DuplicateHostname(String),
+}
+
+/// Truth that a hostname can be stored in a blob header
+fn is_valid_hostname(hostname: &str) -> bool {
+    !hostname.is_empty() && !hostname.chars().any(char::is_control)
+}
+
+/// 64-bit FNV-1a hash of some XML data
+///
+/// This is used instead of the standard library's hasher because the
+/// fingerprint must not change across Rust versions and machines.
+fn fingerprint(xml: &str) -> u64 {
+    /// FNV-1a offset basis
+    const OFFSET_BASIS: u64 = 0xcbf2_9ce4_8422_2325;
+    /// FNV-1a prime
+    const PRIME: u64 = 0x0100_0000_01b3;
+    xml.bytes().fold(OFFSET_BASIS, |hash, byte| {
+        (hash ^ u64::from(byte)).wrapping_mul(PRIME)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::prelude::*;
+    #[allow(unused)]
+    use similar_asserts::assert_eq;
+    use static_assertions::{assert_impl_all, assert_not_impl_any};
+    use std::{
+        error::Error,
+        fmt::{
+            self, Binary, Debug, Display, LowerExp, LowerHex, Octal, Pointer, UpperExp, UpperHex,
+        },
+        hash::Hash,
+        io::{self, Read},
+        ops::Deref,
+        panic::UnwindSafe,
+    };
+
+    // Check that public types in this module keep implementing all expected
+    // traits, in the interest of detecting future semver-breaking changes
+    assert_impl_all!(TopologyBlob:
+        Clone, Debug, Hash, Sized, Sync, Unpin, UnwindSafe
+    );
+    assert_not_impl_any!(TopologyBlob:
+        Binary, Copy, Default, Deref, Display, Drop, IntoIterator, LowerExp,
+        LowerHex, Octal, PartialOrd, Pointer, Read, UpperExp, UpperHex,
+        fmt::Write, io::Write
+    );
+    assert_impl_all!(CaptureError:
+        Clone, Error, From<RawHwlocError>, Hash, Sized, Sync, Unpin, UnwindSafe
+    );
+    assert_impl_all!(ParseBlobError:
+        Clone, Error, Hash, Sized, Sync, Unpin, UnwindSafe
+    );
+    assert_impl_all!(ImportBlobError:
+        Clone, Error, From<ParseBlobError>, Hash, Sized, Sync, Unpin,
+        UnwindSafe
+    );
+    assert_not_impl_any!(ImportBlobError:
+        Binary, Copy, Default, Deref, Drop, IntoIterator, LowerExp, LowerHex,
+        Octal, PartialOrd, Pointer, Read, UpperExp, UpperHex, fmt::Write,
+        io::Write
+    );
+
+    /// Check that the FNV-1a implementation matches reference values
+    #[test]
+    fn fnv1a() {
+        assert_eq!(fingerprint(""), 0xcbf2_9ce4_8422_2325);
+        assert_eq!(fingerprint("a"), 0xaf63_dc4c_8601_ec8c);
+        assert_eq!(fingerprint("foobar"), 0x8594_4171_f739_67e8);
+    }
+
+    /// Check that blobs survive a round trip through the wire format, and can
+    /// be imported into a cluster
+    #[test]
+    fn round_trip() {
+        let topology = Topology::test_instance();
+        let blob = TopologyBlob::capture_as(topology, "node0").unwrap();
+        assert_eq!(blob.hostname(), "node0");
+        assert_eq!(blob.hwloc_api_version(), crate::hwloc_api_version());
+        assert!(blob.timestamp() <= SystemTime::now());
+        assert_eq!(blob.fingerprint(), fingerprint(blob.xml()));
+
+        let bytes = blob.to_bytes();
+        assert_eq!(TopologyBlob::parse(&bytes), Ok(blob.clone()));
+        let loaded = blob.load().unwrap();
+        assert_eq!(loaded.objects().count(), topology.objects().count());
+
+        let mut cluster = Cluster::new();
+        assert_eq!(cluster.import_blob(&bytes), Ok("node0".to_owned()));
+        assert_eq!(
+            cluster
+                .get("node0")
+                .map(|topology| topology.objects().count()),
+            Some(topology.objects().count())
+        );
+        assert_eq!(
+            cluster.import_blob(&bytes),
+            Err(ImportBlobError::DuplicateHostname("node0".to_owned()))
+        );
+        assert_eq!(cluster.len(), 1);
+
+        // Capturing with the hwloc-provided hostname should work whenever
+        // hwloc recorded one
+        let result = TopologyBlob::capture(topology);
+        if let Some(hostname) = topology.root_object().info("HostName") {
+            assert_eq!(result.unwrap().hostname().as_bytes(), hostname.to_bytes());
+        } else {
+            assert_eq!(result, Err(CaptureError::NoHostname));
+        }
+    }
+
+    /// Check that invalid hostnames are rejected
+    #[test]
+    fn bad_hostname() {
+        let topology = Topology::test_instance();
+        for hostname in ["", "node\n0"] {
+            assert_eq!(
+                TopologyBlob::capture_as(topology, hostname),
+                Err(CaptureError::BadHostname(hostname.to_owned()))
+            );
+        }
+    }
+
+    /// Check that corrupted blobs are rejected
+    #[test]
+    fn corruption() {
+        let topology = Topology::test_instance();
+        let blob = TopologyBlob::capture_as(topology, "node0").unwrap();
+        let bytes = blob.to_bytes();
+
+        let mut truncated = bytes.clone();
+        truncated.pop();
+        let result = TopologyBlob::parse(&truncated);
+        assert!(matches!(
+            result,
+            Err(ParseBlobError::FingerprintMismatch { .. })
+        ));
+
+        let new_version = String::from_utf8(bytes)
+            .unwrap()
+            .replacen(" 1\n", " 42\n", 1);
+        assert_eq!(
+            TopologyBlob::parse(new_version.as_bytes()),
+            Err(ParseBlobError::UnsupportedVersion("42".to_owned()))
+        );
+
+        assert_eq!(
+            TopologyBlob::parse(blob.xml().as_bytes()),
+            Err(ParseBlobError::NotABlob)
+        );
+        assert_eq!(TopologyBlob::parse(b"\xff"), Err(ParseBlobError::NotUtf8));
+        assert_eq!(
+            TopologyBlob::parse(b"hwlocality-topology-blob 1\nhostname node0\n\n<xml/>"),
+            Err(ParseBlobError::MissingField("hwloc-api-version"))
+        );
+    }
+
+    proptest! {
+        /// Parsing arbitrary data should fail gracefully
+        #[test]
+        fn parse_garbage(bytes in prop::collection::vec(any::<u8>(), 0..256)) {
+            std::mem::drop(TopologyBlob::parse(&bytes));
+        }
+    }
+}
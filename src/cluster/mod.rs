@@ -0,0 +1,565 @@
+//! Managing the topologies of many machines
+//!
+//! Cluster schedulers and other distributed applications often need to reason
+//! about the hardware topology of many machines at once. A common workflow is
+//! to have each machine export its topology to XML with
+//! [`Topology::export_xml_file()`] or `lstopo machine.xml`, then to import all
+//! of these topologies on a central node.
+//!
+//! The [`Cluster`] type provided by this module implements the bookkeeping
+//! side of this workflow: it stores topologies keyed by hostname, can import
+//! them in bulk from a directory of XML files, and answers some common
+//! cross-machine questions.
+//!
+//! When topologies are sent over the network rather than through a shared
+//! filesystem, the [`blob`] submodule provides a standard wire format that
+//! carries provenance metadata along with the topology.
+
+pub mod blob;
+
+#[cfg(doc)]
+use crate::topology::builder::TopologyBuilder;
+use crate::{
+    object::{
+        attributes::ObjectAttributes,
+        depth::{Depth, NormalDepth},
+        types::ObjectType,
+        TopologyObject,
+    },
+    topology::{
+        builder::{FileInputError, StringInputError},
+        Topology,
+    },
+};
+#[allow(unused)]
+#[cfg(test)]
+use similar_asserts::assert_eq;
+use std::{
+    collections::{btree_map::Entry, BTreeMap},
+    ffi::OsStr,
+    io,
+    path::Path,
+};
+use thiserror::Error;
+
+/// Collection of topologies from multiple machines, keyed by hostname
+///
+/// Machines are kept sorted by hostname, so iteration order is deterministic.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Cluster {
+    /// Topology of each machine, keyed by hostname
+    machines: BTreeMap<String, Topology>,
+}
+//
+impl Cluster {
+    /// Set up an empty cluster
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Import all topologies from a directory of XML files
+    ///
+    /// See [`Cluster::import_xml_dir()`] for more information.
+    ///
+    /// # Errors
+    ///
+    /// See [`Cluster::import_xml_dir()`].
+    pub fn from_xml_dir(path: impl AsRef<Path>) -> Result<Self, ImportError> {
+        let mut cluster = Self::new();
+        cluster.import_xml_dir(path)?;
+        Ok(cluster)
+    }
+
+    /// Number of machines in the cluster
+    pub fn len(&self) -> usize {
+        self.machines.len()
+    }
+
+    /// Truth that the cluster contains no machine
+    pub fn is_empty(&self) -> bool {
+        self.machines.is_empty()
+    }
+
+    /// Add a machine to the cluster
+    ///
+    /// If a machine with the same hostname was already present, its topology
+    /// is replaced and the former topology is returned.
+    pub fn insert(&mut self, hostname: impl Into<String>, topology: Topology) -> Option<Topology> {
+        self.machines.insert(hostname.into(), topology)
+    }
+
+    /// Remove a machine from the cluster, returning its topology
+    pub fn remove(&mut self, hostname: &str) -> Option<Topology> {
+        self.machines.remove(hostname)
+    }
+
+    /// Topology of the machine with a certain hostname
+    pub fn get(&self, hostname: &str) -> Option<&Topology> {
+        self.machines.get(hostname)
+    }
+
+    /// Hostnames of all machines in the cluster, in sorted order
+    pub fn hostnames(&self) -> impl DoubleEndedIterator<Item = &str> + ExactSizeIterator + Clone {
+        self.machines.keys().map(String::as_str)
+    }
+
+    /// Iterate over (hostname, topology) pairs, sorted by hostname
+    pub fn iter(
+        &self,
+    ) -> impl DoubleEndedIterator<Item = (&str, &Topology)> + ExactSizeIterator + Clone {
+        self.machines
+            .iter()
+            .map(|(hostname, topology)| (hostname.as_str(), topology))
+    }
+
+    /// Import a machine's topology from an XML file
+    ///
+    /// The machine is keyed by the `HostName` info attribute of the
+    /// topology's root object, or by the file name without its extension if
+    /// this attribute is missing. The hostname is returned on success.
+    ///
+    /// # Errors
+    ///
+    /// - [`Load`] if the topology could not be loaded from `path`.
+    /// - [`DuplicateHostname`] if a machine with the same hostname is already
+    ///   present in the cluster. The cluster is left unchanged in this case.
+    ///
+    /// [`DuplicateHostname`]: ImportError::DuplicateHostname
+    /// [`Load`]: ImportError::Load
+    pub fn import_xml_file(&mut self, path: impl AsRef<Path>) -> Result<String, ImportError> {
+        /// Polymorphized version of this function (avoids generics code bloat)
+        fn polymorphized(self_: &mut Cluster, path: &Path) -> Result<String, ImportError> {
+            let topology = load_xml_file(path)?;
+            let hostname = hostname(&topology, path);
+            match self_.machines.entry(hostname) {
+                Entry::Occupied(occupied) => {
+                    Err(ImportError::DuplicateHostname(occupied.key().clone()))
+                }
+                Entry::Vacant(vacant) => {
+                    let hostname = vacant.key().clone();
+                    vacant.insert(topology);
+                    Ok(hostname)
+                }
+            }
+        }
+        polymorphized(self, path.as_ref())
+    }
+
+    /// Import all topologies from a directory of XML files
+    ///
+    /// Every file with an `.xml` extension in directory `path` is imported as
+    /// with [`Cluster::import_xml_file()`], in file name order. Other files
+    /// and subdirectories are ignored. The number of imported topologies is
+    /// returned on success.
+    ///
+    /// # Errors
+    ///
+    /// - [`ReadDir`] if the contents of directory `path` could not be listed.
+    /// - [`Load`] if one of the topologies could not be loaded.
+    /// - [`DuplicateHostname`] if two topologies have the same hostname, or if
+    ///   a topology has the same hostname as a machine that was already
+    ///   present in the cluster.
+    ///
+    /// If an error occurs, the cluster is left unchanged.
+    ///
+    /// [`DuplicateHostname`]: ImportError::DuplicateHostname
+    /// [`Load`]: ImportError::Load
+    /// [`ReadDir`]: ImportError::ReadDir
+    pub fn import_xml_dir(&mut self, path: impl AsRef<Path>) -> Result<usize, ImportError> {
+        /// Polymorphized version of this function (avoids generics code bloat)
+        fn polymorphized(self_: &mut Cluster, path: &Path) -> Result<usize, ImportError> {
+            // List XML files in the directory, in a reproducible order
+            let read_dir_error = |e| ImportError::ReadDir(path.into(), e);
+            let mut xml_paths = Vec::new();
+            for entry in std::fs::read_dir(path).map_err(read_dir_error)? {
+                let entry_path = entry.map_err(read_dir_error)?.path();
+                let is_xml = entry_path
+                    .extension()
+                    .and_then(OsStr::to_str)
+                    .is_some_and(|ext| ext.eq_ignore_ascii_case("xml"));
+                if is_xml && entry_path.is_file() {
+                    xml_paths.push(entry_path);
+                }
+            }
+            xml_paths.sort_unstable();
+
+            // Load all topologies before inserting them, so that the cluster
+            // is left unchanged if something goes wrong
+            let mut new_machines = BTreeMap::new();
+            for xml_path in xml_paths {
+                let topology = load_xml_file(&xml_path)?;
+                let hostname = hostname(&topology, &xml_path);
+                if self_.machines.contains_key(&hostname) || new_machines.contains_key(&hostname) {
+                    return Err(ImportError::DuplicateHostname(hostname));
+                }
+                new_machines.insert(hostname, topology);
+            }
+            let num_imported = new_machines.len();
+            self_.machines.append(&mut new_machines);
+            Ok(num_imported)
+        }
+        polymorphized(self, path.as_ref())
+    }
+
+    /// Total number of CPU cores across all machines
+    pub fn total_cores(&self) -> usize {
+        self.machines
+            .values()
+            .map(|topology| topology.objects_with_type(ObjectType::Core).count())
+            .sum()
+    }
+
+    /// Total number of PUs (hardware threads) across all machines
+    pub fn total_pus(&self) -> usize {
+        self.machines
+            .values()
+            .map(|topology| topology.objects_with_type(ObjectType::PU).count())
+            .sum()
+    }
+
+    /// Truth that all machines have the same hardware layout
+    ///
+    /// Machines are considered to have the same layout if their topologies
+    /// have the same object types at each depth, with the same number of
+    /// objects, and the same number of NUMA nodes. Details like hostnames,
+    /// CPU models or the exact amount of memory are not compared.
+    ///
+    /// An empty cluster is considered to be homogeneous.
+    pub fn is_homogeneous(&self) -> bool {
+        let mut shapes = self.machines.values().map(shape);
+        let Some(first_shape) = shapes.next() else {
+            return true;
+        };
+        shapes.all(|shape| shape == first_shape)
+    }
+
+    /// Machines which have at least `min_gpus` GPUs attached to a single NUMA
+    /// node
+    ///
+    /// This is useful for placing jobs that need fast communication between
+    /// several GPUs and the CPU cores that drive them.
+    ///
+    /// GPUs are detected as PCI devices with a display controller class, so
+    /// I/O objects must have been kept in the topology when it was exported,
+    /// see [`TopologyBuilder::with_io_type_filter()`]. GPUs whose locality
+    /// spans several NUMA nodes are not taken into account.
+    pub fn machines_with_gpus_per_numa_node(
+        &self,
+        min_gpus: usize,
+    ) -> impl Iterator<Item = (&str, &Topology)> + Clone {
+        self.iter()
+            .filter(move |(_hostname, topology)| max_gpus_per_numa_node(topology) >= min_gpus)
+    }
+}
+//
+impl Extend<(String, Topology)> for Cluster {
+    fn extend<T: IntoIterator<Item = (String, Topology)>>(&mut self, iter: T) {
+        self.machines.extend(iter);
+    }
+}
+//
+impl FromIterator<(String, Topology)> for Cluster {
+    fn from_iter<T: IntoIterator<Item = (String, Topology)>>(iter: T) -> Self {
+        Self {
+            machines: iter.into_iter().collect(),
+        }
+    }
+}
+//
+impl IntoIterator for Cluster {
+    type Item = (String, Topology);
+    type IntoIter = std::collections::btree_map::IntoIter<String, Topology>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.machines.into_iter()
+    }
+}
+
+/// Error while importing topologies into a [`Cluster`]
+#[derive(Debug, Error)]
+pub enum ImportError {
+    /// Failed to list the contents of a directory
+    #[error("failed to list topology directory {0}")]
+    ReadDir(Box<Path>, #[source] io::Error),
+
+    /// Failed to load a topology from an XML file
+    #[error("failed to load topology from {0}")]
+    Load(Box<Path>, #[source] LoadError),
+
+    /// A machine with the same hostname is already present in the cluster
+    #[error("a machine with hostname {0:?} is already present in the cluster")]
+    DuplicateHostname(String),
+}
+
+/// Reason why a topology could not be loaded from XML
+#[derive(Clone, Debug, Error, Eq, Hash, PartialEq)]
+#[allow(variant_size_differences)]
+pub enum LoadError {
+    /// The XML file could not be used as a topology source
+    #[error(transparent)]
+    BadFile(#[from] FileInputError),
+
+    /// The XML string could not be used as a topology source
+    #[error(transparent)]
+    BadString(#[from] StringInputError),
+
+    /// hwloc failed to build the topology
+    #[error("hwloc failed to build the topology")]
+    Build,
+}
+
+/// Load a topology from an XML file
+fn load_xml_file(path: &Path) -> Result<Topology, ImportError> {
+    let load_error = |e| ImportError::Load(path.into(), e);
+    Topology::builder()
+        .from_xml_file(path)
+        .map_err(|e| load_error(e.into()))?
+        .build()
+        .map_err(|_| load_error(LoadError::Build))
+}
+
+/// Hostname of a topology that was loaded from `path`
+fn hostname(topology: &Topology, path: &Path) -> String {
+    topology.root_object().info("HostName").map_or_else(
+        || {
+            path.file_stem()
+                .unwrap_or(path.as_os_str())
+                .to_string_lossy()
+                .into_owned()
+        },
+        |hostname| hostname.to_string_lossy().into_owned(),
+    )
+}
+
+/// Object type and number of objects at each normal depth of a topology,
+/// followed by the number of NUMA nodes
+fn shape(topology: &Topology) -> Vec<(Option<ObjectType>, usize)> {
+    NormalDepth::iter_range(NormalDepth::MIN, topology.depth())
+        .map(Depth::from)
+        .chain(std::iter::once(Depth::NUMANode))
+        .map(|depth| {
+            (
+                topology.type_at_depth(depth),
+                topology.num_objects_at_depth(depth),
+            )
+        })
+        .collect()
+}
+
+/// Truth that a PCI device is a GPU
+fn is_gpu(pci_device: &TopologyObject) -> bool {
+    /// PCI base class of display controllers
+    const DISPLAY_CONTROLLER_CLASS: u16 = 0x03;
+    let Some(ObjectAttributes::PCIDevice(pci)) = pci_device.attributes() else {
+        unreachable!("All PCI devices should have PCI attributes")
+    };
+    pci.class_id() >> 8 == DISPLAY_CONTROLLER_CLASS
+}
+
+/// Maximal number of GPUs attached to a single NUMA node of a topology
+fn max_gpus_per_numa_node(topology: &Topology) -> usize {
+    let mut gpus_per_node = BTreeMap::<usize, usize>::new();
+    for gpu in topology.pci_devices().filter(|dev| is_gpu(dev)) {
+        let Some(nodeset) = gpu
+            .first_non_io_ancestor()
+            .and_then(TopologyObject::nodeset)
+        else {
+            continue;
+        };
+        if nodeset.weight() != Some(1) {
+            continue;
+        }
+        let node = usize::from(
+            nodeset
+                .first_set()
+                .expect("Checked above that there is one set node"),
+        );
+        *gpus_per_node.entry(node).or_default() += 1;
+    }
+    gpus_per_node.into_values().max().unwrap_or(0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::topology::export::xml::XMLExportFlags;
+    #[allow(unused)]
+    use similar_asserts::assert_eq;
+    use static_assertions::{assert_impl_all, assert_not_impl_any};
+    use std::{
+        error::Error,
+        fmt::{
+            self, Binary, Debug, Display, LowerExp, LowerHex, Octal, Pointer, UpperExp, UpperHex,
+        },
+        hash::Hash,
+        io::Read,
+        ops::Deref,
+        panic::UnwindSafe,
+    };
+
+    // Check that public types in this module keep implementing all expected
+    // traits, in the interest of detecting future semver-breaking changes
+    assert_impl_all!(Cluster:
+        Clone, Debug, Default, Extend<(String, Topology)>,
+        FromIterator<(String, Topology)>, IntoIterator<Item=(String, Topology)>,
+        PartialEq, Sized, Sync, Unpin, UnwindSafe
+    );
+    assert_not_impl_any!(Cluster:
+        Binary, Copy, Deref, Display, Drop, Hash, LowerExp, LowerHex, Octal,
+        PartialOrd, Pointer, Read, UpperExp, UpperHex, fmt::Write, io::Write
+    );
+    assert_impl_all!(ImportError:
+        Error, Sized, Sync, Unpin
+    );
+    assert_not_impl_any!(ImportError:
+        Binary, Clone, Default, Deref, Drop, IntoIterator, LowerExp, LowerHex,
+        Octal, PartialOrd, Pointer, Read, UpperExp, UpperHex, fmt::Write,
+        io::Write
+    );
+    assert_impl_all!(LoadError:
+        Clone, Error, From<FileInputError>, From<StringInputError>, Hash,
+        Sized, Sync, Unpin, UnwindSafe
+    );
+    assert_not_impl_any!(LoadError:
+        Binary, Copy, Default, Deref, Drop, IntoIterator, LowerExp, LowerHex,
+        Octal, PartialOrd, Pointer, Read, UpperExp, UpperHex, fmt::Write,
+        io::Write
+    );
+
+    /// Check the basic bookkeeping and queries of a cluster
+    #[test]
+    fn bookkeeping() {
+        let topology = Topology::test_instance();
+        let mut cluster = Cluster::new();
+        assert!(cluster.is_empty());
+        assert!(cluster.is_homogeneous());
+        assert_eq!(cluster.total_cores(), 0);
+        assert_eq!(cluster.machines_with_gpus_per_numa_node(0).count(), 0);
+
+        assert!(cluster.insert("node1", topology.clone()).is_none());
+        assert!(cluster.insert("node0", topology.clone()).is_none());
+        assert_eq!(cluster.len(), 2);
+        assert_eq!(cluster.hostnames().collect::<Vec<_>>(), ["node0", "node1"]);
+        assert_eq!(cluster.get("node0"), Some(topology));
+        assert_eq!(cluster.get("node2"), None);
+
+        let num_cores = topology.objects_with_type(ObjectType::Core).count();
+        let num_pus = topology.objects_with_type(ObjectType::PU).count();
+        assert_eq!(cluster.total_cores(), 2 * num_cores);
+        assert_eq!(cluster.total_pus(), 2 * num_pus);
+        assert!(cluster.is_homogeneous());
+        assert_eq!(cluster.machines_with_gpus_per_numa_node(0).count(), 2);
+        let max_gpus = max_gpus_per_numa_node(topology);
+        assert_eq!(
+            cluster
+                .machines_with_gpus_per_numa_node(max_gpus + 1)
+                .count(),
+            0
+        );
+
+        assert_eq!(cluster.remove("node1").as_ref(), Some(topology));
+        assert_eq!(cluster.remove("node1"), None);
+        assert_eq!(cluster.len(), 1);
+        assert_eq!(cluster.into_iter().count(), 1);
+    }
+
+    /// Check that topologies with a different layout are detected
+    #[cfg(feature = "hwloc-2_3_0")]
+    #[test]
+    fn heterogeneous() {
+        use crate::topology::editor::RestrictFlags;
+
+        let topology = Topology::test_instance();
+        let mut restricted = topology.clone();
+        let first_pu_set = topology
+            .objects_with_type(ObjectType::PU)
+            .next()
+            .unwrap()
+            .cpuset()
+            .unwrap()
+            .clone_target();
+        restricted.edit(|editor| {
+            editor
+                .restrict(&first_pu_set, RestrictFlags::empty())
+                .unwrap();
+        });
+        let cluster = [
+            ("big".to_owned(), topology.clone()),
+            ("small".to_owned(), restricted),
+        ]
+        .into_iter()
+        .collect::<Cluster>();
+        assert_eq!(cluster.len(), 2);
+        assert_eq!(
+            cluster.is_homogeneous(),
+            topology.objects_with_type(ObjectType::PU).count() == 1
+        );
+    }
+
+    /// Check bulk import from a directory of XML files
+    #[test]
+    fn import_xml_dir() {
+        // Synthetic topologies record no hostname, so they are keyed by file
+        // name when imported
+        let synthetic = |description| {
+            Topology::builder()
+                .from_synthetic(description)
+                .unwrap()
+                .build()
+                .unwrap()
+        };
+        let small = synthetic("Package:1 Core:2 PU:2");
+        let big = synthetic("Package:2 Core:4 PU:2");
+        let dir = tempfile::tempdir().unwrap();
+        for (name, topology) in [("a.xml", &small), ("b.XML", &big)] {
+            topology
+                .export_xml_file(Some(&dir.path().join(name)), XMLExportFlags::empty())
+                .unwrap();
+        }
+        std::fs::write(dir.path().join("README.txt"), "Not a topology").unwrap();
+
+        let mut cluster = Cluster::new();
+        assert_eq!(cluster.import_xml_dir(dir.path()).unwrap(), 2);
+        assert_eq!(cluster.hostnames().collect::<Vec<_>>(), ["a", "b"]);
+        for (hostname, expected) in [("a", &small), ("b", &big)] {
+            let imported = cluster.get(hostname).unwrap();
+            assert_eq!(shape(imported), shape(expected));
+            assert_eq!(imported.cpuset(), expected.cpuset());
+        }
+        assert!(!cluster.is_homogeneous());
+        assert_eq!(cluster.total_cores(), 2 + 8);
+        assert_eq!(cluster.total_pus(), 4 + 16);
+
+        // Importing the same machines again fails and leaves the cluster as is
+        let result = cluster.import_xml_dir(dir.path());
+        assert!(matches!(result, Err(ImportError::DuplicateHostname(hostname)) if hostname == "a"));
+        assert_eq!(cluster.len(), 2);
+
+        // Topologies that record the same hostname clash even if they were
+        // exported to files with different names
+        let topology = Topology::test_instance();
+        if topology.root_object().info("HostName").is_some() {
+            let same_host_dir = tempfile::tempdir().unwrap();
+            for name in ["host1.xml", "host2.xml"] {
+                topology
+                    .export_xml_file(
+                        Some(&same_host_dir.path().join(name)),
+                        XMLExportFlags::empty(),
+                    )
+                    .unwrap();
+            }
+            let mut cluster = Cluster::new();
+            let result = cluster.import_xml_dir(same_host_dir.path());
+            assert!(matches!(result, Err(ImportError::DuplicateHostname(_))));
+            assert!(cluster.is_empty());
+        }
+
+        // Invalid files and directories should be reported as such
+        let bad_dir = tempfile::tempdir().unwrap();
+        std::fs::write(bad_dir.path().join("bad.xml"), "Not a topology").unwrap();
+        let result = Cluster::from_xml_dir(bad_dir.path());
+        assert!(matches!(result, Err(ImportError::Load(..))));
+        let result = Cluster::from_xml_dir(bad_dir.path().join("missing"));
+        assert!(matches!(result, Err(ImportError::ReadDir(..))));
+    }
+}
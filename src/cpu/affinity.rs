@@ -0,0 +1,753 @@
+//! Thread pool CPU affinity policies
+//!
+//! Thread pools and task schedulers commonly need to decide which CPUs each of
+//! their worker threads should be bound to. This module provides the
+//! [`BindingPolicy`] type, which describes a few common strategies for doing
+//! so, and the [`Topology::distribute_policy()`] method, which turns such a
+//! policy into one [`CpuSet`] per thread that can then be passed to
+//! [`Topology::bind_cpu()`].
+//!
+//! [`Topology::exclusive_cores()`] covers the related common need of running
+//! one thread per physical CPU core, with or without the SMT siblings
+//! (hyperthreads) of each core, as specified by [`SmtPolicy`].
+//!
+//! Only CPUs that the current process is allowed to use (as reported by
+//! [`Topology::allowed_cpuset()`]) are considered, except when the CPU sets
+//! are explicitly specified via [`BindingPolicy::Explicit`].
+//!
+//! If the `rayon` feature is enabled, `Topology::rayon_pool_builder()` can
+//! also be used to set up a rayon thread pool whose worker threads are bound
+//! according to a [`BindingPolicy`].
+
+use crate::cpu::binding::CpuBindingFlags;
+#[cfg(any(unix, windows))]
+use crate::{
+    cpu::binding::{spawn_bound, CpuBindingError},
+    errors::HybridError,
+};
+use crate::{
+    cpu::cpuset::CpuSet,
+    object::{depth::NormalDepth, types::ObjectType, TopologyObject},
+    topology::{DistributeFlags, Topology},
+};
+#[cfg(feature = "rayon")]
+use rayon::ThreadPoolBuilder;
+#[allow(unused)]
+#[cfg(test)]
+use similar_asserts::assert_eq;
+use std::num::NonZeroUsize;
+#[cfg(feature = "rayon")]
+use std::sync::Arc;
+#[cfg(any(unix, windows))]
+use std::{io, thread::JoinHandle};
+use thiserror::Error;
+
+/// Strategy for assigning CPUs to the threads of a thread pool
+///
+/// Unless otherwise specified, if there are more threads than CPU sets to
+/// assign them to, CPU sets are handed out again from the beginning in a
+/// round-robin fashion.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub enum BindingPolicy {
+    /// Bind each thread to a single PU, filling PUs in logical order
+    ///
+    /// Consecutive threads end up on PUs that share as many hardware resources
+    /// (cores, caches...) as possible, which is good for workloads where
+    /// threads communicate a lot.
+    Compact,
+
+    /// Spread threads as far away from each other as possible
+    ///
+    /// Threads are distributed across the allowed CPUs of the topology using
+    /// [`Topology::distribute_items()`], so that they share as few hardware
+    /// resources as possible, which is good for bandwidth-bound workloads.
+    /// Each thread is bound to a single PU, and no two threads share a PU
+    /// unless there are more threads than allowed PUs.
+    Scatter,
+
+    /// Bind each thread to all the PUs of one CPU core
+    PerCore,
+
+    /// Bind each thread to all the CPUs that are local to one NUMA node
+    ///
+    /// NUMA nodes that share the same CPUs (e.g. DRAM and high-bandwidth
+    /// memory attached to the same package) are only used once.
+    PerNUMANode,
+
+    /// Bind each thread to all the CPUs below one L3 cache
+    PerL3Cache,
+
+    /// Bind threads to a user-specified list of CPU sets
+    Explicit(Vec<CpuSet>),
+}
+
+/// Whether the SMT siblings of a CPU core should be used
+///
+/// Many modern CPU cores can run multiple hardware threads, which hwloc
+/// represents as multiple [`ObjectType::PU`] children of the
+/// [`ObjectType::Core`] object. This is known as simultaneous multithreading
+/// (SMT) or hyperthreading. Since these hardware threads compete for the
+/// resources of the core, performance-sensitive applications often want to
+/// run only one software thread per core.
+#[derive(Copy, Clone, Debug, Eq, Hash, PartialEq)]
+pub enum SmtPolicy {
+    /// Use all the PUs of each core that the process is allowed to run on
+    IncludeSiblings,
+
+    /// Only use the first PU of each core that the process is allowed to run on
+    ExcludeSiblings,
+}
+
+/// Unit in which [`Topology::effective_parallelism()`] counts CPUs
+#[derive(Copy, Clone, Debug, Eq, Hash, PartialEq)]
+pub enum Granularity {
+    /// Count every PU (hardware thread), including the SMT siblings of cores
+    PU,
+
+    /// Count CPU cores, ignoring their SMT siblings
+    Core,
+}
+
+/// # Thread pool CPU affinity
+///
+/// This functionality is unique to the Rust hwloc bindings
+impl Topology {
+    /// Compute the CPU sets that `num_threads` threads should be bound to
+    /// according to some [`BindingPolicy`]
+    ///
+    /// The output contains one [`CpuSet`] per thread, and is empty if
+    /// `num_threads` is 0.
+    ///
+    /// # Errors
+    ///
+    /// - [`EmptyExplicitList`] if an [`Explicit`] policy was specified with an
+    ///   empty list of CPU sets, or with an empty CPU set in the list.
+    /// - [`NoAllowedCpus`] if this process is not allowed to run on any CPU
+    ///   of the objects that the policy distributes threads across.
+    ///
+    /// [`EmptyExplicitList`]: DistributePolicyError::EmptyExplicitList
+    /// [`Explicit`]: BindingPolicy::Explicit
+    /// [`NoAllowedCpus`]: DistributePolicyError::NoAllowedCpus
+    pub fn distribute_policy(
+        &self,
+        num_threads: usize,
+        policy: &BindingPolicy,
+    ) -> Result<Vec<CpuSet>, DistributePolicyError> {
+        let candidates = match policy {
+            BindingPolicy::Compact => self.allowed_object_cpusets(ObjectType::PU)?,
+            BindingPolicy::Scatter => {
+                if num_threads == 0 {
+                    return Ok(Vec::new());
+                }
+                let no_allowed_cpus = DistributePolicyError::NoAllowedCpus(ObjectType::PU);
+                let allowed = self.allowed_cpuset().clone_target();
+                let roots = self
+                    .largest_objects_inside_cpuset(allowed.clone())
+                    .collect::<Vec<_>>();
+                if roots.is_empty() {
+                    return Err(no_allowed_cpus);
+                }
+                let mut cpusets = self
+                    .distribute_items(
+                        &roots,
+                        num_threads,
+                        NormalDepth::MAX,
+                        DistributeFlags::empty(),
+                    )
+                    .map_err(|_| no_allowed_cpus)?;
+                for cpuset in &mut cpusets {
+                    *cpuset &= &allowed;
+                    cpuset.singlify();
+                }
+                return Ok(cpusets);
+            }
+            BindingPolicy::PerCore => self.allowed_object_cpusets(ObjectType::Core)?,
+            BindingPolicy::PerNUMANode => self.allowed_object_cpusets(ObjectType::NUMANode)?,
+            BindingPolicy::PerL3Cache => self.allowed_object_cpusets(ObjectType::L3Cache)?,
+            BindingPolicy::Explicit(cpusets) => {
+                if cpusets.is_empty() || cpusets.iter().any(CpuSet::is_empty) {
+                    return Err(DistributePolicyError::EmptyExplicitList);
+                }
+                cpusets.clone()
+            }
+        };
+        Ok(candidates.into_iter().cycle().take(num_threads).collect())
+    }
+
+    /// Pick `num_cores` distinct CPU cores and return their CPU sets
+    ///
+    /// Cores are picked in logical order, among those that contain CPUs that
+    /// the current process is allowed to run on, and only these allowed CPUs
+    /// are included in the output. Depending on `smt`, each output [`CpuSet`]
+    /// either contains all the allowed PUs of a core, or only the first one.
+    ///
+    /// Binding one thread to each output CPU set with
+    /// [`SmtPolicy::ExcludeSiblings`] thus guarantees that no two threads
+    /// share a physical core.
+    ///
+    /// # Errors
+    ///
+    /// [`NotEnoughCoresError`] if fewer than `num_cores` cores contain CPUs
+    /// that the current process is allowed to run on.
+    pub fn exclusive_cores(
+        &self,
+        num_cores: usize,
+        smt: SmtPolicy,
+    ) -> Result<Vec<CpuSet>, NotEnoughCoresError> {
+        if num_cores == 0 {
+            return Ok(Vec::new());
+        }
+        let mut cores = self
+            .allowed_object_cpusets(ObjectType::Core)
+            .unwrap_or_default();
+        if cores.len() < num_cores {
+            return Err(NotEnoughCoresError {
+                requested: num_cores,
+                available: cores.len(),
+            });
+        }
+        cores.truncate(num_cores);
+        if smt == SmtPolicy::ExcludeSiblings {
+            for core in &mut cores {
+                core.singlify();
+            }
+        }
+        Ok(cores)
+    }
+
+    /// Number of threads that the current process can effectively run in
+    /// parallel
+    ///
+    /// This is a topology-aware alternative to
+    /// [`std::thread::available_parallelism()`]. It counts the PUs or CPU cores
+    /// (depending on `granularity`) that contain CPUs which are...
+    ///
+    /// - Allowed for use by the current process, as reported by
+    ///   [`Topology::allowed_cpuset()`].
+    /// - Part of the current process' CPU binding, if the topology describes
+    ///   the system that the program is running on and the binding can be
+    ///   queried.
+    /// - On Linux, allowed by the current process' cgroup, as reported by
+    ///   [`Topology::cgroup_allowed_cpuset()`], if the process is managed by
+    ///   cgroup v2.
+    ///
+    /// With [`Granularity::Core`], SMT siblings are not counted, which is
+    /// usually what compute-bound programs want when sizing a thread pool.
+    /// PUs that do not belong to any [`Core`] object, which happens when the
+    /// topology has no Core objects (e.g. because they were filtered out or
+    /// not detected by hwloc), are counted as one core each.
+    ///
+    /// `None` is returned if none of the topology's CPUs can be used.
+    ///
+    /// This functionality is an hwlocality-specific extension to the hwloc API.
+    ///
+    /// [`Core`]: ObjectType::Core
+    #[doc(alias = "available_parallelism")]
+    pub fn effective_parallelism(&self, granularity: Granularity) -> Option<NonZeroUsize> {
+        let mut cpuset = self.allowed_cpuset().clone_target();
+        if self.is_this_system() {
+            if let Ok(binding) = self.cpu_binding(CpuBindingFlags::PROCESS) {
+                cpuset &= binding;
+            }
+            #[cfg(target_os = "linux")]
+            if let Ok(cgroup) = self.cgroup_allowed_cpuset() {
+                cpuset &= cgroup;
+            }
+        }
+        let count = match granularity {
+            Granularity::PU => cpuset.weight().unwrap_or(0),
+            Granularity::Core => {
+                let mut num_cores = 0;
+                let mut coreless_pus = cpuset.clone();
+                for core_set in self
+                    .objects_with_type(ObjectType::Core)
+                    .filter_map(TopologyObject::cpuset)
+                    .filter(|set| set.intersects(&cpuset))
+                {
+                    num_cores += 1;
+                    coreless_pus -= core_set;
+                }
+                num_cores + coreless_pus.weight().unwrap_or(0)
+            }
+        };
+        NonZeroUsize::new(count)
+    }
+
+    /// Allowed CPUs of each object of a certain type, in logical order
+    ///
+    /// Objects without allowed CPUs are skipped, and objects with the same
+    /// allowed CPUs as a previous object are deduplicated.
+    fn allowed_object_cpusets(
+        &self,
+        object_type: ObjectType,
+    ) -> Result<Vec<CpuSet>, DistributePolicyError> {
+        let allowed = self.allowed_cpuset();
+        let mut result = Vec::<CpuSet>::new();
+        for obj in self.objects_with_type(object_type) {
+            let Some(cpuset) = obj.cpuset() else {
+                continue;
+            };
+            let cpuset = cpuset.clone_target() & &*allowed;
+            if !cpuset.is_empty() && !result.contains(&cpuset) {
+                result.push(cpuset);
+            }
+        }
+        if result.is_empty() {
+            return Err(DistributePolicyError::NoAllowedCpus(object_type));
+        }
+        Ok(result)
+    }
+}
+
+/// # Thread pool CPU affinity with rayon
+///
+/// This functionality is unique to the Rust hwloc bindings
+#[cfg(feature = "rayon")]
+impl Topology {
+    /// Set up a rayon thread pool whose worker threads are bound to CPUs
+    /// according to some [`BindingPolicy`]
+    ///
+    /// The returned builder is configured with one worker thread per PU that
+    /// the current process is allowed to run on, and with a start handler
+    /// that binds the `i`-th worker thread to the `i`-th CPU set computed by
+    /// [`Topology::distribute_policy()`]. You can further configure the
+    /// builder before building the thread pool, but you should not replace its
+    /// start handler. If you reduce the number of threads, the CPU sets
+    /// computed for the original number of threads are still used. If you
+    /// increase it, CPU sets are handed out again from the beginning.
+    ///
+    /// Since start handlers cannot report errors, binding failures (e.g. on
+    /// operating systems that do not support thread binding) are ignored,
+    /// and the affected worker threads run unbound.
+    ///
+    /// # Errors
+    ///
+    /// Same as [`Topology::distribute_policy()`].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use hwlocality::{cpu::affinity::BindingPolicy, Topology};
+    /// let topology = Topology::new()?;
+    /// let pool = topology
+    ///     .rayon_pool_builder(&BindingPolicy::PerCore)?
+    ///     .build()?;
+    /// pool.install(|| { /* ... */ });
+    /// # Ok::<(), eyre::Report>(())
+    /// ```
+    pub fn rayon_pool_builder(
+        &self,
+        policy: &BindingPolicy,
+    ) -> Result<ThreadPoolBuilder, DistributePolicyError> {
+        let num_threads = self
+            .allowed_cpuset()
+            .weight()
+            .expect("allowed cpusets should be finite")
+            .max(1);
+        let cpusets = self.distribute_policy(num_threads, policy)?;
+        let topology = Arc::new(self.clone());
+        Ok(ThreadPoolBuilder::new()
+            .num_threads(num_threads)
+            .start_handler(move |thread_idx| {
+                let cpuset = &cpusets[thread_idx % cpusets.len()];
+                // Errors cannot be reported from a start handler, in which
+                // case the worker thread is left unbound
+                std::mem::drop(topology.bind_cpu(cpuset, CpuBindingFlags::THREAD));
+            }))
+    }
+}
+
+/// Spawn the `thread_idx`-th of `num_threads` threads, bound to CPUs according
+/// to some [`BindingPolicy`], before it runs `f`
+///
+/// The thread is bound to the `thread_idx`-th CPU set computed by
+/// [`Topology::distribute_policy()`] for `num_threads` threads, using
+/// [`spawn_bound()`]. The total number of threads is needed because the CPU
+/// sets that the [`Scatter`](BindingPolicy::Scatter) policy hands out depend
+/// on it.
+///
+/// If you are spawning many threads, it is more efficient to call
+/// [`Topology::distribute_policy()`] once and then spawn each thread using
+/// [`spawn_bound()`].
+///
+/// # Errors
+///
+/// - [`Policy`] if the CPU sets could not be computed, see
+///   [`Topology::distribute_policy()`]
+/// - [`Spawn`] if the operating system failed to spawn a thread
+///
+/// As with [`spawn_bound()`], binding errors are reported as the result of
+/// the spawned thread, and `f` is not called in this case.
+///
+/// # Panics
+///
+/// If `thread_idx` is not smaller than `num_threads`.
+///
+/// [`Policy`]: SpawnWithPolicyError::Policy
+/// [`Spawn`]: SpawnWithPolicyError::Spawn
+#[cfg(any(unix, windows))]
+#[cfg_attr(docsrs, doc(cfg(any(unix, windows))))]
+pub fn spawn_bound_with_policy<F, T>(
+    topology: &Topology,
+    policy: &BindingPolicy,
+    num_threads: usize,
+    thread_idx: usize,
+    f: F,
+) -> Result<JoinHandle<Result<T, HybridError<CpuBindingError>>>, SpawnWithPolicyError>
+where
+    F: FnOnce() -> T + Send + 'static,
+    T: Send + 'static,
+{
+    assert!(
+        thread_idx < num_threads,
+        "thread index {thread_idx} is out of range for {num_threads} threads"
+    );
+    let cpusets = topology.distribute_policy(num_threads, policy)?;
+    Ok(spawn_bound(topology, &cpusets[thread_idx], f)?)
+}
+
+/// Error returned by [`spawn_bound_with_policy()`]
+#[cfg(any(unix, windows))]
+#[cfg_attr(docsrs, doc(cfg(any(unix, windows))))]
+#[derive(Debug, Error)]
+pub enum SpawnWithPolicyError {
+    /// Failed to compute the CPU sets that threads should be bound to
+    #[error(transparent)]
+    Policy(#[from] DistributePolicyError),
+
+    /// Failed to spawn the thread
+    #[error("failed to spawn thread")]
+    Spawn(#[from] io::Error),
+}
+
+/// Error returned by [`Topology::distribute_policy()`]
+#[derive(Copy, Clone, Debug, Eq, Error, Hash, PartialEq)]
+pub enum DistributePolicyError {
+    /// An [`Explicit`](BindingPolicy::Explicit) policy must specify at least
+    /// one CPU set, and all CPU sets must be non-empty
+    #[error("explicit binding policies need a list of non-empty CPU sets")]
+    EmptyExplicitList,
+
+    /// No allowed CPU was found in objects of the specified type
+    ///
+    /// This can happen if the topology does not contain any object of this
+    /// type (e.g. there is no L3 cache, or cores were filtered out of the
+    /// topology), or if the process is not allowed to run on any of their
+    /// CPUs.
+    #[error("no allowed CPU found in {0} objects")]
+    NoAllowedCpus(ObjectType),
+}
+
+/// Error returned by [`Topology::exclusive_cores()`] when there are not
+/// enough CPU cores with allowed CPUs
+#[derive(Copy, Clone, Debug, Default, Eq, Error, Hash, PartialEq)]
+#[error("requested {requested} exclusive CPU cores, but only {available} are available")]
+pub struct NotEnoughCoresError {
+    /// Number of cores that was requested
+    pub requested: usize,
+
+    /// Number of cores that the current process is allowed to run on
+    pub available: usize,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::prelude::*;
+    #[allow(unused)]
+    use similar_asserts::assert_eq;
+    use static_assertions::{assert_impl_all, assert_not_impl_any};
+    use std::{
+        error::Error,
+        fmt::{
+            self, Binary, Debug, Display, LowerExp, LowerHex, Octal, Pointer, UpperExp, UpperHex,
+        },
+        hash::Hash,
+        io::{self, Read},
+        ops::Deref,
+        panic::UnwindSafe,
+    };
+
+    // Check that public types in this module keep implementing all expected
+    // traits, in the interest of detecting future semver-breaking changes
+    assert_impl_all!(BindingPolicy:
+        Clone, Debug, Hash, Sized, Sync, Unpin, UnwindSafe
+    );
+    assert_not_impl_any!(BindingPolicy:
+        Binary, Copy, Default, Deref, Display, Drop, IntoIterator, LowerExp,
+        LowerHex, Octal, PartialOrd, Pointer, Read, UpperExp, UpperHex,
+        fmt::Write, io::Write
+    );
+    assert_impl_all!(DistributePolicyError:
+        Copy, Error, Hash, Sized, Sync, Unpin, UnwindSafe
+    );
+    assert_not_impl_any!(DistributePolicyError:
+        Binary, Default, Deref, Drop, IntoIterator, LowerExp, LowerHex,
+        Octal, PartialOrd, Pointer, Read, UpperExp, UpperHex, fmt::Write,
+        io::Write
+    );
+
+    assert_impl_all!(NotEnoughCoresError:
+        Copy, Default, Error, Hash, Sized, Sync, Unpin, UnwindSafe
+    );
+    assert_not_impl_any!(NotEnoughCoresError:
+        Binary, Deref, Drop, IntoIterator, LowerExp, LowerHex, Octal,
+        PartialOrd, Pointer, Read, UpperExp, UpperHex, fmt::Write, io::Write
+    );
+    assert_impl_all!(Granularity:
+        Copy, Debug, Hash, Sized, Sync, Unpin, UnwindSafe
+    );
+    assert_not_impl_any!(Granularity:
+        Binary, Default, Deref, Display, Drop, IntoIterator, LowerExp,
+        LowerHex, Octal, PartialOrd, Pointer, Read, UpperExp, UpperHex,
+        fmt::Write, io::Write
+    );
+    assert_impl_all!(SmtPolicy:
+        Copy, Debug, Hash, Sized, Sync, Unpin, UnwindSafe
+    );
+    assert_not_impl_any!(SmtPolicy:
+        Binary, Default, Deref, Display, Drop, IntoIterator, LowerExp,
+        LowerHex, Octal, PartialOrd, Pointer, Read, UpperExp, UpperHex,
+        fmt::Write, io::Write
+    );
+
+    #[cfg(any(unix, windows))]
+    assert_impl_all!(SpawnWithPolicyError:
+        Error, Send, Sized, Sync, Unpin
+    );
+    #[cfg(any(unix, windows))]
+    assert_not_impl_any!(SpawnWithPolicyError:
+        Binary, Clone, Default, Deref, Drop, IntoIterator, LowerExp, LowerHex,
+        Octal, PartialOrd, Pointer, Read, UpperExp, UpperHex, fmt::Write,
+        io::Write
+    );
+
+    /// Check the output of a per-object policy
+    fn check_per_object(
+        policy: &BindingPolicy,
+        object_type: ObjectType,
+        num_threads: usize,
+    ) -> Result<(), TestCaseError> {
+        let topology = Topology::test_instance();
+        let result = topology.distribute_policy(num_threads, policy);
+        let Ok(expected) = topology.allowed_object_cpusets(object_type) else {
+            prop_assert_eq!(
+                result,
+                Err(DistributePolicyError::NoAllowedCpus(object_type))
+            );
+            return Ok(());
+        };
+        let result = result.unwrap();
+        prop_assert_eq!(result.len(), num_threads);
+        for (idx, cpuset) in result.iter().enumerate() {
+            prop_assert_eq!(cpuset, &expected[idx % expected.len()]);
+            prop_assert!(topology.allowed_cpuset().includes(cpuset));
+        }
+        Ok(())
+    }
+
+    /// Rayon worker threads should be bound as the policy dictates
+    #[cfg(feature = "rayon")]
+    #[test]
+    fn rayon_pool() {
+        use crate::topology::support::CpuBindingSupport;
+
+        let topology = Topology::test_instance();
+        let policy = BindingPolicy::PerCore;
+        let Ok(expected) =
+            topology.distribute_policy(topology.allowed_cpuset().weight().unwrap(), &policy)
+        else {
+            topology.rayon_pool_builder(&policy).unwrap_err();
+            return;
+        };
+        let pool = topology
+            .rayon_pool_builder(&policy)
+            .unwrap()
+            .build()
+            .unwrap();
+        assert_eq!(pool.current_num_threads(), expected.len());
+        let supports_thread_binding =
+            topology
+                .feature_support()
+                .cpu_binding()
+                .is_some_and(|support| {
+                    CpuBindingSupport::get_thread(support) && CpuBindingSupport::set_thread(support)
+                });
+        if !supports_thread_binding {
+            return;
+        }
+        let bindings = pool.broadcast(|ctx| {
+            (
+                ctx.index(),
+                topology.cpu_binding(CpuBindingFlags::THREAD).unwrap(),
+            )
+        });
+        for (thread_idx, binding) in bindings {
+            assert_eq!(binding, expected[thread_idx]);
+        }
+    }
+
+    /// Threads spawned according to a policy should get the matching CPU set
+    #[cfg(any(unix, windows))]
+    #[test]
+    fn spawn_with_policy() {
+        use crate::{cpu::binding::CpuBindingFlags, topology::support::CpuBindingSupport};
+
+        let topology = Topology::test_instance();
+        let policy = BindingPolicy::Compact;
+        let Ok(expected) = topology.distribute_policy(2, &policy) else {
+            assert!(matches!(
+                spawn_bound_with_policy(topology, &policy, 2, 1, || ()),
+                Err(SpawnWithPolicyError::Policy(_))
+            ));
+            return;
+        };
+        let thread_support = |check: fn(&CpuBindingSupport) -> bool| {
+            topology.feature_support().cpu_binding().is_some_and(check)
+        };
+        let can_bind_threads = thread_support(CpuBindingSupport::set_thread);
+        let can_query_threads = thread_support(CpuBindingSupport::get_thread);
+        let result = spawn_bound_with_policy(topology, &policy, 2, 1, || {
+            Topology::test_instance().cpu_binding(CpuBindingFlags::THREAD)
+        })
+        .unwrap()
+        .join()
+        .unwrap();
+        if can_bind_threads {
+            let binding = result.unwrap();
+            if can_query_threads {
+                assert_eq!(binding.unwrap(), expected[1]);
+            }
+        } else {
+            // The thread must not run unbound if binding is unsupported
+            result.unwrap_err();
+        }
+    }
+
+    /// Check that effective parallelism is consistent with the topology
+    #[test]
+    fn effective_parallelism() {
+        let topology = Topology::test_instance();
+        let pus = topology.effective_parallelism(Granularity::PU).unwrap();
+        let cores = topology.effective_parallelism(Granularity::Core).unwrap();
+        assert!(cores <= pus);
+        assert!(pus.get() <= topology.allowed_cpuset().weight().unwrap());
+        assert!(cores.get() <= topology.objects_with_type(ObjectType::Core).count());
+
+        // Binding is not taken into account for foreign topologies
+        let foreign = Topology::test_dual_socket_numa();
+        assert_eq!(
+            foreign
+                .effective_parallelism(Granularity::PU)
+                .map(NonZeroUsize::get),
+            foreign.allowed_cpuset().weight()
+        );
+        assert_eq!(
+            foreign
+                .effective_parallelism(Granularity::Core)
+                .map(NonZeroUsize::get),
+            Some(foreign.objects_with_type(ObjectType::Core).count())
+        );
+
+        // PUs are counted as cores when the topology has no Core objects
+        let coreless = Topology::builder()
+            .from_synthetic("Package:2 PU:3")
+            .unwrap()
+            .build()
+            .unwrap();
+        assert_eq!(coreless.objects_with_type(ObjectType::Core).count(), 0);
+        assert_eq!(
+            coreless.effective_parallelism(Granularity::Core),
+            NonZeroUsize::new(6)
+        );
+        assert_eq!(
+            coreless.effective_parallelism(Granularity::PU),
+            NonZeroUsize::new(6)
+        );
+    }
+
+    proptest! {
+        #[test]
+        fn per_object(num_threads in 0usize..100) {
+            let topology = Topology::test_instance();
+            check_per_object(&BindingPolicy::Compact, ObjectType::PU, num_threads)?;
+            check_per_object(&BindingPolicy::PerCore, ObjectType::Core, num_threads)?;
+            check_per_object(&BindingPolicy::PerNUMANode, ObjectType::NUMANode, num_threads)?;
+            check_per_object(&BindingPolicy::PerL3Cache, ObjectType::L3Cache, num_threads)?;
+
+            // Compact should use distinct PUs until it runs out of them
+            let compact = topology.distribute_policy(num_threads, &BindingPolicy::Compact).unwrap();
+            let num_pus = topology.allowed_cpuset().weight().unwrap();
+            for (idx, cpuset) in compact.iter().enumerate().take(num_pus) {
+                prop_assert_eq!(cpuset.weight(), Some(1));
+                prop_assert!(compact[..idx].iter().all(|other| other != cpuset));
+            }
+        }
+
+        #[test]
+        fn exclusive_cores(num_cores in 0usize..100) {
+            let topology = Topology::test_instance();
+            let with_siblings = topology.exclusive_cores(num_cores, SmtPolicy::IncludeSiblings);
+            let without_siblings = topology.exclusive_cores(num_cores, SmtPolicy::ExcludeSiblings);
+            let available = topology.allowed_object_cpusets(ObjectType::Core).map_or(0, |cores| cores.len());
+            if num_cores > available {
+                let expected = Err(NotEnoughCoresError { requested: num_cores, available });
+                prop_assert_eq!(&with_siblings, &expected);
+                prop_assert_eq!(without_siblings, expected);
+                return Ok(());
+            }
+
+            // Output should be the first allowed cores, with one PU per core
+            // if siblings are excluded
+            let with_siblings = with_siblings.unwrap();
+            let without_siblings = without_siblings.unwrap();
+            prop_assert_eq!(with_siblings.len(), num_cores);
+            prop_assert_eq!(without_siblings.len(), num_cores);
+            let per_core = topology.distribute_policy(num_cores, &BindingPolicy::PerCore);
+            if num_cores > 0 {
+                prop_assert_eq!(&with_siblings, &per_core.unwrap());
+            }
+            for (core, pu) in with_siblings.iter().zip(&without_siblings) {
+                prop_assert_eq!(pu.weight(), Some(1));
+                prop_assert_eq!(pu.first_set(), core.first_set());
+            }
+        }
+
+        #[test]
+        fn scatter(num_threads in 0usize..100) {
+            let topology = Topology::test_instance();
+            let result = topology.distribute_policy(num_threads, &BindingPolicy::Scatter).unwrap();
+            prop_assert_eq!(result.len(), num_threads);
+
+            // Each thread should get one allowed PU, and threads should not
+            // share PUs until there are more threads than allowed PUs
+            let allowed = topology.allowed_cpuset();
+            let num_pus = allowed.weight().unwrap();
+            for (idx, cpuset) in result.iter().enumerate() {
+                prop_assert_eq!(cpuset.weight(), Some(1));
+                prop_assert!(allowed.includes(cpuset));
+                if num_threads <= num_pus {
+                    prop_assert!(result[..idx].iter().all(|other| !other.intersects(cpuset)));
+                }
+            }
+        }
+
+        #[test]
+        fn explicit(cpusets: Vec<CpuSet>, num_threads in 0usize..100) {
+            let topology = Topology::test_instance();
+            let result = topology.distribute_policy(
+                num_threads,
+                &BindingPolicy::Explicit(cpusets.clone())
+            );
+            if cpusets.is_empty() || cpusets.iter().any(CpuSet::is_empty) {
+                prop_assert_eq!(result, Err(DistributePolicyError::EmptyExplicitList));
+                return Ok(());
+            }
+            let result = result.unwrap();
+            prop_assert_eq!(result.len(), num_threads);
+            for (idx, cpuset) in result.iter().enumerate() {
+                prop_assert_eq!(cpuset, &cpusets[idx % cpusets.len()]);
+            }
+        }
+    }
+}
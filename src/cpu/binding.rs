@@ -7,11 +7,14 @@
 //! struct](../../topology/struct.Topology.html#cpu-binding). The module itself
 //! only hosts type definitions that are related to this functionality.
 
+#[cfg(any(unix, windows))]
+use crate::AsThreadId;
 #[cfg(doc)]
-use crate::{bitmap::Bitmap, object::types::ObjectType, topology::support::CpuBindingSupport};
+use crate::{bitmap::Bitmap, topology::support::CpuBindingSupport};
 use crate::{
     cpu::cpuset::CpuSet,
     errors::{self, FlagsError, HybridError, RawHwlocError},
+    object::{types::ObjectType, TopologyObject},
     topology::Topology,
     ProcessId, ThreadId,
 };
@@ -32,8 +35,16 @@ use similar_asserts::assert_eq;
 use std::{
     ffi::{c_int, c_uint},
     fmt::Display,
+    iter::FusedIterator,
+    marker::PhantomData,
     ops::Deref,
 };
+#[cfg(any(unix, windows))]
+use std::{
+    io,
+    sync::mpsc,
+    thread::{self, JoinHandle},
+};
 use thiserror::Error;
 
 /// # CPU binding
@@ -137,6 +148,146 @@ impl Topology {
         polymorphized(self, &set, flags)
     }
 
+    /// Binds the current process or thread on given CPUs, trying several sets
+    /// of flags by order of preference
+    ///
+    /// Portable applications often want the strongest binding guarantees that
+    /// the operating system can provide, but are ready to settle for less.
+    /// This method implements this pattern by trying to [bind](Self::bind_cpu)
+    /// the current process or thread with each entry of `flags_preference` in
+    /// order, until one of them succeeds. It returns the flags that succeeded,
+    /// which tells you which level of binding was achieved.
+    ///
+    /// Entries that are not supported according to [`CpuBindingSupport`], e.g.
+    /// [`THREAD`] entries on an operating system that does not support binding
+    /// the current thread, are skipped without trying them.
+    ///
+    /// # Errors
+    ///
+    /// If no entry of `flags_preference` succeeded, the error from the last
+    /// attempt is returned. If no attempt was made because no entry is
+    /// supported, a [`BadObject(ThisProgram)`] error is returned, and if
+    /// `flags_preference` is empty, a [`BadFlags`] error is returned.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use hwlocality::{cpu::binding::CpuBindingFlags, Topology};
+    /// # let topology = Topology::test_instance();
+    /// let preferences = [
+    ///     CpuBindingFlags::THREAD | CpuBindingFlags::STRICT,
+    ///     CpuBindingFlags::THREAD,
+    ///     CpuBindingFlags::PROCESS | CpuBindingFlags::STRICT,
+    ///     CpuBindingFlags::PROCESS,
+    /// ];
+    /// match topology.bind_cpu_with_fallback(topology.cpuset(), preferences) {
+    ///     Ok(flags) => println!("Bound with flags {flags:?}"),
+    ///     Err(e) => println!("Failed to bind: {e}"),
+    /// }
+    /// # Ok::<(), eyre::Report>(())
+    /// ```
+    ///
+    /// [`BadFlags`]: CpuBindingError::BadFlags
+    /// [`BadObject(ThisProgram)`]: CpuBindingError::BadObject
+    /// [`THREAD`]: CpuBindingFlags::THREAD
+    pub fn bind_cpu_with_fallback(
+        &self,
+        set: impl Deref<Target = CpuSet>,
+        flags_preference: impl IntoIterator<Item = CpuBindingFlags>,
+    ) -> Result<CpuBindingFlags, HybridError<CpuBindingError>> {
+        let set: &CpuSet = &set;
+        let support = self.feature_support().cpu_binding();
+        let is_supported = |flags: CpuBindingFlags| {
+            let Some(support) = support else {
+                return true;
+            };
+            if flags.contains(CpuBindingFlags::PROCESS) {
+                support.set_current_process()
+            } else if flags.contains(CpuBindingFlags::THREAD) {
+                support.set_current_thread()
+            } else {
+                support.set_current_process() || support.set_current_thread()
+            }
+        };
+        let mut last_error = None;
+        for flags in flags_preference {
+            if !is_supported(flags) {
+                last_error
+                    .get_or_insert(CpuBindingError::BadObject(CpuBoundObject::ThisProgram).into());
+                continue;
+            }
+            match self.bind_cpu(set, flags) {
+                Ok(()) => return Ok(flags),
+                Err(e) => last_error = Some(e),
+            }
+        }
+        Err(last_error.unwrap_or_else(|| CpuBindingError::from(CpuBindingFlags::empty()).into()))
+    }
+
+    /// Temporarily bind the current process or thread on given CPUs
+    ///
+    /// This works like [`Topology::bind_cpu()`], except the current binding is
+    /// queried beforehand and restored when the returned [`CpuBindingGuard`]
+    /// is dropped. This makes it easy to pin the current process or thread
+    /// during a latency-critical section or a benchmark, and to reliably undo
+    /// the pinning afterwards, even if the section returns early or panics.
+    ///
+    /// The previous binding is queried using the binding target flag
+    /// ([`ASSUME_SINGLE_THREAD`], [`THREAD`] or [`PROCESS`]) that is specified
+    /// in `flags`, and restored using `flags` without the [`STRICT`] flag.
+    ///
+    /// Requires [`CpuBindingSupport::get_current_process()`] and
+    /// [`CpuBindingSupport::set_current_process()`], or
+    /// [`CpuBindingSupport::get_current_thread()`] and
+    /// [`CpuBindingSupport::set_current_thread()`], depending on flags.
+    ///
+    /// # Errors
+    ///
+    /// Same as [`Topology::cpu_binding()`] and [`Topology::bind_cpu()`]. If
+    /// querying the current binding fails, the binding is left unchanged.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use hwlocality::{cpu::binding::CpuBindingFlags, object::types::ObjectType, Topology};
+    /// # let topology = Topology::test_instance();
+    /// let core_cpuset = topology
+    ///     .objects_with_type(ObjectType::Core)
+    ///     .next()
+    ///     .and_then(|core| core.cpuset())
+    ///     .map(|cpuset| cpuset.clone_target());
+    /// if let Some(cpuset) = core_cpuset {
+    ///     if let Ok(_guard) = topology.bind_cpu_scoped(&cpuset, CpuBindingFlags::THREAD) {
+    ///         // ...latency-critical work runs on the first core...
+    ///     }
+    ///     // ...previous binding is restored here...
+    /// }
+    /// # Ok::<(), eyre::Report>(())
+    /// ```
+    ///
+    /// [`ASSUME_SINGLE_THREAD`]: CpuBindingFlags::ASSUME_SINGLE_THREAD
+    /// [`PROCESS`]: CpuBindingFlags::PROCESS
+    /// [`STRICT`]: CpuBindingFlags::STRICT
+    /// [`THREAD`]: CpuBindingFlags::THREAD
+    pub fn bind_cpu_scoped(
+        &self,
+        set: impl Deref<Target = CpuSet>,
+        flags: CpuBindingFlags,
+    ) -> Result<CpuBindingGuard<'_>, HybridError<CpuBindingError>> {
+        let query_flags = flags
+            & (CpuBindingFlags::PROCESS
+                | CpuBindingFlags::THREAD
+                | CpuBindingFlags::ASSUME_SINGLE_THREAD);
+        let previous = self.cpu_binding(query_flags)?;
+        self.bind_cpu(set, flags)?;
+        Ok(CpuBindingGuard {
+            topology: self,
+            previous: Some(previous),
+            flags: flags - CpuBindingFlags::STRICT,
+            _not_send: PhantomData,
+        })
+    }
+
     /// Get the current process or thread CPU binding
     ///
     /// You must specify exactly one of the [`ASSUME_SINGLE_THREAD`],
@@ -511,6 +662,64 @@ impl Topology {
         }
     }
 
+    /// Get the PUs where the current program or another process last ran,
+    /// along with their enclosing core and package
+    ///
+    /// This is a convenience wrapper around
+    /// [`Topology::last_cpu_location()`] (if `pid` is `None`) and
+    /// [`Topology::last_process_cpu_location()`] (if `pid` is `Some`), which
+    /// translates the resulting [`CpuSet`] into [`CpuLocation`]s, sorted by
+    /// increasing PU OS index. Please refer to the documentation of these
+    /// methods for the meaning of `flags` and for their support requirements.
+    ///
+    /// PUs which are not part of this topology, e.g. because they were
+    /// filtered out as disallowed, are not reported.
+    ///
+    /// This functionality is specific to the Rust bindings.
+    ///
+    /// # Errors
+    ///
+    /// Same as [`Topology::last_cpu_location()`] or
+    /// [`Topology::last_process_cpu_location()`], depending on `pid`.
+    ///
+    /// # Panics
+    ///
+    /// Same as [`Topology::last_process_cpu_location()`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use hwlocality::{cpu::binding::CpuBindingFlags, Topology};
+    /// # let topology = Topology::test_instance();
+    /// let pid = std::process::id();
+    /// if let Ok(locations) = topology.last_cpu_location_objects(Some(pid), CpuBindingFlags::empty()) {
+    ///     for location in locations {
+    ///         println!("Ran on {} of {:?}", location.pu, location.core);
+    ///     }
+    /// }
+    /// # Ok::<(), eyre::Report>(())
+    /// ```
+    pub fn last_cpu_location_objects(
+        &self,
+        pid: Option<ProcessId>,
+        flags: CpuBindingFlags,
+    ) -> Result<
+        impl DoubleEndedIterator<Item = CpuLocation<'_>> + Clone + ExactSizeIterator + FusedIterator,
+        HybridError<CpuBindingError>,
+    > {
+        let cpuset = if let Some(pid) = pid {
+            self.last_process_cpu_location(pid, flags)?
+        } else {
+            self.last_cpu_location(flags)?
+        };
+        Ok(cpuset
+            .iter_set()
+            .filter_map(|os_index| self.pu_with_os_index(usize::from(os_index)))
+            .map(CpuLocation::new)
+            .collect::<Vec<_>>()
+            .into_iter())
+    }
+
     /// Binding for `hwloc_set_cpubind`-like functions
     ///
     /// # Safety
@@ -645,6 +854,63 @@ impl Topology {
     }
 }
 
+/// Spawn a thread that is bound to the CPUs of `set` before it runs `f`
+///
+/// Binding a thread from the inside after it has started running is racy, as
+/// the OS scheduler may have already placed it on other CPUs, and any memory
+/// that it allocated in the meantime may end up far away from its final
+/// location. This function avoids the problem by spawning a thread that waits
+/// until it has been bound using [`Topology::bind_thread_cpu()`] before
+/// calling `f`.
+///
+/// Requires [`CpuBindingSupport::set_thread()`].
+///
+/// # Errors
+///
+/// If the operating system fails to spawn a thread, the associated
+/// [`io::Error`] is returned, as in [`std::thread::Builder::spawn()`].
+///
+/// Otherwise, the handle of the spawned thread is returned. If binding it
+/// failed, `f` is not called and the thread returns the [`CpuBindingError`]
+/// that was reported by [`Topology::bind_thread_cpu()`].
+///
+/// # Examples
+///
+/// ```
+/// # use hwlocality::{cpu::binding::spawn_bound, Topology};
+/// let topology = Topology::new()?;
+/// let handle = spawn_bound(&topology, topology.cpuset(), || 6 * 7)?;
+/// match handle.join().unwrap() {
+///     Ok(result) => assert_eq!(result, 42),
+///     Err(e) => println!("Failed to bind thread: {e}"),
+/// }
+/// # Ok::<(), eyre::Report>(())
+/// ```
+#[cfg(any(unix, windows))]
+#[cfg_attr(docsrs, doc(cfg(any(unix, windows))))]
+pub fn spawn_bound<F, T>(
+    topology: &Topology,
+    set: impl Deref<Target = CpuSet>,
+    f: F,
+) -> io::Result<JoinHandle<Result<T, HybridError<CpuBindingError>>>>
+where
+    F: FnOnce() -> T + Send + 'static,
+    T: Send + 'static,
+{
+    let (sender, receiver) = mpsc::sync_channel(1);
+    let handle = thread::Builder::new().spawn(move || {
+        receiver
+            .recv()
+            .expect("spawning thread should report the binding outcome")?;
+        Ok(f())
+    })?;
+    let binding = topology.bind_thread_cpu(handle.as_thread_id(), set, CpuBindingFlags::empty());
+    sender
+        .send(binding)
+        .expect("spawned thread should wait for the binding outcome");
+    Ok(handle)
+}
+
 bitflags! {
     /// Process/Thread binding flags
     ///
@@ -851,6 +1117,96 @@ impl Display for CpuBoundObject {
     }
 }
 
+/// PU where a process or thread ran, along with its enclosing core and package
+///
+/// Obtained via [`Topology::last_cpu_location_objects()`].
+#[derive(Copy, Clone, Debug)]
+pub struct CpuLocation<'topology> {
+    /// PU where the process or thread ran
+    pub pu: &'topology TopologyObject,
+
+    /// Core that contains this PU, if any
+    pub core: Option<&'topology TopologyObject>,
+
+    /// Package that contains this PU, if any
+    pub package: Option<&'topology TopologyObject>,
+}
+//
+impl<'topology> CpuLocation<'topology> {
+    /// Locate a PU within its enclosing core and package
+    fn new(pu: &'topology TopologyObject) -> Self {
+        let ancestor_with_type =
+            |ty: ObjectType| pu.ancestors().find(|ancestor| ancestor.object_type() == ty);
+        Self {
+            pu,
+            core: ancestor_with_type(ObjectType::Core),
+            package: ancestor_with_type(ObjectType::Package),
+        }
+    }
+}
+
+/// Guard that restores the previous CPU binding of the current process or
+/// thread when dropped
+///
+/// Obtained via [`Topology::bind_cpu_scoped()`].
+///
+/// Since the binding is restored by the thread that drops the guard, this type
+/// cannot be sent to another thread.
+#[derive(Debug)]
+#[must_use = "the previous CPU binding is restored as soon as the guard is dropped"]
+pub struct CpuBindingGuard<'topology> {
+    /// Topology that was used to set up the binding
+    topology: &'topology Topology,
+
+    /// Binding to be restored, `None` once it has been restored
+    previous: Option<CpuSet>,
+
+    /// Flags to be used when restoring the binding
+    flags: CpuBindingFlags,
+
+    /// Binding of the current thread must be restored by the same thread
+    _not_send: PhantomData<*const ()>,
+}
+//
+impl CpuBindingGuard<'_> {
+    /// CPU binding that will be restored when this guard is dropped
+    pub fn previous_binding(&self) -> &CpuSet {
+        self.previous
+            .as_ref()
+            .expect("previous binding is only taken when the guard is consumed")
+    }
+
+    /// Restore the previous CPU binding now, reporting errors
+    ///
+    /// Dropping the guard restores the previous binding too, but can only
+    /// report errors by printing them to stderr.
+    ///
+    /// # Errors
+    ///
+    /// Same as [`Topology::bind_cpu()`].
+    pub fn restore(mut self) -> Result<(), HybridError<CpuBindingError>> {
+        self.restore_impl()
+    }
+
+    /// Restore the previous CPU binding, if not done already
+    fn restore_impl(&mut self) -> Result<(), HybridError<CpuBindingError>> {
+        let Some(previous) = self.previous.take() else {
+            return Ok(());
+        };
+        self.topology.bind_cpu(&previous, self.flags)
+    }
+}
+//
+impl Drop for CpuBindingGuard<'_> {
+    #[allow(clippy::print_stderr)]
+    fn drop(&mut self) {
+        if let Err(e) = self.restore_impl() {
+            // Cannot panic in Drop
+            eprintln!("ERROR: Failed to restore previous CPU binding ({e}).");
+        }
+    }
+}
+
 /// Operation on that object's CPU binding
 #[derive(Copy, Clone, Debug, Display, Eq, Hash, PartialEq)]
 pub(crate) enum CpuBindingOperation {
@@ -974,6 +1330,61 @@ mod tests {
     // single_threaded integration test because of ASSUME_SINGLE_THREAD, but
     // some things can be tested here.
 
+    /// Threads spawned by `spawn_bound()` should run bound, or not at all
+    #[cfg(any(unix, windows))]
+    #[test]
+    fn spawn_bound_thread() {
+        use crate::topology::support::CpuBindingSupport;
+
+        let topology = Topology::test_instance();
+        let target = topology.cpuset().clone_target();
+        let supports_thread_binding =
+            topology
+                .feature_support()
+                .cpu_binding()
+                .is_some_and(|support| {
+                    CpuBindingSupport::get_thread(support) && CpuBindingSupport::set_thread(support)
+                });
+        let result = spawn_bound(topology, &target, || {
+            Topology::test_instance().cpu_binding(CpuBindingFlags::THREAD)
+        })
+        .unwrap()
+        .join()
+        .unwrap();
+        match result {
+            Ok(binding) if supports_thread_binding => assert_eq!(binding.unwrap(), target),
+            Ok(_) => {}
+            Err(e) => assert!(!supports_thread_binding, "unexpected binding error {e}"),
+        }
+    }
+
+    /// Last CPU locations should be PUs, reported with their core and package
+    #[test]
+    fn last_cpu_location_objects() {
+        let topology = Topology::test_instance();
+        let flags = CpuBindingFlags::empty();
+        let Ok(locations) = topology.last_cpu_location_objects(Some(std::process::id()), flags)
+        else {
+            return;
+        };
+        for location in locations {
+            assert_eq!(location.pu.object_type(), ObjectType::PU);
+            assert_eq!(location.pu.cpuset().unwrap().weight(), Some(1));
+            for (ancestor, ty) in [
+                (location.core, ObjectType::Core),
+                (location.package, ObjectType::Package),
+            ] {
+                if let Some(ancestor) = ancestor {
+                    assert_eq!(ancestor.object_type(), ty);
+                    assert!(ancestor
+                        .cpuset()
+                        .unwrap()
+                        .includes(location.pu.cpuset().unwrap()));
+                }
+            }
+        }
+    }
+
     proptest! {
         #[test]
         fn display_cpu_bound_object(object: CpuBoundObject) {
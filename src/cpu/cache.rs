@@ -5,16 +5,27 @@
 //! cache locality optimizations when your performance requirements do not call
 //! for full locality-aware scheduling with manual task and memory pinning.
 //!
+//! This module also hosts the [`CacheLevel`] type, which is used to identify
+//! the caches listed by [`TopologyObject::caches()`].
+//!
 //! This functionality is an hwlocality-specific extension to the hwloc API.
 
 use crate::{
-    object::{attributes::ObjectAttributes, types::ObjectType},
+    object::{
+        attributes::{CacheAssociativity, CacheAttributes, ObjectAttributes},
+        types::{CacheType, ObjectType},
+        TopologyObject,
+    },
     topology::Topology,
 };
 use arrayvec::ArrayVec;
 #[allow(unused)]
 #[cfg(test)]
 use similar_asserts::assert_eq;
+use std::{
+    fmt::{self, Display},
+    num::{NonZeroU64, NonZeroUsize},
+};
 
 /// # CPU cache statistics
 impl Topology {
@@ -58,6 +69,35 @@ impl Topology {
     pub fn cpu_cache_stats(&self) -> Option<CpuCacheStats> {
         CpuCacheStats::new(self)
     }
+
+    /// Summarize the properties of each level of the CPU cache hierarchy
+    ///
+    /// This reports the size, line size, associativity and sharing degree
+    /// of the CPU caches at each level of the cache hierarchy, which is what
+    /// cache blocking algorithms typically need to know.
+    ///
+    /// The summary is computed on first use and cached inside of the
+    /// topology, so this method can be called repeatedly at little cost.
+    ///
+    /// This functionality is an hwlocality-specific extension to the hwloc API.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # let topology = hwlocality::Topology::test_instance();
+    /// #
+    /// for level in topology.cache_hierarchy_summary().levels() {
+    ///     println!(
+    ///         "{}: {:?} bytes, shared by {} PUs",
+    ///         level.level(),
+    ///         level.size(),
+    ///         level.sharing_degree()
+    ///     );
+    /// }
+    /// ```
+    pub fn cache_hierarchy_summary(&self) -> &CacheHierarchySummary {
+        self.indexed_cache_hierarchy()
+    }
 }
 
 /// CPU cache statistics
@@ -188,6 +228,184 @@ impl CpuCacheStats {
     }
 }
 
+/// Position of a CPU cache in the cache hierarchy
+///
+/// This is displayed in the same way as in `lstopo`, e.g. `L1d` for a level 1
+/// data cache, `L1i` for a level 1 instruction cache and `L2` for a level 2
+/// unified cache.
+#[derive(Copy, Clone, Debug, Eq, Hash, PartialEq)]
+pub struct CacheLevel {
+    /// Level of the cache, starting at 1 for the caches closest to the CPU
+    pub depth: usize,
+
+    /// Kind of contents that the cache holds
+    pub cache_type: CacheType,
+}
+//
+impl CacheLevel {
+    /// Level and attributes of a CPU cache object, or `None` if the object is
+    /// not a CPU cache
+    #[allow(clippy::wildcard_enum_match_arm)]
+    pub(crate) fn of_object(object: &TopologyObject) -> Option<(Self, CacheAttributes)> {
+        let depth = match object.object_type() {
+            ObjectType::L1Cache | ObjectType::L1ICache => 1,
+            ObjectType::L2Cache | ObjectType::L2ICache => 2,
+            ObjectType::L3Cache | ObjectType::L3ICache => 3,
+            ObjectType::L4Cache => 4,
+            ObjectType::L5Cache => 5,
+            _ => return None,
+        };
+        let Some(ObjectAttributes::Cache(cache)) = object.attributes() else {
+            unreachable!("Caches should have cache attributes")
+        };
+        let level = Self {
+            depth,
+            cache_type: cache.cache_type(),
+        };
+        Some((level, *cache))
+    }
+}
+//
+impl Display for CacheLevel {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let suffix = match self.cache_type {
+            CacheType::Unified => "",
+            CacheType::Data => "d",
+            CacheType::Instruction => "i",
+        };
+        f.pad(&format!("L{}{suffix}", self.depth))
+    }
+}
+
+/// Summary of the properties of each level of the CPU cache hierarchy
+///
+/// Obtained via [`Topology::cache_hierarchy_summary()`].
+#[derive(Clone, Debug, Default, Eq, Hash, PartialEq)]
+pub struct CacheHierarchySummary {
+    /// Summary of each cache level, by increasing depth
+    levels: Vec<CacheLevelSummary>,
+}
+//
+impl CacheHierarchySummary {
+    /// Summarize the CPU cache hierarchy of a topology
+    pub(crate) fn new(topology: &Topology) -> Self {
+        let mut levels = Vec::<CacheLevelSummary>::new();
+        for &cache_type in CACHE_TYPES_BY_DEPTH {
+            for object in topology.objects_with_type(cache_type) {
+                let (level, attributes) =
+                    CacheLevel::of_object(object).expect("Should be a CPU cache");
+                let instance = CacheLevelSummary {
+                    level,
+                    size: attributes.size(),
+                    line_size: attributes.line_size(),
+                    associativity: attributes.associativity(),
+                    sharing_degree: object
+                        .cpuset()
+                        .and_then(|set| set.weight())
+                        .expect("Caches should have finite cpusets"),
+                    num_instances: 1,
+                };
+                match levels.iter_mut().find(|summary| summary.level == level) {
+                    Some(summary) => {
+                        let num_instances = summary.num_instances + 1;
+                        if instance.size < summary.size {
+                            *summary = instance;
+                        }
+                        summary.num_instances = num_instances;
+                    }
+                    None => levels.push(instance),
+                }
+            }
+        }
+        Self { levels }
+    }
+
+    /// Summary of each CPU cache level, ordered by increasing depth
+    ///
+    /// At a given depth, data and unified caches come before instruction
+    /// caches. Levels that are not present in the topology (including
+    /// instruction caches, which are filtered out by default) are not listed.
+    pub fn levels(&self) -> &[CacheLevelSummary] {
+        &self.levels[..]
+    }
+
+    /// Summary of a particular CPU cache level, if present in the topology
+    pub fn level(&self, level: CacheLevel) -> Option<&CacheLevelSummary> {
+        self.levels.iter().find(|summary| summary.level == level)
+    }
+}
+
+/// Summary of the properties of the CPU caches at one level of the hierarchy
+///
+/// When caches at the same level have different properties (e.g. on hybrid
+/// CPUs with several kinds of cores), the properties of the smallest cache are
+/// reported, which is the conservative choice for cache blocking purposes.
+/// Caches of unknown size are considered to be smaller than all others.
+#[derive(Copy, Clone, Debug, Eq, Hash, PartialEq)]
+pub struct CacheLevelSummary {
+    /// Level of the hierarchy that is being described
+    level: CacheLevel,
+
+    /// Size in bytes, if known
+    size: Option<NonZeroU64>,
+
+    /// Line size in bytes, if known
+    line_size: Option<NonZeroUsize>,
+
+    /// Associativity
+    associativity: CacheAssociativity,
+
+    /// Number of PUs that share one cache
+    sharing_degree: usize,
+
+    /// Number of caches at this level
+    num_instances: usize,
+}
+//
+impl CacheLevelSummary {
+    /// Level of the cache hierarchy that is being described
+    pub fn level(&self) -> CacheLevel {
+        self.level
+    }
+
+    /// Size of one cache in bytes, if known
+    pub fn size(&self) -> Option<NonZeroU64> {
+        self.size
+    }
+
+    /// Cache line size in bytes, if known
+    pub fn line_size(&self) -> Option<NonZeroUsize> {
+        self.line_size
+    }
+
+    /// Cache associativity
+    pub fn associativity(&self) -> CacheAssociativity {
+        self.associativity
+    }
+
+    /// Number of PUs that share one cache
+    pub fn sharing_degree(&self) -> usize {
+        self.sharing_degree
+    }
+
+    /// Number of caches at this level of the hierarchy
+    pub fn num_instances(&self) -> usize {
+        self.num_instances
+    }
+}
+
+/// CPU cache types supported by hwloc, by increasing depth
+const CACHE_TYPES_BY_DEPTH: &[ObjectType] = &[
+    ObjectType::L1Cache,
+    ObjectType::L1ICache,
+    ObjectType::L2Cache,
+    ObjectType::L2ICache,
+    ObjectType::L3Cache,
+    ObjectType::L3ICache,
+    ObjectType::L4Cache,
+    ObjectType::L5Cache,
+];
+
 /// Data (or unified) caches levels supported by hwloc
 const DATA_CACHE_LEVELS: &[ObjectType] = &[
     ObjectType::L1Cache,
@@ -202,6 +420,89 @@ mod tests {
     use super::*;
     use similar_asserts::assert_eq;
 
+    #[test]
+    fn cache_level_display() {
+        let level = |depth, cache_type| CacheLevel { depth, cache_type };
+        assert_eq!(level(1, CacheType::Data).to_string(), "L1d");
+        assert_eq!(level(1, CacheType::Instruction).to_string(), "L1i");
+        assert_eq!(level(3, CacheType::Unified).to_string(), "L3");
+    }
+
+    #[test]
+    fn object_caches() {
+        let topology = Topology::test_instance();
+        for obj in topology.objects() {
+            let expected = obj
+                .ancestors()
+                .filter(|ancestor| ancestor.object_type().is_cpu_cache())
+                .collect::<Vec<_>>();
+            let caches = obj.caches().collect::<Vec<_>>();
+            assert_eq!(caches.len(), expected.len());
+            for ((level, cache, attributes), expected) in caches.into_iter().zip(expected) {
+                assert!(std::ptr::eq(cache, expected));
+                let Some(ObjectAttributes::Cache(expected_attributes)) = expected.attributes()
+                else {
+                    unreachable!()
+                };
+                assert_eq!(attributes, *expected_attributes);
+                assert_eq!(level.cache_type, attributes.cache_type());
+                assert_eq!(
+                    level.cache_type == CacheType::Instruction,
+                    cache.object_type().is_cpu_instruction_cache()
+                );
+                assert!(cache
+                    .object_type()
+                    .to_string()
+                    .starts_with(&format!("L{}", level.depth)));
+            }
+        }
+    }
+
+    #[test]
+    fn hierarchy_summary() {
+        let topology = Topology::test_instance();
+        let summary = topology.cache_hierarchy_summary();
+        assert!(std::ptr::eq(summary, topology.cache_hierarchy_summary()));
+
+        let mut num_levels = 0;
+        let mut last_depth = 0;
+        for (idx, level_summary) in summary.levels().iter().enumerate() {
+            let level = level_summary.level();
+            assert!(level.depth >= last_depth);
+            last_depth = level.depth;
+            assert_eq!(summary.level(level), Some(level_summary));
+            assert!(summary.levels()[..idx]
+                .iter()
+                .all(|other| other.level() != level));
+
+            let instances = topology
+                .objects()
+                .filter_map(|obj| {
+                    CacheLevel::of_object(obj).map(|(level, attr)| (obj, level, attr))
+                })
+                .filter(|(_, other_level, _)| *other_level == level)
+                .collect::<Vec<_>>();
+            assert_eq!(level_summary.num_instances(), instances.len());
+            let smallest_size = instances.iter().map(|(_, _, attr)| attr.size()).min();
+            assert_eq!(Some(level_summary.size()), smallest_size);
+            assert!(instances.iter().any(|(obj, _, attr)| {
+                attr.size() == level_summary.size()
+                    && attr.line_size() == level_summary.line_size()
+                    && attr.associativity() == level_summary.associativity()
+                    && obj.cpuset().unwrap().weight() == Some(level_summary.sharing_degree())
+            }));
+            num_levels += 1;
+        }
+
+        let mut all_levels = topology
+            .objects()
+            .filter_map(|obj| CacheLevel::of_object(obj).map(|(level, _)| level))
+            .collect::<Vec<_>>();
+        all_levels.sort_by_key(|level| (level.depth, level.cache_type == CacheType::Instruction));
+        all_levels.dedup();
+        assert_eq!(num_levels, all_levels.len());
+    }
+
     #[test]
     fn stats() {
         let topology = Topology::test_instance();
@@ -11,10 +11,11 @@ use crate::errors;
 #[cfg(doc)]
 use crate::{bitmap::Bitmap, topology::support::DiscoverySupport};
 use crate::{
+    errors::ObjectWithoutSetsError,
     impl_bitmap_newtype,
     memory::nodeset::NodeSet,
     object::{
-        depth::{Depth, NormalDepth},
+        depth::{Depth, NormalDepth, TypeToDepthError},
         types::ObjectType,
         TopologyObject,
     },
@@ -48,8 +49,13 @@ impl Topology {
     /// iteration can be more efficiently performed by using
     /// [`coarsest_cpuset_partition()`].
     ///
+    /// If `set` is not a subset of the root cpuset, the parts of `set` that lie
+    /// outside of the topology are silently ignored. Use
+    /// [`coarsest_cpuset_partition()`] instead if you would rather get a
+    /// [`CoarsestPartitionError`] in this case.
+    ///
     /// [`coarsest_cpuset_partition()`]: Topology::coarsest_cpuset_partition()
-    #[doc(alias = "hwloc_get_first_largest_obj_inside_cpuset")]
+    #[doc(alias = "hwloc_get_largest_objs_inside_cpuset")]
     pub fn largest_objects_inside_cpuset(
         &self,
         set: CpuSet,
@@ -140,6 +146,127 @@ impl Topology {
         polymorphized(self, &set)
     }
 
+    /// Decompose the given cpuset `set` into a minimal number of objects of
+    /// the `allowed_types`
+    ///
+    /// `set` can be a `&'_ CpuSet` or a `BitmapRef<'_, CpuSet>`.
+    ///
+    /// This returns the smallest collection of objects of the allowed types
+    /// whose cpusets are included in `set` and do not overlap, along with the
+    /// leftover part of `set` which cannot be covered by such objects. This
+    /// is useful for reporting things like "this job uses 2 full L3 domains
+    /// plus 3 stray cores" by calling this method with `allowed_types` set to
+    /// `&[ObjectType::L3Cache, ObjectType::Core]`.
+    ///
+    /// If `allowed_types` contains [`ObjectType::PU`], then the leftover part
+    /// of `set` is the part that lies outside of the topology's cpuset.
+    ///
+    /// Objects with empty CPU sets are ignored (otherwise they would be
+    /// considered included in any given set). Therefore, I/O and Misc objects
+    /// will never be part of the output, as they don't have cpusets.
+    pub fn minimal_cover(
+        &self,
+        set: impl Deref<Target = CpuSet>,
+        allowed_types: &[ObjectType],
+    ) -> MinimalCover<'_> {
+        /// Polymorphized version of this function (avoids generics code bloat)
+        fn polymorphized<'self_>(
+            self_: &'self_ Topology,
+            set: &CpuSet,
+            allowed_types: &[ObjectType],
+        ) -> MinimalCover<'self_> {
+            /// Recursive implementation of the decomposition algorithm
+            fn process_object<'a>(
+                parent: &'a TopologyObject,
+                set: &CpuSet,
+                allowed_types: &[ObjectType],
+                result: &mut MinimalCover<'a>,
+            ) {
+                // Ignore objects that don't intersect the target cpuset
+                let parent_cpuset = parent
+                    .cpuset()
+                    .expect("normal objects should have a cpuset");
+                if !parent_cpuset.intersects(set) {
+                    return;
+                }
+
+                // Take the first object of an allowed type that we encounter
+                // on our way down: since topology object cpusets are either
+                // disjoint or nested, this yields a minimal cover.
+                if parent.is_inside_cpuset(set) && allowed_types.contains(&parent.object_type()) {
+                    result.leftover -= parent_cpuset;
+                    result.objects.push(parent);
+                    return;
+                }
+
+                // Otherwise, look for children that can cover part of the set
+                for child in parent.normal_children() {
+                    process_object(child, set, allowed_types, result);
+                }
+            }
+            let mut result = MinimalCover {
+                objects: Vec::new(),
+                leftover: set.clone(),
+            };
+            process_object(self_.root_object(), set, allowed_types, &mut result);
+            result
+        }
+        polymorphized(self, &set, allowed_types)
+    }
+
+    /// Decompose the given cpuset `set` into objects at a certain depth
+    ///
+    /// Accepted operand types are as follows:
+    ///
+    /// - `set` can be a `&'_ CpuSet` or a `BitmapRef<'_, CpuSet>`
+    /// - `depth` can be a [`Depth`], a [`NormalDepth`] or an [`usize`]
+    ///
+    /// This returns all objects at depth `depth` whose cpusets are included in
+    /// `set`, in logical order, along with the leftover part of `set` which is
+    /// not covered by these objects. This is useful for reporting things like
+    /// "cores 0-3 of package 1" or for partitioning work hierarchically, one
+    /// topology level at a time. Use [`minimal_cover()`] if you would rather
+    /// decompose `set` into objects of several types.
+    ///
+    /// Objects with empty CPU sets are ignored (otherwise they would be
+    /// considered included in any given set). Therefore, no object will ever be
+    /// returned for I/O or Misc depths as those objects have no cpusets, and
+    /// the leftover will then be all of `set`.
+    ///
+    /// [`minimal_cover()`]: Topology::minimal_cover()
+    pub fn decompose_cpuset_at_depth<DepthLike>(
+        &self,
+        set: impl Deref<Target = CpuSet>,
+        depth: DepthLike,
+    ) -> MinimalCover<'_>
+    where
+        DepthLike: TryInto<Depth>,
+        <DepthLike as TryInto<Depth>>::Error: Debug,
+    {
+        /// Polymorphized version of this function (avoids generics code bloat)
+        fn polymorphized<'self_>(
+            self_: &'self_ Topology,
+            set: &CpuSet,
+            depth: Depth,
+        ) -> MinimalCover<'self_> {
+            let mut leftover = set.clone();
+            let objects = self_
+                .objects_at_depth(depth)
+                .filter(|obj| obj.is_inside_cpuset(set))
+                .inspect(|obj| {
+                    leftover -= obj
+                        .cpuset()
+                        .expect("objects inside a cpuset should have a cpuset");
+                })
+                .collect();
+            MinimalCover { objects, leftover }
+        }
+        // There is never any object at depth NormalDepth::MAX, see
+        // objects_inside_cpuset_at_depth() for more info on this hack.
+        let depth = depth.try_into().unwrap_or(Depth::Normal(NormalDepth::MAX));
+        polymorphized(self, &set, depth)
+    }
+
     /// Enumerate objects included in the given cpuset `set` at a certain depth
     ///
     /// Accepted operand types are as follows:
@@ -152,7 +279,6 @@ impl Topology {
     /// always be returned for I/O or Misc depths as those objects have no cpusets.
     #[doc(alias = "hwloc_get_obj_inside_cpuset_by_depth")]
     #[doc(alias = "hwloc_get_next_obj_inside_cpuset_by_depth")]
-    #[doc(alias = "hwloc_get_nbobjs_inside_cpuset_by_depth")]
     pub fn objects_inside_cpuset_at_depth<'result, DepthLike>(
         &'result self,
         set: impl Deref<Target = CpuSet> + 'result,
@@ -203,14 +329,17 @@ impl Topology {
 
     /// Get objects included in the given cpuset `set` with a certain type
     ///
+    /// Objects are yielded by increasing depth, then by logical index, which
+    /// only matters for types like [`Group`] that may exist at several depths.
+    ///
     /// `set` can be a `&'_ CpuSet` or a `BitmapRef<'_, CpuSet>`.
     ///
     /// Objects with empty CPU sets are ignored (otherwise they would be
     /// considered included in any given set). Therefore, an empty iterator will
     /// always be returned for I/O or Misc objects as they don't have cpusets.
-    #[doc(alias = "hwloc_get_obj_inside_cpuset_by_type")]
+    ///
+    /// [`Group`]: ObjectType::Group
     #[doc(alias = "hwloc_get_next_obj_inside_cpuset_by_type")]
-    #[doc(alias = "hwloc_get_nbobjs_inside_cpuset_by_type")]
     pub fn objects_inside_cpuset_with_type<'result>(
         &'result self,
         set: impl Deref<Target = CpuSet> + 'result,
@@ -222,27 +351,177 @@ impl Topology {
         })
     }
 
+    /// Number of objects included in the given cpuset `set` at a certain depth
+    ///
+    /// Accepted operand types are as follows:
+    ///
+    /// - `set` can be a `&'_ CpuSet` or a `BitmapRef<'_, CpuSet>`
+    /// - `depth` can be a [`Depth`], a [`NormalDepth`] or an [`usize`]
+    ///
+    /// This is equivalent to counting the output of
+    /// [`objects_inside_cpuset_at_depth()`], but at normal depths it only
+    /// visits the parts of the topology tree that intersect `set` instead of
+    /// checking every object at the target depth.
+    ///
+    /// Objects with empty CPU sets are ignored (otherwise they would be
+    /// considered included in any given set). Therefore, 0 will always be
+    /// returned for I/O or Misc depths as those objects have no cpusets.
+    ///
+    /// [`objects_inside_cpuset_at_depth()`]: Topology::objects_inside_cpuset_at_depth()
+    #[doc(alias = "hwloc_get_nbobjs_inside_cpuset_by_depth")]
+    pub fn num_objects_inside_cpuset_at_depth<DepthLike>(
+        &self,
+        set: impl Deref<Target = CpuSet>,
+        depth: DepthLike,
+    ) -> usize
+    where
+        DepthLike: TryInto<Depth>,
+        <DepthLike as TryInto<Depth>>::Error: Debug,
+    {
+        /// Polymorphized version of this function (avoids generics code bloat)
+        fn polymorphized(self_: &Topology, set: &CpuSet, depth: Depth) -> usize {
+            // Objects outside of the normal hierarchy must be checked one by one
+            let Depth::Normal(normal_depth) = depth else {
+                return self_
+                    .objects_at_depth(depth)
+                    .filter(|object| object.is_inside_cpuset(set))
+                    .count();
+            };
+
+            /// Recursive implementation of the counting algorithm
+            fn count_objects(parent: &TopologyObject, set: &CpuSet, depth: NormalDepth) -> usize {
+                let parent_cpuset = parent
+                    .cpuset()
+                    .expect("normal objects should have a cpuset");
+                if !parent_cpuset.intersects(set) {
+                    return 0;
+                }
+                if parent.depth() == depth {
+                    return usize::from(parent.is_inside_cpuset(set));
+                }
+                parent
+                    .normal_children()
+                    .map(|child| count_objects(child, set, depth))
+                    .sum()
+            }
+            count_objects(self_.root_object(), set, normal_depth)
+        }
+        let Ok(depth) = depth.try_into() else {
+            return 0;
+        };
+        polymorphized(self, &set, depth)
+    }
+
+    /// Number of objects included in the given cpuset `set` with a certain type
+    ///
+    /// `set` can be a `&'_ CpuSet` or a `BitmapRef<'_, CpuSet>`.
+    ///
+    /// This is equivalent to counting the output of
+    /// [`objects_inside_cpuset_with_type()`], but is more efficient when
+    /// objects of type `object_type` all live at the same normal depth.
+    ///
+    /// Objects with empty CPU sets are ignored (otherwise they would be
+    /// considered included in any given set). Therefore, 0 will always be
+    /// returned for I/O or Misc objects as they don't have cpusets.
+    ///
+    /// [`objects_inside_cpuset_with_type()`]: Topology::objects_inside_cpuset_with_type()
+    #[doc(alias = "hwloc_get_nbobjs_inside_cpuset_by_type")]
+    pub fn num_objects_inside_cpuset_with_type(
+        &self,
+        set: impl Deref<Target = CpuSet>,
+        object_type: ObjectType,
+    ) -> usize {
+        /// Polymorphized version of this function (avoids generics code bloat)
+        fn polymorphized(self_: &Topology, set: &CpuSet, object_type: ObjectType) -> usize {
+            match self_.depth_for_type(object_type) {
+                Ok(depth) => self_.num_objects_inside_cpuset_at_depth(set, depth),
+                Err(TypeToDepthError::Nonexistent) => 0,
+                Err(_) => self_
+                    .objects_with_type(object_type)
+                    .filter(|object| object.is_inside_cpuset(set))
+                    .count(),
+            }
+        }
+        polymorphized(self, &set, object_type)
+    }
+
+    /// Object of type `object_type` with logical index `n` among the objects
+    /// included in CPU set `set`
+    ///
+    /// This is the inverse of [`object_index_inside_cpuset()`]: if `set`
+    /// covers the entire topology, this is the object of type `object_type`
+    /// with logical index `n`. Otherwise, `n` is interpreted as a logical index
+    /// within the part of the topology defined by CPU set `set`, which is
+    /// convenient when e.g. mapping process ranks to PUs within the CPU set
+    /// that a job is allowed to run on.
+    ///
+    /// Objects of some types, like [`Group`], may exist at several depths of
+    /// the topology. Unlike hwloc, which returns no object in this case, this
+    /// method then enumerates the objects inside of `set` depth by depth, from
+    /// the top of the topology to the bottom, and in logical index order within
+    /// each depth, as [`objects_inside_cpuset_with_type()`] does. `n` is then
+    /// an index into this sequence, which is not the inverse of
+    /// [`object_index_inside_cpuset()`] anymore since the latter only counts
+    /// objects at the depth of the object of interest.
+    ///
+    /// `set` can be a `&'_ CpuSet` or a `BitmapRef<'_, CpuSet>`.
+    ///
+    /// Objects with empty CPU sets are ignored (otherwise they would be
+    /// considered included in any given set). Therefore, `None` will always be
+    /// returned for I/O or Misc objects as they don't have cpusets. `None` will
+    /// also be returned if there are `n` or less objects of type `object_type`
+    /// inside of `set`.
+    ///
+    /// [`Group`]: ObjectType::Group
+    /// [`object_index_inside_cpuset()`]: Topology::object_index_inside_cpuset()
+    /// [`objects_inside_cpuset_with_type()`]: Topology::objects_inside_cpuset_with_type()
+    #[doc(alias = "hwloc_get_obj_inside_cpuset_by_type")]
+    pub fn nth_object_inside_cpuset(
+        &self,
+        set: impl Deref<Target = CpuSet>,
+        object_type: ObjectType,
+        n: usize,
+    ) -> Option<&TopologyObject> {
+        /// Polymorphized version of this function (avoids generics code bloat)
+        fn polymorphized<'self_>(
+            self_: &'self_ Topology,
+            set: &CpuSet,
+            object_type: ObjectType,
+            n: usize,
+        ) -> Option<&'self_ TopologyObject> {
+            self_
+                .objects_with_type(object_type)
+                .filter(|object| object.is_inside_cpuset(set))
+                .nth(n)
+        }
+        polymorphized(self, &set, object_type, n)
+    }
+
     /// First largest object included in the given cpuset `set`
     ///
     /// Returns the first object that is included in `set` and whose parent is
     /// not, in descending depth and children iteration order.
     ///
-    /// This is convenient for iterating over all largest objects within a CPU
-    /// set by doing a loop getting the first largest object and clearing its
-    /// CPU set from the remaining CPU set. This very pattern is exposed by
-    /// the `largest_objects_inside_cpuset` method, which is why this method is
-    /// not publicly exposed.
+    /// This is convenient for greedy algorithms that repeatedly carve the
+    /// largest available chunk out of a remaining CPU set, by getting the first
+    /// largest object and clearing its CPU set from the remaining CPU set. If
+    /// you only want to iterate over all largest objects within a CPU set, the
+    /// [`largest_objects_inside_cpuset()`] method implements this very pattern.
     ///
-    /// That being said, if the cpuset is a strict subset of the root cpuset of
-    /// this `Topology`, the work may be more efficiently done by
-    /// `largest_cpuset_partition()`, which only needs to walk the topology
+    /// If the cpuset is a subset of the root cpuset of this `Topology`, the
+    /// full decomposition may be more efficiently computed by
+    /// [`coarsest_cpuset_partition()`], which only needs to walk the topology
     /// tree once.
     ///
     /// `set` can be a `&'_ CpuSet` or a `BitmapRef<'_, CpuSet>`.
     ///
     /// Objects with empty CPU sets are ignored (otherwise they would be
     /// considered included in any given set).
-    fn first_largest_object_inside_cpuset(
+    ///
+    /// [`coarsest_cpuset_partition()`]: Topology::coarsest_cpuset_partition()
+    /// [`largest_objects_inside_cpuset()`]: Topology::largest_objects_inside_cpuset()
+    #[doc(alias = "hwloc_get_first_largest_obj_inside_cpuset")]
+    pub fn first_largest_object_inside_cpuset(
         &self,
         set: impl Deref<Target = CpuSet>,
     ) -> Option<&TopologyObject> {
@@ -315,6 +594,21 @@ impl<'topology> Iterator for LargestObjectsInsideCpuSet<'topology> {
 //
 impl FusedIterator for LargestObjectsInsideCpuSet<'_> {}
 
+/// Decomposition of a cpuset emitted by [`Topology::minimal_cover()`] and
+/// [`Topology::decompose_cpuset_at_depth()`]
+#[derive(Clone, Debug, Default)]
+pub struct MinimalCover<'topology> {
+    /// Objects of the allowed types or depth whose cpusets partition the input
+    /// cpuset, minus the [`leftover`](Self::leftover) part
+    ///
+    /// Objects are listed in depth-first order.
+    pub objects: Vec<&'topology TopologyObject>,
+
+    /// Part of the input cpuset that could not be covered by objects of the
+    /// allowed types or depth
+    pub leftover: CpuSet,
+}
+
 /// [`Topology::coarsest_cpuset_partition()`] was called for an invalid `cpuset`
 ///
 /// This error is returned when the input `cpuset` is not a subset of the root
@@ -346,6 +640,9 @@ impl Topology {
     /// No object is considered to cover the empty cpuset, therefore such a
     /// request will always return None, as if a set going outside of the root
     /// cpuset were passed as input.
+    ///
+    /// This is a shorthand for calling [`TopologyObject::descend_covering()`]
+    /// on the [root object](Topology::root_object()).
     #[doc(alias = "hwloc_get_obj_covering_cpuset")]
     pub fn smallest_object_covering_cpuset(
         &self,
@@ -356,15 +653,7 @@ impl Topology {
             self_: &'self_ Topology,
             set: &CpuSet,
         ) -> Option<&'self_ TopologyObject> {
-            let root = self_.root_object();
-            if !root.covers_cpuset(set) || set.is_empty() {
-                return None;
-            }
-            let mut parent = root;
-            while let Some(child) = parent.normal_child_covering_cpuset(set) {
-                parent = child;
-            }
-            Some(parent)
+            self_.root_object().descend_covering(set)
         }
         polymorphized(self, &set)
     }
@@ -507,6 +796,46 @@ impl CpuSet {
         }
         polymorphized(topology, &nodeset)
     }
+    /// Union of the cpusets of some topology objects
+    ///
+    /// This is the fallible version of the [`FromIterator`] implementation
+    /// that collects topology objects into a `CpuSet`, which panics when one of
+    /// the objects does not have a cpuset.
+    ///
+    /// # Errors
+    ///
+    /// - [`ObjectWithoutSetsError`] if one of the `objects` does not have a
+    ///   cpuset, which is the case of I/O and Misc objects
+    pub fn try_from_objects<'topology>(
+        objects: impl IntoIterator<Item = &'topology TopologyObject>,
+    ) -> Result<Self, ObjectWithoutSetsError> {
+        let mut result = Self::new();
+        result.try_extend_from_objects(objects)?;
+        Ok(result)
+    }
+
+    /// Add the cpusets of some topology objects to this `CpuSet`
+    ///
+    /// This is the fallible version of the [`Extend`] implementation that
+    /// adds topology objects to a `CpuSet`. If an error occurs, the cpusets
+    /// of the objects that were processed before the failing one have already
+    /// been added to this `CpuSet`.
+    ///
+    /// # Errors
+    ///
+    /// - [`ObjectWithoutSetsError`] if one of the `objects` does not have a
+    ///   cpuset, which is the case of I/O and Misc objects
+    pub fn try_extend_from_objects<'topology>(
+        &mut self,
+        objects: impl IntoIterator<Item = &'topology TopologyObject>,
+    ) -> Result<(), ObjectWithoutSetsError> {
+        for obj in objects {
+            *self |= obj
+                .cpuset()
+                .ok_or_else(|| ObjectWithoutSetsError::from(obj))?;
+        }
+        Ok(())
+    }
 }
 
 impl_bitmap_newtype!(
@@ -525,6 +854,36 @@ impl_bitmap_newtype!(
     CpuSet
 );
 
+/// Collect topology objects into the union of their cpusets
+///
+/// # Panics
+///
+/// If one of the objects does not have a cpuset, which is the case of I/O
+/// and Misc objects. Use [`CpuSet::try_from_objects()`] if you need to handle
+/// this situation.
+impl<'topology> FromIterator<&'topology TopologyObject> for CpuSet {
+    fn from_iter<I: IntoIterator<Item = &'topology TopologyObject>>(iter: I) -> Self {
+        let mut result = Self::new();
+        result.extend(iter);
+        result
+    }
+}
+
+/// Add the cpusets of topology objects to a `CpuSet`
+///
+/// # Panics
+///
+/// If one of the objects does not have a cpuset, which is the case of I/O
+/// and Misc objects. Use [`CpuSet::try_extend_from_objects()`] if you need to
+/// handle this situation.
+impl<'topology> Extend<&'topology TopologyObject> for CpuSet {
+    fn extend<I: IntoIterator<Item = &'topology TopologyObject>>(&mut self, iter: I) {
+        if let Err(e) = self.try_extend_from_objects(iter) {
+            panic!("can't collect objects into a CpuSet: {e}")
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -537,8 +896,72 @@ mod tests {
         strategies::topology_related_set,
     };
     use proptest::prelude::*;
+    #[allow(unused)]
+    use similar_asserts::assert_eq;
     use std::collections::HashSet;
 
+    /// Check that [`Topology::nth_object_inside_cpuset()`] enumerates objects
+    /// of a type that exists at several depths by depth, then logical index
+    #[test]
+    fn nth_object_inside_cpuset_multi_depth() {
+        use crate::topology::builder::TypeFilter;
+
+        let topology = Topology::builder()
+            .from_synthetic("Group:2 Group:2 PU:2")
+            .unwrap()
+            .with_type_filter(ObjectType::Group, TypeFilter::KeepAll)
+            .unwrap()
+            .build()
+            .unwrap();
+        let groups = topology
+            .objects_with_type(ObjectType::Group)
+            .collect::<Vec<_>>();
+        assert_eq!(groups.len(), 6);
+        for (n, &group) in groups.iter().enumerate() {
+            let nth = topology
+                .nth_object_inside_cpuset(topology.cpuset(), ObjectType::Group, n)
+                .unwrap();
+            assert!(std::ptr::eq(nth, group));
+            let expected_depth = if n < 2 { 1 } else { 2 };
+            assert_eq!(nth.depth(), expected_depth);
+            assert_eq!(nth.logical_index(), if n < 2 { n } else { n - 2 });
+        }
+        assert!(topology
+            .nth_object_inside_cpuset(topology.cpuset(), ObjectType::Group, 6)
+            .is_none());
+    }
+
+    proptest! {
+        /// Test for collecting topology objects into a [`CpuSet`]
+        #[test]
+        fn cpuset_from_objects(
+            objects in prop::sample::subsequence(
+                Topology::test_objects().to_vec(),
+                0..=Topology::test_objects().len()
+            )
+        ) {
+            let result = CpuSet::try_from_objects(objects.iter().copied());
+            if objects.iter().all(|obj| obj.cpuset().is_some()) {
+                let expected = objects
+                    .iter()
+                    .map(|obj| obj.cpuset().unwrap().clone_target())
+                    .reduce(|set1, set2| set1 | set2)
+                    .unwrap_or_default();
+                prop_assert_eq!(result.unwrap(), &expected);
+                prop_assert_eq!(objects.iter().copied().collect::<CpuSet>(), &expected);
+                let mut extended = CpuSet::new();
+                extended.extend(objects.iter().copied());
+                prop_assert_eq!(extended, expected);
+            } else {
+                let first_without_set = objects.iter().find(|obj| obj.cpuset().is_none()).unwrap();
+                prop_assert_eq!(
+                    result.unwrap_err(),
+                    ObjectWithoutSetsError::from(*first_without_set)
+                );
+            }
+        }
+    }
+
     proptest! {
         /// Test for [`Topology::largest_objects_inside_cpuset()`]
         #[test]
@@ -573,6 +996,85 @@ mod tests {
             prop_assert!(result.next().is_none());
         }
 
+        /// Test for [`Topology::decompose_cpuset_at_depth()`]
+        #[test]
+        fn decompose_cpuset_at_depth(
+            set in topology_related_set(Topology::cpuset),
+            depth in any_hwloc_depth(),
+        ) {
+            let topology = Topology::test_instance();
+            let result = topology.decompose_cpuset_at_depth(&set, depth);
+            compare_object_sets(
+                result.objects.iter().copied(),
+                topology.objects_inside_cpuset_at_depth(&set, depth),
+            )?;
+            let covered = result.objects.iter().copied().collect::<CpuSet>();
+            prop_assert!(!covered.intersects(&result.leftover));
+            prop_assert_eq!(covered | &result.leftover, set);
+        }
+
+        /// Test for [`Topology::first_largest_object_inside_cpuset()`]
+        #[test]
+        fn first_largest_object_inside_cpuset(set in topology_related_set(Topology::cpuset)) {
+            let topology = Topology::test_instance();
+            let result = topology.first_largest_object_inside_cpuset(&set);
+
+            // The first largest object is the first object, in depth-first
+            // order, whose cpuset is included in the input set
+            fn first_inside<'a>(
+                obj: &'a TopologyObject,
+                set: &CpuSet
+            ) -> Option<&'a TopologyObject> {
+                if obj.is_inside_cpuset(set) {
+                    return Some(obj);
+                }
+                obj.normal_children().find_map(|child| first_inside(child, set))
+            }
+            let expected = first_inside(topology.root_object(), &set);
+            prop_assert_eq!(
+                result.map(TopologyObject::global_persistent_index),
+                expected.map(TopologyObject::global_persistent_index)
+            );
+        }
+
+        /// Test for [`Topology::minimal_cover()`]
+        #[test]
+        fn minimal_cover(
+            set in topology_related_set(Topology::cpuset),
+            allowed_types in prop::sample::subsequence(
+                enum_iterator::all::<ObjectType>().collect::<Vec<_>>(),
+                0..=enum_iterator::cardinality::<ObjectType>(),
+            ),
+        ) {
+            let topology = Topology::test_instance();
+            let result = topology.minimal_cover(&set, &allowed_types);
+
+            // Objects should be of the allowed types, inside of the input set,
+            // and should not overlap with each other or with the leftover set
+            let mut covered = CpuSet::new();
+            for obj in &result.objects {
+                prop_assert!(allowed_types.contains(&obj.object_type()));
+                prop_assert!(obj.is_inside_cpuset(&set));
+                let obj_cpuset = obj.cpuset().unwrap();
+                prop_assert!(!covered.intersects(obj_cpuset));
+                covered |= obj_cpuset;
+            }
+            prop_assert!(!covered.intersects(&result.leftover));
+            prop_assert_eq!(&covered | &result.leftover, set.clone());
+
+            // The cover should be minimal, i.e. every object of an allowed
+            // type inside of the input set is inside of an output object
+            for obj in topology.objects() {
+                if allowed_types.contains(&obj.object_type()) && obj.is_inside_cpuset(&set) {
+                    let is_covered = result
+                        .objects
+                        .iter()
+                        .any(|&cover| ptr::eq(cover, obj) || obj.is_in_subtree(cover));
+                    prop_assert!(is_covered);
+                }
+            }
+        }
+
         /// Test for [`Topology::coarsest_cpuset_partition()`]
         #[test]
         fn coarsest_cpuset_partition(set in topology_related_set(Topology::cpuset)) {
@@ -628,6 +1130,43 @@ mod tests {
             );
         }
 
+        /// Test for [`Topology::num_objects_inside_cpuset_with_type()`]
+        #[test]
+        fn num_objects_inside_cpuset_with_type(
+            set in topology_related_set(Topology::cpuset),
+            object_type: ObjectType,
+        ) {
+            let topology = Topology::test_instance();
+            prop_assert_eq!(
+                topology.num_objects_inside_cpuset_with_type(&set, object_type),
+                topology
+                    .objects_with_type(object_type)
+                    .filter(|obj| obj.is_inside_cpuset(&set))
+                    .count()
+            );
+        }
+
+        /// Test for [`Topology::nth_object_inside_cpuset()`]
+        #[test]
+        fn nth_object_inside_cpuset(
+            set in topology_related_set(Topology::cpuset),
+            object_type: ObjectType,
+            n in 0usize..8,
+        ) {
+            let topology = Topology::test_instance();
+            let result = topology.nth_object_inside_cpuset(&set, object_type, n);
+            let expected = topology.objects_inside_cpuset_with_type(&set, object_type).nth(n);
+            prop_assert_eq!(
+                result.map(TopologyObject::global_persistent_index),
+                expected.map(TopologyObject::global_persistent_index)
+            );
+            // Objects of a type that spans several depths (e.g. Group) are
+            // indexed per-depth by object_index_inside_cpuset()
+            if let (Some(obj), Ok(_)) = (result, topology.depth_for_type(object_type)) {
+                prop_assert_eq!(topology.object_index_inside_cpuset(&set, obj), Some(n));
+            }
+        }
+
         /// Test for [`CpuSet::from_nodeset()`]
         #[test]
         fn cpuset_from_nodeset(
@@ -758,7 +1297,8 @@ mod tests {
 
     // === Test methods that need a (set, depth) tuple ===
 
-    /// Test for [`Topology::objects_inside_cpuset_at_depth()`]
+    /// Test for [`Topology::objects_inside_cpuset_at_depth()`] and
+    /// [`Topology::num_objects_inside_cpuset_at_depth()`]
     fn check_objects_inside_cpuset_at_depth<DepthLike>(
         set: &CpuSet,
         depth: DepthLike,
@@ -769,11 +1309,18 @@ mod tests {
         <DepthLike as TryInto<Depth>>::Error: Debug,
     {
         let topology = Topology::test_instance();
-        compare_object_sets(
-            topology.objects_inside_cpuset_at_depth(set, depth),
+        let expected = || {
             topology
                 .objects_at_depth(depth)
-                .filter(|obj| obj.is_inside_cpuset(set)),
+                .filter(|obj| obj.is_inside_cpuset(set))
+        };
+        prop_assert_eq!(
+            topology.num_objects_inside_cpuset_at_depth(set, depth),
+            expected().count()
+        );
+        compare_object_sets(
+            topology.objects_inside_cpuset_at_depth(set, depth),
+            expected(),
         )
     }
 
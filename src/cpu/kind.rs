@@ -19,7 +19,10 @@ use crate::{
     errors::{self},
     ffi::{int, string::LibcString, transparent::AsNewtype},
     info::TextualInfo,
-    topology::{editor::TopologyEditor, Topology},
+    topology::{
+        editor::{TopologyChanges, TopologyEditor},
+        Topology,
+    },
 };
 use derive_more::Display;
 use hwlocality_sys::hwloc_info_s;
@@ -245,6 +248,64 @@ impl Topology {
         }
         polymorphized(self, &set)
     }
+
+    /// Enumerate CPU kinds along with their [`CoreClass`]
+    ///
+    /// CPU kinds are yielded in the same order as [`cpu_kinds()`]. See the
+    /// [`CoreClass`] documentation for a description of how each kind is
+    /// classified.
+    ///
+    /// # Errors
+    ///
+    /// - [`NoData`] if no information about CPU kinds was found
+    ///
+    /// [`cpu_kinds()`]: Topology::cpu_kinds()
+    pub fn cpu_kinds_with_class(&self) -> Result<Vec<(CpuKind<'_>, CoreClass)>, NoData> {
+        let kinds = self.cpu_kinds()?.collect::<Vec<_>>();
+        let classes = CoreClass::classify(&kinds);
+        Ok(kinds.into_iter().zip(classes).collect())
+    }
+
+    /// CPUs that belong to [`Performance`] cores
+    ///
+    /// On homogeneous platforms, and on platforms where no information about
+    /// CPU kinds is available, all CPUs of the topology are considered to be
+    /// performance cores. CPUs whose [`CoreClass`] is [`Unknown`] are neither
+    /// reported here nor by [`efficiency_cores()`].
+    ///
+    /// [`efficiency_cores()`]: Topology::efficiency_cores()
+    /// [`Performance`]: CoreClass::Performance
+    /// [`Unknown`]: CoreClass::Unknown
+    pub fn performance_cores(&self) -> CpuSet {
+        self.cpu_kinds_with_class().map_or_else(
+            |NoData| self.cpuset().clone_target(),
+            |kinds| cpuset_of_class(kinds, CoreClass::Performance),
+        )
+    }
+
+    /// CPUs that belong to [`Efficiency`] cores
+    ///
+    /// This is empty on homogeneous platforms, and on platforms where no
+    /// information about CPU kinds is available.
+    ///
+    /// [`Efficiency`]: CoreClass::Efficiency
+    pub fn efficiency_cores(&self) -> CpuSet {
+        self.cpu_kinds_with_class().map_or_else(
+            |NoData| CpuSet::new(),
+            |kinds| cpuset_of_class(kinds, CoreClass::Efficiency),
+        )
+    }
+}
+
+/// Union of the CPU sets of all CPU kinds of a certain class
+fn cpuset_of_class(kinds: Vec<(CpuKind<'_>, CoreClass)>, class: CoreClass) -> CpuSet {
+    let mut result = CpuSet::new();
+    for (kind, kind_class) in kinds {
+        if kind_class == class {
+            result |= kind.cpuset;
+        }
+    }
+    result
 }
 
 /// Kind of CPU core
@@ -262,6 +323,98 @@ pub struct CpuKind<'topology> {
     /// Textual information
     pub infos: &'topology [TextualInfo],
 }
+//
+impl<'topology> CpuKind<'topology> {
+    /// Value of the textual info with a certain name, if any
+    fn info_value(&self, name: &str) -> Option<&'topology str> {
+        self.infos
+            .iter()
+            .find(|info| info.name_bytes() == name.as_bytes())
+            .and_then(|info| info.value_str().ok())
+    }
+}
+
+/// Coarse classification of a CPU kind on hybrid platforms
+///
+/// Hybrid-aware schedulers often only need to know which cores are meant for
+/// performance-sensitive work and which ones are meant for background work.
+/// This classification is derived from the information that hwloc provides
+/// about each CPU kind, using the following rules in order:
+///
+/// 1. If there is a single CPU kind, the platform is homogeneous and all of
+///    its cores are considered to be [`Performance`] cores.
+/// 2. If the `CoreType` info is available, `IntelCore` kinds are
+///    [`Performance`] cores and `IntelAtom` kinds are [`Efficiency`] cores.
+/// 3. If CPU kinds were ranked by [efficiency](CpuKind::efficiency), the
+///    least efficient kinds are [`Efficiency`] cores and all other kinds are
+///    [`Performance`] cores.
+/// 4. If the `FrequencyMaxMHz` info is available, the kinds with the highest
+///    maximal frequency are [`Performance`] cores and the other kinds are
+///    [`Efficiency`] cores.
+/// 5. Otherwise, the kind is [`Unknown`].
+///
+/// [`Efficiency`]: CoreClass::Efficiency
+/// [`Performance`]: CoreClass::Performance
+/// [`Unknown`]: CoreClass::Unknown
+#[derive(Copy, Clone, Debug, Display, Eq, Hash, PartialEq)]
+pub enum CoreClass {
+    /// High-performance cores, e.g. Intel P-cores or ARM "big" cores
+    Performance,
+
+    /// Energy-efficient cores, e.g. Intel E-cores or ARM "LITTLE" cores
+    Efficiency,
+
+    /// Not enough information is available to classify this kind of core
+    Unknown,
+}
+//
+impl CoreClass {
+    /// Classify a set of CPU kinds, which should cover the whole topology
+    fn classify(kinds: &[CpuKind<'_>]) -> Vec<Self> {
+        if kinds.len() == 1 {
+            return vec![Self::Performance];
+        }
+        let min_efficiency = kinds.iter().filter_map(|kind| kind.efficiency).min();
+        let max_efficiency = kinds.iter().filter_map(|kind| kind.efficiency).max();
+        let max_frequency = |kind: &CpuKind<'_>| {
+            kind.info_value("FrequencyMaxMHz")
+                .and_then(|freq| freq.trim().parse::<u64>().ok())
+        };
+        let max_frequencies = kinds.iter().map(max_frequency).collect::<Vec<_>>();
+        let highest_frequency = max_frequencies.iter().flatten().max().copied();
+        let lowest_frequency = max_frequencies.iter().flatten().min().copied();
+        kinds
+            .iter()
+            .zip(max_frequencies.iter().copied())
+            .map(|(kind, frequency)| {
+                match kind.info_value("CoreType") {
+                    Some("IntelCore") => return Self::Performance,
+                    Some("IntelAtom") => return Self::Efficiency,
+                    _ => {}
+                }
+                if let Some(efficiency) = kind.efficiency {
+                    if min_efficiency != max_efficiency {
+                        return if Some(efficiency) == min_efficiency {
+                            Self::Efficiency
+                        } else {
+                            Self::Performance
+                        };
+                    }
+                }
+                if let Some(frequency) = frequency {
+                    if lowest_frequency != highest_frequency {
+                        return if Some(frequency) == highest_frequency {
+                            Self::Performance
+                        } else {
+                            Self::Efficiency
+                        };
+                    }
+                }
+                Self::Unknown
+            })
+            .collect()
+    }
+}
 
 /// # Kinds of CPU cores
 //
@@ -334,6 +487,7 @@ impl TopologyEditor<'_> {
             let infos_ptr = raw_infos.as_ptr();
             let num_infos =
                 c_uint::try_from(raw_infos.len()).map_err(|_| RegisterError::TooManyInfos)?;
+            self_.record_changes(TopologyChanges::CPU_KINDS);
 
             // SAFETY: - Topology is trusted to contain a valid ptr (type invariant)
             //         - Bitmap is trusted to contain a valid ptr (type invariant)
@@ -453,3 +607,93 @@ impl From<CpuEfficiency> for RegisterError {
         Self::ExcessiveEfficiency(value)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[allow(unused)]
+    use similar_asserts::assert_eq;
+    use static_assertions::{assert_impl_all, assert_not_impl_any};
+    use std::{
+        fmt::{
+            self, Binary, Debug, Display, LowerExp, LowerHex, Octal, Pointer, UpperExp, UpperHex,
+        },
+        hash::Hash,
+        io::{self, Read},
+        panic::UnwindSafe,
+    };
+
+    // Check that public types in this module keep implementing all expected
+    // traits, in the interest of detecting future semver-breaking changes
+    assert_impl_all!(CoreClass:
+        Copy, Debug, Display, Hash, Sized, Sync, Unpin, UnwindSafe
+    );
+    assert_not_impl_any!(CoreClass:
+        Binary, Default, Deref, Drop, IntoIterator, LowerExp, LowerHex, Octal,
+        PartialOrd, Pointer, Read, UpperExp, UpperHex, fmt::Write, io::Write
+    );
+
+    /// CPU kind without textual info
+    fn kind(cpus: &[usize], efficiency: Option<CpuEfficiency>) -> CpuKind<'static> {
+        let mut cpuset = CpuSet::new();
+        for &cpu in cpus {
+            cpuset.set(cpu);
+        }
+        CpuKind {
+            cpuset,
+            efficiency,
+            infos: &[],
+        }
+    }
+
+    /// Check classification rules that do not depend on textual info
+    #[test]
+    fn classify() {
+        assert_eq!(
+            CoreClass::classify(&[kind(&[0, 1], None)]),
+            vec![CoreClass::Performance]
+        );
+        assert_eq!(
+            CoreClass::classify(&[
+                kind(&[0], Some(0)),
+                kind(&[1], Some(1)),
+                kind(&[2], Some(2))
+            ]),
+            vec![
+                CoreClass::Efficiency,
+                CoreClass::Performance,
+                CoreClass::Performance
+            ]
+        );
+        assert_eq!(
+            CoreClass::classify(&[kind(&[0], None), kind(&[1], None)]),
+            vec![CoreClass::Unknown, CoreClass::Unknown]
+        );
+    }
+
+    /// Performance and efficiency cores should be disjoint CPUs of the topology
+    #[test]
+    fn core_sets() {
+        let topology = Topology::test_instance();
+        let performance = topology.performance_cores();
+        let efficiency = topology.efficiency_cores();
+        assert!(!performance.intersects(&efficiency));
+        assert!(topology.cpuset().includes(&performance));
+        assert!(topology.cpuset().includes(&efficiency));
+        if let Ok(kinds) = topology.cpu_kinds_with_class() {
+            for (kind, class) in kinds {
+                match class {
+                    CoreClass::Performance => assert!(performance.includes(&kind.cpuset)),
+                    CoreClass::Efficiency => assert!(efficiency.includes(&kind.cpuset)),
+                    CoreClass::Unknown => {
+                        assert!(!performance.intersects(&kind.cpuset));
+                        assert!(!efficiency.intersects(&kind.cpuset));
+                    }
+                }
+            }
+        } else {
+            assert_eq!(performance, topology.cpuset().clone_target());
+            assert!(efficiency.is_empty());
+        }
+    }
+}
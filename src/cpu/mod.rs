@@ -4,11 +4,13 @@
 //! [`Topology`] struct. The module itself only hosts type definitions that are
 //! related to this functionality.
 
+pub mod affinity;
 pub mod binding;
 pub mod cache;
 pub mod cpuset;
 #[cfg(feature = "hwloc-2_4_0")]
 pub mod kind;
+pub mod spec;
 
 #[cfg(doc)]
 use crate::topology::Topology;
@@ -0,0 +1,605 @@
+//! Textual CPU set specifications
+//!
+//! Configuration files often need to say which CPUs a program should run on.
+//! Raw CPU set bitmasks are not very readable and are tied to the OS numbering
+//! of a particular machine, so this module provides the [`CpuSetSpec`] type,
+//! which describes CPU sets in terms of topology objects using a syntax that
+//! is inspired by that of the `hwloc-calc` command-line tool.
+//!
+//! Specifications are parsed in two steps. First, the text is checked for
+//! syntax errors when the [`CpuSetSpec`] is created, typically at
+//! configuration loading time. Then, [`CpuSetSpec::resolve()`] turns the
+//! specification into a [`CpuSet`] for a particular topology, reporting any
+//! object that does not exist on that machine.
+
+use crate::{
+    cpu::cpuset::CpuSet,
+    object::{types::ObjectType, TopologyObject},
+    topology::Topology,
+};
+#[allow(unused)]
+#[cfg(test)]
+use similar_asserts::assert_eq;
+use std::{
+    fmt::{self, Display},
+    ops::RangeInclusive,
+    str::FromStr,
+};
+use thiserror::Error;
+
+/// Human-editable description of a CPU set
+///
+/// A specification is a whitespace-separated list of terms. The CPU sets
+/// designated by each term are merged together, except for terms prefixed
+/// with `~`, whose CPUs are removed from the result. Terms are applied from
+/// left to right. Two kinds of terms are supported:
+///
+/// - **Object paths** like `package:0.core:2-5.pu:0` designate objects of the
+///   topology by type and logical index. Each `.`-separated component selects
+///   objects of a certain type among those that are inside the objects
+///   selected by the previous component, or among all objects of the topology
+///   for the first component. Indices are relative to the enclosing object,
+///   so `package:1.core:0` is the first core of the second package.
+/// - **PU lists** like `0-3,8` designate PUs by OS index, using the same list
+///   syntax as Linux cpusets and `taskset -c`.
+///
+/// In object paths, types can be spelled as in `hwloc-calc`, e.g. `core`,
+/// `pu`, `l3` or `numa`, and indices can be a comma-separated list of
+/// indices and inclusive index ranges (`0,2-4`), or one of the keywords
+/// `all`, `even` and `odd`.
+///
+/// # Examples
+///
+/// ```
+/// # use hwlocality::{cpu::spec::CpuSetSpec, object::types::ObjectType};
+/// # let topology = hwlocality::Topology::test_instance();
+/// // Parse the specification at configuration loading time...
+/// let spec: CpuSetSpec = "core:all.pu:0".parse()?;
+///
+/// // ...then resolve it against the topology of the current machine
+/// let cpuset = spec.resolve(&topology)?;
+/// assert_eq!(
+///     cpuset.weight(),
+///     Some(topology.objects_with_type(ObjectType::Core).count())
+/// );
+/// # Ok::<(), eyre::Report>(())
+/// ```
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct CpuSetSpec(Vec<Term>);
+//
+impl CpuSetSpec {
+    /// Compute the CPU set designated by this specification in `topology`
+    ///
+    /// # Errors
+    ///
+    /// - [`NoSuchObject`] if an object path refers to an object that does not
+    ///   exist in `topology`.
+    /// - [`NoSuchPU`] if a PU list refers to a PU that does not exist in
+    ///   `topology`.
+    ///
+    /// [`NoSuchObject`]: ResolveCpuSetSpecError::NoSuchObject
+    /// [`NoSuchPU`]: ResolveCpuSetSpecError::NoSuchPU
+    pub fn resolve(&self, topology: &Topology) -> Result<CpuSet, ResolveCpuSetSpecError> {
+        let mut result = CpuSet::new();
+        for term in &self.0 {
+            let term_set = match &term.target {
+                Target::Path(path) => resolve_path(topology, path)?,
+                Target::PUs(indices) => {
+                    let mut set = CpuSet::new();
+                    for os_index in indices.iter() {
+                        if topology.pu_with_os_index(os_index).is_none() {
+                            return Err(ResolveCpuSetSpecError::NoSuchPU(os_index));
+                        }
+                        set.set(os_index);
+                    }
+                    set
+                }
+            };
+            if term.exclude {
+                result -= term_set;
+            } else {
+                result |= term_set;
+            }
+        }
+        Ok(result)
+    }
+}
+//
+impl Display for CpuSetSpec {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (idx, term) in self.0.iter().enumerate() {
+            if idx > 0 {
+                f.write_str(" ")?;
+            }
+            write!(f, "{term}")?;
+        }
+        Ok(())
+    }
+}
+//
+impl FromStr for CpuSetSpec {
+    type Err = ParseCpuSetSpecError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let terms = s
+            .split_whitespace()
+            .map(Term::parse)
+            .collect::<Result<Vec<_>, _>>()?;
+        if terms.is_empty() {
+            return Err(ParseCpuSetSpecError::Empty);
+        }
+        Ok(Self(terms))
+    }
+}
+
+/// Error while parsing a [`CpuSetSpec`]
+#[derive(Clone, Debug, Eq, Error, Hash, PartialEq)]
+pub enum ParseCpuSetSpecError {
+    /// The specification does not contain any term
+    #[error("CPU set specification is empty")]
+    Empty,
+
+    /// A term is neither an object path nor a PU list
+    #[error("{0:?} is neither an object path nor a PU list")]
+    BadTerm(String),
+
+    /// An object path contains an invalid object type
+    #[error("{0:?} is not a valid object type")]
+    BadType(String),
+
+    /// An object path contains an object type that does not have a CPU set
+    #[error("{0} objects don't have a CPU set")]
+    TypeWithoutCpuSet(ObjectType),
+
+    /// An index list is invalid
+    #[error("{0:?} is not a valid index list")]
+    BadIndices(String),
+}
+
+/// Error while resolving a [`CpuSetSpec`] against a [`Topology`]
+#[derive(Clone, Debug, Eq, Error, Hash, PartialEq)]
+pub enum ResolveCpuSetSpecError {
+    /// An object path refers to an object that does not exist
+    #[error("{location} has no {object_type} #{index} (it only has {available})")]
+    NoSuchObject {
+        /// Object path leading to the object that was searched in, or
+        /// `"topology"` if the search was done in the entire topology
+        location: String,
+
+        /// Type of object that was searched for
+        object_type: ObjectType,
+
+        /// Logical index of the object that was searched for
+        index: usize,
+
+        /// Number of objects of this type that actually exist in `location`
+        available: usize,
+    },
+
+    /// A PU list refers to a PU that does not exist
+    #[error("there is no PU with OS index {0}")]
+    NoSuchPU(usize),
+}
+
+/// Term of a [`CpuSetSpec`]
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+struct Term {
+    /// Truth that the CPUs of this term should be removed from the result
+    exclude: bool,
+
+    /// CPUs targeted by this term
+    target: Target,
+}
+//
+impl Term {
+    /// Parse a term
+    fn parse(s: &str) -> Result<Self, ParseCpuSetSpecError> {
+        let (exclude, target) = s.strip_prefix('~').map_or((false, s), |rest| (true, rest));
+        let bad_term = || ParseCpuSetSpecError::BadTerm(s.to_owned());
+        let target = if target.starts_with(|c: char| c.is_ascii_digit()) {
+            Target::PUs(Indices::parse(target)?)
+        } else if target.contains(':') {
+            Target::Path(
+                target
+                    .split('.')
+                    .map(|component| {
+                        let (ty, indices) = component.split_once(':').ok_or_else(bad_term)?;
+                        Ok((parse_type(ty)?, Indices::parse(indices)?))
+                    })
+                    .collect::<Result<Vec<_>, _>>()?,
+            )
+        } else {
+            return Err(bad_term());
+        };
+        Ok(Self { exclude, target })
+    }
+}
+//
+impl Display for Term {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.exclude {
+            f.write_str("~")?;
+        }
+        match &self.target {
+            Target::Path(path) => {
+                for (idx, (ty, indices)) in path.iter().enumerate() {
+                    if idx > 0 {
+                        f.write_str(".")?;
+                    }
+                    write!(f, "{ty}:{indices}")?;
+                }
+                Ok(())
+            }
+            Target::PUs(indices) => write!(f, "{indices}"),
+        }
+    }
+}
+
+/// CPUs targeted by a [`Term`]
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+enum Target {
+    /// Objects designated by a path of (type, logical indices) components
+    Path(Vec<(ObjectType, Indices)>),
+
+    /// PUs designated by OS index
+    PUs(Indices),
+}
+
+/// Set of object indices
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+enum Indices {
+    /// All objects
+    All,
+
+    /// Objects with an even index
+    Even,
+
+    /// Objects with an odd index
+    Odd,
+
+    /// Objects within these inclusive index ranges
+    List(Vec<RangeInclusive<usize>>),
+}
+//
+impl Indices {
+    /// Parse an index list
+    fn parse(s: &str) -> Result<Self, ParseCpuSetSpecError> {
+        let bad_indices = || ParseCpuSetSpecError::BadIndices(s.to_owned());
+        match s {
+            "all" => Ok(Self::All),
+            "even" => Ok(Self::Even),
+            "odd" => Ok(Self::Odd),
+            _ => s
+                .split(',')
+                .map(|range| {
+                    let parse_index = |idx: &str| idx.parse::<usize>().map_err(|_| bad_indices());
+                    let (start, end) = range.split_once('-').unwrap_or((range, range));
+                    let (start, end) = (parse_index(start)?, parse_index(end)?);
+                    if start > end {
+                        return Err(bad_indices());
+                    }
+                    Ok(start..=end)
+                })
+                .collect::<Result<Vec<_>, _>>()
+                .map(Self::List),
+        }
+    }
+
+    /// Explicitly listed indices, in the order where they were specified
+    ///
+    /// Must not be called on keywords, which depend on the number of objects.
+    fn iter(&self) -> impl Iterator<Item = usize> + '_ {
+        let Self::List(ranges) = self else {
+            unreachable!("PU lists can't use keywords, and keywords can't be enumerated")
+        };
+        ranges.iter().cloned().flatten()
+    }
+
+    /// Select objects from a list of candidates
+    ///
+    /// On failure, returns the first index that is out of bounds.
+    fn select<'object>(
+        &self,
+        candidates: &[&'object TopologyObject],
+    ) -> Result<Vec<&'object TopologyObject>, usize> {
+        match self {
+            Self::All => Ok(candidates.to_vec()),
+            Self::Even => Ok(candidates.iter().copied().step_by(2).collect()),
+            Self::Odd => Ok(candidates.iter().copied().skip(1).step_by(2).collect()),
+            Self::List(_) => self
+                .iter()
+                .map(|idx| candidates.get(idx).copied().ok_or(idx))
+                .collect(),
+        }
+    }
+}
+//
+impl Display for Indices {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::All => f.write_str("all"),
+            Self::Even => f.write_str("even"),
+            Self::Odd => f.write_str("odd"),
+            Self::List(ranges) => {
+                for (idx, range) in ranges.iter().enumerate() {
+                    if idx > 0 {
+                        f.write_str(",")?;
+                    }
+                    if range.start() == range.end() {
+                        write!(f, "{}", range.start())?;
+                    } else {
+                        write!(f, "{}-{}", range.start(), range.end())?;
+                    }
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+/// Parse an object type from an object path
+fn parse_type(s: &str) -> Result<ObjectType, ParseCpuSetSpecError> {
+    let bad_type = || ParseCpuSetSpecError::BadType(s.to_owned());
+    // hwloc's type parser ignores trailing characters, but we don't want
+    // typos like "core2" to be silently accepted
+    if s.is_empty() || !s.chars().all(|c| c.is_ascii_alphanumeric()) {
+        return Err(bad_type());
+    }
+    let ty = ObjectType::parse_with_attributes(s)
+        .map_err(|_| bad_type())?
+        .object_type;
+    if !ty.has_sets() {
+        return Err(ParseCpuSetSpecError::TypeWithoutCpuSet(ty));
+    }
+    Ok(ty)
+}
+
+/// Resolve an object path into the union of the CPU sets of the objects that
+/// it designates
+fn resolve_path(
+    topology: &Topology,
+    path: &[(ObjectType, Indices)],
+) -> Result<CpuSet, ResolveCpuSetSpecError> {
+    // Walk the path, keeping track of each selected object's own path
+    let mut current = vec![(String::from("topology"), None::<&TopologyObject>)];
+    for (ty, indices) in path {
+        let ty = *ty;
+        let mut next = Vec::new();
+        for (location, parent) in current {
+            let candidates = parent.map_or_else(
+                || {
+                    topology
+                        .objects_with_type(ty)
+                        .filter(|obj| obj.cpuset().is_some_and(|set| !set.is_empty()))
+                        .collect::<Vec<_>>()
+                },
+                |parent| {
+                    let parent_set = parent
+                        .cpuset()
+                        .expect("Only objects with a cpuset can be selected");
+                    topology
+                        .objects_inside_cpuset_with_type(parent_set, ty)
+                        .collect()
+                },
+            );
+            let selected = indices.select(&candidates).map_err(|index| {
+                ResolveCpuSetSpecError::NoSuchObject {
+                    location: location.clone(),
+                    object_type: ty,
+                    index,
+                    available: candidates.len(),
+                }
+            })?;
+            for obj in selected {
+                let position = candidates
+                    .iter()
+                    .position(|candidate| std::ptr::eq(*candidate, obj))
+                    .expect("Selected objects come from the candidates");
+                let prefix = if parent.is_some() {
+                    format!("{location}.")
+                } else {
+                    String::new()
+                };
+                next.push((format!("{prefix}{ty}:{position}"), Some(obj)));
+            }
+        }
+        current = next;
+    }
+
+    // Merge the CPU sets of the selected objects
+    let mut result = CpuSet::new();
+    for (_location, obj) in current {
+        let obj = obj.expect("Paths have at least one component");
+        result |= obj
+            .cpuset()
+            .expect("Only objects with a cpuset can be selected");
+    }
+    Ok(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::prelude::*;
+    #[allow(unused)]
+    use similar_asserts::assert_eq;
+    use static_assertions::{assert_impl_all, assert_not_impl_any};
+    use std::{
+        error::Error,
+        fmt::{Binary, Debug, LowerExp, LowerHex, Octal, Pointer, UpperExp, UpperHex},
+        hash::Hash,
+        io::{self, Read},
+        ops::Deref,
+        panic::UnwindSafe,
+    };
+
+    // Check that public types in this module keep implementing all expected
+    // traits, in the interest of detecting future semver-breaking changes
+    assert_impl_all!(CpuSetSpec:
+        Clone, Debug, Display, FromStr, Hash, Sized, Sync, Unpin, UnwindSafe
+    );
+    assert_not_impl_any!(CpuSetSpec:
+        Binary, Copy, Default, Deref, Drop, IntoIterator, LowerExp, LowerHex,
+        Octal, PartialOrd, Pointer, Read, UpperExp, UpperHex, fmt::Write,
+        io::Write
+    );
+    assert_impl_all!(ParseCpuSetSpecError:
+        Clone, Error, Hash, Sized, Sync, Unpin, UnwindSafe
+    );
+    assert_impl_all!(ResolveCpuSetSpecError:
+        Clone, Error, Hash, Sized, Sync, Unpin, UnwindSafe
+    );
+
+    /// Parse a specification, panicking on failure
+    fn spec(s: &str) -> CpuSetSpec {
+        s.parse().unwrap()
+    }
+
+    /// Check that syntax errors are detected
+    #[test]
+    fn syntax_errors() {
+        let parse = |s: &str| s.parse::<CpuSetSpec>().unwrap_err();
+        assert_eq!(parse(""), ParseCpuSetSpecError::Empty);
+        assert_eq!(parse("  "), ParseCpuSetSpecError::Empty);
+        assert_eq!(
+            parse("core"),
+            ParseCpuSetSpecError::BadTerm("core".to_owned())
+        );
+        assert_eq!(
+            parse("core:0.pu"),
+            ParseCpuSetSpecError::BadTerm("core:0.pu".to_owned())
+        );
+        assert_eq!(
+            parse("frobnicator:0"),
+            ParseCpuSetSpecError::BadType("frobnicator".to_owned())
+        );
+        assert_eq!(
+            parse("core2:0"),
+            ParseCpuSetSpecError::BadType("core2".to_owned())
+        );
+        assert_eq!(
+            parse("misc:0"),
+            ParseCpuSetSpecError::TypeWithoutCpuSet(ObjectType::Misc)
+        );
+        for bad_indices in ["", "x", "1-", "3-1", "1,,2"] {
+            assert_eq!(
+                parse(&format!("core:{bad_indices}")),
+                ParseCpuSetSpecError::BadIndices(bad_indices.to_owned())
+            );
+        }
+        assert_eq!(
+            parse("0-x"),
+            ParseCpuSetSpecError::BadIndices("0-x".to_owned())
+        );
+    }
+
+    /// Check that specifications are displayed in normalized form
+    #[test]
+    fn display() {
+        assert_eq!(
+            spec("package:0.core:2-5,7.pu:0").to_string(),
+            "Package:0.Core:2-5,7.PU:0"
+        );
+        assert_eq!(spec(" 0-3,8  ~pu:odd ").to_string(), "0-3,8 ~PU:odd");
+        assert_eq!(spec("core:all").to_string(), "Core:all");
+    }
+
+    /// Check object path resolution
+    #[test]
+    fn object_paths() {
+        let topology = Topology::test_instance();
+        let cores = topology
+            .objects_with_type(ObjectType::Core)
+            .collect::<Vec<_>>();
+        let core_set = |core: &TopologyObject| core.cpuset().unwrap().clone_target();
+
+        assert_eq!(
+            spec("core:all").resolve(topology),
+            Ok(topology.cpuset().clone_target())
+        );
+        assert_eq!(spec("core:0").resolve(topology), Ok(core_set(cores[0])));
+        let mut even = CpuSet::new();
+        for core in cores.iter().step_by(2) {
+            even |= core_set(core);
+        }
+        assert_eq!(spec("core:even").resolve(topology), Ok(even.clone()));
+        assert_eq!(spec("core:all ~core:odd").resolve(topology), Ok(even));
+
+        // Indices in nested components are relative to the parent object
+        let first_pus = cores
+            .iter()
+            .map(|core| {
+                topology
+                    .objects_inside_cpuset_with_type(core.cpuset().unwrap(), ObjectType::PU)
+                    .next()
+                    .unwrap()
+                    .cpuset()
+                    .unwrap()
+                    .clone_target()
+            })
+            .fold(CpuSet::new(), |acc, set| acc | set);
+        assert_eq!(spec("core:all.pu:0").resolve(topology), Ok(first_pus));
+
+        // Missing objects are reported with their location
+        let num_cores = cores.len();
+        assert_eq!(
+            spec(&format!("core:{num_cores}")).resolve(topology),
+            Err(ResolveCpuSetSpecError::NoSuchObject {
+                location: "topology".to_owned(),
+                object_type: ObjectType::Core,
+                index: num_cores,
+                available: num_cores,
+            })
+        );
+        let last_core = num_cores - 1;
+        let num_pus = topology
+            .objects_inside_cpuset_with_type(cores[last_core].cpuset().unwrap(), ObjectType::PU)
+            .count();
+        assert_eq!(
+            spec(&format!("core:{last_core}.pu:{num_pus}")).resolve(topology),
+            Err(ResolveCpuSetSpecError::NoSuchObject {
+                location: format!("Core:{last_core}"),
+                object_type: ObjectType::PU,
+                index: num_pus,
+                available: num_pus,
+            })
+        );
+    }
+
+    /// Check PU list resolution
+    #[test]
+    fn pu_lists() {
+        let topology = Topology::test_instance();
+        let os_indices = topology
+            .objects_with_type(ObjectType::PU)
+            .map(|pu| pu.os_index().unwrap())
+            .collect::<Vec<_>>();
+        let list = os_indices
+            .iter()
+            .map(ToString::to_string)
+            .collect::<Vec<_>>()
+            .join(",");
+        assert_eq!(
+            spec(&list).resolve(topology),
+            Ok(topology.cpuset().clone_target())
+        );
+
+        let missing = os_indices.iter().max().unwrap() + 1;
+        assert_eq!(
+            spec(&format!("{missing}")).resolve(topology),
+            Err(ResolveCpuSetSpecError::NoSuchPU(missing))
+        );
+    }
+
+    proptest! {
+        /// Parsing arbitrary strings should fail gracefully, and successfully
+        /// parsed specifications should survive a round trip through Display
+        #[test]
+        fn parse_roundtrip(s in "[~0-9a-zA-Z:.,\\- ]{0,32}") {
+            if let Ok(spec) = s.parse::<CpuSetSpec>() {
+                let displayed = spec.to_string();
+                prop_assert_eq!(displayed.parse::<CpuSetSpec>(), Ok(spec));
+            }
+        }
+    }
+}
@@ -9,7 +9,7 @@
 // At the implementation level, this is also the place where all the low-level
 // handling of hwloc errors is implemented.
 
-use crate::object::{TopologyObject, TopologyObjectID};
+use crate::object::{handle::ObjectId, TopologyObject, TopologyObjectID};
 #[cfg(doc)]
 use crate::topology::Topology;
 use derive_more::From;
@@ -314,6 +314,46 @@ impl<'topology> From<&'topology TopologyObject> for ForeignObjectError {
         Self(object.global_persistent_index())
     }
 }
+//
+impl From<ObjectId> for ForeignObjectError {
+    fn from(object: ObjectId) -> Self {
+        Self(object.into())
+    }
+}
+
+/// An object was looked up by index, but there are not that many objects
+#[derive(Copy, Clone, Debug, Default, Eq, Error, Hash, PartialEq)]
+#[error("requested object #{index}, but there are only {len} objects")]
+pub struct IndexOutOfBounds {
+    /// Index that was requested
+    pub index: usize,
+
+    /// Number of objects that can be looked up
+    pub len: usize,
+}
+
+/// A [`TopologyObject`] without CPU and node sets was used in a context where
+/// these sets are needed
+///
+/// Only objects of a type for which [`ObjectType::has_sets()`] is true have
+/// CPU and node sets. I/O and Misc objects don't have any.
+///
+/// [`ObjectType::has_sets()`]: crate::object::types::ObjectType::has_sets()
+//
+// --- Implementation notes ---
+//
+// Not implementing Copy or exposing the inner data at this point for the same
+// reason as ForeignObjectError.
+#[allow(missing_copy_implementations)]
+#[derive(Clone, Debug, Eq, Error, Hash, PartialEq)]
+#[error("object #{0} doesn't have CPU and node sets")]
+pub struct ObjectWithoutSetsError(TopologyObjectID);
+//
+impl<'topology> From<&'topology TopologyObject> for ObjectWithoutSetsError {
+    fn from(object: &'topology TopologyObject) -> Self {
+        Self(object.global_persistent_index())
+    }
+}
 
 #[cfg(test)]
 mod tests {
@@ -344,6 +384,14 @@ mod tests {
         Octal, PartialOrd, Pointer, Read, UpperExp, UpperHex, fmt::Write,
         io::Write
     );
+    assert_impl_all!(ObjectWithoutSetsError:
+        Clone, Error, Hash, Sized, Sync, Unpin, UnwindSafe
+    );
+    assert_not_impl_any!(ObjectWithoutSetsError:
+        Binary, Copy, Default, Deref, Drop, IntoIterator, LowerExp, LowerHex,
+        Octal, PartialOrd, Pointer, Read, UpperExp, UpperHex, fmt::Write,
+        io::Write
+    );
     assert_impl_all!(HybridError<NulError>:
         Copy, Error, Hash, Sized, Sync, Unpin, UnwindSafe
     );
@@ -351,6 +399,13 @@ mod tests {
         Binary, Default, Deref, Drop, IntoIterator, LowerExp, LowerHex, Octal,
         PartialOrd, Pointer, Read, UpperExp, UpperHex, fmt::Write, io::Write
     );
+    assert_impl_all!(IndexOutOfBounds:
+        Copy, Default, Error, Hash, Sized, Sync, Unpin, UnwindSafe
+    );
+    assert_not_impl_any!(IndexOutOfBounds:
+        Binary, Deref, Drop, IntoIterator, LowerExp, LowerHex, Octal,
+        PartialOrd, Pointer, Read, UpperExp, UpperHex, fmt::Write, io::Write
+    );
     assert_impl_all!(NulError:
         Copy, Default, Error, Hash, Ord, Sized, Sync, Unpin, UnwindSafe
     );
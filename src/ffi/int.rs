@@ -19,15 +19,9 @@
 //!
 //! This module helps you implement both of these strategies.
 
-use derive_more::{Binary, Display, LowerExp, LowerHex, Octal, UpperExp, UpperHex};
-#[cfg(any(test, feature = "proptest"))]
-use proptest::prelude::*;
-#[allow(unused)]
-#[cfg(test)]
-use similar_asserts::assert_eq;
 #[cfg(doc)]
-use std::ops::{Range, RangeFrom, RangeInclusive};
-use std::{
+use core::ops::{Range, RangeFrom, RangeInclusive};
+use core::{
     borrow::Borrow,
     cmp::Ordering,
     ffi::{c_int, c_uint},
@@ -41,6 +35,12 @@ use std::{
     },
     str::FromStr,
 };
+use derive_more::{Binary, Display, LowerExp, LowerHex, Octal, UpperExp, UpperHex};
+#[cfg(any(test, feature = "proptest"))]
+use proptest::prelude::*;
+#[allow(unused)]
+#[cfg(test)]
+use similar_asserts::assert_eq;
 
 /// Assert that a [`c_int`] can be converted to a [`isize`]
 ///
@@ -128,6 +128,45 @@ impl PositiveInt {
     /// could be misinterpreted by careless users.
     pub const EFFECTIVE_BITS: u32 = c_uint::BITS - 1;
 
+    /// Convert from a [`usize`] in a `const` context
+    ///
+    /// This is equivalent to the [`TryFrom<usize>`] implementation, but can
+    /// be used to define constants, which trait methods cannot do yet.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use hwlocality::ffi::PositiveInt;
+    /// const FORTY_TWO: Option<PositiveInt> = PositiveInt::try_from_usize(42);
+    /// assert_eq!(FORTY_TWO, PositiveInt::try_from(42).ok());
+    /// assert_eq!(PositiveInt::try_from_usize(usize::MAX), None);
+    /// ```
+    #[allow(clippy::cast_possible_truncation)]
+    pub const fn try_from_usize(x: usize) -> Option<Self> {
+        if x <= Self::MAX.0 as usize {
+            Some(Self(x as c_uint))
+        } else {
+            None
+        }
+    }
+
+    /// Convert into a [`usize`] in a `const` context
+    ///
+    /// This is equivalent to the [`From<PositiveInt>`] implementation of
+    /// [`usize`], but can be used to define constants.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use hwlocality::ffi::PositiveInt;
+    /// const MAX: usize = PositiveInt::MAX.to_usize();
+    /// assert_eq!(MAX, usize::from(PositiveInt::MAX));
+    /// ```
+    pub const fn to_usize(self) -> usize {
+        // Cannot fail on any platform supported by hwloc, see expect_usize()
+        self.0 as usize
+    }
+
     /// Converts a string slice in a given base to an integer
     ///
     /// The string is expected to be an optional `+` sign followed by digits.
@@ -3857,6 +3896,10 @@ mod tests {
                 PositiveInt::try_from(x),
                 c_int::try_from(x).map(|i| PositiveInt(i.try_into().unwrap()))
             );
+            prop_assert_eq!(PositiveInt::try_from_usize(x), PositiveInt::try_from(x).ok());
+            if let Some(int) = PositiveInt::try_from_usize(x) {
+                prop_assert_eq!(int.to_usize(), x);
+            }
 
             // Multiplying ZERO by any usize works
             let zero = PositiveInt::ZERO;
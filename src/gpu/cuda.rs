@@ -0,0 +1,228 @@
+//! CUDA device locality
+//!
+//! This module is the Rust equivalent of hwloc's `hwloc/cudart.h` header. It
+//! lets you find out which CPUs and topology objects are close to a CUDA
+//! device, identified by its CUDA runtime device ordinal, so that host threads
+//! can be co-located with the GPU they drive.
+//!
+//! This functionality is only available when the `cuda` cargo feature is
+//! enabled, and requires the CUDA runtime library (`libcudart`) at link time.
+
+use crate::{
+    cpu::cpuset::CpuSet,
+    object::{attributes::PCIDomain, TopologyObject},
+    topology::Topology,
+};
+#[allow(unused)]
+#[cfg(test)]
+use similar_asserts::assert_eq;
+use std::ffi::c_int;
+use thiserror::Error;
+
+/// # CUDA device locality
+///
+/// These functions let you find out which CPUs and objects are close to a CUDA
+/// device. They require the topology to describe the system that the program
+/// is running on, and the CUDA runtime to be usable.
+//
+// --- Implementation details ---
+//
+// Upstream docs: https://hwloc.readthedocs.io/en/v2.9/group__hwlocality__cudart.html
+// Since these functions are inline functions in hwloc's headers, they had to be
+// reimplemented in Rust.
+impl Topology {
+    /// CPUs that are close to the CUDA device with ordinal `device`
+    ///
+    /// On Linux, this is the set of CPUs that the operating system reports as
+    /// local to the device's PCI slot. On other operating systems, or if that
+    /// information is unavailable, the complete cpuset of the topology is
+    /// returned.
+    ///
+    /// # Errors
+    ///
+    /// - [`ForeignSystem`] if the topology does not describe the system that
+    ///   the program is running on.
+    /// - [`Runtime`] if the CUDA runtime failed to report the device's PCI
+    ///   bus ID, e.g. because there is no device with ordinal `device`.
+    /// - [`BadPciId`] if the CUDA runtime reported an invalid PCI bus ID.
+    ///
+    /// [`BadPciId`]: CudaDeviceError::BadPciId
+    /// [`ForeignSystem`]: CudaDeviceError::ForeignSystem
+    /// [`Runtime`]: CudaDeviceError::Runtime
+    #[doc(alias = "hwloc_cudart_get_device_cpuset")]
+    pub fn cuda_device_cpuset(&self, device: c_int) -> Result<CpuSet, CudaDeviceError> {
+        if !self.is_this_system() {
+            return Err(CudaDeviceError::ForeignSystem);
+        }
+        let (domain, bus_id, bus_device) = cuda_device_pci_ids(device)?;
+        Ok(super::pci_device_cpuset(
+            self, domain, bus_id, bus_device, 0,
+        ))
+    }
+
+    /// PCI device object that describes the CUDA device with ordinal `device`
+    ///
+    /// Returns `None` if there is no such object in the topology, e.g. because
+    /// I/O device discovery was disabled via type filters.
+    ///
+    /// # Errors
+    ///
+    /// - [`Runtime`] if the CUDA runtime failed to report the device's PCI
+    ///   bus ID, e.g. because there is no device with ordinal `device`.
+    /// - [`BadPciId`] if the CUDA runtime reported an invalid PCI bus ID.
+    ///
+    /// [`BadPciId`]: CudaDeviceError::BadPciId
+    /// [`Runtime`]: CudaDeviceError::Runtime
+    #[doc(alias = "hwloc_cudart_get_device_pcidev")]
+    pub fn cuda_device_pci_object(
+        &self,
+        device: c_int,
+    ) -> Result<Option<&TopologyObject>, CudaDeviceError> {
+        let (domain, bus_id, bus_device) = cuda_device_pci_ids(device)?;
+        Ok(self.pci_device_by_bus_id(domain, bus_id, bus_device, 0))
+    }
+
+    /// OS device object that describes the CUDA device with ordinal `device`
+    ///
+    /// This is the co-processor OS device called `cuda<device>`, which is only
+    /// present if hwloc was built with CUDA support and I/O device discovery
+    /// is enabled. `None` is returned if there is no such object.
+    ///
+    /// Unlike other functions of this module, this function does not call into
+    /// the CUDA runtime.
+    #[doc(alias = "hwloc_cudart_get_device_osdev_by_index")]
+    pub fn cuda_device_osdev(&self, device: c_int) -> Option<&TopologyObject> {
+        super::coprocessor_osdev_by_name(self, &format!("cuda{device}"))
+    }
+}
+
+/// Error while querying the locality of a CUDA device
+#[derive(Copy, Clone, Debug, Eq, Error, Hash, PartialEq)]
+pub enum CudaDeviceError {
+    /// The CUDA runtime reported an error, with this `cudaError_t` code
+    #[error("CUDA runtime call failed with error code {0}")]
+    Runtime(c_int),
+
+    /// The CUDA runtime reported a PCI bus ID component that is out of range
+    #[error("CUDA runtime reported an invalid PCI bus ID component {0}")]
+    BadPciId(c_int),
+
+    /// The topology does not describe the system that the program is running
+    /// on, so its CPUs cannot be associated with local CUDA devices
+    #[error("topology doesn't describe the system the program is running on")]
+    ForeignSystem,
+}
+
+/// Query the PCI domain, bus and device IDs of a CUDA device
+fn cuda_device_pci_ids(device: c_int) -> Result<(PCIDomain, u8, u8), CudaDeviceError> {
+    /// Query one CUDA device attribute
+    fn attribute(device: c_int, attr: c_int) -> Result<c_int, CudaDeviceError> {
+        let mut value = 0;
+        // SAFETY: - value is a valid output location for an int
+        //         - attr is a valid cudaDeviceAttr value
+        //         - The CUDA runtime validates device ordinals
+        let result = unsafe { cudaDeviceGetAttribute(&mut value, attr, device) };
+        if result == CUDA_SUCCESS {
+            Ok(value)
+        } else {
+            Err(CudaDeviceError::Runtime(result))
+        }
+    }
+    let domain = attribute(device, CUDA_DEV_ATTR_PCI_DOMAIN_ID)?;
+    let bus_id = attribute(device, CUDA_DEV_ATTR_PCI_BUS_ID)?;
+    let bus_device = attribute(device, CUDA_DEV_ATTR_PCI_DEVICE_ID)?;
+    Ok((
+        PCIDomain::try_from(domain).map_err(|_| CudaDeviceError::BadPciId(domain))?,
+        u8::try_from(bus_id).map_err(|_| CudaDeviceError::BadPciId(bus_id))?,
+        u8::try_from(bus_device).map_err(|_| CudaDeviceError::BadPciId(bus_device))?,
+    ))
+}
+
+/// `cudaSuccess` error code
+const CUDA_SUCCESS: c_int = 0;
+
+/// `cudaDevAttrPciBusId` device attribute
+const CUDA_DEV_ATTR_PCI_BUS_ID: c_int = 33;
+
+/// `cudaDevAttrPciDeviceId` device attribute
+const CUDA_DEV_ATTR_PCI_DEVICE_ID: c_int = 34;
+
+/// `cudaDevAttrPciDomainId` device attribute
+const CUDA_DEV_ATTR_PCI_DOMAIN_ID: c_int = 50;
+
+#[link(name = "cudart")]
+extern "C" {
+    /// Query an attribute of a CUDA device
+    #[must_use]
+    fn cudaDeviceGetAttribute(value: *mut c_int, attr: c_int, device: c_int) -> c_int;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::object::{
+        attributes::ObjectAttributes,
+        types::{OSDeviceType, ObjectType},
+    };
+    #[allow(unused)]
+    use similar_asserts::assert_eq;
+    use static_assertions::{assert_impl_all, assert_not_impl_any};
+    use std::{
+        error::Error,
+        fmt::{self, Binary, LowerExp, LowerHex, Octal, Pointer, UpperExp, UpperHex},
+        hash::Hash,
+        io::{self, Read},
+        ops::Deref,
+        panic::UnwindSafe,
+        ptr,
+    };
+
+    // Check that public types in this module keep implementing all expected
+    // traits, in the interest of detecting future semver-breaking changes
+    assert_impl_all!(CudaDeviceError:
+        Copy, Error, Hash, Sized, Sync, Unpin, UnwindSafe
+    );
+    assert_not_impl_any!(CudaDeviceError:
+        Binary, Default, Deref, Drop, IntoIterator, LowerExp, LowerHex, Octal,
+        PartialOrd, Pointer, Read, UpperExp, UpperHex, fmt::Write, io::Write
+    );
+
+    /// CUDA OS devices should be found by name
+    #[test]
+    fn cuda_device_osdev() {
+        let topology = Topology::test_gpu_node();
+        let osdev = topology.cuda_device_osdev(0).unwrap();
+        assert_eq!(osdev.object_type(), ObjectType::OSDevice);
+        assert_eq!(osdev.name().unwrap().to_str(), Ok("cuda0"));
+        assert_eq!(osdev.global_persistent_index(), 15);
+        let Some(ObjectAttributes::OSDevice(attr)) = osdev.attributes() else {
+            panic!("OS devices should have OS device attributes")
+        };
+        assert_eq!(attr.device_type(), OSDeviceType::CoProcessor);
+        let pci = osdev.parent().unwrap();
+        assert_eq!(pci.object_type(), ObjectType::PCIDevice);
+        assert!(pci.io_children().any(|child| ptr::eq(child, osdev)));
+
+        // There is a single CUDA device in this topology, and none in
+        // topologies without I/O objects
+        for device in [-1, 1, 2] {
+            assert!(topology.cuda_device_osdev(device).is_none());
+        }
+        let no_io = Topology::test_dual_socket_numa();
+        assert!(no_io.cuda_device_osdev(0).is_none());
+    }
+
+    /// CUDA device locality should be consistent with the topology
+    #[test]
+    fn cuda_device_locality() {
+        let topology = Topology::test_instance();
+        let Ok(cpuset) = topology.cuda_device_cpuset(0) else {
+            return;
+        };
+        assert!(topology.complete_cpuset().includes(&cpuset));
+        assert!(!cpuset.is_empty());
+        if let Some(pci) = topology.cuda_device_pci_object(0).unwrap() {
+            assert_eq!(pci.object_type(), ObjectType::PCIDevice);
+        }
+    }
+}
@@ -0,0 +1,249 @@
+//! Level Zero device locality
+//!
+//! This module is the Rust equivalent of hwloc's `hwloc/levelzero.h` header.
+//! It lets you find out which CPUs and topology objects are close to a oneAPI
+//! Level Zero device, identified by its `ze_device_handle_t` handle.
+//!
+//! This functionality is only available when the `level-zero` cargo feature
+//! is enabled, and requires the Level Zero loader library (`libze_loader`) at
+//! link time.
+
+use crate::{
+    cpu::cpuset::CpuSet,
+    object::{attributes::PCIDomain, TopologyObject},
+    topology::Topology,
+};
+#[allow(unused)]
+#[cfg(test)]
+use similar_asserts::assert_eq;
+use std::{
+    ffi::{c_uint, c_void},
+    ptr,
+};
+use thiserror::Error;
+
+/// Level Zero device handle (`ze_device_handle_t`)
+pub type ZeDeviceHandle = *mut c_void;
+
+/// # Level Zero device locality
+///
+/// These functions let you find out which CPUs and objects are close to a
+/// Level Zero device.
+//
+// --- Implementation details ---
+//
+// Upstream docs: https://hwloc.readthedocs.io/en/v2.9/group__hwlocality__levelzero.html
+// Since these functions are inline functions in hwloc's headers, they had to be
+// reimplemented in Rust.
+impl Topology {
+    /// CPUs that are close to the Level Zero device `device`
+    ///
+    /// On Linux, this is the set of CPUs that the operating system reports as
+    /// local to the device's PCI slot. On other operating systems, or if that
+    /// information is unavailable, the complete cpuset of the topology is
+    /// returned.
+    ///
+    /// # Errors
+    ///
+    /// - [`ForeignSystem`] if the topology does not describe the system that
+    ///   the program is running on.
+    /// - [`Runtime`] if the Level Zero driver failed to report the device's
+    ///   PCI properties.
+    /// - [`BadPciId`] if the Level Zero driver reported an invalid PCI bus ID.
+    ///
+    /// # Safety
+    ///
+    /// `device` must be a valid Level Zero device handle.
+    ///
+    /// [`BadPciId`]: LevelZeroDeviceError::BadPciId
+    /// [`ForeignSystem`]: LevelZeroDeviceError::ForeignSystem
+    /// [`Runtime`]: LevelZeroDeviceError::Runtime
+    #[doc(alias = "hwloc_levelzero_get_device_cpuset")]
+    pub unsafe fn level_zero_device_cpuset(
+        &self,
+        device: ZeDeviceHandle,
+    ) -> Result<CpuSet, LevelZeroDeviceError> {
+        if !self.is_this_system() {
+            return Err(LevelZeroDeviceError::ForeignSystem);
+        }
+        // SAFETY: Per function precondition
+        let (domain, bus_id, bus_device, function) = unsafe { level_zero_device_pci_ids(device)? };
+        Ok(super::pci_device_cpuset(
+            self, domain, bus_id, bus_device, function,
+        ))
+    }
+
+    /// OS device object that describes the Level Zero device `device`
+    ///
+    /// This is the OS device whose name starts with `ze` and whose parent is
+    /// the PCI device of `device`. It is only present if hwloc was built with
+    /// Level Zero support and I/O device discovery is enabled. `None` is
+    /// returned if there is no such object.
+    ///
+    /// # Errors
+    ///
+    /// - [`Runtime`] if the Level Zero driver failed to report the device's
+    ///   PCI properties.
+    /// - [`BadPciId`] if the Level Zero driver reported an invalid PCI bus ID.
+    ///
+    /// # Safety
+    ///
+    /// `device` must be a valid Level Zero device handle.
+    ///
+    /// [`BadPciId`]: LevelZeroDeviceError::BadPciId
+    /// [`Runtime`]: LevelZeroDeviceError::Runtime
+    #[doc(alias = "hwloc_levelzero_get_device_osdev")]
+    pub unsafe fn level_zero_device_osdev(
+        &self,
+        device: ZeDeviceHandle,
+    ) -> Result<Option<&TopologyObject>, LevelZeroDeviceError> {
+        // SAFETY: Per function precondition
+        let (domain, bus_id, bus_device, function) = unsafe { level_zero_device_pci_ids(device)? };
+        Ok(super::pci_osdev_by_bus_id(
+            self, "ze", domain, bus_id, bus_device, function,
+        ))
+    }
+}
+
+/// Error while querying the locality of a Level Zero device
+#[derive(Copy, Clone, Debug, Eq, Error, Hash, PartialEq)]
+pub enum LevelZeroDeviceError {
+    /// The Level Zero driver reported an error, with this `ze_result_t` code
+    #[error("Level Zero call failed with error code {0:#x}")]
+    Runtime(c_uint),
+
+    /// The Level Zero driver reported a PCI bus ID component that is out of
+    /// range
+    #[error("Level Zero driver reported an invalid PCI bus ID component {0}")]
+    BadPciId(u32),
+
+    /// The topology does not describe the system that the program is running
+    /// on, so its CPUs cannot be associated with local Level Zero devices
+    #[error("topology doesn't describe the system the program is running on")]
+    ForeignSystem,
+}
+
+/// Query the PCI domain, bus, device and function IDs of a Level Zero device
+///
+/// # Safety
+///
+/// `device` must be a valid Level Zero device handle.
+unsafe fn level_zero_device_pci_ids(
+    device: ZeDeviceHandle,
+) -> Result<(PCIDomain, u8, u8, u8), LevelZeroDeviceError> {
+    let mut properties = ZePciExtProperties {
+        stype: ZE_STRUCTURE_TYPE_PCI_EXT_PROPERTIES,
+        next: ptr::null(),
+        address: ZePciAddressExt::default(),
+        max_speed: ZePciSpeedExt::default(),
+    };
+    // SAFETY: - device is valid per function precondition
+    //         - properties is a valid ze_pci_ext_properties_t with the
+    //           right structure type and no extension
+    let result = unsafe { zeDevicePciGetPropertiesExt(device, &mut properties) };
+    if result != ZE_RESULT_SUCCESS {
+        return Err(LevelZeroDeviceError::Runtime(result));
+    }
+    let ZePciAddressExt {
+        domain,
+        bus,
+        device,
+        function,
+    } = properties.address;
+    let bad_id = |id: u32| LevelZeroDeviceError::BadPciId(id);
+    Ok((
+        PCIDomain::try_from(domain).map_err(|_| bad_id(domain))?,
+        u8::try_from(bus).map_err(|_| bad_id(bus))?,
+        u8::try_from(device).map_err(|_| bad_id(device))?,
+        u8::try_from(function).map_err(|_| bad_id(function))?,
+    ))
+}
+
+/// `ZE_RESULT_SUCCESS` result code
+const ZE_RESULT_SUCCESS: c_uint = 0;
+
+/// `ZE_STRUCTURE_TYPE_PCI_EXT_PROPERTIES` structure type
+const ZE_STRUCTURE_TYPE_PCI_EXT_PROPERTIES: c_uint = 0x0001_0008;
+
+/// `ze_pci_address_ext_t`
+#[derive(Copy, Clone, Default)]
+#[repr(C)]
+struct ZePciAddressExt {
+    /// PCI domain
+    domain: u32,
+
+    /// PCI bus
+    bus: u32,
+
+    /// PCI device
+    device: u32,
+
+    /// PCI function
+    function: u32,
+}
+
+/// `ze_pci_speed_ext_t`
+#[derive(Copy, Clone, Default)]
+#[repr(C)]
+struct ZePciSpeedExt {
+    /// PCI Express generation
+    gen_version: i32,
+
+    /// Number of lanes
+    width: i32,
+
+    /// Maximal bandwidth in bytes/s
+    max_bandwidth: i64,
+}
+
+/// `ze_pci_ext_properties_t`
+#[repr(C)]
+struct ZePciExtProperties {
+    /// Structure type, must be `ZE_STRUCTURE_TYPE_PCI_EXT_PROPERTIES`
+    stype: c_uint,
+
+    /// Pointer to extension-specific structure, unused
+    next: *const c_void,
+
+    /// PCI address
+    address: ZePciAddressExt,
+
+    /// Maximal PCI link speed
+    max_speed: ZePciSpeedExt,
+}
+
+#[link(name = "ze_loader")]
+extern "C" {
+    /// Query the PCI properties of a Level Zero device
+    #[must_use]
+    fn zeDevicePciGetPropertiesExt(
+        device: ZeDeviceHandle,
+        properties: *mut ZePciExtProperties,
+    ) -> c_uint;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[allow(unused)]
+    use similar_asserts::assert_eq;
+    use static_assertions::{assert_impl_all, assert_not_impl_any};
+    use std::{
+        error::Error,
+        fmt::{self, Binary, LowerExp, LowerHex, Octal, Pointer, UpperExp, UpperHex},
+        hash::Hash,
+        io::{self, Read},
+        ops::Deref,
+        panic::UnwindSafe,
+    };
+
+    // Check that public types in this module keep implementing all expected
+    // traits, in the interest of detecting future semver-breaking changes
+    assert_impl_all!(LevelZeroDeviceError:
+        Copy, Error, Hash, Sized, Sync, Unpin, UnwindSafe
+    );
+    assert_not_impl_any!(LevelZeroDeviceError:
+        Binary, Default, Deref, Drop, IntoIterator, LowerExp, LowerHex, Octal,
+        PartialOrd, Pointer, Read, UpperExp, UpperHex, fmt::Write, io::Write
+    );
+}
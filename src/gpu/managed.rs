@@ -0,0 +1,322 @@
+//! GPUs exposed by vendor management libraries
+//!
+//! When hwloc is built with support for NVIDIA's NVML or AMD's `ROCm` SMI
+//! (RSMI) library, it exposes each GPU managed by these libraries as a [`GPU`]
+//! OS device called `nvml<index>` or `rsmi<index>`, whose parent is the GPU's
+//! PCI device, and which carries identification data like the GPU's UUID and
+//! serial number as textual info attributes.
+//!
+//! The [`ManagedGpu`] type defined in this module wraps these OS devices, so
+//! that GPUs can be selected across vendors without string-matching object
+//! names and info attribute keys by hand. Since it only relies on topology
+//! data, it does not require the management libraries at link time.
+//!
+//! [`GPU`]: OSDeviceType::GPU
+
+use crate::{
+    object::{
+        attributes::ObjectAttributes,
+        types::{OSDeviceType, ObjectType},
+        TopologyObject,
+    },
+    topology::Topology,
+};
+use derive_more::Display;
+#[allow(unused)]
+#[cfg(test)]
+use similar_asserts::assert_eq;
+use std::iter::FusedIterator;
+
+/// # GPUs exposed by vendor management libraries
+///
+/// This functionality is unique to the Rust hwloc bindings, though
+/// [`nvml_device()`] and [`rsmi_device()`] are respectively equivalent to
+/// hwloc's `hwloc_nvml_get_device_osdev_by_index()` and
+/// `hwloc_rsmi_get_device_osdev_by_index()`.
+///
+/// [`nvml_device()`]: Topology::nvml_device()
+/// [`rsmi_device()`]: Topology::rsmi_device()
+impl Topology {
+    /// Enumerate the GPUs exposed by NVIDIA's NVML library
+    pub fn nvml_devices(&self) -> impl FusedIterator<Item = ManagedGpu<'_>> + Clone {
+        self.managed_gpus(GpuManagementLibrary::Nvml)
+    }
+
+    /// Enumerate the GPUs exposed by AMD's `ROCm` SMI library
+    pub fn rsmi_devices(&self) -> impl FusedIterator<Item = ManagedGpu<'_>> + Clone {
+        self.managed_gpus(GpuManagementLibrary::Rsmi)
+    }
+
+    /// Enumerate the GPUs exposed by a certain management library
+    pub fn managed_gpus(
+        &self,
+        library: GpuManagementLibrary,
+    ) -> impl FusedIterator<Item = ManagedGpu<'_>> + Clone {
+        self.os_devices()
+            .filter_map(ManagedGpu::from_osdev)
+            .filter(move |gpu| gpu.library() == library)
+    }
+
+    /// GPU with NVML device index `index`, if any
+    #[doc(alias = "hwloc_nvml_get_device_osdev_by_index")]
+    pub fn nvml_device(&self, index: usize) -> Option<ManagedGpu<'_>> {
+        self.nvml_devices().find(|gpu| gpu.index() == index)
+    }
+
+    /// GPU with RSMI device index `index`, if any
+    #[doc(alias = "hwloc_rsmi_get_device_osdev_by_index")]
+    pub fn rsmi_device(&self, index: usize) -> Option<ManagedGpu<'_>> {
+        self.rsmi_devices().find(|gpu| gpu.index() == index)
+    }
+}
+
+/// GPU management library
+#[derive(Copy, Clone, Debug, Display, Eq, Hash, PartialEq)]
+pub enum GpuManagementLibrary {
+    /// NVIDIA Management Library (NVML)
+    #[display(fmt = "NVML")]
+    Nvml,
+
+    /// AMD `ROCm` System Management Interface (RSMI)
+    #[display(fmt = "RSMI")]
+    Rsmi,
+}
+//
+impl GpuManagementLibrary {
+    /// Prefix of the names of OS devices exposed by this library
+    fn name_prefix(self) -> &'static str {
+        match self {
+            Self::Nvml => "nvml",
+            Self::Rsmi => "rsmi",
+        }
+    }
+
+    /// Info attribute key of the GPU's UUID
+    fn uuid_key(self) -> &'static str {
+        match self {
+            Self::Nvml => "NVIDIAUUID",
+            Self::Rsmi => "AMDUUID",
+        }
+    }
+
+    /// Info attribute key of the GPU's serial number
+    fn serial_key(self) -> &'static str {
+        match self {
+            Self::Nvml => "NVIDIASerial",
+            Self::Rsmi => "AMDSerial",
+        }
+    }
+}
+
+/// GPU exposed by a vendor management library
+///
+/// This is a thin wrapper around the corresponding [`GPU`] OS device, which
+/// can be accessed using [`ManagedGpu::object()`].
+///
+/// [`GPU`]: OSDeviceType::GPU
+#[derive(Copy, Clone, Debug)]
+pub struct ManagedGpu<'topology> {
+    /// OS device that describes this GPU
+    object: &'topology TopologyObject,
+
+    /// Management library that exposes this GPU
+    library: GpuManagementLibrary,
+
+    /// Device index within the management library
+    index: usize,
+}
+//
+impl<'topology> ManagedGpu<'topology> {
+    /// Interpret an OS device as a GPU exposed by a management library
+    ///
+    /// Returns `None` if `object` is not a [`GPU`] OS device called
+    /// `nvml<index>` or `rsmi<index>`.
+    ///
+    /// [`GPU`]: OSDeviceType::GPU
+    pub fn from_osdev(object: &'topology TopologyObject) -> Option<Self> {
+        let Some(ObjectAttributes::OSDevice(attr)) = object.attributes() else {
+            return None;
+        };
+        if attr.device_type() != OSDeviceType::GPU {
+            return None;
+        }
+        let name = object.name()?.to_str().ok()?;
+        [GpuManagementLibrary::Nvml, GpuManagementLibrary::Rsmi]
+            .into_iter()
+            .find_map(|library| {
+                let index = name.strip_prefix(library.name_prefix())?.parse().ok()?;
+                Some(Self {
+                    object,
+                    library,
+                    index,
+                })
+            })
+    }
+
+    /// Find the GPU that a management library exposes for a PCI device
+    ///
+    /// Returns `None` if `pci` is not a PCI device, or if `library` does not
+    /// expose any GPU for it.
+    pub fn from_pci_device(
+        pci: &'topology TopologyObject,
+        library: GpuManagementLibrary,
+    ) -> Option<Self> {
+        if pci.object_type() != ObjectType::PCIDevice {
+            return None;
+        }
+        pci.io_children()
+            .filter_map(Self::from_osdev)
+            .find(|gpu| gpu.library == library)
+    }
+
+    /// OS device that describes this GPU
+    pub fn object(&self) -> &'topology TopologyObject {
+        self.object
+    }
+
+    /// Management library that exposes this GPU
+    pub fn library(&self) -> GpuManagementLibrary {
+        self.library
+    }
+
+    /// Device index within the management library
+    ///
+    /// This is the index that the management library's own API uses to
+    /// designate this GPU.
+    pub fn index(&self) -> usize {
+        self.index
+    }
+
+    /// PCI device of this GPU, if known
+    pub fn pci_device(&self) -> Option<&'topology TopologyObject> {
+        self.object
+            .parent()
+            .filter(|parent| parent.object_type() == ObjectType::PCIDevice)
+    }
+
+    /// Identification data of this GPU
+    pub fn identity(&self) -> GpuIdentity<'topology> {
+        let info = |key: &str| self.object.info(key).and_then(|value| value.to_str().ok());
+        GpuIdentity {
+            vendor: info("GPUVendor"),
+            model: info("GPUModel"),
+            uuid: info(self.library.uuid_key()),
+            serial: info(self.library.serial_key()),
+        }
+    }
+}
+
+/// Identification data of a [`ManagedGpu`]
+///
+/// Each field is `None` if the management library did not provide this
+/// information, or if it is not valid UTF-8.
+#[derive(Copy, Clone, Debug, Default, Eq, Hash, PartialEq)]
+pub struct GpuIdentity<'topology> {
+    /// Name of the GPU's vendor (`GPUVendor` info attribute)
+    pub vendor: Option<&'topology str>,
+
+    /// Name of the GPU model (`GPUModel` info attribute)
+    pub model: Option<&'topology str>,
+
+    /// UUID of the GPU (`NVIDIAUUID` or `AMDUUID` info attribute)
+    pub uuid: Option<&'topology str>,
+
+    /// Serial number of the GPU (`NVIDIASerial` or `AMDSerial` info attribute)
+    pub serial: Option<&'topology str>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::strategies::any_object;
+    use proptest::prelude::*;
+    #[allow(unused)]
+    use similar_asserts::assert_eq;
+    use static_assertions::{assert_impl_all, assert_not_impl_any};
+    use std::{
+        fmt::{
+            self, Binary, Debug, Display, LowerExp, LowerHex, Octal, Pointer, UpperExp, UpperHex,
+        },
+        hash::Hash,
+        io::{self, Read},
+        ops::Deref,
+        panic::UnwindSafe,
+        ptr,
+    };
+
+    // Check that public types in this module keep implementing all expected
+    // traits, in the interest of detecting future semver-breaking changes
+    assert_impl_all!(GpuManagementLibrary:
+        Copy, Debug, Display, Hash, Sized, Sync, Unpin, UnwindSafe
+    );
+    assert_not_impl_any!(GpuManagementLibrary:
+        Binary, Default, Deref, Drop, IntoIterator, LowerExp, LowerHex, Octal,
+        PartialOrd, Pointer, Read, UpperExp, UpperHex, fmt::Write, io::Write
+    );
+    assert_impl_all!(ManagedGpu<'static>:
+        Copy, Debug, Sized, Sync, Unpin, UnwindSafe
+    );
+    assert_not_impl_any!(ManagedGpu<'static>:
+        Binary, Default, Deref, Display, Drop, IntoIterator, LowerExp,
+        LowerHex, Octal, PartialEq, Pointer, Read, UpperExp, UpperHex,
+        fmt::Write, io::Write
+    );
+    assert_impl_all!(GpuIdentity<'static>:
+        Copy, Debug, Default, Hash, Sized, Sync, Unpin, UnwindSafe
+    );
+    assert_not_impl_any!(GpuIdentity<'static>:
+        Binary, Deref, Display, Drop, IntoIterator, LowerExp, LowerHex, Octal,
+        PartialOrd, Pointer, Read, UpperExp, UpperHex, fmt::Write, io::Write
+    );
+
+    /// Managed GPU lookups should be consistent with each other
+    #[test]
+    fn managed_gpus() {
+        let topology = Topology::test_instance();
+        for library in [GpuManagementLibrary::Nvml, GpuManagementLibrary::Rsmi] {
+            for gpu in topology.managed_gpus(library) {
+                assert_eq!(gpu.library(), library);
+                assert_eq!(
+                    gpu.object().name().unwrap().to_str().unwrap(),
+                    format!("{}{}", library.name_prefix(), gpu.index())
+                );
+                let by_index = match library {
+                    GpuManagementLibrary::Nvml => topology.nvml_device(gpu.index()),
+                    GpuManagementLibrary::Rsmi => topology.rsmi_device(gpu.index()),
+                };
+                assert!(ptr::eq(by_index.unwrap().object(), gpu.object()));
+                if let Some(pci) = gpu.pci_device() {
+                    let from_pci = ManagedGpu::from_pci_device(pci, library).unwrap();
+                    assert_eq!(from_pci.library(), library);
+                }
+            }
+        }
+        assert_eq!(
+            topology.nvml_devices().count(),
+            topology.managed_gpus(GpuManagementLibrary::Nvml).count()
+        );
+        assert_eq!(
+            topology.rsmi_devices().count(),
+            topology.managed_gpus(GpuManagementLibrary::Rsmi).count()
+        );
+    }
+
+    proptest! {
+        /// Only GPU OS devices with the right name should be managed GPUs
+        #[test]
+        fn from_osdev(obj in any_object()) {
+            let Some(gpu) = ManagedGpu::from_osdev(obj) else {
+                return Ok(());
+            };
+            prop_assert!(ptr::eq(gpu.object(), obj));
+            prop_assert_eq!(obj.object_type(), ObjectType::OSDevice);
+            let name = obj.name().unwrap().to_str().unwrap();
+            prop_assert!(name.starts_with(gpu.library().name_prefix()));
+            let identity = gpu.identity();
+            prop_assert_eq!(
+                identity.uuid,
+                obj.info(gpu.library().uuid_key()).and_then(|uuid| uuid.to_str().ok())
+            );
+        }
+    }
+}
@@ -0,0 +1,128 @@
+//! GPU and accelerator locality
+//!
+//! hwloc provides a set of interoperability headers (`hwloc/cudart.h`,
+//! `hwloc/opencl.h`...) that tell which CPUs are close to a device handle of
+//! some GPU programming API, and which topology objects describe this device.
+//! Since these headers only contain inline functions, they cannot be bound
+//! directly, and this module reimplements them in Rust instead.
+//!
+//! Each GPU programming API is supported by a dedicated submodule, which is
+//! enabled by the cargo feature of the same name and requires the
+//! corresponding runtime library to be available at link time.
+//!
+//! GPUs that hwloc discovered via vendor management libraries (NVML, RSMI) can
+//! also be enumerated and identified using the [`managed`] module, which does
+//! not have any such requirement.
+//!
+//! Most of this module's functionality is exposed via methods of the
+//! [`Topology`] struct. The module itself only hosts type definitions that are
+//! related to this functionality.
+
+#[cfg(feature = "cuda")]
+pub mod cuda;
+#[cfg(feature = "level-zero")]
+pub mod level_zero;
+pub mod managed;
+#[cfg(feature = "opencl")]
+pub mod opencl;
+
+#[cfg(any(feature = "cuda", feature = "opencl"))]
+use crate::object::types::OSDeviceType;
+#[cfg(all(
+    doc,
+    not(any(feature = "cuda", feature = "level-zero", feature = "opencl"))
+))]
+use crate::topology::Topology;
+#[cfg(any(feature = "cuda", feature = "level-zero", feature = "opencl"))]
+use crate::{
+    cpu::cpuset::CpuSet,
+    object::{
+        attributes::{ObjectAttributes, PCIDomain},
+        TopologyObject,
+    },
+    topology::Topology,
+};
+#[allow(unused)]
+#[cfg(test)]
+use similar_asserts::assert_eq;
+
+/// CPUs that are close to the PCI device with a certain bus ID
+///
+/// On Linux, this is read from the device's `local_cpus` sysfs file. If this
+/// fails, or on other operating systems, the complete cpuset of the topology
+/// is returned, as hwloc's interoperability headers do.
+///
+/// The caller must have checked that `topology` describes the current system.
+#[allow(unused_variables)]
+#[cfg(any(feature = "cuda", feature = "level-zero", feature = "opencl"))]
+pub(crate) fn pci_device_cpuset(
+    topology: &Topology,
+    domain: PCIDomain,
+    bus_id: u8,
+    bus_device: u8,
+    function: u8,
+) -> CpuSet {
+    #[cfg(target_os = "linux")]
+    {
+        let path = format!(
+            "/sys/bus/pci/devices/{domain:04x}:{bus_id:02x}:{bus_device:02x}.{function:x}/local_cpus"
+        );
+        if let Ok(cpuset) = topology.read_path_as_cpumask(path) {
+            if !cpuset.is_empty() {
+                return cpuset;
+            }
+        }
+    }
+    topology.complete_cpuset().clone_target()
+}
+
+/// Find the co-processor OS device with a certain name
+///
+/// This is how hwloc's interoperability headers find the OS device associated
+/// with a device handle, e.g. `cuda0` for the first CUDA device.
+#[cfg(any(feature = "cuda", feature = "opencl"))]
+pub(crate) fn coprocessor_osdev_by_name<'topology>(
+    topology: &'topology Topology,
+    name: &str,
+) -> Option<&'topology TopologyObject> {
+    topology.os_devices().find(|obj| {
+        let Some(ObjectAttributes::OSDevice(attr)) = obj.attributes() else {
+            unreachable!("All OS devices should have OS device attributes")
+        };
+        attr.device_type() == OSDeviceType::CoProcessor
+            && obj
+                .name()
+                .is_some_and(|obj_name| obj_name.to_bytes() == name.as_bytes())
+    })
+}
+
+/// Find the OS device whose name starts with `name_prefix` and whose parent is
+/// the PCI device with a certain bus ID
+///
+/// This is how hwloc's interoperability headers find the OS device associated
+/// with a device handle when the handle does not carry an index.
+#[cfg(any(feature = "level-zero", feature = "opencl"))]
+pub(crate) fn pci_osdev_by_bus_id<'topology>(
+    topology: &'topology Topology,
+    name_prefix: &str,
+    domain: PCIDomain,
+    bus_id: u8,
+    bus_device: u8,
+    function: u8,
+) -> Option<&'topology TopologyObject> {
+    topology.os_devices().find(|obj| {
+        let has_prefix = obj
+            .name()
+            .is_some_and(|name| name.to_bytes().starts_with(name_prefix.as_bytes()));
+        let Some(ObjectAttributes::PCIDevice(pci)) =
+            obj.parent().and_then(TopologyObject::attributes)
+        else {
+            return false;
+        };
+        has_prefix
+            && pci.domain() == domain
+            && pci.bus_id() == bus_id
+            && pci.bus_device() == bus_device
+            && pci.function() == function
+    })
+}
@@ -0,0 +1,372 @@
+//! OpenCL device locality
+//!
+//! This module is the Rust equivalent of hwloc's `hwloc/opencl.h` header. It
+//! lets you find out which CPUs and topology objects are close to an OpenCL
+//! device, identified by its `cl_device_id` handle.
+//!
+//! The PCI bus ID of OpenCL devices is queried using the
+//! `cl_khr_pci_bus_info`, `cl_amd_device_attribute_query` or
+//! `cl_nv_device_attribute_query` extensions, at least one of which must be
+//! supported by the device's OpenCL implementation.
+//!
+//! This functionality is only available when the `opencl` cargo feature is
+//! enabled, and requires the OpenCL ICD loader library (`libOpenCL`) at link
+//! time.
+
+use crate::{
+    cpu::cpuset::CpuSet,
+    object::{attributes::PCIDomain, TopologyObject},
+    topology::Topology,
+};
+#[allow(unused)]
+#[cfg(test)]
+use similar_asserts::assert_eq;
+use std::{
+    ffi::{c_int, c_uint, c_void},
+    mem::{self, MaybeUninit},
+    ptr,
+};
+use thiserror::Error;
+
+/// OpenCL device handle (`cl_device_id`)
+pub type ClDeviceId = *mut c_void;
+
+/// # OpenCL device locality
+///
+/// These functions let you find out which CPUs and objects are close to an
+/// OpenCL device.
+//
+// --- Implementation details ---
+//
+// Upstream docs: https://hwloc.readthedocs.io/en/v2.9/group__hwlocality__opencl.html
+// Since these functions are inline functions in hwloc's headers, they had to be
+// reimplemented in Rust.
+impl Topology {
+    /// CPUs that are close to the OpenCL device `device`
+    ///
+    /// On Linux, this is the set of CPUs that the operating system reports as
+    /// local to the device's PCI slot. On other operating systems, or if that
+    /// information is unavailable, the complete cpuset of the topology is
+    /// returned.
+    ///
+    /// # Errors
+    ///
+    /// - [`ForeignSystem`] if the topology does not describe the system that
+    ///   the program is running on.
+    /// - [`NoPciBusId`] if the OpenCL implementation does not report the PCI
+    ///   bus ID of this device.
+    /// - [`BadPciId`] if the OpenCL implementation reported an invalid PCI bus
+    ///   ID.
+    ///
+    /// # Safety
+    ///
+    /// `device` must be a valid OpenCL device handle.
+    ///
+    /// [`BadPciId`]: OpenClDeviceError::BadPciId
+    /// [`ForeignSystem`]: OpenClDeviceError::ForeignSystem
+    /// [`NoPciBusId`]: OpenClDeviceError::NoPciBusId
+    #[doc(alias = "hwloc_opencl_get_device_cpuset")]
+    pub unsafe fn opencl_device_cpuset(
+        &self,
+        device: ClDeviceId,
+    ) -> Result<CpuSet, OpenClDeviceError> {
+        if !self.is_this_system() {
+            return Err(OpenClDeviceError::ForeignSystem);
+        }
+        // SAFETY: Per function precondition
+        let (domain, bus_id, bus_device, function) = unsafe { opencl_device_pci_ids(device)? };
+        Ok(super::pci_device_cpuset(
+            self, domain, bus_id, bus_device, function,
+        ))
+    }
+
+    /// OS device object that describes the OpenCL device `device`
+    ///
+    /// This is the OS device whose name starts with `opencl` and whose parent
+    /// is the PCI device of `device`. It is only present if hwloc was built
+    /// with OpenCL support and I/O device discovery is enabled. `None` is
+    /// returned if there is no such object.
+    ///
+    /// # Errors
+    ///
+    /// - [`NoPciBusId`] if the OpenCL implementation does not report the PCI
+    ///   bus ID of this device.
+    /// - [`BadPciId`] if the OpenCL implementation reported an invalid PCI bus
+    ///   ID.
+    ///
+    /// # Safety
+    ///
+    /// `device` must be a valid OpenCL device handle.
+    ///
+    /// [`BadPciId`]: OpenClDeviceError::BadPciId
+    /// [`NoPciBusId`]: OpenClDeviceError::NoPciBusId
+    #[doc(alias = "hwloc_opencl_get_device_osdev")]
+    pub unsafe fn opencl_device_osdev(
+        &self,
+        device: ClDeviceId,
+    ) -> Result<Option<&TopologyObject>, OpenClDeviceError> {
+        // SAFETY: Per function precondition
+        let (domain, bus_id, bus_device, function) = unsafe { opencl_device_pci_ids(device)? };
+        Ok(super::pci_osdev_by_bus_id(
+            self, "opencl", domain, bus_id, bus_device, function,
+        ))
+    }
+
+    /// OS device object that describes the OpenCL device with index `device`
+    /// within the OpenCL platform with index `platform`
+    ///
+    /// This is the co-processor OS device called `opencl<platform>d<device>`,
+    /// which is only present if hwloc was built with OpenCL support and I/O
+    /// device discovery is enabled. `None` is returned if there is no such
+    /// object.
+    ///
+    /// Unlike other functions of this module, this function does not call into
+    /// the OpenCL implementation.
+    #[doc(alias = "hwloc_opencl_get_device_osdev_by_index")]
+    pub fn opencl_device_osdev_by_index(
+        &self,
+        platform: usize,
+        device: usize,
+    ) -> Option<&TopologyObject> {
+        super::coprocessor_osdev_by_name(self, &format!("opencl{platform}d{device}"))
+    }
+}
+
+/// Error while querying the locality of an OpenCL device
+#[derive(Copy, Clone, Debug, Eq, Error, Hash, PartialEq)]
+pub enum OpenClDeviceError {
+    /// The OpenCL implementation does not report the PCI bus ID of the device
+    ///
+    /// This happens when none of the OpenCL extensions that expose it is
+    /// supported, or when the device is not a PCI device.
+    #[error("OpenCL implementation doesn't report the device's PCI bus ID")]
+    NoPciBusId,
+
+    /// The OpenCL implementation reported a PCI bus ID component that is out
+    /// of range
+    #[error("OpenCL implementation reported an invalid PCI bus ID component {0}")]
+    BadPciId(c_uint),
+
+    /// The topology does not describe the system that the program is running
+    /// on, so its CPUs cannot be associated with local OpenCL devices
+    #[error("topology doesn't describe the system the program is running on")]
+    ForeignSystem,
+}
+
+/// Query the PCI domain, bus, device and function IDs of an OpenCL device
+///
+/// # Safety
+///
+/// `device` must be a valid OpenCL device handle.
+unsafe fn opencl_device_pci_ids(
+    device: ClDeviceId,
+) -> Result<(PCIDomain, u8, u8, u8), OpenClDeviceError> {
+    /// Query a fixed-size OpenCL device property
+    ///
+    /// # Safety
+    ///
+    /// - `device` must be a valid OpenCL device handle.
+    /// - `T` must be the type of the `param` property.
+    unsafe fn device_info<T: Copy>(device: ClDeviceId, param: c_uint) -> Option<T> {
+        let mut value = MaybeUninit::<T>::uninit();
+        // SAFETY: - device is valid per function precondition
+        //         - value is a valid output location of the specified size
+        //         - The returned size is not needed and can thus be null
+        let result = unsafe {
+            clGetDeviceInfo(
+                device,
+                param,
+                mem::size_of::<T>(),
+                value.as_mut_ptr().cast::<c_void>(),
+                ptr::null_mut(),
+            )
+        };
+        // SAFETY: OpenCL initializes value when it reports success, and T
+        //         is the type of this property per function precondition
+        (result == CL_SUCCESS).then(|| unsafe { value.assume_init() })
+    }
+
+    // Translate a raw PCI bus ID into hwloc's format
+    let bad_id = |id: c_uint| OpenClDeviceError::BadPciId(id);
+    let make_ids = |domain: c_uint, bus: c_uint, dev: c_uint, func: c_uint| {
+        Ok((
+            PCIDomain::try_from(domain).map_err(|_| bad_id(domain))?,
+            u8::try_from(bus).map_err(|_| bad_id(bus))?,
+            u8::try_from(dev).map_err(|_| bad_id(dev))?,
+            u8::try_from(func).map_err(|_| bad_id(func))?,
+        ))
+    };
+
+    // Try the standard Khronos extension first
+    let khr_info =
+        // SAFETY: device is valid per function precondition, and this property
+        //         has the specified type
+        unsafe { device_info::<ClPciBusInfoKhr>(device, CL_DEVICE_PCI_BUS_INFO_KHR) };
+    if let Some(info) = khr_info {
+        return make_ids(
+            info.pci_domain,
+            info.pci_bus,
+            info.pci_device,
+            info.pci_function,
+        );
+    }
+
+    // Then the AMD extension, which does not report PCI domains
+    let amd_topology =
+        // SAFETY: device is valid per function precondition, and this property
+        //         has the specified type
+        unsafe { device_info::<ClDeviceTopologyAmd>(device, CL_DEVICE_TOPOLOGY_AMD) };
+    if let Some(topo) = amd_topology {
+        if topo.ty == CL_DEVICE_TOPOLOGY_TYPE_PCIE_AMD {
+            let byte = c_uint::from;
+            return make_ids(0, byte(topo.bus), byte(topo.device), byte(topo.function));
+        }
+    }
+
+    // Finally the NVidia extension, which packs device and function together
+    // SAFETY: device is valid per function precondition, and these properties
+    //         have the specified type
+    unsafe {
+        let Some(bus) = device_info::<c_uint>(device, CL_DEVICE_PCI_BUS_ID_NV) else {
+            return Err(OpenClDeviceError::NoPciBusId);
+        };
+        let Some(slot) = device_info::<c_uint>(device, CL_DEVICE_PCI_SLOT_ID_NV) else {
+            return Err(OpenClDeviceError::NoPciBusId);
+        };
+        let domain = device_info::<c_uint>(device, CL_DEVICE_PCI_DOMAIN_ID_NV).unwrap_or(0);
+        make_ids(domain, bus & 0xff, slot >> 3, slot & 7)
+    }
+}
+
+/// `CL_SUCCESS` error code
+const CL_SUCCESS: c_int = 0;
+
+/// `CL_DEVICE_PCI_BUS_INFO_KHR` device property (`cl_khr_pci_bus_info`)
+const CL_DEVICE_PCI_BUS_INFO_KHR: c_uint = 0x410F;
+
+/// `CL_DEVICE_TOPOLOGY_AMD` device property (`cl_amd_device_attribute_query`)
+const CL_DEVICE_TOPOLOGY_AMD: c_uint = 0x4037;
+
+/// `CL_DEVICE_TOPOLOGY_TYPE_PCIE_AMD` device topology type
+const CL_DEVICE_TOPOLOGY_TYPE_PCIE_AMD: c_uint = 1;
+
+/// `CL_DEVICE_PCI_BUS_ID_NV` device property (`cl_nv_device_attribute_query`)
+const CL_DEVICE_PCI_BUS_ID_NV: c_uint = 0x4008;
+
+/// `CL_DEVICE_PCI_SLOT_ID_NV` device property (`cl_nv_device_attribute_query`)
+const CL_DEVICE_PCI_SLOT_ID_NV: c_uint = 0x4009;
+
+/// `CL_DEVICE_PCI_DOMAIN_ID_NV` device property (`cl_nv_device_attribute_query`)
+const CL_DEVICE_PCI_DOMAIN_ID_NV: c_uint = 0x400A;
+
+/// `cl_device_pci_bus_info_khr` struct
+#[derive(Copy, Clone)]
+#[repr(C)]
+struct ClPciBusInfoKhr {
+    /// PCI domain
+    pci_domain: c_uint,
+
+    /// PCI bus
+    pci_bus: c_uint,
+
+    /// PCI device
+    pci_device: c_uint,
+
+    /// PCI function
+    pci_function: c_uint,
+}
+
+/// PCI Express variant of `cl_device_topology_amd`
+///
+/// The C struct uses `cl_char` fields, which are reinterpreted as unsigned
+/// here as hwloc does.
+#[derive(Copy, Clone)]
+#[repr(C)]
+struct ClDeviceTopologyAmd {
+    /// Topology type, should be `CL_DEVICE_TOPOLOGY_TYPE_PCIE_AMD`
+    ty: c_uint,
+
+    /// Padding
+    unused: [u8; 17],
+
+    /// PCI bus
+    bus: u8,
+
+    /// PCI device
+    device: u8,
+
+    /// PCI function
+    function: u8,
+}
+
+#[link(name = "OpenCL")]
+extern "C" {
+    /// Query a property of an OpenCL device
+    #[must_use]
+    fn clGetDeviceInfo(
+        device: ClDeviceId,
+        param_name: c_uint,
+        param_value_size: usize,
+        param_value: *mut c_void,
+        param_value_size_ret: *mut usize,
+    ) -> c_int;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[allow(unused)]
+    use similar_asserts::assert_eq;
+    use static_assertions::{assert_impl_all, assert_not_impl_any};
+    use std::{
+        error::Error,
+        fmt::{self, Binary, LowerExp, LowerHex, Octal, Pointer, UpperExp, UpperHex},
+        hash::Hash,
+        io::{self, Read},
+        ops::Deref,
+        panic::UnwindSafe,
+    };
+
+    // Check that public types in this module keep implementing all expected
+    // traits, in the interest of detecting future semver-breaking changes
+    assert_impl_all!(OpenClDeviceError:
+        Copy, Error, Hash, Sized, Sync, Unpin, UnwindSafe
+    );
+    assert_not_impl_any!(OpenClDeviceError:
+        Binary, Default, Deref, Drop, IntoIterator, LowerExp, LowerHex, Octal,
+        PartialOrd, Pointer, Read, UpperExp, UpperHex, fmt::Write, io::Write
+    );
+
+    /// The AMD topology struct should have the same layout as in C
+    #[test]
+    fn amd_topology_layout() {
+        assert_eq!(mem::size_of::<ClDeviceTopologyAmd>(), 24);
+    }
+
+    /// OpenCL OS devices should be found by name
+    #[test]
+    fn opencl_device_osdev_by_index() {
+        let topology = Topology::test_instance();
+        for obj in topology.os_devices() {
+            let Some(name) = obj.name().and_then(|name| name.to_str().ok()) else {
+                continue;
+            };
+            let Some((platform, device)) = name
+                .strip_prefix("opencl")
+                .and_then(|suffix| suffix.split_once('d'))
+            else {
+                continue;
+            };
+            let (Ok(platform), Ok(device)) = (platform.parse(), device.parse()) else {
+                continue;
+            };
+            let found = topology
+                .opencl_device_osdev_by_index(platform, device)
+                .unwrap();
+            assert_eq!(found.name(), obj.name());
+        }
+        assert!(topology
+            .opencl_device_osdev_by_index(usize::MAX, usize::MAX)
+            .is_none());
+    }
+}
@@ -4,12 +4,17 @@
 //! information in a key-value layout. This module provides an interface to
 //! this information.
 
-use crate::ffi::{self, string::LibcString, transparent::TransparentNewtype};
+use crate::{
+    ffi::{self, string::LibcString, transparent::TransparentNewtype},
+    object::TopologyObject,
+};
 use hwlocality_sys::hwloc_info_s;
 #[allow(unused)]
 #[cfg(test)]
 use similar_asserts::assert_eq;
-use std::{ffi::CStr, fmt, hash::Hash};
+use std::{borrow::Cow, ffi::CStr, fmt, hash::Hash, str::Utf8Error};
+#[cfg(unix)]
+use std::{ffi::OsStr, os::unix::ffi::OsStrExt};
 
 /// Textual key-value information
 ///
@@ -74,6 +79,67 @@ impl TextualInfo {
         //           from &self, which itself is derived from &Topology
         unsafe { ffi::deref_str(&self.0.value) }.expect("Infos should have values")
     }
+
+    /// Name as raw bytes, without the trailing NUL
+    ///
+    /// hwloc does not guarantee that info names are valid UTF-8, so this is
+    /// the most general way to handle them.
+    pub fn name_bytes(&self) -> &[u8] {
+        self.name().to_bytes()
+    }
+
+    /// Value as raw bytes, without the trailing NUL
+    ///
+    /// hwloc does not guarantee that info values are valid UTF-8, as they
+    /// sometimes come from vendor firmware, so this is the most general way to
+    /// handle them.
+    pub fn value_bytes(&self) -> &[u8] {
+        self.value().to_bytes()
+    }
+
+    /// Name as an OS string
+    #[cfg(unix)]
+    pub fn name_os(&self) -> &OsStr {
+        OsStr::from_bytes(self.name_bytes())
+    }
+
+    /// Value as an OS string
+    #[cfg(unix)]
+    pub fn value_os(&self) -> &OsStr {
+        OsStr::from_bytes(self.value_bytes())
+    }
+
+    /// Name as a UTF-8 string
+    ///
+    /// # Errors
+    ///
+    /// - [`Utf8Error`] if the name is not valid UTF-8. Use
+    ///   [`name_lossy()`](Self::name_lossy) or
+    ///   [`name_bytes()`](Self::name_bytes) if you need to handle this case.
+    pub fn name_str(&self) -> Result<&str, Utf8Error> {
+        self.name().to_str()
+    }
+
+    /// Value as a UTF-8 string
+    ///
+    /// # Errors
+    ///
+    /// - [`Utf8Error`] if the value is not valid UTF-8. Use
+    ///   [`value_lossy()`](Self::value_lossy) or
+    ///   [`value_bytes()`](Self::value_bytes) if you need to handle this case.
+    pub fn value_str(&self) -> Result<&str, Utf8Error> {
+        self.value().to_str()
+    }
+
+    /// Name as a UTF-8 string, with invalid sequences replaced by `�`
+    pub fn name_lossy(&self) -> Cow<'_, str> {
+        self.name().to_string_lossy()
+    }
+
+    /// Value as a UTF-8 string, with invalid sequences replaced by `�`
+    pub fn value_lossy(&self) -> Cow<'_, str> {
+        self.value().to_string_lossy()
+    }
 }
 
 impl fmt::Debug for TextualInfo {
@@ -111,10 +177,224 @@ unsafe impl TransparentNewtype for TextualInfo {
     type Inner = hwloc_info_s;
 }
 
+/// Typed accessors to the standard info attributes of a [`TopologyObject`]
+///
+/// hwloc documents a set of [well-known info attribute
+/// names](https://hwloc.readthedocs.io/en/v2.9/attributes.html#attributes_info)
+/// which it uses to report things like the name of the CPU model or the UUID
+/// of a GPU. This type exposes them through one accessor per attribute, so
+/// that you do not need to spell out attribute names as strings.
+///
+/// Each accessor returns `None` if the object does not have the attribute, or
+/// if its value is not valid UTF-8 or cannot be parsed into the expected type.
+/// Use [`TopologyObject::info()`] if you need to handle these cases.
+///
+/// You can get this using [`TopologyObject::standard_info()`].
+#[derive(Copy, Clone, Debug)]
+pub struct StandardInfo<'object>(&'object TopologyObject);
+//
+/// Generate accessors to standard textual info attributes
+macro_rules! standard_info_accessors {
+    (
+        $(
+            $(#[$attr:meta])*
+            $accessor:ident: $ty:ty = $key:literal;
+        )*
+    ) => {
+        $(
+            $(#[$attr])*
+            #[doc = ""]
+            #[doc = concat!("This is the `", $key, "` info attribute.")]
+            pub fn $accessor(self) -> Option<$ty> {
+                self.parse($key)
+            }
+        )*
+    };
+}
+//
+impl<'object> StandardInfo<'object> {
+    /// Expose the standard info attributes of a topology object
+    pub(crate) fn new(object: &'object TopologyObject) -> Self {
+        Self(object)
+    }
+
+    /// Object whose info attributes are being queried
+    pub fn object(self) -> &'object TopologyObject {
+        self.0
+    }
+
+    /// Value of the info attribute with name `key`, as a string
+    fn str(self, key: &str) -> Option<&'object str> {
+        self.0.info(key).and_then(|value| value.to_str().ok())
+    }
+
+    /// Value of the info attribute with name `key`, parsed into some type
+    fn parse<T: FromInfoValue<'object>>(self, key: &str) -> Option<T> {
+        self.str(key).and_then(T::from_info_value)
+    }
+
+    standard_info_accessors! {
+        // --- Root object ---
+
+        /// Name of the hwloc backend that discovered the topology (e.g. `Linux`)
+        backend: &'object str = "Backend";
+
+        /// Name of the operating system (e.g. `Linux`)
+        os_name: &'object str = "OSName";
+
+        /// Release of the operating system (e.g. `6.1.0-13-amd64`)
+        os_release: &'object str = "OSRelease";
+
+        /// Version of the operating system
+        os_version: &'object str = "OSVersion";
+
+        /// Name of the host
+        host_name: &'object str = "HostName";
+
+        /// Architecture of the host (e.g. `x86_64`)
+        architecture: &'object str = "Architecture";
+
+        /// Version of hwloc that discovered the topology
+        hwloc_version: &'object str = "hwlocVersion";
+
+        /// Name of the process that discovered the topology
+        process_name: &'object str = "ProcessName";
+
+        /// Linux control group that the discovering process belonged to
+        linux_cgroup: &'object str = "LinuxCgroup";
+
+        // --- Machine object (DMI data) ---
+
+        /// Product name from the DMI tables
+        dmi_product_name: &'object str = "DMIProductName";
+
+        /// Product version from the DMI tables
+        dmi_product_version: &'object str = "DMIProductVersion";
+
+        /// Board vendor from the DMI tables
+        dmi_board_vendor: &'object str = "DMIBoardVendor";
+
+        /// Board name from the DMI tables
+        dmi_board_name: &'object str = "DMIBoardName";
+
+        /// Board version from the DMI tables
+        dmi_board_version: &'object str = "DMIBoardVersion";
+
+        /// Board asset tag from the DMI tables
+        dmi_board_asset_tag: &'object str = "DMIBoardAssetTag";
+
+        /// Chassis vendor from the DMI tables
+        dmi_chassis_vendor: &'object str = "DMIChassisVendor";
+
+        /// Chassis type from the DMI tables
+        dmi_chassis_type: &'object str = "DMIChassisType";
+
+        /// Chassis version from the DMI tables
+        dmi_chassis_version: &'object str = "DMIChassisVersion";
+
+        /// Chassis asset tag from the DMI tables
+        dmi_chassis_asset_tag: &'object str = "DMIChassisAssetTag";
+
+        /// BIOS vendor from the DMI tables
+        dmi_bios_vendor: &'object str = "DMIBIOSVendor";
+
+        /// BIOS version from the DMI tables
+        dmi_bios_version: &'object str = "DMIBIOSVersion";
+
+        /// BIOS date from the DMI tables
+        dmi_bios_date: &'object str = "DMIBIOSDate";
+
+        /// System vendor from the DMI tables
+        dmi_sys_vendor: &'object str = "DMISysVendor";
+
+        // --- Package and PU objects ---
+
+        /// Vendor of the CPU (e.g. `GenuineIntel`)
+        cpu_vendor: &'object str = "CPUVendor";
+
+        /// Name of the CPU model
+        cpu_model: &'object str = "CPUModel";
+
+        /// Numerical identifier of the CPU model
+        cpu_model_number: u32 = "CPUModelNumber";
+
+        /// Numerical identifier of the CPU family
+        cpu_family_number: u32 = "CPUFamilyNumber";
+
+        /// Stepping (revision) of the CPU
+        cpu_stepping: u32 = "CPUStepping";
+
+        // --- Cache objects ---
+
+        /// Truth that a cache is inclusive of the caches below it
+        inclusive: bool = "Inclusive";
+
+        // --- OS devices ---
+
+        /// Vendor of a GPU
+        gpu_vendor: &'object str = "GPUVendor";
+
+        /// Model of a GPU
+        gpu_model: &'object str = "GPUModel";
+
+        /// UUID of an NVIDIA GPU
+        nvidia_uuid: &'object str = "NVIDIAUUID";
+
+        /// Serial number of an NVIDIA GPU
+        nvidia_serial: &'object str = "NVIDIASerial";
+
+        /// UUID of an AMD GPU
+        amd_uuid: &'object str = "AMDUUID";
+
+        /// Serial number of an AMD GPU
+        amd_serial: &'object str = "AMDSerial";
+
+        /// Hardware address of a network interface
+        address: &'object str = "Address";
+
+        /// Port number of a network interface
+        port: u32 = "Port";
+
+        /// Node GUID of an InfiniBand adapter
+        node_guid: &'object str = "NodeGUID";
+
+        /// System image GUID of an InfiniBand adapter
+        sys_image_guid: &'object str = "SysImageGUID";
+    }
+}
+
+/// Conversion from the textual value of a standard info attribute
+trait FromInfoValue<'object>: Sized {
+    /// Perform the conversion, if possible
+    fn from_info_value(value: &'object str) -> Option<Self>;
+}
+//
+impl<'object> FromInfoValue<'object> for &'object str {
+    fn from_info_value(value: &'object str) -> Option<Self> {
+        Some(value)
+    }
+}
+//
+impl FromInfoValue<'_> for bool {
+    fn from_info_value(value: &str) -> Option<Self> {
+        match value.trim() {
+            "1" => Some(true),
+            "0" => Some(false),
+            _ => None,
+        }
+    }
+}
+//
+impl FromInfoValue<'_> for u32 {
+    fn from_info_value(value: &str) -> Option<Self> {
+        value.trim().parse().ok()
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::ffi::transparent::AsNewtype;
+    use crate::{ffi::transparent::AsNewtype, topology::Topology};
     use proptest::prelude::*;
     #[allow(unused)]
     use similar_asserts::assert_eq;
@@ -139,6 +419,69 @@ mod tests {
         LowerExp, LowerHex, Octal, PartialOrd, Pointer, Read, ToOwned,
         UpperExp, UpperHex, fmt::Write, io::Write
     );
+    assert_impl_all!(StandardInfo<'static>:
+        Copy, Debug, Sized, Sync, Unpin, UnwindSafe
+    );
+    assert_not_impl_any!(StandardInfo<'static>:
+        Binary, Default, Deref, Display, Drop, IntoIterator, LowerExp,
+        LowerHex, Octal, PartialEq, Pointer, Read, UpperExp, UpperHex,
+        fmt::Write, io::Write
+    );
+
+    /// Check that standard info accessors match raw info lookups
+    #[test]
+    fn standard_info() {
+        for obj in Topology::test_objects() {
+            let info = obj.standard_info();
+            assert!(std::ptr::eq(info.object(), *obj));
+            let raw = |key: &str| obj.info(key).and_then(|value| value.to_str().ok());
+            assert_eq!(info.backend(), raw("Backend"));
+            assert_eq!(info.architecture(), raw("Architecture"));
+            assert_eq!(info.dmi_product_name(), raw("DMIProductName"));
+            assert_eq!(info.cpu_model(), raw("CPUModel"));
+            assert_eq!(
+                info.cpu_family_number(),
+                raw("CPUFamilyNumber").and_then(|value| value.trim().parse().ok())
+            );
+            assert_eq!(
+                info.inclusive(),
+                raw("Inclusive").and_then(|value| match value.trim() {
+                    "0" => Some(false),
+                    "1" => Some(true),
+                    _ => None,
+                })
+            );
+            assert_eq!(info.gpu_vendor(), raw("GPUVendor"));
+            assert_eq!(info.nvidia_uuid(), raw("NVIDIAUUID"));
+        }
+        let root = Topology::test_instance().root_object();
+        assert!(root.standard_info().backend().is_some());
+    }
+
+    /// Check accessor behavior on infos that are not valid UTF-8
+    #[test]
+    fn non_utf8() {
+        let name = CString::new(b"Vendor\xff".to_vec()).unwrap();
+        let value = CString::new(b"Firmware \xc3\x28 v1".to_vec()).unwrap();
+        let raw_info = hwloc_info_s {
+            name: name.as_ptr().cast_mut(),
+            value: value.as_ptr().cast_mut(),
+        };
+        // SAFETY: raw_info was built from known-good data, which outlives it
+        let info: &TextualInfo = unsafe { (&raw_info).as_newtype() };
+
+        assert_eq!(info.name_bytes(), b"Vendor\xff");
+        assert_eq!(info.value_bytes(), b"Firmware \xc3\x28 v1");
+        #[cfg(unix)]
+        {
+            assert_eq!(info.name_os().as_bytes(), b"Vendor\xff");
+            assert_eq!(info.value_os().as_bytes(), b"Firmware \xc3\x28 v1");
+        }
+        info.name_str().unwrap_err();
+        info.value_str().unwrap_err();
+        assert_eq!(info.name_lossy(), "Vendor\u{FFFD}");
+        assert_eq!(info.value_lossy(), "Firmware \u{FFFD}( v1");
+    }
 
     proptest! {
         #[test]
@@ -158,6 +501,17 @@ mod tests {
             let value_c = CString::new(value.as_ref()).unwrap();
             prop_assert_eq!(&CString::from(info.name()), &name_c);
             prop_assert_eq!(&CString::from(info.value()), &value_c);
+            prop_assert_eq!(info.name_bytes(), name_c.as_bytes());
+            prop_assert_eq!(info.value_bytes(), value_c.as_bytes());
+            #[cfg(unix)]
+            {
+                prop_assert_eq!(info.name_os().as_bytes(), name_c.as_bytes());
+                prop_assert_eq!(info.value_os().as_bytes(), value_c.as_bytes());
+            }
+            prop_assert_eq!(info.name_str(), name_c.to_str());
+            prop_assert_eq!(info.value_str(), value_c.to_str());
+            prop_assert_eq!(info.name_lossy(), name_c.to_string_lossy());
+            prop_assert_eq!(info.value_lossy(), value_c.to_string_lossy());
             prop_assert_eq!(
                 format!("{info:#?}"),
                 format!(
@@ -0,0 +1,456 @@
+//! Linux cgroup v2 resource limits
+//!
+//! Containerized programs usually run in a [cgroup v2] that restricts which
+//! CPUs and NUMA nodes they may use, and how much memory they may allocate.
+//! hwloc takes CPU and NUMA node restrictions into account when computing
+//! [`Topology::allowed_cpuset()`] and [`Topology::allowed_nodeset()`], but
+//! only at the time where the topology is built, and only when the process
+//! is not bound more narrowly by other means.
+//!
+//! This module reads the effective limits of the current process' cgroup
+//! directly from the cgroup filesystem, so that they can be reconciled with a
+//! topology, e.g. by restricting the topology to them.
+//!
+//! [cgroup v2]: https://docs.kernel.org/admin-guide/cgroup-v2.html
+
+#[cfg(feature = "hwloc-2_3_0")]
+use crate::topology::editor::{RestrictFlags, TopologyEditor};
+use crate::{
+    cpu::cpuset::CpuSet, errors::ParameterError, memory::nodeset::NodeSet, topology::Topology,
+};
+#[allow(unused)]
+#[cfg(test)]
+use similar_asserts::assert_eq;
+use std::{
+    fs, io,
+    path::{Path, PathBuf},
+};
+use thiserror::Error;
+
+/// # Linux cgroup v2 resource limits
+impl Topology {
+    /// CPUs of this topology that the current process' cgroup allows using
+    ///
+    /// This is the intersection of the topology's [`cpuset()`] with the
+    /// `cpuset.cpus.effective` of the current process' cgroup. If the cpuset
+    /// controller is not enabled in the cgroup hierarchy, the cgroup does not
+    /// restrict CPU usage and the topology's [`cpuset()`] is returned.
+    ///
+    /// # Errors
+    ///
+    /// - [`ForeignSystem`] if the topology does not describe the system that
+    ///   the program is running on.
+    /// - [`NotCgroupV2`] if the process is not managed by cgroup v2.
+    /// - [`InvalidList`] if the kernel emitted an unexpected CPU list.
+    /// - [`Io`] if the cgroup filesystem could not be read.
+    ///
+    /// [`cpuset()`]: Topology::cpuset()
+    /// [`ForeignSystem`]: CgroupError::ForeignSystem
+    /// [`InvalidList`]: CgroupError::InvalidList
+    /// [`Io`]: CgroupError::Io
+    /// [`NotCgroupV2`]: CgroupError::NotCgroupV2
+    pub fn cgroup_allowed_cpuset(&self) -> Result<CpuSet, CgroupError> {
+        if !self.is_this_system() {
+            return Err(CgroupError::ForeignSystem);
+        }
+        let mut cpuset = self.cpuset().clone_target();
+        if let Some(list) = read_effective(&current_cgroup_dir()?, "cpuset.cpus.effective")? {
+            cpuset &= CpuSet::from_list_string(&list)
+                .map_err(|_| CgroupError::InvalidList(list.clone()))?;
+        }
+        Ok(cpuset)
+    }
+
+    /// NUMA nodes of this topology that the current process' cgroup allows
+    /// using
+    ///
+    /// This is the intersection of the topology's [`nodeset()`] with the
+    /// `cpuset.mems.effective` of the current process' cgroup. If the cpuset
+    /// controller is not enabled in the cgroup hierarchy, the cgroup does not
+    /// restrict memory placement and the topology's [`nodeset()`] is returned.
+    ///
+    /// # Errors
+    ///
+    /// - [`ForeignSystem`] if the topology does not describe the system that
+    ///   the program is running on.
+    /// - [`NotCgroupV2`] if the process is not managed by cgroup v2.
+    /// - [`InvalidList`] if the kernel emitted an unexpected NUMA node list.
+    /// - [`Io`] if the cgroup filesystem could not be read.
+    ///
+    /// [`nodeset()`]: Topology::nodeset()
+    /// [`ForeignSystem`]: CgroupError::ForeignSystem
+    /// [`InvalidList`]: CgroupError::InvalidList
+    /// [`Io`]: CgroupError::Io
+    /// [`NotCgroupV2`]: CgroupError::NotCgroupV2
+    pub fn cgroup_allowed_nodeset(&self) -> Result<NodeSet, CgroupError> {
+        if !self.is_this_system() {
+            return Err(CgroupError::ForeignSystem);
+        }
+        let mut nodeset = self.nodeset().clone_target();
+        if let Some(list) = read_effective(&current_cgroup_dir()?, "cpuset.mems.effective")? {
+            nodeset &= NodeSet::from_list_string(&list)
+                .map_err(|_| CgroupError::InvalidList(list.clone()))?;
+        }
+        Ok(nodeset)
+    }
+}
+
+/// # Linux cgroup v2 resource limits
+#[cfg(feature = "hwloc-2_3_0")]
+impl TopologyEditor<'_> {
+    /// Restrict the topology to the CPUs and NUMA nodes that the current
+    /// process' cgroup allows using
+    ///
+    /// This restricts the topology to [`Topology::cgroup_allowed_cpuset()`],
+    /// then to [`Topology::cgroup_allowed_nodeset()`], using
+    /// [`TopologyEditor::restrict()`] with the specified `flags`.
+    ///
+    /// hwloc can only restrict a topology to either a CPU set or a NUMA node
+    /// set at a time, so this takes two separate restrictions. If the second
+    /// one fails, the topology remains restricted to the cgroup's CPUs.
+    ///
+    /// # Errors
+    ///
+    /// - [`NoOverlap`] if the cgroup does not allow using any CPU or NUMA
+    ///   node of the topology. The topology is not modified in this case.
+    /// - [`RestrictCpuSet`] if hwloc failed to restrict the topology to the
+    ///   cgroup's CPUs. The topology is not modified in this case.
+    /// - [`RestrictNodeSet`] if hwloc failed to restrict the topology to the
+    ///   cgroup's NUMA nodes, e.g. because restricting it to the cgroup's CPUs
+    ///   removed all of these NUMA nodes. The topology remains restricted to
+    ///   the cgroup's CPUs in this case.
+    /// - Other [`CgroupError`]s if the cgroup's limits could not be read, see
+    ///   [`Topology::cgroup_allowed_cpuset()`]. The topology is not modified
+    ///   in this case.
+    ///
+    /// [`NoOverlap`]: CgroupError::NoOverlap
+    /// [`RestrictCpuSet`]: CgroupError::RestrictCpuSet
+    /// [`RestrictNodeSet`]: CgroupError::RestrictNodeSet
+    pub fn restrict_to_cgroup(&mut self, flags: RestrictFlags) -> Result<(), CgroupError> {
+        let topology = self.topology();
+        let cpuset = topology.cgroup_allowed_cpuset()?;
+        let nodeset = topology.cgroup_allowed_nodeset()?;
+        if cpuset.is_empty() || nodeset.is_empty() {
+            return Err(CgroupError::NoOverlap);
+        }
+        if self.topology().cpuset() != cpuset {
+            self.restrict(&cpuset, flags)
+                .map_err(CgroupError::RestrictCpuSet)?;
+        }
+        let nodeset = nodeset & self.topology().nodeset();
+        if self.topology().nodeset() != nodeset {
+            self.restrict(&nodeset, flags)
+                .map_err(CgroupError::RestrictNodeSet)?;
+        }
+        Ok(())
+    }
+}
+
+/// Memory limit of the current process' cgroup, in bytes
+///
+/// This is the smallest `memory.max` of the current process' cgroup and its
+/// ancestors, or `None` if none of them limits memory usage.
+///
+/// # Errors
+///
+/// - [`NotCgroupV2`] if the process is not managed by cgroup v2.
+/// - [`InvalidList`] if the kernel emitted an unexpected memory limit.
+/// - [`Io`] if the cgroup filesystem could not be read.
+///
+/// [`InvalidList`]: CgroupError::InvalidList
+/// [`Io`]: CgroupError::Io
+/// [`NotCgroupV2`]: CgroupError::NotCgroupV2
+pub fn memory_limit() -> Result<Option<u64>, CgroupError> {
+    let (mount_point, cgroup_dir) = (cgroup2_mount_point()?, current_cgroup_dir()?);
+    let mut limit = None;
+    for dir in cgroup_dir
+        .ancestors()
+        .take_while(|dir| dir.starts_with(&mount_point))
+    {
+        let Some(max) = read_optional(&dir.join("memory.max"))? else {
+            continue;
+        };
+        if let Some(max) = parse_memory_max(&max)? {
+            limit = Some(limit.map_or(max, |limit: u64| limit.min(max)));
+        }
+    }
+    Ok(limit)
+}
+
+/// Error while querying cgroup v2 resource limits
+#[derive(Debug, Error)]
+pub enum CgroupError {
+    /// The topology does not describe the system that the program is running
+    /// on, so its resources cannot be related to the process' cgroup
+    #[error("topology doesn't describe the system the program is running on")]
+    ForeignSystem,
+
+    /// The current process is not managed by cgroup v2
+    ///
+    /// This happens on systems that only use the legacy cgroup v1 hierarchy,
+    /// or where the cgroup filesystem is not mounted.
+    #[error("process is not managed by cgroup v2")]
+    NotCgroupV2,
+
+    /// The kernel reported a CPU list, NUMA node list or memory limit that
+    /// could not be parsed
+    #[error("{0:?} is not a valid cgroup resource limit")]
+    InvalidList(String),
+
+    /// The cgroup does not allow using any CPU or NUMA node of the topology
+    #[error("cgroup doesn't allow using any CPU or NUMA node of the topology")]
+    NoOverlap,
+
+    /// hwloc failed to restrict the topology to the cgroup's CPUs
+    #[error("failed to restrict the topology to the cgroup's CPUs")]
+    RestrictCpuSet(#[source] ParameterError<CpuSet>),
+
+    /// hwloc failed to restrict the topology to the cgroup's NUMA nodes
+    #[error("failed to restrict the topology to the cgroup's NUMA nodes")]
+    RestrictNodeSet(#[source] ParameterError<NodeSet>),
+
+    /// Failed to read the cgroup filesystem
+    #[error("failed to read cgroup information")]
+    Io(#[from] io::Error),
+}
+
+/// Location of the current process' cgroup in the cgroup v2 filesystem
+fn current_cgroup_dir() -> Result<PathBuf, CgroupError> {
+    let mount = cgroup2_mount()?;
+    let cgroups = fs::read_to_string("/proc/self/cgroup")?;
+    let cgroup_path = parse_cgroup_path(&cgroups).ok_or(CgroupError::NotCgroupV2)?;
+    Ok(mount.cgroup_dir(cgroup_path))
+}
+
+/// Mount point of the cgroup v2 filesystem
+fn cgroup2_mount_point() -> Result<PathBuf, CgroupError> {
+    Ok(cgroup2_mount()?.mount_point)
+}
+
+/// Mount of the cgroup v2 filesystem
+fn cgroup2_mount() -> Result<Cgroup2Mount, CgroupError> {
+    let mountinfo = fs::read_to_string("/proc/self/mountinfo")?;
+    parse_cgroup2_mount(&mountinfo).ok_or(CgroupError::NotCgroupV2)
+}
+
+/// Mount of the cgroup v2 filesystem, as described by `/proc/<pid>/mountinfo`
+#[derive(Clone, Debug, Eq, PartialEq)]
+struct Cgroup2Mount {
+    /// Cgroup that is mounted at the mount point, e.g. `/docker/<id>` when a
+    /// container gets a view of its own cgroup subtree without a cgroup
+    /// namespace, or `/` when the whole hierarchy is mounted
+    root: PathBuf,
+
+    /// Location of the mount in the filesystem
+    mount_point: PathBuf,
+}
+//
+impl Cgroup2Mount {
+    /// Location of the cgroup with a certain path from `/proc/<pid>/cgroup`
+    ///
+    /// Cgroup paths are relative to the root of the cgroup hierarchy, so the
+    /// part that is covered by the mount's root must be stripped.
+    fn cgroup_dir(&self, cgroup_path: &str) -> PathBuf {
+        let cgroup_path = Path::new(cgroup_path);
+        let relative = cgroup_path
+            .strip_prefix(&self.root)
+            .or_else(|_| cgroup_path.strip_prefix("/"))
+            .unwrap_or(cgroup_path);
+        self.mount_point.join(relative)
+    }
+}
+
+/// Read a cgroup v2 `*.effective` file from a cgroup or its closest ancestor
+/// that has one
+///
+/// Returns `None` if no such file exists, which means that the corresponding
+/// controller is not enabled, or if it is empty.
+fn read_effective(cgroup_dir: &Path, file: &str) -> Result<Option<String>, CgroupError> {
+    let mount_point = cgroup2_mount_point()?;
+    for dir in cgroup_dir
+        .ancestors()
+        .take_while(|dir| dir.starts_with(&mount_point))
+    {
+        if let Some(contents) = read_optional(&dir.join(file))? {
+            return Ok((!contents.is_empty()).then_some(contents));
+        }
+    }
+    Ok(None)
+}
+
+/// Read and trim a file, returning `None` if it does not exist
+fn read_optional(path: &Path) -> io::Result<Option<String>> {
+    match fs::read_to_string(path) {
+        Ok(contents) => Ok(Some(contents.trim().to_owned())),
+        Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(None),
+        Err(e) => Err(e),
+    }
+}
+
+/// Extract the cgroup v2 path from the contents of `/proc/<pid>/cgroup`
+///
+/// In this file, cgroup v2 membership is recorded as `0::<path>`.
+fn parse_cgroup_path(cgroups: &str) -> Option<&str> {
+    cgroups.lines().find_map(|line| line.strip_prefix("0::"))
+}
+
+/// Extract the cgroup v2 mount from the contents of `/proc/<pid>/mountinfo`
+///
+/// Each line of this file lists a mount ID, parent ID, device ID, root, mount
+/// point, mount options and optional fields, then a `-` separator, then the
+/// filesystem type and other information.
+fn parse_cgroup2_mount(mountinfo: &str) -> Option<Cgroup2Mount> {
+    mountinfo.lines().find_map(|line| {
+        let (mount, filesystem) = line.split_once(" - ")?;
+        if filesystem.split_whitespace().next()? != "cgroup2" {
+            return None;
+        }
+        let mut fields = mount.split_whitespace().skip(3);
+        Some(Cgroup2Mount {
+            root: PathBuf::from(fields.next()?),
+            mount_point: PathBuf::from(fields.next()?),
+        })
+    })
+}
+
+/// Parse the contents of a `memory.max` file
+///
+/// This is either a number of bytes, or `max` if memory usage is unlimited.
+fn parse_memory_max(max: &str) -> Result<Option<u64>, CgroupError> {
+    if max == "max" {
+        return Ok(None);
+    }
+    max.parse()
+        .map(Some)
+        .map_err(|_| CgroupError::InvalidList(max.to_owned()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[allow(unused)]
+    use similar_asserts::assert_eq;
+    use static_assertions::{assert_impl_all, assert_not_impl_any};
+    use std::{
+        error::Error,
+        fmt::{self, Binary, Debug, LowerExp, LowerHex, Octal, Pointer, UpperExp, UpperHex},
+        io::Read,
+        ops::Deref,
+    };
+
+    // Check that public types in this module keep implementing all expected
+    // traits, in the interest of detecting future semver-breaking changes
+    assert_impl_all!(CgroupError:
+        Debug, Error, From<io::Error>, Send, Sized, Sync, Unpin
+    );
+    assert_not_impl_any!(CgroupError:
+        Binary, Clone, Default, Deref, Drop, IntoIterator, LowerExp, LowerHex,
+        Octal, PartialOrd, Pointer, Read, UpperExp, UpperHex, fmt::Write,
+        io::Write
+    );
+
+    /// Check parsing of `/proc/<pid>/cgroup`
+    #[test]
+    fn cgroup_path() {
+        assert_eq!(
+            parse_cgroup_path("0::/user.slice/app.scope\n"),
+            Some("/user.slice/app.scope")
+        );
+        assert_eq!(
+            parse_cgroup_path("12:cpuset:/docker/abc\n0::/docker/abc\n"),
+            Some("/docker/abc")
+        );
+        assert_eq!(parse_cgroup_path("12:cpuset:/docker/abc\n"), None);
+    }
+
+    /// Check parsing of `/proc/<pid>/mountinfo`
+    #[test]
+    fn cgroup2_mount() {
+        let mountinfo = "\
+            22 1 0:21 / /proc rw,nosuid shared:12 - proc proc rw\n\
+            35 24 0:30 / /sys/fs/cgroup rw,nosuid,nodev shared:9 - cgroup2 cgroup2 rw,nsdelegate\n";
+        let mount = parse_cgroup2_mount(mountinfo).unwrap();
+        assert_eq!(
+            mount,
+            Cgroup2Mount {
+                root: PathBuf::from("/"),
+                mount_point: PathBuf::from("/sys/fs/cgroup"),
+            }
+        );
+        assert_eq!(
+            mount.cgroup_dir("/user.slice/app.scope"),
+            PathBuf::from("/sys/fs/cgroup/user.slice/app.scope")
+        );
+        assert_eq!(mount.cgroup_dir("/"), PathBuf::from("/sys/fs/cgroup"));
+        assert_eq!(
+            parse_cgroup2_mount("22 1 0:21 / /proc rw shared:12 - proc proc rw\n"),
+            None
+        );
+    }
+
+    /// Check that the mount root is stripped from cgroup paths, as happens in
+    /// containers that do not use a cgroup namespace
+    #[test]
+    fn cgroup_dir_in_container() {
+        let mountinfo = "35 24 0:30 /docker/abc /sys/fs/cgroup ro,nosuid - cgroup2 cgroup rw\n";
+        let mount = parse_cgroup2_mount(mountinfo).unwrap();
+        assert_eq!(mount.root, PathBuf::from("/docker/abc"));
+        assert_eq!(
+            mount.cgroup_dir("/docker/abc"),
+            PathBuf::from("/sys/fs/cgroup")
+        );
+        assert_eq!(
+            mount.cgroup_dir("/docker/abc/worker"),
+            PathBuf::from("/sys/fs/cgroup/worker")
+        );
+    }
+
+    /// Check parsing of `memory.max`
+    #[test]
+    fn memory_max() {
+        assert_eq!(parse_memory_max("max").unwrap(), None);
+        assert_eq!(parse_memory_max("1073741824").unwrap(), Some(1 << 30));
+        assert!(matches!(
+            parse_memory_max("lots"),
+            Err(CgroupError::InvalidList(s)) if s == "lots"
+        ));
+    }
+
+    /// Check the cgroup limits of the current process, if any
+    #[test]
+    fn current_cgroup() {
+        let topology = Topology::test_instance();
+        match (
+            topology.cgroup_allowed_cpuset(),
+            topology.cgroup_allowed_nodeset(),
+        ) {
+            (Ok(cpuset), Ok(nodeset)) => {
+                assert!(topology.cpuset().includes(&cpuset));
+                assert!(topology.nodeset().includes(&nodeset));
+                assert!(topology.allowed_cpuset().intersects(&cpuset));
+                assert!(!nodeset.is_empty());
+            }
+            (Err(CgroupError::NotCgroupV2), Err(CgroupError::NotCgroupV2)) => {}
+            other => panic!("unexpected cgroup query results {other:?}"),
+        }
+        match memory_limit() {
+            Ok(Some(limit)) => assert!(limit > 0),
+            Ok(None) | Err(CgroupError::NotCgroupV2) => {}
+            Err(other) => panic!("unexpected memory limit query error {other}"),
+        }
+    }
+
+    /// Check that restricting a topology to its cgroup yields the cgroup sets
+    #[cfg(feature = "hwloc-2_3_0")]
+    #[test]
+    fn restrict_to_cgroup() {
+        let mut topology = Topology::test_instance().clone();
+        let Ok(cpuset) = topology.cgroup_allowed_cpuset() else {
+            return;
+        };
+        topology
+            .edit(|editor| editor.restrict_to_cgroup(RestrictFlags::empty()))
+            .unwrap();
+        assert_eq!(topology.cpuset(), cpuset);
+    }
+}
@@ -1,5 +1,7 @@
 //! Linux-specific helpers
 
+pub mod cgroup;
+
 #[cfg(doc)]
 use crate::cpu::binding::CpuBindingFlags;
 use crate::{
@@ -8,5 +8,7 @@
 
 #[cfg(any(doc, target_os = "linux"))]
 pub mod linux;
+#[cfg(feature = "verbs")]
+pub mod verbs;
 #[cfg(any(doc, all(feature = "hwloc-2_5_0", target_os = "windows")))]
 pub mod windows;
@@ -0,0 +1,203 @@
+//! InfiniBand verbs device locality
+//!
+//! This module is the Rust equivalent of hwloc's `hwloc/openfabrics-verbs.h`
+//! header. It lets you find out which CPUs and topology objects are close to
+//! an InfiniBand HCA, identified by its `struct ibv_device` pointer or by its
+//! name (e.g. `mlx5_0`), which MPI and RDMA runtimes need in order to place
+//! their threads near the network hardware that they use.
+//!
+//! This functionality is only available when the `verbs` cargo feature is
+//! enabled, and requires the verbs library (`libibverbs`) at link time.
+
+use crate::{
+    cpu::cpuset::CpuSet,
+    object::{attributes::ObjectAttributes, types::OSDeviceType, TopologyObject},
+    topology::Topology,
+};
+#[allow(unused)]
+#[cfg(test)]
+use similar_asserts::assert_eq;
+use std::ffi::{c_char, c_void, CStr};
+use thiserror::Error;
+
+/// InfiniBand verbs device pointer (`struct ibv_device *`)
+///
+/// Such pointers are typically obtained via `ibv_get_device_list()`.
+pub type IbvDevice = *mut c_void;
+
+/// # InfiniBand verbs device locality
+///
+/// These functions let you find out which CPUs and objects are close to an
+/// InfiniBand verbs device.
+//
+// --- Implementation details ---
+//
+// Upstream docs: https://hwloc.readthedocs.io/en/v2.9/group__hwlocality__openfabrics.html
+// Since these functions are inline functions in hwloc's headers, they had to be
+// reimplemented in Rust.
+impl Topology {
+    /// CPUs that are close to the InfiniBand verbs device `device`
+    ///
+    /// On Linux, this is the set of CPUs that the operating system reports as
+    /// local to the device. On other operating systems, or if that information
+    /// is unavailable, the complete cpuset of the topology is returned.
+    ///
+    /// # Errors
+    ///
+    /// - [`ForeignSystem`] if the topology does not describe the system that
+    ///   the program is running on.
+    ///
+    /// # Safety
+    ///
+    /// `device` must be a valid InfiniBand verbs device pointer.
+    ///
+    /// [`ForeignSystem`]: IbvDeviceError::ForeignSystem
+    #[doc(alias = "hwloc_ibv_get_device_cpuset")]
+    pub unsafe fn ibv_device_cpuset(&self, device: IbvDevice) -> Result<CpuSet, IbvDeviceError> {
+        if !self.is_this_system() {
+            return Err(IbvDeviceError::ForeignSystem);
+        }
+        #[cfg(target_os = "linux")]
+        {
+            // SAFETY: Per function precondition
+            let name = unsafe { ibv_device_name(device) };
+            if let Some(name) = name.and_then(|name| name.to_str().ok()) {
+                let path = format!("/sys/class/infiniband/{name}/device/local_cpus");
+                if let Ok(cpuset) = self.read_path_as_cpumask(path) {
+                    if !cpuset.is_empty() {
+                        return Ok(cpuset);
+                    }
+                }
+            }
+        }
+        #[cfg(not(target_os = "linux"))]
+        let _ = device;
+        Ok(self.complete_cpuset().clone_target())
+    }
+
+    /// OS device object that describes the InfiniBand verbs device `device`
+    ///
+    /// This is the [`OSDeviceType::OpenFabrics`] OS device that has the same
+    /// name as `device`. It is only present if I/O device discovery is enabled.
+    /// `None` is returned if there is no such object.
+    ///
+    /// # Safety
+    ///
+    /// `device` must be a valid InfiniBand verbs device pointer.
+    #[doc(alias = "hwloc_ibv_get_device_osdev")]
+    pub unsafe fn ibv_device_osdev(&self, device: IbvDevice) -> Option<&TopologyObject> {
+        // SAFETY: Per function precondition
+        let name = unsafe { ibv_device_name(device) }?;
+        self.ibv_device_osdev_by_name(name.to_bytes())
+    }
+
+    /// OS device object that describes the InfiniBand verbs device with a
+    /// certain name
+    ///
+    /// The name can be specified either as a string or as raw bytes, e.g.
+    /// `"mlx5_0"`. This is the [`OSDeviceType::OpenFabrics`] OS device with
+    /// this name, which is only present if I/O device discovery is enabled.
+    /// `None` is returned if there is no such object.
+    #[doc(alias = "hwloc_ibv_get_device_osdev_by_name")]
+    pub fn ibv_device_osdev_by_name(&self, name: impl AsRef<[u8]>) -> Option<&TopologyObject> {
+        /// Polymorphized version of this function (avoids generics code bloat)
+        fn polymorphized<'self_>(
+            self_: &'self_ Topology,
+            name: &[u8],
+        ) -> Option<&'self_ TopologyObject> {
+            self_.os_devices().find(|obj| {
+                let Some(ObjectAttributes::OSDevice(attr)) = obj.attributes() else {
+                    unreachable!("All OS devices should have OS device attributes")
+                };
+                attr.device_type() == OSDeviceType::OpenFabrics
+                    && obj
+                        .name()
+                        .is_some_and(|obj_name| obj_name.to_bytes() == name)
+            })
+        }
+        polymorphized(self, name.as_ref())
+    }
+}
+
+/// Error while querying the locality of an InfiniBand verbs device
+#[derive(Copy, Clone, Debug, Eq, Error, Hash, PartialEq)]
+pub enum IbvDeviceError {
+    /// The topology does not describe the system that the program is running
+    /// on, so its CPUs cannot be associated with local verbs devices
+    #[error("topology doesn't describe the system the program is running on")]
+    ForeignSystem,
+}
+
+/// Name of an InfiniBand verbs device, if the verbs library reports one
+///
+/// # Safety
+///
+/// `device` must be a valid InfiniBand verbs device pointer.
+unsafe fn ibv_device_name<'device>(device: IbvDevice) -> Option<&'device CStr> {
+    // SAFETY: device is valid per function precondition
+    let name = unsafe { ibv_get_device_name(device) };
+    if name.is_null() {
+        return None;
+    }
+    // SAFETY: The verbs library returns a pointer to a NUL-terminated string
+    //         that lives as long as the device
+    Some(unsafe { CStr::from_ptr(name) })
+}
+
+#[link(name = "ibverbs")]
+extern "C" {
+    /// Query the name of an InfiniBand verbs device
+    #[must_use]
+    fn ibv_get_device_name(device: IbvDevice) -> *const c_char;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[allow(unused)]
+    use similar_asserts::assert_eq;
+    use static_assertions::{assert_impl_all, assert_not_impl_any};
+    use std::{
+        error::Error,
+        fmt::{self, Binary, LowerExp, LowerHex, Octal, Pointer, UpperExp, UpperHex},
+        hash::Hash,
+        io::{self, Read},
+        ops::Deref,
+        panic::UnwindSafe,
+        ptr,
+    };
+
+    // Check that public types in this module keep implementing all expected
+    // traits, in the interest of detecting future semver-breaking changes
+    assert_impl_all!(IbvDeviceError:
+        Copy, Error, Hash, Sized, Sync, Unpin, UnwindSafe
+    );
+    assert_not_impl_any!(IbvDeviceError:
+        Binary, Default, Deref, Drop, IntoIterator, LowerExp, LowerHex, Octal,
+        PartialOrd, Pointer, Read, UpperExp, UpperHex, fmt::Write, io::Write
+    );
+
+    /// Check that OpenFabrics OS devices can be found by name
+    #[test]
+    fn ibv_device_osdev_by_name() {
+        let topology = Topology::test_instance();
+        for osdev in topology.os_devices_with_type(OSDeviceType::OpenFabrics) {
+            let name = osdev.name().unwrap();
+            let found = topology.ibv_device_osdev_by_name(name.to_bytes()).unwrap();
+            assert_eq!(found.name(), Some(name));
+        }
+        for osdev in topology.os_devices() {
+            let Some(ObjectAttributes::OSDevice(attr)) = osdev.attributes() else {
+                unreachable!()
+            };
+            if attr.device_type() == OSDeviceType::OpenFabrics {
+                continue;
+            }
+            if let Some(name) = osdev.name() {
+                if let Some(found) = topology.ibv_device_osdev_by_name(name.to_bytes()) {
+                    assert!(!ptr::eq(found, osdev));
+                }
+            }
+        }
+    }
+}
@@ -217,11 +217,16 @@
     unreachable_pub,
     variant_size_differences
 )]
+// The no-link feature disables a default behavior, which cannot be expressed
+// with a positive feature name without breaking `default-features = false`
+#![allow(clippy::negative_feature_names)]
 
 pub mod bitmap;
+pub mod cluster;
 pub mod cpu;
 pub mod errors;
 pub mod ffi;
+pub mod gpu;
 pub mod info;
 #[doc(hidden)] // Does not currently expose any public interface
 pub mod interop;
@@ -229,7 +234,9 @@ pub mod memory;
 pub mod object;
 pub mod path;
 #[cfg(any(test, feature = "proptest"))]
-pub(crate) mod strategies;
+pub mod strategies;
+#[cfg(any(test, feature = "testing"))]
+pub mod testing;
 pub mod topology;
 
 /// Re-export `proptest` version we're built against
@@ -0,0 +1,269 @@
+//! NUMA-aware memory allocator
+//!
+//! [`Topology::allocate_bound_memory()`] can allocate untyped memory buffers
+//! that are bound to specific NUMA nodes, but it does not integrate with Rust
+//! collections. This module provides the [`NodeBoundAllocator`] type, which
+//! implements the [`Allocator`] trait so that collections like
+//! `Vec<T, NodeBoundAllocator>` can store their data on chosen NUMA nodes.
+//!
+//! Since the standard library's allocator API is not stable yet, the
+//! [`Allocator`] trait is taken from the `allocator-api2` crate, which also
+//! provides stable versions of the standard collections that accept custom
+//! allocators, like [`allocator_api2::vec::Vec`]. When building with a nightly
+//! compiler, you can enable the `nightly` feature of `allocator-api2` to make
+//! its [`Allocator`] trait a re-export of the standard one, at which point
+//! [`NodeBoundAllocator`] can also be used with standard collections.
+//!
+//! ```
+//! # use hwlocality::{memory::binding::{MemoryBindingFlags, MemoryBindingPolicy}, Topology};
+//! use allocator_api2::vec::Vec;
+//! use hwlocality::memory::allocator::NodeBoundAllocator;
+//!
+//! let topology = Topology::new()?;
+//! let allocator = NodeBoundAllocator::new(
+//!     &topology,
+//!     topology.nodeset().clone_target(),
+//!     MemoryBindingPolicy::Bind,
+//!     MemoryBindingFlags::ASSUME_SINGLE_THREAD,
+//! )?;
+//! let mut v = Vec::new_in(allocator);
+//! if v.try_reserve(1024).is_ok() {
+//!     v.extend(0..1024u32);
+//! }
+//! # Ok::<(), eyre::Report>(())
+//! ```
+
+use crate::{
+    memory::{
+        binding::{
+            Bytes, MemoryBindingError, MemoryBindingFlags, MemoryBindingOperation,
+            MemoryBindingPolicy, MemoryBoundObject,
+        },
+        nodeset::NodeSet,
+    },
+    topology::Topology,
+};
+use allocator_api2::alloc::{AllocError, Allocator, Layout};
+#[allow(unused)]
+#[cfg(test)]
+use similar_asserts::assert_eq;
+use std::ptr::{self, NonNull};
+
+/// Memory allocator that binds its allocations to a set of NUMA nodes
+///
+/// Each allocation is performed using [`Topology::allocate_bound_memory()`],
+/// and is thus subject to the same requirements: it needs
+/// [`MemoryBindingSupport::allocate_bound()`] along with support for the
+/// requested binding policy. If the [`STRICT`] flag is not specified and the
+/// requested binding cannot be enforced, hwloc may silently allocate memory
+/// that is not bound as requested.
+///
+/// hwloc allocates memory with page granularity, so this allocator is best
+/// suited to a few large and long-lived allocations, like the buffers of big
+/// collections, rather than to many small allocations. Allocation alignment
+/// is typically the system page size, and allocations that require a larger
+/// alignment will fail.
+///
+/// [`MemoryBindingSupport::allocate_bound()`]: crate::topology::support::MemoryBindingSupport::allocate_bound()
+/// [`STRICT`]: MemoryBindingFlags::STRICT
+#[derive(Clone, Debug)]
+pub struct NodeBoundAllocator<'topology> {
+    /// Topology that memory is allocated from
+    topology: &'topology Topology,
+
+    /// NUMA nodes that allocations are bound to
+    nodeset: NodeSet,
+
+    /// Memory binding policy
+    policy: MemoryBindingPolicy,
+
+    /// Memory binding flags, already validated and adjusted for node sets
+    flags: MemoryBindingFlags,
+}
+//
+impl<'topology> NodeBoundAllocator<'topology> {
+    /// Set up an allocator that binds memory to the NUMA nodes of `nodeset`
+    /// according to `policy` and `flags`
+    ///
+    /// `flags` must follow the rules of [`Topology::allocate_bound_memory()`].
+    /// The [`BY_NODE_SET`] flag is set automatically.
+    ///
+    /// # Errors
+    ///
+    /// - [`BadFlags`] if `flags` are not valid for memory allocation
+    ///
+    /// [`BadFlags`]: MemoryBindingError::BadFlags
+    /// [`BY_NODE_SET`]: MemoryBindingFlags::BY_NODE_SET
+    pub fn new(
+        topology: &'topology Topology,
+        nodeset: NodeSet,
+        policy: MemoryBindingPolicy,
+        mut flags: MemoryBindingFlags,
+    ) -> Result<Self, MemoryBindingError<NodeSet>> {
+        flags.insert(MemoryBindingFlags::BY_NODE_SET);
+        let Some(flags) = flags.validate(MemoryBoundObject::Area, MemoryBindingOperation::Allocate)
+        else {
+            return Err(MemoryBindingError::BadFlags(flags.into()));
+        };
+        Ok(Self {
+            topology,
+            nodeset,
+            policy,
+            flags,
+        })
+    }
+
+    /// Topology that memory is allocated from
+    pub fn topology(&self) -> &'topology Topology {
+        self.topology
+    }
+
+    /// NUMA nodes that allocations are bound to
+    pub fn nodeset(&self) -> &NodeSet {
+        &self.nodeset
+    }
+
+    /// Memory binding policy
+    pub fn policy(&self) -> MemoryBindingPolicy {
+        self.policy
+    }
+
+    /// Memory binding flags
+    pub fn flags(&self) -> MemoryBindingFlags {
+        self.flags
+    }
+}
+//
+// SAFETY: - Allocated blocks are owned hwloc allocations that remain valid
+//           until they are deallocated, since the topology they originate
+//           from outlives the allocator
+//         - Cloned allocators share the same topology, so any of them can
+//           deallocate memory allocated by another
+//         - Allocated blocks have the requested size and alignment
+unsafe impl Allocator for NodeBoundAllocator<'_> {
+    fn allocate(&self, layout: Layout) -> Result<NonNull<[u8]>, AllocError> {
+        // hwloc cannot allocate zero-sized blocks, so follow the Rust
+        // convention of using a dangling well-aligned pointer
+        if layout.size() == 0 {
+            let dangling = NonNull::new(ptr::null_mut::<u8>().wrapping_add(layout.align()))
+                .expect("Alignment cannot be zero");
+            return Ok(NonNull::slice_from_raw_parts(dangling, 0));
+        }
+
+        // Perform the allocation and check its alignment
+        let bytes = self
+            .topology
+            .allocate_bound_memory(layout.size(), &self.nodeset, self.policy, self.flags)
+            .map_err(|_| AllocError)?;
+        if bytes.as_ptr().align_offset(layout.align()) != 0 {
+            return Err(AllocError);
+        }
+        let data = bytes.into_raw();
+        Ok(NonNull::slice_from_raw_parts(data.cast::<u8>(), data.len()))
+    }
+
+    unsafe fn deallocate(&self, ptr: NonNull<u8>, layout: Layout) {
+        if layout.size() > 0 {
+            // SAFETY: Per Allocator contract, ptr was allocated by this
+            //         allocator or a clone of it, which means it originates
+            //         from a successful hwloc allocation of layout.size()
+            //         bytes from self.topology
+            std::mem::drop(unsafe { Bytes::wrap(self.topology, ptr.cast(), layout.size()) });
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{errors::ParameterError, topology::support::MemoryBindingSupport};
+    use allocator_api2::vec::Vec as AllocVec;
+    #[allow(unused)]
+    use similar_asserts::assert_eq;
+    use static_assertions::{assert_impl_all, assert_not_impl_any};
+    use std::{
+        fmt::{
+            self, Binary, Debug, Display, LowerExp, LowerHex, Octal, Pointer, UpperExp, UpperHex,
+        },
+        hash::Hash,
+        io::{self, Read},
+        ops::Deref,
+        panic::UnwindSafe,
+    };
+
+    // Check that public types in this module keep implementing all expected
+    // traits, in the interest of detecting future semver-breaking changes
+    assert_impl_all!(NodeBoundAllocator<'static>:
+        Allocator, Clone, Debug, Send, Sized, Sync, Unpin, UnwindSafe
+    );
+    assert_not_impl_any!(NodeBoundAllocator<'static>:
+        Binary, Copy, Default, Deref, Display, Drop, Hash, IntoIterator,
+        LowerExp, LowerHex, Octal, PartialEq, Pointer, Read, UpperExp,
+        UpperHex, fmt::Write, io::Write
+    );
+
+    /// Set up an allocator that targets all NUMA nodes of the test topology
+    fn test_allocator() -> NodeBoundAllocator<'static> {
+        let topology = Topology::test_instance();
+        NodeBoundAllocator::new(
+            topology,
+            topology.nodeset().clone_target(),
+            MemoryBindingPolicy::Bind,
+            MemoryBindingFlags::ASSUME_SINGLE_THREAD,
+        )
+        .unwrap()
+    }
+
+    /// Constructor should validate flags and set up accessors
+    #[test]
+    fn new() {
+        let topology = Topology::test_instance();
+        let allocator = test_allocator();
+        assert!(std::ptr::eq(allocator.topology(), topology));
+        assert_eq!(allocator.nodeset(), &topology.nodeset().clone_target());
+        assert_eq!(allocator.policy(), MemoryBindingPolicy::Bind);
+        assert_eq!(
+            allocator.flags(),
+            MemoryBindingFlags::ASSUME_SINGLE_THREAD | MemoryBindingFlags::BY_NODE_SET
+        );
+
+        let bad_flags = MemoryBindingFlags::PROCESS | MemoryBindingFlags::THREAD;
+        assert_eq!(
+            NodeBoundAllocator::new(
+                topology,
+                topology.nodeset().clone_target(),
+                MemoryBindingPolicy::Bind,
+                bad_flags,
+            )
+            .unwrap_err(),
+            MemoryBindingError::BadFlags(ParameterError::from(
+                bad_flags | MemoryBindingFlags::BY_NODE_SET
+            ))
+        );
+    }
+
+    /// Collections should work with this allocator if bound allocation works
+    #[test]
+    fn collection() {
+        let allocator = test_allocator();
+
+        // Zero-sized allocations should always succeed
+        let zst = allocator.allocate(Layout::new::<()>()).unwrap();
+        assert_eq!(zst.len(), 0);
+        // SAFETY: zst was allocated by this allocator with this layout
+        unsafe { allocator.deallocate(zst.cast(), Layout::new::<()>()) };
+
+        // Other allocations depend on OS support
+        let supports_bound_alloc = Topology::test_instance()
+            .feature_support()
+            .memory_binding()
+            .is_some_and(MemoryBindingSupport::allocate_bound);
+        let mut v = AllocVec::<u64, _>::new_in(allocator);
+        if v.try_reserve(1000).is_err() {
+            assert!(!supports_bound_alloc);
+            return;
+        }
+        v.extend(0..1000);
+        assert!(v.iter().copied().eq(0..1000));
+    }
+}
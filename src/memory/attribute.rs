@@ -25,7 +25,10 @@ use crate::{
         transparent::{AsInner, AsNewtype},
     },
     object::{types::ObjectType, TopologyObject},
-    topology::{editor::TopologyEditor, Topology},
+    topology::{
+        editor::{TopologyChanges, TopologyEditor},
+        Topology,
+    },
 };
 use bitflags::bitflags;
 use derive_more::{Display, From};
@@ -52,6 +55,7 @@ use std::{
     fmt::{self, Debug},
     hash::Hash,
     mem::MaybeUninit,
+    ops::Deref,
     ptr::{self, NonNull},
 };
 use thiserror::Error;
@@ -223,6 +227,130 @@ impl Topology {
         polymorphized(self, target.into())
     }
 
+    /// NUMA nodes that have a value for some memory attribute, sorted from
+    /// best to worst
+    ///
+    /// This returns all NUMA nodes that have a value for `attribute` (and for
+    /// `initiator`, if the attribute needs one), along with the associated
+    /// values, ordered from the best to the worst value according to the
+    /// attribute's [`flags()`]. NUMA nodes with identical values are listed in
+    /// the order in which hwloc reports them.
+    ///
+    /// The notes on initiator semantics in [`MemoryAttribute::value()`] also
+    /// apply to this function.
+    ///
+    /// # Errors
+    ///
+    /// - [`ForeignInitiator`] if the `initiator` parameter was set to a
+    ///   [`TopologyObject`] that does not belong to this topology
+    /// - [`NeedInitiator`] if no `initiator` was provided but this memory
+    ///   attribute needs one
+    /// - [`UnwantedInitiator`] if an `initiator` was provided but this memory
+    ///   attribute doesn't need one
+    ///
+    /// # Panics
+    ///
+    /// If `attribute` was not obtained from this topology.
+    ///
+    /// [`flags()`]: MemoryAttribute::flags()
+    /// [`ForeignInitiator`]: InitiatorInputError::ForeignInitiator
+    /// [`NeedInitiator`]: InitiatorInputError::NeedInitiator
+    /// [`UnwantedInitiator`]: InitiatorInputError::UnwantedInitiator
+    pub fn numa_nodes_sorted_by<'topology>(
+        &'topology self,
+        attribute: MemoryAttribute<'topology>,
+        initiator: Option<MemoryAttributeLocation<'_>>,
+    ) -> Result<Vec<(&'topology TopologyObject, u64)>, HybridError<InitiatorInputError>> {
+        assert!(
+            ptr::eq(attribute.topology, self),
+            "memory attribute should come from this topology"
+        );
+        let flags = attribute.flags();
+        let (targets, values) = attribute.targets(initiator)?;
+        let mut nodes = targets
+            .into_iter()
+            .zip(values)
+            .filter(|(target, _value)| target.object_type() == ObjectType::NUMANode)
+            .collect::<Vec<_>>();
+        if flags.contains(MemoryAttributeFlags::HIGHER_IS_BEST) {
+            nodes.sort_by_key(|&(_node, value)| std::cmp::Reverse(value));
+        } else {
+            nodes.sort_by_key(|&(_node, value)| value);
+        }
+        Ok(nodes)
+    }
+
+    /// Best NUMA node for allocating memory used by some CPUs, according to
+    /// some memory attribute
+    ///
+    /// `for_cpuset` can be a `&'_ CpuSet` or a `BitmapRef<'_, CpuSet>`.
+    ///
+    /// This considers the NUMA nodes whose locality is larger than, smaller
+    /// than or equal to `for_cpuset`, and returns the one that has the best
+    /// value of `attribute`, along with this value. If `attribute` needs an
+    /// initiator, `for_cpuset` is used as the initiator. For example, to find
+    /// the highest-bandwidth memory near a set of cores, you may use
+    /// `topology.best_numa_node(cpuset, MemoryAttribute::bandwidth(topology))`.
+    ///
+    /// `None` is returned if none of the local NUMA nodes has a value for
+    /// `attribute`. If multiple NUMA nodes have the same best value, the first
+    /// one in the order of [`numa_nodes_sorted_by()`] is returned.
+    ///
+    /// # Errors
+    ///
+    /// - [`RawHwlocError`] if hwloc fails to list the NUMA nodes that are
+    ///   local to `for_cpuset` or their attribute values
+    ///
+    /// # Panics
+    ///
+    /// If `attribute` was not obtained from this topology.
+    ///
+    /// [`numa_nodes_sorted_by()`]: Topology::numa_nodes_sorted_by()
+    pub fn best_numa_node<'topology>(
+        &'topology self,
+        for_cpuset: impl Deref<Target = CpuSet>,
+        attribute: MemoryAttribute<'topology>,
+    ) -> Result<Option<(&'topology TopologyObject, u64)>, RawHwlocError> {
+        /// Polymorphized version of this function (avoids generics code bloat)
+        fn polymorphized<'topology>(
+            self_: &'topology Topology,
+            for_cpuset: &CpuSet,
+            attribute: MemoryAttribute<'topology>,
+        ) -> Result<Option<(&'topology TopologyObject, u64)>, RawHwlocError> {
+            let local_nodes = self_
+                .local_numa_nodes(TargetNumaNodes::Local {
+                    location: for_cpuset.into(),
+                    flags: LocalNUMANodeFlags::LARGER_LOCALITY
+                        | LocalNUMANodeFlags::SMALLER_LOCALITY,
+                })
+                .map_err(|e| match e {
+                    HybridError::Hwloc(e) => e,
+                    HybridError::Rust(e) => {
+                        unreachable!("cpuset locations cannot be foreign: {e}")
+                    }
+                })?;
+            let initiator = attribute
+                .flags()
+                .contains(MemoryAttributeFlags::NEED_INITIATOR)
+                .then(|| for_cpuset.into());
+            let sorted_nodes =
+                self_
+                    .numa_nodes_sorted_by(attribute, initiator)
+                    .map_err(|e| match e {
+                        HybridError::Hwloc(e) => e,
+                        HybridError::Rust(e) => {
+                            unreachable!("initiator was set according to attribute flags: {e}")
+                        }
+                    })?;
+            Ok(sorted_nodes.into_iter().find(|(node, _value)| {
+                local_nodes
+                    .iter()
+                    .any(|local_node| ptr::eq(*local_node, *node))
+            }))
+        }
+        polymorphized(self, &for_cpuset, attribute)
+    }
+
     /// Dump the values of all built-in memory attributes
     pub(crate) fn dump_builtin_attributes(&self) -> MultiAttributeDump<'_> {
         MultiAttributeDump::builtins(self)
@@ -511,8 +639,8 @@ impl<'topology> TopologyEditor<'topology> {
     /// - [`NameTaken`] if another attribute called `name` already exists.
     ///
     /// [`BadFlags`]: RegisterError::BadFlags
-    /// [`HIGHER_IS_BEST`]: [`MemoryAttributeFlags::HIGHER_IS_BEST`]
-    /// [`LOWER_IS_BEST`]: [`MemoryAttributeFlags::LOWER_IS_BEST`]
+    /// [`HIGHER_IS_BEST`]: MemoryAttributeFlags::HIGHER_IS_BEST
+    /// [`LOWER_IS_BEST`]: MemoryAttributeFlags::LOWER_IS_BEST
     /// [`NameContainsNul`]: RegisterError::NameContainsNul
     /// [`NameTaken`]: RegisterError::NameTaken
     #[doc(alias = "hwloc_memattr_register")]
@@ -526,6 +654,7 @@ impl<'topology> TopologyEditor<'topology> {
         }
         let libc_name = LibcString::new(name)?;
         let mut id = hwloc_memattr_id_t::MAX;
+        self.record_changes(TopologyChanges::MEMORY_ATTRIBUTES);
         // SAFETY: - Topology is trusted to contain a valid ptr (type invariant)
         //         - libc_name is trusted to be a valid C string (type
         //           invariant)
@@ -584,8 +713,8 @@ pub enum RegisterError {
     /// You must specify exactly one of the [`HIGHER_IS_BEST`] and
     /// [`LOWER_IS_BEST`] flags.
     ///
-    /// [`HIGHER_IS_BEST`]: [`MemoryAttributeFlags::HIGHER_IS_BEST`]
-    /// [`LOWER_IS_BEST`]: [`MemoryAttributeFlags::LOWER_IS_BEST`]
+    /// [`HIGHER_IS_BEST`]: MemoryAttributeFlags::HIGHER_IS_BEST
+    /// [`LOWER_IS_BEST`]: MemoryAttributeFlags::LOWER_IS_BEST
     #[error(transparent)]
     BadFlags(#[from] FlagsError<MemoryAttributeFlags>),
 }
@@ -721,6 +850,9 @@ impl MemoryAttributeBuilder<'_, '_> {
                 .chain(std::iter::repeat_with(ptr::null));
 
             // Set memory attribute values
+            self_
+                .editor
+                .record_changes(TopologyChanges::MEMORY_ATTRIBUTES);
             for (initiator_ptr, (target_ptr, value)) in initiator_ptrs.zip(target_ptrs_and_values) {
                 // SAFETY: - Topology is trusted to contain a valid ptr (type invariant)
                 //         - hwloc ops are trusted to keep *mut parameters in a
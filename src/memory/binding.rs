@@ -7,16 +7,18 @@
 //! struct](../../topology/struct.Topology.html#memory-binding). The module
 //! itself only hosts type definitions that are related to this functionality.
 
+#[cfg(doc)]
+use crate::topology::support::MemoryBindingSupport;
 use crate::{
     bitmap::{Bitmap, BitmapKind, OwnedSpecializedBitmap, SpecializedBitmap},
+    cpu::cpuset::CpuSet,
     errors::{self, FlagsError, RawHwlocError},
     memory::nodeset::NodeSet,
     topology::Topology,
     ProcessId,
 };
-#[cfg(doc)]
-use crate::{cpu::cpuset::CpuSet, topology::support::MemoryBindingSupport};
 use bitflags::bitflags;
+use bytemuck::Pod;
 use derive_more::Display;
 use enum_iterator::Sequence;
 use errno::Errno;
@@ -36,6 +38,7 @@ use std::{
     borrow::{Borrow, BorrowMut},
     ffi::{c_int, c_void},
     fmt::{self, Debug, Display},
+    marker::PhantomData,
     mem::MaybeUninit,
     ops::{Deref, DerefMut},
     ptr::{self, NonNull},
@@ -265,6 +268,66 @@ impl Topology {
         Ok(bytes)
     }
 
+    /// Allocate some zero-initialized memory on NUMA nodes specified by `set`,
+    /// remembering the requested binding
+    ///
+    /// This works like [`Topology::allocate_bound_memory()`], but the
+    /// allocated memory is filled with zeroes, which allows it to be exposed
+    /// as `[u8]` rather than `[MaybeUninit<u8>]`, and the returned
+    /// [`BoundMemory`] keeps track of the NUMA nodes and policy that it was
+    /// allocated with. Like any other hwloc allocation, it is automatically
+    /// liberated when it goes out of scope.
+    ///
+    /// Since the memory is touched by the current thread during
+    /// zero-initialization, the [`FirstTouch`] policy will locate it near the
+    /// current thread rather than near the eventual user of the memory.
+    ///
+    /// Requires [`MemoryBindingSupport::allocate_bound()`].
+    ///
+    /// # Errors
+    ///
+    /// Same as [`Topology::allocate_bound_memory()`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use hwlocality::{memory::binding::{MemoryBindingFlags, MemoryBindingPolicy}, Topology};
+    /// # let topology = Topology::test_instance();
+    /// if let Ok(mut memory) = topology.allocate_zeroed_bound_memory(
+    ///     4096,
+    ///     &topology.nodeset(),
+    ///     MemoryBindingPolicy::Bind,
+    ///     MemoryBindingFlags::empty(),
+    /// ) {
+    ///     memory[0] = 42;
+    ///     assert_eq!(memory.nodeset(), &*topology.nodeset());
+    /// }
+    /// # Ok::<(), eyre::Report>(())
+    /// ```
+    ///
+    /// [`FirstTouch`]: MemoryBindingPolicy::FirstTouch
+    #[doc(alias = "hwloc_alloc_membind")]
+    pub fn allocate_zeroed_bound_memory<Set: SpecializedBitmap>(
+        &self,
+        len: usize,
+        set: &Set,
+        policy: MemoryBindingPolicy,
+        flags: MemoryBindingFlags,
+    ) -> Result<BoundMemory<'_>, MemoryAllocationError<Set::Owned>> {
+        let mut bytes = self.allocate_bound_memory(len, set, policy, flags)?;
+        bytes.fill(MaybeUninit::new(0));
+        let set: Bitmap = set.to_owned().into();
+        let nodeset = match Set::BITMAP_KIND {
+            BitmapKind::CpuSet => NodeSet::from_cpuset(self, &CpuSet::from(set)),
+            BitmapKind::NodeSet => NodeSet::from(set),
+        };
+        Ok(BoundMemory {
+            bytes,
+            nodeset,
+            policy,
+        })
+    }
+
     /// Set the default memory binding policy of the current process or thread
     /// to prefer the NUMA node(s) specified by `set`.
     ///
@@ -317,6 +380,62 @@ impl Topology {
         }
     }
 
+    /// Temporarily set the default memory binding policy of the current
+    /// process or thread
+    ///
+    /// This works like [`Topology::bind_memory()`], except the current
+    /// binding is queried beforehand and restored when the returned
+    /// [`MemoryBindingGuard`] is dropped. This makes it easy to temporarily
+    /// interleave or pin the allocations of a section of code, without letting
+    /// the policy change leak out of this section on early return or panic.
+    ///
+    /// The previous binding is queried using the binding target flag
+    /// ([`ASSUME_SINGLE_THREAD`], [`THREAD`] or [`PROCESS`]) that is specified
+    /// in `flags`, and restored using `flags` without the [`STRICT`] flag.
+    ///
+    /// Requires [`MemoryBindingSupport::get_current_process()`] and
+    /// [`MemoryBindingSupport::set_current_process()`], or
+    /// [`MemoryBindingSupport::get_current_thread()`] and
+    /// [`MemoryBindingSupport::set_current_thread()`], depending on flags.
+    ///
+    /// # Errors
+    ///
+    /// - [`MixedResults`] if the current binding cannot be restored because
+    ///   the threads of the process do not share a common binding
+    /// - Otherwise same as [`Topology::memory_binding()`] and
+    ///   [`Topology::bind_memory()`]
+    ///
+    /// If querying the current binding fails, the binding is left unchanged.
+    ///
+    /// [`ASSUME_SINGLE_THREAD`]: MemoryBindingFlags::ASSUME_SINGLE_THREAD
+    /// [`MixedResults`]: MemoryBindingError::MixedResults
+    /// [`PROCESS`]: MemoryBindingFlags::PROCESS
+    /// [`STRICT`]: MemoryBindingFlags::STRICT
+    /// [`THREAD`]: MemoryBindingFlags::THREAD
+    pub fn bind_memory_scoped<Set: SpecializedBitmap>(
+        &self,
+        set: &Set,
+        policy: MemoryBindingPolicy,
+        flags: MemoryBindingFlags,
+    ) -> Result<MemoryBindingGuard<'_, Set::Owned>, MemoryBindingError<Set::Owned>> {
+        let mut query_flags = flags
+            & (MemoryBindingFlags::ASSUME_SINGLE_THREAD
+                | MemoryBindingFlags::PROCESS
+                | MemoryBindingFlags::THREAD);
+        if query_flags.contains(MemoryBindingFlags::PROCESS) {
+            query_flags |= MemoryBindingFlags::STRICT;
+        }
+        let (previous_set, previous_policy) = self.memory_binding::<Set::Owned>(query_flags)?;
+        let previous_policy = previous_policy.ok_or(MemoryBindingError::MixedResults)?;
+        self.bind_memory(set, policy, flags)?;
+        Ok(MemoryBindingGuard {
+            topology: self,
+            previous: Some((previous_set, previous_policy)),
+            flags: flags - MemoryBindingFlags::STRICT,
+            _not_send: PhantomData,
+        })
+    }
+
     /// Reset the memory allocation policy of the current process or thread to
     /// the system default
     ///
@@ -686,6 +805,131 @@ impl Topology {
         }
     }
 
+    /// Temporarily bind the memory identified by `target` to the NUMA node(s)
+    /// specified by `set`
+    ///
+    /// This works like [`Topology::bind_memory_area()`], except the current
+    /// binding of `target` is queried beforehand and restored when the
+    /// returned [`MemoryAreaBindingGuard`] is dropped.
+    ///
+    /// The warning about `Target` coverage in the documentation of
+    /// [`Topology::bind_memory_area()`] also applies here. The previous
+    /// binding is restored using `flags` without the [`STRICT`] flag.
+    ///
+    /// Requires [`MemoryBindingSupport::get_area()`] and
+    /// [`MemoryBindingSupport::set_area()`].
+    ///
+    /// # Errors
+    ///
+    /// - [`MixedResults`] if the current binding cannot be restored because
+    ///   the pages of `target` do not share a common binding
+    /// - Otherwise same as [`Topology::area_memory_binding()`] and
+    ///   [`Topology::bind_memory_area()`]
+    ///
+    /// If querying the current binding fails, the binding is left unchanged.
+    ///
+    /// [`MixedResults`]: MemoryBindingError::MixedResults
+    /// [`STRICT`]: MemoryBindingFlags::STRICT
+    pub fn bind_memory_area_scoped<'target, Target: ?Sized, Set: SpecializedBitmap>(
+        &self,
+        target: &'target Target,
+        set: &Set,
+        policy: MemoryBindingPolicy,
+        flags: MemoryBindingFlags,
+    ) -> Result<
+        MemoryAreaBindingGuard<'_, 'target, Target, Set::Owned>,
+        MemoryBindingError<Set::Owned>,
+    > {
+        let (previous_set, previous_policy) =
+            self.area_memory_binding::<Target, Set::Owned>(target, MemoryBindingFlags::STRICT)?;
+        let previous_policy = previous_policy.ok_or(MemoryBindingError::MixedResults)?;
+        self.bind_memory_area(target, set, policy, flags)?;
+        Ok(MemoryAreaBindingGuard {
+            topology: self,
+            target,
+            previous: Some((previous_set, previous_policy)),
+            flags: flags - MemoryBindingFlags::STRICT,
+        })
+    }
+
+    /// Migrate the memory pages of `data` to the NUMA node(s) specified by
+    /// `nodeset`
+    ///
+    /// This is a convenience wrapper around [`Topology::bind_memory_area()`]
+    /// with the [`MIGRATE`] flag, for the common case of moving the contents
+    /// of a `Box<[T]>` or `Vec<T>` to other NUMA nodes (pass `&mut vec[..]`
+    /// or `&mut *boxed` as `data`). Memory binding operates on whole pages, so
+    /// the area is internally extended to the enclosing page boundaries.
+    /// Beware that any other data that lies in these pages will be migrated
+    /// too.
+    ///
+    /// Exclusive access to `data` is required so that no other thread can
+    /// access it while its pages are being moved around.
+    ///
+    /// Requires [`MemoryBindingSupport::set_area()`] and
+    /// [`MemoryBindingSupport::migrate_flag()`].
+    ///
+    /// # Errors
+    ///
+    /// - [`BadSet`] if the system can't bind memory to that node set
+    /// - [`BadTarget`] if `data` is a zero-sized object
+    /// - [`Unsupported`] if the system cannot migrate the specified memory
+    ///   area with the requested policy
+    ///
+    /// [`BadSet`]: MemoryBindingError::BadSet
+    /// [`BadTarget`]: MemoryBindingError::BadTarget
+    /// [`MIGRATE`]: MemoryBindingFlags::MIGRATE
+    /// [`Unsupported`]: MemoryBindingError::Unsupported
+    pub fn migrate_to_nodes<T>(
+        &self,
+        data: &mut [T],
+        nodeset: &NodeSet,
+        policy: MemoryBindingPolicy,
+    ) -> Result<(), MemoryBindingError<NodeSet>> {
+        let data_size = std::mem::size_of_val(data);
+        if data_size == 0 {
+            return Err(MemoryBindingError::BadTarget);
+        }
+
+        // Round the target area to page boundaries
+        let page_size = page_size();
+        let data_ptr = data.as_mut_ptr().cast::<u8>();
+        let start_offset = data_ptr as usize % page_size;
+        let area_ptr = data_ptr.wrapping_sub(start_offset);
+        let area_size = (start_offset + data_size)
+            .checked_add(page_size - 1)
+            .map(|size| size / page_size * page_size)
+            .expect("Slices cannot span the end of the address space");
+
+        // SAFETY: - Area is the correct target for this FFI
+        //         - hwloc_set_area_membind with area_ptr and area_size
+        //           arguments curried away behaves like hwloc_set_membind
+        //         - FFI is guaranteed to be passed valid (topology,
+        //           set, policy, flags)
+        //         - area_ptr/area_size cover the pages of a valid slice, that
+        //           we have exclusive access to
+        //         - area_size cannot be zero since data_size is nonzero
+        unsafe {
+            self.bind_memory_impl(
+                "hwloc_set_area_membind",
+                nodeset,
+                policy,
+                MemoryBindingFlags::MIGRATE,
+                MemoryBoundObject::Area,
+                |topology, set, policy, flags| {
+                    hwlocality_sys::hwloc_set_area_membind(
+                        topology,
+                        area_ptr.cast::<c_void>(),
+                        area_size,
+                        set,
+                        policy,
+                        flags,
+                    )
+                },
+            )
+        }
+    }
+
     /// Reset the memory allocation policy of the memory identified by `target`
     /// to the system default
     ///
@@ -1485,6 +1729,164 @@ pub(crate) fn call_hwloc_int<OwnedSet: OwnedSpecializedBitmap>(
     }
 }
 
+/// Size of a memory page on the host system
+///
+/// hwloc does not expose its own page size query, so we use the OS one.
+fn page_size() -> usize {
+    #[cfg(unix)]
+    {
+        // SAFETY: sysconf has no safety preconditions
+        let page_size = unsafe { libc::sysconf(libc::_SC_PAGESIZE) };
+        usize::try_from(page_size)
+            .ok()
+            .filter(|&size| size > 0)
+            .expect("Failed to query the system page size")
+    }
+    #[cfg(not(unix))]
+    {
+        // Memory area binding is not supported by hwloc on other operating
+        // systems, so this is only used to produce BadFlags/Unsupported errors
+        4096
+    }
+}
+
+/// Guard that restores the previous default memory binding of the current
+/// process or thread when dropped
+///
+/// Obtained via [`Topology::bind_memory_scoped()`].
+///
+/// Since the binding is restored by the thread that drops the guard, this type
+/// cannot be sent to another thread.
+#[derive(Debug)]
+#[must_use = "the previous memory binding is restored as soon as the guard is dropped"]
+pub struct MemoryBindingGuard<'topology, OwnedSet: OwnedSpecializedBitmap> {
+    /// Topology that was used to set up the binding
+    topology: &'topology Topology,
+
+    /// Binding to be restored, `None` once it has been restored
+    previous: Option<(OwnedSet, MemoryBindingPolicy)>,
+
+    /// Flags to be used when restoring the binding
+    flags: MemoryBindingFlags,
+
+    /// Binding of the current thread must be restored by the same thread
+    _not_send: PhantomData<*const ()>,
+}
+//
+impl<OwnedSet: OwnedSpecializedBitmap> MemoryBindingGuard<'_, OwnedSet> {
+    /// Memory binding set and policy that will be restored when this guard is
+    /// dropped
+    pub fn previous_binding(&self) -> (&OwnedSet, MemoryBindingPolicy) {
+        let (set, policy) = self
+            .previous
+            .as_ref()
+            .expect("previous binding is only taken when the guard is consumed");
+        (set, *policy)
+    }
+
+    /// Restore the previous memory binding now, reporting errors
+    ///
+    /// Dropping the guard restores the previous binding too, but can only
+    /// report errors by printing them to stderr.
+    ///
+    /// # Errors
+    ///
+    /// Same as [`Topology::bind_memory()`].
+    pub fn restore(mut self) -> Result<(), MemoryBindingError<OwnedSet>> {
+        self.restore_impl()
+    }
+
+    /// Restore the previous memory binding, if not done already
+    fn restore_impl(&mut self) -> Result<(), MemoryBindingError<OwnedSet>> {
+        let Some((set, policy)) = self.previous.take() else {
+            return Ok(());
+        };
+        self.topology.bind_memory(&set, policy, self.flags)
+    }
+}
+//
+impl<OwnedSet: OwnedSpecializedBitmap> Drop for MemoryBindingGuard<'_, OwnedSet> {
+    #[allow(clippy::print_stderr)]
+    fn drop(&mut self) {
+        if let Err(e) = self.restore_impl() {
+            // Cannot panic in Drop
+            eprintln!("ERROR: Failed to restore previous memory binding ({e}).");
+        }
+    }
+}
+
+/// Guard that restores the previous memory binding of a memory area when
+/// dropped
+///
+/// Obtained via [`Topology::bind_memory_area_scoped()`].
+#[derive(Debug)]
+#[must_use = "the previous memory binding is restored as soon as the guard is dropped"]
+pub struct MemoryAreaBindingGuard<
+    'topology,
+    'target,
+    Target: ?Sized,
+    OwnedSet: OwnedSpecializedBitmap,
+> {
+    /// Topology that was used to set up the binding
+    topology: &'topology Topology,
+
+    /// Memory area whose binding was changed
+    target: &'target Target,
+
+    /// Binding to be restored, `None` once it has been restored
+    previous: Option<(OwnedSet, MemoryBindingPolicy)>,
+
+    /// Flags to be used when restoring the binding
+    flags: MemoryBindingFlags,
+}
+//
+impl<Target: ?Sized, OwnedSet: OwnedSpecializedBitmap>
+    MemoryAreaBindingGuard<'_, '_, Target, OwnedSet>
+{
+    /// Memory binding set and policy that will be restored when this guard is
+    /// dropped
+    pub fn previous_binding(&self) -> (&OwnedSet, MemoryBindingPolicy) {
+        let (set, policy) = self
+            .previous
+            .as_ref()
+            .expect("previous binding is only taken when the guard is consumed");
+        (set, *policy)
+    }
+
+    /// Restore the previous memory binding now, reporting errors
+    ///
+    /// Dropping the guard restores the previous binding too, but can only
+    /// report errors by printing them to stderr.
+    ///
+    /// # Errors
+    ///
+    /// Same as [`Topology::bind_memory_area()`].
+    pub fn restore(mut self) -> Result<(), MemoryBindingError<OwnedSet>> {
+        self.restore_impl()
+    }
+
+    /// Restore the previous memory binding, if not done already
+    fn restore_impl(&mut self) -> Result<(), MemoryBindingError<OwnedSet>> {
+        let Some((set, policy)) = self.previous.take() else {
+            return Ok(());
+        };
+        self.topology
+            .bind_memory_area(self.target, &set, policy, self.flags)
+    }
+}
+//
+impl<Target: ?Sized, OwnedSet: OwnedSpecializedBitmap> Drop
+    for MemoryAreaBindingGuard<'_, '_, Target, OwnedSet>
+{
+    #[allow(clippy::print_stderr)]
+    fn drop(&mut self) {
+        if let Err(e) = self.restore_impl() {
+            // Cannot panic in Drop
+            eprintln!("ERROR: Failed to restore previous memory area binding ({e}).");
+        }
+    }
+}
+
 /// Errors that can occur when allocating memory
 pub type MemoryAllocationError<OwnedSet> = MemoryBindingError<OwnedSet>;
 
@@ -1559,6 +1961,15 @@ impl<'topology> Bytes<'topology> {
             data: NonNull::slice_from_raw_parts(base.cast::<MaybeUninit<u8>>(), size),
         }
     }
+
+    /// Release ownership of the allocation without liberating it
+    ///
+    /// The allocation can later be liberated by wrapping it again with
+    /// [`Bytes::wrap()`] and dropping the result.
+    #[cfg(feature = "allocator-api2")]
+    pub(crate) fn into_raw(self) -> NonNull<[MaybeUninit<u8>]> {
+        std::mem::ManuallyDrop::new(self).data
+    }
 }
 //
 impl AsRef<[MaybeUninit<u8>]> for Bytes<'_> {
@@ -1637,3 +2048,195 @@ unsafe impl Send for Bytes<'_> {}
 //
 // SAFETY: Exposes no internal mutability
 unsafe impl Sync for Bytes<'_> {}
+
+/// Zero-initialized memory that was allocated on specific NUMA nodes
+///
+/// Obtained via [`Topology::allocate_zeroed_bound_memory()`].
+///
+/// This behaves like a `Box<[u8]>` and will similarly automatically liberate
+/// the allocated memory when it goes out of scope. In addition, it remembers
+/// the NUMA nodes and policy that it was allocated with.
+pub struct BoundMemory<'topology> {
+    /// Underlying hwloc allocation, which is fully initialized
+    bytes: Bytes<'topology>,
+
+    /// NUMA nodes that the memory was bound to
+    nodeset: NodeSet,
+
+    /// Policy that the memory was bound with
+    policy: MemoryBindingPolicy,
+}
+//
+impl<'topology> BoundMemory<'topology> {
+    /// NUMA nodes that this memory was requested to be bound to
+    ///
+    /// If the allocation was requested using a [`CpuSet`], this is the set of
+    /// NUMA nodes that are local to these CPUs.
+    pub fn nodeset(&self) -> &NodeSet {
+        &self.nodeset
+    }
+
+    /// Policy that this memory was requested to be bound with
+    pub fn policy(&self) -> MemoryBindingPolicy {
+        self.policy
+    }
+
+    /// Reinterpret this memory as a slice of `T`
+    ///
+    /// `T` must be a plain data type without padding bytes or interior
+    /// mutability, for which any bit pattern is valid, as attested by the
+    /// [`Pod`] trait. Types with padding like `(u8, u16)` are rejected, as
+    /// writing them through [`as_mut_slice_of()`](Self::as_mut_slice_of)
+    /// would leave uninitialized bytes behind.
+    ///
+    /// Returns `None` if `T` is zero-sized, if the memory is not suitably
+    /// aligned for `T`, or if its length is not a multiple of the size of `T`.
+    ///
+    /// # Examples
+    ///
+    /// ```compile_fail
+    /// # use hwlocality::{memory::binding::{MemoryBindingFlags, MemoryBindingPolicy}, Topology};
+    /// # let topology = Topology::test_instance();
+    /// # let memory = topology.allocate_zeroed_bound_memory(
+    /// #     4096,
+    /// #     &topology.nodeset(),
+    /// #     MemoryBindingPolicy::Bind,
+    /// #     MemoryBindingFlags::empty(),
+    /// # ).unwrap();
+    /// // Error: (u8, u16) has a padding byte, so it does not implement Pod
+    /// let padded = memory.as_slice_of::<(u8, u16)>();
+    /// ```
+    pub fn as_slice_of<T: Pod>(&self) -> Option<&[T]> {
+        if std::mem::size_of::<T>() == 0 {
+            return None;
+        }
+        bytemuck::try_cast_slice(self.as_ref()).ok()
+    }
+
+    /// Reinterpret this memory as a mutable slice of `T`
+    ///
+    /// Has the same requirements on `T` as [`as_slice_of()`](Self::as_slice_of),
+    /// and returns `None` under the same conditions.
+    pub fn as_mut_slice_of<T: Pod>(&mut self) -> Option<&mut [T]> {
+        if std::mem::size_of::<T>() == 0 {
+            return None;
+        }
+        bytemuck::try_cast_slice_mut(self.as_mut()).ok()
+    }
+
+    /// Give up the binding metadata and get back the underlying [`Bytes`]
+    pub fn into_bytes(self) -> Bytes<'topology> {
+        self.bytes
+    }
+}
+//
+impl AsRef<[u8]> for BoundMemory<'_> {
+    fn as_ref(&self) -> &[u8] {
+        let bytes = self.bytes.as_ref();
+        // SAFETY: - MaybeUninit<u8> has the same layout as u8
+        //         - The bytes were zero-initialized on allocation and can
+        //           only be overwritten with initialized bytes afterwards,
+        //           since typed access is restricted to padding-free Pod types
+        unsafe { std::slice::from_raw_parts(bytes.as_ptr().cast::<u8>(), bytes.len()) }
+    }
+}
+//
+impl AsMut<[u8]> for BoundMemory<'_> {
+    fn as_mut(&mut self) -> &mut [u8] {
+        let bytes = self.bytes.as_mut();
+        // SAFETY: - MaybeUninit<u8> has the same layout as u8
+        //         - The bytes were zero-initialized on allocation and can
+        //           only be overwritten with initialized bytes afterwards,
+        //           since typed access is restricted to padding-free Pod types
+        unsafe { std::slice::from_raw_parts_mut(bytes.as_mut_ptr().cast::<u8>(), bytes.len()) }
+    }
+}
+//
+impl Borrow<[u8]> for BoundMemory<'_> {
+    fn borrow(&self) -> &[u8] {
+        self.as_ref()
+    }
+}
+//
+impl BorrowMut<[u8]> for BoundMemory<'_> {
+    fn borrow_mut(&mut self) -> &mut [u8] {
+        self.as_mut()
+    }
+}
+//
+impl Debug for BoundMemory<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("BoundMemory")
+            .field("bytes", &self.as_ref())
+            .field("nodeset", &self.nodeset)
+            .field("policy", &self.policy)
+            .finish()
+    }
+}
+//
+impl Deref for BoundMemory<'_> {
+    type Target = [u8];
+
+    fn deref(&self) -> &[u8] {
+        self.as_ref()
+    }
+}
+//
+impl DerefMut for BoundMemory<'_> {
+    fn deref_mut(&mut self) -> &mut [u8] {
+        self.as_mut()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[allow(unused)]
+    use similar_asserts::assert_eq;
+    use static_assertions::assert_not_impl_any;
+
+    // Types with padding bytes must not be usable with BoundMemory's typed
+    // accessors, since writing them would de-initialize the padding bytes
+    // that the [u8] view of the memory exposes
+    assert_not_impl_any!((u8, u16): Pod);
+    /// Padded `#[repr(C)]` struct
+    #[allow(dead_code)]
+    #[repr(C)]
+    struct Padded {
+        /// Followed by a padding byte
+        byte: u8,
+        /// Aligned to 2 bytes
+        half: u16,
+    }
+    assert_not_impl_any!(Padded: Pod);
+
+    /// Zero-initialized bound memory should remember its binding
+    #[test]
+    fn allocate_zeroed_bound_memory() {
+        let topology = Topology::test_instance();
+        let policy = MemoryBindingPolicy::Bind;
+        let flags = MemoryBindingFlags::empty();
+        let Ok(mut memory) =
+            topology.allocate_zeroed_bound_memory(64, &topology.cpuset(), policy, flags)
+        else {
+            return;
+        };
+        assert_eq!(memory.len(), 64);
+        assert!(memory.iter().all(|&byte| byte == 0));
+        assert_eq!(
+            memory.nodeset(),
+            &NodeSet::from_cpuset(topology, topology.cpuset())
+        );
+        assert_eq!(memory.policy(), policy);
+
+        memory[1] = 42;
+        assert_eq!(memory.as_ref()[1], 42);
+        assert_eq!(memory.as_slice_of::<u8>().unwrap()[1], 42);
+        assert!(memory.as_slice_of::<()>().is_none());
+        assert!(memory.as_slice_of::<[u8; 3]>().is_none());
+        if memory.as_ptr() as usize % 2 == 0 {
+            assert_eq!(memory.as_mut_slice_of::<u16>().unwrap().len(), 32);
+        }
+        assert_eq!(memory.into_bytes().len(), 64);
+    }
+}
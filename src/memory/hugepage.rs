@@ -0,0 +1,430 @@
+//! Huge page-backed memory allocation
+//!
+//! Memory-intensive programs often benefit from backing their large buffers
+//! with huge pages (e.g. 2 MiB pages on `x86_64`), which reduces TLB pressure.
+//! hwloc's memory allocation functions cannot request huge pages, so this
+//! module provides [`Topology::allocate_bound_huge_memory()`], which allocates
+//! huge pages from the operating system where it is supported (using
+//! `mmap(MAP_HUGETLB)` on Linux), binds them to the requested NUMA nodes, and
+//! otherwise falls back to a normal hwloc allocation while reporting why huge
+//! pages could not be used.
+//!
+//! This functionality is an hwlocality-specific extension to the hwloc API.
+
+use crate::{
+    bitmap::SpecializedBitmap,
+    memory::{
+        binding::{
+            Bytes, MemoryAllocationError, MemoryBindingError, MemoryBindingFlags,
+            MemoryBindingPolicy,
+        },
+        nodeset::NodeSet,
+    },
+    topology::Topology,
+};
+#[cfg(target_os = "linux")]
+use crate::{
+    bitmap::{Bitmap, BitmapKind},
+    cpu::cpuset::CpuSet,
+};
+use errno::Errno;
+#[allow(unused)]
+#[cfg(test)]
+use similar_asserts::assert_eq;
+use std::{
+    borrow::{Borrow, BorrowMut},
+    fmt::{self, Debug},
+    mem::MaybeUninit,
+    num::NonZeroUsize,
+    ops::{Deref, DerefMut},
+};
+#[cfg(target_os = "linux")]
+use std::{ffi::c_void, fs, ptr::NonNull};
+use thiserror::Error;
+
+/// # Huge page memory allocation
+impl Topology {
+    /// Allocate some memory on NUMA nodes specified by `set`, backed by huge
+    /// pages if possible
+    ///
+    /// This works like [`Topology::allocate_bound_memory()`], except that the
+    /// memory is first requested from the operating system's huge page pool
+    /// and bound to `set` with [`Topology::bind_memory_area()`]. If either of
+    /// these steps fails, the memory is allocated with
+    /// [`Topology::allocate_bound_memory()`] instead, and
+    /// [`HugePageMemory::fallback()`] tells why huge pages could not be used.
+    ///
+    /// Huge pages are currently only supported on Linux, where they must have
+    /// been reserved by the system administrator beforehand (see
+    /// `/proc/sys/vm/nr_hugepages`). The allocation is rounded up to a
+    /// multiple of the default huge page size, which you can check using
+    /// [`HugePageMemory::huge_page_size()`].
+    ///
+    /// Binding target flags [`ASSUME_SINGLE_THREAD`], [`PROCESS`],
+    /// [`THREAD`] and [`MIGRATE`] should not be used with this method.
+    ///
+    /// This functionality is an hwlocality-specific extension to the hwloc API.
+    ///
+    /// # Errors
+    ///
+    /// Huge page allocation failures are not errors, but the fallback
+    /// allocation can fail in the same ways as
+    /// [`Topology::allocate_bound_memory()`].
+    ///
+    /// [`ASSUME_SINGLE_THREAD`]: MemoryBindingFlags::ASSUME_SINGLE_THREAD
+    /// [`MIGRATE`]: MemoryBindingFlags::MIGRATE
+    /// [`PROCESS`]: MemoryBindingFlags::PROCESS
+    /// [`THREAD`]: MemoryBindingFlags::THREAD
+    pub fn allocate_bound_huge_memory<Set: SpecializedBitmap>(
+        &self,
+        len: usize,
+        set: &Set,
+        policy: MemoryBindingPolicy,
+        flags: MemoryBindingFlags,
+    ) -> Result<HugePageMemory<'_>, MemoryAllocationError<Set::Owned>> {
+        #[cfg(target_os = "linux")]
+        let fallback = match HugePageMapping::new(len) {
+            Ok(mapping) => {
+                let set: Bitmap = set.to_owned().into();
+                let nodeset = match Set::BITMAP_KIND {
+                    BitmapKind::CpuSet => NodeSet::from_cpuset(self, &CpuSet::from(set)),
+                    BitmapKind::NodeSet => NodeSet::from(set),
+                };
+                match self.bind_memory_area::<[MaybeUninit<u8>], NodeSet>(
+                    &mapping, &nodeset, policy, flags,
+                ) {
+                    Ok(()) => {
+                        return Ok(HugePageMemory {
+                            storage: Storage::Mapped(mapping),
+                            fallback: None,
+                        })
+                    }
+                    Err(e) => HugePageFallback::BindingFailed(e),
+                }
+            }
+            Err(fallback) => fallback,
+        };
+        #[cfg(not(target_os = "linux"))]
+        let fallback = HugePageFallback::Unsupported;
+        let bytes = self.allocate_bound_memory(len, set, policy, flags)?;
+        Ok(HugePageMemory {
+            storage: Storage::Hwloc(bytes),
+            fallback: Some(fallback),
+        })
+    }
+}
+
+/// Memory allocated via [`Topology::allocate_bound_huge_memory()`]
+///
+/// This behaves like [`Bytes`] and will similarly automatically liberate the
+/// allocated memory when it goes out of scope. In addition, it tells whether
+/// the memory is backed by huge pages.
+pub struct HugePageMemory<'topology> {
+    /// Underlying allocation
+    storage: Storage<'topology>,
+
+    /// Reason why huge pages could not be used, if any
+    fallback: Option<HugePageFallback>,
+}
+//
+impl HugePageMemory<'_> {
+    /// Size of the huge pages that back this memory, if it is backed by huge
+    /// pages
+    pub fn huge_page_size(&self) -> Option<NonZeroUsize> {
+        match &self.storage {
+            #[cfg(target_os = "linux")]
+            Storage::Mapped(mapping) => Some(mapping.page_size),
+            Storage::Hwloc(_) => None,
+        }
+    }
+
+    /// Reason why this memory is not backed by huge pages, if it isn't
+    pub fn fallback(&self) -> Option<&HugePageFallback> {
+        self.fallback.as_ref()
+    }
+}
+//
+impl AsRef<[MaybeUninit<u8>]> for HugePageMemory<'_> {
+    fn as_ref(&self) -> &[MaybeUninit<u8>] {
+        match &self.storage {
+            #[cfg(target_os = "linux")]
+            Storage::Mapped(mapping) => mapping,
+            Storage::Hwloc(bytes) => bytes,
+        }
+    }
+}
+//
+impl AsMut<[MaybeUninit<u8>]> for HugePageMemory<'_> {
+    fn as_mut(&mut self) -> &mut [MaybeUninit<u8>] {
+        match &mut self.storage {
+            #[cfg(target_os = "linux")]
+            Storage::Mapped(mapping) => mapping,
+            Storage::Hwloc(bytes) => bytes,
+        }
+    }
+}
+//
+impl Borrow<[MaybeUninit<u8>]> for HugePageMemory<'_> {
+    fn borrow(&self) -> &[MaybeUninit<u8>] {
+        self.as_ref()
+    }
+}
+//
+impl BorrowMut<[MaybeUninit<u8>]> for HugePageMemory<'_> {
+    fn borrow_mut(&mut self) -> &mut [MaybeUninit<u8>] {
+        self.as_mut()
+    }
+}
+//
+impl Debug for HugePageMemory<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("HugePageMemory")
+            .field("bytes", &self.as_ref())
+            .field("huge_page_size", &self.huge_page_size())
+            .field("fallback", &self.fallback)
+            .finish()
+    }
+}
+//
+impl Deref for HugePageMemory<'_> {
+    type Target = [MaybeUninit<u8>];
+
+    fn deref(&self) -> &Self::Target {
+        self.as_ref()
+    }
+}
+//
+impl DerefMut for HugePageMemory<'_> {
+    fn deref_mut(&mut self) -> &mut [MaybeUninit<u8>] {
+        self.as_mut()
+    }
+}
+
+/// Reason why [`Topology::allocate_bound_huge_memory()`] could not use huge
+/// pages
+#[derive(Clone, Debug, Eq, Error, Hash, PartialEq)]
+pub enum HugePageFallback {
+    /// Huge page allocation is not supported on this operating system
+    #[error("huge pages are not supported on this operating system")]
+    Unsupported,
+
+    /// The operating system failed to allocate huge pages
+    ///
+    /// This usually means that not enough huge pages were reserved by the
+    /// system administrator.
+    #[error("failed to allocate huge pages: {0}")]
+    AllocationFailed(Errno),
+
+    /// Huge pages were allocated, but could not be bound to the requested
+    /// NUMA nodes
+    ///
+    /// If the binding target was specified as a CPU set, it was translated
+    /// into the matching [`NodeSet`] before binding, so the error is always
+    /// expressed in terms of NUMA nodes.
+    #[error("failed to bind huge pages to the requested NUMA nodes")]
+    BindingFailed(#[source] MemoryBindingError<NodeSet>),
+}
+
+/// Storage of a [`HugePageMemory`]
+enum Storage<'topology> {
+    /// Memory mapping backed by huge pages
+    #[cfg(target_os = "linux")]
+    Mapped(HugePageMapping),
+
+    /// Fallback hwloc allocation
+    Hwloc(Bytes<'topology>),
+}
+
+/// Anonymous memory mapping backed by huge pages
+//
+// --- Implementation details ---
+//
+// # Safety
+//
+// `data` is an owned (valid, non-aliased) anonymous memory mapping, whose
+// length `mapped_len` is a nonzero multiple of `page_size`, that should be
+// unmapped on Drop. Its first `len` bytes are exposed.
+#[cfg(target_os = "linux")]
+struct HugePageMapping {
+    /// Base address of the mapping
+    data: NonNull<c_void>,
+
+    /// Number of bytes that were requested by the user
+    len: usize,
+
+    /// Number of bytes that were mapped
+    mapped_len: usize,
+
+    /// Size of the huge pages that back the mapping
+    page_size: NonZeroUsize,
+}
+//
+#[cfg(target_os = "linux")]
+impl HugePageMapping {
+    /// Map `len` bytes of anonymous memory backed by default-sized huge pages
+    fn new(len: usize) -> Result<Self, HugePageFallback> {
+        let page_size = default_huge_page_size().ok_or(HugePageFallback::Unsupported)?;
+        let page_size_minus_1 = page_size.get() - 1;
+        let mapped_len = len
+            .checked_add(page_size_minus_1)
+            .map(|len| len / page_size * page_size.get())
+            .ok_or(HugePageFallback::AllocationFailed(Errno(libc::ENOMEM)))?;
+        if mapped_len == 0 {
+            return Err(HugePageFallback::AllocationFailed(Errno(libc::EINVAL)));
+        }
+        // SAFETY: - Requesting a new anonymous mapping at an address of the
+        //           kernel's choosing cannot affect existing memory
+        //         - mapped_len is nonzero and a multiple of the page size
+        let base = unsafe {
+            libc::mmap(
+                std::ptr::null_mut(),
+                mapped_len,
+                libc::PROT_READ | libc::PROT_WRITE,
+                libc::MAP_PRIVATE | libc::MAP_ANONYMOUS | libc::MAP_HUGETLB,
+                -1,
+                0,
+            )
+        };
+        if base == libc::MAP_FAILED {
+            return Err(HugePageFallback::AllocationFailed(errno::errno()));
+        }
+        Ok(Self {
+            data: NonNull::new(base).expect("successful mmap shouldn't return NULL"),
+            len,
+            mapped_len,
+            page_size,
+        })
+    }
+}
+//
+#[cfg(target_os = "linux")]
+impl Deref for HugePageMapping {
+    type Target = [MaybeUninit<u8>];
+
+    fn deref(&self) -> &Self::Target {
+        // SAFETY: Per type invariant
+        unsafe { std::slice::from_raw_parts(self.data.as_ptr().cast(), self.len) }
+    }
+}
+//
+#[cfg(target_os = "linux")]
+impl DerefMut for HugePageMapping {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        // SAFETY: Per type invariant
+        unsafe { std::slice::from_raw_parts_mut(self.data.as_ptr().cast(), self.len) }
+    }
+}
+//
+#[cfg(target_os = "linux")]
+impl Drop for HugePageMapping {
+    #[allow(clippy::print_stderr)]
+    fn drop(&mut self) {
+        // SAFETY: - data and mapped_len describe an owned mapping per type
+        //           invariant
+        //         - The mapping will not be usable again after Drop
+        if unsafe { libc::munmap(self.data.as_ptr(), self.mapped_len) } != 0 {
+            // Cannot panic in Drop
+            eprintln!(
+                "ERROR: Failed to unmap huge page allocation ({}).",
+                errno::errno()
+            );
+        }
+    }
+}
+//
+// SAFETY: Exposes no internal mutability
+#[cfg(target_os = "linux")]
+unsafe impl Send for HugePageMapping {}
+//
+// SAFETY: Exposes no internal mutability
+#[cfg(target_os = "linux")]
+unsafe impl Sync for HugePageMapping {}
+
+/// Default huge page size of the host system, if huge pages are supported
+#[cfg(target_os = "linux")]
+fn default_huge_page_size() -> Option<NonZeroUsize> {
+    parse_huge_page_size(&fs::read_to_string("/proc/meminfo").ok()?)
+}
+
+/// Extract the default huge page size from the contents of `/proc/meminfo`
+#[cfg(target_os = "linux")]
+fn parse_huge_page_size(meminfo: &str) -> Option<NonZeroUsize> {
+    let line = meminfo
+        .lines()
+        .find_map(|line| line.strip_prefix("Hugepagesize:"))?;
+    let kibibytes = line
+        .trim()
+        .strip_suffix("kB")?
+        .trim()
+        .parse::<usize>()
+        .ok()?;
+    NonZeroUsize::new(kibibytes.checked_mul(1024)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[allow(unused)]
+    use similar_asserts::assert_eq;
+    use static_assertions::{assert_impl_all, assert_not_impl_any};
+    use std::{
+        error::Error,
+        fmt::{Binary, Display, LowerExp, LowerHex, Octal, Pointer, UpperExp, UpperHex},
+        hash::Hash,
+        io::{self, Read},
+        panic::UnwindSafe,
+    };
+
+    // Check that public types in this module keep implementing all expected
+    // traits, in the interest of detecting future semver-breaking changes
+    assert_impl_all!(HugePageFallback:
+        Clone, Display, Error, Hash, Sized, Sync, Unpin, UnwindSafe
+    );
+    assert_not_impl_any!(HugePageFallback:
+        Binary, Copy, Default, Deref, Drop, IntoIterator, LowerExp, LowerHex, Octal,
+        PartialOrd, Pointer, Read, UpperExp, UpperHex, fmt::Write, io::Write
+    );
+    assert_impl_all!(HugePageMemory<'static>:
+        AsMut<[MaybeUninit<u8>]>, AsRef<[MaybeUninit<u8>]>, Debug,
+        Deref<Target = [MaybeUninit<u8>]>, DerefMut, Send, Sized, Sync, Unpin
+    );
+    assert_not_impl_any!(HugePageMemory<'static>:
+        Binary, Clone, Default, Display, IntoIterator, LowerExp, LowerHex,
+        Octal, PartialEq, Pointer, Read, UpperExp, UpperHex, fmt::Write,
+        io::Write
+    );
+
+    /// Check parsing of `/proc/meminfo`
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn huge_page_size() {
+        let meminfo = "MemTotal:       16318412 kB\nHugepagesize:       2048 kB\n";
+        assert_eq!(
+            parse_huge_page_size(meminfo),
+            NonZeroUsize::new(2 * 1024 * 1024)
+        );
+        assert_eq!(parse_huge_page_size("MemTotal:       16318412 kB\n"), None);
+    }
+
+    /// Check that huge page allocation either succeeds or falls back
+    #[test]
+    fn allocate_bound_huge_memory() {
+        let topology = Topology::test_instance();
+        let Ok(mut memory) = topology.allocate_bound_huge_memory(
+            1000,
+            &topology.nodeset(),
+            MemoryBindingPolicy::Bind,
+            MemoryBindingFlags::empty(),
+        ) else {
+            return;
+        };
+        assert_eq!(memory.len(), 1000);
+        assert_ne!(
+            memory.huge_page_size().is_some(),
+            memory.fallback().is_some()
+        );
+        #[cfg(not(target_os = "linux"))]
+        assert_eq!(memory.fallback(), Some(&HugePageFallback::Unsupported));
+        memory.fill(MaybeUninit::new(42));
+    }
+}
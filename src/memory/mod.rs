@@ -4,9 +4,12 @@
 //! [`Topology`] struct. The module itself only hosts type definitions that are
 //! related to this functionality.
 
+#[cfg(feature = "allocator-api2")]
+pub mod allocator;
 #[cfg(feature = "hwloc-2_3_0")]
 pub mod attribute;
 pub mod binding;
+pub mod hugepage;
 pub mod nodeset;
 
 #[cfg(doc)]
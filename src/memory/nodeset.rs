@@ -5,7 +5,10 @@
 
 #[cfg(doc)]
 use crate::{bitmap::Bitmap, topology::support::DiscoverySupport};
-use crate::{cpu::cpuset::CpuSet, impl_bitmap_newtype, topology::Topology};
+use crate::{
+    cpu::cpuset::CpuSet, errors::ObjectWithoutSetsError, impl_bitmap_newtype,
+    object::TopologyObject, topology::Topology,
+};
 #[allow(unused)]
 #[cfg(test)]
 use similar_asserts::assert_eq;
@@ -44,6 +47,46 @@ impl NodeSet {
         }
         polymorphized(topology, &cpuset)
     }
+    /// Union of the nodesets of some topology objects
+    ///
+    /// This is the fallible version of the [`FromIterator`] implementation
+    /// that collects topology objects into a `NodeSet`, which panics when one of
+    /// the objects does not have a nodeset.
+    ///
+    /// # Errors
+    ///
+    /// - [`ObjectWithoutSetsError`] if one of the `objects` does not have a
+    ///   nodeset, which is the case of I/O and Misc objects
+    pub fn try_from_objects<'topology>(
+        objects: impl IntoIterator<Item = &'topology TopologyObject>,
+    ) -> Result<Self, ObjectWithoutSetsError> {
+        let mut result = Self::new();
+        result.try_extend_from_objects(objects)?;
+        Ok(result)
+    }
+
+    /// Add the nodesets of some topology objects to this `NodeSet`
+    ///
+    /// This is the fallible version of the [`Extend`] implementation that
+    /// adds topology objects to a `NodeSet`. If an error occurs, the nodesets
+    /// of the objects that were processed before the failing one have already
+    /// been added to this `NodeSet`.
+    ///
+    /// # Errors
+    ///
+    /// - [`ObjectWithoutSetsError`] if one of the `objects` does not have a
+    ///   nodeset, which is the case of I/O and Misc objects
+    pub fn try_extend_from_objects<'topology>(
+        &mut self,
+        objects: impl IntoIterator<Item = &'topology TopologyObject>,
+    ) -> Result<(), ObjectWithoutSetsError> {
+        for obj in objects {
+            *self |= obj
+                .nodeset()
+                .ok_or_else(|| ObjectWithoutSetsError::from(obj))?;
+        }
+        Ok(())
+    }
 }
 
 impl_bitmap_newtype!(
@@ -60,12 +103,73 @@ impl_bitmap_newtype!(
     NodeSet
 );
 
+/// Collect topology objects into the union of their nodesets
+///
+/// # Panics
+///
+/// If one of the objects does not have a nodeset, which is the case of I/O
+/// and Misc objects. Use [`NodeSet::try_from_objects()`] if you need to handle
+/// this situation.
+impl<'topology> FromIterator<&'topology TopologyObject> for NodeSet {
+    fn from_iter<I: IntoIterator<Item = &'topology TopologyObject>>(iter: I) -> Self {
+        let mut result = Self::new();
+        result.extend(iter);
+        result
+    }
+}
+
+/// Add the nodesets of topology objects to a `NodeSet`
+///
+/// # Panics
+///
+/// If one of the objects does not have a nodeset, which is the case of I/O
+/// and Misc objects. Use [`NodeSet::try_extend_from_objects()`] if you need to
+/// handle this situation.
+impl<'topology> Extend<&'topology TopologyObject> for NodeSet {
+    fn extend<I: IntoIterator<Item = &'topology TopologyObject>>(&mut self, iter: I) {
+        if let Err(e) = self.try_extend_from_objects(iter) {
+            panic!("can't collect objects into a NodeSet: {e}")
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::strategies::topology_related_set;
     use proptest::prelude::*;
 
+    proptest! {
+        /// Test for collecting topology objects into a [`NodeSet`]
+        #[test]
+        fn nodeset_from_objects(
+            objects in prop::sample::subsequence(
+                Topology::test_objects().to_vec(),
+                0..=Topology::test_objects().len()
+            )
+        ) {
+            let result = NodeSet::try_from_objects(objects.iter().copied());
+            if objects.iter().all(|obj| obj.nodeset().is_some()) {
+                let expected = objects
+                    .iter()
+                    .map(|obj| obj.nodeset().unwrap().clone_target())
+                    .reduce(|set1, set2| set1 | set2)
+                    .unwrap_or_default();
+                prop_assert_eq!(result.unwrap(), &expected);
+                prop_assert_eq!(objects.iter().copied().collect::<NodeSet>(), &expected);
+                let mut extended = NodeSet::new();
+                extended.extend(objects.iter().copied());
+                prop_assert_eq!(extended, expected);
+            } else {
+                let first_without_set = objects.iter().find(|obj| obj.nodeset().is_none()).unwrap();
+                prop_assert_eq!(
+                    result.unwrap_err(),
+                    ObjectWithoutSetsError::from(*first_without_set)
+                );
+            }
+        }
+    }
+
     proptest! {
         /// Test for [`NodeSet::from_cpuset()`]
         #[test]
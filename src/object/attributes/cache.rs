@@ -171,6 +171,53 @@ unsafe impl TransparentNewtype for CacheAttributes {
     type Inner = hwloc_cache_attr_s;
 }
 
+/// Properties of a CPU or memory-side cache
+///
+/// This gathers the [`CacheAttributes`] of a cache object together with the
+/// properties that hwloc reports as textual info attributes, such as whether
+/// the cache is inclusive.
+///
+/// You can get this using [`TopologyObject::cache_info()`].
+///
+/// [`TopologyObject::cache_info()`]: crate::object::TopologyObject::cache_info()
+#[derive(Copy, Clone, Debug, Eq, Hash, PartialEq)]
+pub struct CacheInfo {
+    /// Size of the cache in bytes, if known
+    pub size: Option<NonZeroU64>,
+
+    /// Depth of the cache (e.g. 1 for L1, 2 for L2, ...)
+    pub depth: usize,
+
+    /// Cache line size in bytes, if known
+    pub line_size: Option<NonZeroUsize>,
+
+    /// Ways of associativity
+    pub associativity: CacheAssociativity,
+
+    /// Cache type
+    pub cache_type: CacheType,
+
+    /// Truth that the cache is inclusive, if known
+    ///
+    /// An inclusive cache contains a copy of all the data that is cached by
+    /// the lower-level caches below it. This is the `Inclusive` info attribute.
+    pub inclusive: Option<bool>,
+}
+//
+impl CacheInfo {
+    /// Gather the properties of a cache
+    pub(crate) fn new(attr: &CacheAttributes, inclusive: Option<bool>) -> Self {
+        Self {
+            size: attr.size(),
+            depth: attr.depth(),
+            line_size: attr.line_size(),
+            associativity: attr.associativity(),
+            cache_type: attr.cache_type(),
+            inclusive,
+        }
+    }
+}
+
 /// Cache associativity
 #[derive(Copy, Clone, Debug, Default, Eq, Hash, PartialEq)]
 pub enum CacheAssociativity {
@@ -253,6 +300,14 @@ pub(super) mod tests {
         Binary, Deref, Display, Drop, IntoIterator, LowerExp, LowerHex,
         Octal, Pointer, Read, UpperExp, UpperHex, fmt::Write, io::Write
     );
+    assert_impl_all!(CacheInfo:
+        Copy, Debug, Hash, Sized, Sync, Unpin, UnwindSafe
+    );
+    assert_not_impl_any!(CacheInfo:
+        Binary, Default, Deref, Display, Drop, IntoIterator, LowerExp, LowerHex,
+        Octal, PartialOrd, Pointer, Read, UpperExp, UpperHex, fmt::Write,
+        io::Write
+    );
     assert_impl_all!(CacheAttributes:
         Copy, Debug, Hash, Sized, Sync, Unpin, UnwindSafe
     );
@@ -262,6 +317,32 @@ pub(super) mod tests {
         io::Write
     );
 
+    /// Check that cache info is consistent with cache attributes
+    #[test]
+    fn cache_info() {
+        for obj in crate::topology::Topology::test_objects() {
+            let Some(info) = obj.cache_info() else {
+                assert!(!obj.object_type().is_cpu_cache());
+                #[cfg(feature = "hwloc-2_1_0")]
+                assert!(obj.memory_cache_attributes().is_none());
+                continue;
+            };
+            let attr = match obj.attributes() {
+                Some(ObjectAttributes::Cache(attr)) => Some(attr),
+                _ => None,
+            };
+            #[cfg(feature = "hwloc-2_1_0")]
+            let attr = attr.or_else(|| obj.memory_cache_attributes());
+            let attr = attr.unwrap();
+            assert_eq!(info.size, attr.size());
+            assert_eq!(info.depth, attr.depth());
+            assert_eq!(info.line_size, attr.line_size());
+            assert_eq!(info.associativity, attr.associativity());
+            assert_eq!(info.cache_type, attr.cache_type());
+            assert_eq!(info.inclusive, obj.standard_info().inclusive());
+        }
+    }
+
     /// Pick a random CPU cache type
     fn cpu_cache_type() -> impl Strategy<Value = ObjectType> {
         let cache_types = enum_iterator::all::<ObjectType>()
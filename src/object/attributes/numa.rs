@@ -49,6 +49,12 @@ impl<'object> NUMANodeAttributes<'object> {
     }
 
     /// Memory page types, sorted by increasing page size
+    ///
+    /// The first entry normally describes the normal memory pages of the
+    /// operating system, and subsequent entries describe huge pages (e.g.
+    /// 2 MiB and 1 GiB pages on `x86_64`). This lets hugepage-aware allocators
+    /// find out how much memory of each page size is available on this NUMA
+    /// node, see [`MemoryPageType::total_size()`].
     #[doc(alias = "hwloc_numanode_attr_s::page_types")]
     #[doc(alias = "hwloc_obj_attr_u::hwloc_numanode_attr_s::page_types")]
     pub fn page_types(&self) -> &'object [MemoryPageType] {
@@ -144,6 +150,14 @@ impl MemoryPageType {
     pub fn count(&self) -> u64 {
         self.0.count
     }
+
+    /// Total amount of memory in pages of this size, in bytes
+    ///
+    /// This is [`size()`](Self::size()) times [`count()`](Self::count()),
+    /// saturating to `u64::MAX` in the unlikely event of overflow.
+    pub fn total_size(&self) -> u64 {
+        self.size().get().saturating_mul(self.count())
+    }
 }
 //
 #[cfg(any(test, feature = "proptest"))]
@@ -295,6 +309,10 @@ pub(super) mod tests {
                 prop_assert!(page_size > prev_page_size);
             }
             prev_page_size = Some(page_size);
+            prop_assert_eq!(
+                page_type.total_size(),
+                page_size.get().saturating_mul(page_type.count())
+            );
 
             prop_assert_eq!(
                 format!("{page_type:?}"),
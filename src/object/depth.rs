@@ -24,9 +24,9 @@
 
 // Main docs: https://hwloc.readthedocs.io/en/v2.9/group__hwlocality__levels.html
 
-use crate::ffi::int::PositiveInt;
 #[cfg(doc)]
 use crate::object::{types::ObjectType, TopologyObject};
+use crate::{ffi::int::PositiveInt, object::types::ParseTypeError};
 #[cfg(feature = "hwloc-2_1_0")]
 use hwlocality_sys::HWLOC_TYPE_DEPTH_MEMCACHE;
 use hwlocality_sys::{
@@ -270,6 +270,18 @@ pub enum TypeToDepthError {
     Unexpected(c_int),
 }
 
+/// Error from an hwloc query looking for the depth of an object type string
+#[derive(Clone, Debug, Eq, Error, Hash, PartialEq)]
+pub enum TypeStringToDepthError {
+    /// The input string is not a valid hwloc object type string
+    #[error(transparent)]
+    Parse(#[from] ParseTypeError),
+
+    /// The input object type could not be translated into a single depth
+    #[error(transparent)]
+    Depth(#[from] TypeToDepthError),
+}
+
 #[cfg(test)]
 mod tests {
     use crate::tests::assert_panics;
@@ -307,6 +319,15 @@ mod tests {
         Binary, Default, Deref, Drop, IntoIterator, LowerExp, LowerHex, Octal,
         PartialOrd, Pointer, Read, UpperExp, UpperHex, fmt::Write, io::Write
     );
+    assert_impl_all!(TypeStringToDepthError:
+        Clone, Error, From<ParseTypeError>, From<TypeToDepthError>, Hash,
+        Sized, Sync, Unpin, UnwindSafe
+    );
+    assert_not_impl_any!(TypeStringToDepthError:
+        Binary, Copy, Default, Deref, Drop, IntoIterator, LowerExp, LowerHex,
+        Octal, PartialOrd, Pointer, Read, UpperExp, UpperHex, fmt::Write,
+        io::Write
+    );
 
     #[test]
     fn special_values() {
@@ -19,7 +19,7 @@ use crate::errors::FlagsError;
 #[cfg(any(doc, feature = "hwloc-2_3_0"))]
 use crate::object::depth::NormalDepth;
 #[cfg(feature = "hwloc-2_3_0")]
-use crate::topology::editor::TopologyEditor;
+use crate::topology::editor::{TopologyChanges, TopologyEditor};
 use crate::{
     errors::{self, ForeignObjectError, RawHwlocError},
     ffi::{self, int, transparent::TransparentNewtype},
@@ -32,7 +32,7 @@ use crate::{
     ffi::string::LibcString,
 };
 use bitflags::bitflags;
-#[cfg(feature = "hwloc-2_5_0")]
+#[cfg(feature = "hwloc-2_1_0")]
 use enum_iterator::Sequence;
 #[cfg(feature = "hwloc-2_1_0")]
 use hwlocality_sys::HWLOC_DISTANCES_KIND_HETEROGENEOUS_TYPES;
@@ -93,6 +93,43 @@ impl Topology {
         }
     }
 
+    /// Latencies between NUMA nodes, as an owned matrix
+    ///
+    /// This is a convenience shortcut for the common case where you are only
+    /// interested in the relative latencies of NUMA nodes, as typically
+    /// provided by the ACPI SLIT table on x86 systems. It looks up a latency
+    /// matrix between NUMA nodes using [`distances_with_type()`], preferring
+    /// OS-provided matrices over user-provided ones, and turns it into a
+    /// [`DistanceMatrix`] that is indexed by NUMA node logical index.
+    ///
+    /// Returns `None` if no such matrix is available, which is typically the
+    /// case on machines with a single NUMA node.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use hwlocality::Topology;
+    /// # let topology = Topology::test_instance();
+    /// if let Some(matrix) = topology.numa_distance_matrix()? {
+    ///     for node in matrix.nodes() {
+    ///         println!(
+    ///             "Nodes by increasing latency from node #{node}: {:?}",
+    ///             matrix.nearest_nodes(node).unwrap()
+    ///         );
+    ///     }
+    /// }
+    /// # Ok::<(), eyre::Report>(())
+    /// ```
+    ///
+    /// [`distances_with_type()`]: Topology::distances_with_type()
+    #[allow(clippy::missing_errors_doc)]
+    pub fn numa_distance_matrix(&self) -> Result<Option<DistanceMatrix>, RawHwlocError> {
+        let mut matrices =
+            self.distances_with_type(Some(DistancesKind::MEANS_LATENCY), ObjectType::NUMANode)?;
+        matrices.sort_by_key(|distances| !distances.kind().contains(DistancesKind::FROM_OS));
+        Ok(matrices.first().map(DistanceMatrix::new))
+    }
+
     /// Retrieve distance matrices for objects at a specific depth in the
     /// topology (if any)
     ///
@@ -222,6 +259,28 @@ impl Topology {
         }
     }
 
+    /// Retrieve a distance matrix with a well-known name
+    ///
+    /// This is a typed alternative to [`Topology::distances_with_name()`] for
+    /// the distances matrices that hwloc's own discovery backends create. An
+    /// empty list is returned if the relevant backend is not available or did
+    /// not find the associated hardware.
+    #[allow(clippy::missing_errors_doc)]
+    #[cfg(feature = "hwloc-2_1_0")]
+    #[doc(alias = "hwloc_distances_get_by_name")]
+    pub fn distances_with_known_name(
+        &self,
+        name: KnownDistancesName,
+    ) -> Result<Vec<Distances<'_>>, RawHwlocError> {
+        self.distances_with_name(name.as_str())
+            .map_err(|e| match e {
+                HybridError::Hwloc(e) => e,
+                HybridError::Rust(NulError) => {
+                    unreachable!("well-known distances names don't contain NUL")
+                }
+            })
+    }
+
     /// Call one of the `hwloc_distances_get(_by)?` APIs
     ///
     /// # Safety
@@ -360,6 +419,12 @@ impl TopologyEditor<'_> {
     /// object 0 to object 1, then object 0 to object 2, ... all the way to
     /// object N, and then from object 1 to object 0, and so on.
     ///
+    /// This is typically used to record bandwidth or latency measurements,
+    /// e.g. from a micro-benchmark, inside of the topology. Distances added
+    /// this way can later be retrieved using [`Topology::distances()`], and
+    /// they are saved along with the rest of the topology when it is exported
+    /// to XML.
+    ///
     /// # Errors
     ///
     /// - [`InconsistentData`] if the number of distances returned by the
@@ -380,6 +445,7 @@ impl TopologyEditor<'_> {
     /// [`ForeignEndpoint`]: AddDistancesError::ForeignEndpoint
     /// [`HETEROGENEOUS_TYPES`]: DistancesKind::HETEROGENEOUS_TYPES
     /// [`NameContainsNul`]: AddDistancesError::NameContainsNul
+    #[doc(alias = "hwloc_distances_add")]
     #[doc(alias = "hwloc_distances_add_create")]
     #[doc(alias = "hwloc_distances_add_values")]
     #[doc(alias = "hwloc_distances_add_commit")]
@@ -434,6 +500,7 @@ impl TopologyEditor<'_> {
             let values = distances.as_ptr();
 
             // Create new empty distances structure
+            self_.record_changes(TopologyChanges::DISTANCES);
             // SAFETY: - Topology is trusted to contain a valid ptr (type invariant)
             //         - name is trusted to be a valid C string (type invariant)
             //         - hwloc ops are trusted not to modify *const parameters
@@ -627,6 +694,7 @@ impl TopologyEditor<'_> {
             self_: &mut TopologyEditor<'_>,
             distances: NonNull<hwloc_distances_s>,
         ) -> Result<(), RawHwlocError> {
+            self_.record_changes(TopologyChanges::DISTANCES);
             // SAFETY: - Topology is trusted to contain a valid ptr (type invariant)
             //         - hwloc ops are trusted to keep *mut parameters in a valid
             //           state unless stated otherwise
@@ -656,6 +724,7 @@ impl TopologyEditor<'_> {
     #[allow(clippy::missing_errors_doc)]
     #[doc(alias = "hwloc_distances_remove")]
     pub fn remove_all_distances(&mut self) -> Result<(), RawHwlocError> {
+        self.record_changes(TopologyChanges::DISTANCES);
         // SAFETY: - Topology is trusted to contain a valid ptr (type invariant)
         //         - Distances lifetime is bound by the host topology, so this
         //           will invalidate all Distances struct in existence and thus
@@ -690,6 +759,7 @@ impl TopologyEditor<'_> {
             self_: &mut TopologyEditor<'_>,
             depth: Depth,
         ) -> Result<(), RawHwlocError> {
+            self_.record_changes(TopologyChanges::DISTANCES);
             // SAFETY: - Topology is trusted to contain a valid ptr (type invariant)
             //         - Distances lifetime is bound by the host topology, so this
             //           will invalidate all Distances struct in existence and thus
@@ -1404,6 +1474,129 @@ unsafe impl Send for Distances<'_> {}
 // SAFETY: No internal mutability
 unsafe impl Sync for Distances<'_> {}
 
+/// Owned latency matrix between NUMA nodes
+///
+/// Obtained via [`Topology::numa_distance_matrix()`].
+///
+/// Unlike [`Distances`], this type does not borrow from the [`Topology`] and
+/// identifies NUMA nodes by their logical index, which makes it convenient to
+/// store alongside other per-node data. Latency values have no particular
+/// unit, they are only meaningful relative to each other.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct DistanceMatrix {
+    /// Logical indices of the NUMA nodes covered by the matrix, in increasing
+    /// order
+    nodes: Vec<usize>,
+
+    /// Latencies between these nodes, in sender-major order
+    latencies: Vec<u64>,
+}
+//
+impl DistanceMatrix {
+    /// Extract a latency matrix from hwloc distances between NUMA nodes
+    fn new(distances: &Distances<'_>) -> Self {
+        let mut nodes = distances
+            .objects()
+            .enumerate()
+            .filter_map(|(idx, node)| Some((node?.logical_index(), idx)))
+            .collect::<Vec<_>>();
+        nodes.sort_unstable();
+        let latencies = nodes
+            .iter()
+            .flat_map(|&(_, sender)| {
+                nodes
+                    .iter()
+                    .map(move |&(_, receiver)| distances[(sender, receiver)])
+            })
+            .collect();
+        Self {
+            nodes: nodes.into_iter().map(|(node, _)| node).collect(),
+            latencies,
+        }
+    }
+
+    /// Logical indices of the NUMA nodes covered by this matrix, in
+    /// increasing order
+    pub fn nodes(
+        &self,
+    ) -> impl DoubleEndedIterator<Item = usize> + Clone + ExactSizeIterator + FusedIterator + '_
+    {
+        self.nodes.iter().copied()
+    }
+
+    /// Number of NUMA nodes covered by this matrix
+    pub fn num_nodes(&self) -> usize {
+        self.nodes.len()
+    }
+
+    /// Latency from the NUMA node with logical index `from` to the NUMA node
+    /// with logical index `to`
+    ///
+    /// Returns `None` if one of these nodes is not covered by this matrix.
+    pub fn latency(&self, from: usize, to: usize) -> Option<u64> {
+        let from = self.position(from)?;
+        let to = self.position(to)?;
+        Some(self.latencies[from * self.num_nodes() + to])
+    }
+
+    /// Latency from `from` to `to`, relative to the latency from `from` to
+    /// itself
+    ///
+    /// This normalizes latencies so that local accesses have a relative
+    /// latency of 1.0, which makes it easy to tell e.g. that remote accesses
+    /// are twice as slow as local ones, whatever the unit of the raw latency
+    /// values.
+    ///
+    /// Returns `None` if one of these nodes is not covered by this matrix, or
+    /// if the local latency of `from` is zero.
+    #[allow(clippy::cast_precision_loss)]
+    pub fn relative_latency(&self, from: usize, to: usize) -> Option<f64> {
+        let local = self.latency(from, from)?;
+        let remote = self.latency(from, to)?;
+        (local != 0).then(|| remote as f64 / local as f64)
+    }
+
+    /// Latencies divided by the largest latency in the matrix
+    ///
+    /// The result is in sender-major order, following [`nodes()`], and all
+    /// values lie between 0.0 and 1.0. If all latencies are zero, they are
+    /// left as zero.
+    ///
+    /// [`nodes()`]: Self::nodes()
+    #[allow(clippy::cast_precision_loss)]
+    pub fn normalized(&self) -> Vec<f64> {
+        let max = self.latencies.iter().copied().max().unwrap_or(0).max(1) as f64;
+        self.latencies
+            .iter()
+            .map(|&latency| latency as f64 / max)
+            .collect()
+    }
+
+    /// Other NUMA nodes covered by this matrix, ordered by increasing latency
+    /// from the NUMA node with logical index `node`
+    ///
+    /// Nodes with equal latency are ordered by logical index. Returns `None`
+    /// if `node` is not covered by this matrix.
+    pub fn nearest_nodes(&self, node: usize) -> Option<Vec<usize>> {
+        let sender = self.position(node)?;
+        let row = &self.latencies[sender * self.num_nodes()..][..self.num_nodes()];
+        let mut others = self
+            .nodes
+            .iter()
+            .zip(row)
+            .filter(|&(&other, _)| other != node)
+            .map(|(&other, &latency)| (latency, other))
+            .collect::<Vec<_>>();
+        others.sort_unstable();
+        Some(others.into_iter().map(|(_, other)| other).collect())
+    }
+
+    /// Position of a NUMA node in the matrix
+    fn position(&self, node: usize) -> Option<usize> {
+        self.nodes.binary_search(&node).ok()
+    }
+}
+
 bitflags! {
     /// Kinds of distance matrices
     ///
@@ -1472,6 +1665,51 @@ impl DistancesKind {
 #[cfg(any(test, feature = "proptest"))]
 crate::impl_arbitrary_for_bitflags!(DistancesKind, hwloc_distances_kind_e);
 
+/// Names of the distances matrices that hwloc discovery backends may create
+///
+/// See [the hwloc documentation](https://hwloc.readthedocs.io/en/v2.9/topoattrs.html#topoattrs_distances)
+/// for more information about these matrices. Some of them are only created
+/// by recent hwloc releases.
+#[cfg(feature = "hwloc-2_1_0")]
+#[derive(Copy, Clone, Debug, derive_more::Display, Eq, Hash, PartialEq, Sequence)]
+pub enum KnownDistancesName {
+    /// Latencies between NUMA nodes, as reported by the operating system
+    ///
+    /// On x86 systems, this usually comes from the ACPI SLIT table.
+    NUMALatency,
+
+    /// Bandwidth of the xGMI links between AMD GPUs, from the RSMI backend
+    XGMIBandwidth,
+
+    /// Number of xGMI hops between AMD GPUs, from the RSMI backend
+    XGMIHops,
+
+    /// Bandwidth of the Xe Link connections between Intel GPUs, from the
+    /// oneAPI Level Zero backend
+    XeLinkBandwidth,
+
+    /// Bandwidth of the NVLink links between NVIDIA GPUs, NVSwitches and
+    /// CPUs, from the NVML backend
+    NVLinkBandwidth,
+}
+//
+#[cfg(feature = "hwloc-2_1_0")]
+impl KnownDistancesName {
+    /// Name of the matrix, as used by hwloc
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::NUMALatency => "NUMALatency",
+            Self::XGMIBandwidth => "XGMIBandwidth",
+            Self::XGMIHops => "XGMIHops",
+            Self::XeLinkBandwidth => "XeLinkBandwidth",
+            Self::NVLinkBandwidth => "NVLinkBandwidth",
+        }
+    }
+}
+//
+#[cfg(feature = "hwloc-2_1_0")]
+crate::impl_arbitrary_for_sequence!(KnownDistancesName);
+
 /// Transformations of distances structures
 #[cfg(feature = "hwloc-2_5_0")]
 #[derive(
@@ -1549,3 +1787,168 @@ crate::impl_arbitrary_for_sequence!(DistancesTransform);
 #[derive(Copy, Clone, Debug, Default, Eq, Error, Hash, PartialEq)]
 #[error("can't empty a distance matrix using DistancesTransform::RemoveNone")]
 pub struct TransformError;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[allow(unused)]
+    use similar_asserts::assert_eq;
+
+    /// Check that two floating-point numbers are close enough
+    fn assert_close(actual: f64, expected: f64) {
+        assert!(
+            (actual - expected).abs() < 1e-9,
+            "{actual} is not close to {expected}"
+        );
+    }
+
+    /// Check the accessors of a handmade distance matrix
+    #[test]
+    fn distance_matrix() {
+        let matrix = DistanceMatrix {
+            nodes: vec![0, 1, 3],
+            latencies: vec![10, 20, 30, 20, 10, 15, 30, 15, 10],
+        };
+        assert_eq!(matrix.nodes().collect::<Vec<_>>(), [0, 1, 3]);
+        assert_eq!(matrix.num_nodes(), 3);
+        assert_eq!(matrix.latency(1, 3), Some(15));
+        assert_eq!(matrix.latency(3, 0), Some(30));
+        assert_eq!(matrix.latency(2, 3), None);
+        assert_close(matrix.relative_latency(0, 3).unwrap(), 3.0);
+        assert_close(matrix.relative_latency(3, 3).unwrap(), 1.0);
+        assert_eq!(matrix.relative_latency(0, 2), None);
+        assert_eq!(matrix.nearest_nodes(0), Some(vec![1, 3]));
+        assert_eq!(matrix.nearest_nodes(3), Some(vec![1, 0]));
+        assert_eq!(matrix.nearest_nodes(2), None);
+        let normalized = matrix.normalized();
+        assert_eq!(normalized.len(), 9);
+        assert_close(normalized[2], 1.0);
+        assert_close(normalized[4], 1.0 / 3.0);
+    }
+
+    /// Check that user-provided NUMA latencies can be retrieved as a matrix
+    #[cfg(feature = "hwloc-2_5_0")]
+    #[test]
+    fn numa_distance_matrix() {
+        let mut topology = Topology::test_dual_socket_numa().clone();
+        assert_eq!(topology.numa_distance_matrix().unwrap(), None);
+        topology.edit(|editor| {
+            editor
+                .add_distances(
+                    Some("NUMALatency"),
+                    DistancesKind::FROM_USER | DistancesKind::MEANS_LATENCY,
+                    AddDistancesFlags::empty(),
+                    |topology| {
+                        // List nodes in reverse order to check reordering
+                        let nodes = topology
+                            .objects_with_type(ObjectType::NUMANode)
+                            .rev()
+                            .map(Some)
+                            .collect();
+                        (nodes, vec![10, 30, 20, 10])
+                    },
+                )
+                .unwrap();
+        });
+        let matrix = topology.numa_distance_matrix().unwrap().unwrap();
+        assert_eq!(matrix.nodes().collect::<Vec<_>>(), [0, 1]);
+        assert_eq!(matrix.latency(0, 0), Some(10));
+        assert_eq!(matrix.latency(0, 1), Some(20));
+        assert_eq!(matrix.latency(1, 0), Some(30));
+        assert_eq!(matrix.nearest_nodes(1), Some(vec![0]));
+    }
+
+    /// Check that inconsistent distance matrices are rejected
+    #[cfg(feature = "hwloc-2_5_0")]
+    #[test]
+    fn add_distances_errors() {
+        let mut topology = Topology::test_dual_socket_numa().clone();
+        topology.edit(|editor| {
+            let mut add = |num_objects: usize, num_distances: usize| {
+                editor.add_distances(
+                    None,
+                    DistancesKind::FROM_USER | DistancesKind::MEANS_LATENCY,
+                    AddDistancesFlags::empty(),
+                    |topology| {
+                        let objects = topology
+                            .objects_with_type(ObjectType::NUMANode)
+                            .cycle()
+                            .take(num_objects)
+                            .map(Some)
+                            .collect();
+                        (objects, vec![10; num_distances])
+                    },
+                )
+            };
+            assert!(matches!(
+                add(2, 3),
+                Err(HybridError::Rust(AddDistancesError::InconsistentData))
+            ));
+            assert!(matches!(
+                add(1, 1),
+                Err(HybridError::Rust(AddDistancesError::BadObjectsCount(1)))
+            ));
+        });
+        assert!(topology.distances(None).unwrap().is_empty());
+    }
+
+    /// Check that distance matrices can be transformed
+    #[cfg(feature = "hwloc-2_5_0")]
+    #[test]
+    fn transform() {
+        let mut topology = Topology::test_dual_socket_numa().clone();
+        topology.edit(|editor| {
+            editor
+                .add_distances(
+                    Some("NUMABandwidth"),
+                    DistancesKind::FROM_USER | DistancesKind::MEANS_BANDWIDTH,
+                    AddDistancesFlags::empty(),
+                    |topology| {
+                        let nodes = topology
+                            .objects_with_type(ObjectType::NUMANode)
+                            .map(Some)
+                            .collect();
+                        (nodes, vec![100, 50, 50, 100])
+                    },
+                )
+                .unwrap();
+        });
+        let nodes = topology
+            .objects_with_type(ObjectType::NUMANode)
+            .collect::<Vec<_>>();
+        let mut matrices = topology
+            .distances(Some(DistancesKind::MEANS_BANDWIDTH))
+            .unwrap();
+        assert_eq!(matrices.len(), 1);
+        let mut distances = matrices.pop().unwrap();
+
+        distances
+            .transform(DistancesTransform::BandwidthToLinkCount)
+            .unwrap();
+        assert_eq!(distances.distances(), [0, 1, 1, 0]);
+
+        distances
+            .replace_objects(|idx, _| (idx != 0).then(|| nodes[idx]))
+            .unwrap();
+        assert!(matches!(
+            distances.transform(DistancesTransform::RemoveNone),
+            Err(HybridError::Rust(TransformError))
+        ));
+    }
+
+    /// Check that well-known distances names match their hwloc spelling
+    #[cfg(feature = "hwloc-2_1_0")]
+    #[test]
+    fn known_distances_name() {
+        for name in enum_iterator::all::<KnownDistancesName>() {
+            assert_eq!(name.to_string(), name.as_str());
+            let by_name = Topology::test_instance()
+                .distances_with_name(name.as_str())
+                .unwrap();
+            let by_known_name = Topology::test_instance()
+                .distances_with_known_name(name)
+                .unwrap();
+            assert_eq!(by_name.len(), by_known_name.len());
+        }
+    }
+}
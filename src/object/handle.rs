@@ -0,0 +1,316 @@
+//! Stable object handles
+//!
+//! Borrowing a [`TopologyObject`] from a [`Topology`] prevents any modification
+//! of that topology for as long as the object is in use, and even if you work
+//! around this with pointers, topology edits may invalidate objects. The
+//! [`ObjectHandle`] type defined in this module provides a way to keep track
+//! of an object across topology edits, and to later get back to the object if
+//! it still exists.
+//!
+//! When topology provenance checks are not needed, the lighter [`ObjectId`]
+//! identifier can be used instead, and resolved in constant time using
+//! [`Topology::object_by_id()`].
+
+use super::{types::ObjectType, TopologyObject, TopologyObjectID};
+use crate::topology::Topology;
+#[allow(unused)]
+#[cfg(test)]
+use similar_asserts::assert_eq;
+use thiserror::Error;
+
+/// Stable handle to a [`TopologyObject`]
+///
+/// Unlike `&TopologyObject`, this handle does not borrow from the [`Topology`]
+/// that the object belongs to, and it remains valid across topology edits. It
+/// can thus be stored in your own data structures, then later [resolved] back
+/// into a `&TopologyObject` if the object still exists.
+///
+/// Handles are based on [global persistent
+/// indices](TopologyObject::global_persistent_index()), which are unique and
+/// stable within a given topology. To prevent handles from being accidentally
+/// resolved into unrelated objects, they also record which topology the object
+/// originates from, and can only be resolved in that topology. Note that
+/// clones of a topology are considered to be distinct topologies.
+///
+/// [resolved]: ObjectHandle::resolve()
+//
+// --- Implementation details ---
+//
+// Topologies are identified by a process-wide unique identifier, which is
+// allocated when a `Topology` is created or cloned and never reused, so that
+// handles from a topology that has been dropped cannot be resolved in a new
+// topology that happens to be allocated at the same address.
+#[derive(Copy, Clone, Debug, Eq, Hash, PartialEq)]
+pub struct ObjectHandle {
+    /// Global persistent index of the target object
+    id: TopologyObjectID,
+
+    /// Type of the target object, used to speed up lookup
+    object_type: ObjectType,
+
+    /// Identifier of the topology that the target object belongs to
+    topology: u64,
+}
+//
+impl ObjectHandle {
+    /// Global persistent index of the target object
+    pub fn global_persistent_index(&self) -> TopologyObjectID {
+        self.id
+    }
+
+    /// Type of the target object
+    pub fn object_type(&self) -> ObjectType {
+        self.object_type
+    }
+
+    /// Get back to the target object, if it still exists in `topology`
+    ///
+    /// # Errors
+    ///
+    /// - [`ForeignTopology`] if this handle does not originate from `topology`.
+    /// - [`Removed`] if the target object was removed from `topology` by a
+    ///   topology edit, e.g. a restriction to a smaller set of PUs.
+    ///
+    /// [`ForeignTopology`]: ResolveHandleError::ForeignTopology
+    /// [`Removed`]: ResolveHandleError::Removed
+    pub fn resolve(self, topology: &Topology) -> Result<&TopologyObject, ResolveHandleError> {
+        if self.topology != topology.id() {
+            return Err(ResolveHandleError::ForeignTopology);
+        }
+        topology
+            .indexed_object(self.id)
+            .filter(|obj| obj.object_type() == self.object_type)
+            .ok_or(ResolveHandleError::Removed(self.id))
+    }
+}
+
+/// Lightweight identifier of a [`TopologyObject`]
+///
+/// This is a thin wrapper around an object's [global persistent
+/// index](TopologyObject::global_persistent_index()), which can be resolved
+/// back into a `&TopologyObject` in constant time using
+/// [`Topology::object_by_id()`].
+///
+/// Unlike [`ObjectHandle`], an `ObjectId` does not record which topology the
+/// object originates from, so resolving it in a different topology (including
+/// a clone of the original topology) may silently yield an unrelated object.
+#[derive(Copy, Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct ObjectId(TopologyObjectID);
+//
+impl From<TopologyObjectID> for ObjectId {
+    fn from(value: TopologyObjectID) -> Self {
+        Self(value)
+    }
+}
+//
+impl From<ObjectId> for TopologyObjectID {
+    fn from(value: ObjectId) -> Self {
+        value.0
+    }
+}
+
+impl Topology {
+    /// Look up an object by [`ObjectId`]
+    ///
+    /// Returns `None` if there is no object with this identifier in the
+    /// topology, e.g. because it has been removed by a topology edit.
+    ///
+    /// This lookup is performed in constant time using an index that is built
+    /// when the topology is loaded and updated after each edit. Note that the
+    /// identifier is not checked to originate from this topology, see
+    /// [`ObjectHandle`] if you need such a check.
+    pub fn object_by_id(&self, id: ObjectId) -> Option<&TopologyObject> {
+        self.indexed_object(id.0)
+    }
+}
+
+/// Error returned by [`ObjectHandle::resolve()`]
+#[derive(Copy, Clone, Debug, Eq, Error, Hash, PartialEq)]
+pub enum ResolveHandleError {
+    /// The handle originates from another topology
+    #[error("object handle doesn't belong to this topology")]
+    ForeignTopology,
+
+    /// The target object does not exist in the topology anymore
+    #[error("object #{0} has been removed from the topology")]
+    Removed(TopologyObjectID),
+}
+
+impl TopologyObject {
+    /// Lightweight identifier of this object
+    ///
+    /// See [`ObjectId`] for more information.
+    pub fn id(&self) -> ObjectId {
+        ObjectId(self.global_persistent_index())
+    }
+
+    /// Stable handle to this object
+    ///
+    /// See [`ObjectHandle`] for more information.
+    pub fn handle(&self) -> ObjectHandle {
+        let root = std::iter::once(self)
+            .chain(self.ancestors())
+            .last()
+            .expect("By definition, this iterator always has >= 1 element");
+        ObjectHandle {
+            id: self.global_persistent_index(),
+            object_type: self.object_type(),
+            topology: Topology::id_from_root(root),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::strategies::any_object;
+    use proptest::prelude::*;
+    #[allow(unused)]
+    use similar_asserts::assert_eq;
+    use static_assertions::{assert_impl_all, assert_not_impl_any};
+    use std::{
+        error::Error,
+        fmt::{
+            self, Binary, Debug, Display, LowerExp, LowerHex, Octal, Pointer, UpperExp, UpperHex,
+        },
+        hash::Hash,
+        io::{self, Read},
+        ops::Deref,
+        panic::UnwindSafe,
+        ptr,
+    };
+
+    // Check that public types in this module keep implementing all expected
+    // traits, in the interest of detecting future semver-breaking changes
+    assert_impl_all!(ObjectHandle:
+        Copy, Debug, Hash, Sized, Sync, Unpin, UnwindSafe
+    );
+    assert_not_impl_any!(ObjectHandle:
+        Binary, Default, Deref, Display, Drop, IntoIterator, LowerExp, LowerHex,
+        Octal, PartialOrd, Pointer, Read, UpperExp, UpperHex, fmt::Write,
+        io::Write
+    );
+    assert_impl_all!(ObjectId:
+        Copy, Debug, From<TopologyObjectID>, Hash, Into<TopologyObjectID>, Ord,
+        Sized, Sync, Unpin, UnwindSafe
+    );
+    assert_not_impl_any!(ObjectId:
+        Binary, Default, Deref, Display, Drop, IntoIterator, LowerExp, LowerHex,
+        Octal, Pointer, Read, UpperExp, UpperHex, fmt::Write, io::Write
+    );
+    assert_impl_all!(ResolveHandleError:
+        Copy, Error, Hash, Sized, Sync, Unpin, UnwindSafe
+    );
+    assert_not_impl_any!(ResolveHandleError:
+        Binary, Default, Deref, Drop, IntoIterator, LowerExp, LowerHex, Octal,
+        PartialOrd, Pointer, Read, UpperExp, UpperHex, fmt::Write, io::Write
+    );
+
+    proptest! {
+        /// Handles should only resolve in the topology they come from
+        #[test]
+        fn resolve(obj in any_object()) {
+            let handle = obj.handle();
+            prop_assert_eq!(handle.global_persistent_index(), obj.global_persistent_index());
+            prop_assert_eq!(handle.object_type(), obj.object_type());
+            for topology in [Topology::test_instance(), Topology::foreign_instance()] {
+                let result = handle.resolve(topology);
+                if topology.objects().any(|other| ptr::eq(obj, other)) {
+                    prop_assert!(ptr::eq(result.unwrap(), obj));
+                } else {
+                    prop_assert_eq!(result.unwrap_err(), ResolveHandleError::ForeignTopology);
+                }
+            }
+        }
+
+        /// Object identifiers should resolve to the object they come from
+        #[test]
+        fn object_by_id(obj in any_object()) {
+            let id = obj.id();
+            prop_assert_eq!(TopologyObjectID::from(id), obj.global_persistent_index());
+            prop_assert_eq!(ObjectId::from(obj.global_persistent_index()), id);
+            let topology = Topology::test_instance();
+            if topology.objects().any(|other| ptr::eq(obj, other)) {
+                prop_assert!(ptr::eq(topology.object_by_id(id).unwrap(), obj));
+            }
+        }
+
+        /// Unknown object identifiers should not resolve
+        #[test]
+        fn unknown_id(id: TopologyObjectID) {
+            let topology = Topology::test_instance();
+            let expected = topology
+                .objects()
+                .find(|obj| obj.global_persistent_index() == id);
+            let actual = topology.object_by_id(ObjectId::from(id));
+            prop_assert_eq!(actual.is_some(), expected.is_some());
+            if let (Some(actual), Some(expected)) = (actual, expected) {
+                prop_assert!(ptr::eq(actual, expected));
+            }
+        }
+    }
+
+    /// Handles from a dropped topology should not resolve in other topologies
+    #[test]
+    fn resolve_after_drop() {
+        let topology = Topology::test_instance().clone();
+        let handle = topology.root_object().handle();
+        assert!(ptr::eq(
+            handle.resolve(&topology).unwrap(),
+            topology.root_object()
+        ));
+        drop(topology);
+        for _ in 0..4 {
+            let topology = Topology::test_instance().clone();
+            assert_eq!(
+                handle.resolve(&topology).unwrap_err(),
+                ResolveHandleError::ForeignTopology
+            );
+        }
+    }
+
+    /// Handles should survive topology edits
+    #[cfg(feature = "hwloc-2_3_0")]
+    #[test]
+    fn resolve_after_edit() {
+        use crate::topology::editor::RestrictFlags;
+
+        // Record handles to all objects, then restrict the topology to one PU
+        let mut topology = Topology::test_instance().clone();
+        let handles = topology
+            .objects()
+            .map(TopologyObject::handle)
+            .collect::<Vec<_>>();
+        let first_pu_set = topology
+            .objects_with_type(ObjectType::PU)
+            .next()
+            .unwrap()
+            .cpuset()
+            .unwrap()
+            .clone_target();
+        topology.edit(|editor| {
+            editor
+                .restrict(&first_pu_set, RestrictFlags::empty())
+                .unwrap();
+        });
+
+        // Objects that are still there should resolve, others should be
+        // reported as removed
+        for handle in handles {
+            let id = handle.global_persistent_index();
+            let remaining = topology
+                .objects()
+                .find(|obj| obj.global_persistent_index() == id);
+            if let Some(obj) = remaining {
+                assert!(ptr::eq(handle.resolve(&topology).unwrap(), obj));
+                assert!(ptr::eq(topology.object_by_id(obj.id()).unwrap(), obj));
+            } else {
+                assert_eq!(
+                    handle.resolve(&topology).unwrap_err(),
+                    ResolveHandleError::Removed(id)
+                );
+                assert!(topology.object_by_id(ObjectId::from(id)).is_none());
+            }
+        }
+    }
+}
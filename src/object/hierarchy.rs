@@ -2,21 +2,23 @@
 
 use super::{
     attributes::ObjectAttributes,
-    depth::{Depth, NormalDepth, TypeToDepthError},
-    types::{CacheType, ObjectType},
+    depth::{Depth, NormalDepth, TypeStringToDepthError, TypeToDepthError},
+    types::{CacheType, ObjectType, ParseTypeError},
     TopologyObject,
 };
 use crate::{
-    ffi::{int, transparent::AsNewtype},
+    errors::IndexOutOfBounds,
+    ffi::{int, string::LibcString, transparent::AsNewtype},
     object::TopologyObjectID,
     topology::Topology,
 };
-use hwlocality_sys::hwloc_obj_type_t;
+use hwlocality_sys::{hwloc_get_type_depth_e, hwloc_obj_type_t};
 use num_enum::TryFromPrimitiveError;
 #[allow(unused)]
 #[cfg(test)]
 use similar_asserts::assert_eq;
 use std::{collections::HashMap, ffi::c_uint, fmt::Debug, iter::FusedIterator};
+use thiserror::Error;
 
 /// # Object levels, depths and types
 ///
@@ -359,6 +361,59 @@ impl Topology {
         result
     }
 
+    /// Depth of objects matching an hwloc object type string
+    ///
+    /// This accepts the same type strings as
+    /// [`ObjectType::parse_with_attributes()`], and is equivalent to calling
+    /// [`depth_for_type()`] on the parsed object type, except that group type
+    /// strings that specify a group depth like `"Group1"` are resolved to the
+    /// depth of the matching group level.
+    ///
+    /// # Errors
+    ///
+    /// - [`Parse`] if `s` is not a valid hwloc object type string
+    /// - [`Depth`] if the parsed object type is not present in the topology or
+    ///   exists at multiple depths, as in [`depth_for_type()`]
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use hwlocality::object::types::ObjectType;
+    /// # let topology = hwlocality::Topology::test_instance();
+    /// assert_eq!(
+    ///     topology.depth_for_type_string("PU")?,
+    ///     topology.depth_for_type(ObjectType::PU)?
+    /// );
+    /// # Ok::<(), eyre::Report>(())
+    /// ```
+    ///
+    /// [`depth_for_type()`]: Self::depth_for_type()
+    /// [`Depth`]: TypeStringToDepthError::Depth
+    /// [`Parse`]: TypeStringToDepthError::Parse
+    #[doc(alias = "hwloc_type_sscanf_as_depth")]
+    pub fn depth_for_type_string(&self, s: &str) -> Result<Depth, TypeStringToDepthError> {
+        let string = LibcString::new(s).map_err(|_| ParseTypeError(s.to_owned()))?;
+        let mut raw_type = hwloc_obj_type_t::MAX;
+        let mut raw_depth: hwloc_get_type_depth_e = 0;
+        // SAFETY: - Topology is trusted to contain a valid ptr (type invariant)
+        //         - hwloc_type_sscanf_as_depth does not modify the topology,
+        //           even though it takes a non-const topology pointer
+        //         - string is a valid C string (LibcString type invariant)
+        //         - raw_type and raw_depth are valid out-pointers
+        let result = unsafe {
+            hwlocality_sys::hwloc_type_sscanf_as_depth(
+                string.borrow(),
+                &mut raw_type,
+                self.as_ptr().cast_mut(),
+                &mut raw_depth,
+            )
+        };
+        if result < 0 {
+            return Err(ParseTypeError(s.to_owned()).into());
+        }
+        Ok(Depth::from_raw(raw_depth)?)
+    }
+
     /// Type of objects at the given `depth`, if any
     ///
     /// `depth` can be a [`Depth`], a [`NormalDepth`] or an [`usize`].
@@ -492,32 +547,7 @@ impl Topology {
         ) -> impl DoubleEndedIterator<Item = &TopologyObject> + Clone + ExactSizeIterator + FusedIterator
         {
             let size = self_.num_objects_at_depth(depth);
-            let depth = depth.to_raw();
-            (0..size).map(move |idx| {
-                let idx = c_uint::try_from(idx).expect("Can't happen, size comes from hwloc");
-                let ptr =
-                    // SAFETY: - Topology is trusted to contain a valid ptr
-                    //           (type invariant)
-                    //         - hwloc ops are trusted not to modify *const
-                    //           parameters
-                    //         - By construction, Depth only exposes values that
-                    //           map into hwloc_get_depth_type_e values
-                    //           understood by the configured version of hwloc,
-                    //           and build.rs checks that the active version of
-                    //           hwloc is not older than that, so into() may
-                    //           only generate valid hwloc_get_depth_type_e
-                    //           values for current hwloc
-                    //         - idx is in bounds by construction
-                    unsafe { hwlocality_sys::hwloc_get_obj_by_depth(self_.as_ptr(), depth, idx) };
-                assert!(
-                    !ptr.is_null(),
-                    "Got null pointer from hwloc_get_obj_by_depth"
-                );
-                // SAFETY: If hwloc_get_obj_by_depth returns a non-null pointer,
-                //         it's assumed to be successful and thus that the
-                //         output pointer and its target are valid
-                unsafe { (&*ptr).as_newtype() }
-            })
+            (0..size).map(move |idx| self_.object_at_unchecked(depth, idx))
         }
 
         // This little hack works because hwloc topologies never get anywhere
@@ -528,6 +558,189 @@ impl Topology {
         polymorphized(self, depth)
     }
 
+    /// [`TopologyObject`] at the given `depth` with the given logical index
+    ///
+    /// `depth` can be a [`Depth`], a [`NormalDepth`] or an [`usize`].
+    ///
+    /// This is a constant-time equivalent of
+    /// `topology.objects_at_depth(depth).nth(index)`.
+    ///
+    /// # Errors
+    ///
+    /// - [`IndexOutOfBounds`] if there are no more than `index` objects at
+    ///   this depth
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use hwlocality::object::depth::NormalDepth;
+    /// # let topology = hwlocality::Topology::test_instance();
+    /// let root = topology.object_at(NormalDepth::MIN, 0)?;
+    /// assert!(std::ptr::eq(root, topology.root_object()));
+    /// assert!(topology.object_at(NormalDepth::MIN, 1).is_err());
+    /// # Ok::<(), eyre::Report>(())
+    /// ```
+    #[doc(alias = "hwloc_get_obj_by_depth")]
+    pub fn object_at<DepthLike>(
+        &self,
+        depth: DepthLike,
+        index: usize,
+    ) -> Result<&TopologyObject, IndexOutOfBounds>
+    where
+        DepthLike: TryInto<Depth>,
+        <DepthLike as TryInto<Depth>>::Error: Debug,
+    {
+        /// Polymorphized version of this function (avoids generics code bloat)
+        fn polymorphized(
+            self_: &Topology,
+            depth: Depth,
+            index: usize,
+        ) -> Result<&TopologyObject, IndexOutOfBounds> {
+            let len = self_.num_objects_at_depth(depth);
+            if index >= len {
+                return Err(IndexOutOfBounds { index, len });
+            }
+            Ok(self_.object_at_unchecked(depth, index))
+        }
+
+        // There are no objects at depths that hwloc cannot represent
+        let Ok(depth) = depth.try_into() else {
+            return Err(IndexOutOfBounds { index, len: 0 });
+        };
+        polymorphized(self, depth, index)
+    }
+
+    /// [`TopologyObject`] with the given [`ObjectType`] and index
+    ///
+    /// Objects are indexed in the order of [`Topology::objects_with_type()`],
+    /// i.e. by increasing depth, then by logical index. For object types that
+    /// only exist at a single depth, which is all types except for
+    /// [`ObjectType::Group`], the index is thus the logical index.
+    ///
+    /// This is a constant-time lookup into the same per-type object index as
+    /// [`Topology::objects_with_type()`].
+    ///
+    /// # Errors
+    ///
+    /// - [`IndexOutOfBounds`] if there are no more than `index` objects of
+    ///   this type
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use hwlocality::object::types::ObjectType;
+    /// # let topology = hwlocality::Topology::test_instance();
+    /// let first_pu = topology.object_with_type_at(ObjectType::PU, 0)?;
+    /// assert_eq!(first_pu.object_type(), ObjectType::PU);
+    /// assert_eq!(first_pu.logical_index(), 0);
+    /// # Ok::<(), eyre::Report>(())
+    /// ```
+    #[doc(alias = "hwloc_get_obj_by_type")]
+    pub fn object_with_type_at(
+        &self,
+        object_type: ObjectType,
+        index: usize,
+    ) -> Result<&TopologyObject, IndexOutOfBounds> {
+        self.indexed_object_with_type(object_type, index)
+            .ok_or_else(|| IndexOutOfBounds {
+                index,
+                len: self.objects_with_type(object_type).len(),
+            })
+    }
+
+    /// [`TopologyObject`] designated by a [path string](TopologyObject::path_string())
+    ///
+    /// Type names in the path are matched without regard to ASCII case, and
+    /// the root object may be designated either as `Machine` or `Machine#0`.
+    ///
+    /// # Errors
+    ///
+    /// - [`InvalidComponent`] if a component of the path is not of the form
+    ///   `Type#index`, or just `Type` for the root object
+    /// - [`NoSuchObject`] if no object matches a component of the path
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use hwlocality::object::types::ObjectType;
+    /// # let topology = hwlocality::Topology::test_instance();
+    /// let root = topology.object_by_path("Machine")?;
+    /// assert!(std::ptr::eq(root, topology.root_object()));
+    /// assert!(topology.object_by_path("Machine/PU").is_err());
+    /// # Ok::<(), eyre::Report>(())
+    /// ```
+    ///
+    /// [`InvalidComponent`]: ObjectPathError::InvalidComponent
+    /// [`NoSuchObject`]: ObjectPathError::NoSuchObject
+    pub fn object_by_path(&self, path: &str) -> Result<&TopologyObject, ObjectPathError> {
+        /// Split a path component into a type name and an optional index
+        fn parse_component(component: &str) -> Result<(&str, Option<usize>), ObjectPathError> {
+            let Some((type_name, index)) = component.split_once('#') else {
+                return Ok((component, None));
+            };
+            let index = index
+                .parse::<usize>()
+                .map_err(|_| ObjectPathError::InvalidComponent(component.to_owned()))?;
+            Ok((type_name, Some(index)))
+        }
+
+        // Check the root component
+        let mut components = path.split('/');
+        let root = self.root_object();
+        let root_component = components.next().unwrap_or_default();
+        let (root_type, root_index) = parse_component(root_component)?;
+        if !root.path_type_name().eq_ignore_ascii_case(root_type) || root_index.unwrap_or(0) != 0 {
+            return Err(ObjectPathError::NoSuchObject(root_component.to_owned()));
+        }
+
+        // Walk down the object tree
+        let mut current = root;
+        for component in components {
+            let (type_name, Some(index)) = parse_component(component)? else {
+                return Err(ObjectPathError::InvalidComponent(component.to_owned()));
+            };
+            current = current
+                .all_children()
+                .find(|child| {
+                    child.logical_index() == index
+                        && child.path_type_name().eq_ignore_ascii_case(type_name)
+                })
+                .ok_or_else(|| ObjectPathError::NoSuchObject(component.to_owned()))?;
+        }
+        Ok(current)
+    }
+
+    /// Object at a certain depth and logical index, which must be in bounds
+    ///
+    /// # Panics
+    ///
+    /// If `index` is not smaller than `self.num_objects_at_depth(depth)`.
+    fn object_at_unchecked(&self, depth: Depth, index: usize) -> &TopologyObject {
+        let idx = c_uint::try_from(index).expect("In-bounds indices come from hwloc");
+        let ptr =
+            // SAFETY: - Topology is trusted to contain a valid ptr (type
+            //           invariant)
+            //         - hwloc ops are trusted not to modify *const parameters
+            //         - By construction, Depth only exposes values that map
+            //           into hwloc_get_depth_type_e values understood by the
+            //           configured version of hwloc, and build.rs checks that
+            //           the active version of hwloc is not older than that, so
+            //           into() may only generate valid hwloc_get_depth_type_e
+            //           values for current hwloc
+            //         - hwloc returns a null pointer for out-of-bounds indices
+            unsafe {
+                hwlocality_sys::hwloc_get_obj_by_depth(self.as_ptr(), depth.to_raw(), idx)
+            };
+        assert!(
+            !ptr.is_null(),
+            "Got null pointer from hwloc_get_obj_by_depth"
+        );
+        // SAFETY: If hwloc_get_obj_by_depth returns a non-null pointer,
+        //         it's assumed to be successful and thus that the
+        //         output pointer and its target are valid
+        unsafe { (&*ptr).as_newtype() }
+    }
+
     /// [`TopologyObject`] at the root of the topology
     ///
     /// Its type is [`ObjectType::Machine`].
@@ -562,6 +775,10 @@ impl Topology {
 
     /// [`TopologyObject`]s with the given [`ObjectType`]
     ///
+    /// Objects are ordered by increasing depth, then by logical index. This is
+    /// a constant-time lookup into a per-type object index, which is built
+    /// when the topology is loaded and rebuilt after each topology edit.
+    ///
     /// # Examples
     ///
     /// ```
@@ -597,24 +814,7 @@ impl Topology {
         object_type: ObjectType,
     ) -> impl DoubleEndedIterator<Item = &TopologyObject> + Clone + ExactSizeIterator + FusedIterator
     {
-        let type_depth = self.depth_for_type(object_type);
-        let depth_iter = NormalDepth::iter_range(NormalDepth::MIN, self.depth())
-            .map(Depth::from)
-            .chain(Depth::VIRTUAL_DEPTHS.iter().copied())
-            .filter(move |&depth| {
-                type_depth.map_or_else(
-                    |_| self.type_at_depth(depth).expect("Depth should exist") == object_type,
-                    |type_depth| depth == type_depth,
-                )
-            });
-        let size = depth_iter
-            .clone()
-            .map(move |depth| self.num_objects_at_depth(depth))
-            .sum();
-        ObjectsWithType {
-            size,
-            inner: depth_iter.flat_map(move |depth| self.objects_at_depth(depth)),
-        }
+        self.indexed_objects_with_type(object_type)
     }
 
     /// Truth that this topology has the same object hierarchy as another, where
@@ -682,57 +882,16 @@ impl Topology {
     }
 }
 
-/// Iterator emitted by [`TopologyObject::objects_with_type()`]
-///
-/// Needed because iterator combinator chains don't implement all desired
-/// [`Iterator`] subtraits.
-#[derive(Copy, Clone)]
-struct ObjectsWithType<Inner> {
-    /// Number of items that this iterator will yield
-    size: usize,
-
-    /// Inner iterator
-    inner: Inner,
-}
-//
-impl<'topology, Inner: Iterator<Item = &'topology TopologyObject>> Iterator
-    for ObjectsWithType<Inner>
-{
-    type Item = &'topology TopologyObject;
-
-    fn next(&mut self) -> Option<Self::Item> {
-        let next = self.inner.next()?;
-        self.size -= 1;
-        Some(next)
-    }
-
-    fn size_hint(&self) -> (usize, Option<usize>) {
-        (self.size, Some(self.size))
-    }
+/// Error returned by [`Topology::object_by_path()`]
+#[derive(Clone, Debug, Eq, Error, Hash, PartialEq)]
+pub enum ObjectPathError {
+    /// A path component is not of the form `Type#index`
+    #[error("{0:?} is not a valid object path component")]
+    InvalidComponent(String),
 
-    fn count(self) -> usize {
-        self.size
-    }
-}
-//
-impl<'topology, Inner: DoubleEndedIterator<Item = &'topology TopologyObject>> DoubleEndedIterator
-    for ObjectsWithType<Inner>
-{
-    fn next_back(&mut self) -> Option<Self::Item> {
-        let next = self.inner.next_back()?;
-        self.size -= 1;
-        Some(next)
-    }
-}
-//
-impl<'topology, Inner: Iterator<Item = &'topology TopologyObject>> ExactSizeIterator
-    for ObjectsWithType<Inner>
-{
-}
-//
-impl<'topology, Inner: FusedIterator<Item = &'topology TopologyObject>> FusedIterator
-    for ObjectsWithType<Inner>
-{
+    /// No object matches a path component
+    #[error("no object matches object path component {0:?}")]
+    NoSuchObject(String),
 }
 
 #[allow(clippy::cognitive_complexity)]
@@ -863,6 +1022,126 @@ pub(crate) mod tests {
         Ok(())
     }
 
+    /// Check that type string -> depth translation is correct
+    #[test]
+    fn depth_for_type_string() {
+        let topology = Topology::test_instance();
+        let type_to_depths = type_to_depths();
+        for s in [
+            "Machine", "Package", "Core", "PU", "L1", "L2Cache", "L3", "L1i", "NUMANode", "Bridge",
+            "PCIDev", "OSDev", "Misc", "Group",
+        ] {
+            let object_type = ObjectType::parse_with_attributes(s).unwrap().object_type;
+            assert_eq!(
+                topology.depth_for_type_string(s),
+                topology
+                    .depth_for_type(object_type)
+                    .map_err(TypeStringToDepthError::from)
+            );
+        }
+        if let Some(group_depths) = type_to_depths.get(&ObjectType::Group) {
+            for &depth in group_depths {
+                let group = topology.objects_at_depth(depth).next().unwrap();
+                let Some(ObjectAttributes::Group(attributes)) = group.attributes() else {
+                    unreachable!()
+                };
+                assert_eq!(
+                    topology.depth_for_type_string(&format!("Group{}", attributes.depth())),
+                    Ok(depth)
+                );
+            }
+        }
+        assert_eq!(
+            topology.depth_for_type_string("NotAType"),
+            Err(ParseTypeError("NotAType".to_owned()).into())
+        );
+    }
+
+    /// Check indexed object lookups against the iterator-based queries
+    #[test]
+    fn object_lookup() {
+        let topology = Topology::test_instance();
+        for depth in valid_depths() {
+            let objects = topology.objects_at_depth(depth).collect::<Vec<_>>();
+            for (idx, obj) in objects.iter().enumerate() {
+                assert!(ptr::eq(topology.object_at(depth, idx).unwrap(), *obj));
+            }
+            assert_eq!(
+                topology.object_at(depth, objects.len()).unwrap_err(),
+                IndexOutOfBounds {
+                    index: objects.len(),
+                    len: objects.len()
+                }
+            );
+        }
+        assert_eq!(
+            topology.object_at(usize::MAX, 0).unwrap_err(),
+            IndexOutOfBounds { index: 0, len: 0 }
+        );
+        for ty in enum_iterator::all::<ObjectType>() {
+            let objects = topology.objects_with_type(ty).collect::<Vec<_>>();
+            for (idx, obj) in objects.iter().enumerate() {
+                assert!(ptr::eq(
+                    topology.object_with_type_at(ty, idx).unwrap(),
+                    *obj
+                ));
+            }
+            assert_eq!(
+                topology.object_with_type_at(ty, objects.len()).unwrap_err(),
+                IndexOutOfBounds {
+                    index: objects.len(),
+                    len: objects.len()
+                }
+            );
+        }
+    }
+
+    /// Check that object paths resolve back to the original object
+    #[test]
+    fn object_paths() {
+        let topology = Topology::test_instance();
+        for obj in topology.objects() {
+            let path = obj.path_string();
+            assert!(ptr::eq(topology.object_by_path(&path).unwrap(), obj));
+            assert!(ptr::eq(
+                topology.object_by_path(&path.to_ascii_lowercase()).unwrap(),
+                obj
+            ));
+        }
+        assert!(ptr::eq(
+            topology.object_by_path("Machine#0").unwrap(),
+            topology.root_object()
+        ));
+
+        let num_pus = topology.objects_with_type(ObjectType::PU).len();
+        let bad_pu = format!("PU#{num_pus}");
+        for (path, error) in [
+            ("", ObjectPathError::NoSuchObject(String::new())),
+            (
+                "Machine#1",
+                ObjectPathError::NoSuchObject("Machine#1".to_owned()),
+            ),
+            (
+                "Package#0",
+                ObjectPathError::NoSuchObject("Package#0".to_owned()),
+            ),
+            (
+                "Machine/PU",
+                ObjectPathError::InvalidComponent("PU".to_owned()),
+            ),
+            (
+                "Machine/PU#x",
+                ObjectPathError::InvalidComponent("PU#x".to_owned()),
+            ),
+            (
+                &format!("Machine/{bad_pu}"),
+                ObjectPathError::NoSuchObject(bad_pu),
+            ),
+        ] {
+            assert_eq!(topology.object_by_path(path).unwrap_err(), error);
+        }
+    }
+
     /// Check the root object
     ///
     /// It's the top of the topology, so we know a lot about it.
@@ -60,6 +60,21 @@ impl Topology {
             .flat_map(|&depth| self.objects_at_depth(depth))
     }
 
+    /// Full list of memory-side caches in the topology
+    ///
+    /// Memory-side caches are filtered out by default, so this will only yield
+    /// objects if the topology was built with a [`TypeFilter`] that keeps
+    /// [`ObjectType::MemCache`] objects. Their size, line size and
+    /// associativity can be queried using
+    /// [`TopologyObject::memory_cache_attributes()`].
+    ///
+    /// [`ObjectType::MemCache`]: crate::object::types::ObjectType::MemCache
+    /// [`TypeFilter`]: crate::topology::builder::TypeFilter
+    #[cfg(feature = "hwloc-2_1_0")]
+    pub fn memory_caches(&self) -> impl FusedIterator<Item = &TopologyObject> + Clone {
+        self.objects_at_depth(Depth::MemCache)
+    }
+
     /// Full list of I/O objects in the topology, ordered by type
     pub fn io_objects(&self) -> impl FusedIterator<Item = &TopologyObject> + Clone {
         Depth::IO_DEPTHS
@@ -137,6 +152,15 @@ pub(crate) mod tests {
             .all(|obj| obj.object_type().is_memory()));
         let memory_keys = object_ids_from_set(&memory_objects);
 
+        #[cfg(feature = "hwloc-2_1_0")]
+        {
+            let memory_caches = checked_object_set(topology.memory_caches());
+            assert!(memory_caches
+                .values()
+                .all(|obj| obj.object_type() == ObjectType::MemCache));
+            assert!(object_ids_from_set(&memory_caches).is_subset(&memory_keys));
+        }
+
         let io_objects = checked_object_set(topology.io_objects());
         assert!(io_objects.values().all(|obj| obj.object_type().is_io()));
         let io_keys = object_ids_from_set(&io_objects);
@@ -15,13 +15,16 @@
 pub mod attributes;
 pub mod depth;
 pub mod distance;
+pub mod handle;
 pub(crate) mod hierarchy;
 pub(crate) mod lists;
 pub mod search;
+pub mod traversal;
 pub mod types;
 
+pub use self::hierarchy::ObjectPathError;
 use self::{
-    attributes::{DownstreamAttributes, ObjectAttributes, PCIDomain},
+    attributes::{CacheAttributes, CacheInfo, DownstreamAttributes, ObjectAttributes, PCIDomain},
     depth::{Depth, NormalDepth},
     types::ObjectType,
 };
@@ -29,12 +32,12 @@ use self::{
 use crate::topology::{builder::BuildFlags, support::DiscoverySupport, Topology};
 use crate::{
     bitmap::BitmapRef,
-    cpu::cpuset::CpuSet,
+    cpu::{cache::CacheLevel, cpuset::CpuSet},
     ffi::{
         self, int,
         transparent::{AsNewtype, TransparentNewtype},
     },
-    info::TextualInfo,
+    info::{StandardInfo, TextualInfo},
     memory::nodeset::NodeSet,
 };
 #[cfg(feature = "hwloc-2_3_0")]
@@ -46,6 +49,8 @@ use hwlocality_sys::{hwloc_obj, HWLOC_UNKNOWN_INDEX};
 #[allow(unused)]
 #[cfg(test)]
 use similar_asserts::assert_eq;
+#[cfg(feature = "hwloc-2_3_0")]
+use std::ffi::c_uint;
 use std::{
     ffi::{c_char, CStr},
     fmt::{self, Debug, Display},
@@ -186,8 +191,15 @@ impl TopologyObject {
     /// - [`NulError`] if `subtype` contains NUL chars.
     #[cfg(feature = "hwloc-2_3_0")]
     pub unsafe fn set_subtype_unchecked(&mut self, subtype: &str) -> Result<(), NulError> {
-        // SAFETY: Per input precondition
-        self.0.subtype = unsafe { LibcString::new(subtype)?.into_raw() };
+        let subtype = LibcString::new(subtype)?;
+        // SAFETY: - Subtype strings are allocated by the libc's malloc, which
+        //           is the same as ours per input precondition
+        //         - The old subtype is not used after being freed, and the
+        //           new one is handed over to hwloc, which will free it
+        unsafe {
+            libc::free(self.0.subtype.cast());
+            self.0.subtype = subtype.into_raw();
+        }
         Ok(())
     }
 
@@ -203,6 +215,33 @@ impl TopologyObject {
         unsafe { ffi::deref_str(&self.0.name) }
     }
 
+    /// Set the object-specific name
+    ///
+    /// This is only exposed via `TopologyEditor::set_object_name()` because
+    /// hwloc uses the name of some objects, like Misc objects, as an
+    /// identifier, which must not be changed behind the editor's back.
+    ///
+    /// # Safety
+    ///
+    /// Same as [`TopologyObject::set_subtype_unchecked()`].
+    ///
+    /// # Errors
+    ///
+    /// - [`NulError`] if `name` contains NUL chars.
+    #[cfg(feature = "hwloc-2_3_0")]
+    pub(crate) unsafe fn set_name_unchecked(&mut self, name: &str) -> Result<(), NulError> {
+        let name = LibcString::new(name)?;
+        // SAFETY: - Name strings are allocated by the libc's malloc, which is
+        //           the same as ours per input precondition
+        //         - The old name is not used after being freed, and the new
+        //           one is handed over to hwloc, which will free it
+        unsafe {
+            libc::free(self.0.name.cast());
+            self.0.name = name.into_raw();
+        }
+        Ok(())
+    }
+
     /// Object type-specific attributes, if any
     #[doc(alias = "hwloc_obj::attr")]
     pub fn attributes(&self) -> Option<ObjectAttributes<'_>> {
@@ -210,6 +249,48 @@ impl TopologyObject {
         unsafe { ObjectAttributes::new(self.object_type(), &self.0.attr) }
     }
 
+    /// Cache attributes of a [`MemCache`] object
+    ///
+    /// Memory-side caches are described by the same attributes as CPU caches,
+    /// but since they are not CPU caches, [`attributes()`] does not expose
+    /// them. This method exposes them, and returns `None` if this object is
+    /// not a memory-side cache.
+    ///
+    /// [`attributes()`]: Self::attributes()
+    /// [`MemCache`]: ObjectType::MemCache
+    #[cfg(feature = "hwloc-2_1_0")]
+    #[doc(alias = "hwloc_obj_attr_u::cache")]
+    pub fn memory_cache_attributes(&self) -> Option<&CacheAttributes> {
+        if self.object_type() != ObjectType::MemCache || self.0.attr.is_null() {
+            return None;
+        }
+        // SAFETY: - We checked that the attribute pointer is non-null
+        //         - hwloc populates the cache attributes of MemCache objects
+        //         - Pointer validity is assumed as a type invariant
+        //         - Rust aliasing rules are enforced by deriving the reference
+        //           from &self, which itself is derived from &Topology
+        Some(unsafe { (&(*self.0.attr).cache).as_newtype() })
+    }
+
+    /// Properties of this object, if it is a CPU or memory-side cache
+    ///
+    /// This combines the [`CacheAttributes`] of the cache with the cache
+    /// properties that hwloc reports as info attributes, like `Inclusive`, so
+    /// that you do not need to look both up and parse the latter by hand.
+    ///
+    /// `None` is returned if this object is not a cache.
+    pub fn cache_info(&self) -> Option<CacheInfo> {
+        let inclusive = || self.standard_info().inclusive();
+        if let Some(ObjectAttributes::Cache(attr)) = self.attributes() {
+            return Some(CacheInfo::new(attr, inclusive()));
+        }
+        #[cfg(feature = "hwloc-2_1_0")]
+        if let Some(attr) = self.memory_cache_attributes() {
+            return Some(CacheInfo::new(attr, inclusive()));
+        }
+        None
+    }
+
     /// The OS-provided physical index number
     ///
     /// It is not guaranteed unique across the entire machine,
@@ -237,6 +318,19 @@ impl TopologyObject {
     pub fn global_persistent_index(&self) -> TopologyObjectID {
         self.0.gp_index
     }
+
+    /// Raw pointer to the underlying hwloc object
+    ///
+    /// This is meant for interoperability with other C libraries which consume
+    /// hwloc objects, such as MPI implementations or OpenMP runtimes.
+    ///
+    /// The pointer is only valid as long as the topology that this object
+    /// belongs to is not modified or dropped, and the pointed-to object must
+    /// not be modified through it. If you need to modify the topology through
+    /// raw hwloc APIs, use `Topology::with_raw()` instead.
+    pub fn as_raw(&self) -> *const hwloc_obj {
+        &self.0
+    }
 }
 
 /// Global persistent [`TopologyObject`] ID
@@ -448,6 +542,38 @@ impl TopologyObject {
             .find(|ancestor| ancestor.object_type().is_cpu_data_cache())
     }
 
+    /// Iterate over the CPU caches above this object, from the closest to the
+    /// furthest one
+    ///
+    /// This lists all ancestors of this object that are CPU caches (data,
+    /// instruction and unified caches of all levels) along with their level
+    /// in the cache hierarchy and their attributes, which tells you which
+    /// caches e.g. a given PU has access to. Caches that are filtered out of
+    /// the topology are not listed, and in particular instruction caches are
+    /// filtered out by default.
+    ///
+    /// The object itself is not listed, even if it is a cache.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # let topology = hwlocality::Topology::test_instance();
+    /// use hwlocality::object::types::ObjectType;
+    ///
+    /// let pu = topology.objects_with_type(ObjectType::PU).next().unwrap();
+    /// for (level, cache, attributes) in pu.caches() {
+    ///     println!("{level} cache {cache} has size {:?}", attributes.size());
+    /// }
+    /// ```
+    pub fn caches(
+        &self,
+    ) -> impl FusedIterator<Item = (CacheLevel, &Self, CacheAttributes)> + Clone {
+        self.ancestors().filter_map(|ancestor| {
+            let (level, attributes) = CacheLevel::of_object(ancestor)?;
+            Some((level, ancestor, attributes))
+        })
+    }
+
     /// Get the first non-I/O ancestor object
     ///
     /// Find the smallest non-I/O ancestor object. This object (normal or
@@ -460,6 +586,55 @@ impl TopologyObject {
     pub fn first_non_io_ancestor(&self) -> Option<&Self> {
         self.ancestors().find(|obj| obj.cpuset().is_some())
     }
+
+    /// Canonical path from the topology root to this object
+    ///
+    /// The path lists the root object and all the descendants leading to this
+    /// object, separated by slashes, e.g. `Machine/Package#1/L3#0/Core#5/PU#11`.
+    /// Each object below the root is designated by its type and its logical
+    /// index, where CPU caches use the [`CacheLevel`] notation (e.g. `L1d`)
+    /// and other objects use the [`ObjectType`] notation.
+    ///
+    /// This is meant for configuration files and logs that need to refer to
+    /// specific objects in a human-readable way. The path can be resolved back
+    /// into an object using [`Topology::object_by_path()`], and it remains
+    /// valid as long as the topology is built in the same way on the same
+    /// machine.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use hwlocality::object::types::ObjectType;
+    /// # let topology = hwlocality::Topology::test_instance();
+    /// let pu = topology.objects_with_type(ObjectType::PU).next().unwrap();
+    /// let path = pu.path_string();
+    /// assert!(path.starts_with("Machine/"));
+    /// assert!(path.ends_with("/PU#0"));
+    /// assert!(std::ptr::eq(topology.object_by_path(&path)?, pu));
+    /// # Ok::<(), eyre::Report>(())
+    /// ```
+    pub fn path_string(&self) -> String {
+        let mut components = std::iter::once(self)
+            .chain(self.ancestors())
+            .map(|obj| {
+                if obj.parent().is_some() {
+                    format!("{}#{}", obj.path_type_name(), obj.logical_index())
+                } else {
+                    obj.path_type_name()
+                }
+            })
+            .collect::<Vec<_>>();
+        components.reverse();
+        components.join("/")
+    }
+
+    /// Type name that is used by [`TopologyObject::path_string()`]
+    pub(crate) fn path_type_name(&self) -> String {
+        CacheLevel::of_object(self).map_or_else(
+            || self.object_type().to_string(),
+            |(level, _attributes)| level.to_string(),
+        )
+    }
 }
 
 /// Iterator over ancestors of a topology object
@@ -606,6 +781,37 @@ impl TopologyObject {
             .find(|child| child.covers_cpuset(set))
     }
 
+    /// Get the deepest object of this subtree covering at least the given
+    /// cpuset `set`
+    ///
+    /// `set` can be a `&'_ CpuSet` or a `BitmapRef<'_, CpuSet>`.
+    ///
+    /// Starting from this object, this repeatedly descends into the
+    /// [normal child covering `set`](Self::normal_child_covering_cpuset())
+    /// until no such child exists, then returns the last object that was
+    /// reached. If this object does not cover `set` itself, `None` is returned.
+    ///
+    /// This function will always return `None` if the given set is empty or
+    /// this topology object doesn't have a cpuset (I/O or Misc objects), as
+    /// no object is considered to cover the empty cpuset.
+    pub fn descend_covering(&self, set: impl Deref<Target = CpuSet>) -> Option<&Self> {
+        /// Polymorphized version of this function (avoids generics code bloat)
+        fn polymorphized<'self_>(
+            self_: &'self_ TopologyObject,
+            set: &CpuSet,
+        ) -> Option<&'self_ TopologyObject> {
+            if !self_.covers_cpuset(set) {
+                return None;
+            }
+            let mut parent = self_;
+            while let Some(child) = parent.normal_child_covering_cpuset(set) {
+                parent = child;
+            }
+            Some(parent)
+        }
+        polymorphized(self, &set)
+    }
+
     /// Number of memory children
     #[doc(alias = "hwloc_obj::memory_arity")]
     pub fn memory_arity(&self) -> usize {
@@ -922,6 +1128,11 @@ impl TopologyObject {
 
     /// Search the given key name in object infos and return the corresponding value
     ///
+    /// The key can be specified either as a string or as raw bytes, which
+    /// allows looking up infos whose names are not valid UTF-8. Matching is
+    /// performed on raw bytes, so infos with invalid UTF-8 names or values are
+    /// never skipped.
+    ///
     /// Beware that hwloc allows multiple informations with the same key to
     /// exist, although no sane programs should leverage this possibility.
     /// If multiple keys match the given name, only the first one is returned.
@@ -930,15 +1141,26 @@ impl TopologyObject {
     /// you need to do this sort of search many times, consider collecting
     /// `infos()` into a `HashMap` or `BTreeMap` for increased lookup efficiency.
     #[doc(alias = "hwloc_obj_get_info_by_name")]
-    pub fn info(&self, key: &str) -> Option<&CStr> {
-        self.infos().iter().find_map(|info| {
-            let Ok(info_name) = info.name().to_str() else {
-                // hwloc does not currently emit invalid Unicode, but it might
-                // someday if a malicious C program tampered with the topology
-                return None;
-            };
-            (info_name == key).then_some(info.value())
-        })
+    pub fn info(&self, key: impl AsRef<[u8]>) -> Option<&CStr> {
+        /// Polymorphized version of this function (avoids generics code bloat)
+        fn polymorphized<'self_>(
+            self_: &'self_ TopologyObject,
+            key: &[u8],
+        ) -> Option<&'self_ CStr> {
+            self_
+                .infos()
+                .iter()
+                .find_map(|info| (info.name_bytes() == key).then_some(info.value()))
+        }
+        polymorphized(self, key.as_ref())
+    }
+
+    /// Typed accessors to the standard info attributes of this object
+    ///
+    /// This lets you query well-known info attributes like `CPUModel` or
+    /// `NVIDIAUUID` without spelling out their names as strings.
+    pub fn standard_info(&self) -> StandardInfo<'_> {
+        StandardInfo::new(self)
     }
 
     /// Add the given info name and value pair to the given object
@@ -972,10 +1194,146 @@ impl TopologyObject {
         .map(std::mem::drop)
         .map_err(HybridError::Hwloc)
     }
+
+    /// Set the value associated with an info name, replacing any previous one
+    ///
+    /// This exposes [`TopologyObject::replace_info_unchecked()`] as a safe
+    /// method on operating systems which aren't known to facilitate mixing and
+    /// matching libc versions between an application and its dependencies.
+    #[allow(clippy::missing_errors_doc)]
+    #[cfg(all(feature = "hwloc-2_3_0", not(windows)))]
+    pub fn replace_info(&mut self, name: &str, value: &str) -> Result<(), HybridError<NulError>> {
+        // SAFETY: Underlying OS is assumed not to ergonomically encourage
+        //         unsafe multi-libc linkage
+        unsafe { self.replace_info_unchecked(name, value) }
+    }
+
+    /// Set the value associated with an info name, replacing any previous one
+    ///
+    /// hwloc itself can only append infos to an object, which means that
+    /// [`add_info()`] cannot be used to correct an annotation that was
+    /// previously added. This method works around this limitation by directly
+    /// rewriting the object's info array: the first info with a matching name
+    /// gets its value replaced, and any other info with the same name is
+    /// removed. If there was no info with this name, a new one is appended.
+    ///
+    /// The result is visible to all hwloc consumers, including XML export.
+    ///
+    /// # Safety
+    ///
+    /// This method is only safe to call if you can guarantee that your
+    /// application links against the same libc/CRT as hwloc. See
+    /// [`TopologyObject::set_subtype_unchecked()`] for more information.
+    ///
+    /// # Errors
+    ///
+    /// - [`NulError`] if `name` or `value` contains NUL chars.
+    ///
+    /// [`add_info()`]: Self::add_info()
+    #[cfg(feature = "hwloc-2_3_0")]
+    pub unsafe fn replace_info_unchecked(
+        &mut self,
+        name: &str,
+        value: &str,
+    ) -> Result<(), HybridError<NulError>> {
+        // If there is no info with this name yet, just append a new one
+        let Some(first_idx) = self
+            .infos()
+            .iter()
+            .position(|info| info.name_bytes() == name.as_bytes())
+        else {
+            return self.add_info(name, value);
+        };
+
+        // Otherwise, replace the value of the first matching info...
+        let value = LibcString::new(value)?;
+        // SAFETY: - infos and count are in sync per type invariant, and
+        //           first_idx was checked to be in bounds above
+        //         - Info strings are allocated by the libc's malloc, which is
+        //           the same as ours per input precondition
+        //         - The old value is not used after being freed, and the new
+        //           value is handed over to hwloc, which will free it
+        unsafe {
+            let info = self.0.infos.add(first_idx);
+            libc::free((*info).value.cast());
+            (*info).value = value.into_raw();
+        }
+
+        // ...and discard the other ones
+        // SAFETY: Per input precondition
+        unsafe { self.remove_infos_after(first_idx + 1, name) };
+        Ok(())
+    }
+
+    /// Remove all infos with a certain name, returning how many were removed
+    ///
+    /// This exposes [`TopologyObject::remove_info_unchecked()`] as a safe
+    /// method on operating systems which aren't known to facilitate mixing and
+    /// matching libc versions between an application and its dependencies.
+    #[cfg(all(feature = "hwloc-2_3_0", not(windows)))]
+    pub fn remove_info(&mut self, name: &str) -> usize {
+        // SAFETY: Underlying OS is assumed not to ergonomically encourage
+        //         unsafe multi-libc linkage
+        unsafe { self.remove_info_unchecked(name) }
+    }
+
+    /// Remove all infos with a certain name, returning how many were removed
+    ///
+    /// hwloc does not provide a way to remove infos from an object, so this is
+    /// done by directly rewriting the object's info array. The relative order
+    /// of the remaining infos is preserved.
+    ///
+    /// # Safety
+    ///
+    /// This method is only safe to call if you can guarantee that your
+    /// application links against the same libc/CRT as hwloc. See
+    /// [`TopologyObject::set_subtype_unchecked()`] for more information.
+    #[cfg(feature = "hwloc-2_3_0")]
+    pub unsafe fn remove_info_unchecked(&mut self, name: &str) -> usize {
+        // SAFETY: Per input precondition
+        unsafe { self.remove_infos_after(0, name) }
+    }
 }
 
 // # Internal utilities
 impl TopologyObject {
+    /// Remove infos with a certain name, starting at index `start` of the info
+    /// array, and return how many infos were removed
+    ///
+    /// # Safety
+    ///
+    /// The application must link against the same libc/CRT as hwloc.
+    #[cfg(feature = "hwloc-2_3_0")]
+    unsafe fn remove_infos_after(&mut self, start: usize, name: &str) -> usize {
+        let len = self.infos().len();
+        let mut kept = start;
+        for idx in start..len {
+            // SAFETY: - infos and count are in sync per type invariant, and
+            //           idx and kept are both below the info count
+            //         - Info strings are allocated by the libc's malloc, which
+            //           is the same as ours per function precondition
+            //         - Removed strings are freed once and their slot is then
+            //           either overwritten or truncated away, so they can't be
+            //           used after being freed
+            unsafe {
+                let info = self.0.infos.add(idx);
+                let info_name = CStr::from_ptr((*info).name);
+                if info_name.to_bytes() == name.as_bytes() {
+                    libc::free((*info).name.cast());
+                    libc::free((*info).value.cast());
+                } else {
+                    if kept != idx {
+                        self.0.infos.add(kept).write(info.read());
+                    }
+                    kept += 1;
+                }
+            }
+        }
+        self.0.infos_count =
+            c_uint::try_from(kept).expect("Can't have more infos than there initially were");
+        len - kept
+    }
+
     /// Display this object's type and attributes
     fn display(&self, f: &mut fmt::Formatter<'_>, verbose: bool) -> fmt::Result {
         // SAFETY: - These are indeed snprintf-like APIs
@@ -1156,6 +1514,18 @@ pub(crate) mod tests {
         check_children(obj)?;
         check_infos(obj)?;
         check_displays(obj)?;
+        #[cfg(feature = "hwloc-2_1_0")]
+        check_memory_cache(obj)?;
+        Ok(())
+    }
+
+    /// Check that only memory-side caches expose memory-side cache attributes
+    #[cfg(feature = "hwloc-2_1_0")]
+    fn check_memory_cache(obj: &TopologyObject) -> Result<(), TestCaseError> {
+        if obj.memory_cache_attributes().is_some() {
+            prop_assert_eq!(obj.object_type(), ObjectType::MemCache);
+            prop_assert!(obj.attributes().is_none());
+        }
         Ok(())
     }
 
@@ -1189,6 +1559,9 @@ pub(crate) mod tests {
 
         let first_ancestor = obj.ancestors().next().unwrap();
         prop_assert!(ptr::eq(parent, first_ancestor));
+        // SAFETY: Object pointers are valid as long as the topology is
+        let raw_parent = unsafe { (*obj.as_raw()).parent };
+        prop_assert!(ptr::eq(raw_parent, parent.as_raw()));
 
         if let (Depth::Normal(parent_depth), Depth::Normal(obj_depth)) =
             (parent.depth(), obj.depth())
@@ -1350,14 +1723,14 @@ pub(crate) mod tests {
     /// Check that an object's info metadata matches expectations
     fn check_infos(obj: &TopologyObject) -> Result<(), TestCaseError> {
         for info in obj.infos() {
-            if let Ok(name) = info.name().to_str() {
-                prop_assert_eq!(
-                    obj.info(name),
-                    obj.infos()
-                        .iter()
-                        .find(|other_info| other_info.name() == info.name())
-                        .map(TextualInfo::value)
-                );
+            let expected = obj
+                .infos()
+                .iter()
+                .find(|other_info| other_info.name() == info.name())
+                .map(TextualInfo::value);
+            prop_assert_eq!(obj.info(info.name_bytes()), expected);
+            if let Ok(name) = info.name_str() {
+                prop_assert_eq!(obj.info(name), expected);
             }
         }
         // NOTE: Looking up invalid info names is tested elsewhere
@@ -1569,6 +1942,18 @@ pub(crate) mod tests {
             }
         }
 
+        /// Test [`TopologyObject::descend_covering()`]
+        #[test]
+        fn descend_covering((obj, set) in object_and_related_cpuset()) {
+            let Some(result) = obj.descend_covering(&set) else {
+                prop_assert!(!obj.covers_cpuset(&set));
+                return Ok(());
+            };
+            prop_assert!(result.covers_cpuset(&set));
+            prop_assert!(ptr::eq(result, obj) || result.is_in_subtree(obj));
+            prop_assert!(result.normal_child_covering_cpuset(&set).is_none());
+        }
+
         /// Test [`TopologyObject::is_inside_cpuset()`]
         #[test]
         fn is_inside_cpuset((obj, set) in object_and_related_cpuset()) {
@@ -1794,6 +2179,68 @@ pub(crate) mod tests {
                     Ok(())
                 })?;
             }
+
+            // Try to replace an info (key, value) pair that was added twice
+            #[cfg(not(windows))]
+            #[test]
+            fn replace_info(name in any_string(), values in prop::array::uniform3(any_string())) {
+                // Skip NUL checks, which are covered by add_info, and names
+                // that collide with the other infos used by this test
+                let has_nul = |s: &str| s.chars().any(|c| c == '\0');
+                prop_assume!(!has_nul(&name) && !values.iter().any(|v| has_nul(v)));
+                prop_assume!(!["before", "between", "after"].contains(&name.as_str()));
+                test_object_editing(|obj| {
+                    // Add two infos with the same name, then replace them
+                    obj.add_info("before", "a").unwrap();
+                    obj.add_info(&name, &values[0]).unwrap();
+                    obj.add_info("between", "b").unwrap();
+                    obj.add_info(&name, &values[1]).unwrap();
+                    obj.add_info("after", "c").unwrap();
+                    obj.replace_info(&name, &values[2]).unwrap();
+
+                    // Only one info with this name should remain, in the
+                    // position of the first one, and others should be intact
+                    let infos = obj
+                        .infos()
+                        .iter()
+                        .map(|info| (info.name().to_str().unwrap(), info.value().to_str().unwrap()))
+                        .collect::<Vec<_>>();
+                    let expected = vec![
+                        ("before", "a"),
+                        (name.as_str(), values[2].as_str()),
+                        ("between", "b"),
+                        ("after", "c"),
+                    ];
+                    prop_assert_eq!(infos, expected);
+                    prop_assert_eq!(obj.info(&name).unwrap().to_str().unwrap(), &values[2]);
+                    Ok(())
+                })?;
+            }
+
+            // Try to remove an info (key, value) pair that was added twice
+            #[cfg(not(windows))]
+            #[test]
+            fn remove_info(name in any_string(), value in any_string()) {
+                test_object_editing(|obj| {
+                    // Adding infos with NUL chars will fail, covered by add_info
+                    let num_added = usize::from(obj.add_info(&name, &value).is_ok());
+                    obj.add_info("other", "info").unwrap();
+                    let num_added = num_added + usize::from(obj.add_info(&name, &value).is_ok());
+
+                    // Remove the infos and check the outcome
+                    let num_removed = obj.remove_info(&name);
+                    prop_assert!(obj.info(&name).is_none());
+                    if name == "other" {
+                        prop_assert_eq!(num_removed, num_added + 1);
+                        prop_assert!(obj.infos().is_empty());
+                    } else {
+                        prop_assert_eq!(num_removed, num_added);
+                        prop_assert_eq!(obj.infos().len(), 1);
+                        prop_assert_eq!(obj.info("other").unwrap().to_str().unwrap(), "info");
+                    }
+                    Ok(())
+                })?;
+            }
         }
     }
 }
@@ -1,10 +1,12 @@
 //! Looking for I/O objects
 
 use crate::{
+    cpu::cpuset::CpuSet,
     errors::ParameterError,
     object::{
-        attributes::{ObjectAttributes, PCIDomain},
+        attributes::{ObjectAttributes, PCIDeviceAttributes, PCIDomain},
         depth::Depth,
+        types::{BridgeType, OSDeviceType},
         TopologyObject,
     },
     topology::Topology,
@@ -98,6 +100,73 @@ impl Topology {
         self.objects_at_depth(Depth::OSDevice)
     }
 
+    /// Enumerate OS devices of a certain type
+    pub fn os_devices_with_type(
+        &self,
+        ty: OSDeviceType,
+    ) -> impl DoubleEndedIterator<Item = &TopologyObject> + Clone + FusedIterator {
+        self.os_devices().filter(move |osdev| {
+            let Some(ObjectAttributes::OSDevice(attributes)) = osdev.attributes() else {
+                unreachable!("All OS devices should have OS device attributes")
+            };
+            attributes.device_type() == ty
+        })
+    }
+
+    /// Enumerate network interfaces, e.g. `eth0` on Linux
+    pub fn network_devices(
+        &self,
+    ) -> impl DoubleEndedIterator<Item = NetworkDevice<'_>> + Clone + FusedIterator {
+        self.os_devices_with_type(OSDeviceType::Network)
+            .map(|object| NetworkDevice { object })
+    }
+
+    /// Enumerate storage devices, e.g. `sda` on Linux
+    pub fn block_devices(
+        &self,
+    ) -> impl DoubleEndedIterator<Item = BlockDevice<'_>> + Clone + FusedIterator {
+        self.os_devices_with_type(OSDeviceType::Storage)
+            .map(|object| BlockDevice { object })
+    }
+
+    /// Enumerate GPU devices, e.g. `card0` for a Linux DRM device
+    ///
+    /// hwloc may expose the same physical GPU through several OS devices, one
+    /// per software interface that gives access to it.
+    pub fn gpu_devices(
+        &self,
+    ) -> impl DoubleEndedIterator<Item = GPUDevice<'_>> + Clone + FusedIterator {
+        self.os_devices_with_type(OSDeviceType::GPU)
+            .map(|object| GPUDevice { object })
+    }
+
+    /// Enumerate DMA engine devices, e.g. `dma0chan0` on Linux
+    pub fn dma_devices(
+        &self,
+    ) -> impl DoubleEndedIterator<Item = &TopologyObject> + Clone + FusedIterator {
+        self.os_devices_with_type(OSDeviceType::DMA)
+    }
+
+    /// Find the OS device with a certain name
+    ///
+    /// The name can be specified either as a string or as raw bytes, e.g.
+    /// `"mlx5_0"` for a Mellanox InfiniBand adapter or `"eth0"` for a network
+    /// interface. If multiple OS devices share the same name, only the first
+    /// one is returned.
+    pub fn find_osdev_by_name(&self, name: impl AsRef<[u8]>) -> Option<&TopologyObject> {
+        /// Polymorphized version of this function (avoids generics code bloat)
+        fn polymorphized<'self_>(
+            self_: &'self_ Topology,
+            name: &[u8],
+        ) -> Option<&'self_ TopologyObject> {
+            self_.os_devices().find(|obj| {
+                obj.name()
+                    .is_some_and(|obj_name| obj_name.to_bytes() == name)
+            })
+        }
+        polymorphized(self, name.as_ref())
+    }
+
     /// Enumerate bridges in the system
     #[doc(alias = "hwloc_get_next_bridge")]
     pub fn bridges(
@@ -106,6 +175,239 @@ impl Topology {
     {
         self.objects_at_depth(Depth::Bridge)
     }
+
+    /// Enumerate host bridges in the system
+    ///
+    /// Host bridges are the roots of the PCI hierarchy, which connect it to
+    /// the rest of the system. The bridges and devices below each of them can
+    /// be enumerated using [`TopologyObject::pci_subtree()`].
+    pub fn host_bridges(
+        &self,
+    ) -> impl DoubleEndedIterator<Item = &TopologyObject> + Clone + FusedIterator {
+        self.bridges().filter(|bridge| {
+            let Some(ObjectAttributes::Bridge(attributes)) = bridge.attributes() else {
+                unreachable!("All bridges should have bridge attributes")
+            };
+            attributes.upstream_type() == BridgeType::Host
+        })
+    }
+
+    /// Locality of each PCI device in the system
+    ///
+    /// This reports the PCI attributes (bus ID, class, link speed...) of
+    /// each PCI device, in the same order as [`pci_devices()`], along with
+    /// the CPUs and NUMA nodes that are closest to it. This is the information
+    /// that NIC and GPU placement logic typically needs.
+    ///
+    /// The output is empty if PCI devices were filtered out of the topology,
+    /// which is the default. See [`TopologyBuilder::with_io_type_filter()`]
+    /// for more information.
+    ///
+    /// [`pci_devices()`]: Self::pci_devices()
+    /// [`TopologyBuilder::with_io_type_filter()`]: crate::topology::builder::TopologyBuilder::with_io_type_filter()
+    pub fn pci_locality_map(&self) -> Vec<PCIDeviceLocality<'_>> {
+        self.pci_devices()
+            .map(|device| {
+                let Some(ObjectAttributes::PCIDevice(attributes)) = device.attributes() else {
+                    unreachable!("All PCI devices should have PCI attributes")
+                };
+                let locality = device
+                    .first_non_io_ancestor()
+                    .expect("I/O objects should have a non-I/O ancestor");
+                let cpuset = locality
+                    .cpuset()
+                    .expect("Non-I/O objects should have a cpuset")
+                    .clone_target();
+                let numa_nodes = locality
+                    .nodeset()
+                    .map(|nodeset| self.nodes_from_nodeset(nodeset).collect())
+                    .unwrap_or_default();
+                PCIDeviceLocality {
+                    device,
+                    attributes: *attributes,
+                    locality,
+                    cpuset,
+                    numa_nodes,
+                }
+            })
+            .collect()
+    }
+}
+
+/// Network interface
+///
+/// Yielded by [`Topology::network_devices()`].
+#[derive(Copy, Clone, Debug)]
+pub struct NetworkDevice<'topology> {
+    /// Underlying OS device object
+    object: &'topology TopologyObject,
+}
+//
+impl<'topology> NetworkDevice<'topology> {
+    /// Underlying OS device object
+    pub fn object(&self) -> &'topology TopologyObject {
+        self.object
+    }
+
+    /// Interface name, e.g. `eth0`
+    pub fn name(&self) -> Option<&'topology str> {
+        self.object.name().and_then(|name| name.to_str().ok())
+    }
+
+    /// Hardware address, e.g. a MAC address for Ethernet interfaces
+    ///
+    /// This is the `Address` info of the OS device.
+    pub fn address(&self) -> Option<&'topology str> {
+        info_str(self.object, "Address")
+    }
+
+    /// Port number, for interfaces of multi-port NICs
+    ///
+    /// This is the `Port` info of the OS device.
+    pub fn port(&self) -> Option<u32> {
+        info_str(self.object, "Port")?.parse().ok()
+    }
+}
+
+/// Storage device
+///
+/// Yielded by [`Topology::block_devices()`].
+#[derive(Copy, Clone, Debug)]
+pub struct BlockDevice<'topology> {
+    /// Underlying OS device object
+    object: &'topology TopologyObject,
+}
+//
+impl<'topology> BlockDevice<'topology> {
+    /// Underlying OS device object
+    pub fn object(&self) -> &'topology TopologyObject {
+        self.object
+    }
+
+    /// Device name, e.g. `sda`
+    pub fn name(&self) -> Option<&'topology str> {
+        self.object.name().and_then(|name| name.to_str().ok())
+    }
+
+    /// Linux major and minor device numbers
+    ///
+    /// This is the `LinuxDeviceID` info of the OS device.
+    pub fn linux_device_id(&self) -> Option<(u32, u32)> {
+        let (major, minor) = info_str(self.object, "LinuxDeviceID")?.split_once(':')?;
+        Some((major.parse().ok()?, minor.parse().ok()?))
+    }
+
+    /// Device size in bytes
+    ///
+    /// This is derived from the `Size` info of the OS device, which hwloc
+    /// specifies in KiB.
+    pub fn size(&self) -> Option<u64> {
+        info_str(self.object, "Size")?
+            .parse::<u64>()
+            .ok()?
+            .checked_mul(1024)
+    }
+
+    /// Device vendor
+    pub fn vendor(&self) -> Option<&'topology str> {
+        info_str(self.object, "Vendor")
+    }
+
+    /// Device model
+    pub fn model(&self) -> Option<&'topology str> {
+        info_str(self.object, "Model")
+    }
+}
+
+/// GPU device
+///
+/// Yielded by [`Topology::gpu_devices()`].
+#[derive(Copy, Clone, Debug)]
+pub struct GPUDevice<'topology> {
+    /// Underlying OS device object
+    object: &'topology TopologyObject,
+}
+//
+impl<'topology> GPUDevice<'topology> {
+    /// Underlying OS device object
+    pub fn object(&self) -> &'topology TopologyObject {
+        self.object
+    }
+
+    /// Device name, e.g. `card0`
+    pub fn name(&self) -> Option<&'topology str> {
+        self.object.name().and_then(|name| name.to_str().ok())
+    }
+
+    /// GPU vendor
+    ///
+    /// This is the `GPUVendor` info of the OS device.
+    pub fn vendor(&self) -> Option<&'topology str> {
+        info_str(self.object, "GPUVendor")
+    }
+
+    /// GPU model
+    ///
+    /// This is the `GPUModel` info of the OS device.
+    pub fn model(&self) -> Option<&'topology str> {
+        info_str(self.object, "GPUModel")
+    }
+}
+
+/// Value of a textual info of an object, if present and valid UTF-8
+fn info_str<'topology>(object: &'topology TopologyObject, key: &str) -> Option<&'topology str> {
+    object.info(key).and_then(|value| value.to_str().ok())
+}
+
+/// Locality of a PCI device
+///
+/// Returned by [`Topology::pci_locality_map()`].
+#[derive(Clone, Debug)]
+pub struct PCIDeviceLocality<'topology> {
+    /// PCI device object
+    pub device: &'topology TopologyObject,
+
+    /// PCI attributes of the device (bus ID, class, link speed...)
+    pub attributes: PCIDeviceAttributes,
+
+    /// First non-I/O ancestor of the device
+    ///
+    /// This is the smallest normal object whose CPUs are close to the device,
+    /// e.g. a package or a NUMA-specific group.
+    pub locality: &'topology TopologyObject,
+
+    /// CPUs that are closest to the device
+    pub cpuset: CpuSet,
+
+    /// NUMA nodes that are closest to the device
+    pub numa_nodes: Vec<&'topology TopologyObject>,
+}
+//
+impl PCIDeviceLocality<'_> {
+    /// PCI bus ID of the device, in `domain:bus:device.function` notation
+    ///
+    /// This is the format that [`Topology::pci_device_by_bus_id_string()`]
+    /// accepts, and that tools like `lspci` display.
+    pub fn bus_id(&self) -> String {
+        format!(
+            "{:04x}:{:02x}:{:02x}.{:01x}",
+            self.attributes.domain(),
+            self.attributes.bus_id(),
+            self.attributes.bus_device(),
+            self.attributes.function()
+        )
+    }
+
+    /// PCI class ID of the device
+    pub fn class_id(&self) -> u16 {
+        self.attributes.class_id()
+    }
+
+    /// PCI link speed of the device in GB/s, if known
+    pub fn link_speed(&self) -> Option<f32> {
+        let speed = self.attributes.link_speed();
+        (speed > 0.0).then_some(speed)
+    }
 }
 
 #[allow(clippy::cognitive_complexity)]
@@ -140,6 +442,136 @@ mod tests {
         Ok(())
     }
 
+    /// Check that [`Topology::pci_locality_map()`] is consistent with the
+    /// PCI devices of the topology
+    #[test]
+    fn pci_locality_map() {
+        let topology = Topology::test_instance();
+        let map = topology.pci_locality_map();
+        assert_eq!(map.len(), topology.pci_devices().len());
+        for (entry, device) in map.iter().zip(topology.pci_devices()) {
+            assert!(ptr::eq(entry.device, device));
+            assert!(ptr::eq(
+                entry.locality,
+                device.first_non_io_ancestor().unwrap()
+            ));
+            assert_eq!(entry.cpuset, entry.locality.cpuset().unwrap());
+            for node in &entry.numa_nodes {
+                assert!(entry
+                    .locality
+                    .nodeset()
+                    .unwrap()
+                    .is_set(node.os_index().unwrap()));
+            }
+            assert!(ptr::eq(
+                topology
+                    .pci_device_by_bus_id_string(&entry.bus_id())
+                    .unwrap()
+                    .unwrap(),
+                device
+            ));
+        }
+    }
+
+    /// Check that [`Topology::host_bridges()`] yields the roots of the PCI
+    /// hierarchy
+    #[test]
+    fn host_bridges() {
+        let topology = Topology::test_instance();
+        let host_bridges = topology.host_bridges().collect::<Vec<_>>();
+        for bridge in topology.bridges() {
+            let is_root = bridge
+                .parent()
+                .is_some_and(|parent| !parent.object_type().is_io());
+            assert_eq!(
+                host_bridges.iter().any(|host| ptr::eq(*host, bridge)),
+                is_root
+            );
+        }
+    }
+
+    /// Check the typed OS device iterators
+    #[test]
+    fn os_devices_with_type() {
+        let topology = Topology::test_instance();
+        for ty in enum_iterator::all::<OSDeviceType>() {
+            let expected = topology.os_devices().filter(|osdev| {
+                matches!(
+                    osdev.attributes(),
+                    Some(ObjectAttributes::OSDevice(attr)) if attr.device_type() == ty
+                )
+            });
+            compare_object_sets(topology.os_devices_with_type(ty), expected).unwrap();
+        }
+        compare_object_sets(
+            topology.network_devices().map(|dev| dev.object()),
+            topology.os_devices_with_type(OSDeviceType::Network),
+        )
+        .unwrap();
+        compare_object_sets(
+            topology.block_devices().map(|dev| dev.object()),
+            topology.os_devices_with_type(OSDeviceType::Storage),
+        )
+        .unwrap();
+        compare_object_sets(
+            topology.gpu_devices().map(|dev| dev.object()),
+            topology.os_devices_with_type(OSDeviceType::GPU),
+        )
+        .unwrap();
+        compare_object_sets(
+            topology.dma_devices(),
+            topology.os_devices_with_type(OSDeviceType::DMA),
+        )
+        .unwrap();
+        for network in topology.network_devices() {
+            let object = network.object();
+            assert_eq!(
+                network.address(),
+                object.info("Address").and_then(|s| s.to_str().ok())
+            );
+        }
+        for block in topology.block_devices() {
+            if let Some((major, minor)) = block.linux_device_id() {
+                let info = block.object().info("LinuxDeviceID").unwrap();
+                assert_eq!(info.to_str().unwrap(), format!("{major}:{minor}"));
+            }
+        }
+    }
+
+    // --- Find OS devices by name ---
+
+    /// Check that [`Topology::find_osdev_by_name()`] finds the first OS device with a name
+    fn check_find_osdev_by_name(name: &[u8]) -> Result<(), TestCaseError> {
+        let topology = Topology::test_instance();
+        let expected = topology.os_devices().find(|obj| {
+            obj.name()
+                .is_some_and(|obj_name| obj_name.to_bytes() == name)
+        });
+        let result = topology.find_osdev_by_name(name);
+        prop_assert_eq!(result.is_some(), expected.is_some());
+        if let (Some(result), Some(expected)) = (result, expected) {
+            prop_assert!(ptr::eq(result, expected));
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn valid_find_osdev_by_name() -> Result<(), TestCaseError> {
+        for osdev in Topology::test_instance().os_devices() {
+            if let Some(name) = osdev.name() {
+                check_find_osdev_by_name(name.to_bytes())?;
+            }
+        }
+        Ok(())
+    }
+
+    proptest! {
+        #[test]
+        fn find_osdev_by_name(name in any_string()) {
+            check_find_osdev_by_name(name.as_bytes())?;
+        }
+    }
+
     // --- Find PCI devices by address ---
 
     /// PCI device address
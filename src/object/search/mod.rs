@@ -17,9 +17,18 @@ use crate::{
 #[allow(unused)]
 #[cfg(test)]
 use similar_asserts::assert_eq;
-use std::{fmt::Debug, iter::FusedIterator, ops::Deref, ptr};
+use std::{
+    collections::HashMap,
+    fmt::{self, Debug},
+    iter::FusedIterator,
+    ops::Deref,
+    ptr::{self, NonNull},
+};
 use thiserror::Error;
 
+// Re-export the I/O search types
+pub use self::io::{BlockDevice, GPUDevice, NetworkDevice, PCIDeviceLocality};
+
 /// # Finding other objects
 //
 // --- Implementation details ---
@@ -30,17 +39,31 @@ use thiserror::Error;
 impl Topology {
     /// Get the object of type [`ObjectType::PU`] with the specified OS index
     ///
-    /// If you want to convert an entire CPU set into the PU objects it
-    /// contains, using [`pus_from_cpuset()`] will be more efficient than
-    /// repeatedly calling this function with every OS index from the [`CpuSet`].
+    /// This is a constant-time lookup into the map returned by
+    /// [`pu_index()`](Self::pu_index()). If you want to convert an entire CPU
+    /// set into the PU objects it contains, using [`pus_from_cpuset()`] will
+    /// be more efficient than repeatedly calling this function with every OS
+    /// index from the [`CpuSet`].
     ///
     /// Requires [`DiscoverySupport::pu_count()`].
     ///
     /// [`pus_from_cpuset()`]: Self::pus_from_cpuset()
     #[doc(alias = "hwloc_get_pu_obj_by_os_index")]
     pub fn pu_with_os_index(&self, os_index: usize) -> Option<&TopologyObject> {
-        self.objs_and_os_indices(ObjectType::PU)
-            .find_map(|(pu, pu_os_index)| (pu_os_index == os_index).then_some(pu))
+        self.pu_index().get(os_index)
+    }
+
+    /// Map from OS index to [`ObjectType::PU`] objects
+    ///
+    /// The map is built the first time this method is called, and rebuilt
+    /// after the topology is edited, so that subsequent calls (e.g. per-task
+    /// lookups in a scheduler) are cheap.
+    ///
+    /// Requires [`DiscoverySupport::pu_count()`].
+    ///
+    /// This functionality is specific to the Rust bindings.
+    pub fn pu_index(&self) -> OsIndexMap<'_> {
+        OsIndexMap(self.indexed_objects_by_os_index(ObjectType::PU))
     }
 
     /// Get the objects of type [`ObjectType::PU`] covered by the specified cpuset
@@ -60,10 +83,11 @@ impl Topology {
 
     /// Get the object of type [`NUMANode`] with the specified OS index
     ///
-    /// If you want to convert an entire [`NodeSet` into the [`NUMANode`]
-    /// objects it contains, using [`nodes_from_nodeset()`] will be more
-    /// efficient than repeatedly calling this function with every OS index from
-    /// the [`NodeSet`].
+    /// This is a constant-time lookup into the map returned by
+    /// [`node_index()`](Self::node_index()). If you want to convert an entire
+    /// [`NodeSet`] into the [`NUMANode`] objects it contains, using
+    /// [`nodes_from_nodeset()`] will be more efficient than repeatedly calling
+    /// this function with every OS index from the [`NodeSet`].
     ///
     /// Requires [`DiscoverySupport::numa_count()`].
     ///
@@ -71,8 +95,18 @@ impl Topology {
     /// [`NUMANode`]: ObjectType::NUMANode
     #[doc(alias = "hwloc_get_numanode_obj_by_os_index")]
     pub fn node_with_os_index(&self, os_index: usize) -> Option<&TopologyObject> {
-        self.objs_and_os_indices(ObjectType::NUMANode)
-            .find_map(|(node, node_os_index)| (node_os_index == os_index).then_some(node))
+        self.node_index().get(os_index)
+    }
+
+    /// Map from OS index to [`ObjectType::NUMANode`] objects
+    ///
+    /// Like [`pu_index()`](Self::pu_index()), but for NUMA nodes.
+    ///
+    /// Requires [`DiscoverySupport::numa_count()`].
+    ///
+    /// This functionality is specific to the Rust bindings.
+    pub fn node_index(&self) -> OsIndexMap<'_> {
+        OsIndexMap(self.indexed_objects_by_os_index(ObjectType::NUMANode))
     }
 
     /// Get the objects of type [`ObjectType::NUMANode`] covered by the
@@ -139,54 +173,12 @@ impl Topology {
         &'result self,
         obj: &'result TopologyObject,
     ) -> Result<impl Iterator<Item = &TopologyObject> + Clone + 'result, ClosestObjectsError> {
-        // Validate input object
-        if !self.contains(obj) {
-            return Err(ClosestObjectsError::ForeignObject(obj.into()));
-        }
-        let obj_cpuset = obj
-            .cpuset()
-            .ok_or_else(|| ClosestObjectsError::MissingCpuSet(obj.into()))?;
-
-        /// Assert that an object has a cpuset, return both
-        fn obj_and_cpuset<'obj>(
-            obj: &'obj TopologyObject,
-            error: &str,
-        ) -> (&'obj TopologyObject, BitmapRef<'obj, CpuSet>) {
-            (obj, obj.cpuset().expect(error))
-        }
-
-        /// Find the first ancestor of an object that knows about more objects
-        /// than that object (if any), and return it along with its cpuset
-        fn find_larger_parent<'obj>(
-            known_obj: &'obj TopologyObject,
-            known_cpuset: &CpuSet,
-        ) -> Option<(&'obj TopologyObject, BitmapRef<'obj, CpuSet>)> {
-            known_obj
-                .ancestors()
-                .map(|ancestor| {
-                    obj_and_cpuset(
-                        ancestor,
-                        "Ancestors of an object with a cpuset should have a cpuset",
-                    )
-                })
-                .find(|(_ancestor, ancestor_cpuset)| ancestor_cpuset != known_cpuset)
-        }
-        let mut ancestor_and_cpuset = find_larger_parent(obj, &obj_cpuset);
-
         // Prepare to jointly iterate over cousins and their cpusets
         // On each pass, we're going to find which cousins are covered by the
         // current ancestor, keeping the other cousins around to iterate over
         // them again during the next pass with a higher-level ancestor.
-        let mut cousins_and_cpusets = self
-            .objects_at_depth(obj.depth())
-            .filter(|cousin_or_obj| !ptr::eq(*cousin_or_obj, obj))
-            .map(|cousin| {
-                obj_and_cpuset(
-                    cousin,
-                    "Cousins of an object with a cpuset should have a cpuset",
-                )
-            })
-            .collect::<Vec<_>>();
+        let (mut ancestor_and_cpuset, mut cousins_and_cpusets) =
+            self.closest_objects_search(obj)?;
         let mut next_cousins_and_cpusets = Vec::new();
 
         // Emit the final iterator
@@ -212,6 +204,113 @@ impl Topology {
         }))
     }
 
+    /// Enumerate objects at the same depth as `obj`, grouped by increasing
+    /// physical distance
+    ///
+    /// This works like [`objects_closest_to()`], but instead of a flat list of
+    /// objects, it yields one [`ProximityTier`] per common ancestor of `obj`
+    /// and its cousins. This tells you where the locality "steps" are, e.g.
+    /// which cores share an L3 cache with the reference core, which other
+    /// cores are in the same package, and which cores are remote.
+    ///
+    /// Ancestors that do not bring any new cousin into consideration (e.g. an
+    /// L3 cache that is shared by a whole package) do not produce an empty
+    /// tier, so tier ranks are contiguous.
+    ///
+    /// This search may only be applied to objects that have a cpuset (normal
+    /// and memory objects) and belong to this topology.
+    ///
+    /// # Errors
+    ///
+    /// - [`ForeignObject`] if `obj` does not belong to this topology.
+    /// - [`MissingCpuSet`] if `obj` does not have a cpuset.
+    ///
+    /// [`ForeignObject`]: ClosestObjectsError::ForeignObject
+    /// [`MissingCpuSet`]: ClosestObjectsError::MissingCpuSet
+    /// [`objects_closest_to()`]: Self::objects_closest_to()
+    pub fn objects_by_proximity<'result>(
+        &'result self,
+        obj: &'result TopologyObject,
+    ) -> Result<impl Iterator<Item = ProximityTier<'result>> + Clone + 'result, ClosestObjectsError>
+    {
+        let (mut ancestor_and_cpuset, mut cousins_and_cpusets) =
+            self.closest_objects_search(obj)?;
+        let mut rank = 0;
+        Ok(std::iter::from_fn(move || {
+            while !cousins_and_cpusets.is_empty() {
+                // Split the remaining cousins into those that are covered by
+                // the current ancestor and those that are not
+                let (ancestor, ancestor_cpuset) = ancestor_and_cpuset.take()?;
+                let (covered, uncovered) = std::mem::take(&mut cousins_and_cpusets)
+                    .into_iter()
+                    .partition::<Vec<_>, _>(|(_cousin, cousin_cpuset)| {
+                        ancestor_cpuset.includes(&**cousin_cpuset)
+                    });
+                cousins_and_cpusets = uncovered;
+                ancestor_and_cpuset = find_larger_parent(ancestor, &ancestor_cpuset);
+
+                // Emit a tier if this ancestor covers new cousins
+                if !covered.is_empty() {
+                    let tier = ProximityTier {
+                        rank,
+                        common_ancestor: ancestor,
+                        objects: covered.into_iter().map(|(cousin, _)| cousin).collect(),
+                    };
+                    rank += 1;
+                    return Some(tier);
+                }
+            }
+            None
+        }))
+    }
+
+    /// Common setup of [`objects_closest_to()`] and [`objects_by_proximity()`]
+    ///
+    /// Validates the input object, then returns its first ancestor that
+    /// covers more objects than it does (if any) along with the cousins of the
+    /// input object, in logical index order. Ancestors and cousins come with
+    /// their cpuset.
+    ///
+    /// # Errors
+    ///
+    /// See [`objects_closest_to()`].
+    ///
+    /// [`objects_by_proximity()`]: Self::objects_by_proximity()
+    /// [`objects_closest_to()`]: Self::objects_closest_to()
+    #[allow(clippy::type_complexity)]
+    fn closest_objects_search<'result>(
+        &'result self,
+        obj: &'result TopologyObject,
+    ) -> Result<
+        (
+            Option<(&'result TopologyObject, BitmapRef<'result, CpuSet>)>,
+            Vec<(&'result TopologyObject, BitmapRef<'result, CpuSet>)>,
+        ),
+        ClosestObjectsError,
+    > {
+        // Validate input object
+        if !self.contains(obj) {
+            return Err(ClosestObjectsError::ForeignObject(obj.into()));
+        }
+        let obj_cpuset = obj
+            .cpuset()
+            .ok_or_else(|| ClosestObjectsError::MissingCpuSet(obj.into()))?;
+        let ancestor_and_cpuset = find_larger_parent(obj, &obj_cpuset);
+
+        // Collect cousins and their cpusets
+        let cousins_and_cpusets = self
+            .objects_at_depth(obj.depth())
+            .filter(|cousin_or_obj| !ptr::eq(*cousin_or_obj, obj))
+            .map(|cousin| {
+                obj_and_cpuset(
+                    cousin,
+                    "Cousins of an object with a cpuset should have a cpuset",
+                )
+            })
+            .collect::<Vec<_>>();
+        Ok((ancestor_and_cpuset, cousins_and_cpusets))
+    }
+
     /// Find an object via a parent->child chain specified by types and indices
     ///
     /// For example, if called with `&[(NUMANode, 0), (Package, 1), (Core, 2)]`,
@@ -359,9 +458,170 @@ impl Topology {
         //         - Output is bound to the lifetime of the topology it comes from
         Ok((!ptr.is_null()).then(|| unsafe { (&*ptr).as_newtype() }))
     }
+
+    /// Enumerate objects that have a certain textual info attribute
+    ///
+    /// This yields all objects of the topology, in the order of
+    /// [`Topology::objects()`], that have an info attribute whose name is
+    /// `key` and whose value is `value`, e.g. the [`Machine`] object with
+    /// `DMIProductName` set to a certain computer model.
+    ///
+    /// Keys and values can be specified either as strings or as raw bytes,
+    /// which allows looking up infos that are not valid UTF-8. Unlike
+    /// [`TopologyObject::info()`], all infos with a matching key are
+    /// considered, not just the first one.
+    ///
+    /// This performs a linear scan of the topology. If you need to do this
+    /// sort of search many times, consider building a `HashMap` from the
+    /// infos you are interested in for increased lookup efficiency.
+    ///
+    /// [`Machine`]: ObjectType::Machine
+    pub fn objects_with_info(
+        &self,
+        key: impl AsRef<[u8]>,
+        value: impl AsRef<[u8]>,
+    ) -> impl FusedIterator<Item = &TopologyObject> + Clone + '_ {
+        let key: Box<[u8]> = key.as_ref().into();
+        let value: Box<[u8]> = value.as_ref().into();
+        self.objects().filter(move |obj| {
+            obj.infos()
+                .iter()
+                .any(|info| info.name_bytes() == &key[..] && info.value_bytes() == &value[..])
+        })
+    }
+}
+
+/// Map from OS index to PU or NUMA node objects
+///
+/// Returned by [`Topology::pu_index()`] and [`Topology::node_index()`].
+//
+// --- Implementation details ---
+//
+// # Safety
+//
+// As a type invariant, the map only contains pointers to objects of a topology
+// that is borrowed for 'topology.
+#[derive(Copy, Clone)]
+pub struct OsIndexMap<'topology>(&'topology HashMap<usize, NonNull<TopologyObject>>);
+//
+impl<'topology> OsIndexMap<'topology> {
+    /// Object with the specified OS index, if any
+    pub fn get(&self, os_index: usize) -> Option<&'topology TopologyObject> {
+        self.0.get(&os_index).map(|ptr| {
+            // SAFETY: Per type invariant
+            unsafe { ptr.as_ref() }
+        })
+    }
+
+    /// Truth that an object with the specified OS index exists
+    pub fn contains(&self, os_index: usize) -> bool {
+        self.0.contains_key(&os_index)
+    }
+
+    /// Number of objects in the map
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    /// Truth that the map contains no object
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// Iterate over `(OS index, object)` pairs in arbitrary order
+    pub fn iter(
+        &self,
+    ) -> impl ExactSizeIterator<Item = (usize, &'topology TopologyObject)> + Clone + FusedIterator
+    {
+        self.0.iter().map(|(os_index, ptr)| {
+            // SAFETY: Per type invariant
+            (*os_index, unsafe { ptr.as_ref() })
+        })
+    }
+}
+//
+impl Debug for OsIndexMap<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_map().entries(self.iter()).finish()
+    }
+}
+//
+// SAFETY: Only exposes shared references to objects, and TopologyObject is Sync
+unsafe impl Send for OsIndexMap<'_> {}
+//
+// SAFETY: Only exposes shared references to objects, and TopologyObject is Sync
+unsafe impl Sync for OsIndexMap<'_> {}
+
+/// Assert that an object has a cpuset, return both
+fn obj_and_cpuset<'obj>(
+    obj: &'obj TopologyObject,
+    error: &str,
+) -> (&'obj TopologyObject, BitmapRef<'obj, CpuSet>) {
+    (obj, obj.cpuset().expect(error))
+}
+
+/// Find the first ancestor of an object that knows about more objects than
+/// that object (if any), and return it along with its cpuset
+fn find_larger_parent<'obj>(
+    known_obj: &'obj TopologyObject,
+    known_cpuset: &CpuSet,
+) -> Option<(&'obj TopologyObject, BitmapRef<'obj, CpuSet>)> {
+    known_obj
+        .ancestors()
+        .map(|ancestor| {
+            obj_and_cpuset(
+                ancestor,
+                "Ancestors of an object with a cpuset should have a cpuset",
+            )
+        })
+        .find(|(_ancestor, ancestor_cpuset)| ancestor_cpuset != known_cpuset)
 }
 
-/// Error returned by [`Topology::objects_closest_to()`]
+/// Group of objects that are equally close to a reference object
+///
+/// Yielded by [`Topology::objects_by_proximity()`].
+#[derive(Clone, Debug)]
+pub struct ProximityTier<'topology> {
+    /// Rank of this tier, 0 being the closest
+    rank: usize,
+
+    /// Smallest ancestor that the reference object shares with these objects
+    common_ancestor: &'topology TopologyObject,
+
+    /// Objects from this tier, in logical index order
+    objects: Vec<&'topology TopologyObject>,
+}
+//
+impl<'topology> ProximityTier<'topology> {
+    /// Rank of this tier, starting at 0 for the closest objects
+    pub fn rank(&self) -> usize {
+        self.rank
+    }
+
+    /// Smallest ancestor that the reference object shares with the objects of
+    /// this tier
+    ///
+    /// Looking at the type of this object tells you what kind of locality the
+    /// objects of this tier share with the reference object, e.g. an L3 cache
+    /// or a package.
+    pub fn common_ancestor(&self) -> &'topology TopologyObject {
+        self.common_ancestor
+    }
+
+    /// Objects from this tier, in logical index order
+    pub fn objects(
+        &self,
+    ) -> impl DoubleEndedIterator<Item = &'topology TopologyObject>
+           + Clone
+           + ExactSizeIterator
+           + FusedIterator
+           + '_ {
+        self.objects.iter().copied()
+    }
+}
+
+/// Error returned by [`Topology::objects_closest_to()`] and
+/// [`Topology::objects_by_proximity()`]
 #[derive(Clone, Debug, Eq, Error, PartialEq)]
 pub enum ClosestObjectsError {
     /// Target object does not belong to this topology
@@ -434,8 +694,10 @@ impl<'topology> From<&'topology TopologyObject> for LocalObjectError {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::strategies::{any_object, topology_related_set};
+    use crate::strategies::{any_object, any_string, topology_related_set};
     use proptest::prelude::*;
+    #[allow(unused)]
+    use similar_asserts::assert_eq;
     use std::{
         collections::{BTreeMap, HashMap},
         sync::OnceLock,
@@ -516,6 +778,44 @@ mod tests {
             // Iteration should end once all cousins have been seen
             prop_assert!(iterator.next().is_none());
         }
+
+        /// Test that [`Topology::objects_by_proximity()`] works as expected
+        #[test]
+        fn objects_by_proximity(obj in any_object()) {
+            // Errors should be the same as for objects_closest_to
+            let topology = Topology::test_instance();
+            let result = topology.objects_by_proximity(obj);
+            if let Err(closest_error) = topology.objects_closest_to(obj) {
+                prop_assert_eq!(result.err(), Some(closest_error));
+                return Ok(());
+            }
+
+            // Tiers should have contiguous ranks and increasingly far common
+            // ancestors, which must be the first common ancestor of obj and
+            // each of the tier's objects
+            let mut seen_cousins = HashMap::new();
+            let mut last_ancestor_depth = None;
+            for (expected_rank, tier) in result.unwrap().enumerate() {
+                prop_assert_eq!(tier.rank(), expected_rank);
+                let ancestor = tier.common_ancestor();
+                let ancestor_depth = ancestor.depth().expect_normal();
+                if let Some(last_depth) = last_ancestor_depth {
+                    prop_assert!(ancestor_depth < last_depth);
+                }
+                last_ancestor_depth = Some(ancestor_depth);
+                prop_assert_ne!(tier.objects().len(), 0);
+                for cousin in tier.objects() {
+                    prop_assert!(ptr::eq(obj.first_common_ancestor(cousin).unwrap(), ancestor));
+                    prop_assert!(seen_cousins.insert(cousin.global_persistent_index(), cousin).is_none());
+                }
+            }
+
+            // All cousins should have been covered
+            prop_assert_eq!(
+                seen_cousins.len(),
+                topology.objects_at_depth(obj.depth()).len() - 1
+            );
+        }
     }
 
     // --- Querying stuff by OS index ---
@@ -592,6 +892,25 @@ mod tests {
         }
     }
 
+    /// Check the maps returned by [`Topology::pu_index()`] and
+    /// [`Topology::node_index()`]
+    #[test]
+    fn os_index_maps() {
+        let topology = Topology::test_instance();
+        for (map, expected) in [
+            (topology.pu_index(), os_index_to_pu()),
+            (topology.node_index(), os_index_to_node()),
+        ] {
+            assert_eq!(map.len(), expected.len());
+            assert_eq!(map.is_empty(), expected.is_empty());
+            assert_eq!(map.iter().len(), expected.len());
+            for (os_index, obj) in map.iter() {
+                assert!(map.contains(os_index));
+                assert!(ptr::eq(obj, expected[&os_index]));
+            }
+        }
+    }
+
     proptest! {
         /// Stochastic test for possibly-nonexistent NUMA node OS indices
         fn any_node_with_os_index(os_index: usize) {
@@ -986,4 +1305,43 @@ mod tests {
             Ok(false)
         }
     }
+
+    // --- Searching objects by info ---
+
+    /// Check that [`Topology::objects_with_info()`] yields the objects with a certain info
+    fn check_objects_with_info(key: &[u8], value: &[u8]) -> Result<(), TestCaseError> {
+        let topology = Topology::test_instance();
+        let expected = topology.objects().filter(|obj| {
+            obj.infos()
+                .iter()
+                .any(|info| info.name_bytes() == key && info.value_bytes() == value)
+        });
+        let result = topology.objects_with_info(key, value).collect::<Vec<_>>();
+        let expected = expected.collect::<Vec<_>>();
+        prop_assert_eq!(result.len(), expected.len());
+        for (result, expected) in result.into_iter().zip(expected) {
+            prop_assert!(ptr::eq(result, expected));
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn valid_objects_with_info() -> Result<(), TestCaseError> {
+        for obj in Topology::test_objects() {
+            for info in obj.infos() {
+                check_objects_with_info(info.name_bytes(), info.value_bytes())?;
+                prop_assert!(Topology::test_instance()
+                    .objects_with_info(info.name_bytes(), info.value_bytes())
+                    .any(|candidate| ptr::eq(candidate, *obj)));
+            }
+        }
+        Ok(())
+    }
+
+    proptest! {
+        #[test]
+        fn objects_with_info(key in any_string(), value in any_string()) {
+            check_objects_with_info(key.as_bytes(), value.as_bytes())?;
+        }
+    }
 }
@@ -0,0 +1,414 @@
+//! Whole-tree traversal
+//!
+//! Most of the [`Topology`] API is organized around depths and object types.
+//! When you need to walk the full object tree instead, e.g. to dump it or
+//! analyze it, the breadth-first and depth-first iterators defined in this
+//! module will enumerate every object of a subtree (normal, memory, I/O and
+//! Misc objects alike) without requiring a hand-rolled recursive walk.
+//!
+//! Objects can be filtered using the standard [`Iterator::filter()`] adapter.
+//! To avoid visiting the descendants of an object altogether, call
+//! `skip_subtree()` on the iterator right after this object was yielded.
+
+use super::{types::ObjectType, TopologyObject};
+use crate::{cpu::cpuset::CpuSet, topology::Topology};
+#[allow(unused)]
+#[cfg(test)]
+use similar_asserts::assert_eq;
+use std::{collections::VecDeque, iter::FusedIterator, ops::Deref};
+
+/// # Whole-tree traversal
+///
+/// This functionality is unique to the Rust hwloc bindings
+impl Topology {
+    /// Iterate over all objects of the topology in breadth-first order
+    ///
+    /// See [`TopologyObject::breadth_first()`] for more information.
+    pub fn objects_breadth_first(&self) -> BreadthFirst<'_> {
+        self.root_object().breadth_first()
+    }
+
+    /// Iterate over all objects of the topology in depth-first pre-order
+    ///
+    /// See [`TopologyObject::depth_first()`] for more information.
+    pub fn objects_depth_first(&self) -> DepthFirst<'_> {
+        self.root_object().depth_first()
+    }
+}
+
+/// # Subtree traversal
+///
+/// This functionality is unique to the Rust hwloc bindings
+impl TopologyObject {
+    /// Iterate over this object and its descendants in breadth-first order
+    ///
+    /// This object is yielded first, then its children, then its
+    /// grandchildren, and so on. Children of a given object are visited in
+    /// [`TopologyObject::all_children()`] order, i.e. normal children first,
+    /// then memory, I/O and Misc children.
+    pub fn breadth_first(&self) -> BreadthFirst<'_> {
+        BreadthFirst {
+            queue: VecDeque::from([self]),
+            last: None,
+        }
+    }
+
+    /// Iterate over this object and its descendants in depth-first pre-order
+    ///
+    /// Each object is yielded before its descendants, and the full subtree of
+    /// an object is enumerated before moving to its next sibling. Children of
+    /// a given object are visited in [`TopologyObject::all_children()`] order,
+    /// i.e. normal children first, then memory, I/O and Misc children.
+    pub fn depth_first(&self) -> DepthFirst<'_> {
+        DepthFirst {
+            stack: vec![self],
+            last: None,
+        }
+    }
+
+    /// Iterate over the descendants of this object in depth-first pre-order
+    ///
+    /// This is like [`TopologyObject::depth_first()`], except this object
+    /// itself is not yielded.
+    pub fn descendants(&self) -> DepthFirst<'_> {
+        let mut stack = self.all_children().collect::<Vec<_>>();
+        stack.reverse();
+        DepthFirst { stack, last: None }
+    }
+
+    /// Iterate over the descendants of this object that have a certain type
+    ///
+    /// Objects are yielded in depth-first pre-order. For normal and memory
+    /// objects, this is the same as their logical order, as long as all
+    /// objects of type `ty` are at the same depth.
+    pub fn descendants_with_type(
+        &self,
+        ty: ObjectType,
+    ) -> impl FusedIterator<Item = &Self> + Clone {
+        self.descendants()
+            .filter(move |obj| obj.object_type() == ty)
+    }
+
+    /// Iterate over the descendants of this object that are inside of the
+    /// cpuset `set`
+    ///
+    /// `set` can be a `&'_ CpuSet` or a `BitmapRef<'_, CpuSet>`.
+    ///
+    /// Objects are yielded in depth-first pre-order. See
+    /// [`TopologyObject::is_inside_cpuset()`] for a definition of which
+    /// objects are considered to be inside of `set`. Since I/O and Misc
+    /// objects do not have a cpuset, they are never yielded.
+    pub fn descendants_inside_cpuset(
+        &self,
+        set: impl Deref<Target = CpuSet>,
+    ) -> impl FusedIterator<Item = &Self> + Clone {
+        let set = CpuSet::clone(&set);
+        self.descendants()
+            .filter(move |obj| obj.is_inside_cpuset(&set))
+    }
+
+    /// Iterate over the PCI bridges and devices below this object
+    ///
+    /// This is meant to be called on a bridge, e.g. one of the
+    /// [`Topology::host_bridges()`], and walks the PCI tree below it in
+    /// depth-first pre-order. Since hwloc sorts the I/O children of each
+    /// bridge by PCI bus ID, objects are yielded in bus order. OS devices and
+    /// Misc objects are not yielded, and neither is this object.
+    ///
+    /// If called on a normal or memory object, this walks the PCI trees that
+    /// are directly attached to this object, but not those that are attached
+    /// to its descendants.
+    pub fn pci_subtree(&self) -> impl FusedIterator<Item = &Self> + Clone {
+        /// Truth that an object belongs to the PCI hierarchy
+        fn is_pci(obj: &TopologyObject) -> bool {
+            matches!(
+                obj.object_type(),
+                ObjectType::Bridge | ObjectType::PCIDevice
+            )
+        }
+        let mut stack = self
+            .io_children()
+            .filter(|child| is_pci(child))
+            .collect::<Vec<_>>();
+        stack.reverse();
+        std::iter::from_fn(move || {
+            let next = stack.pop()?;
+            let first_child_idx = stack.len();
+            stack.extend(next.io_children().filter(|child| is_pci(child)));
+            stack[first_child_idx..].reverse();
+            Some(next)
+        })
+        .fuse()
+    }
+}
+
+/// Breadth-first iterator over a subtree of topology objects
+///
+/// Produced by [`TopologyObject::breadth_first()`] and
+/// [`Topology::objects_breadth_first()`].
+//
+// --- Implementation details ---
+//
+// Children of the last yielded object are only enqueued on the next call to
+// `next()`, which gives `skip_subtree()` a chance to prevent it.
+#[derive(Clone, Debug)]
+pub struct BreadthFirst<'topology> {
+    /// Objects that remain to be yielded
+    queue: VecDeque<&'topology TopologyObject>,
+
+    /// Last yielded object, whose children have not been enqueued yet
+    last: Option<&'topology TopologyObject>,
+}
+//
+impl BreadthFirst<'_> {
+    /// Do not visit the descendants of the last yielded object
+    ///
+    /// Has no effect if no object has been yielded yet.
+    pub fn skip_subtree(&mut self) {
+        self.last = None;
+    }
+}
+//
+impl<'topology> Iterator for BreadthFirst<'topology> {
+    type Item = &'topology TopologyObject;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if let Some(last) = self.last.take() {
+            self.queue.extend(last.all_children());
+        }
+        let next = self.queue.pop_front()?;
+        self.last = Some(next);
+        Some(next)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let pending_children = self.last.map_or(0, num_children);
+        (self.queue.len() + pending_children, None)
+    }
+}
+//
+impl FusedIterator for BreadthFirst<'_> {}
+
+/// Depth-first pre-order iterator over a subtree of topology objects
+///
+/// Produced by [`TopologyObject::depth_first()`] and
+/// [`Topology::objects_depth_first()`].
+//
+// --- Implementation details ---
+//
+// Children of the last yielded object are only pushed on the next call to
+// `next()`, which gives `skip_subtree()` a chance to prevent it.
+#[derive(Clone, Debug)]
+pub struct DepthFirst<'topology> {
+    /// Objects that remain to be yielded, next object at the top
+    stack: Vec<&'topology TopologyObject>,
+
+    /// Last yielded object, whose children have not been pushed yet
+    last: Option<&'topology TopologyObject>,
+}
+//
+impl DepthFirst<'_> {
+    /// Do not visit the descendants of the last yielded object
+    ///
+    /// Has no effect if no object has been yielded yet.
+    pub fn skip_subtree(&mut self) {
+        self.last = None;
+    }
+}
+//
+impl<'topology> Iterator for DepthFirst<'topology> {
+    type Item = &'topology TopologyObject;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if let Some(last) = self.last.take() {
+            // Push children in reverse order so the first child is on top
+            let first_child_idx = self.stack.len();
+            self.stack.extend(last.all_children());
+            self.stack[first_child_idx..].reverse();
+        }
+        let next = self.stack.pop()?;
+        self.last = Some(next);
+        Some(next)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let pending_children = self.last.map_or(0, num_children);
+        (self.stack.len() + pending_children, None)
+    }
+}
+//
+impl FusedIterator for DepthFirst<'_> {}
+
+/// Total number of children of an object, of any kind
+fn num_children(obj: &TopologyObject) -> usize {
+    obj.normal_arity() + obj.memory_arity() + obj.io_arity() + obj.misc_arity()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{object::lists::tests::compare_object_sets, strategies::any_object};
+    use proptest::prelude::*;
+    use static_assertions::{assert_impl_all, assert_not_impl_any};
+    use std::{
+        fmt::{
+            self, Binary, Debug, Display, LowerExp, LowerHex, Octal, Pointer, UpperExp, UpperHex,
+        },
+        hash::Hash,
+        io::{self, Read},
+        ops::Deref,
+        panic::UnwindSafe,
+        ptr,
+    };
+
+    // Check that public types in this module keep implementing all expected
+    // traits, in the interest of detecting future semver-breaking changes
+    assert_impl_all!(BreadthFirst<'static>:
+        Clone, Debug, FusedIterator, Sized, Sync, Unpin, UnwindSafe
+    );
+    assert_not_impl_any!(BreadthFirst<'static>:
+        Binary, Copy, Default, Deref, Display, Drop, Hash, LowerExp, LowerHex,
+        Octal, PartialEq, Pointer, Read, UpperExp, UpperHex, fmt::Write,
+        io::Write
+    );
+    assert_impl_all!(DepthFirst<'static>:
+        Clone, Debug, FusedIterator, Sized, Sync, Unpin, UnwindSafe
+    );
+    assert_not_impl_any!(DepthFirst<'static>:
+        Binary, Copy, Default, Deref, Display, Drop, Hash, LowerExp, LowerHex,
+        Octal, PartialEq, Pointer, Read, UpperExp, UpperHex, fmt::Write,
+        io::Write
+    );
+
+    /// Number of ancestors of an object
+    fn tree_depth(obj: &TopologyObject) -> usize {
+        obj.ancestors().count()
+    }
+
+    /// Check that two iterators yield the same objects in the same order
+    fn check_same_objects<'a>(
+        actual: impl IntoIterator<Item = &'a TopologyObject>,
+        expected: impl IntoIterator<Item = &'a TopologyObject>,
+    ) -> Result<(), TestCaseError> {
+        let actual = actual.into_iter().collect::<Vec<_>>();
+        let expected = expected.into_iter().collect::<Vec<_>>();
+        prop_assert_eq!(actual.len(), expected.len());
+        for (actual, expected) in actual.into_iter().zip(expected) {
+            prop_assert!(ptr::eq(actual, expected));
+        }
+        Ok(())
+    }
+
+    /// Whole-topology traversals should yield every object exactly once
+    #[test]
+    fn whole_topology() {
+        let topology = Topology::test_instance();
+        compare_object_sets(topology.objects_breadth_first(), topology.objects()).unwrap();
+        compare_object_sets(topology.objects_depth_first(), topology.objects()).unwrap();
+    }
+
+    proptest! {
+        /// Subtree traversals should yield the subtree root and its descendants
+        #[test]
+        fn subtree(obj in any_object()) {
+            let topology = if Topology::test_objects().iter().any(|other| ptr::eq(obj, *other)) {
+                Topology::test_instance()
+            } else {
+                Topology::foreign_instance()
+            };
+            let expected = || {
+                topology
+                    .objects()
+                    .filter(|other| ptr::eq(*other, obj) || other.is_in_subtree(obj))
+            };
+            compare_object_sets(obj.breadth_first(), expected())?;
+            compare_object_sets(obj.depth_first(), expected())?;
+        }
+
+        /// Descendant iterators should yield the subtree minus its root
+        #[test]
+        fn descendants(obj in any_object(), ty: ObjectType, set: CpuSet) {
+            let descendants = obj.descendants().collect::<Vec<_>>();
+            check_same_objects(descendants.clone(), obj.depth_first().skip(1))?;
+            check_same_objects(
+                obj.descendants_with_type(ty),
+                descendants.iter().copied().filter(|desc| desc.object_type() == ty),
+            )?;
+            check_same_objects(
+                obj.descendants_inside_cpuset(&set),
+                descendants.into_iter().filter(|desc| desc.is_inside_cpuset(&set)),
+            )?;
+        }
+
+        /// PCI subtree traversal should yield the PCI descendants of I/O
+        /// objects in depth-first order
+        #[test]
+        fn pci_subtree(obj in any_object()) {
+            let pci_subtree = obj.pci_subtree().collect::<Vec<_>>();
+            prop_assert!(pci_subtree.iter().all(|desc| matches!(
+                desc.object_type(),
+                ObjectType::Bridge | ObjectType::PCIDevice
+            )));
+            if obj.object_type().is_io() {
+                check_same_objects(
+                    pci_subtree,
+                    obj.descendants().filter(|desc| matches!(
+                        desc.object_type(),
+                        ObjectType::Bridge | ObjectType::PCIDevice
+                    )),
+                )?;
+            }
+        }
+
+        /// Breadth-first traversal should never go back up the tree
+        #[test]
+        fn breadth_first_order(obj in any_object()) {
+            let depths = obj.breadth_first().map(tree_depth).collect::<Vec<_>>();
+            prop_assert!(depths.windows(2).all(|pair| pair[0] <= pair[1]));
+        }
+
+        /// Depth-first traversal should yield each object right after either
+        /// its parent or a descendant of its previous sibling
+        #[test]
+        fn depth_first_order(obj in any_object()) {
+            let mut iter = obj.depth_first();
+            prop_assert!(ptr::eq(iter.next().unwrap(), obj));
+            let mut visited = vec![obj];
+            for next in iter {
+                let parent = next.parent().unwrap();
+                while !ptr::eq(*visited.last().unwrap(), parent) {
+                    visited.pop();
+                    prop_assert!(!visited.is_empty());
+                }
+                visited.push(next);
+            }
+        }
+
+        /// Skipping subtrees should skip all descendants
+        #[test]
+        fn skip_subtree(obj in any_object()) {
+            let mut bfs = obj.breadth_first();
+            prop_assert!(ptr::eq(bfs.next().unwrap(), obj));
+            bfs.skip_subtree();
+            prop_assert!(bfs.next().is_none());
+
+            let mut dfs = obj.depth_first();
+            prop_assert!(ptr::eq(dfs.next().unwrap(), obj));
+            dfs.skip_subtree();
+            prop_assert!(dfs.next().is_none());
+
+            // Skipping the subtree of each child should only yield children
+            let mut dfs = obj.depth_first();
+            dfs.next();
+            let mut children = Vec::new();
+            while let Some(child) = dfs.next() {
+                children.push(child);
+                dfs.skip_subtree();
+            }
+            prop_assert_eq!(children.len(), obj.all_children().count());
+            for (child, expected) in children.into_iter().zip(obj.all_children()) {
+                prop_assert!(ptr::eq(child, expected));
+            }
+        }
+    }
+}
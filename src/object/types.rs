@@ -8,7 +8,6 @@
 // - Enums: https://hwloc.readthedocs.io/en/v2.9/group__hwlocality__object__types.html
 // - Kinds: https://hwloc.readthedocs.io/en/v2.9/group__hwlocality__helper__types.html
 
-use crate::errors;
 #[cfg(doc)]
 use crate::{
     cpu::cpuset::CpuSet,
@@ -19,25 +18,38 @@ use crate::{
         support::DiscoverySupport,
     },
 };
+use crate::{
+    errors,
+    ffi::{int, string::LibcString},
+};
 use derive_more::Display;
 use enum_iterator::Sequence;
 use hwlocality_sys::{
-    hwloc_obj_type_t, HWLOC_OBJ_BRIDGE, HWLOC_OBJ_BRIDGE_HOST, HWLOC_OBJ_BRIDGE_PCI,
-    HWLOC_OBJ_CACHE_DATA, HWLOC_OBJ_CACHE_INSTRUCTION, HWLOC_OBJ_CACHE_UNIFIED, HWLOC_OBJ_CORE,
-    HWLOC_OBJ_GROUP, HWLOC_OBJ_L1CACHE, HWLOC_OBJ_L1ICACHE, HWLOC_OBJ_L2CACHE, HWLOC_OBJ_L2ICACHE,
-    HWLOC_OBJ_L3CACHE, HWLOC_OBJ_L3ICACHE, HWLOC_OBJ_L4CACHE, HWLOC_OBJ_L5CACHE, HWLOC_OBJ_MACHINE,
-    HWLOC_OBJ_MISC, HWLOC_OBJ_NUMANODE, HWLOC_OBJ_OSDEV_COPROC, HWLOC_OBJ_OSDEV_DMA,
-    HWLOC_OBJ_OSDEV_GPU, HWLOC_OBJ_OSDEV_NETWORK, HWLOC_OBJ_OSDEV_OPENFABRICS,
-    HWLOC_OBJ_OSDEV_STORAGE, HWLOC_OBJ_OS_DEVICE, HWLOC_OBJ_PACKAGE, HWLOC_OBJ_PCI_DEVICE,
-    HWLOC_OBJ_PU, HWLOC_TYPE_UNORDERED,
+    hwloc_obj_attr_u, hwloc_obj_type_t, HWLOC_OBJ_BRIDGE, HWLOC_OBJ_BRIDGE_HOST,
+    HWLOC_OBJ_BRIDGE_PCI, HWLOC_OBJ_CACHE_DATA, HWLOC_OBJ_CACHE_INSTRUCTION,
+    HWLOC_OBJ_CACHE_UNIFIED, HWLOC_OBJ_CORE, HWLOC_OBJ_GROUP, HWLOC_OBJ_L1CACHE,
+    HWLOC_OBJ_L1ICACHE, HWLOC_OBJ_L2CACHE, HWLOC_OBJ_L2ICACHE, HWLOC_OBJ_L3CACHE,
+    HWLOC_OBJ_L3ICACHE, HWLOC_OBJ_L4CACHE, HWLOC_OBJ_L5CACHE, HWLOC_OBJ_MACHINE, HWLOC_OBJ_MISC,
+    HWLOC_OBJ_NUMANODE, HWLOC_OBJ_OSDEV_COPROC, HWLOC_OBJ_OSDEV_DMA, HWLOC_OBJ_OSDEV_GPU,
+    HWLOC_OBJ_OSDEV_NETWORK, HWLOC_OBJ_OSDEV_OPENFABRICS, HWLOC_OBJ_OSDEV_STORAGE,
+    HWLOC_OBJ_OS_DEVICE, HWLOC_OBJ_PACKAGE, HWLOC_OBJ_PCI_DEVICE, HWLOC_OBJ_PU,
+    HWLOC_TYPE_UNORDERED,
 };
 #[cfg(feature = "hwloc-2_1_0")]
 use hwlocality_sys::{HWLOC_OBJ_DIE, HWLOC_OBJ_MEMCACHE};
 use num_enum::{IntoPrimitive, TryFromPrimitive};
+#[cfg(feature = "serde")]
+use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
 #[allow(unused)]
 #[cfg(test)]
 use similar_asserts::assert_eq;
-use std::{cmp::Ordering, ffi::c_int};
+use std::{
+    cmp::Ordering,
+    ffi::{c_int, c_uint, CStr},
+    fmt::{self, Display},
+    str::FromStr,
+};
+use thiserror::Error;
 
 /// Type of one side (upstream or downstream) of an I/O bridge
 #[derive(
@@ -57,6 +69,16 @@ pub enum BridgeType {
 }
 //
 crate::impl_arbitrary_for_sequence!(BridgeType);
+//
+impl FromStr for BridgeType {
+    type Err = ParseTypeError;
+
+    fn from_str(s: &str) -> Result<Self, ParseTypeError> {
+        parse_type_string(s, enum_iterator::all::<Self>(), |ty| {
+            std::iter::once(ty.to_string())
+        })
+    }
+}
 
 /// Cache type
 #[derive(
@@ -80,11 +102,27 @@ pub enum CacheType {
 }
 //
 crate::impl_arbitrary_for_sequence!(CacheType);
+//
+/// In addition to the strings emitted by the [`Display`] implementation, this
+/// also accepts the single-letter cache type suffixes that are used in hwloc
+/// type strings like `"L1d"`, namely `"u"`, `"d"` and `"i"`.
+impl FromStr for CacheType {
+    type Err = ParseTypeError;
+
+    fn from_str(s: &str) -> Result<Self, ParseTypeError> {
+        parse_type_string(s, enum_iterator::all::<Self>(), |ty| {
+            let letter = match ty {
+                Self::Unified => "u",
+                Self::Data => "d",
+                Self::Instruction => "i",
+            };
+            [ty.to_string(), letter.to_owned()].into_iter()
+        })
+    }
+}
 
 /// Type of a OS device
-#[derive(
-    Copy, Clone, Debug, Display, Eq, Hash, IntoPrimitive, TryFromPrimitive, PartialEq, Sequence,
-)]
+#[derive(Copy, Clone, Debug, Eq, Hash, IntoPrimitive, TryFromPrimitive, PartialEq, Sequence)]
 #[doc(alias = "hwloc_obj_osdev_type_e")]
 #[doc(alias = "hwloc_obj_osdev_type_t")]
 #[repr(u32)]
@@ -137,6 +175,51 @@ pub enum OSDeviceType {
 }
 //
 crate::impl_arbitrary_for_sequence!(OSDeviceType);
+//
+impl OSDeviceType {
+    /// Name of this OS device type in hwloc type strings
+    ///
+    /// This is the spelling used by hwloc's command-line tools, for example
+    /// `"Net"` for [`OSDeviceType::Network`]. It is also what the [`Display`]
+    /// implementation emits.
+    pub fn hwloc_name(self) -> &'static str {
+        match self {
+            #[cfg(not(feature = "hwloc-3_0_0"))]
+            Self::Storage => "Block",
+            #[cfg(feature = "hwloc-3_0_0")]
+            Self::Storage => "Storage",
+            Self::GPU => "GPU",
+            Self::Network => "Net",
+            Self::OpenFabrics => "OpenFabrics",
+            Self::DMA => "DMA",
+            Self::CoProcessor => "CoProc",
+            #[cfg(feature = "hwloc-3_0_0")]
+            Self::Memory => "Memory",
+        }
+    }
+}
+//
+/// OS device types are displayed using the same strings as in hwloc object
+/// type strings, as returned by [`OSDeviceType::hwloc_name()`].
+impl Display for OSDeviceType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.pad(self.hwloc_name())
+    }
+}
+//
+/// In addition to the strings emitted by the [`Display`] implementation, this
+/// also accepts the `"Block"` and `"Storage"` aliases of
+/// [`OSDeviceType::Storage`], which are used by different hwloc versions.
+impl FromStr for OSDeviceType {
+    type Err = ParseTypeError;
+
+    fn from_str(s: &str) -> Result<Self, ParseTypeError> {
+        parse_type_string(s, enum_iterator::all::<Self>(), |ty| {
+            let alias = (ty == Self::Storage).then(|| ["Block", "Storage"].map(str::to_owned));
+            std::iter::once(ty.to_string()).chain(alias.into_iter().flatten())
+        })
+    }
+}
 
 /// Represents the type of a [`TopologyObject`].
 ///
@@ -152,9 +235,7 @@ crate::impl_arbitrary_for_sequence!(OSDeviceType);
 /// It can also help to think of it as comparing the relative depths of each type, so
 /// a `ObjectType::Machine` will be smaller than a `ObjectType::PU` since the machine
 /// contains processing units.
-#[derive(
-    Copy, Clone, Debug, Display, Eq, Hash, IntoPrimitive, TryFromPrimitive, PartialEq, Sequence,
-)]
+#[derive(Copy, Clone, Debug, Eq, Hash, IntoPrimitive, TryFromPrimitive, PartialEq, Sequence)]
 #[doc(alias = "hwloc_obj_type_e")]
 #[doc(alias = "hwloc_obj_type_t")]
 #[non_exhaustive]
@@ -340,6 +421,36 @@ pub enum ObjectType {
 }
 //
 impl ObjectType {
+    /// hwloc's canonical name for this object type
+    ///
+    /// This is the type string used by hwloc's command-line tools, for
+    /// example `"L1iCache"` for [`ObjectType::L1ICache`] or `"PCIDev"` for
+    /// [`ObjectType::PCIDevice`]. It is also what the [`Display`]
+    /// implementation emits.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use hwlocality::object::types::ObjectType;
+    /// assert_eq!(ObjectType::PCIDevice.hwloc_name(), "PCIDev");
+    /// assert_eq!(ObjectType::PCIDevice.to_string(), "PCIDev");
+    /// ```
+    #[doc(alias = "hwloc_obj_type_string")]
+    pub fn hwloc_name(self) -> &'static str {
+        // SAFETY: - By construction, ObjectType only exposes values that map
+        //           into hwloc_obj_type_t values understood by the configured
+        //           version of hwloc, and build.rs checks that the active
+        //           version of hwloc is not older than that, so to_raw may only
+        //           generate valid hwloc_obj_type_t values for current hwloc
+        //         - hwloc_obj_type_string returns pointers to static strings
+        let s = unsafe {
+            let ptr = hwlocality_sys::hwloc_obj_type_string(self.to_raw());
+            assert!(!ptr.is_null(), "got null type string from hwloc");
+            CStr::from_ptr(ptr)
+        };
+        s.to_str().expect("hwloc type strings should be ASCII")
+    }
+
     /// Truth that this type is part of the normal hierarchy (not Memory, I/O or Misc)
     #[doc(alias = "hwloc_obj_type_is_normal")]
     pub fn is_normal(self) -> bool {
@@ -434,6 +545,71 @@ impl ObjectType {
         self.is_normal() || self.is_memory()
     }
 
+    /// Parse an object type string, along with the type attributes it specifies
+    ///
+    /// This accepts the same type strings as hwloc's command-line tools, such
+    /// as `"Core"`, `"L2"`, `"L3Cache"`, `"L1i"`, `"NUMANode"`, `"Group1"` or
+    /// `"PCI"`. Matching is case-insensitive and most type names can be
+    /// abbreviated as long as they remain unambiguous. Parsing stops at the
+    /// first character that cannot be part of a type name, so that for
+    /// example `"Core:2"` is parsed as [`ObjectType::Core`].
+    ///
+    /// # Errors
+    ///
+    /// - [`ParseTypeError`] if `s` is not a valid hwloc object type string
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use hwlocality::object::types::{
+    /// #     CacheType, ObjectType, TypeSpec, TypeSpecAttributes
+    /// # };
+    /// let spec = ObjectType::parse_with_attributes("L2d")?;
+    /// assert_eq!(
+    ///     spec,
+    ///     TypeSpec {
+    ///         object_type: ObjectType::L2Cache,
+    ///         attributes: Some(TypeSpecAttributes::Cache {
+    ///             depth: 2,
+    ///             cache_type: Some(CacheType::Data),
+    ///         }),
+    ///     }
+    /// );
+    /// #
+    /// # Ok::<(), eyre::Report>(())
+    /// ```
+    #[doc(alias = "hwloc_type_sscanf")]
+    pub fn parse_with_attributes(s: &str) -> Result<TypeSpec, ParseTypeError> {
+        let invalid = || ParseTypeError(s.to_owned());
+        let string = LibcString::new(s).map_err(|_| invalid())?;
+        let mut raw_type = hwloc_obj_type_t::MAX;
+        // SAFETY: hwloc_obj_attr_u is a union of plain old data types, for
+        //         which the all-zeroes bit pattern is a valid value
+        let mut raw_attributes: hwloc_obj_attr_u = unsafe { std::mem::zeroed() };
+        // SAFETY: - string is a valid C string (LibcString type invariant)
+        //         - raw_type and raw_attributes are valid out-pointers and
+        //           attrsize is the size of the raw_attributes buffer
+        let result = unsafe {
+            hwlocality_sys::hwloc_type_sscanf(
+                string.borrow(),
+                &mut raw_type,
+                &mut raw_attributes,
+                std::mem::size_of::<hwloc_obj_attr_u>(),
+            )
+        };
+        if result < 0 {
+            return Err(invalid());
+        }
+        let object_type = Self::try_from(raw_type).map_err(|_| invalid())?;
+        // SAFETY: hwloc_type_sscanf sets the union field that matches the
+        //         parsed object type, and other fields remain zeroed
+        let attributes = unsafe { TypeSpecAttributes::from_raw(object_type, &raw_attributes) };
+        Ok(TypeSpec {
+            object_type,
+            attributes,
+        })
+    }
+
     /// Convert to the internal representation used by hwloc
     ///
     /// Used to avoid Into/From type inference ambiguities.
@@ -467,6 +643,39 @@ impl ObjectType {
 //
 crate::impl_arbitrary_for_sequence!(ObjectType);
 //
+/// Object types are deserialized from hwloc's canonical type strings, as
+/// accepted by the [`FromStr`] implementation.
+#[cfg(feature = "serde")]
+impl<'de> Deserialize<'de> for ObjectType {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let s = String::deserialize(deserializer)?;
+        s.parse().map_err(de::Error::custom)
+    }
+}
+//
+/// Object types are displayed using hwloc's canonical type strings, as
+/// returned by [`ObjectType::hwloc_name()`].
+impl Display for ObjectType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.pad(self.hwloc_name())
+    }
+}
+//
+/// This parses hwloc's canonical type strings, as emitted by the [`Display`]
+/// implementation, in a case-insensitive manner. To accept the full range of
+/// type strings that hwloc's command-line tools accept, including
+/// abbreviations and type attributes, use
+/// [`ObjectType::parse_with_attributes()`] instead.
+impl FromStr for ObjectType {
+    type Err = ParseTypeError;
+
+    fn from_str(s: &str) -> Result<Self, ParseTypeError> {
+        parse_type_string(s, enum_iterator::all::<Self>(), |ty| {
+            std::iter::once(ty.to_string())
+        })
+    }
+}
+//
 impl PartialOrd for ObjectType {
     fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
         let result =
@@ -485,11 +694,118 @@ impl PartialOrd for ObjectType {
         }
     }
 }
+//
+/// Object types are serialized as hwloc's canonical type strings, as emitted
+/// by the [`Display`] implementation.
+#[cfg(feature = "serde")]
+impl Serialize for ObjectType {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+/// Object type specification, as parsed by [`ObjectType::parse_with_attributes()`]
+#[derive(Copy, Clone, Debug, Eq, Hash, PartialEq)]
+pub struct TypeSpec {
+    /// Object type
+    pub object_type: ObjectType,
+
+    /// Type-specific attributes that were specified by the type string, if any
+    pub attributes: Option<TypeSpecAttributes>,
+}
+
+/// Type-specific attributes that can be specified by an object type string
+#[derive(Copy, Clone, Debug, Eq, Hash, PartialEq)]
+pub enum TypeSpecAttributes {
+    /// CPU cache attributes
+    ///
+    /// These are specified by all CPU cache type strings, like `"L2"`.
+    Cache {
+        /// Depth of the cache (e.g. 1 for L1, 2 for L2, ...)
+        depth: usize,
+
+        /// Cache type, if specified (e.g. `"L2d"` for a data cache)
+        cache_type: Option<CacheType>,
+    },
+
+    /// Group depth, as specified by type strings like `"Group1"`
+    Group {
+        /// Depth of the group object
+        depth: usize,
+    },
+
+    /// Bridge type, as specified by `"HostBridge"` or `"PCIBridge"`
+    Bridge {
+        /// Upstream type of the bridge
+        upstream_type: BridgeType,
+
+        /// Downstream type of the bridge
+        downstream_type: BridgeType,
+    },
+
+    /// OS device type, as specified by type strings like `"GPU"`
+    OSDevice(OSDeviceType),
+}
+//
+impl TypeSpecAttributes {
+    /// Extract the attributes set by `hwloc_type_sscanf()`, if any
+    ///
+    /// # Safety
+    ///
+    /// `attributes` must have been zero-initialized, then filled in by a
+    /// successful `hwloc_type_sscanf()` call that returned `object_type`.
+    #[allow(clippy::wildcard_enum_match_arm)]
+    unsafe fn from_raw(object_type: ObjectType, attributes: &hwloc_obj_attr_u) -> Option<Self> {
+        // SAFETY: Per input precondition, the union field that matches
+        //         object_type is initialized, and other fields are zeroed
+        unsafe {
+            match object_type {
+                _ if object_type.is_cpu_cache() => Some(Self::Cache {
+                    depth: int::expect_usize(attributes.cache.depth),
+                    cache_type: attributes.cache.ty.try_into().ok(),
+                }),
+                ObjectType::Group => (attributes.group.depth != c_uint::MAX).then(|| Self::Group {
+                    depth: int::expect_usize(attributes.group.depth),
+                }),
+                ObjectType::Bridge => {
+                    let upstream_type = attributes.bridge.upstream_type.try_into().ok()?;
+                    let downstream_type = attributes.bridge.downstream_type.try_into().ok()?;
+                    Some(Self::Bridge {
+                        upstream_type,
+                        downstream_type,
+                    })
+                }
+                ObjectType::OSDevice => attributes.osdev.ty.try_into().ok().map(Self::OSDevice),
+                _ => None,
+            }
+        }
+    }
+}
+
+/// Error returned when a string is not a valid hwloc type string
+///
+/// This is used when parsing [`ObjectType`]s and their [`BridgeType`],
+/// [`CacheType`] and [`OSDeviceType`] subtypes.
+#[derive(Clone, Debug, Default, Eq, Error, Hash, PartialEq)]
+#[error("{0:?} is not a valid hwloc type string")]
+pub struct ParseTypeError(pub String);
+
+/// Parse a string into one of the `candidates`, using the strings emitted by
+/// `names` for each candidate, ignoring ASCII case
+fn parse_type_string<Type: Copy, Names: Iterator<Item = String>>(
+    s: &str,
+    mut candidates: impl Iterator<Item = Type>,
+    mut names: impl FnMut(Type) -> Names,
+) -> Result<Type, ParseTypeError> {
+    candidates
+        .find(|&ty| names(ty).any(|name| name.eq_ignore_ascii_case(s)))
+        .ok_or_else(|| ParseTypeError(s.to_owned()))
+}
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::strategies::test_object;
+    use crate::strategies::{any_string, test_object};
     use hwlocality_sys::{hwloc_obj_bridge_type_t, hwloc_obj_cache_type_t, hwloc_obj_osdev_type_t};
     use proptest::prelude::*;
     #[allow(unused)]
@@ -509,8 +825,8 @@ mod tests {
     // Check that public types in this module keep implementing all expected
     // traits, in the interest of detecting future semver-breaking changes
     assert_impl_all!(BridgeType:
-        Copy, Debug, Display, Hash, Into<hwloc_obj_bridge_type_t>, Sized, Sync,
-        TryFrom<hwloc_obj_bridge_type_t>, Unpin, UnwindSafe
+        Copy, Debug, Display, FromStr, Hash, Into<hwloc_obj_bridge_type_t>, Sized,
+        Sync, TryFrom<hwloc_obj_bridge_type_t>, Unpin, UnwindSafe
     );
     assert_not_impl_any!(BridgeType:
         Binary, Default, Deref, Drop, Error, IntoIterator, LowerExp, LowerHex,
@@ -518,8 +834,8 @@ mod tests {
         io::Write
     );
     assert_impl_all!(CacheType:
-        Copy, Debug, Display, Hash, Into<hwloc_obj_cache_type_t>, Sized, Sync,
-        TryFrom<hwloc_obj_cache_type_t>, Unpin, UnwindSafe
+        Copy, Debug, Display, FromStr, Hash, Into<hwloc_obj_cache_type_t>, Sized,
+        Sync, TryFrom<hwloc_obj_cache_type_t>, Unpin, UnwindSafe
     );
     assert_not_impl_any!(CacheType:
         Binary, Default, Deref, Drop, Error, IntoIterator, LowerExp, LowerHex,
@@ -527,17 +843,41 @@ mod tests {
         io::Write
     );
     assert_impl_all!(ObjectType:
-        Copy, Debug, Display, Hash, Into<hwloc_obj_type_t>, PartialOrd, Sized,
-        Sync, TryFrom<hwloc_obj_type_t>, Unpin, UnwindSafe
+        Copy, Debug, Display, FromStr, Hash, Into<hwloc_obj_type_t>,
+        PartialOrd, Sized, Sync, TryFrom<hwloc_obj_type_t>, Unpin, UnwindSafe
     );
     assert_not_impl_any!(ObjectType:
         Binary, Default, Deref, Drop, Error, IntoIterator, LowerExp, LowerHex,
         Octal, Ord, Pointer, Read, UpperExp, UpperHex, fmt::Write,
         io::Write
     );
+    assert_impl_all!(ParseTypeError:
+        Clone, Debug, Default, Display, Error, Hash, Sized, Sync, Unpin,
+        UnwindSafe
+    );
+    assert_not_impl_any!(ParseTypeError:
+        Binary, Copy, Deref, Drop, IntoIterator, LowerExp, LowerHex, Octal,
+        PartialOrd, Pointer, Read, UpperExp, UpperHex, fmt::Write, io::Write
+    );
+    assert_impl_all!(TypeSpec:
+        Copy, Debug, Hash, Sized, Sync, Unpin, UnwindSafe
+    );
+    assert_not_impl_any!(TypeSpec:
+        Binary, Default, Deref, Display, Drop, Error, IntoIterator, LowerExp,
+        LowerHex, Octal, PartialOrd, Pointer, Read, UpperExp, UpperHex,
+        fmt::Write, io::Write
+    );
+    assert_impl_all!(TypeSpecAttributes:
+        Copy, Debug, Hash, Sized, Sync, Unpin, UnwindSafe
+    );
+    assert_not_impl_any!(TypeSpecAttributes:
+        Binary, Default, Deref, Display, Drop, Error, IntoIterator, LowerExp,
+        LowerHex, Octal, PartialOrd, Pointer, Read, UpperExp, UpperHex,
+        fmt::Write, io::Write
+    );
     assert_impl_all!(OSDeviceType:
-        Copy, Debug, Display, Hash, Into<hwloc_obj_osdev_type_t>, Sized, Sync,
-        TryFrom<hwloc_obj_osdev_type_t>, Unpin, UnwindSafe
+        Copy, Debug, Display, FromStr, Hash, Into<hwloc_obj_osdev_type_t>, Sized,
+        Sync, TryFrom<hwloc_obj_osdev_type_t>, Unpin, UnwindSafe
     );
     assert_not_impl_any!(OSDeviceType:
         Binary, Default, Deref, Drop, Error, IntoIterator, LowerExp, LowerHex,
@@ -545,15 +885,159 @@ mod tests {
         io::Write
     );
 
+    /// Check that a type's string conversions round-trip
+    fn check_type_strings<Type>(ty: Type) -> Result<(), TestCaseError>
+    where
+        Type: Copy + Debug + Display + Eq + FromStr<Err = ParseTypeError>,
+    {
+        let s = ty.to_string();
+        prop_assert_eq!(s.parse::<Type>(), Ok(ty));
+        prop_assert_eq!(s.to_lowercase().parse::<Type>(), Ok(ty));
+        prop_assert_eq!(s.to_uppercase().parse::<Type>(), Ok(ty));
+        Ok(())
+    }
+
+    #[test]
+    fn type_strings() {
+        assert_eq!(ObjectType::L1ICache.to_string(), "L1iCache");
+        assert_eq!(ObjectType::NUMANode.to_string(), "NUMANode");
+        assert_eq!(ObjectType::PCIDevice.to_string(), "PCIDev");
+        assert_eq!(ObjectType::OSDevice.to_string(), "OSDev");
+        assert_eq!(format!("{:>8}", ObjectType::PU), "      PU");
+        assert_eq!("pcidev".parse::<ObjectType>(), Ok(ObjectType::PCIDevice));
+        assert_eq!(
+            "PCIDevice".parse::<ObjectType>(),
+            Err(ParseTypeError("PCIDevice".to_owned()))
+        );
+        assert_eq!(OSDeviceType::Network.to_string(), "Net");
+        assert_eq!(OSDeviceType::CoProcessor.to_string(), "CoProc");
+        assert_eq!("net".parse::<OSDeviceType>(), Ok(OSDeviceType::Network));
+        assert_eq!("block".parse::<OSDeviceType>(), Ok(OSDeviceType::Storage));
+        assert_eq!("storage".parse::<OSDeviceType>(), Ok(OSDeviceType::Storage));
+        assert_eq!("d".parse::<CacheType>(), Ok(CacheType::Data));
+        assert_eq!(
+            "Core:2".parse::<ObjectType>(),
+            Err(ParseTypeError("Core:2".to_owned()))
+        );
+    }
+
+    #[test]
+    fn parse_with_attributes() {
+        let parse = |s| ObjectType::parse_with_attributes(s).unwrap();
+        let without_attributes = |object_type| TypeSpec {
+            object_type,
+            attributes: None,
+        };
+        assert_eq!(parse("Machine"), without_attributes(ObjectType::Machine));
+        assert_eq!(parse("core"), without_attributes(ObjectType::Core));
+        assert_eq!(parse("PU:2"), without_attributes(ObjectType::PU));
+        assert_eq!(parse("NUMANode"), without_attributes(ObjectType::NUMANode));
+        assert_eq!(parse("PCI"), without_attributes(ObjectType::PCIDevice));
+        assert_eq!(parse("Group"), without_attributes(ObjectType::Group));
+        assert_eq!(
+            parse("Group1"),
+            TypeSpec {
+                object_type: ObjectType::Group,
+                attributes: Some(TypeSpecAttributes::Group { depth: 1 }),
+            }
+        );
+        assert_eq!(
+            parse("L3Cache"),
+            TypeSpec {
+                object_type: ObjectType::L3Cache,
+                attributes: Some(TypeSpecAttributes::Cache {
+                    depth: 3,
+                    cache_type: None,
+                }),
+            }
+        );
+        assert_eq!(
+            parse("L1i"),
+            TypeSpec {
+                object_type: ObjectType::L1ICache,
+                attributes: Some(TypeSpecAttributes::Cache {
+                    depth: 1,
+                    cache_type: Some(CacheType::Instruction),
+                }),
+            }
+        );
+        assert_eq!(
+            parse("HostBridge"),
+            TypeSpec {
+                object_type: ObjectType::Bridge,
+                attributes: Some(TypeSpecAttributes::Bridge {
+                    upstream_type: BridgeType::Host,
+                    downstream_type: BridgeType::PCI,
+                }),
+            }
+        );
+        assert_eq!(
+            parse("GPU"),
+            TypeSpec {
+                object_type: ObjectType::OSDevice,
+                attributes: Some(TypeSpecAttributes::OSDevice(OSDeviceType::GPU)),
+            }
+        );
+        for invalid in ["", "NotAType", "L9", "L4i", "Co\0re"] {
+            assert_eq!(
+                ObjectType::parse_with_attributes(invalid),
+                Err(ParseTypeError(invalid.to_owned()))
+            );
+        }
+    }
+
     proptest! {
-        // For object subtypes, the only logic we implement is arbitrary, so just
-        // exercise that it doesn't crash and we're good to go
+        // For object subtypes, the only logic we implement is arbitrary and
+        // string conversions, so just check that the latter round-trip
         #[test]
-        fn arbitrary_bridge_type(_ty: BridgeType) {}
+        fn bridge_type_strings(ty: BridgeType) {
+            check_type_strings(ty)?;
+        }
         #[test]
-        fn arbitrary_cache_type(_ty: CacheType) {}
+        fn cache_type_strings(ty: CacheType) {
+            check_type_strings(ty)?;
+        }
+        #[test]
+        fn os_device_type_strings(ty: OSDeviceType) {
+            check_type_strings(ty)?;
+            prop_assert_eq!(ty.to_string(), ty.hwloc_name());
+        }
+
+        #[test]
+        fn object_type_strings(ty: ObjectType) {
+            check_type_strings(ty)?;
+            prop_assert_eq!(ty.to_string(), ty.hwloc_name());
+            prop_assert_eq!(
+                ObjectType::parse_with_attributes(&ty.to_string()).unwrap().object_type,
+                ty
+            );
+        }
+
         #[test]
-        fn arbitrary_os_device_type(_ty: OSDeviceType) {}
+        fn parse_any_string(s in any_string()) {
+            fn check_parse<Type>(s: &str) -> Result<(), TestCaseError>
+            where
+                Type: Copy + Debug + Display + Eq + FromStr<Err = ParseTypeError>,
+            {
+                match s.parse::<Type>() {
+                    Ok(ty) => check_type_strings(ty)?,
+                    Err(e) => prop_assert_eq!(e, ParseTypeError(s.to_owned())),
+                }
+                Ok(())
+            }
+            check_parse::<BridgeType>(&s)?;
+            check_parse::<CacheType>(&s)?;
+            check_parse::<ObjectType>(&s)?;
+            check_parse::<OSDeviceType>(&s)?;
+        }
+
+        #[cfg(feature = "serde")]
+        #[test]
+        fn serde_object_type(ty: ObjectType) {
+            let json = serde_json::to_string(&ty).unwrap();
+            prop_assert_eq!(&json, &format!("\"{ty}\""));
+            prop_assert_eq!(serde_json::from_str::<ObjectType>(&json).unwrap(), ty);
+        }
 
         // For top-level object types, however, we do have some logic to test
 
@@ -2,19 +2,33 @@
 //!
 //! This is a place for centralizing proptest strategies which are more clever
 //! than an [`Arbitrary`] and don't have one clear place to go in the codebase.
+//!
+//! Some of these strategies are also useful for property-based testing of
+//! code that uses hwlocality, and are therefore exposed publicly when the
+//! `proptest` feature is enabled. For example, [`cpuset_within()`] generates
+//! [`CpuSet`]s that only cover the CPUs of a certain topology, which is more
+//! realistic input for placement algorithms than any [`CpuSet`]. Combined
+//! with the [`Arbitrary`] implementation of [`SyntheticTopology`], this lets
+//! you fuzz such algorithms over a variety of small realistic machines.
+//!
+//! [`SyntheticTopology`]: crate::topology::synthetic::SyntheticTopology
 
-use crate::bitmap::BitmapIndex;
+use crate::{
+    bitmap::{Bitmap, BitmapIndex},
+    cpu::cpuset::CpuSet,
+    memory::nodeset::NodeSet,
+    topology::Topology,
+};
 #[cfg(test)]
 use crate::{
-    bitmap::{Bitmap, BitmapRef, OwnedSpecializedBitmap, SpecializedBitmap},
+    bitmap::{BitmapRef, OwnedSpecializedBitmap, SpecializedBitmap},
     object::TopologyObject,
-    topology::Topology,
 };
 use enum_iterator::Sequence;
 use proptest::{
     collection::SizeRange,
     prelude::*,
-    sample::Select,
+    sample::{Select, Subsequence},
     strategy::{Map, TupleUnion, WA},
     string::RegexGeneratorStrategy,
 };
@@ -182,3 +196,64 @@ pub(crate) fn topology_related_set<Set: OwnedSpecializedBitmap>(
 ) -> impl Strategy<Value = Set> {
     set_with_reference(topology_set(Topology::test_instance()).as_ref())
 }
+
+/// Generate [`CpuSet`]s that only contain CPUs from a certain [`Topology`]
+///
+/// The generated sets are subsets of [`Topology::cpuset()`], with a bias
+/// towards the empty set and the full topology cpuset, which are edge cases
+/// of many placement algorithms.
+pub fn cpuset_within(topology: &Topology) -> SubsetStrategy<CpuSet> {
+    subset_of(topology.cpuset().as_ref())
+}
+
+/// Generate [`NodeSet`]s that only contain NUMA nodes from a certain
+/// [`Topology`]
+///
+/// The generated sets are subsets of [`Topology::nodeset()`], with a bias
+/// towards the empty set and the full topology nodeset, which are edge cases
+/// of many placement algorithms.
+pub fn nodeset_within(topology: &Topology) -> SubsetStrategy<NodeSet> {
+    subset_of(topology.nodeset().as_ref())
+}
+
+/// Generate subsets of a finite reference set
+fn subset_of<Set: Debug + From<Bitmap>>(reference: &Bitmap) -> SubsetStrategy<Set> {
+    let elems = reference.iter_set().collect::<Vec<_>>();
+    let num_elems = elems.len();
+    prop_oneof![
+        1 => Just(Vec::new()),
+        3 => prop::sample::subsequence(elems.clone(), 0..=num_elems),
+        1 => Just(elems),
+    ]
+    .prop_map(|elems| Set::from(elems.into_iter().collect::<Bitmap>()))
+}
+
+/// Strategy emitted by [`cpuset_within()`] and [`nodeset_within()`]
+pub type SubsetStrategy<Set> = Map<
+    TupleUnion<(
+        WA<Just<Vec<BitmapIndex>>>,
+        WA<Subsequence<BitmapIndex>>,
+        WA<Just<Vec<BitmapIndex>>>,
+    )>,
+    fn(Vec<BitmapIndex>) -> Set,
+>;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[allow(unused)]
+    use similar_asserts::assert_eq;
+
+    proptest! {
+        /// Check that topology-bounded sets stay within the topology
+        #[test]
+        fn sets_within_topology(
+            cpuset in cpuset_within(Topology::test_instance()),
+            nodeset in nodeset_within(Topology::test_instance()),
+        ) {
+            let topology = Topology::test_instance();
+            prop_assert!(topology.cpuset().includes(&cpuset));
+            prop_assert!(topology.nodeset().includes(&nodeset));
+        }
+    }
+}
@@ -0,0 +1,213 @@
+//! Canned topologies for unit tests
+//!
+//! Code that adapts to the hardware topology is hard to test in CI, where the
+//! machines are usually small and homogeneous, and in any case differ from the
+//! production hardware. This module provides a set of reproducible topologies
+//! that downstream crates can use in their unit tests, typically by enabling
+//! the `testing` cargo feature in their `[dev-dependencies]`.
+//!
+//! These topologies are not probed from the host system, so CPU and memory
+//! binding operations will not do anything on them.
+
+#[cfg(feature = "hwloc-2_4_0")]
+use crate::cpu::cpuset::CpuSet;
+use crate::topology::{
+    builder::{TopologyBuilder, TypeFilter},
+    synthetic::SyntheticTopology,
+    Topology,
+};
+use std::sync::OnceLock;
+
+/// # Canned test topologies
+///
+/// Each of these topologies is built on first use, then cached for the
+/// remainder of the process' lifetime.
+impl Topology {
+    /// Dual-socket server with one NUMA node per socket
+    ///
+    /// Each package contains a single NUMA node, with a shared L3 cache and 8
+    /// CPU cores that have 2 PUs (hardware threads) each, for a total of 32
+    /// PUs. Its synthetic description is
+    /// `Package:2 NUMANode:1 L3Cache:1 Core:8 PU:2`.
+    pub fn test_dual_socket_numa() -> &'static Self {
+        static INSTANCE: OnceLock<Topology> = OnceLock::new();
+        INSTANCE.get_or_init(|| {
+            SyntheticTopology::new()
+                .packages(2)
+                .numa_per_package(1)
+                .l3(1)
+                .cores(8)
+                .pus(2)
+                .build()
+                .build()
+                .expect("Failed to build dual-socket test topology")
+        })
+    }
+
+    /// Hybrid CPU with big and LITTLE cores
+    ///
+    /// A single package contains 12 single-threaded CPU cores, grouped in 3
+    /// clusters of 4 cores sharing an L2 cache. The first cluster (PUs 0 to 3)
+    /// is made of high-performance "big" cores, while the other two clusters
+    /// (PUs 4 to 11) are made of energy-efficient "LITTLE" cores.
+    ///
+    /// The two kinds of cores are reported by [`Topology::cpu_kinds()`], with
+    /// LITTLE cores having a lower efficiency than big cores, and their
+    /// `CoreType` info set to `IntelAtom` and `IntelCore` respectively.
+    #[cfg(feature = "hwloc-2_4_0")]
+    pub fn test_hybrid_big_little() -> &'static Self {
+        static INSTANCE: OnceLock<Topology> = OnceLock::new();
+        INSTANCE.get_or_init(|| {
+            let mut topology = SyntheticTopology::new()
+                .l2(3)
+                .cores(4)
+                .build()
+                .build()
+                .expect("Failed to build hybrid test topology");
+            topology.edit(|editor| {
+                let kinds = [(0..=3, 1, "IntelCore"), (4..=11, 0, "IntelAtom")];
+                for (pus, efficiency, core_type) in kinds {
+                    editor
+                        .register_cpu_kind(
+                            &CpuSet::from_range(pus),
+                            Some(efficiency),
+                            [("CoreType", core_type)],
+                        )
+                        .expect("Failed to register test CPU kind");
+                }
+            });
+            topology
+        })
+    }
+
+    /// Single-socket node with a GPU attached
+    ///
+    /// A single package contains a NUMA node and 4 single-threaded CPU cores.
+    /// An NVIDIA GPU is attached to the machine through a PCI host bridge, and
+    /// is exposed by the `card0` display and `cuda0` CUDA OS devices.
+    ///
+    /// This topology is imported from XML with all I/O objects kept.
+    pub fn test_gpu_node() -> &'static Self {
+        static INSTANCE: OnceLock<Topology> = OnceLock::new();
+        INSTANCE.get_or_init(|| {
+            TopologyBuilder::new()
+                .with_io_type_filter(TypeFilter::KeepAll)
+                .expect("KeepAll should be a supported I/O type filter")
+                .from_xml(GPU_NODE_XML)
+                .expect("GPU test topology XML should be valid")
+                .build()
+                .expect("Failed to build GPU test topology")
+        })
+    }
+}
+
+/// XML description of [`Topology::test_gpu_node()`]
+const GPU_NODE_XML: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<!DOCTYPE topology SYSTEM "hwloc2.dtd">
+<topology version="2.0">
+  <object type="Machine" os_index="0" cpuset="0x0000000f" complete_cpuset="0x0000000f" allowed_cpuset="0x0000000f" nodeset="0x00000001" complete_nodeset="0x00000001" allowed_nodeset="0x00000001" gp_index="1">
+    <object type="Package" os_index="0" cpuset="0x0000000f" complete_cpuset="0x0000000f" nodeset="0x00000001" complete_nodeset="0x00000001" gp_index="2">
+      <object type="NUMANode" os_index="0" cpuset="0x0000000f" complete_cpuset="0x0000000f" nodeset="0x00000001" complete_nodeset="0x00000001" gp_index="3" local_memory="17179869184">
+        <page_type size="4096" count="4194304"/>
+      </object>
+      <object type="Core" os_index="0" cpuset="0x00000001" complete_cpuset="0x00000001" nodeset="0x00000001" complete_nodeset="0x00000001" gp_index="4">
+        <object type="PU" os_index="0" cpuset="0x00000001" complete_cpuset="0x00000001" nodeset="0x00000001" complete_nodeset="0x00000001" gp_index="5"/>
+      </object>
+      <object type="Core" os_index="1" cpuset="0x00000002" complete_cpuset="0x00000002" nodeset="0x00000001" complete_nodeset="0x00000001" gp_index="6">
+        <object type="PU" os_index="1" cpuset="0x00000002" complete_cpuset="0x00000002" nodeset="0x00000001" complete_nodeset="0x00000001" gp_index="7"/>
+      </object>
+      <object type="Core" os_index="2" cpuset="0x00000004" complete_cpuset="0x00000004" nodeset="0x00000001" complete_nodeset="0x00000001" gp_index="8">
+        <object type="PU" os_index="2" cpuset="0x00000004" complete_cpuset="0x00000004" nodeset="0x00000001" complete_nodeset="0x00000001" gp_index="9"/>
+      </object>
+      <object type="Core" os_index="3" cpuset="0x00000008" complete_cpuset="0x00000008" nodeset="0x00000001" complete_nodeset="0x00000001" gp_index="10">
+        <object type="PU" os_index="3" cpuset="0x00000008" complete_cpuset="0x00000008" nodeset="0x00000001" complete_nodeset="0x00000001" gp_index="11"/>
+      </object>
+    </object>
+    <object type="Bridge" gp_index="12" bridge_type="0-1" depth="0" bridge_pci="0000:[01-01]">
+      <object type="PCIDev" gp_index="13" pci_busid="0000:01:00.0" pci_type="0302 [10de:20b0] [10de:134f] a1" pci_link_speed="31.507692">
+        <info name="PCIVendor" value="NVIDIA Corporation"/>
+        <info name="PCIDevice" value="GA100 [A100 SXM4 40GB]"/>
+        <object type="OSDev" gp_index="14" name="card0" subtype="Display" osdev_type="1"/>
+        <object type="OSDev" gp_index="15" name="cuda0" subtype="CUDA" osdev_type="5">
+          <info name="CoProcType" value="CUDA"/>
+          <info name="Backend" value="CUDA"/>
+          <info name="GPUVendor" value="NVIDIA Corporation"/>
+          <info name="GPUModel" value="NVIDIA A100-SXM4-40GB"/>
+          <info name="CUDAGlobalMemorySize" value="41943040"/>
+        </object>
+      </object>
+    </object>
+  </object>
+</topology>
+"#;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::object::types::ObjectType;
+    #[allow(unused)]
+    use similar_asserts::assert_eq;
+
+    /// Check the number of objects of each type in a test topology
+    fn check_object_counts(topology: &Topology, expected_count: impl Fn(ObjectType) -> usize) {
+        for ty in enum_iterator::all::<ObjectType>() {
+            assert_eq!(
+                topology.objects_with_type(ty).count(),
+                expected_count(ty),
+                "{ty}"
+            );
+        }
+    }
+
+    /// Check the dual-socket test topology
+    #[test]
+    fn dual_socket_numa() {
+        let topology = Topology::test_dual_socket_numa();
+        assert!(std::ptr::eq(topology, Topology::test_dual_socket_numa()));
+        #[allow(clippy::wildcard_enum_match_arm)]
+        check_object_counts(topology, |ty| match ty {
+            ObjectType::Machine => 1,
+            ObjectType::Package | ObjectType::NUMANode | ObjectType::L3Cache => 2,
+            ObjectType::Core => 16,
+            ObjectType::PU => 32,
+            _ => 0,
+        });
+    }
+
+    /// Check the hybrid test topology
+    #[cfg(feature = "hwloc-2_4_0")]
+    #[test]
+    fn hybrid_big_little() {
+        let topology = Topology::test_hybrid_big_little();
+        #[allow(clippy::wildcard_enum_match_arm)]
+        check_object_counts(topology, |ty| match ty {
+            ObjectType::Machine | ObjectType::Package | ObjectType::NUMANode => 1,
+            ObjectType::L2Cache => 3,
+            ObjectType::Core | ObjectType::PU => 12,
+            _ => 0,
+        });
+
+        let kinds = topology.cpu_kinds().unwrap().collect::<Vec<_>>();
+        assert_eq!(kinds.len(), 2);
+        assert_eq!(kinds[0].cpuset, CpuSet::from_range(4..=11));
+        assert_eq!(kinds[1].cpuset, CpuSet::from_range(0..=3));
+        assert!(kinds[0].efficiency < kinds[1].efficiency);
+    }
+
+    /// Check the GPU node test topology
+    #[test]
+    fn gpu_node() {
+        let topology = Topology::test_gpu_node();
+        #[allow(clippy::wildcard_enum_match_arm)]
+        check_object_counts(topology, |ty| match ty {
+            ObjectType::Machine
+            | ObjectType::Package
+            | ObjectType::NUMANode
+            | ObjectType::Bridge
+            | ObjectType::PCIDevice => 1,
+            ObjectType::OSDevice => 2,
+            ObjectType::Core | ObjectType::PU => 4,
+            _ => 0,
+        });
+    }
+}
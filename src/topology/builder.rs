@@ -58,6 +58,7 @@ use num_enum::{IntoPrimitive, TryFromPrimitive};
 use similar_asserts::assert_eq;
 use std::{
     fmt::{self, Pointer},
+    io::{self, Read},
     path::{Path, PathBuf},
     ptr::NonNull,
 };
@@ -138,7 +139,10 @@ impl TopologyBuilder {
         // Transfer hwloc_topology ownership to a Topology
         let inner = self.0;
         std::mem::forget(self);
-        Ok(Topology(inner))
+        // SAFETY: hwloc_topology_load produced a fully built topology
+        let mut topology = unsafe { Topology::wrap(inner) };
+        topology.index_objects();
+        Ok(topology)
     }
 }
 
@@ -170,10 +174,17 @@ impl TopologyBuilder {
     /// from the current process. Calling this method permits to make it expose
     /// the topology of the machine from the point of view of another process.
     ///
+    /// This is useful for monitoring tools that need to know which resources
+    /// another process may use, for instance because it has been restricted
+    /// to a subset of the machine by Linux cgroups. Unless
+    /// [`BuildFlags::INCLUDE_DISALLOWED`] is set, resources that the target
+    /// process is not allowed to use are removed from the resulting topology.
+    ///
     /// # Errors
     ///
     /// - [`FromPIDError`] if the topology cannot be configured from this
-    ///   process.
+    ///   process, typically because the operating system does not support
+    ///   viewing the topology from another process.
     ///
     /// # Panics
     ///
@@ -374,10 +385,35 @@ impl TopologyBuilder {
         polymorphized(self, path.as_ref())
     }
 
+    /// Read the topology from an XML description provided by a reader
+    ///
+    /// This works a lot like [`TopologyBuilder::from_xml()`], but reads the XML
+    /// description from an arbitrary [`Read`] implementation, such as a
+    /// network socket or an archive entry, instead of taking a string.
+    ///
+    /// Since hwloc only accepts complete XML descriptions, the reader is read
+    /// until the end before the XML description is handed over to hwloc.
+    ///
+    /// # Errors
+    ///
+    /// - [`Io`] if reading from `reader` fails, or the XML description is not
+    ///   valid UTF-8
+    /// - [`Input`] if the XML description is rejected, see
+    ///   [`TopologyBuilder::from_xml()`]
+    ///
+    /// [`Input`]: ReaderInputError::Input
+    /// [`Io`]: ReaderInputError::Io
+    #[doc(alias = "hwloc_topology_set_xmlbuffer")]
+    pub fn from_xml_reader(self, mut reader: impl Read) -> Result<Self, ReaderInputError> {
+        let mut xml = String::new();
+        reader.read_to_string(&mut xml)?;
+        Ok(self.from_xml(&xml)?)
+    }
+
     /// Prevent a discovery component from being used for a topology
     ///
     /// `name` is the name of the discovery component that should not be used
-    /// when loading topology topology. The name is a string such as "cuda".
+    /// when loading the topology. The name is a string such as "cuda".
     /// For components with multiple phases, it may also be suffixed with the
     /// name of a phase, for instance "linux:io". A list of components
     /// distributed with hwloc can be found
@@ -386,13 +422,17 @@ impl TopologyBuilder {
     ///
     /// This may be used to avoid expensive parts of the discovery process. For
     /// instance, CUDA-specific discovery may be expensive and unneeded while
-    /// generic I/O discovery could still be useful.
+    /// generic I/O discovery could still be useful. It can also be used to
+    /// exclude a misbehaving backend, such as one that relies on a broken GPU
+    /// driver, without disabling discovery of a whole category of objects
+    /// through type filters.
     ///
     /// # Errors
     ///
     /// - [`NulError`] if `name` contains NUL chars.
     #[cfg(feature = "hwloc-2_1_0")]
     #[doc(alias = "hwloc_topology_set_components")]
+    #[doc(alias = "blacklist_component")]
     pub fn without_component(mut self, name: &str) -> Result<Self, HybridError<NulError>> {
         let name = LibcString::new(name)?;
         // SAFETY: - TopologyBuilder is trusted to contain a valid ptr (type invariant)
@@ -443,6 +483,19 @@ impl From<NulError> for StringInputError {
     }
 }
 
+/// Failed to read the topology source from a reader
+#[allow(variant_size_differences)]
+#[derive(Debug, Error)]
+pub enum ReaderInputError {
+    /// Failed to read the topology data from the reader
+    #[error("failed to read topology data")]
+    Io(#[from] io::Error),
+
+    /// The topology data that was read is not valid
+    #[error(transparent)]
+    Input(#[from] StringInputError),
+}
+
 /// An invalid input file path was specified as the topology source
 #[derive(Clone, Debug, Error, Eq, Hash, PartialEq)]
 pub enum FileInputError {
@@ -684,7 +737,14 @@ impl TopologyBuilder {
     }
 
     /// Current filtering for the given object type
+    ///
+    /// This can be used to check which filter a type ended up with after
+    /// calling batch helpers like [`with_cpu_cache_type_filter()`], which
+    /// may adjust the requested filter or silently ignore some types.
+    ///
+    /// [`with_cpu_cache_type_filter()`]: Self::with_cpu_cache_type_filter()
     #[allow(clippy::missing_errors_doc)]
+    #[doc(alias = "hwloc_topology_get_type_filter")]
     pub fn type_filter(&self, ty: ObjectType) -> Result<TypeFilter, RawHwlocError> {
         let mut filter = hwloc_type_filter_e::MAX;
         // SAFETY: - TopologyBuilder is trusted to contain a valid ptr (type invariant)
@@ -1092,6 +1152,14 @@ pub(crate) mod tests {
         LowerExp, LowerHex, Octal, PartialOrd, Pointer, Read,
         UpperExp, UpperHex, fmt::Write, io::Write
     );
+    assert_impl_all!(ReaderInputError:
+        Error, From<io::Error>, From<StringInputError>, Sized, Sync, Unpin
+    );
+    assert_not_impl_any!(ReaderInputError:
+        Binary, Clone, Default, Deref, Drop, IntoIterator,
+        LowerExp, LowerHex, Octal, PartialOrd, Pointer, Read,
+        UpperExp, UpperHex, fmt::Write, io::Write
+    );
     assert_impl_all!(StringInputError:
         Copy, Error, From<NulError>, Hash, Sized, Sync, Unpin, UnwindSafe
     );
@@ -1156,7 +1224,7 @@ pub(crate) mod tests {
         build_flags: BuildFlags,
         type_filter: impl Fn(ObjectType) -> Result<TypeFilter, TestCaseError>,
     ) -> Result<(), TestCaseError> {
-        prop_assert_eq!(format!("{:p}", *topology), format!("{:p}", topology.0));
+        prop_assert_eq!(format!("{:p}", *topology), format!("{:p}", topology.raw));
         prop_assert!(topology.is_abi_compatible());
         prop_assert_eq!(topology.build_flags(), build_flags);
         prop_assert_eq!(
@@ -1253,6 +1321,19 @@ pub(crate) mod tests {
         Ok(())
     }
 
+    /// Check error reporting of XML import from readers
+    #[test]
+    fn from_xml_reader_errors() {
+        assert!(matches!(
+            TopologyBuilder::new().from_xml_reader(&[0xff, 0xfe, 0xfd][..]),
+            Err(ReaderInputError::Io(_))
+        ));
+        assert!(matches!(
+            TopologyBuilder::new().from_xml_reader(&b"<a>\0</a>"[..]),
+            Err(ReaderInputError::Input(StringInputError::ContainsNul))
+        ));
+    }
+
     /// Set up a [`TopologyBuilder`] with random flags from proptest, if the
     /// flags are right
     /// FIXME: Test more aspects of build flags
@@ -1498,6 +1579,23 @@ pub(crate) mod tests {
                 check_xml_topology(&topology)?;
             }
 
+            // Test round trip through a writer/reader pair
+            {
+                let mut bytes = Vec::new();
+                default
+                    .export_xml_writer(&mut bytes, XMLExportFlags::default())
+                    .unwrap();
+                let xml = default.export_xml(XMLExportFlags::default()).unwrap();
+                prop_assert_eq!(&bytes[..], xml.as_bytes());
+                let topology = builder_with_flags(build_flags)?
+                    .unwrap()
+                    .from_xml_reader(&bytes[..])
+                    .unwrap()
+                    .build()
+                    .unwrap();
+                check_xml_topology(&topology)?;
+            }
+
             // Test round trip throguh XML file
             {
                 let path = NamedTempFile::new().unwrap().into_temp_path();
@@ -0,0 +1,335 @@
+//! Capturing hwloc's discovery diagnostics
+//!
+//! hwloc reports some problems that it encounters during topology discovery,
+//! such as invalid locality information from a buggy BIOS, by printing
+//! messages to the standard error stream of the process. This module lets you
+//! capture these messages, so that your application can log them or report
+//! them to the user in its own way.
+
+#[cfg(unix)]
+use super::builder::TopologyBuilder;
+use super::Topology;
+#[cfg(unix)]
+use crate::errors::HybridError;
+use std::fmt::{self, Display};
+#[cfg(unix)]
+use std::{
+    fs::File,
+    io,
+    os::fd::{AsRawFd, FromRawFd, OwnedFd},
+    sync::Mutex,
+};
+
+/// # Discovery diagnostics
+impl Topology {
+    /// Diagnostics that hwloc emitted while this topology was being built
+    ///
+    /// This is only available for topologies that were built using
+    /// [`TopologyBuilder::build_with_diagnostics()`], and will be `None` for
+    /// topologies that were built in another way.
+    ///
+    /// Clones of a topology share the diagnostics of the original topology.
+    pub fn discovery_diagnostics(&self) -> Option<&DiscoveryDiagnostics> {
+        self.diagnostics.as_ref()
+    }
+}
+
+/// # Topology building with diagnostics
+#[cfg(unix)]
+impl TopologyBuilder {
+    /// Load the topology, capturing the diagnostics that hwloc emits
+    ///
+    /// This works like [`TopologyBuilder::build()`], but the messages that
+    /// hwloc prints to the standard error stream during topology discovery are
+    /// captured instead, and made available via
+    /// [`Topology::discovery_diagnostics()`].
+    ///
+    /// This is done by temporarily taking over the standard error stream of
+    /// the whole process while the topology is being built, so you should be
+    /// aware of the following caveats:
+    ///
+    /// - Anything that other threads print to the standard error stream in
+    ///   the meantime will not reach it, and will be captured as diagnostics
+    ///   instead.
+    /// - hwloc only reports some problems once per process, so they will only
+    ///   be captured by the first topology build that encounters them.
+    /// - Setting the `HWLOC_HIDE_ERRORS` environment variable prevents hwloc
+    ///   from emitting some or all diagnostics.
+    ///
+    /// Concurrent calls to this method are serialized.
+    ///
+    /// # Errors
+    ///
+    /// - [`Rust`] if the standard error stream could not be redirected
+    /// - [`Hwloc`] if hwloc fails to build the topology
+    ///
+    /// [`Hwloc`]: HybridError::Hwloc
+    /// [`Rust`]: HybridError::Rust
+    #[doc(alias = "hwloc_topology_load")]
+    pub fn build_with_diagnostics(self) -> Result<Topology, HybridError<io::Error>> {
+        let (result, output) = capture_stderr(|| self.build())?;
+        let mut topology = result.map_err(HybridError::Hwloc)?;
+        topology.diagnostics = Some(DiscoveryDiagnostics::parse(&output));
+        Ok(topology)
+    }
+}
+
+/// Messages that hwloc emitted during topology discovery
+///
+/// Obtained via [`Topology::discovery_diagnostics()`].
+///
+/// hwloc's diagnostics are meant for human consumption, so they are exposed
+/// as a list of messages. Multi-line messages, which hwloc surrounds with a
+/// box of asterisks, are reported as a single message without the box.
+#[derive(Clone, Debug, Default, Eq, Hash, PartialEq)]
+pub struct DiscoveryDiagnostics {
+    /// Messages emitted by hwloc, in order
+    messages: Vec<String>,
+}
+//
+impl DiscoveryDiagnostics {
+    /// Messages that hwloc emitted, in order
+    pub fn messages(&self) -> impl ExactSizeIterator<Item = &str> + Clone {
+        self.messages.iter().map(String::as_str)
+    }
+
+    /// Truth that hwloc did not emit any message
+    pub fn is_empty(&self) -> bool {
+        self.messages.is_empty()
+    }
+
+    /// Split the raw output of hwloc into messages
+    pub(crate) fn parse(output: &str) -> Self {
+        /// Truth that a line is the top or bottom border of a message box
+        fn is_box_border(line: &str) -> bool {
+            line.len() >= 3 && line.bytes().all(|b| b == b'*')
+        }
+
+        let mut messages = Vec::new();
+        let mut boxed_lines = None::<Vec<&str>>;
+        for line in output.lines().map(str::trim_end) {
+            if is_box_border(line) {
+                if let Some(lines) = boxed_lines.take() {
+                    messages.push(lines.join("\n").trim_matches('\n').to_owned());
+                } else {
+                    boxed_lines = Some(Vec::new());
+                }
+            } else if let Some(lines) = &mut boxed_lines {
+                let line = line.strip_prefix('*').unwrap_or(line);
+                lines.push(line.strip_prefix(' ').unwrap_or(line));
+            } else if !line.is_empty() {
+                messages.push(line.to_owned());
+            }
+        }
+        if let Some(lines) = boxed_lines {
+            messages.push(lines.join("\n").trim_matches('\n').to_owned());
+        }
+        messages.retain(|message| !message.is_empty());
+        Self { messages }
+    }
+}
+//
+impl Display for DiscoveryDiagnostics {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (idx, message) in self.messages.iter().enumerate() {
+            if idx > 0 {
+                writeln!(f)?;
+            }
+            write!(f, "{message}")?;
+        }
+        Ok(())
+    }
+}
+
+/// Lock that serializes redirections of the standard error stream
+#[cfg(unix)]
+static STDERR_CAPTURE_LOCK: Mutex<()> = Mutex::new(());
+
+/// Run `f` while capturing everything that is written to the standard error
+/// file descriptor of the process
+#[cfg(unix)]
+fn capture_stderr<R>(f: impl FnOnce() -> R) -> io::Result<(R, String)> {
+    /// Convert the result of a libc call into an `io::Result`
+    fn check(result: libc::c_int) -> io::Result<libc::c_int> {
+        if result < 0 {
+            Err(io::Error::last_os_error())
+        } else {
+            Ok(result)
+        }
+    }
+
+    /// Restores the original stderr when dropped, even if `f` panics
+    struct RestoreStderr(Option<OwnedFd>);
+    //
+    impl RestoreStderr {
+        /// Restore the original stderr, closing the write end of the pipe
+        fn restore(&mut self) -> io::Result<()> {
+            if let Some(saved_stderr) = self.0.take() {
+                // SAFETY: dup2() does not affect memory safety
+                check(unsafe { libc::dup2(saved_stderr.as_raw_fd(), libc::STDERR_FILENO) })?;
+            }
+            Ok(())
+        }
+    }
+    //
+    impl Drop for RestoreStderr {
+        #[allow(clippy::print_stderr)]
+        fn drop(&mut self) {
+            if let Err(e) = self.restore() {
+                // Cannot panic in Drop
+                eprintln!("ERROR: Failed to restore stderr ({e}).");
+            }
+        }
+    }
+
+    // Only one thread may redirect stderr at a time
+    let _guard = STDERR_CAPTURE_LOCK
+        .lock()
+        .unwrap_or_else(std::sync::PoisonError::into_inner);
+
+    // Set up a pipe and a copy of the original stderr
+    let mut fds = [-1; 2];
+    // SAFETY: fds is a valid array of two file descriptors, as pipe() expects
+    check(unsafe { libc::pipe(fds.as_mut_ptr()) })?;
+    // SAFETY: pipe() succeeded, so these are open fds that we now own
+    let (read_end, write_end) =
+        unsafe { (File::from_raw_fd(fds[0]), OwnedFd::from_raw_fd(fds[1])) };
+    // SAFETY: dup() does not affect memory safety
+    let saved_stderr = check(unsafe { libc::dup(libc::STDERR_FILENO) })?;
+    // SAFETY: dup() succeeded, so this is an open fd that we now own
+    let saved_stderr = unsafe { OwnedFd::from_raw_fd(saved_stderr) };
+
+    // Drain the pipe in a separate thread so that writers never block
+    let reader = std::thread::spawn(move || {
+        let mut output = Vec::new();
+        let mut read_end = read_end;
+        io::copy(&mut read_end, &mut output).map(|_| output)
+    });
+
+    // Redirect stderr to the pipe while f runs, then restore it. Restoring
+    // stderr closes the last write end of the pipe, which stops the reader.
+    // This happens in a drop guard so that stderr is restored if f panics.
+    // SAFETY: dup2() does not affect memory safety
+    check(unsafe { libc::dup2(write_end.as_raw_fd(), libc::STDERR_FILENO) })?;
+    let mut restore_stderr = RestoreStderr(Some(saved_stderr));
+    std::mem::drop(write_end);
+    let result = f();
+    restore_stderr.restore()?;
+
+    // Collect the captured output
+    let output = reader
+        .join()
+        .expect("stderr reader thread should not panic")?;
+    Ok((result, String::from_utf8_lossy(&output).into_owned()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[allow(unused)]
+    use similar_asserts::assert_eq;
+    use static_assertions::{assert_impl_all, assert_not_impl_any};
+    use std::{
+        fmt::{Binary, Debug, LowerExp, LowerHex, Octal, Pointer, UpperExp, UpperHex},
+        hash::Hash,
+        io::{self, Read},
+        ops::Deref,
+        panic::UnwindSafe,
+    };
+
+    // Check that public types in this module keep implementing all expected
+    // traits, in the interest of detecting future semver-breaking changes
+    assert_impl_all!(DiscoveryDiagnostics:
+        Clone, Debug, Default, Display, Hash, Sized, Sync, Unpin, UnwindSafe
+    );
+    assert_not_impl_any!(DiscoveryDiagnostics:
+        Binary, Copy, Deref, Drop, IntoIterator, LowerExp, LowerHex, Octal,
+        PartialOrd, Pointer, Read, UpperExp, UpperHex, fmt::Write, io::Write
+    );
+
+    /// Check the splitting of hwloc output into messages
+    #[test]
+    fn parse() {
+        let output = "\
+hwloc/linux: failed to find sysfs cpu topology directory
+****************************************************************************
+* hwloc 2.9.0 received invalid information from the operating system.
+*
+* Failed with: intersection without inclusion
+****************************************************************************
+
+hwloc: unterminated message";
+        let diagnostics = DiscoveryDiagnostics::parse(output);
+        assert_eq!(
+            diagnostics.messages().collect::<Vec<_>>(),
+            [
+                "hwloc/linux: failed to find sysfs cpu topology directory",
+                "hwloc 2.9.0 received invalid information from the operating system.\n\
+                \n\
+                Failed with: intersection without inclusion",
+                "hwloc: unterminated message",
+            ]
+        );
+        assert!(!diagnostics.is_empty());
+        assert!(DiscoveryDiagnostics::parse("\n\n").is_empty());
+        assert_eq!(
+            DiscoveryDiagnostics::parse("a\nb").to_string(),
+            "a\nb".to_owned()
+        );
+    }
+
+    /// Check that stderr output is captured
+    ///
+    /// Other tests may write to stderr concurrently, so the captured output is
+    /// only checked to contain our message.
+    #[cfg(unix)]
+    #[test]
+    fn capture() {
+        let message = b"hello from stderr\n";
+        let (written, output) = capture_stderr(|| {
+            // SAFETY: message is a valid buffer of the specified length
+            unsafe { libc::write(libc::STDERR_FILENO, message.as_ptr().cast(), message.len()) }
+        })
+        .unwrap();
+        assert_eq!(usize::try_from(written).unwrap(), message.len());
+        assert!(output.contains("hello from stderr\n"));
+    }
+
+    /// Check that stderr is restored if the closure panics
+    #[cfg(unix)]
+    #[test]
+    fn capture_panic() {
+        /// Identify the file that stderr currently points to
+        fn stderr_identity() -> (libc::dev_t, libc::ino_t) {
+            let mut stat = std::mem::MaybeUninit::<libc::stat>::uninit();
+            // SAFETY: stat is a valid output buffer for fstat()
+            let result = unsafe { libc::fstat(libc::STDERR_FILENO, stat.as_mut_ptr()) };
+            assert_eq!(result, 0);
+            // SAFETY: fstat() succeeded, so stat is initialized
+            let stat = unsafe { stat.assume_init() };
+            (stat.st_dev, stat.st_ino)
+        }
+
+        let before = stderr_identity();
+        let result = std::panic::catch_unwind(|| capture_stderr(|| panic!("expected panic")));
+        result.unwrap_err();
+        assert_eq!(stderr_identity(), before);
+    }
+
+    /// Check that topologies built with diagnostics expose them
+    #[cfg(unix)]
+    #[test]
+    fn build_with_diagnostics() {
+        let topology = TopologyBuilder::new()
+            .from_synthetic("Package:2 Core:2 PU:2")
+            .unwrap()
+            .build_with_diagnostics()
+            .unwrap();
+        assert!(topology.discovery_diagnostics().is_some());
+        assert_eq!(
+            topology.clone().discovery_diagnostics(),
+            topology.discovery_diagnostics()
+        );
+        assert!(Topology::test_instance().discovery_diagnostics().is_none());
+    }
+}
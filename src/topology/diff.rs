@@ -0,0 +1,845 @@
+//! Topology differences
+//!
+//! Applications that manage many similar machines, like cluster management
+//! tools, may want to store a single reference topology along with small
+//! per-node differences instead of one full topology per node. This module
+//! provides the [`TopologyDiff`] type, which can be computed using
+//! [`Topology::diff()`], exported to and imported from XML, and applied to
+//! the reference topology to get back the per-node topology.
+//!
+//! hwloc can only represent a few kinds of differences, namely changes to
+//! object names, textual infos and local memory sizes. More complex changes
+//! are reported as [`TooComplex`] entries, which cannot be applied nor
+//! exported.
+//!
+//! [`TooComplex`]: TopologyDiffEntry::TooComplex
+//
+// --- Implementation details ---
+//
+// Upstream docs: https://hwloc.readthedocs.io/en/v2.9/group__hwlocality__diff.html
+
+#[cfg(feature = "hwloc-2_3_0")]
+use super::editor::TopologyChanges;
+use super::{builder::FileInputError, builder::StringInputError, export::xml::XML, Topology};
+use crate::{
+    errors::{self, NulError, RawHwlocError},
+    ffi::{self, int, string::LibcString},
+    object::depth::Depth,
+    path::{self, PathError},
+};
+#[cfg(feature = "hwloc-2_3_0")]
+use bitflags::bitflags;
+use errno::Errno;
+#[cfg(feature = "hwloc-2_3_0")]
+use hwlocality_sys::{hwloc_topology_diff_apply_flags_e, HWLOC_TOPOLOGY_DIFF_APPLY_REVERSE};
+use hwlocality_sys::{
+    hwloc_topology_diff_obj_attr_u, hwloc_topology_diff_t, hwloc_topology_diff_u,
+    HWLOC_TOPOLOGY_DIFF_OBJ_ATTR, HWLOC_TOPOLOGY_DIFF_OBJ_ATTR_INFO,
+    HWLOC_TOPOLOGY_DIFF_OBJ_ATTR_NAME, HWLOC_TOPOLOGY_DIFF_OBJ_ATTR_SIZE,
+    HWLOC_TOPOLOGY_DIFF_TOO_COMPLEX,
+};
+use libc::EINVAL;
+#[allow(unused)]
+#[cfg(test)]
+use similar_asserts::assert_eq;
+use std::{
+    ffi::{c_int, c_uint, CStr},
+    fmt::{self, Debug},
+    iter::FusedIterator,
+    path::{Path, PathBuf},
+    ptr,
+};
+use thiserror::Error;
+
+/// # Topology differences
+//
+// --- Implementation details ---
+//
+// Upstream docs: https://hwloc.readthedocs.io/en/v2.9/group__hwlocality__diff.html
+impl Topology {
+    /// Compute the difference between this topology and `other`
+    ///
+    /// Applying the resulting [`TopologyDiff`] to this topology yields a
+    /// topology that is equivalent to `other`, as far as the supported kinds
+    /// of differences are concerned. If the topologies differ in ways that
+    /// hwloc cannot represent (e.g. they do not have the same objects), the
+    /// diff will contain [`TooComplex`] entries that pinpoint the first
+    /// objects below which differences could not be represented.
+    ///
+    /// [`TooComplex`]: TopologyDiffEntry::TooComplex
+    #[doc(alias = "hwloc_topology_diff_build")]
+    pub fn diff(&self, other: &Self) -> TopologyDiff {
+        let mut diff = ptr::null_mut();
+        // SAFETY: - Topologies are trusted to contain valid ptrs (type invariant)
+        //         - hwloc_topology_diff_build only reads from both topologies
+        //           once their lazy caches have been evaluated, which
+        //           Topology::refresh() ensures, so it is fine to pass them
+        //           as *mut even though we only have &self
+        //         - diff is an out parameter, its initial value is not read
+        //         - Per hwloc documentation, flags must be 0
+        let result = errors::call_hwloc_int_normal("hwloc_topology_diff_build", || unsafe {
+            hwlocality_sys::hwloc_topology_diff_build(
+                self.as_ptr().cast_mut(),
+                other.as_ptr().cast_mut(),
+                0,
+                &mut diff,
+            )
+        });
+        match result {
+            Ok(0 | 1) => TopologyDiff(diff),
+            Ok(other) => unreachable!("Unexpected hwloc_topology_diff_build result {other}"),
+            Err(e) => panic!("Diffing loaded topologies should not fail, but got {e}"),
+        }
+    }
+}
+
+/// Difference between two [`Topology`]s
+///
+/// Created using [`Topology::diff()`], [`TopologyDiff::from_xml()`] or
+/// [`TopologyDiff::from_xml_file()`].
+//
+// --- Implementation details ---
+//
+// # Safety
+//
+// As a type invariant, the inner pointer is either null (if the diff is empty)
+// or the head of a valid hwloc difference list that is owned by this struct.
+#[doc(alias = "hwloc_topology_diff_t")]
+pub struct TopologyDiff(hwloc_topology_diff_t);
+//
+impl TopologyDiff {
+    /// Load a diff from an XML string
+    ///
+    /// Returns the diff along with the identifier of the reference topology
+    /// that was specified when the diff was exported, if any.
+    ///
+    /// # Errors
+    ///
+    /// - [`ContainsNul`] if `xml` contains NUL chars.
+    /// - [`Invalid`] if `xml` was rejected by hwloc as an invalid topology
+    ///   difference.
+    ///
+    /// [`ContainsNul`]: StringInputError::ContainsNul
+    /// [`Invalid`]: StringInputError::Invalid
+    #[doc(alias = "hwloc_topology_diff_load_xmlbuffer")]
+    pub fn from_xml(xml: &str) -> Result<(Self, Option<String>), StringInputError> {
+        let xml = LibcString::new(xml)?;
+        let mut diff = ptr::null_mut();
+        let mut refname = ptr::null_mut();
+        // SAFETY: - LibcString should yield valid C strings, which we're not
+        //           using beyond their intended lifetime
+        //         - hwloc ops are trusted not to modify *const parameters
+        //         - xml string and length are in sync
+        //         - diff and refname are out parameters, their initial value
+        //           is not read
+        let result =
+            errors::call_hwloc_int_normal("hwloc_topology_diff_load_xmlbuffer", || unsafe {
+                hwlocality_sys::hwloc_topology_diff_load_xmlbuffer(
+                    xml.borrow(),
+                    xml.len()
+                        .try_into()
+                        .expect("XML buffer is too big for hwloc"),
+                    &mut diff,
+                    &mut refname,
+                )
+            });
+        match result {
+            // SAFETY: hwloc_topology_diff_load_xmlbuffer succeeded
+            Ok(_) => Ok(unsafe { Self::wrap_loaded(diff, refname) }),
+            Err(RawHwlocError {
+                errno: Some(Errno(EINVAL)) | None,
+                ..
+            }) => Err(StringInputError::Invalid),
+            Err(other_err) => unreachable!("Unexpected hwloc error: {other_err}"),
+        }
+    }
+
+    /// Load a diff from an XML file
+    ///
+    /// Returns the diff along with the identifier of the reference topology
+    /// that was specified when the diff was exported, if any.
+    ///
+    /// # Errors
+    ///
+    /// - [`BadRustPath`] if `path` contains NUL chars or is not valid Unicode.
+    /// - [`Invalid`] if hwloc failed to read the file, or rejected its
+    ///   contents as an invalid topology difference.
+    ///
+    /// [`BadRustPath`]: FileInputError::BadRustPath
+    /// [`Invalid`]: FileInputError::Invalid
+    #[doc(alias = "hwloc_topology_diff_load_xml")]
+    pub fn from_xml_file(path: impl AsRef<Path>) -> Result<(Self, Option<String>), FileInputError> {
+        /// Polymorphized version of this function (avoids generics code bloat)
+        fn polymorphized(path: &Path) -> Result<(TopologyDiff, Option<String>), FileInputError> {
+            let hwloc_path = path::make_hwloc_path(path)?;
+            let mut diff = ptr::null_mut();
+            let mut refname = ptr::null_mut();
+            // SAFETY: - hwloc_path has been checked to be fit for hwloc
+            //           consumption
+            //         - hwloc ops are trusted not to modify *const parameters
+            //         - diff and refname are out parameters, their initial
+            //           value is not read
+            errors::call_hwloc_int_normal("hwloc_topology_diff_load_xml", || unsafe {
+                hwlocality_sys::hwloc_topology_diff_load_xml(
+                    hwloc_path.borrow(),
+                    &mut diff,
+                    &mut refname,
+                )
+            })
+            .map_err(|_| FileInputError::Invalid(PathBuf::from(path).into()))?;
+            // SAFETY: hwloc_topology_diff_load_xml succeeded
+            Ok(unsafe { TopologyDiff::wrap_loaded(diff, refname) })
+        }
+        polymorphized(path.as_ref())
+    }
+
+    /// Export this diff to an XML string
+    ///
+    /// `refname` is an optional identifier for the reference topology, which
+    /// will be returned when the diff is loaded back by
+    /// [`TopologyDiff::from_xml()`].
+    ///
+    /// The XML string is allocated by hwloc's XML machinery, which can only
+    /// manage memory while a topology is alive. You must therefore specify a
+    /// topology whose lifetime the XML string will be bound to, normally the
+    /// reference topology that this diff applies to.
+    ///
+    /// # Errors
+    ///
+    /// - [`ContainsNul`] if `refname` contains NUL chars.
+    /// - [`TooComplex`] if the diff contains
+    ///   [`TooComplex`](TopologyDiffEntry::TooComplex) entries.
+    ///
+    /// [`ContainsNul`]: ExportDiffError::ContainsNul
+    /// [`TooComplex`]: ExportDiffError::TooComplex
+    #[doc(alias = "hwloc_topology_diff_export_xmlbuffer")]
+    pub fn export_xml<'topology>(
+        &self,
+        topology: &'topology Topology,
+        refname: Option<&str>,
+    ) -> Result<XML<'topology>, ExportDiffError> {
+        self.check_exportable()?;
+        let refname = refname.map(LibcString::new).transpose()?;
+        let refname_ptr = refname.as_ref().map_or(ptr::null(), LibcString::borrow);
+        let mut xmlbuffer = ptr::null_mut();
+        let mut buflen = 0;
+        // SAFETY: - Diff pointer is valid by type invariant
+        //         - refname is either null or a valid C string, which we're
+        //           not using beyond its intended lifetime
+        //         - hwloc ops are trusted not to modify *const parameters
+        //         - xmlbuffer and buflen are out parameters, their initial
+        //           value is not read
+        errors::call_hwloc_int_normal("hwloc_topology_diff_export_xmlbuffer", || unsafe {
+            hwlocality_sys::hwloc_topology_diff_export_xmlbuffer(
+                self.0,
+                refname_ptr,
+                &mut xmlbuffer,
+                &mut buflen,
+            )
+        })?;
+        let buflen = int::expect_usize(
+            c_uint::try_from(buflen).expect("Got negative buffer length from hwloc"),
+        );
+        // SAFETY: - xmlbuffer was just allocated by hwloc's XML export
+        //           machinery while topology is alive, and is not aliased
+        //         - If hwloc call succeeded, xmlbuffer and buflen should be OK
+        Ok(unsafe { XML::wrap(topology, xmlbuffer, buflen) }
+            .expect("Got null pointer from hwloc_topology_diff_export_xmlbuffer"))
+    }
+
+    /// Export this diff to an XML file at filesystem location `path`
+    ///
+    /// `refname` is an optional identifier for the reference topology, which
+    /// will be returned when the diff is loaded back by
+    /// [`TopologyDiff::from_xml_file()`].
+    ///
+    /// # Errors
+    ///
+    /// - [`BadPath`] if `path` contains NUL chars or is not valid Unicode.
+    /// - [`ContainsNul`] if `refname` contains NUL chars.
+    /// - [`Hwloc`] if hwloc failed to write the file.
+    /// - [`TooComplex`] if the diff contains
+    ///   [`TooComplex`](TopologyDiffEntry::TooComplex) entries.
+    ///
+    /// [`BadPath`]: ExportDiffError::BadPath
+    /// [`ContainsNul`]: ExportDiffError::ContainsNul
+    /// [`Hwloc`]: ExportDiffError::Hwloc
+    /// [`TooComplex`]: ExportDiffError::TooComplex
+    #[doc(alias = "hwloc_topology_diff_export_xml")]
+    pub fn export_xml_file(
+        &self,
+        refname: Option<&str>,
+        path: impl AsRef<Path>,
+    ) -> Result<(), ExportDiffError> {
+        /// Polymorphized version of this function (avoids generics code bloat)
+        fn polymorphized(
+            self_: &TopologyDiff,
+            refname: Option<&str>,
+            path: &Path,
+        ) -> Result<(), ExportDiffError> {
+            self_.check_exportable()?;
+            let path = path::make_hwloc_path(path)?;
+            let refname = refname.map(LibcString::new).transpose()?;
+            let refname_ptr = refname.as_ref().map_or(ptr::null(), LibcString::borrow);
+            // SAFETY: - Diff pointer is valid by type invariant
+            //         - refname is either null or a valid C string, which
+            //           we're not using beyond its intended lifetime
+            //         - path has been checked to be fit for hwloc consumption
+            //         - hwloc ops are trusted not to modify *const parameters
+            errors::call_hwloc_int_normal("hwloc_topology_diff_export_xml", || unsafe {
+                hwlocality_sys::hwloc_topology_diff_export_xml(self_.0, refname_ptr, path.borrow())
+            })?;
+            Ok(())
+        }
+        polymorphized(self, refname, path.as_ref())
+    }
+
+    /// Truth that this diff has no entry, i.e. the topologies are identical
+    pub fn is_empty(&self) -> bool {
+        self.0.is_null()
+    }
+
+    /// Truth that this diff contains [`TooComplex`] entries
+    ///
+    /// Such diffs can neither be applied nor exported.
+    ///
+    /// [`TooComplex`]: TopologyDiffEntry::TooComplex
+    pub fn is_too_complex(&self) -> bool {
+        self.entries()
+            .any(|entry| matches!(entry, TopologyDiffEntry::TooComplex { .. }))
+    }
+
+    /// Entries of this diff
+    pub fn entries(&self) -> impl FusedIterator<Item = TopologyDiffEntry<'_>> + Clone {
+        let mut next = self.0;
+        std::iter::from_fn(move || {
+            // SAFETY: - Diff list elements are valid by type invariant
+            //         - Output lifetime is bound to &self, during which the
+            //           diff list cannot be modified
+            let current = unsafe { next.as_ref()? };
+            // SAFETY: All diff list elements start with the generic header
+            next = unsafe { current.generic.next };
+            // SAFETY: Diff list elements are valid by type invariant
+            Some(unsafe { TopologyDiffEntry::from_raw(current) })
+        })
+        .fuse()
+    }
+
+    /// Wrap a diff that was loaded from XML, along with its refname
+    ///
+    /// # Safety
+    ///
+    /// `diff` and `refname` must have been produced by a successful call to
+    /// `hwloc_topology_diff_load_xml()` or
+    /// `hwloc_topology_diff_load_xmlbuffer()`.
+    unsafe fn wrap_loaded(
+        diff: hwloc_topology_diff_t,
+        refname: *mut std::ffi::c_char,
+    ) -> (Self, Option<String>) {
+        let diff = Self(diff);
+        // SAFETY: refname is either null or a valid C string, per precondition
+        let refname_str = unsafe { ffi::deref_str(&refname) }
+            .map(|refname| refname.to_string_lossy().into_owned());
+        // SAFETY: refname was allocated with malloc by hwloc and must be
+        //         liberated by the caller, and it is not used after this
+        unsafe { libc::free(refname.cast()) };
+        (diff, refname_str)
+    }
+
+    /// Check that this diff can be exported
+    fn check_exportable(&self) -> Result<(), ExportDiffError> {
+        if self.is_too_complex() {
+            Err(ExportDiffError::TooComplex)
+        } else {
+            Ok(())
+        }
+    }
+}
+
+/// # Applying differences
+#[cfg(feature = "hwloc-2_3_0")]
+impl TopologyDiff {
+    /// Apply this diff to `topology`
+    ///
+    /// `topology` should normally be the first argument of the
+    /// [`Topology::diff()`] call that produced this diff, in which case it
+    /// will end up equivalent to the second argument. With
+    /// [`DiffApplyFlags::REVERSE`], the diff is applied in reverse, which
+    /// turns the second argument of [`Topology::diff()`] back into the first.
+    ///
+    /// If any entry of the diff cannot be applied, the entries that were
+    /// already applied are reverted, so the topology is left unmodified.
+    ///
+    /// # Errors
+    ///
+    /// - [`Mismatch`] if an entry of the diff does not match the topology,
+    ///   e.g. because the diff was computed from another reference topology.
+    /// - [`TooComplex`] if the diff contains
+    ///   [`TooComplex`](TopologyDiffEntry::TooComplex) entries.
+    ///
+    /// [`Mismatch`]: ApplyDiffError::Mismatch
+    /// [`TooComplex`]: ApplyDiffError::TooComplex
+    #[doc(alias = "hwloc_topology_diff_apply")]
+    pub fn apply(
+        &self,
+        topology: &mut Topology,
+        flags: DiffApplyFlags,
+    ) -> Result<(), ApplyDiffError> {
+        if self.is_too_complex() {
+            return Err(ApplyDiffError::TooComplex);
+        }
+        if self.is_empty() {
+            return Ok(());
+        }
+        let diff = self.0;
+        let result = topology.edit(move |editor| {
+            editor.record_changes(TopologyChanges::OBJECT_METADATA);
+            // SAFETY: - Topology is trusted to contain a valid ptr (type
+            //           invariant), and we have exclusive access to it
+            //         - Diff pointer is valid by type invariant, and hwloc
+            //           ops are trusted not to modify it
+            //         - flags only allows values supported by hwloc
+            errors::call_hwloc_int_raw(
+                "hwloc_topology_diff_apply",
+                || unsafe {
+                    hwlocality_sys::hwloc_topology_diff_apply(
+                        editor.topology_mut_ptr(),
+                        diff,
+                        flags.bits(),
+                    )
+                },
+                0,
+            )
+        });
+        // hwloc reports the 1-based index of the failing entry as -result
+        result.map(std::mem::drop).map_err(|e| {
+            let entry = usize::try_from(-(e.result + 1))
+                .expect("Unexpected hwloc_topology_diff_apply result");
+            ApplyDiffError::Mismatch(entry)
+        })
+    }
+}
+
+impl Debug for TopologyDiff {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.entries()).finish()
+    }
+}
+
+impl Drop for TopologyDiff {
+    #[doc(alias = "hwloc_topology_diff_destroy")]
+    fn drop(&mut self) {
+        if self.0.is_null() {
+            return;
+        }
+        // SAFETY: - Diff pointer is valid and owned by type invariant
+        //         - Diff will not be usable again after Drop
+        let result = errors::call_hwloc_int_normal("hwloc_topology_diff_destroy", || unsafe {
+            hwlocality_sys::hwloc_topology_diff_destroy(self.0)
+        });
+        if let Err(e) = result {
+            unreachable!("Destroying a diff should not fail, but got {e}");
+        }
+    }
+}
+
+impl PartialEq for TopologyDiff {
+    fn eq(&self, other: &Self) -> bool {
+        self.entries().eq(other.entries())
+    }
+}
+
+impl Eq for TopologyDiff {}
+
+// SAFETY: TopologyDiff exclusively owns its data and has no shared mutability
+unsafe impl Send for TopologyDiff {}
+
+// SAFETY: TopologyDiff has no shared mutability
+unsafe impl Sync for TopologyDiff {}
+
+/// One entry of a [`TopologyDiff`]
+///
+/// Objects are identified by their depth and logical index in the reference
+/// topology, i.e. the one that the diff is applied to.
+#[derive(Copy, Clone, Debug, Eq, Hash, PartialEq)]
+#[doc(alias = "hwloc_topology_diff_u")]
+pub enum TopologyDiffEntry<'diff> {
+    /// An object attribute was changed
+    #[doc(alias = "HWLOC_TOPOLOGY_DIFF_OBJ_ATTR")]
+    ObjectAttribute {
+        /// Depth of the modified object
+        object_depth: Depth,
+
+        /// Logical index of the modified object
+        object_index: usize,
+
+        /// Modification that was made
+        change: ObjectAttributeChange<'diff>,
+    },
+
+    /// The difference below this object is too complex to be represented
+    #[doc(alias = "HWLOC_TOPOLOGY_DIFF_TOO_COMPLEX")]
+    TooComplex {
+        /// Depth of the object below which differences were not checked
+        object_depth: Depth,
+
+        /// Logical index of the object below which differences were not
+        /// checked
+        object_index: usize,
+    },
+}
+//
+impl<'diff> TopologyDiffEntry<'diff> {
+    /// Decode a raw diff list element
+    ///
+    /// # Safety
+    ///
+    /// `raw` must be a valid hwloc diff list element
+    unsafe fn from_raw(raw: &'diff hwloc_topology_diff_u) -> Self {
+        /// Decode an object identifier
+        fn object_id(depth: c_int, index: c_uint) -> (Depth, usize) {
+            (
+                Depth::from_raw(depth).expect("Got invalid object depth from hwloc"),
+                int::expect_usize(index),
+            )
+        }
+
+        // SAFETY: All diff list elements start with the generic header
+        match unsafe { raw.generic.ty } {
+            HWLOC_TOPOLOGY_DIFF_OBJ_ATTR => {
+                // SAFETY: Union variant is given by the type field
+                let obj_attr = unsafe { &raw.obj_attr };
+                let (object_depth, object_index) =
+                    object_id(obj_attr.obj_depth, obj_attr.obj_index);
+                Self::ObjectAttribute {
+                    object_depth,
+                    object_index,
+                    // SAFETY: Per input precondition
+                    change: unsafe { ObjectAttributeChange::from_raw(&obj_attr.diff) },
+                }
+            }
+            HWLOC_TOPOLOGY_DIFF_TOO_COMPLEX => {
+                // SAFETY: Union variant is given by the type field
+                let too_complex = unsafe { &raw.too_complex };
+                let (object_depth, object_index) =
+                    object_id(too_complex.obj_depth, too_complex.obj_index);
+                Self::TooComplex {
+                    object_depth,
+                    object_index,
+                }
+            }
+            unknown => unreachable!("Got unknown topology diff type {unknown}"),
+        }
+    }
+}
+
+/// Object attribute modification within a [`TopologyDiffEntry`]
+#[derive(Copy, Clone, Debug, Eq, Hash, PartialEq)]
+#[doc(alias = "hwloc_topology_diff_obj_attr_u")]
+pub enum ObjectAttributeChange<'diff> {
+    /// The amount of memory local to the object was changed
+    #[doc(alias = "HWLOC_TOPOLOGY_DIFF_OBJ_ATTR_SIZE")]
+    LocalMemory {
+        /// Previous amount of local memory in bytes
+        old: u64,
+
+        /// New amount of local memory in bytes
+        new: u64,
+    },
+
+    /// The object name was changed
+    #[doc(alias = "HWLOC_TOPOLOGY_DIFF_OBJ_ATTR_NAME")]
+    Name {
+        /// Previous name, if any
+        old: Option<&'diff CStr>,
+
+        /// New name, if any
+        new: Option<&'diff CStr>,
+    },
+
+    /// The value of a textual info was changed
+    #[doc(alias = "HWLOC_TOPOLOGY_DIFF_OBJ_ATTR_INFO")]
+    Info {
+        /// Name of the info
+        name: &'diff CStr,
+
+        /// Previous value
+        old: Option<&'diff CStr>,
+
+        /// New value
+        new: Option<&'diff CStr>,
+    },
+}
+//
+impl<'diff> ObjectAttributeChange<'diff> {
+    /// Decode a raw object attribute modification
+    ///
+    /// # Safety
+    ///
+    /// `raw` must be a valid hwloc object attribute modification
+    unsafe fn from_raw(raw: &'diff hwloc_topology_diff_obj_attr_u) -> Self {
+        // SAFETY: All object attribute modifications start with the generic
+        //         header
+        match unsafe { raw.generic.ty } {
+            HWLOC_TOPOLOGY_DIFF_OBJ_ATTR_SIZE => {
+                // SAFETY: Union variant is given by the type field
+                let uint64 = unsafe { &raw.uint64 };
+                Self::LocalMemory {
+                    old: uint64.oldvalue,
+                    new: uint64.newvalue,
+                }
+            }
+            ty @ (HWLOC_TOPOLOGY_DIFF_OBJ_ATTR_NAME | HWLOC_TOPOLOGY_DIFF_OBJ_ATTR_INFO) => {
+                // SAFETY: Union variant is given by the type field
+                let string = unsafe { &raw.string };
+                // SAFETY: Strings are either null or valid C strings per input
+                //         precondition, and cannot be modified while borrowed
+                let (old, new) = unsafe {
+                    (
+                        ffi::deref_str(&string.oldvalue),
+                        ffi::deref_str(&string.newvalue),
+                    )
+                };
+                if ty == HWLOC_TOPOLOGY_DIFF_OBJ_ATTR_NAME {
+                    Self::Name { old, new }
+                } else {
+                    Self::Info {
+                        // SAFETY: Same as above
+                        name: unsafe { ffi::deref_str(&string.name) }
+                            .expect("Got info diff without an info name"),
+                        old,
+                        new,
+                    }
+                }
+            }
+            unknown => unreachable!("Got unknown object attribute diff type {unknown}"),
+        }
+    }
+}
+
+#[cfg(feature = "hwloc-2_3_0")]
+bitflags! {
+    /// Flags to be given to [`TopologyDiff::apply()`]
+    #[derive(Copy, Clone, Debug, Default, Eq, Hash, PartialEq)]
+    #[doc(alias = "hwloc_topology_diff_apply_flags_e")]
+    pub struct DiffApplyFlags: hwloc_topology_diff_apply_flags_e {
+        /// Apply the diff in reverse direction
+        #[doc(alias = "HWLOC_TOPOLOGY_DIFF_APPLY_REVERSE")]
+        const REVERSE = HWLOC_TOPOLOGY_DIFF_APPLY_REVERSE;
+    }
+}
+//
+#[cfg(feature = "hwloc-2_3_0")]
+crate::impl_arbitrary_for_bitflags!(DiffApplyFlags, hwloc_topology_diff_apply_flags_e);
+
+/// Error returned by [`TopologyDiff::apply()`]
+#[cfg(feature = "hwloc-2_3_0")]
+#[derive(Copy, Clone, Debug, Eq, Error, Hash, PartialEq)]
+pub enum ApplyDiffError {
+    /// The entry with the specified index does not match the topology, so the
+    /// diff could not be applied
+    #[error("topology diff entry #{0} does not match the topology")]
+    Mismatch(usize),
+
+    /// The diff contains differences that are too complex to be applied
+    #[error("topology diff is too complex to be applied")]
+    TooComplex,
+}
+
+/// Error returned by [`TopologyDiff::export_xml()`] and
+/// [`TopologyDiff::export_xml_file()`]
+#[derive(Copy, Clone, Debug, Eq, Error, Hash, PartialEq)]
+pub enum ExportDiffError {
+    /// The output file path is not suitable for hwloc consumption
+    #[error(transparent)]
+    BadPath(#[from] PathError),
+
+    /// The reference topology identifier contains NUL chars
+    #[error("topology diff reference name can't contain NUL chars")]
+    ContainsNul,
+
+    /// hwloc failed to export the diff
+    #[error(transparent)]
+    Hwloc(#[from] RawHwlocError),
+
+    /// The diff contains differences that are too complex to be exported
+    #[error("topology diff is too complex to be exported")]
+    TooComplex,
+}
+//
+impl From<NulError> for ExportDiffError {
+    fn from(NulError: NulError) -> Self {
+        Self::ContainsNul
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[allow(unused)]
+    use similar_asserts::assert_eq;
+    use static_assertions::{assert_impl_all, assert_not_impl_any};
+    use std::{
+        error::Error,
+        fmt::{
+            self, Binary, Debug, Display, LowerExp, LowerHex, Octal, Pointer, UpperExp, UpperHex,
+        },
+        hash::Hash,
+        io::{self, Read},
+        ops::Deref,
+        panic::UnwindSafe,
+    };
+    #[cfg(all(feature = "hwloc-2_3_0", not(windows)))]
+    use tempfile::NamedTempFile;
+
+    // Check that public types in this module keep implementing all expected
+    // traits, in the interest of detecting future semver-breaking changes
+    assert_impl_all!(TopologyDiff:
+        Debug, Drop, Eq, Send, Sized, Sync, Unpin
+    );
+    assert_not_impl_any!(TopologyDiff:
+        Binary, Clone, Default, Deref, Display, Hash, IntoIterator, LowerExp,
+        LowerHex, Octal, PartialOrd, Pointer, Read, UpperExp, UpperHex,
+        fmt::Write, io::Write
+    );
+    assert_impl_all!(TopologyDiffEntry<'static>:
+        Copy, Debug, Hash, Sized, Sync, Unpin, UnwindSafe
+    );
+    assert_not_impl_any!(TopologyDiffEntry<'static>:
+        Binary, Default, Deref, Display, Drop, IntoIterator, LowerExp, LowerHex,
+        Octal, PartialOrd, Pointer, Read, UpperExp, UpperHex, fmt::Write,
+        io::Write
+    );
+    assert_impl_all!(ObjectAttributeChange<'static>:
+        Copy, Debug, Hash, Sized, Sync, Unpin, UnwindSafe
+    );
+    assert_not_impl_any!(ObjectAttributeChange<'static>:
+        Binary, Default, Deref, Display, Drop, IntoIterator, LowerExp, LowerHex,
+        Octal, PartialOrd, Pointer, Read, UpperExp, UpperHex, fmt::Write,
+        io::Write
+    );
+    assert_impl_all!(ExportDiffError:
+        Copy, Error, From<NulError>, From<PathError>, From<RawHwlocError>,
+        Hash, Sized, Sync, Unpin, UnwindSafe
+    );
+    assert_not_impl_any!(ExportDiffError:
+        Binary, Default, Deref, Drop, IntoIterator, LowerExp, LowerHex, Octal,
+        PartialOrd, Pointer, Read, UpperExp, UpperHex, fmt::Write, io::Write
+    );
+    #[cfg(feature = "hwloc-2_3_0")]
+    assert_impl_all!(ApplyDiffError:
+        Copy, Error, Hash, Sized, Sync, Unpin, UnwindSafe
+    );
+    #[cfg(feature = "hwloc-2_3_0")]
+    assert_not_impl_any!(ApplyDiffError:
+        Binary, Default, Deref, Drop, IntoIterator, LowerExp, LowerHex, Octal,
+        PartialOrd, Pointer, Read, UpperExp, UpperHex, fmt::Write, io::Write
+    );
+
+    /// Diffing a topology with itself should produce an empty diff
+    #[test]
+    fn self_diff() {
+        let topology = Topology::test_instance();
+        let diff = topology.diff(topology);
+        assert!(diff.is_empty());
+        assert!(!diff.is_too_complex());
+        assert_eq!(diff.entries().count(), 0);
+        assert_eq!(format!("{diff:?}"), "[]");
+
+        let xml = diff.export_xml(topology, Some("reference")).unwrap();
+        let (loaded, refname) = TopologyDiff::from_xml(&xml).unwrap();
+        assert_eq!(loaded, diff);
+        assert_eq!(refname.as_deref(), Some("reference"));
+    }
+
+    /// Invalid diff XML should be rejected
+    #[test]
+    fn bad_xml() {
+        assert_eq!(
+            TopologyDiff::from_xml("<not a diff>").unwrap_err(),
+            StringInputError::Invalid
+        );
+        assert_eq!(
+            TopologyDiff::from_xml("\0").unwrap_err(),
+            StringInputError::ContainsNul
+        );
+        let topology = Topology::test_instance();
+        assert_eq!(
+            topology
+                .diff(topology)
+                .export_xml(topology, Some("\0"))
+                .unwrap_err(),
+            ExportDiffError::ContainsNul
+        );
+    }
+
+    /// Changing an info value should produce an applicable diff
+    #[cfg(all(feature = "hwloc-2_3_0", not(windows)))]
+    #[test]
+    fn info_diff() {
+        // Find an object with infos, if any
+        let reference = Topology::test_instance();
+        let Some((depth, index, info)) = reference
+            .objects()
+            .find_map(|obj| Some((obj.depth(), obj.logical_index(), obj.infos().first()?)))
+        else {
+            return;
+        };
+        let info_name = info.name().to_str().unwrap().to_owned();
+        let old_value = info.value().to_owned();
+
+        // Modify its value in a copy of the topology
+        let mut modified = reference.clone();
+        modified.edit(|editor| {
+            editor
+                .replace_object_info(
+                    |topology| topology.objects_at_depth(depth).nth(index).unwrap(),
+                    &info_name,
+                    "hwlocality-diff-test",
+                )
+                .unwrap();
+        });
+
+        // Check the diff
+        let diff = reference.diff(&modified);
+        assert!(!diff.is_too_complex());
+        let entries = diff.entries().collect::<Vec<_>>();
+        assert_eq!(entries.len(), 1);
+        let TopologyDiffEntry::ObjectAttribute {
+            object_depth,
+            object_index,
+            change: ObjectAttributeChange::Info { name, old, new },
+        } = entries[0]
+        else {
+            panic!("Unexpected diff entry {:?}", entries[0]);
+        };
+        assert_eq!(object_depth, depth);
+        assert_eq!(object_index, index);
+        assert_eq!(name.to_str().unwrap(), info_name);
+        assert_eq!(old, Some(old_value.as_c_str()));
+        assert_eq!(new.unwrap().to_str().unwrap(), "hwlocality-diff-test");
+
+        // Apply it forward and backward
+        let mut patched = reference.clone();
+        diff.apply(&mut patched, DiffApplyFlags::empty()).unwrap();
+        assert!(patched.diff(&modified).is_empty());
+        diff.apply(&mut patched, DiffApplyFlags::REVERSE).unwrap();
+        assert!(patched.diff(reference).is_empty());
+        assert_eq!(
+            diff.apply(&mut patched, DiffApplyFlags::REVERSE),
+            Err(ApplyDiffError::Mismatch(0))
+        );
+
+        // Round trip through XML
+        let xml = diff.export_xml(reference, None).unwrap();
+        let (loaded, refname) = TopologyDiff::from_xml(&xml).unwrap();
+        assert_eq!(loaded, diff);
+        assert_eq!(refname, None);
+
+        let tmp = NamedTempFile::new().unwrap();
+        diff.export_xml_file(Some("reference"), tmp.path()).unwrap();
+        let (loaded, refname) = TopologyDiff::from_xml_file(tmp.path()).unwrap();
+        assert_eq!(loaded, diff);
+        assert_eq!(refname.as_deref(), Some("reference"));
+    }
+}
@@ -41,7 +41,9 @@ use crate::{
         transparent::{AsInner, AsNewtype},
     },
     memory::nodeset::NodeSet,
-    object::{attributes::GroupAttributes, types::ObjectType, TopologyObject},
+    object::{
+        attributes::GroupAttributes, handle::ObjectHandle, types::ObjectType, TopologyObject,
+    },
     topology::{builder::TypeFilter, Topology},
 };
 use bitflags::bitflags;
@@ -58,7 +60,7 @@ use libc::{EINVAL, ENOMEM, ENOSYS};
 use similar_asserts::assert_eq;
 use std::{
     fmt::{self, Debug, Write},
-    panic::{AssertUnwindSafe, UnwindSafe},
+    panic::{AssertUnwindSafe, RefUnwindSafe, UnwindSafe},
     ptr::{self, NonNull},
 };
 use thiserror::Error;
@@ -83,17 +85,182 @@ impl Topology {
     /// efficient topology editing emerged, the right thing to do would
     /// probably be to set up an alternate hwloc Rust binding optimized for
     /// that, sharing as much code as possible with hwlocality.
+    ///
+    /// Once editing is over, the hooks registered with
+    /// [`Topology::add_edit_hook()`] are notified of the changes, if any.
     #[doc(alias = "hwloc_topology_refresh")]
     pub fn edit<R>(&mut self, edit: impl UnwindSafe + FnOnce(&mut TopologyEditor<'_>) -> R) -> R {
+        self.edit_impl(false, edit)
+    }
+
+    /// Implementation of [`Topology::edit()`]
+    ///
+    /// `stale` must be set if the topology has not been refreshed since it
+    /// was last modified, as is the case of the output of
+    /// [`Topology::duplicate()`].
+    fn edit_impl<R>(
+        &mut self,
+        stale: bool,
+        edit: impl UnwindSafe + FnOnce(&mut TopologyEditor<'_>) -> R,
+    ) -> R {
         // Set up topology editing
         let mut editor = TopologyEditor::new(self);
-        let mut editor = AssertUnwindSafe(&mut editor);
+        editor.2 = stale;
+        let mut editor_ref = AssertUnwindSafe(&mut editor);
 
         // Run the user-provided edit callback, catching panics
-        let result = std::panic::catch_unwind(move || edit(&mut editor));
+        let result = std::panic::catch_unwind(move || edit(&mut editor_ref));
+        let changes = editor.1;
+        let stale = editor.2;
+
+        // Force eager evaluation of all caches if the topology was modified
+        // since they were last evaluated, then notify edit hooks
+        if stale {
+            self.refresh();
+        }
+        self.run_edit_hooks(changes);
+
+        // Return user callback result or resume unwinding as appropriate
+        match result {
+            Ok(result) => result,
+            Err(e) => std::panic::resume_unwind(e),
+        }
+    }
+
+    /// Clone this topology, then restrict the clone to a CPU set or nodeset
+    ///
+    /// This is a shortcut for cloning the topology and calling
+    /// [`TopologyEditor::restrict()`] on the clone within [`Topology::edit()`],
+    /// which comes in handy when repeatedly simulating sub-partitions of a
+    /// machine. See the documentation of [`TopologyEditor::restrict()`] for
+    /// more information about the semantics of `set` and `flags`.
+    ///
+    /// This is faster than cloning and restricting the topology in two steps,
+    /// as the lazily computed caches of the clone are only evaluated once,
+    /// after restriction.
+    ///
+    /// The original topology is left untouched, and like other clones, the
+    /// restricted clone does not inherit the hooks that were registered with
+    /// [`Topology::add_edit_hook()`].
+    ///
+    /// # Errors
+    ///
+    /// It is an error to attempt to remove all CPUs or NUMA nodes from a
+    /// topology using a `set` that has no intersection with the relevant
+    /// topology set. A [`ParameterError`] will be returned in this case.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use hwlocality::{object::types::ObjectType, topology::Topology};
+    /// let topology = Topology::test_instance();
+    /// let first_core = topology.objects_with_type(ObjectType::Core).next().unwrap();
+    /// let cpuset = first_core.cpuset().unwrap();
+    /// let restricted = topology.restricted_clone(&cpuset, Default::default())?;
+    /// assert_eq!(restricted.objects_with_type(ObjectType::Core).count(), 1);
+    /// assert_eq!(restricted.cpuset(), cpuset);
+    /// # Ok::<_, eyre::Report>(())
+    /// ```
+    #[doc(alias = "duplicate_restricted")]
+    pub fn restricted_clone<Set: SpecializedBitmap>(
+        &self,
+        set: &Set,
+        flags: RestrictFlags,
+    ) -> Result<Self, ParameterError<Set::Owned>> {
+        let set = AssertUnwindSafe(set.borrow());
+        // No need to refresh the duplicate here: it is flagged as stale, so
+        // the editor refreshes it when restrict() first queries it.
+        let mut clone = self.duplicate();
+        clone.edit_impl(true, move |editor| editor.restrict(*set, flags))?;
+        Ok(clone)
+    }
 
-        // Force eager evaluation of all caches
+    /// Register a callback to be invoked after this topology is edited
+    ///
+    /// The callback is invoked at the end of every [`Topology::edit()`]
+    /// session that attempted to change the topology, with the refreshed
+    /// topology and a summary of the attempted changes. This lets you
+    /// reliably invalidate application-side caches that depend on the
+    /// topology.
+    ///
+    /// Hooks are not copied when the topology is cloned. If a hook panics, the
+    /// panic is propagated to the caller of the editing function, and all
+    /// hooks of this topology are unregistered.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use hwlocality::topology::{Topology, editor::TopologyChanges};
+    /// # use std::sync::{Arc, atomic::{AtomicBool, Ordering}};
+    /// let mut topology = Topology::test_instance().clone();
+    /// let cache_valid = Arc::new(AtomicBool::new(true));
+    /// let hook_cache_valid = cache_valid.clone();
+    /// topology.add_edit_hook(move |_topology, changes| {
+    ///     if changes.contains(TopologyChanges::RESTRICTED) {
+    ///         hook_cache_valid.store(false, Ordering::Relaxed);
+    ///     }
+    /// });
+    /// let cpuset = topology.cpuset().clone_target();
+    /// topology.edit(|editor| editor.restrict(&cpuset, Default::default()))?;
+    /// assert!(!cache_valid.load(Ordering::Relaxed));
+    /// # Ok::<_, eyre::Report>(())
+    /// ```
+    pub fn add_edit_hook(
+        &mut self,
+        hook: impl FnMut(&Self, TopologyChanges) + Send + 'static,
+    ) -> EditHookId {
+        self.edit_hooks.add(Box::new(hook))
+    }
+
+    /// Unregister a callback that was registered with
+    /// [`Topology::add_edit_hook()`]
+    ///
+    /// Returns `true` if the callback was registered and has been removed.
+    pub fn remove_edit_hook(&mut self, id: EditHookId) -> bool {
+        self.edit_hooks.remove(id)
+    }
+
+    /// Notify edit hooks of a set of changes to the topology, if any
+    ///
+    /// Must be called after refreshing the topology.
+    fn run_edit_hooks(&mut self, changes: TopologyChanges) {
+        if changes.is_empty() {
+            return;
+        }
+        let mut hooks = std::mem::take(&mut self.edit_hooks);
+        hooks.run(self, changes);
+        self.edit_hooks = hooks;
+    }
+
+    /// Give C code temporary mutable access to the underlying hwloc topology
+    ///
+    /// This is meant for interoperability with other C libraries which consume
+    /// and may modify hwloc topologies, such as MPI implementations or OpenMP
+    /// runtimes. If you only need read-only access, use [`Topology::as_raw()`].
+    ///
+    /// Once the callback returns, or unwinds, the topology is refreshed and
+    /// checked for consistency (the latter only in debug builds) like at the
+    /// end of [`Topology::edit()`], so that it can safely be used through
+    /// `&self` again. Edit hooks are then notified that any kind of change
+    /// may have happened.
+    ///
+    /// While the callback cannot do anything harmful without using `unsafe`,
+    /// beware that the following actions will result in undefined behavior:
+    ///
+    /// - Destroying the topology or using the pointer after the callback
+    ///   returns.
+    /// - Modifying the topology in a way that leaves it in an inconsistent
+    ///   state that `hwloc_topology_refresh()` can't recover from.
+    #[doc(alias = "hwloc_topology_refresh")]
+    pub fn with_raw<R>(&mut self, f: impl UnwindSafe + FnOnce(*mut hwloc_topology) -> R) -> R {
+        // Run the user-provided callback, catching panics
+        let ptr = self.as_mut_ptr();
+        let result = std::panic::catch_unwind(move || f(ptr));
+
+        // Restore the topology invariants, then notify edit hooks of all
+        // possible changes since we can't tell what happened
         self.refresh();
+        self.run_edit_hooks(TopologyChanges::all());
 
         // Return user callback result or resume unwinding as appropriate
         match result {
@@ -130,6 +297,9 @@ impl Topology {
             //         - hwloc ops are trusted not to modify *const parameters
             unsafe { hwlocality_sys::hwloc_topology_check(self.as_ptr()) }
         }
+
+        // Objects may have been added or removed
+        self.index_objects();
     }
 }
 
@@ -161,27 +331,45 @@ impl Topology {
 // module. Instead, functionality which is very strongly related to one other
 // code module is implemented in that module, leaving the editor module focused
 // on basic lifecycle and cross-cutting issues.
+//
+// In addition to the topology, the editor records the changes that were
+// attempted so far, and whether the topology may have been modified since its
+// caches were last refreshed. The latter must be set by any access to the
+// topology that may modify it, i.e. `topology_mut()` and `topology_mut_ptr()`.
 #[derive(Debug)]
-pub struct TopologyEditor<'topology>(&'topology mut Topology);
+pub struct TopologyEditor<'topology>(&'topology mut Topology, TopologyChanges, bool);
 
 /// # General-purpose utilities
 impl<'topology> TopologyEditor<'topology> {
     /// Wrap an `&mut Topology` into a topology editor
     pub(crate) fn new(topology: &'topology mut Topology) -> Self {
-        Self(topology)
+        Self(topology, TopologyChanges::empty(), false)
+    }
+
+    /// Record that a certain kind of change is about to be attempted
+    pub(crate) fn record_changes(&mut self, changes: TopologyChanges) {
+        self.1 |= changes;
     }
 
     /// Get a shared reference to the inner Topology
     ///
-    /// This requires rebuilding inner caches, which can be costly. Prefer
+    /// If the topology was modified since the last call to this method, this
+    /// requires rebuilding inner caches, which can be costly. Prefer
     /// accessing the topology before or after editing it if possible.
     pub fn topology(&mut self) -> &Topology {
-        self.topology_mut().refresh();
-        self.topology_mut()
+        if self.2 {
+            self.0.refresh();
+            self.2 = false;
+        }
+        self.0
     }
 
     /// Get a mutable reference to the inner Topology
+    ///
+    /// The topology is assumed to be modified, so its caches will be rebuilt
+    /// on the next call to [`TopologyEditor::topology()`].
     pub(crate) fn topology_mut(&mut self) -> &mut Topology {
+        self.2 = true;
         self.0
     }
 
@@ -285,6 +473,7 @@ impl<'topology> TopologyEditor<'topology> {
             }
 
             // Apply requested restriction
+            self_.record_changes(TopologyChanges::RESTRICTED);
             // SAFETY: - Topology is trusted to contain a valid ptr (type invariant)
             //         - hwloc ops are trusted to keep *mut parameters in a
             //           valid state unless stated otherwise
@@ -344,6 +533,10 @@ impl<'topology> TopologyEditor<'topology> {
     /// Removing objects from a topology should rather be performed with
     /// [`TopologyEditor::restrict()`].
     ///
+    /// The [`allow_all()`], [`allow_local_restrictions()`] and
+    /// [`allow_custom()`] shortcuts let you make the same changes without
+    /// spelling out an [`AllowSet`].
+    ///
     /// # Errors
     ///
     /// - [`EmptyCustom`] if an `AllowSet::Custom` does not do anything because
@@ -359,6 +552,9 @@ impl<'topology> TopologyEditor<'topology> {
     /// [`InvalidCpuset`]: AllowSetError::InvalidCpuset
     /// [`InvalidNodeset`]: AllowSetError::InvalidNodeset
     /// [`Unsupported`]: AllowSetError::Unsupported
+    /// [`allow_all()`]: Self::allow_all()
+    /// [`allow_custom()`]: Self::allow_custom()
+    /// [`allow_local_restrictions()`]: Self::allow_local_restrictions()
     #[doc(alias = "hwloc_topology_allow")]
     pub fn allow(&mut self, allow_set: AllowSet<'_>) -> Result<(), HybridError<AllowSetError>> {
         // Convert AllowSet into a valid `hwloc_topology_allow` configuration
@@ -394,6 +590,7 @@ impl<'topology> TopologyEditor<'topology> {
         };
 
         // Call hwloc
+        self.record_changes(TopologyChanges::ALLOWED_SETS);
         // SAFETY: - Topology is trusted to contain a valid ptr (type invariant)
         //         - hwloc ops are trusted to keep *mut parameters in a
         //           valid state unless stated otherwise
@@ -415,6 +612,70 @@ impl<'topology> TopologyEditor<'topology> {
         }
     }
 
+    /// Mark all objects of the topology as allowed
+    ///
+    /// This is a shortcut for [`allow(AllowSet::All)`](Self::allow()), see
+    /// that function for more information.
+    ///
+    /// # Errors
+    ///
+    /// - [`Unsupported`] if this operation is not supported by the host
+    ///   operating system.
+    ///
+    /// [`Unsupported`]: AllowSetError::Unsupported
+    #[doc(alias = "HWLOC_ALLOW_FLAG_ALL")]
+    pub fn allow_all(&mut self) -> Result<(), HybridError<AllowSetError>> {
+        self.allow(AllowSet::All)
+    }
+
+    /// Only allow the objects that are available to the current process
+    ///
+    /// This is a shortcut for
+    /// [`allow(AllowSet::LocalRestrictions)`](Self::allow()), see that
+    /// function for more information.
+    ///
+    /// # Errors
+    ///
+    /// - [`Unsupported`] if this operation is not supported by the host
+    ///   operating system.
+    ///
+    /// [`Unsupported`]: AllowSetError::Unsupported
+    #[doc(alias = "HWLOC_ALLOW_FLAG_LOCAL_RESTRICTIONS")]
+    pub fn allow_local_restrictions(&mut self) -> Result<(), HybridError<AllowSetError>> {
+        self.allow(AllowSet::LocalRestrictions)
+    }
+
+    /// Allow a custom set of PUs and/or NUMA nodes
+    ///
+    /// This is a shortcut for [`allow(AllowSet::Custom { cpuset, nodeset
+    /// })`](Self::allow()), see that function and [`AllowSet::Custom`] for
+    /// more information. At least one of `cpuset` and `nodeset` must be
+    /// specified, and the allowed sets that are not specified are left
+    /// unchanged.
+    ///
+    /// # Errors
+    ///
+    /// - [`EmptyCustom`] if neither `cpuset` nor `nodeset` is specified.
+    /// - [`InvalidCpuset`] if applying `cpuset` would amount to disallowing
+    ///   all CPUs from the topology.
+    /// - [`InvalidNodeset`] if applying `nodeset` would amount to disallowing
+    ///   all NUMA nodes from the topology.
+    /// - [`Unsupported`] if this operation is not supported by the host
+    ///   operating system.
+    ///
+    /// [`EmptyCustom`]: AllowSetError::EmptyCustom
+    /// [`InvalidCpuset`]: AllowSetError::InvalidCpuset
+    /// [`InvalidNodeset`]: AllowSetError::InvalidNodeset
+    /// [`Unsupported`]: AllowSetError::Unsupported
+    #[doc(alias = "HWLOC_ALLOW_FLAG_CUSTOM")]
+    pub fn allow_custom(
+        &mut self,
+        cpuset: Option<&CpuSet>,
+        nodeset: Option<&NodeSet>,
+    ) -> Result<(), HybridError<AllowSetError>> {
+        self.allow(AllowSet::Custom { cpuset, nodeset })
+    }
+
     /// Add more structure to the topology by creating an intermediate [`Group`]
     ///
     /// Sibling normal objects below a common parent object can be grouped to
@@ -592,12 +853,121 @@ impl<'topology> TopologyEditor<'topology> {
         }
 
         // Create group object
+        self.record_changes(TopologyChanges::OBJECTS_INSERTED);
         let mut group = AllocatedGroup::new(self).map_err(HybridError::Hwloc)?;
         group.add_children(find_parent, child_filter)?;
         group.configure_merging(dont_merge);
         group.insert().map_err(HybridError::Hwloc)
     }
 
+    /// Group some objects under a new [`Group`] object with a descriptive
+    /// subtype
+    ///
+    /// This exposes [`TopologyEditor::insert_group_over_unchecked()`] as a
+    /// safe method on operating systems which aren't known to facilitate
+    /// mixing and matching libc versions between an application and its
+    /// dependencies.
+    ///
+    /// [`Group`]: ObjectType::Group
+    #[allow(clippy::missing_errors_doc)]
+    #[cfg(all(feature = "hwloc-2_3_0", not(windows)))]
+    pub fn insert_group_over(
+        &mut self,
+        dont_merge: bool,
+        find_members: impl FnOnce(&Topology) -> Vec<&TopologyObject>,
+        subtype: &str,
+    ) -> Result<InsertedGroup<'topology>, HybridError<InsertGroupError>> {
+        // SAFETY: Underlying OS is assumed not to ergonomically encourage
+        //         unsafe multi-libc linkage
+        unsafe { self.insert_group_over_unchecked(dont_merge, find_members, subtype) }
+    }
+
+    /// Group some objects under a new [`Group`] object with a descriptive
+    /// subtype
+    ///
+    /// Unlike [`insert_group_object()`], which selects group members amongst
+    /// the children of a single parent object, this method directly takes the
+    /// list of objects that the group should cover, as selected by the
+    /// `find_members` callback. The group will cover the union of the cpusets
+    /// and nodesets of these objects, and hwloc will insert it wherever this
+    /// union fits in the topology tree.
+    ///
+    /// Members must be normal or memory objects. As with
+    /// [`insert_group_object()`], hwloc may add extra objects to the group in
+    /// order to honor its group consistency rules, and may return an existing
+    /// object that is equivalent to the proposed group instead of creating a
+    /// new one, unless `dont_merge` is set. Please refer to the documentation
+    /// of [`insert_group_object()`] for more details about these rules, and
+    /// about the identifier invalidation that group insertion entails.
+    ///
+    /// If a new group is created, its subtype is set to `subtype`, so that
+    /// `lstopo` displays this string instead of "Group" as its type name. If
+    /// an existing object is returned instead, it is left untouched.
+    ///
+    /// # Safety
+    ///
+    /// This method is only safe to call if you can guarantee that your
+    /// application links against the same libc/CRT as hwloc. See
+    /// [`TopologyObject::set_subtype_unchecked()`] for more information.
+    ///
+    /// # Errors
+    ///
+    /// - [`FilteredOut`] if one attempts to create a group in a topology where
+    ///   they are filtered out using [`TypeFilter::KeepNone`].
+    /// - [`BadMemberType`] if one of the designated members is neither a
+    ///   normal nor a memory object.
+    /// - [`ForeignMember`] if one of the designated members does not belong
+    ///   to the topology that is being edited.
+    /// - [`Empty`] if `find_members` did not select any object.
+    /// - [`SubtypeContainsNul`] if `subtype` contains NUL chars.
+    ///
+    /// [`BadMemberType`]: InsertGroupError::BadMemberType
+    /// [`Empty`]: InsertGroupError::Empty
+    /// [`FilteredOut`]: InsertGroupError::FilteredOut
+    /// [`ForeignMember`]: InsertGroupError::ForeignMember
+    /// [`Group`]: ObjectType::Group
+    /// [`insert_group_object()`]: Self::insert_group_object()
+    /// [`SubtypeContainsNul`]: InsertGroupError::SubtypeContainsNul
+    #[cfg(feature = "hwloc-2_3_0")]
+    #[doc(alias = "hwloc_obj_add_other_obj_sets")]
+    #[doc(alias = "hwloc_topology_insert_group_object")]
+    pub unsafe fn insert_group_over_unchecked(
+        &mut self,
+        dont_merge: bool,
+        find_members: impl FnOnce(&Topology) -> Vec<&TopologyObject>,
+        subtype: &str,
+    ) -> Result<InsertedGroup<'topology>, HybridError<InsertGroupError>> {
+        // Check subtype upfront, so that we never insert a group that we
+        // cannot subsequently annotate
+        if subtype.contains('\0') {
+            return Err(InsertGroupError::SubtypeContainsNul.into());
+        }
+
+        // Check type filter
+        let group_filter = self
+            .topology()
+            .type_filter(ObjectType::Group)
+            .map_err(HybridError::Hwloc)?;
+        if group_filter == TypeFilter::KeepNone {
+            return Err(InsertGroupError::FilteredOut.into());
+        }
+
+        // Create and insert group object
+        self.record_changes(TopologyChanges::OBJECTS_INSERTED);
+        let mut group = AllocatedGroup::new(self).map_err(HybridError::Hwloc)?;
+        group.add_members(find_members)?;
+        group.configure_merging(dont_merge);
+        let mut inserted = group.insert().map_err(HybridError::Hwloc)?;
+
+        // Annotate newly created group
+        if let InsertedGroup::New(group) = &mut inserted {
+            // SAFETY: Per function precondition
+            unsafe { group.set_subtype_unchecked(subtype) }
+                .expect("subtype was checked to be free of NUL chars");
+        }
+        Ok(inserted)
+    }
+
     /// Add a [`Misc`] object as a leaf of the topology
     ///
     /// A new [`Misc`] object will be created and inserted into the topology as
@@ -709,11 +1079,366 @@ impl<'topology> TopologyEditor<'topology> {
             parent.into()
         };
 
+        self.record_changes(TopologyChanges::OBJECTS_INSERTED);
         // SAFETY: parent comes from this topology, source ref has been dropped
         unsafe { polymorphized(self, name, parent) }
     }
+
+    /// Add a [`Misc`] object as a leaf of the topology, and get a stable handle
+    /// to it
+    ///
+    /// This works like [`TopologyEditor::insert_misc_object()`], but instead
+    /// of a reference to the new object, which cannot outlive the topology
+    /// edit, it returns an [`ObjectHandle`]. This handle can be stored by the
+    /// application, e.g. to keep track of which Misc object represents which
+    /// application-level entity, and later be [resolved] back into the object
+    /// once the edit is over.
+    ///
+    /// # Errors
+    ///
+    /// Same as [`TopologyEditor::insert_misc_object()`].
+    ///
+    /// [`Misc`]: ObjectType::Misc
+    /// [resolved]: ObjectHandle::resolve()
+    pub fn insert_misc_object_handle(
+        &mut self,
+        name: &str,
+        find_parent: impl FnOnce(&Topology) -> &TopologyObject,
+    ) -> Result<ObjectHandle, HybridError<InsertMiscError>> {
+        self.insert_misc_object(name, find_parent)
+            .map(|obj| obj.handle())
+    }
 }
 
+/// # Object infos
+///
+/// hwloc only provides a way to append (key, value) info pairs to objects,
+/// which makes it impossible to correct a previously added annotation without
+/// reloading the topology. The following methods work around this limitation
+/// by directly rewriting the info array of the target object, so that the
+/// changes are visible to all hwloc consumers, including XML export.
+///
+/// The name and subtype of objects can be changed in the same way.
+#[cfg(feature = "hwloc-2_3_0")]
+impl<'topology> TopologyEditor<'topology> {
+    /// Set the value of the `name` info of the object selected by
+    /// `find_object`, replacing any previous value
+    ///
+    /// This exposes [`TopologyEditor::replace_object_info_unchecked()`] as a
+    /// safe method on operating systems which aren't known to facilitate mixing
+    /// and matching libc versions between an application and its dependencies.
+    #[allow(clippy::missing_errors_doc)]
+    #[cfg(not(windows))]
+    pub fn replace_object_info(
+        &mut self,
+        find_object: impl FnOnce(&Topology) -> &TopologyObject,
+        name: &str,
+        value: &str,
+    ) -> Result<(), HybridError<EditInfoError>> {
+        // SAFETY: Underlying OS is assumed not to ergonomically encourage
+        //         unsafe multi-libc linkage
+        unsafe { self.replace_object_info_unchecked(find_object, name, value) }
+    }
+
+    /// Set the value of the `name` info of the object selected by
+    /// `find_object`, replacing any previous value
+    ///
+    /// See [`TopologyObject::replace_info_unchecked()`] for a description of
+    /// how duplicate and missing infos are handled.
+    ///
+    /// # Safety
+    ///
+    /// This method is only safe to call if you can guarantee that your
+    /// application links against the same libc/CRT as hwloc. See
+    /// [`TopologyObject::set_subtype_unchecked()`] for more information.
+    ///
+    /// # Errors
+    ///
+    /// - [`ForeignObject`] if the `&TopologyObject` returned by `find_object`
+    ///   does not belong to this [`Topology`].
+    /// - [`NulError`] if `name` or `value` contains NUL chars.
+    ///
+    /// [`ForeignObject`]: EditInfoError::ForeignObject
+    /// [`NulError`]: EditInfoError::NulError
+    pub unsafe fn replace_object_info_unchecked(
+        &mut self,
+        find_object: impl FnOnce(&Topology) -> &TopologyObject,
+        name: &str,
+        value: &str,
+    ) -> Result<(), HybridError<EditInfoError>> {
+        self.record_changes(TopologyChanges::OBJECT_METADATA);
+        let object = self
+            .find_object_mut(find_object)
+            .map_err(|e| HybridError::Rust(e.into()))?;
+        // SAFETY: Per input precondition
+        unsafe { object.replace_info_unchecked(name, value) }.map_err(|e| match e {
+            HybridError::Rust(e) => HybridError::Rust(e.into()),
+            HybridError::Hwloc(e) => HybridError::Hwloc(e),
+        })
+    }
+
+    /// Remove all infos called `name` from the object selected by
+    /// `find_object`, returning how many infos were removed
+    ///
+    /// This exposes [`TopologyEditor::remove_object_info_unchecked()`] as a
+    /// safe method on operating systems which aren't known to facilitate mixing
+    /// and matching libc versions between an application and its dependencies.
+    #[allow(clippy::missing_errors_doc)]
+    #[cfg(not(windows))]
+    pub fn remove_object_info(
+        &mut self,
+        find_object: impl FnOnce(&Topology) -> &TopologyObject,
+        name: &str,
+    ) -> Result<usize, ForeignObjectError> {
+        // SAFETY: Underlying OS is assumed not to ergonomically encourage
+        //         unsafe multi-libc linkage
+        unsafe { self.remove_object_info_unchecked(find_object, name) }
+    }
+
+    /// Remove all infos called `name` from the object selected by
+    /// `find_object`, returning how many infos were removed
+    ///
+    /// # Safety
+    ///
+    /// This method is only safe to call if you can guarantee that your
+    /// application links against the same libc/CRT as hwloc. See
+    /// [`TopologyObject::set_subtype_unchecked()`] for more information.
+    ///
+    /// # Errors
+    ///
+    /// - [`ForeignObjectError`] if the `&TopologyObject` returned by
+    ///   `find_object` does not belong to this [`Topology`].
+    pub unsafe fn remove_object_info_unchecked(
+        &mut self,
+        find_object: impl FnOnce(&Topology) -> &TopologyObject,
+        name: &str,
+    ) -> Result<usize, ForeignObjectError> {
+        self.record_changes(TopologyChanges::OBJECT_METADATA);
+        let object = self.find_object_mut(find_object)?;
+        // SAFETY: Per input precondition
+        Ok(unsafe { object.remove_info_unchecked(name) })
+    }
+
+    // --- Object names and subtypes ---
+
+    /// Set the subtype of the object selected by `find_object`
+    ///
+    /// This exposes [`TopologyEditor::set_object_subtype_unchecked()`] as a
+    /// safe method on operating systems which aren't known to facilitate mixing
+    /// and matching libc versions between an application and its dependencies.
+    #[allow(clippy::missing_errors_doc)]
+    #[cfg(not(windows))]
+    pub fn set_object_subtype(
+        &mut self,
+        find_object: impl FnOnce(&Topology) -> &TopologyObject,
+        subtype: &str,
+    ) -> Result<(), EditInfoError> {
+        // SAFETY: Underlying OS is assumed not to ergonomically encourage
+        //         unsafe multi-libc linkage
+        unsafe { self.set_object_subtype_unchecked(find_object, subtype) }
+    }
+
+    /// Set the subtype of the object selected by `find_object`
+    ///
+    /// This works like [`TopologyObject::set_subtype_unchecked()`], but does
+    /// not require you to get an `&mut TopologyObject` first. Like other
+    /// object annotations, the new subtype is visible to all hwloc consumers,
+    /// including XML export.
+    ///
+    /// # Safety
+    ///
+    /// This method is only safe to call if you can guarantee that your
+    /// application links against the same libc/CRT as hwloc. See
+    /// [`TopologyObject::set_subtype_unchecked()`] for more information.
+    ///
+    /// # Errors
+    ///
+    /// - [`ForeignObject`] if the `&TopologyObject` returned by `find_object`
+    ///   does not belong to this [`Topology`].
+    /// - [`NulError`] if `subtype` contains NUL chars.
+    ///
+    /// [`ForeignObject`]: EditInfoError::ForeignObject
+    /// [`NulError`]: EditInfoError::NulError
+    #[doc(alias = "hwloc_obj::subtype")]
+    pub unsafe fn set_object_subtype_unchecked(
+        &mut self,
+        find_object: impl FnOnce(&Topology) -> &TopologyObject,
+        subtype: &str,
+    ) -> Result<(), EditInfoError> {
+        self.record_changes(TopologyChanges::OBJECT_METADATA);
+        let object = self.find_object_mut(find_object)?;
+        // SAFETY: Per input precondition
+        Ok(unsafe { object.set_subtype_unchecked(subtype) }?)
+    }
+
+    /// Set the name of the object selected by `find_object`
+    ///
+    /// This exposes [`TopologyEditor::set_object_name_unchecked()`] as a safe
+    /// method on operating systems which aren't known to facilitate mixing and
+    /// matching libc versions between an application and its dependencies.
+    #[allow(clippy::missing_errors_doc)]
+    #[cfg(not(windows))]
+    pub fn set_object_name(
+        &mut self,
+        find_object: impl FnOnce(&Topology) -> &TopologyObject,
+        name: &str,
+    ) -> Result<(), EditInfoError> {
+        // SAFETY: Underlying OS is assumed not to ergonomically encourage
+        //         unsafe multi-libc linkage
+        unsafe { self.set_object_name_unchecked(find_object, name) }
+    }
+
+    /// Set the name of the object selected by `find_object`
+    ///
+    /// The new name is visible to all hwloc consumers, including XML export.
+    /// Beware that the names of [`Misc`] objects are supposed to be unique
+    /// across the topology, as explained in the documentation of
+    /// [`TopologyEditor::insert_misc_object()`].
+    ///
+    /// # Safety
+    ///
+    /// This method is only safe to call if you can guarantee that your
+    /// application links against the same libc/CRT as hwloc. See
+    /// [`TopologyObject::set_subtype_unchecked()`] for more information.
+    ///
+    /// # Errors
+    ///
+    /// - [`ForeignObject`] if the `&TopologyObject` returned by `find_object`
+    ///   does not belong to this [`Topology`].
+    /// - [`NulError`] if `name` contains NUL chars.
+    ///
+    /// [`ForeignObject`]: EditInfoError::ForeignObject
+    /// [`Misc`]: ObjectType::Misc
+    /// [`NulError`]: EditInfoError::NulError
+    #[doc(alias = "hwloc_obj::name")]
+    pub unsafe fn set_object_name_unchecked(
+        &mut self,
+        find_object: impl FnOnce(&Topology) -> &TopologyObject,
+        name: &str,
+    ) -> Result<(), EditInfoError> {
+        self.record_changes(TopologyChanges::OBJECT_METADATA);
+        let object = self.find_object_mut(find_object)?;
+        // SAFETY: Per input precondition
+        Ok(unsafe { object.set_name_unchecked(name) }?)
+    }
+
+    // --- Implementation details ---
+
+    /// Get mutable access to the object selected by `find_object`
+    ///
+    /// This must only be used to modify object properties that hwloc does not
+    /// cache, like infos, since no hwloc refresh will be triggered.
+    ///
+    /// # Errors
+    ///
+    /// - [`ForeignObjectError`] if the `&TopologyObject` returned by
+    ///   `find_object` does not belong to this [`Topology`].
+    fn find_object_mut(
+        &mut self,
+        find_object: impl FnOnce(&Topology) -> &TopologyObject,
+    ) -> Result<&mut TopologyObject, ForeignObjectError> {
+        let mut object: NonNull<TopologyObject> = {
+            let topology = self.topology();
+            let object = find_object(topology);
+            if !topology.contains(object) {
+                return Err(object.into());
+            }
+            object.into()
+        };
+        // SAFETY: - object comes from this topology, which we have exclusive
+        //           access to through &mut self
+        //         - The source &TopologyObject has been dropped, so no other
+        //           reference to the object is live
+        //         - Output lifetime is bound to &mut self
+        Ok(unsafe { object.as_mut() })
+    }
+}
+
+bitflags! {
+    /// Kinds of changes that may have been made while editing a [`Topology`]
+    ///
+    /// This is passed to the hooks registered with
+    /// [`Topology::add_edit_hook()`]. Flags are set as soon as a change of the
+    /// corresponding kind is attempted, even if it eventually fails, so hooks
+    /// may occasionally be notified of changes that did not actually happen,
+    /// but they will never miss a change.
+    #[derive(Copy, Clone, Debug, Default, Eq, Hash, PartialEq)]
+    pub struct TopologyChanges: u32 {
+        /// The topology was restricted to a smaller set of CPUs or NUMA nodes
+        ///
+        /// This can remove objects and change all object sets and indices.
+        const RESTRICTED = 1 << 0;
+
+        /// The allowed sets of CPUs and NUMA nodes were changed
+        const ALLOWED_SETS = 1 << 1;
+
+        /// New objects were inserted into the topology
+        ///
+        /// This can change object depths and logical indices.
+        const OBJECTS_INSERTED = 1 << 2;
+
+        /// Object metadata, such as textual infos, was changed
+        const OBJECT_METADATA = 1 << 3;
+
+        /// Distances between objects were added or removed
+        const DISTANCES = 1 << 4;
+
+        /// Memory attributes were registered or modified
+        const MEMORY_ATTRIBUTES = 1 << 5;
+
+        /// CPU kinds were registered
+        const CPU_KINDS = 1 << 6;
+    }
+}
+//
+crate::impl_arbitrary_for_bitflags!(TopologyChanges, u32);
+
+/// Identifier of a hook registered with [`Topology::add_edit_hook()`]
+#[derive(Copy, Clone, Debug, Eq, Hash, PartialEq)]
+pub struct EditHookId(u64);
+
+/// Callback to be invoked after a topology is edited
+type EditHook = Box<dyn FnMut(&Topology, TopologyChanges) + Send>;
+
+/// Set of edit hooks registered on a topology
+#[derive(Default)]
+pub(crate) struct EditHooks {
+    /// Registered hooks
+    hooks: Vec<(EditHookId, EditHook)>,
+
+    /// Identifier of the next hook to be registered
+    next_id: u64,
+}
+//
+impl EditHooks {
+    /// Register a new hook
+    fn add(&mut self, hook: EditHook) -> EditHookId {
+        let id = EditHookId(self.next_id);
+        self.next_id += 1;
+        self.hooks.push((id, hook));
+        id
+    }
+
+    /// Unregister a hook, tell if it was registered
+    fn remove(&mut self, id: EditHookId) -> bool {
+        let old_len = self.hooks.len();
+        self.hooks.retain(|(hook_id, _)| *hook_id != id);
+        self.hooks.len() != old_len
+    }
+
+    /// Run all hooks
+    fn run(&mut self, topology: &Topology, changes: TopologyChanges) {
+        for (_, hook) in &mut self.hooks {
+            hook(topology, changes);
+        }
+    }
+}
+//
+// Hooks are only called after editing is complete, and a panicking hook
+// results in all hooks being dropped, so no broken state can be observed.
+impl RefUnwindSafe for EditHooks {}
+impl UnwindSafe for EditHooks {}
+
 bitflags! {
     /// Flags to be given to [`TopologyEditor::restrict()`]
     #[derive(Copy, Clone, Debug, Default, Eq, Hash, PartialEq)]
@@ -1182,6 +1907,23 @@ pub enum InsertGroupError {
     /// group creation model, without adding extra objects to the group.
     #[error("attempted to create an inconsistent group (see GroupChildFilter docs)")]
     Inconsistent,
+
+    /// Attempted to make an object that is neither a normal nor a memory
+    /// object a member of a group
+    ///
+    /// Only normal and memory objects can be grouped. I/O and Misc objects can
+    /// only be grouped indirectly, by grouping the normal objects under which
+    /// they reside.
+    #[error("group members must be normal or memory objects, not {0}")]
+    BadMemberType(ObjectType),
+
+    /// Specified group member does not belong to this topology
+    #[error("group member {0}")]
+    ForeignMember(ForeignObjectError),
+
+    /// Requested group subtype contains NUL chars, which hwloc can't handle
+    #[error("group subtype can't contain the NUL char")]
+    SubtypeContainsNul,
 }
 
 /// RAII guard for `Group` objects that have been allocated, but not inserted
@@ -1268,61 +2010,107 @@ impl<'editor, 'topology> AllocatedGroup<'editor, 'topology> {
             return Err(InsertGroupError::Empty);
         }
 
-        /// Polymorphized subset of this function (avoids generics code bloat)
-        ///
-        /// # Safety
-        ///
-        /// - `group` must point to the inner group of an [`AllocatedGroup`]
-        /// - `children` must have been checked to belong to the topology of
-        ///   said [`AllocatedGroup`]
-        unsafe fn polymorphized(group: NonNull<TopologyObject>, children: Vec<&TopologyObject>) {
-            // Add children to this group
-            for child in children {
-                let result =
-                    // SAFETY: - group is assumed to be valid as a type
-                    //           invariant of AllocatedGroup
-                    //         - hwloc ops are trusted not to modify *const
-                    //           parameters
-                    //         - child was checked to belong to the same
-                    //           topology as group
-                    //         - AsInner is trusted to be implemented correctly
-                    errors::call_hwloc_int_normal("hwloc_obj_add_other_obj_sets", || unsafe {
-                        hwlocality_sys::hwloc_obj_add_other_obj_sets(
-                            group.as_inner().as_ptr(),
-                            child.as_inner(),
-                        )
-                    });
-                let handle_enomem =
-                    |raw_err: RawHwlocError| panic!("Internal reallocation failed: {raw_err}");
-                match result {
-                    Ok(_) => {}
-                    Err(
-                        raw_err @ RawHwlocError {
-                            errno: Some(errno::Errno(ENOMEM)),
-                            ..
-                        },
-                    ) => handle_enomem(raw_err),
-                    #[cfg(windows)]
-                    Err(raw_err @ RawHwlocError { errno: None, .. }) => {
-                        // As explained in the RawHwlocError documentation,
-                        // errno values may not correctly propagate from hwloc
-                        // to hwlocality on Windows. Since there is only one
-                        // expected errno value here, we'll interpret lack of
-                        // errno as ENOMEM on Windows.
-                        handle_enomem(raw_err)
-                    }
-                    Err(raw_err) => unreachable!("Unexpected hwloc error: {raw_err}"),
-                }
+        // Add children to this group
+        // SAFETY: - This is indeed the inner group of this AllocatedGroup
+        //         - children can only belong to this topology
+        unsafe { Self::add_sets_of(self.group, children) };
+        Ok(())
+    }
+
+    /// Expand cpu sets and node sets to cover the designated member objects
+    ///
+    /// This is only meant to be executed once, as an alternative to
+    /// [`add_children()`](Self::add_children).
+    ///
+    /// # Errors
+    ///
+    /// - [`BadMemberType`] if one of the designated members is neither a
+    ///   normal nor a memory object.
+    /// - [`ForeignMember`] if one of the designated members does not belong
+    ///   to the topology that is being edited.
+    /// - [`Empty`] if `find_members` did not select any object.
+    ///
+    /// [`BadMemberType`]: InsertGroupError::BadMemberType
+    /// [`Empty`]: InsertGroupError::Empty
+    /// [`ForeignMember`]: InsertGroupError::ForeignMember
+    #[cfg(feature = "hwloc-2_3_0")]
+    pub(self) fn add_members(
+        &mut self,
+        find_members: impl FnOnce(&Topology) -> Vec<&TopologyObject>,
+    ) -> Result<(), InsertGroupError> {
+        // Enumerate and check members
+        let topology = self.editor.topology();
+        let members = find_members(topology);
+        if members.is_empty() {
+            return Err(InsertGroupError::Empty);
+        }
+        for &member in &members {
+            let ty = member.object_type();
+            if !(ty.is_normal() || ty.is_memory()) {
+                return Err(InsertGroupError::BadMemberType(ty));
+            }
+            if !topology.contains(member) {
+                return Err(InsertGroupError::ForeignMember(member.into()));
             }
         }
 
-        // Call into the polymorphized function
+        // Add members to this group
         // SAFETY: - This is indeed the inner group of this AllocatedGroup
-        //         - children can only belong to this topology
-        unsafe { polymorphized(self.group, children) };
+        //         - members were checked to belong to this topology
+        unsafe { Self::add_sets_of(self.group, members) };
         Ok(())
     }
 
+    /// Expand the cpu sets and node sets of `group` to cover `objects`
+    ///
+    /// This is kept separate from the generic methods above to avoid generics
+    /// code bloat.
+    ///
+    /// # Safety
+    ///
+    /// - `group` must point to the inner group of an [`AllocatedGroup`]
+    /// - `objects` must have been checked to belong to the topology of
+    ///   said [`AllocatedGroup`]
+    unsafe fn add_sets_of(group: NonNull<TopologyObject>, objects: Vec<&TopologyObject>) {
+        for object in objects {
+            let result =
+                // SAFETY: - group is assumed to be valid as a type
+                //           invariant of AllocatedGroup
+                //         - hwloc ops are trusted not to modify *const
+                //           parameters
+                //         - object was checked to belong to the same
+                //           topology as group
+                //         - AsInner is trusted to be implemented correctly
+                errors::call_hwloc_int_normal("hwloc_obj_add_other_obj_sets", || unsafe {
+                    hwlocality_sys::hwloc_obj_add_other_obj_sets(
+                        group.as_inner().as_ptr(),
+                        object.as_inner(),
+                    )
+                });
+            let handle_enomem =
+                |raw_err: RawHwlocError| panic!("Internal reallocation failed: {raw_err}");
+            match result {
+                Ok(_) => {}
+                Err(
+                    raw_err @ RawHwlocError {
+                        errno: Some(errno::Errno(ENOMEM)),
+                        ..
+                    },
+                ) => handle_enomem(raw_err),
+                #[cfg(windows)]
+                Err(raw_err @ RawHwlocError { errno: None, .. }) => {
+                    // As explained in the RawHwlocError documentation,
+                    // errno values may not correctly propagate from hwloc
+                    // to hwlocality on Windows. Since there is only one
+                    // expected errno value here, we'll interpret lack of
+                    // errno as ENOMEM on Windows.
+                    handle_enomem(raw_err)
+                }
+                Err(raw_err) => unreachable!("Unexpected hwloc error: {raw_err}"),
+            }
+        }
+    }
+
     /// Configure hwloc's group merging policy
     ///
     /// By default, hwloc may or may not merge identical groups covering the
@@ -1499,6 +2287,20 @@ impl From<NulError> for InsertMiscError {
     }
 }
 
+/// Error returned by [`TopologyEditor::replace_object_info()`]
+#[cfg(feature = "hwloc-2_3_0")]
+#[derive(Clone, Debug, Eq, Error, Hash, PartialEq)]
+pub enum EditInfoError {
+    /// Specified object does not belong to this topology
+    #[error("edited object {0}")]
+    ForeignObject(#[from] ForeignObjectError),
+
+    /// Info name or value, object name or subtype contains NUL chars, which
+    /// hwloc can't handle
+    #[error("edited string {0}")]
+    NulError(#[from] NulError),
+}
+
 // NOTE: Do not implement traits like AsRef/Deref/Borrow for TopologyEditor,
 //       that would be unsafe as it would expose &Topology with unevaluated lazy
 //       hwloc caches, and calling their methods could violates Rust's aliasing
@@ -1521,7 +2323,7 @@ mod tests {
         ffi::CStr,
         fmt::Debug,
         panic::RefUnwindSafe,
-        sync::OnceLock,
+        sync::{Arc, Mutex, OnceLock},
     };
 
     /// Make sure opening/closing the editor doesn't affect the topology
@@ -1535,6 +2337,68 @@ mod tests {
         assert_eq!(&topology, reference);
     }
 
+    /// Make sure raw topology access works and doesn't affect the topology
+    #[test]
+    fn with_raw() {
+        let reference = Topology::test_instance();
+        let mut topology = reference.clone();
+        let expected_ptr = topology.as_raw();
+        let depth = topology.with_raw(|ptr| {
+            assert_eq!(ptr.cast_const(), expected_ptr);
+            // SAFETY: Pointer is valid for the duration of the callback
+            unsafe { hwlocality_sys::hwloc_topology_get_depth(ptr) }
+        });
+        assert_eq!(NormalDepth::try_from_c_int(depth), Ok(reference.depth()));
+        assert_eq!(&topology, reference);
+    }
+
+    /// Make sure edit hooks are notified of topology changes
+    #[test]
+    fn edit_hooks() {
+        let mut topology = Topology::test_instance().clone();
+        let notifications = Arc::new(Mutex::new(Vec::new()));
+        let hook_notifications = Arc::clone(&notifications);
+        let id = topology.add_edit_hook(move |_topology, changes| {
+            hook_notifications.lock().unwrap().push(changes);
+        });
+        let take_notifications = || std::mem::take(&mut *notifications.lock().unwrap());
+
+        // Editing sessions that don't change anything are not notified
+        topology.edit(|_editor| ());
+        assert_eq!(take_notifications(), Vec::new());
+
+        // Changes are reported, even if they don't end up doing anything
+        topology.edit(|editor| {
+            let cpuset = editor.topology().cpuset().clone_target();
+            editor.restrict(&cpuset, RestrictFlags::empty()).unwrap();
+        });
+        assert_eq!(take_notifications(), vec![TopologyChanges::RESTRICTED]);
+        topology.edit(|editor| {
+            editor
+                .insert_misc_object("EditHook", Topology::root_object)
+                .unwrap();
+        });
+        assert_eq!(
+            take_notifications(),
+            vec![TopologyChanges::OBJECTS_INSERTED]
+        );
+
+        // Raw access may change anything
+        topology.with_raw(|_ptr| ());
+        assert_eq!(take_notifications(), vec![TopologyChanges::all()]);
+
+        // Clones don't inherit hooks
+        let mut clone = topology.clone();
+        clone.with_raw(|_ptr| ());
+        assert_eq!(take_notifications(), Vec::new());
+
+        // Removed hooks are not notified anymore
+        assert!(topology.remove_edit_hook(id));
+        assert!(!topology.remove_edit_hook(id));
+        topology.with_raw(|_ptr| ());
+        assert_eq!(take_notifications(), Vec::new());
+    }
+
     // --- Test topology restrictions ---
 
     proptest! {
@@ -1553,6 +2417,25 @@ mod tests {
         ) {
             check_restrict(Topology::test_instance(), &nodeset, flags)?;
         }
+
+        /// Test [`Topology::restricted_clone()`] against the two-step path
+        #[test]
+        fn restricted_clone(
+            cpuset in topology_related_set(Topology::cpuset),
+            flags: RestrictFlags,
+        ) {
+            let topology = Topology::test_instance();
+            let mut expected = topology.clone();
+            let expected_result = expected.edit(|editor| editor.restrict(&cpuset, flags));
+            let result = topology.restricted_clone(&cpuset, flags);
+            match (result, expected_result) {
+                (Ok(restricted), Ok(())) => prop_assert_eq!(&restricted, &expected),
+                (Err(error), Err(expected_error)) => prop_assert_eq!(error, expected_error),
+                (result, expected_result) => {
+                    prop_assert!(false, "got {result:?}, expected {expected_result:?}");
+                }
+            }
+        }
     }
 
     /// Set-generic test for [`TopologyEditor::restrict()`]
@@ -1966,6 +2849,27 @@ mod tests {
                 prop_assert_eq!(&topology, initial_topology);
             }
         }
+
+        /// Test the [`TopologyEditor::allow()`] shortcuts against the
+        /// equivalent [`AllowSet`]s
+        #[test]
+        fn allow_shortcuts(owned_allow_set in any_allow_set()) {
+            let allow_set = owned_allow_set.as_allow_set();
+            let mut expected = Topology::test_instance().clone();
+            let expected_result = expected.edit(|editor| editor.allow(allow_set));
+
+            let mut topology = Topology::test_instance().clone();
+            let result = topology.edit(|editor| match allow_set {
+                AllowSet::All => editor.allow_all(),
+                AllowSet::LocalRestrictions => editor.allow_local_restrictions(),
+                AllowSet::Custom { cpuset, nodeset } => editor.allow_custom(cpuset, nodeset),
+            });
+            prop_assert_eq!(
+                format!("{result:?}"),
+                format!("{expected_result:?}")
+            );
+            prop_assert_eq!(&topology, &expected);
+        }
     }
 
     // --- Grouping objects ---
@@ -2388,6 +3292,93 @@ mod tests {
         }
     }
 
+    /// Test [`TopologyEditor::insert_group_over()`]
+    #[cfg(all(feature = "hwloc-2_3_0", not(windows)))]
+    #[test]
+    fn insert_group_over() {
+        let initial_topology = Topology::test_instance();
+        let mut topology = initial_topology.clone();
+        topology.edit(|editor| {
+            // Handle invalid inputs
+            let mut check_error = |find_members: &dyn Fn(&Topology) -> Vec<&TopologyObject>,
+                                   subtype: &str,
+                                   expected: InsertGroupError| {
+                let result = editor.insert_group_over(false, find_members, subtype);
+                assert_eq!(result.unwrap_err(), HybridError::Rust(expected));
+            };
+            check_error(&|_| Vec::new(), "Empty", InsertGroupError::Empty);
+            check_error(
+                &|topology| vec![topology.root_object()],
+                "Nul\0",
+                InsertGroupError::SubtypeContainsNul,
+            );
+            let foreign_root = Topology::foreign_instance().root_object();
+            check_error(
+                &|_| vec![foreign_root],
+                "Foreign",
+                InsertGroupError::ForeignMember(foreign_root.into()),
+            );
+            assert_eq!(editor.topology(), initial_topology);
+            let misc = editor
+                .insert_misc_object("insert_group_over", |topology| topology.root_object())
+                .unwrap()
+                .global_persistent_index();
+            let misc_result = editor.insert_group_over(
+                false,
+                |topology| {
+                    topology
+                        .objects_with_type(ObjectType::Misc)
+                        .filter(|obj| obj.global_persistent_index() == misc)
+                        .collect()
+                },
+                "Misc",
+            );
+            assert_eq!(
+                misc_result.unwrap_err(),
+                HybridError::Rust(InsertGroupError::BadMemberType(ObjectType::Misc))
+            );
+
+            // Group the first two normal children of an object that has more
+            let Some(parent) = initial_topology
+                .objects()
+                .find(|obj| obj.normal_arity() >= 3)
+            else {
+                return;
+            };
+            let member_ids = parent
+                .normal_children()
+                .take(2)
+                .map(TopologyObject::global_persistent_index)
+                .collect::<HashSet<_>>();
+            let expected_cpuset =
+                parent
+                    .normal_children()
+                    .take(2)
+                    .fold(CpuSet::new(), |mut acc, member| {
+                        acc |= member.cpuset().unwrap();
+                        acc
+                    });
+            let result = editor
+                .insert_group_over(
+                    true,
+                    |topology| {
+                        topology
+                            .objects()
+                            .filter(|obj| member_ids.contains(&obj.global_persistent_index()))
+                            .collect()
+                    },
+                    "Pair",
+                )
+                .unwrap();
+            let InsertedGroup::New(group) = result else {
+                panic!("dont_merge should force group creation");
+            };
+            assert_eq!(group.object_type(), ObjectType::Group);
+            assert_eq!(group.subtype().unwrap().to_str().unwrap(), "Pair");
+            assert_eq!(group.cpuset().unwrap(), expected_cpuset);
+        });
+    }
+
     // --- Misc objects ---
 
     /// General test of misc object insertion
@@ -2464,9 +3455,43 @@ mod tests {
                 .any(|child| child.global_persistent_index() == obj.global_persistent_index()));
             Ok(())
         })?;
+
+        // Per-type object lookups should reflect the edit
+        let ids = |objects: &mut dyn Iterator<Item = &TopologyObject>| {
+            objects
+                .map(TopologyObject::global_persistent_index)
+                .collect::<Vec<_>>()
+        };
+        prop_assert_eq!(
+            ids(&mut topology.objects_with_type(ObjectType::Misc)),
+            ids(&mut topology
+                .objects()
+                .filter(|obj| obj.object_type() == ObjectType::Misc))
+        );
         Ok(topology)
     }
 
+    /// Handles to inserted Misc objects should resolve after the edit
+    #[test]
+    fn insert_misc_object_handle() {
+        let mut topology = Topology::test_instance().clone();
+        if topology.type_filter(ObjectType::Misc).unwrap() == TypeFilter::KeepNone {
+            return;
+        }
+        let name = "MiscHandleTest";
+        let handle = topology
+            .edit(|editor| editor.insert_misc_object_handle(name, Topology::root_object))
+            .unwrap();
+        assert_eq!(handle.object_type(), ObjectType::Misc);
+        let obj = handle.resolve(&topology).unwrap();
+        assert_eq!(obj.object_type(), ObjectType::Misc);
+        assert_eq!(obj.name().unwrap().to_str().unwrap(), name);
+        assert_eq!(
+            obj.parent().unwrap().global_persistent_index(),
+            topology.root_object().global_persistent_index()
+        );
+    }
+
     proptest! {
         /// ...with the normal type filter
         #[test]
@@ -2512,4 +3537,154 @@ mod tests {
             check_insert_misc_object(&topology, &name2, parent2)?;
         }
     }
+
+    // --- Object infos ---
+
+    proptest! {
+        /// Replace an info of an existing object
+        #[cfg(all(feature = "hwloc-2_3_0", not(windows)))]
+        #[test]
+        fn replace_object_info(
+            object in any_object(),
+            name in any_string(),
+            (value1, value2) in (any_string(), any_string()),
+        ) {
+            let initial_topology = Topology::test_instance();
+            let mut topology = initial_topology.clone();
+            topology.edit(|editor| {
+                // Add a first value, then replace it with a second one
+                let find_object = || find_parent_like(initial_topology, object);
+                let res1 = editor.replace_object_info(find_object(), &name, &value1);
+                let res2 = editor.replace_object_info(find_object(), &name, &value2);
+
+                // Handle foreign objects
+                let topology = editor.topology();
+                let object = find_object()(topology);
+                if !topology.contains(object) {
+                    let expected = HybridError::Rust(EditInfoError::ForeignObject(object.into()));
+                    prop_assert_eq!(&res1.unwrap_err(), &expected);
+                    prop_assert_eq!(&res2.unwrap_err(), &expected);
+                    prop_assert_eq!(topology, initial_topology);
+                    return Ok(());
+                }
+
+                // Handle inner NULs
+                let has_nul = |s: &str| s.chars().any(|c| c == '\0');
+                let nul_error = HybridError::Rust(EditInfoError::NulError(NulError));
+                if has_nul(&name) {
+                    prop_assert_eq!(&res1.unwrap_err(), &nul_error);
+                    prop_assert_eq!(&res2.unwrap_err(), &nul_error);
+                    prop_assert_eq!(topology, initial_topology);
+                    return Ok(());
+                }
+                if has_nul(&value1) {
+                    prop_assert_eq!(&res1.unwrap_err(), &nul_error);
+                } else {
+                    res1.unwrap();
+                }
+                let expected_value = if has_nul(&value2) {
+                    prop_assert_eq!(&res2.unwrap_err(), &nul_error);
+                    (!has_nul(&value1)).then_some(&value1)
+                } else {
+                    res2.unwrap();
+                    Some(&value2)
+                };
+
+                // Check final info state
+                let matching_values = object
+                    .infos()
+                    .iter()
+                    .filter(|info| info.name().to_bytes() == name.as_bytes())
+                    .map(|info| info.value().to_str().unwrap())
+                    .collect::<Vec<_>>();
+                prop_assert_eq!(
+                    matching_values,
+                    expected_value.map(String::as_str).into_iter().collect::<Vec<_>>()
+                );
+                Ok(())
+            })?;
+        }
+
+        /// Remove infos from an existing object
+        #[cfg(all(feature = "hwloc-2_3_0", not(windows)))]
+        #[test]
+        fn remove_object_info(object in any_object(), name in any_string()) {
+            let initial_topology = Topology::test_instance();
+            let mut topology = initial_topology.clone();
+            topology.edit(|editor| {
+                let find_object = || find_parent_like(initial_topology, object);
+                let res = editor.remove_object_info(find_object(), &name);
+
+                // Handle foreign objects
+                let topology = editor.topology();
+                let object = find_object()(topology);
+                if !topology.contains(object) {
+                    prop_assert_eq!(res, Err(object.into()));
+                    prop_assert_eq!(topology, initial_topology);
+                    return Ok(());
+                }
+
+                // Check that the right infos were removed
+                let is_removed = |info: &crate::info::TextualInfo| info.name().to_bytes() == name.as_bytes();
+                let initial_object = initial_topology
+                    .objects()
+                    .find(|obj| obj.global_persistent_index() == object.global_persistent_index())
+                    .unwrap();
+                let expected_infos = initial_object
+                    .infos()
+                    .iter()
+                    .filter(|info| !is_removed(info))
+                    .collect::<Vec<_>>();
+                prop_assert_eq!(
+                    res.unwrap(),
+                    initial_object.infos().len() - expected_infos.len()
+                );
+                prop_assert_eq!(object.infos().iter().collect::<Vec<_>>(), expected_infos);
+                Ok(())
+            })?;
+        }
+
+        /// Change the subtype and name of an existing object
+        #[cfg(all(feature = "hwloc-2_3_0", not(windows)))]
+        #[test]
+        fn set_object_subtype_and_name(
+            object in any_object(),
+            (subtype, name) in (any_string(), any_string()),
+        ) {
+            let initial_topology = Topology::test_instance();
+            let mut topology = initial_topology.clone();
+            topology.edit(|editor| {
+                let find_object = || find_parent_like(initial_topology, object);
+                let subtype_res = editor.set_object_subtype(find_object(), &subtype);
+                let name_res = editor.set_object_name(find_object(), &name);
+
+                // Handle foreign objects
+                let topology = editor.topology();
+                let object = find_object()(topology);
+                if !topology.contains(object) {
+                    let expected = EditInfoError::ForeignObject(object.into());
+                    prop_assert_eq!(&subtype_res.unwrap_err(), &expected);
+                    prop_assert_eq!(&name_res.unwrap_err(), &expected);
+                    prop_assert_eq!(topology, initial_topology);
+                    return Ok(());
+                }
+
+                // Handle inner NULs, otherwise check the new strings
+                let check_string = |res: Result<(), EditInfoError>,
+                                    new_value: &str,
+                                    actual: Option<&CStr>| {
+                    if new_value.contains('\0') {
+                        prop_assert_eq!(res, Err(EditInfoError::NulError(NulError)));
+                    } else {
+                        prop_assert_eq!(res, Ok(()));
+                        prop_assert_eq!(actual.unwrap().to_str().unwrap(), new_value);
+                    }
+                    Ok(())
+                };
+                check_string(subtype_res, &subtype, object.subtype())?;
+                check_string(name_res, &name, object.name())?;
+                Ok(())
+            })?;
+        }
+    }
 }
@@ -0,0 +1,309 @@
+//! Exporting topologies to Graphviz DOT
+//!
+//! The DOT language of [Graphviz](https://graphviz.org) can be used to render
+//! the object tree of a topology as a graph, which is similar to what
+//! `lstopo --of dot` does. Unlike lstopo, the Rust implementation lets you
+//! customize the label and colors of every node via a callback.
+//!
+//! This is a visualization format, topologies cannot be imported back from it.
+
+use crate::{
+    object::{types::ObjectType, TopologyObject},
+    topology::Topology,
+};
+#[allow(unused)]
+#[cfg(test)]
+use similar_asserts::assert_eq;
+use std::fmt::{self, Debug, Write};
+
+/// # Exporting Topologies to Graphviz DOT
+///
+/// This functionality is unique to the Rust hwloc bindings
+impl Topology {
+    /// Render the object tree of this topology as a Graphviz DOT graph
+    ///
+    /// Each object becomes a node whose default label describes the object's
+    /// type, CPU set, major attributes (e.g. cache sizes or PCI IDs), logical
+    /// and OS index and name. Each node is connected to its parent by an edge.
+    /// Node appearance can be customized using
+    /// [`DotExportOptions::node_style`].
+    ///
+    /// The output can be rendered using e.g. `dot -Tsvg`.
+    pub fn export_dot(&self, options: DotExportOptions<'_>) -> String {
+        let mut dot = String::from("digraph topology {\n");
+        dot.push_str("  node [shape=box, style=filled];\n");
+        let mut objects = self.objects_depth_first();
+        while let Some(obj) = objects.next() {
+            if !options.includes(obj) {
+                objects.skip_subtree();
+                continue;
+            }
+            let mut style = DotNodeStyle::default_for(obj);
+            if let Some(node_style) = options.node_style {
+                node_style(obj, &mut style);
+            }
+            let id = obj.global_persistent_index();
+            writeln!(
+                dot,
+                "  n{id} [label=\"{}\", fillcolor=\"{}\", fontcolor=\"{}\"];",
+                escape(&style.label),
+                escape(&style.fill_color),
+                escape(&style.font_color),
+            )
+            .expect("Writing to a String cannot fail");
+            if let Some(parent) = obj.parent() {
+                let parent_id = parent.global_persistent_index();
+                writeln!(dot, "  n{parent_id} -> n{id};").expect("Writing to a String cannot fail");
+            }
+        }
+        dot.push_str("}\n");
+        dot
+    }
+}
+
+/// Options for [`Topology::export_dot()`]
+///
+/// The [`Default`] options include all objects and use the default node style.
+#[derive(Copy, Clone)]
+pub struct DotExportOptions<'callback> {
+    /// Include I/O objects like PCI devices and OS devices
+    pub io_objects: bool,
+
+    /// Include [`Misc`](ObjectType::Misc) objects
+    pub misc_objects: bool,
+
+    /// Callback that can adjust the style of each node
+    ///
+    /// It is called with the object and the default style of its node, which
+    /// it can modify as it sees fit.
+    #[allow(clippy::type_complexity)]
+    pub node_style: Option<&'callback dyn Fn(&TopologyObject, &mut DotNodeStyle)>,
+}
+//
+impl DotExportOptions<'_> {
+    /// Truth that an object and its children should be exported
+    fn includes(&self, obj: &TopologyObject) -> bool {
+        let ty = obj.object_type();
+        (self.io_objects || !ty.is_io()) && (self.misc_objects || ty != ObjectType::Misc)
+    }
+}
+//
+impl Debug for DotExportOptions<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("DotExportOptions")
+            .field("io_objects", &self.io_objects)
+            .field("misc_objects", &self.misc_objects)
+            .field(
+                "node_style",
+                &self.node_style.map(|_| "<custom style callback>"),
+            )
+            .finish()
+    }
+}
+//
+impl Default for DotExportOptions<'_> {
+    fn default() -> Self {
+        Self {
+            io_objects: true,
+            misc_objects: true,
+            node_style: None,
+        }
+    }
+}
+
+/// Appearance of a node in a Graphviz DOT graph
+///
+/// Colors can be specified in any format supported by Graphviz, e.g. by name
+/// (`"lightblue"`) or in RGB hexadecimal notation (`"#add8e6"`).
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct DotNodeStyle {
+    /// Text displayed inside of the node, may span multiple lines
+    pub label: String,
+
+    /// Background color of the node
+    pub fill_color: String,
+
+    /// Color of the label text
+    pub font_color: String,
+}
+//
+impl DotNodeStyle {
+    /// Default style for an object's node
+    ///
+    /// The colors are inspired by those of lstopo's graphical output.
+    pub fn default_for(obj: &TopologyObject) -> Self {
+        let mut label = obj.to_string();
+        write!(label, "\nL#{}", obj.logical_index()).expect("Writing to a String cannot fail");
+        if let Some(os_index) = obj.os_index() {
+            write!(label, " P#{os_index}").expect("Writing to a String cannot fail");
+        }
+        if let Some(name) = obj.name() {
+            write!(label, "\n{}", name.to_string_lossy()).expect("Writing to a String cannot fail");
+        }
+        #[allow(clippy::wildcard_enum_match_arm)]
+        let fill_color = match obj.object_type() {
+            ObjectType::Package | ObjectType::OSDevice => "#dedede",
+            ObjectType::Core => "#bebebe",
+            ObjectType::NUMANode => "#efdfde",
+            ObjectType::Group | ObjectType::Misc => "#e8e8e8",
+            ObjectType::PCIDevice => "#d2e7a4",
+            _ => "#ffffff",
+        };
+        Self {
+            label,
+            fill_color: fill_color.to_owned(),
+            font_color: "#000000".to_owned(),
+        }
+    }
+}
+
+/// Escape a string for use inside of a double-quoted DOT string
+fn escape(s: &str) -> String {
+    let mut result = String::with_capacity(s.len());
+    for c in s.chars() {
+        match c {
+            '"' => result.push_str("\\\""),
+            '\\' => result.push_str("\\\\"),
+            '\n' => result.push_str("\\n"),
+            '\r' => {}
+            other => result.push(other),
+        }
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::prelude::*;
+    #[allow(unused)]
+    use similar_asserts::assert_eq;
+    use static_assertions::{assert_impl_all, assert_not_impl_any};
+    use std::{
+        fmt::{
+            self, Binary, Debug, Display, LowerExp, LowerHex, Octal, Pointer, UpperExp, UpperHex,
+        },
+        hash::Hash,
+        io::{self, Read},
+        ops::Deref,
+        panic::UnwindSafe,
+    };
+
+    // Check that public types in this module keep implementing all expected
+    // traits, in the interest of detecting future semver-breaking changes
+    assert_impl_all!(DotExportOptions<'static>:
+        Copy, Debug, Default, Sized, Unpin
+    );
+    assert_not_impl_any!(DotExportOptions<'static>:
+        Binary, Deref, Display, Drop, IntoIterator, LowerExp, LowerHex, Octal,
+        PartialEq, Pointer, Read, UpperExp, UpperHex, fmt::Write, io::Write
+    );
+    assert_impl_all!(DotNodeStyle:
+        Clone, Debug, Hash, Sized, Sync, Unpin, UnwindSafe
+    );
+    assert_not_impl_any!(DotNodeStyle:
+        Binary, Copy, Default, Deref, Display, Drop, IntoIterator, LowerExp,
+        LowerHex, Octal, PartialOrd, Pointer, Read, UpperExp, UpperHex,
+        fmt::Write, io::Write
+    );
+
+    /// Node declaration lines of a DOT graph
+    fn node_lines(dot: &str) -> Vec<&str> {
+        dot.lines()
+            .filter(|line| line.starts_with("  n") && !line.contains("->"))
+            .collect()
+    }
+
+    /// Edge declaration lines of a DOT graph
+    fn edge_lines(dot: &str) -> Vec<&str> {
+        dot.lines().filter(|line| line.contains("->")).collect()
+    }
+
+    /// Default export should contain every object and parent-child edge
+    #[test]
+    fn default_export() {
+        let topology = Topology::test_instance();
+        let dot = topology.export_dot(DotExportOptions::default());
+        assert!(dot.starts_with("digraph topology {\n"));
+        assert!(dot.ends_with("}\n"));
+
+        let num_objects = topology.objects().count();
+        assert_eq!(node_lines(&dot).len(), num_objects);
+        assert_eq!(edge_lines(&dot).len(), num_objects - 1);
+        for obj in topology.objects() {
+            let id = obj.global_persistent_index();
+            let style = DotNodeStyle::default_for(obj);
+            let expected = format!(
+                "  n{id} [label=\"{}\", fillcolor=\"{}\", fontcolor=\"{}\"];",
+                escape(&style.label),
+                style.fill_color,
+                style.font_color
+            );
+            assert!(dot.lines().any(|line| line == expected));
+            if let Some(parent) = obj.parent() {
+                let edge = format!("  n{} -> n{id};", parent.global_persistent_index());
+                assert!(dot.lines().any(|line| line == edge));
+            }
+        }
+    }
+
+    /// Filtering out I/O and Misc objects should remove them from the graph
+    #[test]
+    fn filtered_export() {
+        let topology = Topology::test_instance();
+        let dot = topology.export_dot(DotExportOptions {
+            io_objects: false,
+            misc_objects: false,
+            ..Default::default()
+        });
+        let num_objects = topology
+            .objects()
+            .filter(|obj| {
+                obj.ancestors()
+                    .chain(std::iter::once(*obj))
+                    .all(|obj| !obj.object_type().is_io() && obj.object_type() != ObjectType::Misc)
+            })
+            .count();
+        assert_eq!(node_lines(&dot).len(), num_objects);
+        assert_eq!(edge_lines(&dot).len(), num_objects - 1);
+    }
+
+    /// Node style callbacks should be honored
+    #[test]
+    fn custom_style() {
+        let topology = Topology::test_instance();
+        let style = |obj: &TopologyObject, style: &mut DotNodeStyle| {
+            if obj.object_type() == ObjectType::PU {
+                style.label = "PU".to_owned();
+                style.fill_color = "red".to_owned();
+            }
+        };
+        let dot = topology.export_dot(DotExportOptions {
+            node_style: Some(&style),
+            ..Default::default()
+        });
+        let num_red = node_lines(&dot)
+            .into_iter()
+            .filter(|line| line.contains("label=\"PU\", fillcolor=\"red\""))
+            .count();
+        assert_eq!(num_red, topology.objects_with_type(ObjectType::PU).count());
+    }
+
+    proptest! {
+        /// Escaped strings should not contain unescaped quotes or newlines
+        #[test]
+        fn escaping(s in any::<String>()) {
+            let escaped = escape(&s);
+            prop_assert!(!escaped.contains('\n'));
+            prop_assert!(!escaped.contains('\r'));
+            let mut chars = escaped.chars();
+            while let Some(c) = chars.next() {
+                match c {
+                    '\\' => prop_assert!(matches!(chars.next(), Some('\\' | '"' | 'n'))),
+                    '"' => prop_assert!(false, "unescaped quote in {escaped:?}"),
+                    _ => {}
+                }
+            }
+        }
+    }
+}
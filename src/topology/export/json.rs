@@ -0,0 +1,509 @@
+//! Exporting topologies to JSON
+//!
+//! This format is meant for consumers that do not link to hwloc, e.g. web
+//! dashboards or Python and JavaScript tools, which can read it using their
+//! standard JSON parser. Unlike XML export, it is produced by hwlocality
+//! itself, and its structure is documented below and kept stable across
+//! hwlocality releases. New keys may be added in the future, so consumers
+//! should ignore the keys that they do not know about.
+//!
+//! Topologies cannot be imported back from JSON. Use [XML
+//! export](super::xml) if you need to save a topology and load it again later.
+//!
+//! # Schema
+//!
+//! The top-level value is an object with the following keys:
+//!
+//! - `"version"`: Version of this schema, currently `1`. It will only be
+//!   increased if an existing key is removed or changes meaning.
+//! - `"root"`: The root object of the topology, in the format described below.
+//! - `"distances"`: Array of the distance matrices of the topology, in the
+//!   format described below.
+//!
+//! Each topology object is described by a JSON object with these keys:
+//!
+//! - `"type"`: Object type, as displayed by [`ObjectType`] (e.g.
+//!   `"Package"`).
+//! - `"subtype"`, `"name"`: Object subtype and name as strings, or `null`.
+//! - `"depth"`: Depth of the object as an integer for normal objects, or as
+//!   a string naming the virtual depth (e.g. `"NUMANode"`) for other objects.
+//! - `"logical_index"`: Logical index of the object among objects of the same
+//!   type.
+//! - `"os_index"`: OS-provided physical index of the object, or `null`.
+//! - `"gp_index"`: Global persistent index of the object, which uniquely
+//!   identifies it within the topology and is used by distance matrices.
+//! - `"cpuset"`, `"complete_cpuset"`, `"nodeset"`, `"complete_nodeset"`: CPU
+//!   and NUMA node sets in the list format of [`Bitmap`]'s [`Display`]
+//!   implementation (e.g. `"0-3,8"`), or `null` for objects without a set.
+//! - `"total_memory"`: Total memory in bytes within the object's subtree.
+//! - `"attributes"`: Type-specific attributes as an object, or `null` for
+//!   object types that have none. The keys are named after the accessors of
+//!   the corresponding [`ObjectAttributes`] variant (e.g. `"size"`,
+//!   `"line_size"` and `"cache_type"` for caches), with an additional
+//!   `"bus_id"` string in `domain:bus:device.function` format for PCI devices.
+//! - `"infos"`: Array of `{"name": ..., "value": ...}` textual info objects.
+//! - `"children"`, `"memory_children"`, `"io_children"`, `"misc_children"`:
+//!   Arrays of child objects, in the same format.
+//!
+//! Each distance matrix is described by a JSON object with these keys:
+//!
+//! - `"name"`: Name of the distance matrix (e.g. `"NUMALatency"`), or `null`.
+//! - `"kind"`: Array of the names of the [`DistancesKind`] flags that are set
+//!   (e.g. `["FROM_OS", "MEANS_LATENCY"]`).
+//! - `"objects"`: Array of the global persistent indices of the objects that
+//!   the matrix covers, or `null` for objects that were removed.
+//! - `"values"`: Distances between these objects, as an array of rows.
+//!
+//! [`Bitmap`]: crate::bitmap::Bitmap
+//! [`DistancesKind`]: crate::object::distance::DistancesKind
+//! [`ObjectAttributes`]: crate::object::attributes::ObjectAttributes
+//! [`ObjectType`]: crate::object::types::ObjectType
+
+use crate::{
+    object::{
+        attributes::{
+            BridgeAttributes, CacheAssociativity, DownstreamAttributes, ObjectAttributes,
+            PCIDeviceAttributes, UpstreamAttributes,
+        },
+        depth::Depth,
+        distance::Distances,
+        TopologyObject,
+    },
+    topology::Topology,
+};
+#[allow(unused)]
+#[cfg(test)]
+use similar_asserts::assert_eq;
+use std::fmt::{Display, Write};
+
+/// # Exporting Topologies to JSON
+///
+/// This functionality is unique to the Rust hwloc bindings
+impl Topology {
+    /// Export the topology to JSON
+    ///
+    /// The output follows the schema that is documented in the [module-level
+    /// documentation](self). It is meant to be consumed by tools that do not
+    /// depend on hwloc, and cannot be imported back.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use hwlocality::Topology;
+    /// let topology = Topology::test_instance();
+    /// let json = topology.export_json();
+    /// assert!(json.starts_with("{\"version\":1,"));
+    /// ```
+    pub fn export_json(&self) -> String {
+        let mut out = String::new();
+        let mut top = JsonObject::begin(&mut out);
+        top.key("version").push('1');
+        write_object(top.key("root"), self.root_object());
+        let distances = top.key("distances");
+        distances.push('[');
+        // Failing to query distances only affects this section of the output
+        for (idx, matrix) in self.distances(None).unwrap_or_default().iter().enumerate() {
+            if idx > 0 {
+                distances.push(',');
+            }
+            write_distances(distances, matrix);
+        }
+        distances.push(']');
+        top.end();
+        out
+    }
+}
+
+/// JSON object that is being written
+struct JsonObject<'out> {
+    /// Output string
+    out: &'out mut String,
+
+    /// Truth that no key has been written yet
+    first: bool,
+}
+//
+impl<'out> JsonObject<'out> {
+    /// Start writing a JSON object
+    fn begin(out: &'out mut String) -> Self {
+        out.push('{');
+        Self { out, first: true }
+    }
+
+    /// Write a key, then return the output string so the value can be written
+    fn key(&mut self, name: &str) -> &mut String {
+        if !self.first {
+            self.out.push(',');
+        }
+        self.first = false;
+        write_string(self.out, name);
+        self.out.push(':');
+        self.out
+    }
+
+    /// Write a key and a value whose [`Display`] output is valid JSON
+    fn raw(&mut self, name: &str, value: impl Display) {
+        write!(self.key(name), "{value}").expect("Writing to a String cannot fail");
+    }
+
+    /// Write a key and an optional value whose [`Display`] output is valid
+    /// JSON, using `null` for `None`
+    fn raw_or_null(&mut self, name: &str, value: Option<impl Display>) {
+        match value {
+            Some(value) => self.raw(name, value),
+            None => self.raw(name, "null"),
+        }
+    }
+
+    /// Write a key and a string value
+    fn string(&mut self, name: &str, value: impl Display) {
+        let value = value.to_string();
+        write_string(self.key(name), &value);
+    }
+
+    /// Write a key and an optional string value, using `null` for `None`
+    fn string_or_null(&mut self, name: &str, value: Option<impl Display>) {
+        match value {
+            Some(value) => self.string(name, value),
+            None => self.raw(name, "null"),
+        }
+    }
+
+    /// Finish writing the JSON object
+    fn end(self) {
+        self.out.push('}');
+    }
+}
+
+/// Write a topology object and its children
+fn write_object(out: &mut String, obj: &TopologyObject) {
+    let mut json = JsonObject::begin(out);
+    json.string("type", obj.object_type());
+    json.string_or_null("subtype", obj.subtype().map(|s| s.to_string_lossy()));
+    json.string_or_null("name", obj.name().map(|s| s.to_string_lossy()));
+    #[allow(clippy::wildcard_enum_match_arm)]
+    match obj.depth() {
+        Depth::Normal(depth) => json.raw("depth", depth),
+        virtual_depth => json.string("depth", format!("{virtual_depth:?}")),
+    }
+    json.raw("logical_index", obj.logical_index());
+    json.raw_or_null("os_index", obj.os_index());
+    json.raw("gp_index", obj.global_persistent_index());
+    json.string_or_null("cpuset", obj.cpuset());
+    json.string_or_null("complete_cpuset", obj.complete_cpuset());
+    json.string_or_null("nodeset", obj.nodeset());
+    json.string_or_null("complete_nodeset", obj.complete_nodeset());
+    json.raw("total_memory", obj.total_memory());
+    match obj.attributes() {
+        Some(attributes) => write_attributes(json.key("attributes"), attributes),
+        None => json.raw("attributes", "null"),
+    }
+    let infos = json.key("infos");
+    infos.push('[');
+    for (idx, info) in obj.infos().iter().enumerate() {
+        if idx > 0 {
+            infos.push(',');
+        }
+        let mut info_json = JsonObject::begin(infos);
+        info_json.string("name", info.name_lossy());
+        info_json.string("value", info.value_lossy());
+        info_json.end();
+    }
+    infos.push(']');
+    for (key, children) in [
+        ("children", obj.normal_children().collect::<Vec<_>>()),
+        ("memory_children", obj.memory_children().collect()),
+        ("io_children", obj.io_children().collect()),
+        ("misc_children", obj.misc_children().collect()),
+    ] {
+        let out = json.key(key);
+        out.push('[');
+        for (idx, child) in children.into_iter().enumerate() {
+            if idx > 0 {
+                out.push(',');
+            }
+            write_object(out, child);
+        }
+        out.push(']');
+    }
+    json.end();
+}
+
+/// Write the type-specific attributes of an object
+fn write_attributes(out: &mut String, attributes: ObjectAttributes<'_>) {
+    let mut json = JsonObject::begin(out);
+    match attributes {
+        ObjectAttributes::NUMANode(numa) => {
+            json.raw_or_null("local_memory", numa.local_memory());
+            let page_types = json.key("page_types");
+            page_types.push('[');
+            for (idx, page_type) in numa.page_types().iter().enumerate() {
+                if idx > 0 {
+                    page_types.push(',');
+                }
+                let mut page_json = JsonObject::begin(page_types);
+                page_json.raw("size", page_type.size());
+                page_json.raw("count", page_type.count());
+                page_json.end();
+            }
+            page_types.push(']');
+        }
+        ObjectAttributes::Cache(cache) => {
+            json.raw_or_null("size", cache.size());
+            json.raw("depth", cache.depth());
+            json.raw_or_null("line_size", cache.line_size());
+            match cache.associativity() {
+                CacheAssociativity::Unknown => json.raw("associativity", "null"),
+                CacheAssociativity::Ways(ways) => json.raw("associativity", ways),
+                CacheAssociativity::Full => json.string("associativity", "full"),
+            }
+            json.string("cache_type", cache.cache_type());
+        }
+        ObjectAttributes::Group(group) => {
+            json.raw("depth", group.depth());
+            #[cfg(feature = "hwloc-2_0_4")]
+            json.raw("merging_prevented", group.merging_prevented());
+        }
+        ObjectAttributes::PCIDevice(pci) => write_pci_fields(&mut json, pci),
+        ObjectAttributes::Bridge(bridge) => write_bridge_fields(&mut json, bridge),
+        ObjectAttributes::OSDevice(osdev) => json.string("device_type", osdev.device_type()),
+    }
+    json.end();
+}
+
+/// Write the attributes of a PCI device into a JSON object
+fn write_pci_fields(json: &mut JsonObject<'_>, pci: &PCIDeviceAttributes) {
+    json.string(
+        "bus_id",
+        format_args!(
+            "{:04x}:{:02x}:{:02x}.{:01x}",
+            pci.domain(),
+            pci.bus_id(),
+            pci.bus_device(),
+            pci.function()
+        ),
+    );
+    json.raw("domain", pci.domain());
+    json.raw("bus", pci.bus_id());
+    json.raw("device", pci.bus_device());
+    json.raw("function", pci.function());
+    json.raw("class_id", pci.class_id());
+    json.raw("vendor_id", pci.vendor_id());
+    json.raw("device_id", pci.device_id());
+    json.raw("subvendor_id", pci.subvendor_id());
+    json.raw("subdevice_id", pci.subdevice_id());
+    json.raw("revision", pci.revision());
+    let link_speed = pci.link_speed();
+    json.raw_or_null("link_speed", link_speed.is_finite().then_some(link_speed));
+}
+
+/// Write the attributes of a bridge into a JSON object
+fn write_bridge_fields(json: &mut JsonObject<'_>, bridge: &BridgeAttributes) {
+    json.string("upstream_type", bridge.upstream_type());
+    match bridge.upstream_attributes() {
+        Some(UpstreamAttributes::PCI(pci)) => {
+            let mut upstream = JsonObject::begin(json.key("upstream"));
+            write_pci_fields(&mut upstream, pci);
+            upstream.end();
+        }
+        None => json.raw("upstream", "null"),
+    }
+    json.string("downstream_type", bridge.downstream_type());
+    match bridge.downstream_attributes() {
+        Some(DownstreamAttributes::PCI(pci)) => {
+            let mut downstream = JsonObject::begin(json.key("downstream"));
+            downstream.raw("domain", pci.domain());
+            downstream.raw("secondary_bus", pci.secondary_bus());
+            downstream.raw("subordinate_bus", pci.subordinate_bus());
+            downstream.end();
+        }
+        None => json.raw("downstream", "null"),
+    }
+    json.raw("depth", bridge.depth());
+}
+
+/// Write a distance matrix
+fn write_distances(out: &mut String, distances: &Distances<'_>) {
+    let mut json = JsonObject::begin(out);
+    #[cfg(feature = "hwloc-2_1_0")]
+    json.string_or_null("name", distances.name().map(|s| s.to_string_lossy()));
+    #[cfg(not(feature = "hwloc-2_1_0"))]
+    json.raw("name", "null");
+    let kind = json.key("kind");
+    kind.push('[');
+    for (idx, (name, _)) in distances.kind().iter_names().enumerate() {
+        if idx > 0 {
+            kind.push(',');
+        }
+        write_string(kind, name);
+    }
+    kind.push(']');
+    let objects = json.key("objects");
+    objects.push('[');
+    for (idx, obj) in distances.objects().enumerate() {
+        if idx > 0 {
+            objects.push(',');
+        }
+        match obj {
+            Some(obj) => write!(objects, "{}", obj.global_persistent_index())
+                .expect("Writing to a String cannot fail"),
+            None => objects.push_str("null"),
+        }
+    }
+    objects.push(']');
+    let values = json.key("values");
+    values.push('[');
+    let num_objects = distances.num_objects();
+    if num_objects > 0 {
+        for (row_idx, row) in distances.distances().chunks(num_objects).enumerate() {
+            if row_idx > 0 {
+                values.push(',');
+            }
+            values.push('[');
+            for (idx, value) in row.iter().enumerate() {
+                if idx > 0 {
+                    values.push(',');
+                }
+                write!(values, "{value}").expect("Writing to a String cannot fail");
+            }
+            values.push(']');
+        }
+    }
+    values.push(']');
+    json.end();
+}
+
+/// Write a string as a double-quoted JSON string
+fn write_string(out: &mut String, s: &str) {
+    out.reserve(s.len() + 2);
+    out.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c if c.is_control() && u32::from(c) < 0x20 => {
+                write!(out, "\\u{:04x}", u32::from(c)).expect("Writing to a String cannot fail");
+            }
+            other => out.push(other),
+        }
+    }
+    out.push('"');
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::object::types::ObjectType;
+    use proptest::prelude::*;
+    use serde_json::Value;
+    #[allow(unused)]
+    use similar_asserts::assert_eq;
+
+    /// Check that a JSON object matches a topology object and its children,
+    /// returning the number of objects that were checked
+    fn check_object(json: &Value, obj: &TopologyObject) -> usize {
+        assert_eq!(json["type"], obj.object_type().to_string());
+        assert_eq!(json["logical_index"], obj.logical_index());
+        assert_eq!(json["gp_index"], obj.global_persistent_index());
+        assert_eq!(json["total_memory"], obj.total_memory());
+        match obj.os_index() {
+            Some(os_index) => assert_eq!(json["os_index"], os_index),
+            None => assert!(json["os_index"].is_null()),
+        }
+        match obj.cpuset() {
+            Some(cpuset) => assert_eq!(json["cpuset"], cpuset.to_string()),
+            None => assert!(json["cpuset"].is_null()),
+        }
+        match obj.nodeset() {
+            Some(nodeset) => assert_eq!(json["nodeset"], nodeset.to_string()),
+            None => assert!(json["nodeset"].is_null()),
+        }
+        assert_eq!(obj.attributes().is_none(), json["attributes"].is_null());
+        assert_eq!(json["infos"].as_array().unwrap().len(), obj.infos().len());
+
+        let mut num_objects = 1;
+        for (key, children) in [
+            ("children", obj.normal_children().collect::<Vec<_>>()),
+            ("memory_children", obj.memory_children().collect()),
+            ("io_children", obj.io_children().collect()),
+            ("misc_children", obj.misc_children().collect()),
+        ] {
+            let json_children = json[key].as_array().unwrap();
+            assert_eq!(json_children.len(), children.len());
+            for (json_child, child) in json_children.iter().zip(children) {
+                num_objects += check_object(json_child, child);
+            }
+        }
+        num_objects
+    }
+
+    /// Check that the JSON export of the test topology describes all objects
+    #[test]
+    fn export_test_instance() {
+        let topology = Topology::test_instance();
+        let json: Value = serde_json::from_str(&topology.export_json()).unwrap();
+        assert_eq!(json["version"], 1);
+        let num_objects = check_object(&json["root"], topology.root_object());
+        assert_eq!(num_objects, topology.objects().count());
+        assert_eq!(json["root"]["type"], ObjectType::Machine.to_string());
+        assert_eq!(json["root"]["depth"], 0);
+        assert_eq!(
+            json["distances"].as_array().unwrap().len(),
+            topology.distances(None).unwrap().len()
+        );
+    }
+
+    /// Check that distance matrices are exported
+    #[cfg(feature = "hwloc-2_5_0")]
+    #[test]
+    fn export_distances() {
+        use crate::object::distance::{AddDistancesFlags, DistancesKind};
+
+        let mut topology = Topology::test_dual_socket_numa().clone();
+        topology.edit(|editor| {
+            editor
+                .add_distances(
+                    Some("TestLatency"),
+                    DistancesKind::FROM_USER | DistancesKind::MEANS_LATENCY,
+                    AddDistancesFlags::empty(),
+                    |topology| {
+                        let nodes = topology
+                            .objects_with_type(ObjectType::NUMANode)
+                            .map(Some)
+                            .collect();
+                        (nodes, vec![10, 20, 20, 10])
+                    },
+                )
+                .unwrap();
+        });
+        let json: Value = serde_json::from_str(&topology.export_json()).unwrap();
+        let matrices = json["distances"].as_array().unwrap();
+        let matrix = matrices
+            .iter()
+            .find(|matrix| matrix["name"] == "TestLatency")
+            .unwrap();
+        assert_eq!(
+            matrix["kind"],
+            serde_json::json!(["FROM_USER", "MEANS_LATENCY"])
+        );
+        let nodes = topology
+            .objects_with_type(ObjectType::NUMANode)
+            .map(TopologyObject::global_persistent_index)
+            .collect::<Vec<_>>();
+        assert_eq!(matrix["objects"], serde_json::json!(nodes));
+        assert_eq!(matrix["values"], serde_json::json!([[10, 20], [20, 10]]));
+    }
+
+    proptest! {
+        /// Strings should round trip through JSON
+        #[test]
+        fn string_escaping(s in any::<String>()) {
+            let mut out = String::new();
+            write_string(&mut out, &s);
+            prop_assert_eq!(serde_json::from_str::<String>(&out).unwrap(), s);
+        }
+    }
+}
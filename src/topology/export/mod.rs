@@ -12,7 +12,13 @@
 //!   devices), but does so extremely concisely.
 //! - XML export can, in principle, handle every single topology that hwloc can
 //!   probe, but does so at the cost of extra complexity.
+//!
+//! Topologies can also be exported to the Graphviz DOT format for visualization
+//! purposes, and to a documented JSON format for consumption by tools that do
+//! not depend on hwloc, but these formats cannot be imported back.
 
+pub mod dot;
+pub mod json;
 pub mod synthetic;
 pub mod xml;
 
@@ -132,3 +132,63 @@ crate::impl_arbitrary_for_bitflags!(
     SyntheticExportFlags,
     hwloc_topology_export_synthetic_flags_e
 );
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::topology::synthetic::SyntheticDescription;
+    #[allow(unused)]
+    use similar_asserts::assert_eq;
+
+    /// Export the dual-socket test topology with some flags and parse it back
+    fn export_parsed(flags: SyntheticExportFlags) -> SyntheticDescription {
+        let exported = Topology::test_dual_socket_numa()
+            .export_synthetic(flags)
+            .unwrap();
+        let description = SyntheticDescription::parse(&exported).unwrap();
+        assert_eq!(description.to_string(), exported);
+        description
+    }
+
+    /// Check that each export flag has the documented effect
+    #[test]
+    fn export_flags() {
+        let default = export_parsed(SyntheticExportFlags::empty());
+        let num_pus = |description: &SyntheticDescription| {
+            description
+                .levels
+                .iter()
+                .map(|level| level.arity.get())
+                .product::<usize>()
+        };
+        assert_eq!(num_pus(&default), 32);
+        assert!(default.levels.iter().any(|level| !level.memory.is_empty()));
+
+        let no_attributes = export_parsed(SyntheticExportFlags::NO_ATTRIBUTES);
+        assert!(no_attributes.attributes.is_empty());
+        for level in &no_attributes.levels {
+            assert!(level.attributes.is_empty());
+            assert!(level
+                .memory
+                .iter()
+                .all(|memory| memory.attributes.is_empty()));
+        }
+        assert_eq!(num_pus(&no_attributes), 32);
+
+        let no_memory = export_parsed(SyntheticExportFlags::IGNORE_MEMORY);
+        assert!(no_memory.memory.is_empty());
+        assert!(no_memory.levels.iter().all(|level| level.memory.is_empty()));
+        assert_eq!(num_pus(&no_memory), 32);
+
+        let v1 = export_parsed(SyntheticExportFlags::V1);
+        assert!(v1.memory.is_empty());
+        assert!(v1.levels.iter().all(|level| level.memory.is_empty()));
+
+        let no_extended = export_parsed(SyntheticExportFlags::NO_EXTENDED_TYPES);
+        assert!(no_extended.levels.iter().all(|level| level
+            .object_type
+            .as_deref()
+            .map_or(true, |ty| !ty.starts_with("L3"))));
+        assert_eq!(num_pus(&no_extended), 32);
+    }
+}
@@ -21,6 +21,7 @@ use std::{
     ffi::{c_char, c_uint, CStr, OsStr},
     fmt::{self, Debug, Display},
     hash::Hash,
+    io::{self, Write},
     ops::{Deref, Index},
     path::Path,
     ptr::{self, NonNull},
@@ -124,6 +125,34 @@ impl Topology {
         Ok(unsafe { XML::wrap(self, xmlbuffer, buflen) }
             .expect("Got null pointer from hwloc_topology_export_xmlbuffer"))
     }
+
+    /// Export the topology as XML into a writer
+    ///
+    /// This works a lot like [`Topology::export_xml()`], but writes the XML
+    /// output into an arbitrary [`Write`] implementation, such as a network
+    /// socket or an archive entry. The output may be loaded later using
+    /// [`TopologyBuilder::from_xml_reader()`].
+    ///
+    /// Since hwloc only exports complete XML descriptions, the XML output is
+    /// generated in memory before being written out.
+    ///
+    /// # Errors
+    ///
+    /// - [`Rust`] if writing into `writer` fails
+    /// - [`Hwloc`] if hwloc fails to export the topology
+    ///
+    /// [`Hwloc`]: HybridError::Hwloc
+    /// [`Rust`]: HybridError::Rust
+    #[doc(alias = "hwloc_topology_export_xmlbuffer")]
+    pub fn export_xml_writer(
+        &self,
+        mut writer: impl Write,
+        flags: XMLExportFlags,
+    ) -> Result<(), HybridError<io::Error>> {
+        let xml = self.export_xml(flags).map_err(HybridError::Hwloc)?;
+        writer.write_all(xml.as_bytes())?;
+        Ok(())
+    }
 }
 
 bitflags! {
@@ -151,7 +180,9 @@ crate::impl_arbitrary_for_bitflags!(XMLExportFlags, hwloc_topology_export_xml_fl
 // # Safety
 //
 // As a type invariant, the data pointer is assumed to always point to a valid,
-// non-aliased XML string that was allocated from specified topology.
+// non-aliased XML string that was allocated by hwloc's XML export machinery
+// while the specified topology was alive, and can thus be liberated with
+// `hwloc_free_xmlbuffer()` on this topology.
 pub struct XML<'topology> {
     /// Underlying hwloc topology
     topology: &'topology Topology,
@@ -166,7 +197,8 @@ impl<'topology> XML<'topology> {
     ///
     /// # Safety
     ///
-    /// - `base` must originate from `topology` and have no mutable aliases
+    /// - `base` must have been allocated by an hwloc XML export function
+    ///   while `topology` was alive, and have no mutable aliases
     /// - `len` must match `base`
     ///
     /// # Panics
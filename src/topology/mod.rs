@@ -7,27 +7,36 @@
 //! almost any other feature of the library is accessed.
 
 pub mod builder;
+pub mod diagnostics;
+pub mod diff;
 #[cfg(feature = "hwloc-2_3_0")]
 pub mod editor;
 pub mod export;
+pub mod summary;
 pub mod support;
+pub mod synthetic;
+pub mod watch;
 
+#[cfg(feature = "hwloc-2_3_0")]
+use self::editor::EditHooks;
 use self::{
     builder::{BuildFlags, TopologyBuilder, TypeFilter},
-    support::FeatureSupport,
+    diagnostics::DiscoveryDiagnostics,
+    support::{FeatureSupport, SupportSummary},
 };
 #[cfg(all(feature = "hwloc-2_3_0", doc))]
 use crate::topology::support::MiscSupport;
 use crate::{
     bitmap::{Bitmap, BitmapRef, OwnedSpecializedBitmap},
-    cpu::cpuset::CpuSet,
+    cpu::{cache::CacheHierarchySummary, cpuset::CpuSet},
     errors::{self, ForeignObjectError, RawHwlocError},
     ffi::transparent::AsNewtype,
     memory::nodeset::NodeSet,
     object::{
         depth::{Depth, NormalDepth},
+        handle::ObjectId,
         types::ObjectType,
-        TopologyObject,
+        TopologyObject, TopologyObjectID,
     },
 };
 use bitflags::bitflags;
@@ -37,15 +46,26 @@ use hwlocality_sys::{
     HWLOC_DISTRIB_FLAG_REVERSE,
 };
 use libc::EINVAL;
+#[cfg(feature = "serde")]
+use serde::{de, ser, Deserialize, Deserializer, Serialize, Serializer};
 #[allow(unused)]
 #[cfg(test)]
 use similar_asserts::assert_eq;
 use std::{
-    collections::BTreeMap,
+    any::Any,
+    borrow::Borrow,
+    collections::{BTreeMap, HashMap},
     fmt::{self, Debug, Pointer},
+    iter::FusedIterator,
+    marker::PhantomData,
+    mem::ManuallyDrop,
     ops::Deref,
+    panic::AssertUnwindSafe,
     ptr::{self, NonNull},
-    sync::OnceLock,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc, Mutex, OnceLock, PoisonError, RwLock,
+    },
 };
 use thiserror::Error;
 
@@ -72,6 +92,8 @@ use thiserror::Error;
 /// - [Distributing work items over a topology](#distributing-work-items-over-a-topology)
 /// - [CPU and node sets of entire topologies](#cpu-and-node-sets-of-entire-topologies)
 /// - [Finding I/O objects](#finding-io-objects)
+/// - [Application-defined data](#application-defined-data) (specific to Rust bindings)
+/// - [Raw hwloc interoperability](#raw-hwloc-interoperability)
 /// - [Exporting Topologies to XML](#exporting-topologies-to-xml)
 /// - [Exporting Topologies to Synthetic](#exporting-topologies-to-synthetic)
 /// - [Retrieve distances between objects](#retrieve-distances-between-objects)
@@ -107,9 +129,73 @@ use thiserror::Error;
 // Any binding to an hwloc topology function that takes a user-provided
 // &TopologyObject parameter **must** check that this object does belongs to the
 // topology using the Topology::contains() method before passing it to hwloc.
+//
+// Edit hooks are only accessed through &mut Topology, so they do not need to
+// be Sync for Topology to be Sync.
+//
+// Topology and object userdata are stored on the Rust side rather than in
+// hwloc's userdata pointers, because hwloc_topology_dup() would alias the
+// latter, and because other C libraries may use them for their own purposes
+// when topologies are exchanged with them. Object userdata is keyed by global
+// persistent index. Entries of objects that are removed by a topology edit are
+// pruned by `Topology::index_objects()`.
+//
+// The object index must list every object of the topology. It must thus be
+// rebuilt using `Topology::index_objects()` whenever the topology is created or
+// refreshed, and its pointers must not be used to mutate objects.
+//
+// The topology identifier is registered in `TOPOLOGY_IDS` by
+// `Topology::wrap()`, and must be unregistered using `Topology::release_id()`
+// before the topology is destroyed or handed over to another library.
 #[doc(alias = "hwloc_topology")]
 #[doc(alias = "hwloc_topology_t")]
-pub struct Topology(NonNull<hwloc_topology>);
+pub struct Topology {
+    /// Underlying hwloc topology
+    raw: NonNull<hwloc_topology>,
+
+    /// Process-wide unique identifier of the underlying hwloc topology
+    id: u64,
+
+    /// Lookup tables for the objects of the topology
+    object_index: ObjectIndex,
+
+    /// Application-defined data attached to the topology
+    ///
+    /// This data does not affect the unwind safety of the topology.
+    userdata: AssertUnwindSafe<Option<UserData>>,
+
+    /// Application-defined data attached to objects of the topology
+    ///
+    /// Like the topology-wide userdata, this data does not affect the unwind
+    /// safety of the topology.
+    object_userdata: AssertUnwindSafe<HashMap<TopologyObjectID, UserData>>,
+
+    /// Callbacks to be invoked after the topology is edited
+    #[cfg(feature = "hwloc-2_3_0")]
+    edit_hooks: EditHooks,
+
+    /// Diagnostics that hwloc emitted while building the topology, if captured
+    diagnostics: Option<DiscoveryDiagnostics>,
+}
+
+/// Process-wide topology instance, see [`Topology::global()`]
+///
+/// This is `None` until the process-wide topology is first requested. Each
+/// snapshot is reference-counted, so that it is freed once it has been
+/// replaced by [`Topology::reload_global()`] and all users are done with it.
+static GLOBAL_INSTANCE: RwLock<Option<Arc<Topology>>> = RwLock::new(None);
+
+/// Next topology identifier to be allocated by [`Topology::wrap()`]
+static NEXT_TOPOLOGY_ID: AtomicU64 = AtomicU64::new(0);
+
+/// Identifiers of the hwloc topologies that are currently wrapped
+///
+/// Entries are keyed by root object address, which is stable across topology
+/// edits and cannot be shared by two live topologies, so that an identifier
+/// can be recovered from any object of the topology. Each entry also counts
+/// how many [`Topology`] wrappers currently use it, as several
+/// [`TopologyRef`]s may borrow the same hwloc topology.
+static TOPOLOGY_IDS: Mutex<BTreeMap<usize, (u64, usize)>> = Mutex::new(BTreeMap::new());
 
 /// # Topology building
 //
@@ -175,6 +261,79 @@ impl Topology {
         INSTANCE.get_or_init(|| Self::test_instance().clone())
     }
 
+    /// Process-wide topology instance
+    ///
+    /// Many programs only ever need a single, read-only view of the topology
+    /// of the machine they run on. This method lazily builds such a topology
+    /// on first use, with the default configuration of [`Topology::new()`],
+    /// so that it does not need to be passed around everywhere.
+    ///
+    /// If you need a topology with a different configuration (e.g. including
+    /// I/O objects or disallowed CPUs), build your own instance using
+    /// [`Topology::builder()`] instead.
+    ///
+    /// The process-wide topology is a snapshot of the system at the time where
+    /// it was built, and each call to this method returns the current
+    /// snapshot. If you are interested in hotplug events, use
+    /// [`Topology::reload_global()`] to replace the current snapshot with a
+    /// fresh one. This is why snapshots are returned as an [`Arc`] rather than
+    /// a `&'static Topology`: snapshots that you obtained before a reload
+    /// remain valid for as long as you hold them, and are freed once the last
+    /// [`Arc`] pointing to them is dropped. Call this method again to get the
+    /// new snapshot.
+    ///
+    /// # Panics
+    ///
+    /// If the topology needs to be built and hwloc fails to do so. This should
+    /// only happen in extreme circumstances like memory exhaustion.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use hwlocality::Topology;
+    /// let topology = Topology::global();
+    /// assert!(topology.depth() >= 2);
+    /// ```
+    pub fn global() -> Arc<Self> {
+        if let Some(topology) = &*GLOBAL_INSTANCE
+            .read()
+            .unwrap_or_else(PoisonError::into_inner)
+        {
+            return Arc::clone(topology);
+        }
+        let mut instance = GLOBAL_INSTANCE
+            .write()
+            .unwrap_or_else(PoisonError::into_inner);
+        // Another thread may have built the topology while we were waiting
+        // for the write lock, in which case get_or_insert_with keeps it
+        Arc::clone(instance.get_or_insert_with(|| {
+            Arc::new(Self::new().expect("Failed to build the process-wide topology"))
+        }))
+    }
+
+    /// Replace the process-wide topology with a freshly built one
+    ///
+    /// Subsequent calls to [`Topology::global()`] will return the new
+    /// topology, which is also returned by this method. Snapshots of the
+    /// previous process-wide topology that are still in use remain valid, and
+    /// the previous topology is freed once the last of them is dropped.
+    ///
+    /// Building a topology is expensive, so you should only call this method
+    /// when the topology of the system is known to have changed (e.g. CPU or
+    /// memory hotplug), not periodically in a tight loop.
+    ///
+    /// # Errors
+    ///
+    /// - [`RawHwlocError`] if hwloc fails to build the new topology. In that
+    ///   case, the previous process-wide topology is kept.
+    pub fn reload_global() -> Result<Arc<Self>, RawHwlocError> {
+        let topology = Arc::new(Self::new()?);
+        *GLOBAL_INSTANCE
+            .write()
+            .unwrap_or_else(PoisonError::into_inner) = Some(Arc::clone(&topology));
+        Ok(topology)
+    }
+
     /// Prepare to create a Topology with custom configuration
     ///
     /// # Examples
@@ -325,6 +484,24 @@ impl Topology {
         unsafe { ptr.as_ref().as_newtype() }
     }
 
+    /// Owned summary of all the feature support flags of this topology
+    ///
+    /// This is an owned copy of the flags exposed by
+    /// [`Topology::feature_support()`], which can be displayed or serialized
+    /// in one go for diagnostic purposes.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use hwlocality::Topology;
+    /// let topology = Topology::new()?;
+    /// println!("This platform supports...\n{}", topology.support_summary());
+    /// # Ok::<(), eyre::Report>(())
+    /// ```
+    pub fn support_summary(&self) -> SupportSummary {
+        SupportSummary::from(self.feature_support())
+    }
+
     /// Quickly check a support flag
     ///
     /// # Examples
@@ -781,6 +958,73 @@ impl Topology {
         }
     }
 
+    /// Allowed CPUs that are not covered by any of the given `objects`
+    ///
+    /// This is the complement of the union of the cpusets of `objects` with
+    /// respect to [`Topology::allowed_cpuset()`], which means that offline
+    /// CPUs, and disallowed CPUs when [`BuildFlags::INCLUDE_DISALLOWED`] is
+    /// set, will never be part of the output.
+    ///
+    /// Objects without a cpuset (I/O and Misc objects) do not cover any CPU.
+    ///
+    /// # Errors
+    ///
+    /// - [`ForeignObjectError`] if one of the `objects` does not belong to this
+    ///   topology.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use hwlocality::{object::types::ObjectType, Topology};
+    /// # let topology = Topology::test_instance();
+    /// let first_core = topology.objects_with_type(ObjectType::Core).next().unwrap();
+    /// let other_cpus = topology.cpuset_not_covered_by([first_core])?;
+    /// assert!(!other_cpus.intersects(first_core.cpuset().unwrap()));
+    /// # Ok::<_, eyre::Report>(())
+    /// ```
+    pub fn cpuset_not_covered_by<'objects>(
+        &self,
+        objects: impl IntoIterator<Item = &'objects TopologyObject>,
+    ) -> Result<CpuSet, ForeignObjectError> {
+        let mut result = self.allowed_cpuset().clone_target();
+        for object in objects {
+            if !self.contains(object) {
+                return Err(object.into());
+            }
+            if let Some(cpuset) = object.cpuset() {
+                result -= cpuset;
+            }
+        }
+        Ok(result)
+    }
+
+    /// Allowed CPUs that are not part of any of the given `allocations`
+    ///
+    /// This is the complement of the union of `allocations` with respect to
+    /// [`Topology::allowed_cpuset()`], which means that offline CPUs, and
+    /// disallowed CPUs when [`BuildFlags::INCLUDE_DISALLOWED`] is set, will
+    /// never be part of the output, even if they are not part of any
+    /// allocation. This makes it suitable for finding out which CPUs remain
+    /// available once a number of jobs have been assigned CPUs.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use hwlocality::{cpu::cpuset::CpuSet, Topology};
+    /// # let topology = Topology::test_instance();
+    /// let first_pu = topology.cpuset().first_set().unwrap();
+    /// let remaining = topology.unallocated_cpuset(&[CpuSet::from(first_pu)]);
+    /// assert!(!remaining.is_set(first_pu));
+    /// # Ok::<_, eyre::Report>(())
+    /// ```
+    pub fn unallocated_cpuset(&self, allocations: &[CpuSet]) -> CpuSet {
+        let mut result = self.allowed_cpuset().clone_target();
+        for allocation in allocations {
+            result -= allocation;
+        }
+        result
+    }
+
     /// Topology node set
     ///
     /// This is equivalent to calling [`TopologyObject::nodeset()`] on
@@ -894,11 +1138,514 @@ impl Topology {
     }
 }
 
+/// # Application-defined data
+///
+/// Each topology can carry one value of an arbitrary application-defined type,
+/// which makes it easy to pass application context around along with the
+/// topology that it relates to. Each object of the topology can similarly
+/// carry one value of an arbitrary type, e.g. some scheduling state, which is
+/// looked up using the object's [`ObjectId`].
+///
+/// This data is not exported to XML, and it is not copied when the topology is
+/// cloned: clones of a topology start without any application-defined data.
+/// The data that is attached to an object is dropped when the object is
+/// removed from the topology by a topology edit.
+//
+// --- Implementation details ---
+//
+// Upstream docs: https://hwloc.readthedocs.io/en/v2.9/group__hwlocality__configuration.html
+impl Topology {
+    /// Attach application-defined data to this topology
+    ///
+    /// Any data that was previously attached to this topology is returned.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use hwlocality::Topology;
+    /// let mut topology = Topology::new()?;
+    /// topology.set_userdata("hello");
+    /// assert_eq!(topology.userdata::<&str>(), Some(&"hello"));
+    /// assert_eq!(topology.userdata::<u32>(), None);
+    /// # Ok::<_, eyre::Report>(())
+    /// ```
+    pub fn set_userdata<T: Any + Send + Sync>(
+        &mut self,
+        value: T,
+    ) -> Option<Box<dyn Any + Send + Sync>> {
+        self.userdata.replace(Box::new(value))
+    }
+
+    /// Application-defined data attached to this topology, if any
+    ///
+    /// Returns `None` if no data was attached to this topology using
+    /// [`set_userdata()`], or if that data is not of type `T`.
+    ///
+    /// [`set_userdata()`]: Self::set_userdata()
+    pub fn userdata<T: Any>(&self) -> Option<&T> {
+        self.userdata.as_ref()?.downcast_ref()
+    }
+
+    /// Mutable access to the application-defined data attached to this
+    /// topology, if any
+    ///
+    /// Returns `None` if no data was attached to this topology using
+    /// [`set_userdata()`], or if that data is not of type `T`.
+    ///
+    /// [`set_userdata()`]: Self::set_userdata()
+    pub fn userdata_mut<T: Any>(&mut self) -> Option<&mut T> {
+        self.userdata.as_mut()?.downcast_mut()
+    }
+
+    /// Detach the application-defined data from this topology, if any
+    pub fn take_userdata(&mut self) -> Option<Box<dyn Any + Send + Sync>> {
+        self.userdata.take()
+    }
+
+    /// Attach application-defined data to an object of this topology
+    ///
+    /// Any data that was previously attached to this object is returned.
+    ///
+    /// # Errors
+    ///
+    /// - [`ForeignObjectError`] if there is no object with identifier
+    ///   `object` in this topology
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use hwlocality::Topology;
+    /// let mut topology = Topology::new()?;
+    /// let root = topology.root_object().id();
+    /// topology.set_object_userdata(root, 42u32)?;
+    /// assert_eq!(topology.object_userdata::<u32>(root), Some(&42));
+    /// assert_eq!(topology.object_userdata::<&str>(root), None);
+    /// # Ok::<_, eyre::Report>(())
+    /// ```
+    pub fn set_object_userdata<T: Any + Send + Sync>(
+        &mut self,
+        object: ObjectId,
+        value: T,
+    ) -> Result<Option<Box<dyn Any + Send + Sync>>, ForeignObjectError> {
+        if self.object_by_id(object).is_none() {
+            return Err(object.into());
+        }
+        Ok(self.object_userdata.insert(object.into(), Box::new(value)))
+    }
+
+    /// Application-defined data attached to an object of this topology, if any
+    ///
+    /// Returns `None` if no data was attached to this object using
+    /// [`set_object_userdata()`], or if that data is not of type `T`.
+    ///
+    /// [`set_object_userdata()`]: Self::set_object_userdata()
+    pub fn object_userdata<T: Any>(&self, object: ObjectId) -> Option<&T> {
+        self.object_userdata.get(&object.into())?.downcast_ref()
+    }
+
+    /// Mutable access to the application-defined data attached to an object of
+    /// this topology, if any
+    ///
+    /// Returns `None` if no data was attached to this object using
+    /// [`set_object_userdata()`], or if that data is not of type `T`.
+    ///
+    /// [`set_object_userdata()`]: Self::set_object_userdata()
+    pub fn object_userdata_mut<T: Any>(&mut self, object: ObjectId) -> Option<&mut T> {
+        self.object_userdata.get_mut(&object.into())?.downcast_mut()
+    }
+
+    /// Detach the application-defined data from an object of this topology,
+    /// if any
+    pub fn take_object_userdata(&mut self, object: ObjectId) -> Option<Box<dyn Any + Send + Sync>> {
+        self.object_userdata.remove(&object.into())
+    }
+}
+
+/// Application-defined data attached to a [`Topology`] or its objects
+type UserData = Box<dyn Any + Send + Sync>;
+
+/// # Raw hwloc interoperability
+impl Topology {
+    /// Raw pointer to the underlying hwloc topology
+    ///
+    /// This is meant for interoperability with other C libraries which consume
+    /// hwloc topologies, such as MPI implementations or OpenMP runtimes.
+    ///
+    /// The pointer is only valid as long as this `Topology` is not modified or
+    /// dropped, and the topology must not be modified through it, which
+    /// includes calling hwloc functions that take a `hwloc_topology_t` and
+    /// refresh lazily computed caches. If you need to pass this topology to C
+    /// code which may modify it, use `Topology::with_raw()` instead.
+    pub fn as_raw(&self) -> *const hwloc_topology {
+        self.as_ptr()
+    }
+
+    /// Take ownership of a topology that was created by another C library
+    ///
+    /// The resulting `Topology` will destroy the underlying hwloc topology
+    /// when it is dropped. If you only want to access the topology temporarily
+    /// while the C library retains ownership, use [`borrow_from_raw()`].
+    ///
+    /// # Safety
+    ///
+    /// - `topology` must point to a valid hwloc topology, which has been fully
+    ///   built with `hwloc_topology_load()`.
+    /// - Ownership of this topology is transferred to the returned `Topology`,
+    ///   so it must not be used, modified or destroyed by anyone else
+    ///   afterwards.
+    /// - Before hwloc 2.3, where this cannot be ensured automatically, any
+    ///   modification to the topology must have been followed by a full
+    ///   refresh of its lazily computed caches.
+    ///
+    /// [`borrow_from_raw()`]: Self::borrow_from_raw()
+    #[allow(unused_mut)]
+    pub unsafe fn from_raw(topology: NonNull<hwloc_topology>) -> Self {
+        // SAFETY: Per input precondition
+        let mut topology = unsafe { Self::wrap(topology) };
+        #[cfg(feature = "hwloc-2_3_0")]
+        topology.refresh();
+        #[cfg(not(feature = "hwloc-2_3_0"))]
+        topology.index_objects();
+        topology
+    }
+
+    /// Temporarily access a topology that belongs to another C library
+    ///
+    /// The underlying hwloc topology will not be destroyed when the resulting
+    /// [`TopologyRef`] is dropped. Since the [`TopologyRef`] borrows
+    /// `topology`, it cannot be used beyond the scope of the pointer that the
+    /// C library handed out, which should match the time where the topology
+    /// is known to be valid.
+    ///
+    /// # Examples
+    ///
+    /// ```compile_fail
+    /// # use hwlocality::Topology;
+    /// # let owned = Topology::test_instance().clone().into_raw();
+    /// let borrowed = {
+    ///     let raw = owned;
+    ///     // Error: the TopologyRef cannot outlive raw
+    ///     unsafe { Topology::borrow_from_raw(&raw) }
+    /// };
+    /// ```
+    ///
+    /// # Safety
+    ///
+    /// - `topology` must point to a valid hwloc topology, which has been fully
+    ///   built with `hwloc_topology_load()` and whose lazily computed caches
+    ///   have all been refreshed after the last modification.
+    /// - This topology must remain valid and must not be modified or destroyed
+    ///   for the entire `'topology` lifetime.
+    pub unsafe fn borrow_from_raw<'topology>(
+        topology: &'topology NonNull<hwloc_topology>,
+    ) -> TopologyRef<'topology> {
+        // SAFETY: Per input precondition
+        let mut topology = unsafe { Self::wrap(*topology) };
+        topology.index_objects();
+        TopologyRef(ManuallyDrop::new(topology), PhantomData)
+    }
+
+    /// Hand over ownership of this topology to another C library
+    ///
+    /// The returned topology must eventually be destroyed using
+    /// `hwloc_topology_destroy()` or handed back to [`from_raw()`]. Any
+    /// application-defined data that was attached to it using
+    /// [`set_userdata()`] or [`set_object_userdata()`] is dropped, whereas
+    /// hwloc's own userdata pointer is left untouched.
+    ///
+    /// [`from_raw()`]: Self::from_raw()
+    /// [`set_object_userdata()`]: Self::set_object_userdata()
+    /// [`set_userdata()`]: Self::set_userdata()
+    pub fn into_raw(mut self) -> NonNull<hwloc_topology> {
+        std::mem::take(&mut *self.userdata);
+        #[cfg(feature = "hwloc-2_3_0")]
+        std::mem::take(&mut self.edit_hooks);
+        std::mem::take(&mut self.object_index);
+        std::mem::take(&mut *self.object_userdata);
+        self.release_id();
+        ManuallyDrop::new(self).raw
+    }
+}
+
+/// Borrowed [`Topology`] that belongs to another C library
+///
+/// This is returned by [`Topology::borrow_from_raw()`], and it can be used as a
+/// `&Topology` via [`Deref`].
+//
+// --- Implementation details ---
+//
+// # Safety
+//
+// As a type invariant, the inner topology is valid for 'topology, and must not
+// be destroyed on Drop nor be mutated.
+pub struct TopologyRef<'topology>(ManuallyDrop<Topology>, PhantomData<&'topology Topology>);
+//
+impl AsRef<Topology> for TopologyRef<'_> {
+    fn as_ref(&self) -> &Topology {
+        &self.0
+    }
+}
+//
+impl Borrow<Topology> for TopologyRef<'_> {
+    fn borrow(&self) -> &Topology {
+        &self.0
+    }
+}
+//
+impl Debug for TopologyRef<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("TopologyRef").field(&*self.0).finish()
+    }
+}
+//
+impl Deref for TopologyRef<'_> {
+    type Target = Topology;
+
+    fn deref(&self) -> &Topology {
+        &self.0
+    }
+}
+//
+impl Drop for TopologyRef<'_> {
+    fn drop(&mut self) {
+        // Only free Rust-side state, the hwloc topology is borrowed
+        std::mem::take(&mut self.0.object_index);
+        self.0.release_id();
+    }
+}
+
+/// Lookup tables for the objects of a [`Topology`]
+//
+// --- Implementation details ---
+//
+// # Safety
+//
+// As a type invariant, all pointers point to objects of the topology that owns
+// this index, and every object of the topology is listed (see the `Topology`
+// type invariants for how this is maintained).
+#[derive(Default)]
+struct ObjectIndex {
+    /// Objects indexed by global persistent index
+    by_id: HashMap<TopologyObjectID, NonNull<TopologyObject>>,
+
+    /// Objects of each type, ordered by increasing depth then logical index
+    by_type: HashMap<ObjectType, Box<[NonNull<TopologyObject>]>>,
+
+    /// PUs indexed by OS index, built on first use
+    pus_by_os_index: OnceLock<HashMap<usize, NonNull<TopologyObject>>>,
+
+    /// NUMA nodes indexed by OS index, built on first use
+    nodes_by_os_index: OnceLock<HashMap<usize, NonNull<TopologyObject>>>,
+
+    /// Summary of the CPU cache hierarchy, built on first use
+    cache_hierarchy: OnceLock<CacheHierarchySummary>,
+}
+//
+// SAFETY: Object pointers are only used to produce shared references, and
+//         TopologyObject is Sync
+unsafe impl Send for ObjectIndex {}
+//
+// SAFETY: Object pointers are only used to produce shared references, and
+//         TopologyObject is Sync
+unsafe impl Sync for ObjectIndex {}
+
 // # General-purpose internal utilities
 impl Topology {
+    /// Wrap a fully built hwloc topology
+    ///
+    /// # Safety
+    ///
+    /// Safety preconditions of [`Topology::from_raw()`] apply, except for the
+    /// refreshing requirement which is left to the caller.
+    ///
+    /// The object index is left empty, and must be built with
+    /// [`Topology::index_objects()`] once the topology has been refreshed.
+    ///
+    /// The topology is given a fresh [identifier](Self::id()), unless the
+    /// underlying hwloc topology is already wrapped by another `Topology`, in
+    /// which case that topology's identifier is reused.
+    pub(crate) unsafe fn wrap(raw: NonNull<hwloc_topology>) -> Self {
+        let mut result = Self {
+            raw,
+            id: 0,
+            userdata: AssertUnwindSafe(None),
+            object_index: ObjectIndex::default(),
+            object_userdata: AssertUnwindSafe(HashMap::new()),
+            #[cfg(feature = "hwloc-2_3_0")]
+            edit_hooks: EditHooks::default(),
+            diagnostics: None,
+        };
+        let root = root_address(result.root_object());
+        let mut ids = TOPOLOGY_IDS.lock().unwrap_or_else(PoisonError::into_inner);
+        let (id, users) = ids
+            .entry(root)
+            .or_insert_with(|| (NEXT_TOPOLOGY_ID.fetch_add(1, Ordering::Relaxed), 0));
+        *users += 1;
+        result.id = *id;
+        result
+    }
+
+    /// Process-wide unique identifier of this topology
+    ///
+    /// Clones of a topology get a different identifier, but topology edits
+    /// preserve it.
+    pub(crate) fn id(&self) -> u64 {
+        self.id
+    }
+
+    /// Identifier of the topology whose root object is `root`
+    pub(crate) fn id_from_root(root: &TopologyObject) -> u64 {
+        TOPOLOGY_IDS
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .get(&root_address(root))
+            .expect("Objects should belong to a topology that is wrapped")
+            .0
+    }
+
+    /// Unregister this topology's identifier
+    ///
+    /// Must be called exactly once before the underlying hwloc topology is
+    /// destroyed or stops being wrapped by this `Topology`.
+    fn release_id(&self) {
+        let root = root_address(self.root_object());
+        let mut ids = TOPOLOGY_IDS.lock().unwrap_or_else(PoisonError::into_inner);
+        let (_, users) = ids
+            .get_mut(&root)
+            .expect("Wrapped topologies should be registered");
+        *users -= 1;
+        if *users == 0 {
+            ids.remove(&root);
+        }
+    }
+
+    /// (Re)build the object index
+    ///
+    /// Must be called whenever the topology is created or refreshed. Userdata
+    /// of objects that do not exist anymore is dropped.
+    pub(crate) fn index_objects(&mut self) {
+        let mut by_id = HashMap::new();
+        let mut by_type = HashMap::<_, Vec<_>>::new();
+        for obj in self.objects() {
+            let ptr = NonNull::from(obj);
+            by_id.insert(obj.global_persistent_index(), ptr);
+            by_type.entry(obj.object_type()).or_default().push(ptr);
+        }
+        self.object_userdata.retain(|id, _| by_id.contains_key(id));
+        self.object_index = ObjectIndex {
+            by_id,
+            by_type: by_type
+                .into_iter()
+                .map(|(ty, objects)| (ty, objects.into()))
+                .collect(),
+            pus_by_os_index: OnceLock::new(),
+            nodes_by_os_index: OnceLock::new(),
+            cache_hierarchy: OnceLock::new(),
+        };
+    }
+
+    /// Look up an object by global persistent index using the object index
+    pub(crate) fn indexed_object(&self, id: TopologyObjectID) -> Option<&TopologyObject> {
+        self.object_index.by_id.get(&id).map(|ptr| {
+            // SAFETY: - The object index only contains pointers to objects of
+            //           this topology (type invariant)
+            //         - Output lifetime is bound to &self, during which the
+            //           topology cannot be modified
+            unsafe { ptr.as_ref() }
+        })
+    }
+
+    /// Get the OS index -> object map for PUs or NUMA nodes from the object
+    /// index, building it on first use
+    ///
+    /// # Panics
+    ///
+    /// If `object_type` is neither [`ObjectType::PU`] nor
+    /// [`ObjectType::NUMANode`].
+    pub(crate) fn indexed_objects_by_os_index(
+        &self,
+        object_type: ObjectType,
+    ) -> &HashMap<usize, NonNull<TopologyObject>> {
+        #[allow(clippy::wildcard_enum_match_arm)]
+        let map = match object_type {
+            ObjectType::PU => &self.object_index.pus_by_os_index,
+            ObjectType::NUMANode => &self.object_index.nodes_by_os_index,
+            other => unreachable!("{other} objects are not indexed by OS index"),
+        };
+        map.get_or_init(|| {
+            self.object_index
+                .by_type
+                .get(&object_type)
+                .map_or(&[][..], |objects| &objects[..])
+                .iter()
+                .map(|ptr| {
+                    // SAFETY: - The object index only contains pointers to
+                    //           objects of this topology (type invariant)
+                    //         - Reference does not escape this closure
+                    let obj = unsafe { ptr.as_ref() };
+                    let os_index = obj
+                        .os_index()
+                        .expect("PUs and NUMA nodes should have an OS index");
+                    (os_index, *ptr)
+                })
+                .collect()
+        })
+    }
+
+    /// Get the CPU cache hierarchy summary from the object index, building it
+    /// on first use
+    pub(crate) fn indexed_cache_hierarchy(&self) -> &CacheHierarchySummary {
+        self.object_index
+            .cache_hierarchy
+            .get_or_init(|| CacheHierarchySummary::new(self))
+    }
+
+    /// Look up all objects of a certain type using the object index
+    ///
+    /// Objects are ordered by increasing depth, then by logical index.
+    pub(crate) fn indexed_objects_with_type(
+        &self,
+        object_type: ObjectType,
+    ) -> impl DoubleEndedIterator<Item = &TopologyObject> + Clone + ExactSizeIterator + FusedIterator
+    {
+        self.object_index
+            .by_type
+            .get(&object_type)
+            .map_or(&[][..], |objects| &objects[..])
+            .iter()
+            .map(|ptr| {
+                // SAFETY: - The object index only contains pointers to objects
+                //           of this topology (type invariant)
+                //         - Output lifetime is bound to &self, during which the
+                //           topology cannot be modified
+                unsafe { ptr.as_ref() }
+            })
+    }
+
+    /// Look up the `index`-th object of a certain type using the object index
+    ///
+    /// Objects are ordered as in [`Topology::indexed_objects_with_type()`].
+    pub(crate) fn indexed_object_with_type(
+        &self,
+        object_type: ObjectType,
+        index: usize,
+    ) -> Option<&TopologyObject> {
+        self.object_index
+            .by_type
+            .get(&object_type)
+            .and_then(|objects| objects.get(index))
+            .map(|ptr| {
+                // SAFETY: - The object index only contains pointers to objects
+                //           of this topology (type invariant)
+                //         - Output lifetime is bound to &self, during which the
+                //           topology cannot be modified
+                unsafe { ptr.as_ref() }
+            })
+    }
+
     /// Contained hwloc topology pointer (for interaction with hwloc)
     pub(crate) fn as_ptr(&self) -> *const hwloc_topology {
-        self.0.as_ptr()
+        self.raw.as_ptr()
     }
 
     /// Contained mutable hwloc topology pointer (for interaction with hwloc)
@@ -908,7 +1655,7 @@ impl Topology {
     /// unless followed by `hwloc_topology_refresh()`. This subtlety is handled
     /// by the [`Topology::edit()`] mechanism.
     pub(crate) fn as_mut_ptr(&mut self) -> *mut hwloc_topology {
-        self.0.as_ptr()
+        self.raw.as_ptr()
     }
 
     /// Check if a [`TopologyObject`] is part of this topology
@@ -932,9 +1679,34 @@ impl Topology {
     }
 }
 
+/// Clones are made using `hwloc_topology_dup()`, and their lazily computed
+/// caches are evaluated eagerly before they are returned, so that they can be
+/// shared between threads like the original topology right away.
+///
+/// Since cloning only requires `&self`, a topology that is shared between
+/// threads can be cloned from any of them, concurrently with other read-only
+/// queries. Userdata and edit hooks are not copied to the clone.
 impl Clone for Topology {
     #[doc(alias = "hwloc_topology_dup")]
+    #[allow(unused_mut)]
     fn clone(&self) -> Self {
+        let mut clone = self.duplicate();
+        #[cfg(feature = "hwloc-2_3_0")]
+        clone.refresh();
+        #[cfg(not(feature = "hwloc-2_3_0"))]
+        clone.index_objects();
+        clone
+    }
+}
+//
+impl Topology {
+    /// Duplicate this topology without evaluating its lazy caches
+    ///
+    /// The object index of the duplicate is empty, so it must be refreshed
+    /// (or, before hwloc 2.3, indexed) before it is exposed to the user. This
+    /// lets operations that modify the duplicate right away avoid doing this
+    /// work twice.
+    pub(crate) fn duplicate(&self) -> Self {
         let mut clone = ptr::null_mut();
         // SAFETY: - Topology is trusted to contain a valid ptr (type invariant)
         //         - hwloc ops are trusted not to modify *const parameters
@@ -944,7 +1716,12 @@ impl Clone for Topology {
         })
         .expect("Duplicating a topology should not fail");
 
-        Self(NonNull::new(clone).expect("Got null pointer from hwloc_topology_dup"))
+        let clone = NonNull::new(clone).expect("Got null pointer from hwloc_topology_dup");
+        // SAFETY: hwloc_topology_dup produces a fully built topology if it
+        //         succeeds
+        let mut clone = unsafe { Self::wrap(clone) };
+        clone.diagnostics = self.diagnostics.clone();
+        clone
     }
 }
 
@@ -957,7 +1734,8 @@ impl Debug for Topology {
             .field("is_abi_compatible", &self.is_abi_compatible())
             .field("build_flags", &self.build_flags())
             .field("is_this_system", &self.is_this_system())
-            .field("feature_support", self.feature_support());
+            .field("feature_support", self.feature_support())
+            .field("discovery_diagnostics", &self.discovery_diagnostics());
         let type_filters = enum_iterator::all::<ObjectType>()
             .map(|ty| {
                 (
@@ -1011,9 +1789,27 @@ impl Debug for Topology {
     }
 }
 
+/// Topologies are deserialized from hwloc XML, as produced by the
+/// [`Serialize`] implementation.
+///
+/// The topology is built with default [`TopologyBuilder`] settings. Use
+/// [`TopologyBuilder::from_xml()`] directly if you need more control.
+#[cfg(feature = "serde")]
+impl<'de> Deserialize<'de> for Topology {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let xml = String::deserialize(deserializer)?;
+        Self::builder()
+            .from_xml(&xml)
+            .map_err(de::Error::custom)?
+            .build()
+            .map_err(de::Error::custom)
+    }
+}
+
 impl Drop for Topology {
     #[doc(alias = "hwloc_topology_destroy")]
     fn drop(&mut self) {
+        self.release_id();
         // SAFETY: - Topology is trusted to contain a valid ptr (type invariant)
         //         - Topology will not be usable again after Drop
         unsafe { hwlocality_sys::hwloc_topology_destroy(self.as_mut_ptr()) }
@@ -1108,10 +1904,28 @@ impl PartialEq for Topology {
 
 impl Pointer for Topology {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        <NonNull<hwloc_topology> as Pointer>::fmt(&self.0, f)
+        <NonNull<hwloc_topology> as Pointer>::fmt(&self.raw, f)
+    }
+}
+
+/// Topologies are serialized as a string of hwloc XML, as produced by
+/// [`Topology::export_xml()`] with default flags.
+#[cfg(feature = "serde")]
+impl Serialize for Topology {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let xml = self
+            .export_xml(export::xml::XMLExportFlags::empty())
+            .map_err(ser::Error::custom)?;
+        serializer.serialize_str(&xml)
     }
 }
 
+/// Key of a topology in [`TOPOLOGY_IDS`]
+fn root_address(root: &TopologyObject) -> usize {
+    let root: *const TopologyObject = root;
+    root as usize
+}
+
 // SAFETY: No shared mutability
 unsafe impl Send for Topology {}
 
@@ -1122,7 +1936,11 @@ unsafe impl Sync for Topology {}
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::{ffi::PositiveInt, topology::builder::tests::DataSource};
+    use crate::{
+        ffi::PositiveInt,
+        strategies::{any_object, topology_related_set},
+        topology::builder::tests::DataSource,
+    };
     use bitflags::Flags;
     use proptest::prelude::*;
     #[allow(unused)]
@@ -1139,6 +1957,7 @@ mod tests {
             BitAnd, BitAndAssign, BitOr, BitOrAssign, BitXor, BitXorAssign, Not, Sub, SubAssign,
         },
         panic::UnwindSafe,
+        sync::Arc,
     };
 
     // Check that public types in this module keep implementing all expected
@@ -1164,6 +1983,15 @@ mod tests {
     assert_impl_all!(DistributeError:
         Clone, Error, Hash, Sized, Sync, Unpin, UnwindSafe
     );
+    assert_impl_all!(TopologyRef<'static>:
+        AsRef<Topology>, Borrow<Topology>, Debug, Deref<Target=Topology>, Drop,
+        Sized, Sync, Unpin, UnwindSafe
+    );
+    assert_not_impl_any!(TopologyRef<'static>:
+        Binary, Clone, Default, Display, IntoIterator, LowerExp, LowerHex,
+        Octal, PartialEq, Pointer, Read, UpperExp, UpperHex, fmt::Write,
+        io::Write
+    );
     assert_not_impl_any!(DistributeError:
         Binary, Copy, Default, Deref, Drop, IntoIterator,
         LowerExp, LowerHex, Octal, PartialOrd, Pointer, Read,
@@ -1175,6 +2003,155 @@ mod tests {
         assert_eq!(DistributeFlags::default(), DistributeFlags::empty());
     }
 
+    #[test]
+    fn global() {
+        // The global topology is built once and then reused
+        let first = Topology::global();
+        assert!(Arc::ptr_eq(&first, &Topology::global()));
+        assert_eq!(first.build_flags(), BuildFlags::empty());
+
+        // Reloading replaces the global topology, but keeps the old one valid
+        // until its last user is done with it
+        let reloaded = Topology::reload_global().unwrap();
+        assert!(!Arc::ptr_eq(&first, &reloaded));
+        assert!(Arc::ptr_eq(&reloaded, &Topology::global()));
+        assert_eq!(first.cpuset(), reloaded.cpuset());
+        let first_weak = Arc::downgrade(&first);
+        drop(first);
+        assert!(first_weak.upgrade().is_none());
+    }
+
+    #[test]
+    fn userdata() {
+        // Fresh topologies have no userdata
+        let mut topology = Topology::test_instance().clone();
+        assert_eq!(topology.userdata::<u32>(), None);
+        assert!(topology.take_userdata().is_none());
+
+        // Userdata can be set, queried with the right type and modified
+        let data = Arc::new(42u32);
+        assert!(topology.set_userdata(Arc::clone(&data)).is_none());
+        assert_eq!(topology.userdata::<Arc<u32>>(), Some(&data));
+        assert_eq!(topology.userdata::<u32>(), None);
+        assert_eq!(topology.userdata_mut::<u32>(), None);
+        *topology.userdata_mut::<Arc<u32>>().unwrap() = Arc::new(24);
+        assert_eq!(Arc::strong_count(&data), 1);
+        assert_eq!(**topology.userdata::<Arc<u32>>().unwrap(), 24);
+
+        // Userdata is not shared with clones
+        let clone = topology.clone();
+        assert_eq!(clone.userdata::<Arc<u32>>(), None);
+
+        // Setting userdata again returns the previous value
+        let old = topology.set_userdata(Arc::clone(&data)).unwrap();
+        assert_eq!(**old.downcast::<Arc<u32>>().unwrap(), 24);
+        assert_eq!(Arc::strong_count(&data), 2);
+
+        // Taking the userdata detaches it
+        let old = topology.take_userdata().unwrap();
+        assert_eq!(topology.userdata::<Arc<u32>>(), None);
+        assert!(topology.take_userdata().is_none());
+        std::mem::drop(old);
+        assert_eq!(Arc::strong_count(&data), 1);
+
+        // Userdata is dropped along with the topology
+        topology.set_userdata(Arc::clone(&data));
+        assert_eq!(Arc::strong_count(&data), 2);
+        std::mem::drop(topology);
+        assert_eq!(Arc::strong_count(&data), 1);
+    }
+
+    #[test]
+    fn object_userdata() {
+        let mut topology = Topology::test_instance().clone();
+        let root = topology.root_object().id();
+        let pu = topology
+            .objects_with_type(ObjectType::PU)
+            .next()
+            .unwrap()
+            .id();
+
+        // Fresh topologies have no object userdata
+        assert_eq!(topology.object_userdata::<u32>(root), None);
+        assert!(topology.take_object_userdata(root).is_none());
+
+        // Object userdata can be set, queried and modified per object
+        let data = Arc::new(42u32);
+        assert!(topology
+            .set_object_userdata(root, Arc::clone(&data))
+            .unwrap()
+            .is_none());
+        assert_eq!(topology.object_userdata::<Arc<u32>>(root), Some(&data));
+        assert_eq!(topology.object_userdata::<Arc<u32>>(pu), None);
+        assert_eq!(topology.object_userdata::<u32>(root), None);
+        *topology.object_userdata_mut::<Arc<u32>>(root).unwrap() = Arc::new(24);
+        assert_eq!(Arc::strong_count(&data), 1);
+
+        // Object userdata is not shared with clones
+        topology.set_object_userdata(pu, Arc::clone(&data)).unwrap();
+        let clone = topology.clone();
+        assert_eq!(clone.object_userdata::<Arc<u32>>(pu), None);
+
+        // Taking object userdata detaches it
+        let old = topology.take_object_userdata(pu).unwrap();
+        assert_eq!(topology.object_userdata::<Arc<u32>>(pu), None);
+        std::mem::drop(old);
+        assert_eq!(Arc::strong_count(&data), 1);
+
+        // Object userdata is dropped along with the topology
+        topology.set_object_userdata(pu, Arc::clone(&data)).unwrap();
+        assert_eq!(Arc::strong_count(&data), 2);
+        std::mem::drop(topology);
+        assert_eq!(Arc::strong_count(&data), 1);
+    }
+
+    #[test]
+    fn raw_ownership() {
+        // Hand a topology over to C code, keeping some userdata around
+        let reference = Topology::test_instance();
+        let mut topology = reference.clone();
+        let data = Arc::new(42u32);
+        topology.set_userdata(Arc::clone(&data));
+        let raw = topology.into_raw();
+        assert_eq!(Arc::strong_count(&data), 1);
+
+        // hwloc's userdata pointer belongs to the C code, and is preserved
+        let mut c_data = 24u32;
+        let c_data_ptr: *mut u32 = &mut c_data;
+        // SAFETY: - raw comes from a valid topology
+        //         - hwloc never reads or modifies the userdata pointer
+        unsafe { hwlocality_sys::hwloc_topology_set_userdata(raw.as_ptr(), c_data_ptr.cast()) };
+
+        // Borrow the topology from C code
+        {
+            // SAFETY: raw comes from a valid topology, which is not modified
+            //         while borrowed
+            let borrowed = unsafe { Topology::borrow_from_raw(&raw) };
+            assert_eq!(borrowed.as_raw(), raw.as_ptr().cast_const());
+            assert_eq!(&*borrowed, reference);
+            assert_eq!(borrowed.as_ref(), reference);
+            assert_eq!(Borrow::<Topology>::borrow(&borrowed), reference);
+            assert_eq!(
+                format!("{borrowed:?}"),
+                format!("TopologyRef({reference:?})")
+            );
+        }
+
+        // Take ownership of the topology back
+        // SAFETY: raw comes from a valid topology, which is not otherwise
+        //         used after this point
+        let mut topology = unsafe { Topology::from_raw(raw) };
+        assert_eq!(topology.as_raw(), raw.as_ptr().cast_const());
+        assert_eq!(&topology, reference);
+        assert_eq!(topology.userdata::<Arc<u32>>(), None);
+        topology.set_userdata(Arc::clone(&data));
+        // SAFETY: - topology contains a valid topology pointer
+        //         - hwloc_topology_get_userdata only reads the topology
+        let hwloc_userdata =
+            unsafe { hwlocality_sys::hwloc_topology_get_userdata(topology.as_mut_ptr()) };
+        assert_eq!(hwloc_userdata, c_data_ptr.cast());
+    }
+
     #[test]
     fn clone() -> Result<(), TestCaseError> {
         let topology = Topology::test_instance();
@@ -1191,6 +2168,43 @@ mod tests {
         Ok(())
     }
 
+    /// Check that a shared topology can be cloned from several threads
+    #[test]
+    fn concurrent_clone() {
+        let topology = Topology::test_instance();
+        std::thread::scope(|scope| {
+            let clones = (0..4)
+                .map(|_| scope.spawn(|| topology.clone()))
+                .collect::<Vec<_>>();
+            for clone in clones {
+                let clone = clone.join().unwrap();
+                assert_eq!(topology, &clone);
+                assert_eq!(
+                    clone.objects_with_type(ObjectType::PU).count(),
+                    topology.objects_with_type(ObjectType::PU).count()
+                );
+            }
+        });
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn serde() {
+        let topology = Topology::test_instance();
+        let json = serde_json::to_string(topology).unwrap();
+        let xml = topology
+            .export_xml(export::xml::XMLExportFlags::empty())
+            .unwrap();
+        assert_eq!(json, serde_json::to_string(&*xml).unwrap());
+
+        let deserialized = serde_json::from_str::<Topology>(&json).unwrap();
+        assert_eq!(deserialized.objects().count(), topology.objects().count());
+        assert_eq!(deserialized.cpuset(), topology.cpuset());
+        assert_eq!(deserialized.nodeset(), topology.nodeset());
+
+        serde_json::from_str::<Topology>("\"not XML\"").unwrap_err();
+    }
+
     #[allow(clippy::print_stdout, clippy::use_debug)]
     #[test]
     fn debug_and_self_eq() {
@@ -1549,6 +2563,42 @@ mod tests {
         }
     }
 
+    proptest! {
+        #[test]
+        fn cpuset_not_covered_by(objects in prop::collection::vec(any_object(), 0..4)) {
+            let topology = Topology::test_instance();
+            let result = topology.cpuset_not_covered_by(objects.iter().copied());
+            if let Some(foreign) = objects.iter().find(|obj| !topology.contains(obj)) {
+                prop_assert_eq!(result, Err(ForeignObjectError::from(*foreign)));
+                return Ok(());
+            }
+            let mut expected = topology.allowed_cpuset().clone_target();
+            for obj in &objects {
+                if let Some(cpuset) = obj.cpuset() {
+                    expected -= cpuset;
+                }
+            }
+            prop_assert_eq!(result, Ok(expected));
+        }
+
+        #[test]
+        fn unallocated_cpuset(
+            allocations in prop::collection::vec(topology_related_set(Topology::cpuset), 0..4)
+        ) {
+            let topology = Topology::test_instance();
+            let result = topology.unallocated_cpuset(&allocations);
+            prop_assert!(topology.allowed_cpuset().includes(&result));
+            for allocation in &allocations {
+                prop_assert!(!result.intersects(allocation));
+            }
+            let allocated = allocations.iter().fold(CpuSet::new(), |acc, set| acc | set);
+            prop_assert_eq!(
+                &result | (&allocated & topology.allowed_cpuset()),
+                topology.allowed_cpuset().clone_target()
+            );
+        }
+    }
+
     /// Pick a random normal object from the foreign test instance
     fn foreign_normal_object() -> impl Strategy<Value = &'static TopologyObject> {
         static FOREIGNERS: OnceLock<Box<[&'static TopologyObject]>> = OnceLock::new();
@@ -0,0 +1,310 @@
+//! One-call machine description
+//!
+//! Monitoring agents and diagnostic tools commonly report a small set of
+//! facts about the machine they run on: how many packages, cores and PUs it
+//! has, how much memory, what the CPU caches look like... This module provides
+//! the [`TopologySummary`] type, which gathers these facts in one call to
+//! [`Topology::summary()`] and can be displayed in human-readable form or
+//! (with the `serde` feature) serialized.
+//!
+//! This functionality is an hwlocality-specific extension to the hwloc API.
+
+use super::Topology;
+use crate::{
+    cpu::cache::CacheLevelSummary,
+    object::{
+        attributes::ObjectAttributes,
+        types::{OSDeviceType, ObjectType},
+        TopologyObject,
+    },
+};
+#[cfg(feature = "serde")]
+use serde::{ser::SerializeStruct, Serialize, Serializer};
+use std::{
+    collections::HashSet,
+    fmt::{self, Display},
+};
+
+/// # Machine summary
+impl Topology {
+    /// Summarize the main properties of the machine in one call
+    ///
+    /// This gathers the number of packages, cores, PUs and NUMA nodes, the
+    /// total amount of memory, the CPU cache hierarchy, the number of cores
+    /// of each class on hybrid CPUs, and the number of PCI devices and GPUs
+    /// into a plain data struct.
+    ///
+    /// Object types that were filtered out of the topology (e.g. I/O objects,
+    /// which are not enumerated by default) are reported as zero objects.
+    ///
+    /// This functionality is an hwlocality-specific extension to the hwloc API.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use hwlocality::Topology;
+    /// let topology = Topology::new()?;
+    /// println!("{}", topology.summary());
+    /// # Ok::<(), eyre::Report>(())
+    /// ```
+    pub fn summary(&self) -> TopologySummary {
+        TopologySummary::new(self)
+    }
+}
+
+/// Owned summary of the main properties of a machine
+///
+/// Obtained via [`Topology::summary()`].
+#[derive(Clone, Debug, Default, Eq, Hash, PartialEq)]
+pub struct TopologySummary {
+    /// Number of CPU packages
+    pub packages: usize,
+
+    /// Number of CPU cores
+    pub cores: usize,
+
+    /// Number of PUs (hardware threads)
+    pub pus: usize,
+
+    /// Number of NUMA nodes
+    pub numa_nodes: usize,
+
+    /// Total amount of memory in bytes, including huge pages
+    pub total_memory: u64,
+
+    /// Properties of each level of the CPU cache hierarchy, by increasing
+    /// depth, as reported by [`Topology::cache_hierarchy_summary()`]
+    pub caches: Vec<CacheLevelSummary>,
+
+    /// Number of cores that are classified as [`Performance`] cores
+    ///
+    /// On homogeneous platforms, and on platforms where no information about
+    /// CPU kinds is available, all cores are considered to be performance
+    /// cores.
+    ///
+    /// [`Performance`]: crate::cpu::kind::CoreClass::Performance
+    #[cfg(feature = "hwloc-2_4_0")]
+    pub performance_cores: usize,
+
+    /// Number of cores that are classified as [`Efficiency`] cores
+    ///
+    /// [`Efficiency`]: crate::cpu::kind::CoreClass::Efficiency
+    #[cfg(feature = "hwloc-2_4_0")]
+    pub efficiency_cores: usize,
+
+    /// Number of PCI devices
+    pub pci_devices: usize,
+
+    /// Number of GPUs
+    ///
+    /// hwloc may expose the same GPU through several OS devices (e.g. a DRM
+    /// device and a CUDA device), so this counts the distinct parents of
+    /// [`OSDeviceType::GPU`] OS devices.
+    pub gpus: usize,
+}
+//
+impl TopologySummary {
+    /// Summarize a topology
+    fn new(topology: &Topology) -> Self {
+        let count = |ty| topology.objects_with_type(ty).count();
+        let gpus = topology
+            .os_devices()
+            .filter(|osdev| {
+                matches!(
+                    osdev.attributes(),
+                    Some(ObjectAttributes::OSDevice(attr)) if attr.device_type() == OSDeviceType::GPU
+                )
+            })
+            .filter_map(|gpu| gpu.parent())
+            .map(TopologyObject::global_persistent_index)
+            .collect::<HashSet<_>>()
+            .len();
+        #[cfg(feature = "hwloc-2_4_0")]
+        let count_cores_in = |set: crate::cpu::cpuset::CpuSet| {
+            topology
+                .objects_with_type(ObjectType::Core)
+                .filter(|core| core.cpuset().is_some_and(|cpuset| set.intersects(cpuset)))
+                .count()
+        };
+        Self {
+            packages: count(ObjectType::Package),
+            cores: count(ObjectType::Core),
+            pus: count(ObjectType::PU),
+            numa_nodes: count(ObjectType::NUMANode),
+            total_memory: topology.root_object().total_memory(),
+            caches: topology.cache_hierarchy_summary().levels().to_vec(),
+            #[cfg(feature = "hwloc-2_4_0")]
+            performance_cores: count_cores_in(topology.performance_cores()),
+            #[cfg(feature = "hwloc-2_4_0")]
+            efficiency_cores: count_cores_in(topology.efficiency_cores()),
+            pci_devices: count(ObjectType::PCIDevice),
+            gpus,
+        }
+    }
+}
+//
+impl Display for TopologySummary {
+    /// Describe the machine, one property per line
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "Packages: {}", self.packages)?;
+        write!(f, "Cores: {}", self.cores)?;
+        #[cfg(feature = "hwloc-2_4_0")]
+        if self.efficiency_cores > 0 {
+            write!(
+                f,
+                " ({} performance, {} efficiency)",
+                self.performance_cores, self.efficiency_cores
+            )?;
+        }
+        writeln!(f)?;
+        writeln!(f, "PUs: {}", self.pus)?;
+        writeln!(f, "NUMA nodes: {}", self.numa_nodes)?;
+        writeln!(f, "Total memory: {} bytes", self.total_memory)?;
+        for cache in &self.caches {
+            write!(f, "{} cache: ", cache.level())?;
+            match cache.size() {
+                Some(size) => write!(f, "{size} bytes")?,
+                None => write!(f, "unknown size")?,
+            }
+            writeln!(f, " x {}", cache.num_instances())?;
+        }
+        writeln!(f, "PCI devices: {}", self.pci_devices)?;
+        write!(f, "GPUs: {}", self.gpus)
+    }
+}
+//
+#[cfg(feature = "serde")]
+impl Serialize for TopologySummary {
+    /// Serialize as a struct, where CPU caches are serialized as a map from
+    /// cache level names (e.g. `L1d`) to cache properties
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        /// Serialization helper for the CPU cache hierarchy
+        struct Caches<'summary>(&'summary [CacheLevelSummary]);
+        //
+        impl Serialize for Caches<'_> {
+            fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+                serializer.collect_map(
+                    self.0
+                        .iter()
+                        .map(|cache| (cache.level().to_string(), Cache(cache))),
+                )
+            }
+        }
+
+        /// Serialization helper for one level of the CPU cache hierarchy
+        struct Cache<'summary>(&'summary CacheLevelSummary);
+        //
+        impl Serialize for Cache<'_> {
+            fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+                let mut state = serializer.serialize_struct("CacheLevelSummary", 4)?;
+                state.serialize_field("size", &self.0.size().map(u64::from))?;
+                state.serialize_field("line_size", &self.0.line_size().map(usize::from))?;
+                state.serialize_field("sharing_degree", &self.0.sharing_degree())?;
+                state.serialize_field("num_instances", &self.0.num_instances())?;
+                state.end()
+            }
+        }
+
+        let num_fields = if cfg!(feature = "hwloc-2_4_0") { 10 } else { 8 };
+        let mut state = serializer.serialize_struct("TopologySummary", num_fields)?;
+        state.serialize_field("packages", &self.packages)?;
+        state.serialize_field("cores", &self.cores)?;
+        state.serialize_field("pus", &self.pus)?;
+        state.serialize_field("numa_nodes", &self.numa_nodes)?;
+        state.serialize_field("total_memory", &self.total_memory)?;
+        state.serialize_field("caches", &Caches(&self.caches))?;
+        #[cfg(feature = "hwloc-2_4_0")]
+        {
+            state.serialize_field("performance_cores", &self.performance_cores)?;
+            state.serialize_field("efficiency_cores", &self.efficiency_cores)?;
+        }
+        state.serialize_field("pci_devices", &self.pci_devices)?;
+        state.serialize_field("gpus", &self.gpus)?;
+        state.end()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[allow(unused)]
+    use similar_asserts::assert_eq;
+    use static_assertions::{assert_impl_all, assert_not_impl_any};
+    use std::{
+        fmt::{Binary, Debug, LowerExp, LowerHex, Octal, Pointer, UpperExp, UpperHex},
+        hash::Hash,
+        io::{self, Read},
+        ops::Deref,
+        panic::UnwindSafe,
+    };
+
+    // Check that public types in this module keep implementing all expected
+    // traits, in the interest of detecting future semver-breaking changes
+    assert_impl_all!(TopologySummary:
+        Clone, Debug, Default, Display, Hash, Sized, Sync, Unpin, UnwindSafe
+    );
+    assert_not_impl_any!(TopologySummary:
+        Binary, Copy, Deref, Drop, IntoIterator, LowerExp, LowerHex, Octal,
+        PartialOrd, Pointer, Read, UpperExp, UpperHex, fmt::Write, io::Write
+    );
+
+    /// Check the summary of the dual-socket NUMA test topology
+    #[test]
+    fn dual_socket_numa() {
+        let topology = Topology::test_dual_socket_numa();
+        let summary = topology.summary();
+        assert_eq!(summary.packages, 2);
+        assert_eq!(summary.numa_nodes, 2);
+        assert_eq!(
+            summary.cores,
+            topology.objects_with_type(ObjectType::Core).count()
+        );
+        assert_eq!(
+            summary.pus,
+            topology.objects_with_type(ObjectType::PU).count()
+        );
+        assert_eq!(summary.total_memory, topology.root_object().total_memory());
+        assert_eq!(
+            summary.caches,
+            topology.cache_hierarchy_summary().levels().to_vec()
+        );
+        #[cfg(feature = "hwloc-2_4_0")]
+        {
+            assert_eq!(summary.performance_cores, summary.cores);
+            assert_eq!(summary.efficiency_cores, 0);
+        }
+        assert_eq!(summary.pci_devices, 0);
+        assert_eq!(summary.gpus, 0);
+
+        let display = summary.to_string();
+        assert!(display.starts_with("Packages: 2\n"));
+        assert!(display.contains("NUMA nodes: 2\n"));
+        assert!(display.ends_with("GPUs: 0"));
+
+        #[cfg(feature = "serde")]
+        {
+            let json = serde_json::to_value(&summary).unwrap();
+            assert_eq!(json["packages"], 2);
+            assert_eq!(json["numa_nodes"], 2);
+            assert_eq!(json["total_memory"], summary.total_memory);
+            for cache in &summary.caches {
+                let level = cache.level().to_string();
+                assert_eq!(
+                    json["caches"][&level]["num_instances"],
+                    cache.num_instances()
+                );
+            }
+        }
+    }
+
+    /// Check that the summary of the test topology is consistent with it
+    #[test]
+    fn test_instance() {
+        let topology = Topology::test_instance();
+        let summary = topology.summary();
+        assert_eq!(summary.pci_devices, topology.pci_devices().count());
+        assert!(summary.gpus <= topology.os_devices().count());
+        #[cfg(feature = "hwloc-2_4_0")]
+        assert!(summary.performance_cores + summary.efficiency_cores <= summary.cores);
+    }
+}
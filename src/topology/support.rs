@@ -3,7 +3,8 @@
 //! Generally speaking, a given hardware/OS platform will not support every
 //! hwloc feature. This module exposes the feature support flags,
 //! which you can query using the [`Topology::feature_support()`] method and its
-//! [`Topology::supports()`] shortcut.
+//! [`Topology::supports()`] shortcut, or summarize into an owned
+//! [`SupportSummary`] using [`Topology::support_summary()`].
 
 // - API: https://hwloc.readthedocs.io/en/v2.9/group__hwlocality__configuration.html#gab8c76173c4a8ce1a9a9366012b1388e6
 // - Struct: https://hwloc.readthedocs.io/en/v2.9/structhwloc__topology__support.html
@@ -22,12 +23,14 @@ use hwlocality_sys::{
 };
 #[cfg(any(test, feature = "proptest"))]
 use proptest::prelude::*;
+#[cfg(feature = "serde")]
+use serde::{Serialize, Serializer};
 #[allow(unused)]
 #[cfg(test)]
 use similar_asserts::assert_eq;
 use std::{
     ffi::c_uchar,
-    fmt::{self, Debug},
+    fmt::{self, Debug, Display},
     hash::Hash,
 };
 
@@ -625,6 +628,212 @@ unsafe impl TransparentNewtype for MiscSupport {
     type Inner = hwloc_topology_misc_support;
 }
 
+/// Owned summary of all the feature support flags of a topology
+///
+/// Unlike [`FeatureSupport`], which is owned by a topology and can only be
+/// queried through it, this is a plain-data copy of all support flags that
+/// can be stored, displayed in human-readable form or (with the `serde`
+/// feature) serialized, which is convenient for diagnostic tools that want
+/// to report what the current platform can do.
+///
+/// Groups of support flags that hwloc did not provide are set to `None`.
+///
+/// # Examples
+///
+/// ```
+/// # use hwlocality::Topology;
+/// let topology = Topology::new()?;
+/// let summary = topology.support_summary();
+/// println!("{summary}");
+/// # Ok::<(), eyre::Report>(())
+/// ```
+#[derive(Copy, Clone, Debug, Default, Eq, Hash, PartialEq)]
+pub struct SupportSummary {
+    /// Support for discovering information about the topology
+    pub discovery: Option<DiscoverySupport>,
+
+    /// Support for getting and setting thread/process CPU bindings
+    pub cpu_binding: Option<CpuBindingSupport>,
+
+    /// Support for getting and setting thread/process NUMA node bindings
+    pub memory_binding: Option<MemoryBindingSupport>,
+
+    /// Miscellaneous support information
+    #[cfg(feature = "hwloc-2_3_0")]
+    pub misc: Option<MiscSupport>,
+}
+//
+impl SupportSummary {
+    /// Name and flags of each group of support flags, in display order
+    fn groups(&self) -> Vec<(&'static str, Option<FlagList>)> {
+        #[allow(unused_mut)]
+        let mut groups = vec![
+            (
+                "discovery",
+                self.discovery.as_ref().map(SupportFlags::flags),
+            ),
+            (
+                "cpu_binding",
+                self.cpu_binding.as_ref().map(SupportFlags::flags),
+            ),
+            (
+                "memory_binding",
+                self.memory_binding.as_ref().map(SupportFlags::flags),
+            ),
+        ];
+        #[cfg(feature = "hwloc-2_3_0")]
+        groups.push(("misc", self.misc.as_ref().map(SupportFlags::flags)));
+        groups
+    }
+}
+//
+impl Display for SupportSummary {
+    /// List the supported features of each group, one group per line
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (idx, (group, flags)) in self.groups().into_iter().enumerate() {
+            if idx != 0 {
+                writeln!(f)?;
+            }
+            write!(f, "{group}: ")?;
+            let Some(flags) = flags else {
+                write!(f, "unknown")?;
+                continue;
+            };
+            let mut supported = flags
+                .into_iter()
+                .filter_map(|(name, set)| set.then_some(name))
+                .peekable();
+            if supported.peek().is_none() {
+                write!(f, "none")?;
+            }
+            for (flag_idx, name) in supported.enumerate() {
+                if flag_idx != 0 {
+                    write!(f, ", ")?;
+                }
+                write!(f, "{name}")?;
+            }
+        }
+        Ok(())
+    }
+}
+//
+impl From<&FeatureSupport> for SupportSummary {
+    fn from(support: &FeatureSupport) -> Self {
+        Self {
+            discovery: support.discovery().copied(),
+            cpu_binding: support.cpu_binding().copied(),
+            memory_binding: support.memory_binding().copied(),
+            #[cfg(feature = "hwloc-2_3_0")]
+            misc: support.misc().copied(),
+        }
+    }
+}
+//
+#[cfg(feature = "serde")]
+impl Serialize for SupportSummary {
+    /// Serialize as a map from group names to maps from flag names to flag
+    /// values, where unknown groups are serialized as `None`
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        /// Serialization helper for a group of support flags
+        struct FlagMap(FlagList);
+        //
+        impl Serialize for FlagMap {
+            fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+                serializer.collect_map(self.0.iter().copied())
+            }
+        }
+        serializer.collect_map(
+            self.groups()
+                .into_iter()
+                .map(|(group, flags)| (group, flags.map(FlagMap))),
+        )
+    }
+}
+
+/// Name and value of each support flag in a group
+type FlagList = Vec<(&'static str, bool)>;
+
+/// Group of support flags that can be listed by name
+trait SupportFlags {
+    /// Name and value of each support flag in this group
+    fn flags(&self) -> FlagList;
+}
+//
+impl SupportFlags for DiscoverySupport {
+    fn flags(&self) -> FlagList {
+        #[allow(unused_mut)]
+        let mut flags = vec![
+            ("pu_count", self.pu_count()),
+            ("numa_count", self.numa_count()),
+            ("numa_memory", self.numa_memory()),
+        ];
+        #[cfg(feature = "hwloc-2_1_0")]
+        flags.extend([
+            ("disallowed_pu", self.disallowed_pu()),
+            ("disallowed_numa", self.disallowed_numa()),
+        ]);
+        #[cfg(feature = "hwloc-2_4_0")]
+        flags.push(("cpukind_efficiency", self.cpukind_efficiency()));
+        flags
+    }
+}
+//
+impl SupportFlags for CpuBindingSupport {
+    fn flags(&self) -> FlagList {
+        vec![
+            ("set_current_process", self.set_current_process()),
+            ("get_current_process", self.get_current_process()),
+            ("set_process", self.set_process()),
+            ("get_process", self.get_process()),
+            ("set_current_thread", self.set_current_thread()),
+            ("get_current_thread", self.get_current_thread()),
+            ("set_thread", self.set_thread()),
+            ("get_thread", self.get_thread()),
+            (
+                "get_current_process_last_cpu_location",
+                self.get_current_process_last_cpu_location(),
+            ),
+            (
+                "get_process_last_cpu_location",
+                self.get_process_last_cpu_location(),
+            ),
+            (
+                "get_current_thread_last_cpu_location",
+                self.get_current_thread_last_cpu_location(),
+            ),
+        ]
+    }
+}
+//
+impl SupportFlags for MemoryBindingSupport {
+    fn flags(&self) -> FlagList {
+        vec![
+            ("set_current_process", self.set_current_process()),
+            ("get_current_process", self.get_current_process()),
+            ("set_process", self.set_process()),
+            ("get_process", self.get_process()),
+            ("set_current_thread", self.set_current_thread()),
+            ("get_current_thread", self.get_current_thread()),
+            ("set_area", self.set_area()),
+            ("get_area", self.get_area()),
+            ("get_area_memory_location", self.get_area_memory_location()),
+            ("allocate_bound", self.allocate_bound()),
+            ("first_touch_policy", self.first_touch_policy()),
+            ("bind_policy", self.bind_policy()),
+            ("interleave_policy", self.interleave_policy()),
+            ("next_touch_policy", self.next_touch_policy()),
+            ("migrate_flag", self.migrate_flag()),
+        ]
+    }
+}
+//
+#[cfg(feature = "hwloc-2_3_0")]
+impl SupportFlags for MiscSupport {
+    fn flags(&self) -> FlagList {
+        vec![("imported", self.imported())]
+    }
+}
+
 /// Decode topology support flag
 fn support_flag(flag: c_uchar) -> bool {
     assert!(
@@ -693,6 +902,14 @@ mod tests {
         PartialOrd, Pointer, Read, UpperExp, UpperHex, fmt::Write, io::Write
     );
 
+    assert_impl_all!(SupportSummary:
+        Copy, Default, Display, Hash, Sized, Sync, Unpin, UnwindSafe
+    );
+    assert_not_impl_any!(SupportSummary:
+        Binary, Deref, Drop, IntoIterator, LowerExp, LowerHex, Octal,
+        PartialOrd, Pointer, Read, UpperExp, UpperHex, fmt::Write, io::Write
+    );
+
     #[cfg(not(feature = "hwloc-2_3_0"))]
     fn support_components(
     ) -> impl Strategy<Value = (DiscoverySupport, CpuBindingSupport, MemoryBindingSupport)> {
@@ -1062,6 +1279,54 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn summary() {
+        let topology = Topology::test_instance();
+        let support = topology.feature_support();
+        let summary = topology.support_summary();
+        assert_eq!(summary, SupportSummary::from(support));
+        assert_eq!(summary.discovery.as_ref(), support.discovery());
+        assert_eq!(summary.cpu_binding.as_ref(), support.cpu_binding());
+        assert_eq!(summary.memory_binding.as_ref(), support.memory_binding());
+        #[cfg(feature = "hwloc-2_3_0")]
+        assert_eq!(summary.misc.as_ref(), support.misc());
+
+        let display = summary.to_string();
+        let groups = summary.groups();
+        assert_eq!(display.lines().count(), groups.len());
+        for (line, (group, flags)) in display.lines().zip(groups) {
+            let (name, supported) = line.split_once(": ").unwrap();
+            assert_eq!(name, group);
+            let Some(flags) = flags else {
+                assert_eq!(supported, "unknown");
+                continue;
+            };
+            let expected = flags
+                .iter()
+                .filter_map(|&(name, set)| set.then_some(name))
+                .collect::<Vec<_>>();
+            if expected.is_empty() {
+                assert_eq!(supported, "none");
+            } else {
+                assert_eq!(supported, expected.join(", "));
+            }
+        }
+
+        #[cfg(feature = "serde")]
+        {
+            let json = serde_json::to_value(summary).unwrap();
+            for (group, flags) in summary.groups() {
+                let Some(flags) = flags else {
+                    assert!(json[group].is_null());
+                    continue;
+                };
+                for (flag, set) in flags {
+                    assert_eq!(json[group][flag], set);
+                }
+            }
+        }
+    }
+
     fn check_flag(
         flag_repr: c_uchar,
         flag: impl FnOnce() -> bool + UnwindSafe,
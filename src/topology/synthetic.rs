@@ -0,0 +1,847 @@
+//! Typed synthetic topology descriptions
+//!
+//! [`TopologyBuilder::from_synthetic()`] accepts hwloc's [textual synthetic
+//! topology description](https://hwloc.readthedocs.io/en/v2.9/synthetic.html),
+//! which is very concise but easy to get wrong. The [`SyntheticTopology`]
+//! builder defined in this module lets you describe the most common symmetric
+//! topologies with typed method calls instead, which is convenient when
+//! testing NUMA-aware code against hardware that you do not have at hand.
+//!
+//! Existing textual descriptions, such as those produced by
+//! [`Topology::export_synthetic()`], can also be parsed into a
+//! [`SyntheticDescription`], which can be inspected or modified before being
+//! fed back to a [`TopologyBuilder`].
+
+use super::builder::{StringInputError, TopologyBuilder};
+#[cfg(doc)]
+use super::Topology;
+use crate::object::types::{ObjectType, ParseTypeError, TypeSpec};
+#[cfg(any(test, feature = "proptest"))]
+use proptest::prelude::*;
+#[cfg(any(test, feature = "proptest"))]
+use std::ops::RangeInclusive;
+use std::{
+    fmt::{self, Display},
+    num::NonZeroUsize,
+};
+use thiserror::Error;
+
+/// Typed description of a symmetric synthetic topology
+///
+/// Every level is described by its arity, i.e. the number of objects of that
+/// level below each object of the parent level. For example, `cores(8)` means
+/// that there are 8 cores below each object of the closest enclosing level
+/// that was specified (L2 cache, L3 cache, NUMA node, or package).
+///
+/// Packages, cores and PUs are always present, and their arity defaults to 1.
+/// NUMA nodes and cache levels are only present if their arity was specified.
+///
+/// # Example
+///
+/// ```rust
+/// # use hwlocality::{object::types::ObjectType, topology::synthetic::SyntheticTopology};
+/// let topology = SyntheticTopology::new()
+///     .packages(2)
+///     .numa_per_package(1)
+///     .l3(1)
+///     .cores(8)
+///     .pus(2)
+///     .build()
+///     .build()?;
+/// assert_eq!(topology.objects_with_type(ObjectType::NUMANode).count(), 2);
+/// assert_eq!(topology.objects_with_type(ObjectType::PU).count(), 32);
+/// # Ok::<(), eyre::Report>(())
+/// ```
+#[derive(Copy, Clone, Debug, Eq, Hash, PartialEq)]
+pub struct SyntheticTopology {
+    /// Number of packages in the machine
+    packages: NonZeroUsize,
+
+    /// Number of NUMA nodes per package, if specified
+    numa_per_package: Option<NonZeroUsize>,
+
+    /// Number of L3 caches per parent object, if specified
+    l3: Option<NonZeroUsize>,
+
+    /// Number of L2 caches per parent object, if specified
+    l2: Option<NonZeroUsize>,
+
+    /// Number of L1 caches per parent object, if specified
+    l1: Option<NonZeroUsize>,
+
+    /// Number of cores per parent object
+    cores: NonZeroUsize,
+
+    /// Number of PUs per core
+    pus: NonZeroUsize,
+}
+//
+impl SyntheticTopology {
+    /// Start describing a synthetic topology
+    ///
+    /// The initial description is a machine with a single package, which
+    /// contains a single core with a single PU.
+    pub fn new() -> Self {
+        Self {
+            packages: NonZeroUsize::MIN,
+            numa_per_package: None,
+            l3: None,
+            l2: None,
+            l1: None,
+            cores: NonZeroUsize::MIN,
+            pus: NonZeroUsize::MIN,
+        }
+    }
+
+    /// Set the number of packages in the machine
+    ///
+    /// # Panics
+    ///
+    /// If `count` is zero.
+    pub fn packages(mut self, count: usize) -> Self {
+        self.packages = Self::arity(count);
+        self
+    }
+
+    /// Set the number of NUMA nodes in each package
+    ///
+    /// # Panics
+    ///
+    /// If `count` is zero.
+    pub fn numa_per_package(mut self, count: usize) -> Self {
+        self.numa_per_package = Some(Self::arity(count));
+        self
+    }
+
+    /// Set the number of L3 caches below each NUMA node or package
+    ///
+    /// # Panics
+    ///
+    /// If `count` is zero.
+    pub fn l3(mut self, count: usize) -> Self {
+        self.l3 = Some(Self::arity(count));
+        self
+    }
+
+    /// Set the number of L2 caches below each parent object
+    ///
+    /// # Panics
+    ///
+    /// If `count` is zero.
+    pub fn l2(mut self, count: usize) -> Self {
+        self.l2 = Some(Self::arity(count));
+        self
+    }
+
+    /// Set the number of L1 caches below each parent object
+    ///
+    /// # Panics
+    ///
+    /// If `count` is zero.
+    pub fn l1(mut self, count: usize) -> Self {
+        self.l1 = Some(Self::arity(count));
+        self
+    }
+
+    /// Set the number of CPU cores below each parent object
+    ///
+    /// # Panics
+    ///
+    /// If `count` is zero.
+    pub fn cores(mut self, count: usize) -> Self {
+        self.cores = Self::arity(count);
+        self
+    }
+
+    /// Set the number of PUs (hardware threads) in each CPU core
+    ///
+    /// # Panics
+    ///
+    /// If `count` is zero.
+    pub fn pus(mut self, count: usize) -> Self {
+        self.pus = Self::arity(count);
+        self
+    }
+
+    /// Total number of PUs in the described topology
+    pub fn num_pus(&self) -> usize {
+        [
+            Some(self.packages),
+            self.numa_per_package,
+            self.l3,
+            self.l2,
+            self.l1,
+            Some(self.cores),
+            Some(self.pus),
+        ]
+        .into_iter()
+        .flatten()
+        .map(NonZeroUsize::get)
+        .product()
+    }
+
+    /// Set up a [`TopologyBuilder`] that loads this synthetic topology
+    ///
+    /// This is equivalent to calling [`TopologyBuilder::from_synthetic()`]
+    /// with the textual description of this topology, which is what the
+    /// [`Display`] implementation of this type produces.
+    #[doc(alias = "hwloc_topology_set_synthetic")]
+    pub fn build(&self) -> TopologyBuilder {
+        TopologyBuilder::new()
+            .from_synthetic(&self.to_string())
+            .expect("Typed synthetic topology descriptions should be valid")
+    }
+
+    /// Check the arity of a level of the synthetic topology
+    fn arity(count: usize) -> NonZeroUsize {
+        NonZeroUsize::new(count).expect("Synthetic topology levels can't be empty")
+    }
+}
+//
+/// Generate random small synthetic topologies
+///
+/// Each level has an arity of at most 4, and the generated topologies have at
+/// most 512 PUs, which keeps them cheap enough to build in property tests.
+#[cfg(any(test, feature = "proptest"))]
+impl Arbitrary for SyntheticTopology {
+    type Parameters = ();
+    type Strategy = prop::strategy::Map<
+        (
+            RangeInclusive<usize>,
+            OptionalArity,
+            OptionalArity,
+            OptionalArity,
+            OptionalArity,
+            RangeInclusive<usize>,
+            RangeInclusive<usize>,
+        ),
+        fn(ArbitraryArities) -> Self,
+    >;
+
+    fn arbitrary_with((): Self::Parameters) -> Self::Strategy {
+        let optional_arity = || prop::option::of(1..=2usize);
+        (
+            1..=2usize,
+            optional_arity(),
+            optional_arity(),
+            optional_arity(),
+            optional_arity(),
+            1..=4usize,
+            1..=2usize,
+        )
+            .prop_map(|(packages, numa_per_package, l3, l2, l1, cores, pus)| {
+                let mut result = Self::new().packages(packages).cores(cores).pus(pus);
+                result.numa_per_package = numa_per_package.map(Self::arity);
+                result.l3 = l3.map(Self::arity);
+                result.l2 = l2.map(Self::arity);
+                result.l1 = l1.map(Self::arity);
+                result
+            })
+    }
+}
+//
+impl Default for SyntheticTopology {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+//
+impl Display for SyntheticTopology {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let levels = [
+            ("Package", Some(self.packages)),
+            ("NUMANode", self.numa_per_package),
+            ("L3Cache", self.l3),
+            ("L2Cache", self.l2),
+            ("L1Cache", self.l1),
+            ("Core", Some(self.cores)),
+            ("PU", Some(self.pus)),
+        ];
+        let mut first = true;
+        for (name, arity) in levels {
+            let Some(arity) = arity else { continue };
+            if !first {
+                write!(f, " ")?;
+            }
+            write!(f, "{name}:{arity}")?;
+            first = false;
+        }
+        Ok(())
+    }
+}
+
+/// Strategy for optional synthetic topology levels
+#[cfg(any(test, feature = "proptest"))]
+type OptionalArity = prop::option::OptionStrategy<RangeInclusive<usize>>;
+
+/// Level arities from the [`Arbitrary`] implementation of [`SyntheticTopology`]
+#[cfg(any(test, feature = "proptest"))]
+type ArbitraryArities = (
+    usize,
+    Option<usize>,
+    Option<usize>,
+    Option<usize>,
+    Option<usize>,
+    usize,
+    usize,
+);
+
+/// Parsed textual synthetic topology description
+///
+/// This is a typed tree view of the [synthetic topology
+/// format](https://hwloc.readthedocs.io/en/v2.9/synthetic.html) accepted by
+/// [`TopologyBuilder::from_synthetic()`] and produced by
+/// [`Topology::export_synthetic()`]. Unlike [`SyntheticTopology`], it can
+/// represent any description that hwloc can emit, including level attributes
+/// like cache sizes and memory children like NUMA nodes attached to a level.
+///
+/// Descriptions can be parsed with [`SyntheticDescription::parse()`], modified
+/// via the public fields, turned back into text via the [`Display`]
+/// implementation, and loaded via [`SyntheticDescription::build()`].
+///
+/// # Example
+///
+/// ```rust
+/// # use hwlocality::{object::types::ObjectType, topology::synthetic::SyntheticDescription};
+/// let mut description = SyntheticDescription::parse("Package:2 [NUMANode] Core:4 PU:2")?;
+/// assert_eq!(description.levels.len(), 3);
+/// assert_eq!(description.levels[0].memory[0].object_type, "NUMANode");
+///
+/// // Remove SMT and load the resulting topology
+/// description.levels.pop();
+/// assert_eq!(description.to_string(), "Package:2 [NUMANode] Core:4");
+/// let topology = description.build()?.build()?;
+/// assert_eq!(topology.objects_with_type(ObjectType::PU).count(), 8);
+/// # Ok::<(), eyre::Report>(())
+/// ```
+#[derive(Clone, Debug, Default, Eq, Hash, PartialEq)]
+pub struct SyntheticDescription {
+    /// Attributes of the root object, as `(key, value)` pairs
+    pub attributes: Vec<(String, String)>,
+
+    /// Memory objects attached to the root object
+    pub memory: Vec<SyntheticMemory>,
+
+    /// Levels of normal objects, from the root to the leaves
+    pub levels: Vec<SyntheticLevel>,
+}
+//
+impl SyntheticDescription {
+    /// Parse a textual synthetic topology description
+    ///
+    /// This only checks the syntax of the description. Semantic problems, like
+    /// unknown object types or attributes, are only detected by hwloc when
+    /// the description is loaded via [`build()`](Self::build()).
+    ///
+    /// # Errors
+    ///
+    /// - [`Unbalanced`] if some parentheses or brackets are not closed, or
+    ///   closed without having been opened.
+    /// - [`InvalidLevel`] if a level or memory object cannot be parsed.
+    /// - [`InvalidAttribute`] if an attribute is not in `key=value` format.
+    /// - [`NoLevels`] if the description does not contain any level.
+    ///
+    /// [`InvalidAttribute`]: ParseSyntheticError::InvalidAttribute
+    /// [`InvalidLevel`]: ParseSyntheticError::InvalidLevel
+    /// [`NoLevels`]: ParseSyntheticError::NoLevels
+    /// [`Unbalanced`]: ParseSyntheticError::Unbalanced
+    pub fn parse(description: &str) -> Result<Self, ParseSyntheticError> {
+        let mut result = Self::default();
+        let mut rest = description.trim_start();
+        while !rest.is_empty() {
+            let (token, tail) = split_token(rest)?;
+            rest = tail.trim_start();
+            if token.starts_with('(') {
+                let attributes = parse_attributes(token)?;
+                match result.levels.last_mut() {
+                    Some(level) => level.attributes.extend(attributes),
+                    None => result.attributes.extend(attributes),
+                }
+            } else if token.starts_with('[') {
+                let memory = SyntheticMemory::parse(token)?;
+                match result.levels.last_mut() {
+                    Some(level) => level.memory.push(memory),
+                    None => result.memory.push(memory),
+                }
+            } else {
+                result.levels.push(SyntheticLevel::parse(token)?);
+            }
+        }
+        if result.levels.is_empty() {
+            return Err(ParseSyntheticError::NoLevels);
+        }
+        Ok(result)
+    }
+
+    /// Set up a [`TopologyBuilder`] that loads this synthetic topology
+    ///
+    /// This is equivalent to calling [`TopologyBuilder::from_synthetic()`]
+    /// with the textual description produced by the [`Display`]
+    /// implementation of this type.
+    ///
+    /// # Errors
+    ///
+    /// - [`StringInputError`] if hwloc rejects the description, e.g. because
+    ///   it was modified in an invalid way.
+    #[doc(alias = "hwloc_topology_set_synthetic")]
+    pub fn build(&self) -> Result<TopologyBuilder, StringInputError> {
+        TopologyBuilder::new().from_synthetic(&self.to_string())
+    }
+}
+//
+impl Display for SyntheticDescription {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write_attributes(f, &self.attributes)?;
+        let mut separator = if self.attributes.is_empty() { "" } else { " " };
+        for memory in &self.memory {
+            write!(f, "{separator}{memory}")?;
+            separator = " ";
+        }
+        for level in &self.levels {
+            write!(f, "{separator}{level}")?;
+            separator = " ";
+        }
+        Ok(())
+    }
+}
+//
+impl From<SyntheticTopology> for SyntheticDescription {
+    fn from(value: SyntheticTopology) -> Self {
+        Self::parse(&value.to_string())
+            .expect("Typed synthetic topology descriptions should be valid")
+    }
+}
+
+/// Level of normal objects in a [`SyntheticDescription`]
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct SyntheticLevel {
+    /// Object type string, e.g. `"Core"` or `"L2d"`
+    ///
+    /// This is `None` for levels that are only specified by their arity,
+    /// which is allowed for compatibility with hwloc v1.x.
+    pub object_type: Option<String>,
+
+    /// Number of objects of this level below each object of the parent level
+    pub arity: NonZeroUsize,
+
+    /// Level attributes like cache sizes, as `(key, value)` pairs
+    pub attributes: Vec<(String, String)>,
+
+    /// Memory objects attached to each object of this level
+    pub memory: Vec<SyntheticMemory>,
+}
+//
+impl SyntheticLevel {
+    /// Typed object type of this level, if specified
+    ///
+    /// # Errors
+    ///
+    /// - [`ParseTypeError`] if the object type string is not understood by
+    ///   hwloc.
+    pub fn type_spec(&self) -> Option<Result<TypeSpec, ParseTypeError>> {
+        self.object_type
+            .as_deref()
+            .map(ObjectType::parse_with_attributes)
+    }
+
+    /// Parse a level token, like `Core:4` or `L2:2(size=1MB)`
+    fn parse(token: &str) -> Result<Self, ParseSyntheticError> {
+        let invalid = || ParseSyntheticError::InvalidLevel(token.to_owned());
+        let (head, attributes) = split_attributes(token)?;
+        let (object_type, arity) = match head.split_once(':') {
+            Some((object_type, arity)) => (Some(object_type.to_owned()), arity),
+            None => (None, head),
+        };
+        if object_type.as_ref().is_some_and(String::is_empty) {
+            return Err(invalid());
+        }
+        let arity = arity
+            .parse::<usize>()
+            .ok()
+            .and_then(NonZeroUsize::new)
+            .ok_or_else(invalid)?;
+        Ok(Self {
+            object_type,
+            arity,
+            attributes,
+            memory: Vec::new(),
+        })
+    }
+}
+//
+impl Display for SyntheticLevel {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Some(object_type) = &self.object_type {
+            write!(f, "{object_type}:")?;
+        }
+        write!(f, "{}", self.arity)?;
+        write_attributes(f, &self.attributes)?;
+        for memory in &self.memory {
+            write!(f, " {memory}")?;
+        }
+        Ok(())
+    }
+}
+
+/// Memory object in a [`SyntheticDescription`], e.g. `[NUMANode(memory=1GB)]`
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct SyntheticMemory {
+    /// Object type string, e.g. `"NUMANode"` or `"MemCache"`
+    pub object_type: String,
+
+    /// Object attributes like memory sizes, as `(key, value)` pairs
+    pub attributes: Vec<(String, String)>,
+}
+//
+impl SyntheticMemory {
+    /// Typed object type of this memory object
+    ///
+    /// # Errors
+    ///
+    /// - [`ParseTypeError`] if the object type string is not understood by
+    ///   hwloc.
+    pub fn type_spec(&self) -> Result<TypeSpec, ParseTypeError> {
+        ObjectType::parse_with_attributes(&self.object_type)
+    }
+
+    /// Parse a bracketed memory token
+    fn parse(token: &str) -> Result<Self, ParseSyntheticError> {
+        let invalid = || ParseSyntheticError::InvalidLevel(token.to_owned());
+        let inner = token
+            .strip_prefix('[')
+            .and_then(|inner| inner.strip_suffix(']'))
+            .ok_or_else(invalid)?
+            .trim();
+        let (object_type, attributes) = split_attributes(inner)?;
+        if object_type.is_empty() || object_type.contains(|c: char| c.is_whitespace() || c == '[') {
+            return Err(invalid());
+        }
+        Ok(Self {
+            object_type: object_type.to_owned(),
+            attributes,
+        })
+    }
+}
+//
+impl Display for SyntheticMemory {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "[{}", self.object_type)?;
+        write_attributes(f, &self.attributes)?;
+        write!(f, "]")
+    }
+}
+
+/// Error returned when a string is not a valid synthetic topology description
+#[derive(Clone, Debug, Eq, Error, Hash, PartialEq)]
+pub enum ParseSyntheticError {
+    /// A parenthesis or bracket is not properly closed or opened
+    #[error("unbalanced {0:?} in synthetic topology description")]
+    Unbalanced(char),
+
+    /// A level or memory object could not be parsed
+    #[error("{0:?} is not a valid synthetic topology level")]
+    InvalidLevel(String),
+
+    /// An attribute is not in `key=value` format
+    #[error("{0:?} is not a valid synthetic topology attribute")]
+    InvalidAttribute(String),
+
+    /// The description does not contain any level
+    #[error("synthetic topology description has no levels")]
+    NoLevels,
+}
+
+/// Split the next whitespace-delimited token, ignoring whitespace within
+/// parentheses and brackets
+fn split_token(s: &str) -> Result<(&str, &str), ParseSyntheticError> {
+    let mut delimiters = Vec::new();
+    for (idx, c) in s.char_indices() {
+        match c {
+            '(' | '[' => delimiters.push(c),
+            ')' | ']' => {
+                let expected = if c == ')' { '(' } else { '[' };
+                if delimiters.pop() != Some(expected) {
+                    return Err(ParseSyntheticError::Unbalanced(c));
+                }
+            }
+            c if c.is_whitespace() && delimiters.is_empty() => return Ok(s.split_at(idx)),
+            _ => {}
+        }
+    }
+    delimiters
+        .pop()
+        .map_or(Ok((s, "")), |c| Err(ParseSyntheticError::Unbalanced(c)))
+}
+
+/// Attributes of a synthetic topology level or object, as `(key, value)` pairs
+type Attributes = Vec<(String, String)>;
+
+/// Split trailing parenthesized attributes from a token
+fn split_attributes(token: &str) -> Result<(&str, Attributes), ParseSyntheticError> {
+    match token.find('(') {
+        Some(start) => Ok((&token[..start], parse_attributes(&token[start..])?)),
+        None => Ok((token, Vec::new())),
+    }
+}
+
+/// Parse a parenthesized attribute list, like `(size=32KB linesize=64)`
+fn parse_attributes(token: &str) -> Result<Attributes, ParseSyntheticError> {
+    let inner = token
+        .strip_prefix('(')
+        .and_then(|inner| inner.strip_suffix(')'))
+        .ok_or_else(|| ParseSyntheticError::InvalidAttribute(token.to_owned()))?;
+    inner
+        .split_whitespace()
+        .map(|attribute| match attribute.split_once('=') {
+            Some((key, value)) if !key.is_empty() && !key.contains(['(', ')']) => {
+                Ok((key.to_owned(), value.to_owned()))
+            }
+            _ => Err(ParseSyntheticError::InvalidAttribute(attribute.to_owned())),
+        })
+        .collect()
+}
+
+/// Write a parenthesized attribute list, if there are any attributes
+fn write_attributes(f: &mut fmt::Formatter<'_>, attributes: &[(String, String)]) -> fmt::Result {
+    if attributes.is_empty() {
+        return Ok(());
+    }
+    write!(f, "(")?;
+    for (idx, (key, value)) in attributes.iter().enumerate() {
+        if idx > 0 {
+            write!(f, " ")?;
+        }
+        write!(f, "{key}={value}")?;
+    }
+    write!(f, ")")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[allow(unused)]
+    use similar_asserts::assert_eq;
+    use static_assertions::{assert_impl_all, assert_not_impl_any};
+    use std::{
+        error::Error,
+        fmt::{Binary, Debug, LowerExp, LowerHex, Octal, Pointer, UpperExp, UpperHex},
+        hash::Hash,
+        io::{self, Read},
+        ops::Deref,
+        panic::UnwindSafe,
+    };
+
+    // Check that public types in this module keep implementing all expected
+    // traits, in the interest of detecting future semver-breaking changes
+    assert_impl_all!(SyntheticTopology:
+        Copy, Debug, Default, Display, Hash, Sized, Sync, Unpin, UnwindSafe
+    );
+    assert_not_impl_any!(SyntheticTopology:
+        Binary, Deref, Drop, IntoIterator, LowerExp, LowerHex, Octal,
+        PartialOrd, Pointer, Read, UpperExp, UpperHex, fmt::Write, io::Write
+    );
+    assert_impl_all!(SyntheticDescription:
+        Clone, Debug, Default, Display, From<SyntheticTopology>, Hash, Sized,
+        Sync, Unpin, UnwindSafe
+    );
+    assert_not_impl_any!(SyntheticDescription:
+        Binary, Copy, Deref, Drop, IntoIterator, LowerExp, LowerHex, Octal,
+        PartialOrd, Pointer, Read, UpperExp, UpperHex, fmt::Write, io::Write
+    );
+    assert_impl_all!(SyntheticLevel:
+        Clone, Debug, Display, Hash, Sized, Sync, Unpin, UnwindSafe
+    );
+    assert_not_impl_any!(SyntheticLevel:
+        Binary, Copy, Default, Deref, Drop, IntoIterator, LowerExp, LowerHex,
+        Octal, PartialOrd, Pointer, Read, UpperExp, UpperHex, fmt::Write,
+        io::Write
+    );
+    assert_impl_all!(SyntheticMemory:
+        Clone, Debug, Display, Hash, Sized, Sync, Unpin, UnwindSafe
+    );
+    assert_not_impl_any!(SyntheticMemory:
+        Binary, Copy, Default, Deref, Drop, IntoIterator, LowerExp, LowerHex,
+        Octal, PartialOrd, Pointer, Read, UpperExp, UpperHex, fmt::Write,
+        io::Write
+    );
+    assert_impl_all!(ParseSyntheticError:
+        Clone, Error, Hash, Sized, Sync, Unpin, UnwindSafe
+    );
+    assert_not_impl_any!(ParseSyntheticError:
+        Binary, Copy, Default, Deref, Drop, IntoIterator, LowerExp, LowerHex,
+        Octal, PartialOrd, Pointer, Read, UpperExp, UpperHex, fmt::Write,
+        io::Write
+    );
+
+    /// Check the textual description and the resulting topology
+    #[test]
+    fn build() {
+        let synthetic = SyntheticTopology::new()
+            .packages(2)
+            .numa_per_package(1)
+            .l3(1)
+            .cores(8)
+            .pus(2);
+        assert_eq!(
+            synthetic.to_string(),
+            "Package:2 NUMANode:1 L3Cache:1 Core:8 PU:2"
+        );
+        assert_eq!(synthetic.num_pus(), 32);
+
+        let topology = synthetic.build().build().unwrap();
+        #[allow(clippy::wildcard_enum_match_arm)]
+        let expected_object_count = |ty: ObjectType| match ty {
+            ObjectType::Machine => 1,
+            ObjectType::Package | ObjectType::NUMANode | ObjectType::L3Cache => 2,
+            ObjectType::Core => 2 * 8,
+            ObjectType::PU => 2 * 8 * 2,
+            _ => 0,
+        };
+        for ty in enum_iterator::all::<ObjectType>() {
+            assert_eq!(
+                topology.objects_with_type(ty).count(),
+                expected_object_count(ty),
+                "{ty}"
+            );
+        }
+    }
+
+    /// Check the default description
+    #[test]
+    fn default() {
+        let synthetic = SyntheticTopology::default();
+        assert_eq!(synthetic, SyntheticTopology::new());
+        assert_eq!(synthetic.to_string(), "Package:1 Core:1 PU:1");
+        assert_eq!(synthetic.num_pus(), 1);
+    }
+
+    /// Check parsing of a description with attributes and memory children
+    #[test]
+    fn parse_description() {
+        let text = "(memory=1GB) Package:2 [NUMANode(memory=512MB)] L2d:4(size=1MB linesize=64) 2";
+        let description = SyntheticDescription::parse(text).unwrap();
+        assert_eq!(
+            description.attributes,
+            [("memory".to_owned(), "1GB".to_owned())]
+        );
+        assert!(description.memory.is_empty());
+        assert_eq!(description.levels.len(), 3);
+
+        let package = &description.levels[0];
+        assert_eq!(package.object_type.as_deref(), Some("Package"));
+        assert_eq!(package.arity.get(), 2);
+        assert_eq!(
+            package.type_spec().unwrap().unwrap().object_type,
+            ObjectType::Package
+        );
+        assert_eq!(
+            package.memory,
+            [SyntheticMemory {
+                object_type: "NUMANode".to_owned(),
+                attributes: vec![("memory".to_owned(), "512MB".to_owned())],
+            }]
+        );
+        assert_eq!(
+            package.memory[0].type_spec().unwrap().object_type,
+            ObjectType::NUMANode
+        );
+
+        let l2 = &description.levels[1];
+        assert_eq!(
+            l2.type_spec().unwrap().unwrap().object_type,
+            ObjectType::L2Cache
+        );
+        assert_eq!(l2.arity.get(), 4);
+        assert_eq!(
+            l2.attributes,
+            [
+                ("size".to_owned(), "1MB".to_owned()),
+                ("linesize".to_owned(), "64".to_owned())
+            ]
+        );
+
+        let pu = &description.levels[2];
+        assert_eq!(pu.object_type, None);
+        assert!(pu.type_spec().is_none());
+        assert_eq!(pu.arity.get(), 2);
+
+        assert_eq!(description.to_string(), text);
+    }
+
+    /// Check that invalid descriptions are rejected
+    #[test]
+    fn parse_errors() {
+        use ParseSyntheticError::*;
+        for (text, error) in [
+            ("", NoLevels),
+            ("[NUMANode]", NoLevels),
+            ("Package:2(size=1", Unbalanced('(')),
+            ("Package:2 [NUMANode", Unbalanced('[')),
+            ("Package:2)", Unbalanced(')')),
+            ("Package:0", InvalidLevel("Package:0".to_owned())),
+            ("Package:x", InvalidLevel("Package:x".to_owned())),
+            (":2", InvalidLevel(":2".to_owned())),
+            ("Package:2 []", InvalidLevel("[]".to_owned())),
+            ("Package:2(size)", InvalidAttribute("size".to_owned())),
+        ] {
+            assert_eq!(SyntheticDescription::parse(text), Err(error), "{text:?}");
+        }
+    }
+
+    /// Check that exported descriptions can be parsed, modified and rebuilt
+    #[test]
+    fn export_parse_build() {
+        let topology = crate::topology::Topology::test_dual_socket_numa();
+        let exported = topology
+            .export_synthetic(crate::topology::export::synthetic::SyntheticExportFlags::empty())
+            .unwrap();
+        let mut description = SyntheticDescription::parse(&exported).unwrap();
+        assert_eq!(description.to_string(), exported);
+
+        let pus = description.levels.last_mut().unwrap();
+        assert_eq!(
+            pus.type_spec().unwrap().unwrap().object_type,
+            ObjectType::PU
+        );
+        pus.arity = NonZeroUsize::MIN;
+        pus.attributes.clear();
+        let rebuilt = description.build().unwrap().build().unwrap();
+        assert_eq!(
+            rebuilt.objects_with_type(ObjectType::PU).count(),
+            topology.objects_with_type(ObjectType::Core).count()
+        );
+    }
+
+    proptest! {
+        /// Check that random synthetic topologies can be built
+        #[test]
+        fn arbitrary(synthetic: SyntheticTopology) {
+            let topology = synthetic.build().build().unwrap();
+            prop_assert_eq!(
+                topology.objects_with_type(ObjectType::PU).count(),
+                synthetic.num_pus()
+            );
+        }
+
+        /// Check that typed descriptions round trip through parsing
+        #[test]
+        fn parse_typed(synthetic: SyntheticTopology) {
+            let description = SyntheticDescription::from(synthetic);
+            prop_assert_eq!(description.to_string(), synthetic.to_string());
+            prop_assert!(description.levels.iter().all(|level| level.memory.is_empty()));
+            let num_pus = description
+                .levels
+                .iter()
+                .map(|level| level.arity.get())
+                .product::<usize>();
+            prop_assert_eq!(num_pus, synthetic.num_pus());
+        }
+    }
+
+    /// Check that empty levels are rejected
+    #[test]
+    #[should_panic]
+    fn empty_level() {
+        let _ = SyntheticTopology::new().cores(0);
+    }
+}
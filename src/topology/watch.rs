@@ -0,0 +1,1056 @@
+//! Keeping a topology in sync with its source
+//!
+//! Some deployments do not let hwloc probe the hardware on each machine, but
+//! instead distribute centrally managed XML descriptions of the machines.
+//! Programs consuming these descriptions must then notice when they change
+//! and reload them. Similarly, long-running daemons that probe the hardware
+//! of the machine they run on must notice when CPUs or NUMA nodes are
+//! hotplugged or taken offline, or when the set of resources that they are
+//! allowed to use changes (e.g. because their cgroup was reconfigured).
+//!
+//! The [`TopologyWatcher`] type defined in this module manages this
+//! lifecycle: it keeps track of the current topology, reloads it when its
+//! source changes, and tells subscribers which objects, PUs and NUMA nodes
+//! changed. On Linux, it can sleep until the kernel reports that the source
+//! may have changed (via inotify for XML files, and via hotplug uevents for
+//! the system's hardware), and it falls back to polling elsewhere.
+
+use super::{builder::StringInputError, Topology};
+use crate::{
+    cpu::cpuset::CpuSet,
+    errors::RawHwlocError,
+    memory::nodeset::NodeSet,
+    object::{TopologyObject, TopologyObjectID},
+};
+#[allow(unused)]
+#[cfg(test)]
+use similar_asserts::assert_eq;
+use std::{
+    collections::BTreeMap,
+    fmt::{self, Debug},
+    io,
+    path::{Path, PathBuf},
+    time::{Duration, SystemTime},
+};
+#[cfg(target_os = "linux")]
+use std::{
+    ffi::{c_int, CString, OsString},
+    os::{
+        fd::{AsRawFd, FromRawFd, OwnedFd},
+        unix::ffi::OsStrExt,
+    },
+    time::Instant,
+};
+use thiserror::Error;
+
+/// Topology that is kept in sync with its source
+///
+/// The source is either an XML file, which is considered to have changed
+/// when its contents change, a user callback which returns the current XML
+/// description of the topology, or the hardware of the system that the
+/// program is running on, which is considered to have changed when CPUs or
+/// NUMA nodes go online or offline, or when the resources that the process is
+/// allowed to use change.
+///
+/// The watcher does not spawn any thread: it checks the source for changes
+/// every time [`TopologyWatcher::poll()`] or [`TopologyWatcher::wait()`] is
+/// called, so you can decide how often that happens and from which thread.
+///
+/// # Example
+///
+/// ```rust,no_run
+/// # use hwlocality::topology::watch::TopologyWatcher;
+/// # use std::time::Duration;
+/// let mut watcher = TopologyWatcher::from_xml_file("/etc/machine.xml")?;
+/// watcher.subscribe(|topology, changes| {
+///     println!(
+///         "Topology reloaded: {} objects added, {} removed, {} modified",
+///         changes.added().len(),
+///         changes.removed().len(),
+///         changes.modified().len()
+///     );
+/// });
+/// loop {
+///     watcher.wait(Duration::from_secs(10))?;
+/// }
+/// # Ok::<(), eyre::Report>(())
+/// ```
+pub struct TopologyWatcher {
+    /// Source of the topology's XML description
+    source: Source,
+
+    /// State of the source when the current topology was loaded
+    ///
+    /// This is the XML description of the topology for XML sources, and a
+    /// description of the online and allowed CPUs and NUMA nodes when
+    /// watching the hardware of the system that the program is running on.
+    state: String,
+
+    /// Current topology
+    topology: Topology,
+
+    /// Registered subscribers
+    subscribers: Vec<(SubscriptionId, Subscriber)>,
+
+    /// Identifier of the next subscriber to be registered
+    next_subscription_id: u64,
+
+    /// Kernel notifications that the source may have changed, if available
+    #[cfg(target_os = "linux")]
+    events: Option<KernelEvents>,
+}
+//
+impl TopologyWatcher {
+    /// Watch an XML file
+    ///
+    /// The file may have been generated with [`Topology::export_xml_file()`]
+    /// or `lstopo file.xml`.
+    ///
+    /// # Errors
+    ///
+    /// See [`TopologyWatcher::poll()`].
+    pub fn from_xml_file(path: impl AsRef<Path>) -> Result<Self, WatchError> {
+        Self::new(Source::File {
+            path: path.as_ref().to_path_buf(),
+            last_seen: None,
+        })
+    }
+
+    /// Watch a user-defined XML source
+    ///
+    /// `read_xml` is called on every [`TopologyWatcher::poll()`] and should
+    /// return the current XML description of the topology. It may, for
+    /// example, download the description from a configuration server.
+    ///
+    /// # Errors
+    ///
+    /// See [`TopologyWatcher::poll()`].
+    pub fn from_callback(
+        read_xml: impl FnMut() -> io::Result<String> + Send + 'static,
+    ) -> Result<Self, WatchError> {
+        Self::new(Source::Callback(Box::new(read_xml)))
+    }
+
+    /// Watch the hardware of the system that the program is running on
+    ///
+    /// The topology is probed with [`Topology::new()`], and probed again
+    /// whenever CPUs or NUMA nodes are hotplugged or go online or offline, or
+    /// when the set of CPUs and NUMA nodes that the process is allowed to use
+    /// changes, e.g. because its cgroup was reconfigured.
+    ///
+    /// On Linux, changes are detected by checking the online CPU and NUMA
+    /// node masks in sysfs, along with the process' allowed CPUs and NUMA
+    /// nodes in procfs, which is much cheaper than probing the topology.
+    /// [`TopologyWatcher::wait()`] additionally listens to the kernel's
+    /// hotplug events, so that CPUs and NUMA nodes going online or offline
+    /// are noticed as soon as they happen. On other operating systems, or if
+    /// these files are unavailable, the topology is probed on every poll and
+    /// its CPU and NUMA node sets are compared instead.
+    ///
+    /// Because hwloc numbers objects in discovery order, hotplug may change
+    /// the [global persistent index] of unrelated objects, which will then be
+    /// reported as added and removed. Use the [`TopologyChangeSummary`] sets
+    /// of added and removed PUs and NUMA nodes, which are identified by OS
+    /// index, to find out what actually changed.
+    ///
+    /// # Errors
+    ///
+    /// See [`TopologyWatcher::poll()`].
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// # use hwlocality::topology::watch::TopologyWatcher;
+    /// # use std::time::Duration;
+    /// let mut watcher = TopologyWatcher::this_system()?;
+    /// watcher.subscribe(|_topology, changes| {
+    ///     println!("PUs added: {}", changes.added_pus());
+    ///     println!("PUs removed: {}", changes.removed_pus());
+    /// });
+    /// loop {
+    ///     watcher.wait(Duration::from_secs(1))?;
+    /// }
+    /// # Ok::<(), eyre::Report>(())
+    /// ```
+    ///
+    /// [global persistent index]: TopologyObject::global_persistent_index()
+    pub fn this_system() -> Result<Self, WatchError> {
+        Self::new(Source::ThisSystem)
+    }
+
+    /// Current topology
+    pub fn topology(&self) -> &Topology {
+        &self.topology
+    }
+
+    /// Register a callback to be invoked whenever the topology is reloaded
+    ///
+    /// The callback receives the new topology, along with a summary of the
+    /// objects that changed with respect to the previous topology.
+    pub fn subscribe(
+        &mut self,
+        subscriber: impl FnMut(&Topology, &TopologyChangeSummary) + Send + 'static,
+    ) -> SubscriptionId {
+        let id = SubscriptionId(self.next_subscription_id);
+        self.next_subscription_id += 1;
+        self.subscribers.push((id, Box::new(subscriber)));
+        id
+    }
+
+    /// Unregister a callback that was registered with
+    /// [`TopologyWatcher::subscribe()`]
+    ///
+    /// Returns `true` if the callback was registered and has been removed.
+    pub fn unsubscribe(&mut self, id: SubscriptionId) -> bool {
+        let old_len = self.subscribers.len();
+        self.subscribers.retain(|(sub_id, _)| *sub_id != id);
+        self.subscribers.len() != old_len
+    }
+
+    /// Check the source for changes, and reload the topology if it changed
+    ///
+    /// If the topology was reloaded, subscribers are notified and the summary
+    /// of changes is returned. Otherwise, `None` is returned.
+    ///
+    /// Note that the topology is reloaded whenever its XML description
+    /// changes, even if the changes do not affect topology objects, in which
+    /// case the summary of changes will be empty.
+    ///
+    /// # Errors
+    ///
+    /// - [`Source`] if the XML description, or the state of the system's
+    ///   hardware, could not be read.
+    /// - [`Invalid`] if hwloc rejected the XML description.
+    /// - [`Build`] if hwloc failed to build the topology.
+    ///
+    /// If an error occurs, the current topology is kept.
+    ///
+    /// [`Build`]: WatchError::Build
+    /// [`Invalid`]: WatchError::Invalid
+    /// [`Source`]: WatchError::Source
+    pub fn poll(&mut self) -> Result<Option<TopologyChangeSummary>, WatchError> {
+        let Some(state) = self.source.read_if_modified()? else {
+            return Ok(None);
+        };
+        if state == self.state {
+            return Ok(None);
+        }
+        let topology = self.source.load(&state)?;
+        let changes = TopologyChangeSummary::new(&self.topology, &topology);
+        self.state = state;
+        self.topology = topology;
+        for (_, subscriber) in &mut self.subscribers {
+            subscriber(&self.topology, &changes);
+        }
+        Ok(Some(changes))
+    }
+
+    /// Wait for the source to change, then reload the topology if it changed
+    ///
+    /// On Linux, this blocks until the kernel reports an event that may
+    /// affect the topology, or until `timeout` has elapsed, then checks the
+    /// source with [`TopologyWatcher::poll()`]. XML files are watched with
+    /// inotify, which reports when they are written or replaced, and the
+    /// hardware of this system is watched by listening to the kernel's
+    /// hotplug events (uevents) for CPUs, memory blocks and NUMA nodes, like
+    /// udev does.
+    ///
+    /// Some changes are not reported by kernel events, like changes to the
+    /// set of CPUs and NUMA nodes that the process is allowed to use, or
+    /// changes to user callback sources. These are only noticed when
+    /// `timeout` elapses. On other operating systems, or if kernel events are
+    /// unavailable (e.g. in containers that have their own network
+    /// namespace), this method sleeps for `timeout` and then polls.
+    ///
+    /// # Errors
+    ///
+    /// See [`TopologyWatcher::poll()`]. Additionally, [`Source`] is returned
+    /// if waiting for kernel events failed.
+    ///
+    /// [`Source`]: WatchError::Source
+    pub fn wait(&mut self, timeout: Duration) -> Result<Option<TopologyChangeSummary>, WatchError> {
+        #[cfg(target_os = "linux")]
+        if let Some(events) = &self.events {
+            events.wait(timeout)?;
+            return self.poll();
+        }
+        std::thread::sleep(timeout);
+        self.poll()
+    }
+
+    /// Set up a watcher and load the initial topology
+    fn new(mut source: Source) -> Result<Self, WatchError> {
+        // Kernel events must be subscribed to before the source is first
+        // read, otherwise changes that happen in between would be missed
+        #[cfg(target_os = "linux")]
+        let events = KernelEvents::new(&source);
+        let state = source
+            .read_if_modified()?
+            .expect("Sources are always considered modified on first read");
+        let topology = source.load(&state)?;
+        Ok(Self {
+            source,
+            state,
+            topology,
+            subscribers: Vec::new(),
+            next_subscription_id: 0,
+            #[cfg(target_os = "linux")]
+            events,
+        })
+    }
+}
+//
+impl Debug for TopologyWatcher {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("TopologyWatcher")
+            .field("source", &self.source)
+            .field("topology", &self.topology)
+            .field("num_subscribers", &self.subscribers.len())
+            .finish_non_exhaustive()
+    }
+}
+
+/// Identifier of a subscriber registered with [`TopologyWatcher::subscribe()`]
+#[derive(Copy, Clone, Debug, Eq, Hash, PartialEq)]
+pub struct SubscriptionId(u64);
+
+/// Callback to be invoked after a topology is reloaded
+type Subscriber = Box<dyn FnMut(&Topology, &TopologyChangeSummary) + Send>;
+
+/// Summary of the objects that changed when a topology was reloaded
+///
+/// Objects are identified by [global persistent
+/// index](TopologyObject::global_persistent_index()), which hwloc preserves
+/// in XML exports. An object is considered modified if its type, name,
+/// subtype, OS index, CPU or NUMA node sets, textual infos or parent changed.
+/// Identifiers are sorted in ascending order.
+///
+/// PUs and NUMA nodes that appeared or disappeared from the topology's
+/// [`cpuset()`] and [`nodeset()`] are also reported, identified by OS index.
+///
+/// [`cpuset()`]: Topology::cpuset()
+/// [`nodeset()`]: Topology::nodeset()
+#[derive(Clone, Debug, Default, Eq, Hash, PartialEq)]
+pub struct TopologyChangeSummary {
+    /// Objects that only exist in the new topology
+    added: Vec<TopologyObjectID>,
+
+    /// Objects that only exist in the old topology
+    removed: Vec<TopologyObjectID>,
+
+    /// Objects that exist in both topologies, but changed
+    modified: Vec<TopologyObjectID>,
+
+    /// PUs that only exist in the new topology
+    added_pus: CpuSet,
+
+    /// PUs that only exist in the old topology
+    removed_pus: CpuSet,
+
+    /// NUMA nodes that only exist in the new topology
+    added_numa_nodes: NodeSet,
+
+    /// NUMA nodes that only exist in the old topology
+    removed_numa_nodes: NodeSet,
+}
+//
+impl TopologyChangeSummary {
+    /// Compare two topologies
+    fn new(old: &Topology, new: &Topology) -> Self {
+        /// Index a topology's objects by global persistent index
+        fn objects_by_id(topology: &Topology) -> BTreeMap<TopologyObjectID, &TopologyObject> {
+            topology
+                .objects()
+                .map(|obj| (obj.global_persistent_index(), obj))
+                .collect()
+        }
+        let (old_objects, new_objects) = (objects_by_id(old), objects_by_id(new));
+        let mut result = Self::default();
+        for (&id, &old_obj) in &old_objects {
+            match new_objects.get(&id) {
+                Some(&new_obj) => {
+                    if !same_object(old_obj, new_obj) {
+                        result.modified.push(id);
+                    }
+                }
+                None => result.removed.push(id),
+            }
+        }
+        result.added = new_objects
+            .keys()
+            .filter(|id| !old_objects.contains_key(id))
+            .copied()
+            .collect();
+        result.added_pus = new.cpuset().clone_target() - old.cpuset();
+        result.removed_pus = old.cpuset().clone_target() - new.cpuset();
+        result.added_numa_nodes = new.nodeset().clone_target() - old.nodeset();
+        result.removed_numa_nodes = old.nodeset().clone_target() - new.nodeset();
+        result
+    }
+
+    /// Objects that were added to the topology
+    pub fn added(&self) -> &[TopologyObjectID] {
+        &self.added
+    }
+
+    /// Objects that were removed from the topology
+    pub fn removed(&self) -> &[TopologyObjectID] {
+        &self.removed
+    }
+
+    /// Objects whose properties changed
+    pub fn modified(&self) -> &[TopologyObjectID] {
+        &self.modified
+    }
+
+    /// PUs that were added to the topology, e.g. by CPU hotplug
+    pub fn added_pus(&self) -> &CpuSet {
+        &self.added_pus
+    }
+
+    /// PUs that were removed from the topology, e.g. by taking CPUs offline
+    pub fn removed_pus(&self) -> &CpuSet {
+        &self.removed_pus
+    }
+
+    /// NUMA nodes that were added to the topology, e.g. by memory hotplug
+    pub fn added_numa_nodes(&self) -> &NodeSet {
+        &self.added_numa_nodes
+    }
+
+    /// NUMA nodes that were removed from the topology
+    pub fn removed_numa_nodes(&self) -> &NodeSet {
+        &self.removed_numa_nodes
+    }
+
+    /// Truth that no object changed
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty()
+            && self.removed.is_empty()
+            && self.modified.is_empty()
+            && self.added_pus.is_empty()
+            && self.removed_pus.is_empty()
+            && self.added_numa_nodes.is_empty()
+            && self.removed_numa_nodes.is_empty()
+    }
+}
+
+/// Error while reloading a topology from its source
+#[derive(Debug, Error)]
+pub enum WatchError {
+    /// Failed to read the XML description or the state of the hardware
+    #[error("failed to read the topology's source")]
+    Source(#[from] io::Error),
+
+    /// hwloc rejected the XML description
+    #[error(transparent)]
+    Invalid(#[from] StringInputError),
+
+    /// hwloc failed to build the topology
+    #[error("failed to build the topology")]
+    Build(#[source] RawHwlocError),
+}
+
+/// Source of a topology
+enum Source {
+    /// XML file
+    File {
+        /// Location of the file
+        path: PathBuf,
+
+        /// Modification time and length of the file when it was last read
+        last_seen: Option<(SystemTime, u64)>,
+    },
+
+    /// User callback
+    Callback(Box<dyn FnMut() -> io::Result<String> + Send>),
+
+    /// Hardware of the system that the program is running on
+    ThisSystem,
+}
+//
+impl Source {
+    /// Read the state of the source, unless it is known not to have changed
+    ///
+    /// For XML sources, this is the XML description of the topology.
+    fn read_if_modified(&mut self) -> Result<Option<String>, WatchError> {
+        match self {
+            Self::File { path, last_seen } => {
+                // File metadata is not fully reliable (modification times have
+                // limited granularity on some filesystems and can be
+                // unavailable), so it is only used to skip reading files which
+                // most likely didn't change
+                let metadata = std::fs::metadata(&*path)?;
+                let seen = metadata
+                    .modified()
+                    .ok()
+                    .map(|modified| (modified, metadata.len()));
+                if seen.is_some() && seen == *last_seen {
+                    return Ok(None);
+                }
+                let xml = std::fs::read_to_string(&*path)?;
+                *last_seen = seen;
+                Ok(Some(xml))
+            }
+            Self::Callback(read_xml) => Ok(Some(read_xml()?)),
+            Self::ThisSystem => this_system_state().map(Some),
+        }
+    }
+
+    /// Load the topology, given a state from [`Source::read_if_modified()`]
+    fn load(&self, state: &str) -> Result<Topology, WatchError> {
+        match self {
+            Self::File { .. } | Self::Callback(_) => load(state),
+            Self::ThisSystem => Topology::new().map_err(WatchError::Build),
+        }
+    }
+}
+//
+impl Debug for Source {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::File { path, .. } => f.debug_tuple("File").field(path).finish(),
+            Self::Callback(_) => f.write_str("Callback(..)"),
+            Self::ThisSystem => f.write_str("ThisSystem"),
+        }
+    }
+}
+
+/// Load a topology from an XML description
+fn load(xml: &str) -> Result<Topology, WatchError> {
+    Topology::builder()
+        .from_xml(xml)?
+        .build()
+        .map_err(WatchError::Build)
+}
+
+/// Describe the online and allowed CPUs and NUMA nodes of this system
+fn this_system_state() -> Result<String, WatchError> {
+    #[cfg(target_os = "linux")]
+    {
+        /// Read a pseudo-file, treating missing files as empty
+        fn read(path: &str) -> io::Result<String> {
+            match std::fs::read_to_string(path) {
+                Ok(contents) => Ok(contents),
+                Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(String::new()),
+                Err(e) => Err(e),
+            }
+        }
+        let online_cpus = read("/sys/devices/system/cpu/online")?;
+        if !online_cpus.is_empty() {
+            let online_nodes = read("/sys/devices/system/node/online")?;
+            let status = read("/proc/self/status")?;
+            let allowed = status
+                .lines()
+                .filter(|line| {
+                    line.starts_with("Cpus_allowed_list:") || line.starts_with("Mems_allowed_list:")
+                })
+                .collect::<Vec<_>>()
+                .join("\n");
+            return Ok(format!(
+                "online CPUs: {}\nonline nodes: {}\n{allowed}",
+                online_cpus.trim(),
+                online_nodes.trim()
+            ));
+        }
+    }
+
+    // Polling fallback: probe the topology and describe its sets
+    let topology = Topology::new().map_err(WatchError::Build)?;
+    Ok(format!(
+        "cpuset: {}\nnodeset: {}\nallowed cpuset: {}\nallowed nodeset: {}",
+        topology.cpuset(),
+        topology.nodeset(),
+        topology.allowed_cpuset(),
+        topology.allowed_nodeset()
+    ))
+}
+
+/// Kernel notifications that a topology source may have changed
+#[cfg(target_os = "linux")]
+#[derive(Debug)]
+struct KernelEvents {
+    /// Non-blocking file descriptor from which events are read
+    fd: OwnedFd,
+
+    /// Kind of events that are read from `fd`
+    kind: KernelEventKind,
+}
+//
+#[cfg(target_os = "linux")]
+impl KernelEvents {
+    /// Start listening to kernel events for a certain topology source
+    ///
+    /// Returns `None` if this kind of source has no associated kernel events,
+    /// or if listening to them failed, in which case the watcher falls back
+    /// to polling.
+    fn new(source: &Source) -> Option<Self> {
+        match source {
+            Source::File { path, .. } => Self::watch_file(path),
+            Source::ThisSystem => Self::hotplug(),
+            Source::Callback(_) => None,
+        }
+    }
+
+    /// Watch writes and renames to an XML file with inotify
+    ///
+    /// The parent directory is watched, rather than the file itself, so that
+    /// files that are atomically replaced via rename keep being watched.
+    fn watch_file(path: &Path) -> Option<Self> {
+        let file_name = path.file_name()?.to_owned();
+        let parent = path
+            .parent()
+            .filter(|parent| !parent.as_os_str().is_empty())
+            .unwrap_or_else(|| Path::new("."));
+        let parent = CString::new(parent.as_os_str().as_bytes()).ok()?;
+        // SAFETY: inotify_init1 has no safety preconditions
+        let fd = unsafe { libc::inotify_init1(libc::IN_CLOEXEC | libc::IN_NONBLOCK) };
+        if fd < 0 {
+            return None;
+        }
+        // SAFETY: fd is a newly created file descriptor that nobody else owns
+        let fd = unsafe { OwnedFd::from_raw_fd(fd) };
+        // SAFETY: fd is a valid inotify file descriptor and parent is a valid
+        //         NUL-terminated C string
+        let wd = unsafe {
+            libc::inotify_add_watch(
+                fd.as_raw_fd(),
+                parent.as_ptr(),
+                libc::IN_CLOSE_WRITE | libc::IN_MOVED_TO,
+            )
+        };
+        (wd >= 0).then_some(Self {
+            fd,
+            kind: KernelEventKind::File(file_name),
+        })
+    }
+
+    /// Listen to the kernel's hotplug events (uevents)
+    fn hotplug() -> Option<Self> {
+        // SAFETY: socket has no safety preconditions
+        let fd = unsafe {
+            libc::socket(
+                libc::AF_NETLINK,
+                libc::SOCK_DGRAM | libc::SOCK_CLOEXEC | libc::SOCK_NONBLOCK,
+                libc::NETLINK_KOBJECT_UEVENT,
+            )
+        };
+        if fd < 0 {
+            return None;
+        }
+        // SAFETY: fd is a newly created file descriptor that nobody else owns
+        let fd = unsafe { OwnedFd::from_raw_fd(fd) };
+        // SAFETY: sockaddr_nl is a C struct for which all-zeroes is valid
+        let mut address: libc::sockaddr_nl = unsafe { std::mem::zeroed() };
+        address.nl_family = libc::sa_family_t::try_from(libc::AF_NETLINK)
+            .expect("AF_NETLINK should fit in sa_family_t");
+        // Kernel uevents are broadcast to netlink multicast group 1
+        address.nl_groups = 1;
+        let address_len = libc::socklen_t::try_from(std::mem::size_of::<libc::sockaddr_nl>())
+            .expect("sockaddr_nl should have a small size");
+        // SAFETY: fd is a valid socket and address is a valid sockaddr_nl
+        //         whose size is address_len
+        let result = unsafe {
+            libc::bind(
+                fd.as_raw_fd(),
+                std::ptr::addr_of!(address).cast(),
+                address_len,
+            )
+        };
+        (result == 0).then_some(Self {
+            fd,
+            kind: KernelEventKind::Hotplug,
+        })
+    }
+
+    /// Wait until an event that may affect the topology is received, or
+    /// until `timeout` has elapsed
+    fn wait(&self, timeout: Duration) -> io::Result<()> {
+        let deadline = Instant::now().checked_add(timeout);
+        loop {
+            if self.drain()? {
+                return Ok(());
+            }
+            let remaining = deadline.map_or(Duration::MAX, |deadline| {
+                deadline.saturating_duration_since(Instant::now())
+            });
+            if remaining.is_zero() {
+                return Ok(());
+            }
+            // Round up so that we don't busy-loop on sub-millisecond timeouts
+            let timeout_ms =
+                c_int::try_from((remaining.as_micros() + 999) / 1000).unwrap_or(c_int::MAX);
+            let mut pollfd = libc::pollfd {
+                fd: self.fd.as_raw_fd(),
+                events: libc::POLLIN,
+                revents: 0,
+            };
+            // SAFETY: pollfd is a valid array of one pollfd, targeting a
+            //         valid file descriptor
+            if unsafe { libc::poll(&mut pollfd, 1, timeout_ms) } < 0 {
+                let error = io::Error::last_os_error();
+                if error.kind() != io::ErrorKind::Interrupted {
+                    return Err(error);
+                }
+            }
+        }
+    }
+
+    /// Read all pending events, and tell if any may affect the topology
+    fn drain(&self) -> io::Result<bool> {
+        let mut buf = [0u8; 8192];
+        let mut relevant = false;
+        loop {
+            // SAFETY: fd is a valid file descriptor and buf is valid for
+            //         writes of buf.len() bytes
+            let len =
+                unsafe { libc::read(self.fd.as_raw_fd(), buf.as_mut_ptr().cast(), buf.len()) };
+            match usize::try_from(len) {
+                Ok(0) => return Ok(relevant),
+                Ok(len) => {
+                    relevant |= match &self.kind {
+                        KernelEventKind::File(file_name) => {
+                            inotify_events_mention(&buf[..len], file_name)
+                        }
+                        KernelEventKind::Hotplug => is_topology_uevent(&buf[..len]),
+                    }
+                }
+                Err(_) => {
+                    let error = io::Error::last_os_error();
+                    if error.kind() == io::ErrorKind::WouldBlock {
+                        return Ok(relevant);
+                    } else if error.raw_os_error() == Some(libc::ENOBUFS) {
+                        // The kernel dropped events because we did not read
+                        // them fast enough, some of which may be relevant
+                        relevant = true;
+                    } else if error.kind() != io::ErrorKind::Interrupted {
+                        return Err(error);
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Kind of [`KernelEvents`]
+#[cfg(target_os = "linux")]
+#[derive(Debug)]
+enum KernelEventKind {
+    /// inotify events from the parent directory of an XML file, with the
+    /// name of the XML file
+    File(OsString),
+
+    /// Kernel hotplug events (uevents)
+    Hotplug,
+}
+
+/// Truth that a buffer of inotify events mentions a certain file, or reports
+/// that some events were lost
+#[cfg(target_os = "linux")]
+fn inotify_events_mention(mut events: &[u8], file_name: &OsString) -> bool {
+    // struct inotify_event is laid out as { wd: i32, mask: u32, cookie: u32,
+    // len: u32 }, followed by a NUL-padded name of `len` bytes
+    let header_len = std::mem::size_of::<libc::inotify_event>();
+    while let Some(header) = events.get(..header_len) {
+        let field = |offset: usize| {
+            u32::from_ne_bytes(
+                header[offset..offset + 4]
+                    .try_into()
+                    .expect("Slice has the right length"),
+            )
+        };
+        let mask = field(4);
+        let name_len = usize::try_from(field(12)).expect("u32 should fit in usize");
+        let name = events
+            .get(header_len..header_len + name_len)
+            .unwrap_or_default();
+        let name = name.split(|&byte| byte == 0).next().unwrap_or_default();
+        if mask & libc::IN_Q_OVERFLOW != 0 || name == file_name.as_bytes() {
+            return true;
+        }
+        events = events.get(header_len + name_len..).unwrap_or_default();
+    }
+    false
+}
+
+/// Truth that a kernel uevent is about CPUs, memory blocks or NUMA nodes
+///
+/// uevents are formatted as `action@devpath` followed by `KEY=value`
+/// properties, all NUL-separated.
+#[cfg(target_os = "linux")]
+fn is_topology_uevent(uevent: &[u8]) -> bool {
+    uevent.split(|&byte| byte == 0).any(|field| {
+        matches!(
+            field,
+            b"SUBSYSTEM=cpu" | b"SUBSYSTEM=memory" | b"SUBSYSTEM=node"
+        )
+    })
+}
+
+/// Truth that two objects with the same global persistent index, from two
+/// different topologies, have the same properties
+fn same_object(old: &TopologyObject, new: &TopologyObject) -> bool {
+    let parent_id =
+        |obj: &TopologyObject| obj.parent().map(TopologyObject::global_persistent_index);
+    old.object_type() == new.object_type()
+        && old.name() == new.name()
+        && old.subtype() == new.subtype()
+        && old.os_index() == new.os_index()
+        && old.cpuset() == new.cpuset()
+        && old.complete_cpuset() == new.complete_cpuset()
+        && old.nodeset() == new.nodeset()
+        && old.complete_nodeset() == new.complete_nodeset()
+        && old.infos() == new.infos()
+        && parent_id(old) == parent_id(new)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::topology::export::xml::XMLExportFlags;
+    #[allow(unused)]
+    use similar_asserts::assert_eq;
+    use static_assertions::{assert_impl_all, assert_not_impl_any};
+    use std::{
+        error::Error,
+        fmt::{Binary, Display, LowerExp, LowerHex, Octal, Pointer, UpperExp, UpperHex},
+        hash::Hash,
+        io::Read,
+        ops::Deref,
+        panic::UnwindSafe,
+        sync::{Arc, Mutex},
+    };
+
+    // Check that public types in this module keep implementing all expected
+    // traits, in the interest of detecting future semver-breaking changes
+    assert_impl_all!(TopologyWatcher:
+        Debug, Send, Sized, Unpin
+    );
+    assert_not_impl_any!(TopologyWatcher:
+        Binary, Clone, Default, Deref, Display, Drop, IntoIterator, LowerExp,
+        LowerHex, Octal, PartialEq, Pointer, Read, Sync, UpperExp, UpperHex,
+        fmt::Write, io::Write
+    );
+    assert_impl_all!(SubscriptionId:
+        Copy, Debug, Hash, Sized, Sync, Unpin, UnwindSafe
+    );
+    assert_impl_all!(TopologyChangeSummary:
+        Clone, Debug, Default, Hash, Sized, Sync, Unpin, UnwindSafe
+    );
+    assert_impl_all!(WatchError:
+        Error, From<io::Error>, From<StringInputError>, Sized, Sync, Unpin
+    );
+
+    /// Check that a topology compared with itself has no changes
+    #[test]
+    fn no_changes() {
+        let topology = Topology::test_instance();
+        let changes = TopologyChangeSummary::new(topology, topology);
+        assert!(changes.is_empty());
+        assert_eq!(changes, TopologyChangeSummary::default());
+    }
+
+    /// Check that restricting a topology is reported as PU and node removal
+    #[cfg(feature = "hwloc-2_3_0")]
+    #[test]
+    fn pu_and_node_changes() {
+        use crate::object::types::ObjectType;
+
+        let topology = Topology::test_instance();
+        let first_pu = topology.objects_with_type(ObjectType::PU).next().unwrap();
+        let restricted = topology
+            .restricted_clone(&first_pu.cpuset().unwrap(), Default::default())
+            .unwrap();
+
+        let changes = TopologyChangeSummary::new(topology, &restricted);
+        assert_eq!(
+            changes.removed_pus(),
+            &(topology.cpuset().clone_target() - restricted.cpuset())
+        );
+        assert_eq!(
+            changes.removed_numa_nodes(),
+            &(topology.nodeset().clone_target() - restricted.nodeset())
+        );
+        assert!(changes.added_pus().is_empty());
+        assert!(changes.added_numa_nodes().is_empty());
+
+        let changes = TopologyChangeSummary::new(&restricted, topology);
+        assert_eq!(
+            changes.added_pus(),
+            &(topology.cpuset().clone_target() - restricted.cpuset())
+        );
+        assert!(changes.removed_pus().is_empty());
+    }
+
+    /// Check watching the hardware of the system that the program runs on
+    #[test]
+    fn this_system() {
+        let mut watcher = TopologyWatcher::this_system().unwrap();
+        assert!(watcher.topology().is_this_system());
+        assert_eq!(
+            watcher.topology().cpuset(),
+            Topology::test_instance().cpuset()
+        );
+        assert_eq!(watcher.poll().unwrap(), None);
+        assert_eq!(watcher.wait(Duration::from_millis(10)).unwrap(), None);
+    }
+
+    /// Check recognition of hotplug uevents
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn topology_uevents() {
+        assert!(is_topology_uevent(
+            b"online@/devices/system/cpu/cpu3\0ACTION=online\0DEVPATH=/devices/system/cpu/cpu3\0SUBSYSTEM=cpu\0SEQNUM=4242\0"
+        ));
+        assert!(is_topology_uevent(
+            b"add@/devices/system/node/node1\0ACTION=add\0SUBSYSTEM=node\0"
+        ));
+        assert!(!is_topology_uevent(
+            b"add@/devices/pci0000:00/usb1/1-1\0ACTION=add\0SUBSYSTEM=usb\0"
+        ));
+        assert!(!is_topology_uevent(b""));
+    }
+
+    /// Check parsing of inotify events
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn inotify_events() {
+        /// Build an inotify event with a NUL-padded name
+        fn event(mask: u32, name: &[u8]) -> Vec<u8> {
+            let padded_len = name.len() / 16 * 16 + 16;
+            let mut result = Vec::new();
+            result.extend_from_slice(&1i32.to_ne_bytes());
+            result.extend_from_slice(&mask.to_ne_bytes());
+            result.extend_from_slice(&0u32.to_ne_bytes());
+            result.extend_from_slice(&u32::try_from(padded_len).unwrap().to_ne_bytes());
+            result.extend_from_slice(name);
+            result.resize(result.len() + padded_len - name.len(), 0);
+            result
+        }
+        let file_name = OsString::from("topology.xml");
+        let other = event(libc::IN_CLOSE_WRITE, b"other.xml");
+        let target = event(libc::IN_MOVED_TO, b"topology.xml");
+        let prefixed = event(libc::IN_CLOSE_WRITE, b"topology.xml.tmp");
+        assert!(!inotify_events_mention(&other, &file_name));
+        assert!(!inotify_events_mention(&prefixed, &file_name));
+        assert!(inotify_events_mention(
+            &[other, target].concat(),
+            &file_name
+        ));
+        assert!(inotify_events_mention(
+            &event(libc::IN_Q_OVERFLOW, b""),
+            &file_name
+        ));
+        assert!(!inotify_events_mention(&[], &file_name));
+    }
+
+    /// Check reloading from a user callback
+    #[test]
+    fn callback_source() {
+        let topology = Topology::test_instance();
+        let xml = topology
+            .export_xml(XMLExportFlags::empty())
+            .unwrap()
+            .to_string();
+        let current_xml = Arc::new(Mutex::new(xml.clone()));
+        let source_xml = Arc::clone(&current_xml);
+        let mut watcher =
+            TopologyWatcher::from_callback(move || Ok(source_xml.lock().unwrap().clone())).unwrap();
+        let notifications = Arc::new(Mutex::new(0));
+        let subscriber_notifications = Arc::clone(&notifications);
+        let id = watcher.subscribe(move |_topology, _changes| {
+            *subscriber_notifications.lock().unwrap() += 1;
+        });
+
+        // Unchanged sources don't trigger reloads
+        assert_eq!(watcher.poll().unwrap(), None);
+        assert_eq!(*notifications.lock().unwrap(), 0);
+
+        // Changed sources trigger reloads, even if no object changed
+        current_xml.lock().unwrap().push('\n');
+        assert_eq!(
+            watcher.poll().unwrap(),
+            Some(TopologyChangeSummary::default())
+        );
+        assert_eq!(*notifications.lock().unwrap(), 1);
+        assert_eq!(
+            watcher.topology().objects().count(),
+            topology.objects().count()
+        );
+
+        // Invalid sources are reported, and the topology is kept
+        *current_xml.lock().unwrap() = "Not a topology".to_owned();
+        assert!(matches!(watcher.poll(), Err(WatchError::Invalid(_))));
+        assert_eq!(*notifications.lock().unwrap(), 1);
+        assert_eq!(
+            watcher.topology().objects().count(),
+            topology.objects().count()
+        );
+
+        // Unsubscribed callbacks are not notified anymore
+        assert!(watcher.unsubscribe(id));
+        assert!(!watcher.unsubscribe(id));
+        *current_xml.lock().unwrap() = xml;
+        assert!(watcher.poll().unwrap().is_some());
+        assert_eq!(*notifications.lock().unwrap(), 1);
+    }
+
+    /// Check reloading from a file, including change detection
+    #[cfg(feature = "hwloc-2_3_0")]
+    #[test]
+    fn file_source() {
+        use crate::{object::types::ObjectType, topology::editor::RestrictFlags};
+
+        let topology = Topology::test_instance();
+        if topology.objects_with_type(ObjectType::PU).count() == 1 {
+            return;
+        }
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("topology.xml");
+        topology
+            .export_xml_file(Some(&path), XMLExportFlags::empty())
+            .unwrap();
+        let mut watcher = TopologyWatcher::from_xml_file(&path).unwrap();
+        assert_eq!(watcher.poll().unwrap(), None);
+        let initial_count = watcher.topology().objects().count();
+
+        // Restrict the topology to its first PU, and export that
+        let mut restricted = topology.clone();
+        let first_pu_set = topology
+            .objects_with_type(ObjectType::PU)
+            .next()
+            .unwrap()
+            .cpuset()
+            .unwrap()
+            .clone_target();
+        restricted.edit(|editor| {
+            editor
+                .restrict(&first_pu_set, RestrictFlags::empty())
+                .unwrap();
+        });
+        let restricted_xml = restricted
+            .export_xml(XMLExportFlags::empty())
+            .unwrap()
+            .to_string();
+        std::fs::write(&path, restricted_xml).unwrap();
+
+        // Check that the reload is detected. Since the restricted topology has
+        // fewer objects, its XML description is shorter, so this works even on
+        // filesystems with coarse modification times.
+        let changes = watcher.wait(Duration::from_secs(1)).unwrap().unwrap();
+        assert!(changes.added().is_empty());
+        assert_eq!(
+            changes.removed().len(),
+            initial_count - watcher.topology().objects().count()
+        );
+        assert_eq!(watcher.poll().unwrap(), None);
+    }
+
+    /// Check that missing files are reported
+    #[test]
+    fn missing_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let result = TopologyWatcher::from_xml_file(dir.path().join("missing.xml"));
+        assert!(matches!(result, Err(WatchError::Source(_))));
+    }
+}
@@ -0,0 +1,24 @@
+//! Test process that only uses the parts of hwlocality which do not call into
+//! hwloc, and can thus be built with the `no-link` feature on a machine where
+//! hwloc is not installed.
+//!
+//! Do not use any hwloc-backed functionality (topologies, bitmaps...) in this
+//! integration test, or it will fail to link in the CI job that checks this.
+
+use hwlocality::bitmap::indices::BitmapIndex;
+
+/// Constant built using the `const` part of the [`BitmapIndex`] API
+const LAST_PU: Option<BitmapIndex> = BitmapIndex::try_from_usize(255);
+
+/// Check basic [`BitmapIndex`] arithmetic and conversions
+#[test]
+fn bitmap_index() {
+    let last_pu = LAST_PU.unwrap();
+    assert_eq!(last_pu.to_usize(), 255);
+    assert_eq!(BitmapIndex::try_from(255usize), Ok(last_pu));
+    assert_eq!(usize::from(last_pu + 1isize), 256);
+    assert_eq!(last_pu.checked_add(BitmapIndex::MAX), None);
+    assert_eq!("255".parse::<BitmapIndex>(), Ok(last_pu));
+    assert_eq!(last_pu.to_string(), "255");
+    assert_eq!(BitmapIndex::try_from_usize(usize::MAX), None);
+}
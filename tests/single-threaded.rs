@@ -14,6 +14,11 @@ use hwlocality::{
         cpuset::CpuSet,
     },
     errors::{HybridError, ParameterError, RawHwlocError},
+    memory::{
+        binding::{MemoryBindingFlags, MemoryBindingPolicy},
+        nodeset::NodeSet,
+    },
+    object::types::ObjectType,
     topology::Topology,
 };
 use proptest::{prelude::*, test_runner::TestRunner};
@@ -133,6 +138,91 @@ fn single_threaded_test() {
                 Ok(())
             })
             .unwrap();
+
+        // Setting CPU bindings with fallback
+        let preferences = [
+            CpuBindingFlags::THREAD | CpuBindingFlags::STRICT,
+            CpuBindingFlags::THREAD,
+            CpuBindingFlags::PROCESS | CpuBindingFlags::STRICT,
+            CpuBindingFlags::PROCESS,
+        ];
+        match topology.bind_cpu_with_fallback(topology.cpuset(), preferences) {
+            Ok(flags) => {
+                assert!(preferences.contains(&flags));
+                if flags.contains(CpuBindingFlags::PROCESS) {
+                    assert!(cpubind_support.set_current_process());
+                } else {
+                    assert!(cpubind_support.set_current_thread());
+                }
+            }
+            Err(e) => println!("Failed to bind with fallback: {e}"),
+        }
+        assert_eq!(
+            topology.bind_cpu_with_fallback(topology.cpuset(), []),
+            Err(HybridError::Rust(CpuBindingError::from(
+                CpuBindingFlags::empty()
+            )))
+        );
+
+        // Temporarily setting CPU bindings
+        if cpubind_support.get_current_thread() && cpubind_support.set_current_thread() {
+            let flags = CpuBindingFlags::THREAD;
+            let initial = topology.cpu_binding(flags).unwrap();
+            let target = topology
+                .objects_with_type(ObjectType::PU)
+                .next()
+                .unwrap()
+                .cpuset()
+                .unwrap()
+                .clone_target();
+            {
+                let guard = topology.bind_cpu_scoped(&target, flags).unwrap();
+                assert_eq!(guard.previous_binding(), &initial);
+                assert_eq!(topology.cpu_binding(flags).unwrap(), target);
+            }
+            assert_eq!(topology.cpu_binding(flags).unwrap(), initial);
+            let guard = topology.bind_cpu_scoped(&target, flags).unwrap();
+            guard.restore().unwrap();
+            assert_eq!(topology.cpu_binding(flags).unwrap(), initial);
+        }
+    }
+
+    // Test temporary memory binding changes
+    if let Some(membind_support) = topology.feature_support().memory_binding() {
+        let nodeset = topology.nodeset();
+        let policy = MemoryBindingPolicy::Bind;
+        if membind_support.get_current_thread()
+            && membind_support.set_current_thread()
+            && membind_support.bind_policy()
+        {
+            let flags = MemoryBindingFlags::THREAD;
+            let initial = topology.memory_binding::<NodeSet>(flags).unwrap();
+            {
+                let guard = topology
+                    .bind_memory_scoped(&nodeset, policy, flags)
+                    .unwrap();
+                assert_eq!(guard.previous_binding().0, &initial.0);
+                assert_eq!(Some(guard.previous_binding().1), initial.1);
+            }
+            assert_eq!(topology.memory_binding::<NodeSet>(flags).unwrap(), initial);
+        }
+        if membind_support.get_area() && membind_support.set_area() && membind_support.bind_policy()
+        {
+            let data = vec![42u8; 1 << 20];
+            let flags = MemoryBindingFlags::empty();
+            let result = topology.bind_memory_area_scoped(&data[..], &nodeset, policy, flags);
+            if let Ok(guard) = result {
+                let (initial_set, initial_policy) = guard.previous_binding();
+                let initial = (initial_set.clone(), Some(initial_policy));
+                guard.restore().unwrap();
+                assert_eq!(
+                    topology
+                        .area_memory_binding::<_, NodeSet>(&data[..], MemoryBindingFlags::STRICT)
+                        .unwrap(),
+                    initial
+                );
+            }
+        }
     }
 
     // TODO: Add other single-threaded tests here